@@ -0,0 +1,3 @@
+fn main() {
+    traits::ex03_plugins::run();
+}
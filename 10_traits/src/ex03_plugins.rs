@@ -0,0 +1,141 @@
+//! Topic 3 — Trait objects: a registry of `Box<dyn Plugin>` dispatched by name at runtime.
+//!
+//! Run via: `cargo run --bin ex03_plugins`
+
+use crate::plugin::{Plugin, PluginError, PluginRegistry};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+/// Says hello in a configurable language.
+struct Greeter {
+    greeting: String,
+}
+
+impl Plugin for Greeter {
+    fn name(&self) -> &str {
+        "greet"
+    }
+
+    fn description(&self) -> &str {
+        "greet <name>... — say hello to everyone listed"
+    }
+
+    fn execute(&mut self, args: &[&str]) -> Result<String, PluginError> {
+        if args.is_empty() {
+            return Err(PluginError::invalid_args(
+                self.name(),
+                "who should I greet?",
+            ));
+        }
+        Ok(format!("{}, {}!", self.greeting, args.join(" and ")))
+    }
+}
+
+/// Integer arithmetic that remembers its last result as `ans`.
+struct Calculator {
+    last: i64,
+}
+
+impl Calculator {
+    fn operand(&self, raw: &str) -> Result<i64, PluginError> {
+        if raw == "ans" {
+            return Ok(self.last);
+        }
+        raw.parse()
+            .map_err(|_| PluginError::invalid_args("calc", format!("`{raw}` is not a number")))
+    }
+}
+
+impl Plugin for Calculator {
+    fn name(&self) -> &str {
+        "calc"
+    }
+
+    fn description(&self) -> &str {
+        "calc <a> <+|-|*|/> <b> — integer math; `ans` is the previous result"
+    }
+
+    fn execute(&mut self, args: &[&str]) -> Result<String, PluginError> {
+        let [a, op, b] = args else {
+            return Err(PluginError::invalid_args(
+                self.name(),
+                "expected <a> <op> <b>",
+            ));
+        };
+        let (a, b) = (self.operand(a)?, self.operand(b)?);
+        let result = match *op {
+            "+" => a.checked_add(b),
+            "-" => a.checked_sub(b),
+            "*" => a.checked_mul(b),
+            "/" => a.checked_div(b),
+            other => {
+                return Err(PluginError::invalid_args(
+                    self.name(),
+                    format!("unknown operator `{other}`"),
+                ));
+            }
+        }
+        .ok_or_else(|| PluginError::invalid_args(self.name(), "overflow or division by zero"))?;
+        self.last = result;
+        Ok(result.to_string())
+    }
+}
+
+/// Reverses each word; uses the default `description`.
+struct Reverser;
+
+impl Plugin for Reverser {
+    fn name(&self) -> &str {
+        "reverse"
+    }
+
+    fn execute(&mut self, args: &[&str]) -> Result<String, PluginError> {
+        let words: Vec<String> = args.iter().map(|w| w.chars().rev().collect()).collect();
+        Ok(words.join(" "))
+    }
+}
+
+pub fn run() {
+    let mut registry = PluginRegistry::new();
+    // Three unrelated types behind one `Box<dyn Plugin>` element type.
+    let plugins: Vec<Box<dyn Plugin>> = vec![
+        Box::new(Greeter {
+            greeting: String::from("Annyeong"),
+        }),
+        Box::new(Calculator { last: 0 }),
+        Box::new(Reverser),
+    ];
+    for plugin in plugins {
+        registry.register(plugin).expect("plugin names are unique");
+    }
+
+    section("Registered plugins");
+    for name in registry.names() {
+        let plugin = registry.get(name).expect("listed names exist");
+        println!("{name:<8} {}", plugin.description());
+    }
+
+    section("Dispatch by name");
+    let script = [
+        "greet Sooyoung Alex",
+        "calc 6 * 7",
+        "calc ans - 2",
+        "reverse traits are neat",
+        "calc 1 / 0",
+        "greet",
+        "dance now",
+    ];
+    for line in script {
+        match registry.dispatch_line(line) {
+            Ok(output) => println!("> {line:<24} {output}"),
+            Err(e) => println!("> {line:<24} error: {e}"),
+        }
+    }
+
+    section("Duplicate registration");
+    if let Err(e) = registry.register(Box::new(Reverser)) {
+        println!("{e}");
+    }
+}
@@ -3,5 +3,7 @@
 
 pub mod ex01_person_setup;
 pub mod ex02_trait_bounds;
+pub mod ex03_plugins;
 pub mod naming;
 pub mod person;
+pub mod plugin;
@@ -0,0 +1,223 @@
+//! Dynamic dispatch with trait objects: a `Plugin` trait and a registry of `Box<dyn Plugin>`.
+//!
+//! Unlike the generic functions in `naming.rs` (one compiled copy per concrete
+//! type), the registry stores plugins of *different* types in one collection
+//! and picks which `execute` to call at runtime through each box's vtable.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Something the registry can invoke by name with string arguments.
+///
+/// The trait is object safe (no generic methods, no `Self` by value), which is
+/// what allows `Box<dyn Plugin>`.
+pub trait Plugin {
+    /// Unique key the plugin is registered and dispatched under.
+    fn name(&self) -> &str;
+
+    /// One-line help text; plugins may keep the default.
+    fn description(&self) -> &str {
+        "(no description)"
+    }
+
+    /// Run the plugin. `&mut self` lets plugins keep state between calls.
+    fn execute(&mut self, args: &[&str]) -> Result<String, PluginError>;
+}
+
+/// Why a registry operation or plugin call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginError {
+    UnknownPlugin(String),
+    DuplicateName(String),
+    InvalidArgs { plugin: String, message: String },
+}
+
+impl PluginError {
+    /// Shorthand for plugins rejecting their arguments.
+    pub fn invalid_args(plugin: &str, message: impl Into<String>) -> PluginError {
+        PluginError::InvalidArgs {
+            plugin: plugin.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::UnknownPlugin(name) => write!(f, "no plugin named `{name}`"),
+            PluginError::DuplicateName(name) => {
+                write!(f, "a plugin named `{name}` is already registered")
+            }
+            PluginError::InvalidArgs { plugin, message } => write!(f, "{plugin}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// Plugins keyed by name; iteration is in name order.
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: BTreeMap<String, Box<dyn Plugin>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> PluginRegistry {
+        PluginRegistry::default()
+    }
+
+    /// Add a plugin under its own `name()`; a second plugin with the same name is refused.
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) -> Result<(), PluginError> {
+        let name = plugin.name().to_string();
+        if self.plugins.contains_key(&name) {
+            return Err(PluginError::DuplicateName(name));
+        }
+        self.plugins.insert(name, plugin);
+        Ok(())
+    }
+
+    /// Remove and return the plugin called `name`.
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn Plugin>> {
+        self.plugins.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Plugin> {
+        self.plugins.get(name).map(|plugin| plugin.as_ref())
+    }
+
+    /// Registered names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Look up `name` and run it with `args` — the dynamic-dispatch call site.
+    pub fn dispatch(&mut self, name: &str, args: &[&str]) -> Result<String, PluginError> {
+        let plugin = self
+            .plugins
+            .get_mut(name)
+            .ok_or_else(|| PluginError::UnknownPlugin(name.to_string()))?;
+        plugin.execute(args)
+    }
+
+    /// Split `line` on whitespace and dispatch: the first word is the plugin name.
+    pub fn dispatch_line(&mut self, line: &str) -> Result<String, PluginError> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        self.dispatch(name, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl Plugin for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn execute(&mut self, args: &[&str]) -> Result<String, PluginError> {
+            Ok(args.join(" "))
+        }
+    }
+
+    struct Tally {
+        calls: u32,
+    }
+
+    impl Plugin for Tally {
+        fn name(&self) -> &str {
+            "tally"
+        }
+
+        fn description(&self) -> &str {
+            "counts its calls"
+        }
+
+        fn execute(&mut self, args: &[&str]) -> Result<String, PluginError> {
+            if !args.is_empty() {
+                return Err(PluginError::invalid_args("tally", "takes no arguments"));
+            }
+            self.calls += 1;
+            Ok(self.calls.to_string())
+        }
+    }
+
+    fn registry() -> PluginRegistry {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(Tally { calls: 0 })).unwrap();
+        registry.register(Box::new(Echo)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn dispatches_by_name_to_different_types() {
+        let mut registry = registry();
+        assert_eq!(
+            registry.dispatch("echo", &["hi", "there"]).unwrap(),
+            "hi there"
+        );
+        assert_eq!(registry.dispatch_line("tally").unwrap(), "1");
+        assert_eq!(registry.dispatch_line("  tally  ").unwrap(), "2");
+    }
+
+    #[test]
+    fn lookup_lists_names_and_descriptions() {
+        let registry = registry();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["echo", "tally"]);
+        assert_eq!(
+            registry.get("tally").unwrap().description(),
+            "counts its calls"
+        );
+        assert_eq!(
+            registry.get("echo").unwrap().description(),
+            "(no description)"
+        );
+        assert!(registry.get("missing").is_none());
+    }
+
+    #[test]
+    fn duplicate_and_unknown_names_are_errors() {
+        let mut registry = registry();
+        assert_eq!(
+            registry.register(Box::new(Echo)),
+            Err(PluginError::DuplicateName("echo".to_string()))
+        );
+        assert_eq!(
+            registry.dispatch("nope", &[]),
+            Err(PluginError::UnknownPlugin("nope".to_string()))
+        );
+        assert_eq!(
+            registry.dispatch_line(""),
+            Err(PluginError::UnknownPlugin(String::new()))
+        );
+    }
+
+    #[test]
+    fn plugin_errors_pass_through() {
+        let mut registry = registry();
+        let err = registry.dispatch("tally", &["x"]).unwrap_err();
+        assert_eq!(err.to_string(), "tally: takes no arguments");
+    }
+
+    #[test]
+    fn unregister_returns_the_plugin_with_its_state() {
+        let mut registry = registry();
+        registry.dispatch("tally", &[]).unwrap();
+        let mut tally = registry.unregister("tally").unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(tally.execute(&[]).unwrap(), "2");
+    }
+}
@@ -0,0 +1,53 @@
+// Rc + RefCell + Weak together — an N-ary tree from `pointers::tree`.
+// Children are owned through `Rc<RefCell<…>>`; parents are reached through `Weak`, so no cycle leaks.
+//
+// Run: `cargo run --bin ex06_tree`
+
+use pointers::tree::TreeNode;
+
+fn main() {
+    // Build a small directory-like tree; `add_child` returns a handle to the new node.
+    let root = TreeNode::new("/");
+    let home = root.add_child("home");
+    let alice = home.add_child("alice");
+    alice.add_child("notes.txt");
+    alice.add_child("todo.md");
+    let etc = root.add_child("etc");
+    etc.add_child("hosts");
+    print!("{root:?}");
+
+    // Traversals are plain iterators over node handles.
+    let pre_order: Vec<_> = root.depth_first().map(|n| *n.value()).collect();
+    let by_level: Vec<_> = root.breadth_first().map(|n| *n.value()).collect();
+    println!("depth-first:   {pre_order:?}");
+    println!("breadth-first: {by_level:?}");
+
+    // The `Weak` parent link lets a node walk back up without owning its parent.
+    let todo = alice.children()[1].clone();
+    let mut path = vec![*todo.value()];
+    let mut cursor = todo.parent();
+    while let Some(node) = cursor {
+        path.push(*node.value());
+        cursor = node.parent();
+    }
+    path.reverse();
+    println!("path to todo.md: {}", path.join(" > "));
+
+    // Move a subtree: `attach` detaches `alice` from `home` first.
+    etc.attach(&alice).expect("etc is not inside alice");
+    println!("\nafter moving alice under etc:");
+    print!("{root:?}");
+
+    // Making an ancestor its own descendant is refused instead of creating an `Rc` cycle.
+    if let Err(e) = alice.attach(&root) {
+        println!("attach root under alice: {e}");
+    }
+
+    // Dropping every strong handle frees the whole tree; the `Weak` handle sees it go.
+    let weak_alice = alice.downgrade();
+    drop((root, home, alice, etc, todo));
+    println!(
+        "alice still alive after dropping the tree? {}",
+        weak_alice.upgrade().is_some()
+    );
+}
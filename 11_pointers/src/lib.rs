@@ -0,0 +1,3 @@
+//! Chapter 11 — pointers: reusable smart-pointer structures shared with the examples in `src/bin/`.
+
+pub mod tree;
@@ -0,0 +1,361 @@
+//! N-ary tree built from `Rc<RefCell<…>>` child links and `Weak` parent links.
+//!
+//! Parents own their children (strong `Rc`), children only point back at their
+//! parent (`Weak`). Because no cycle of strong references ever forms, dropping
+//! the last handle to the root frees the whole tree — the tests below check
+//! that nothing leaks.
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+struct Node<T> {
+    value: T,
+    parent: Weak<RefCell<Node<T>>>,
+    children: Vec<Rc<RefCell<Node<T>>>>,
+}
+
+/// A handle to one node. Cloning the handle is cheap and shares the node (it is an `Rc`).
+pub struct TreeNode<T>(Rc<RefCell<Node<T>>>);
+
+/// Why a structural change was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    /// Attaching the node would make it its own ancestor.
+    WouldCreateCycle,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::WouldCreateCycle => write!(f, "a node cannot become its own descendant"),
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl<T> TreeNode<T> {
+    /// A new root with no parent and no children.
+    pub fn new(value: T) -> TreeNode<T> {
+        TreeNode(Rc::new(RefCell::new(Node {
+            value,
+            parent: Weak::new(),
+            children: Vec::new(),
+        })))
+    }
+
+    /// Append a new leaf holding `value` and return a handle to it.
+    pub fn add_child(&self, value: T) -> TreeNode<T> {
+        let child = TreeNode::new(value);
+        self.link(&child);
+        child
+    }
+
+    /// Move `child` (and its subtree) under this node as the last child.
+    ///
+    /// A child that already has a parent is detached from it first. Attaching
+    /// this node or one of its ancestors is refused, since that would form a cycle.
+    pub fn attach(&self, child: &TreeNode<T>) -> Result<(), TreeError> {
+        if self.ancestors_and_self().any(|node| node.ptr_eq(child)) {
+            return Err(TreeError::WouldCreateCycle);
+        }
+        child.detach();
+        self.link(child);
+        Ok(())
+    }
+
+    /// Unlink this node from its parent, making it the root of its own tree.
+    /// Returns `false` if it was already a root.
+    pub fn detach(&self) -> bool {
+        let Some(parent) = self.parent() else {
+            return false;
+        };
+        parent
+            .0
+            .borrow_mut()
+            .children
+            .retain(|c| !Rc::ptr_eq(c, &self.0));
+        self.0.borrow_mut().parent = Weak::new();
+        true
+    }
+
+    /// Remove and return the child at `index`, or `None` if out of range.
+    pub fn remove_child(&self, index: usize) -> Option<TreeNode<T>> {
+        let child = {
+            let mut node = self.0.borrow_mut();
+            if index >= node.children.len() {
+                return None;
+            }
+            TreeNode(node.children.remove(index))
+        };
+        child.0.borrow_mut().parent = Weak::new();
+        Some(child)
+    }
+
+    pub fn value(&self) -> Ref<'_, T> {
+        Ref::map(self.0.borrow(), |node| &node.value)
+    }
+
+    pub fn value_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.0.borrow_mut(), |node| &mut node.value)
+    }
+
+    /// The parent, if it is still alive and this node is not a root.
+    pub fn parent(&self) -> Option<TreeNode<T>> {
+        self.0.borrow().parent.upgrade().map(TreeNode)
+    }
+
+    pub fn children(&self) -> Vec<TreeNode<T>> {
+        self.0
+            .borrow()
+            .children
+            .iter()
+            .map(|c| TreeNode(Rc::clone(c)))
+            .collect()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.parent().is_none()
+    }
+
+    /// The root of the tree this node belongs to.
+    pub fn root(&self) -> TreeNode<T> {
+        self.ancestors_and_self()
+            .last()
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Number of edges between this node and its root.
+    pub fn depth(&self) -> usize {
+        self.ancestors_and_self().count() - 1
+    }
+
+    /// Number of nodes in this subtree, including this one.
+    pub fn subtree_size(&self) -> usize {
+        self.depth_first().count()
+    }
+
+    /// `true` when both handles point at the same node.
+    pub fn ptr_eq(&self, other: &TreeNode<T>) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Non-owning handle, e.g. to check later whether the node was freed.
+    pub fn downgrade(&self) -> WeakTreeNode<T> {
+        WeakTreeNode(Rc::downgrade(&self.0))
+    }
+
+    /// This subtree in pre-order: a node, then each child's subtree left to right.
+    pub fn depth_first(&self) -> DepthFirst<T> {
+        DepthFirst {
+            stack: vec![self.clone()],
+        }
+    }
+
+    /// This subtree level by level, left to right.
+    pub fn breadth_first(&self) -> BreadthFirst<T> {
+        BreadthFirst {
+            queue: VecDeque::from([self.clone()]),
+        }
+    }
+
+    // This node, its parent, its grandparent, … up to the root.
+    fn ancestors_and_self(&self) -> impl Iterator<Item = TreeNode<T>> {
+        std::iter::successors(Some(self.clone()), |node| node.parent())
+    }
+
+    fn link(&self, child: &TreeNode<T>) {
+        child.0.borrow_mut().parent = Rc::downgrade(&self.0);
+        self.0.borrow_mut().children.push(Rc::clone(&child.0));
+    }
+}
+
+impl<T> Clone for TreeNode<T> {
+    fn clone(&self) -> Self {
+        TreeNode(Rc::clone(&self.0))
+    }
+}
+
+/// A [`TreeNode`] handle that does not keep the node alive.
+pub struct WeakTreeNode<T>(Weak<RefCell<Node<T>>>);
+
+impl<T> WeakTreeNode<T> {
+    /// The node, or `None` once every strong handle (including its parent's) is gone.
+    pub fn upgrade(&self) -> Option<TreeNode<T>> {
+        self.0.upgrade().map(TreeNode)
+    }
+}
+
+/// Indented outline of the subtree, one node per line.
+impl<T: fmt::Debug> fmt::Debug for TreeNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base = self.depth();
+        for node in self.depth_first() {
+            let indent = "  ".repeat(node.depth() - base);
+            writeln!(f, "{indent}{:?}", *node.value())?;
+        }
+        Ok(())
+    }
+}
+
+/// Pre-order iterator returned by [`TreeNode::depth_first`].
+pub struct DepthFirst<T> {
+    stack: Vec<TreeNode<T>>,
+}
+
+impl<T> Iterator for DepthFirst<T> {
+    type Item = TreeNode<T>;
+
+    fn next(&mut self) -> Option<TreeNode<T>> {
+        let node = self.stack.pop()?;
+        // Push in reverse so the leftmost child is visited next.
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+/// Level-order iterator returned by [`TreeNode::breadth_first`].
+pub struct BreadthFirst<T> {
+    queue: VecDeque<TreeNode<T>>,
+}
+
+impl<T> Iterator for BreadthFirst<T> {
+    type Item = TreeNode<T>;
+
+    fn next(&mut self) -> Option<TreeNode<T>> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children());
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    // root
+    // ├── a
+    // │   ├── a1
+    // │   └── a2
+    // └── b
+    fn sample() -> (
+        TreeNode<&'static str>,
+        TreeNode<&'static str>,
+        TreeNode<&'static str>,
+    ) {
+        let root = TreeNode::new("root");
+        let a = root.add_child("a");
+        a.add_child("a1");
+        a.add_child("a2");
+        let b = root.add_child("b");
+        (root, a, b)
+    }
+
+    fn values<T: Copy>(nodes: impl Iterator<Item = TreeNode<T>>) -> Vec<T> {
+        nodes.map(|n| *n.value()).collect()
+    }
+
+    #[test]
+    fn traversals_visit_every_node_in_order() {
+        let (root, _, _) = sample();
+        assert_eq!(values(root.depth_first()), ["root", "a", "a1", "a2", "b"]);
+        assert_eq!(values(root.breadth_first()), ["root", "a", "b", "a1", "a2"]);
+        assert_eq!(root.subtree_size(), 5);
+    }
+
+    #[test]
+    fn parent_links_point_back_up() {
+        let (root, a, _) = sample();
+        let a2 = &a.children()[1];
+        assert!(a2.parent().unwrap().ptr_eq(&a));
+        assert!(a2.root().ptr_eq(&root));
+        assert_eq!(a2.depth(), 2);
+        assert!(root.is_root());
+    }
+
+    #[test]
+    fn remove_child_returns_an_independent_subtree() {
+        let (root, _, _) = sample();
+        let a = root.remove_child(0).unwrap();
+        assert!(a.is_root());
+        assert_eq!(a.subtree_size(), 3);
+        assert_eq!(values(root.depth_first()), ["root", "b"]);
+        assert!(root.remove_child(5).is_none());
+    }
+
+    #[test]
+    fn attach_moves_a_subtree_between_parents() {
+        let (root, a, b) = sample();
+        b.attach(&a).unwrap();
+        assert_eq!(values(root.depth_first()), ["root", "b", "a", "a1", "a2"]);
+        assert!(a.parent().unwrap().ptr_eq(&b));
+        assert_eq!(a.children()[0].depth(), 3);
+    }
+
+    #[test]
+    fn attach_refuses_cycles() {
+        let (root, a, _) = sample();
+        let a1 = a.children()[0].clone();
+        assert_eq!(a1.attach(&root), Err(TreeError::WouldCreateCycle));
+        assert_eq!(a.attach(&a), Err(TreeError::WouldCreateCycle));
+        assert_eq!(root.subtree_size(), 5);
+    }
+
+    #[test]
+    fn value_mut_edits_in_place() {
+        let root = TreeNode::new(1);
+        let child = root.add_child(2);
+        *child.value_mut() += 40;
+        assert_eq!(values(root.depth_first()), [1, 42]);
+    }
+
+    #[test]
+    fn dropping_the_root_frees_every_node() {
+        let (root, a, b) = sample();
+        let weak_nodes: Vec<_> = root.depth_first().map(|n| n.downgrade()).collect();
+        drop((a, b));
+        assert!(weak_nodes.iter().all(|w| w.upgrade().is_some()));
+        drop(root);
+        assert!(weak_nodes.iter().all(|w| w.upgrade().is_none()));
+    }
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn every_value_is_dropped_exactly_once() {
+        let dropped = Rc::new(Cell::new(0));
+        {
+            let root = TreeNode::new(DropCounter(Rc::clone(&dropped)));
+            let child = root.add_child(DropCounter(Rc::clone(&dropped)));
+            for _ in 0..3 {
+                child.add_child(DropCounter(Rc::clone(&dropped)));
+            }
+            // Detached subtrees are freed as soon as their last handle goes too.
+            let orphan = child.remove_child(0).unwrap();
+            drop(orphan);
+            assert_eq!(dropped.get(), 1);
+        }
+        assert_eq!(dropped.get(), 5);
+    }
+
+    #[test]
+    fn handles_share_the_strong_count_only_with_the_parent() {
+        let root = TreeNode::new(0);
+        let child = root.add_child(1);
+        // One strong ref from the parent's `children`, one from our handle.
+        assert_eq!(Rc::strong_count(&child.0), 2);
+        // The child's back link to the root is weak.
+        assert_eq!(Rc::strong_count(&root.0), 1);
+        assert_eq!(Rc::weak_count(&root.0), 1);
+    }
+}
@@ -0,0 +1,3 @@
+//! Chapter 12 — generics: reusable generic types shared with the demo in `main.rs`.
+
+pub mod vecn;
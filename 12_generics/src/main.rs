@@ -1,5 +1,5 @@
 // Chapter examples: generics on structs, trait implementations, and blanket impls.
-use std::ops::AddAssign;
+use generics::vecn::{Point, VecN};
 
 // A concrete struct: both coordinates are always `i32`.
 #[derive(Debug)]
@@ -8,51 +8,8 @@ struct IntPoint {
     y: i32,
 }
 
-// A generic struct: `x` and `y` share the same type parameter `T`.
-#[derive(Debug)]
-struct Point<T> {
-    x: T,
-    y: T,
-}
-
-// Inherent impl: methods that belong to `Point<T>` for any `T`.
-// `AddAssign<T>` is required so `self.x += x` type-checks (same for `y`).
-impl<T> Point<T> {
-    fn move_point(&mut self, x: T, y: T)
-    where
-        T: AddAssign<T>,
-    {
-        self.x += x;
-        self.y += y;
-    }
-}
-
-// Implement the standard `+=` operator for points via `AddAssign`.
-// After this, `point += other_point` works when `T` supports `+=`.
-impl<T: AddAssign> AddAssign for Point<T> {
-    fn add_assign(&mut self, other: Self) {
-        self.x += other.x;
-        self.y += other.y;
-    }
-}
-
-// Implement equality so you can use `==` and `!=` on two `Point<T>` values.
-// `Self` is the type (`Point<T>`); `self` / `other` are the values being compared.
-impl<T: PartialEq> PartialEq for Point<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
-    }
-}
-
-// Specialization for `Point<String>`: `String` uses `+=` with `&str`, not with `String`,
-// so we offer a dedicated helper instead of forcing awkward generic bounds.
-impl Point<String> {
-    fn move_point_str(&mut self, x: &str, y: &str) {
-        self.x += x;
-        self.y += y;
-    }
-}
-
+// `Point<T>` is the 2D case of the generic `VecN<T, N>` in `src/vecn.rs`:
+// `T` is the element type, `N` a const generic for the number of components.
 // --- Custom traits (our own interfaces) ---
 
 trait CanRun {
@@ -114,12 +71,9 @@ fn main() {
     println!("int_point x : {}, y : {}", int_point.x, int_point.y);
 
     // Same struct, three different `T`s: `i32`, `f64`, `String`
-    let mut int_point_generic = Point { x: 1, y: 2 };
-    let mut float_point = Point { x: 1.0, y: 2.0 };
-    let mut string_point = Point {
-        x: String::from("1"),
-        y: String::from("2"),
-    };
+    let mut int_point_generic = Point::new([1, 2]);
+    let mut float_point = Point::new([1.0, 2.0]);
+    let mut string_point = Point::new([String::from("1"), String::from("2")]);
 
     int_point_generic.move_point(1, 2);
     println!("int_point_generic: {:?}", int_point_generic);
@@ -128,18 +82,44 @@ fn main() {
     string_point.move_point_str("1", "2");
     println!("string_point: {:?}", string_point);
 
-    // Uses our `AddAssign for Point<T>` impl
-    let int_point_generic2 = Point { x: 10, y: 20 };
+    // Uses our `AddAssign for VecN<T, N>` impl
+    let int_point_generic2 = Point::new([10, 20]);
     int_point_generic += int_point_generic2;
     println!("int_point_generic: {:?}", int_point_generic);
 
-    // Uses our `PartialEq for Point<T>` impl
-    let int_point_generic3 = Point { x: 10, y: 20 };
+    // Uses our `PartialEq for VecN<T, N>` impl
+    let int_point_generic3 = Point::new([10, 20]);
     if int_point_generic == int_point_generic3 {
         println!("int_point_generic is equal to int_point_generic3");
     } else {
         println!("int_point_generic is not equal to int_point_generic3");
     }
+    println!(
+        "int_point_generic x : {}, y : {}",
+        int_point_generic.x(),
+        int_point_generic.y()
+    );
+
+    // Same code, three components: operators, dot product, length, interpolation.
+    let a = VecN::new([1.0, 2.0, 2.0]);
+    let b = VecN::new([4.0, 0.0, -1.0]);
+    println!("a + b = {:?}", a + b);
+    println!("a - b = {:?}", a - b);
+    println!("a * 2 = {:?}, -a = {:?}", a * 2.0, -a);
+    println!("a . b = {}, |a| = {}", a.dot(&b), a.norm());
+    println!("halfway from a to b: {:?}", a.lerp(b, 0.5));
+
+    // `distance`, `angle_between`, … are written once against `num_traits::Float`,
+    // so the same methods work on `f32` vectors.
+    let p = Point::new([3.0_f32, 4.0]);
+    let q = Point::new([4.0_f32, 0.0]);
+    println!("distance p -> q: {}", p.distance(&q));
+    println!("p normalized: {:?}", p.normalized());
+    if let Some(angle) = p.angle_between(&q) {
+        println!("angle between p and q: {:.1} degrees", angle.to_degrees());
+    }
+    println!("p projected onto q: {:?}", p.project_onto(&q));
+    // `a + int_point_generic` does not compile: `VecN<f64, 3>` and `VecN<i32, 2>` differ.
 
     // `Vec<Person>` gets `CanRun` / `CanWalk` from the blanket impls above.
     let people = vec![
@@ -0,0 +1,297 @@
+//! `VecN<T, N>`: a fixed-size vector generic over both its element type and its length.
+//!
+//! `T` is an ordinary type parameter; `N` is a *const generic*, so `VecN<f64, 3>`
+//! and `VecN<f64, 2>` are different types and mixing them is a compile error.
+//! `Point<T>` is just the two-dimensional case.
+
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub};
+
+use num_traits::{Float, Zero};
+
+/// `N` components of type `T`.
+#[derive(Debug, Clone, Copy)]
+pub struct VecN<T, const N: usize> {
+    components: [T; N],
+}
+
+/// A 2D point is a `VecN` with two components (`x`, `y`).
+pub type Point<T> = VecN<T, 2>;
+
+impl<T, const N: usize> VecN<T, N> {
+    pub fn new(components: [T; N]) -> Self {
+        VecN { components }
+    }
+
+    pub fn components(&self) -> &[T; N] {
+        &self.components
+    }
+
+    pub fn into_components(self) -> [T; N] {
+        self.components
+    }
+
+    // Combine two vectors component by component. `array::map` moves each element,
+    // so this works for non-`Copy` `T` such as `String` too.
+    fn zip_with<U, R>(self, other: VecN<U, N>, mut f: impl FnMut(T, U) -> R) -> VecN<R, N> {
+        let mut other = other.components.into_iter();
+        VecN::new(self.components.map(|a| f(a, other.next().unwrap())))
+    }
+}
+
+// Methods that only exist when `N == 2`: named accessors for points.
+impl<T> Point<T> {
+    pub fn x(&self) -> &T {
+        &self.components[0]
+    }
+
+    pub fn y(&self) -> &T {
+        &self.components[1]
+    }
+
+    // `AddAssign<T>` is required so `self.x += x` type-checks (same for `y`).
+    pub fn move_point(&mut self, x: T, y: T)
+    where
+        T: AddAssign<T>,
+    {
+        self.components[0] += x;
+        self.components[1] += y;
+    }
+}
+
+// Specialization for `Point<String>`: `String` uses `+=` with `&str`, not with `String`,
+// so we offer a dedicated helper instead of forcing awkward generic bounds.
+impl Point<String> {
+    pub fn move_point_str(&mut self, x: &str, y: &str) {
+        self.components[0] += x;
+        self.components[1] += y;
+    }
+}
+
+// `Zero` (from `num_traits`) supplies both `T::zero()` and `+`, so this block works
+// for integers and floats alike.
+impl<T: Copy + Zero + Mul<Output = T>, const N: usize> VecN<T, N> {
+    /// Sum of component-wise products.
+    pub fn dot(&self, other: &Self) -> T {
+        self.components
+            .iter()
+            .zip(&other.components)
+            .fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+    }
+
+    /// `self · self`, the squared length (no square root, so it works for integers).
+    pub fn norm_squared(&self) -> T {
+        self.dot(self)
+    }
+}
+
+// Everything below needs a square root or a division, so it is bounded by
+// `num_traits::Float` and works for `f32` and `f64` with one implementation.
+// Operations that would divide by a zero length return `None` instead of NaN.
+impl<T: Float, const N: usize> VecN<T, N> {
+    /// Euclidean length.
+    pub fn norm(&self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    /// Euclidean distance between two points.
+    pub fn distance(&self, other: &Self) -> T {
+        (*self - *other).norm()
+    }
+
+    /// Unit-length vector pointing the same way, or `None` for the zero vector.
+    pub fn normalized(&self) -> Option<Self> {
+        let norm = self.norm();
+        if norm.is_zero() {
+            return None;
+        }
+        Some(*self * norm.recip())
+    }
+
+    /// Angle between the two vectors in radians (`0..=π`), or `None` if either is zero.
+    pub fn angle_between(&self, other: &Self) -> Option<T> {
+        let lengths = self.norm() * other.norm();
+        if lengths.is_zero() {
+            return None;
+        }
+        // Rounding can push the cosine just past ±1, where `acos` would return NaN.
+        let cos = (self.dot(other) / lengths).max(-T::one()).min(T::one());
+        Some(cos.acos())
+    }
+
+    /// Component of `self` along `onto` (the vector projection), or `None` if `onto` is zero.
+    pub fn project_onto(&self, onto: &Self) -> Option<Self> {
+        let onto_sq = onto.norm_squared();
+        if onto_sq.is_zero() {
+            return None;
+        }
+        Some(*onto * (self.dot(onto) / onto_sq))
+    }
+
+    /// Point `t` of the way from `self` to `other` (`t = 0` is `self`, `t = 1` is `other`).
+    pub fn lerp(self, other: Self, t: T) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl<T, const N: usize> Index<usize> for VecN<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.components[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for VecN<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.components[i]
+    }
+}
+
+// `a + b` and `a - b`, component by component.
+impl<T: Add<Output = T>, const N: usize> Add for VecN<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<T: Sub<Output = T>, const N: usize> Sub for VecN<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+// `v * scalar`: scales every component by the same value.
+impl<T: Copy + Mul<Output = T>, const N: usize> Mul<T> for VecN<T, N> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        VecN::new(self.components.map(|a| a * scalar))
+    }
+}
+
+impl<T: Neg<Output = T>, const N: usize> Neg for VecN<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        VecN::new(self.components.map(|a| -a))
+    }
+}
+
+// Implement the standard `+=` operator via `AddAssign`.
+// After this, `point += other_point` works when `T` supports `+=`.
+impl<T: AddAssign, const N: usize> AddAssign for VecN<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        for (a, b) in self.components.iter_mut().zip(other.components) {
+            *a += b;
+        }
+    }
+}
+
+// Implement equality so you can use `==` and `!=` on two vectors.
+// `Self` is the type (`VecN<T, N>`); `self` / `other` are the values being compared.
+impl<T: PartialEq, const N: usize> PartialEq for VecN<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.components == other.components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_work_component_wise() {
+        let a = VecN::new([1, 2, 3]);
+        let b = VecN::new([4, 5, 6]);
+        assert_eq!(a + b, VecN::new([5, 7, 9]));
+        assert_eq!(b - a, VecN::new([3, 3, 3]));
+        assert_eq!(a * 2, VecN::new([2, 4, 6]));
+        assert_eq!(-a, VecN::new([-1, -2, -3]));
+    }
+
+    #[test]
+    fn dot_and_norm() {
+        let a = VecN::new([1, 2, 3]);
+        assert_eq!(a.dot(&VecN::new([4, -5, 6])), 12);
+        assert_eq!(a.norm_squared(), 14);
+        assert_eq!(VecN::new([3.0, 4.0]).norm(), 5.0);
+    }
+
+    #[test]
+    fn lerp_hits_both_ends_and_the_midpoint() {
+        let a = VecN::new([0.0, 10.0, -4.0]);
+        let b = VecN::new([2.0, 20.0, 4.0]);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), VecN::new([1.0, 15.0, 0.0]));
+    }
+
+    fn close<T: Float>(a: T, b: T) -> bool {
+        (a - b).abs() <= T::epsilon() * T::from(16).unwrap()
+    }
+
+    // Written once against `Float`, run for both `f32` and `f64` below.
+    fn check_float_utilities<T: Float + std::fmt::Debug>() {
+        let n = |v: f64| T::from(v).unwrap();
+        let a = VecN::new([n(3.0), n(4.0)]);
+        let b = VecN::new([n(0.0), n(2.0)]);
+
+        assert!(close(a.norm(), n(5.0)));
+        assert!(close(a.distance(&b), n(13.0).sqrt()));
+
+        let unit = a.normalized().unwrap();
+        assert!(close(unit.norm(), T::one()));
+        assert!(close(unit[0], n(0.6)) && close(unit[1], n(0.8)));
+
+        let x_axis = VecN::new([T::one(), T::zero()]);
+        let y_axis = VecN::new([T::zero(), n(5.0)]);
+        assert!(close(
+            x_axis.angle_between(&y_axis).unwrap(),
+            n(std::f64::consts::FRAC_PI_2)
+        ));
+        assert!(close(
+            x_axis.angle_between(&(-x_axis)).unwrap(),
+            n(std::f64::consts::PI)
+        ));
+        assert!(close(a.angle_between(&(a * n(3.0))).unwrap(), T::zero()));
+
+        let projected = a.project_onto(&x_axis).unwrap();
+        assert_eq!(projected, VecN::new([n(3.0), T::zero()]));
+        // What is left over is perpendicular to the axis.
+        assert!(close((a - projected).dot(&x_axis), T::zero()));
+
+        let zero = VecN::new([T::zero(), T::zero()]);
+        assert_eq!(zero.normalized(), None);
+        assert_eq!(a.angle_between(&zero), None);
+        assert_eq!(a.project_onto(&zero), None);
+    }
+
+    #[test]
+    fn float_utilities_f32() {
+        check_float_utilities::<f32>();
+    }
+
+    #[test]
+    fn float_utilities_f64() {
+        check_float_utilities::<f64>();
+    }
+
+    #[test]
+    fn angle_stays_finite_for_nearly_parallel_vectors() {
+        let a = VecN::new([0.1_f32, 0.2, 0.3]);
+        let angle = a.angle_between(&(a * 7.0)).unwrap();
+        assert!(angle.is_finite() && angle < 1e-3);
+    }
+
+    #[test]
+    fn string_points_move_by_appending() {
+        let mut p = Point::new([String::from("1"), String::from("2")]);
+        p.move_point_str("a", "b");
+        assert_eq!((p.x().as_str(), p.y().as_str()), ("1a", "2b"));
+        assert_eq!(p[0], "1a");
+    }
+}
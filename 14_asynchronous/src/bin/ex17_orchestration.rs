@@ -0,0 +1,3 @@
+fn main() {
+    asynchronous::ex17_orchestration::run();
+}
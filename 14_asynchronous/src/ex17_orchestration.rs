@@ -0,0 +1,104 @@
+//! Topic 14 — Orchestrating many tasks: bounded concurrency, timeouts, and retry with backoff.
+//!
+//! Run via: `cargo run --bin ex17_orchestration`
+
+use std::cell::Cell;
+
+use tokio::time::{Duration, Instant, sleep};
+
+use crate::helpers;
+use crate::orchestration::{Backoff, join_all_limited, retry_with_backoff, with_timeout};
+
+/// A pretend HTTP endpoint: answers after `latency`, failing its first `flaky_calls` requests.
+struct FakeApi {
+    name: &'static str,
+    latency: Duration,
+    flaky_calls: u32,
+    calls: Cell<u32>,
+}
+
+impl FakeApi {
+    fn new(name: &'static str, latency_ms: u64, flaky_calls: u32) -> FakeApi {
+        FakeApi {
+            name,
+            latency: Duration::from_millis(latency_ms),
+            flaky_calls,
+            calls: Cell::new(0),
+        }
+    }
+
+    async fn fetch(&self) -> Result<String, String> {
+        self.calls.set(self.calls.get() + 1);
+        sleep(self.latency).await;
+        if self.calls.get() <= self.flaky_calls {
+            Err(format!("{}: 503 Service Unavailable", self.name))
+        } else {
+            Ok(format!("{}: 200 OK", self.name))
+        }
+    }
+}
+
+fn apis() -> Vec<FakeApi> {
+    vec![
+        FakeApi::new("users", 120, 0),
+        FakeApi::new("orders", 200, 0),
+        FakeApi::new("inventory", 100, 2),
+        FakeApi::new("pricing", 80, 0),
+        FakeApi::new("reviews", 900, 0),
+        FakeApi::new("shipping", 100, 1),
+    ]
+}
+
+async fn run_inner() {
+    helpers::section("Sequential baseline");
+    let started = Instant::now();
+    for api in &apis() {
+        let _ = api.fetch().await;
+    }
+    println!("6 calls one after another: {:.0?}", started.elapsed());
+
+    helpers::section("join_all_limited: at most 3 in flight, with timeout + retry");
+    let services = apis();
+    let policy = Backoff {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(50),
+        ..Backoff::default()
+    };
+    let started = Instant::now();
+    let calls = services.iter().map(|api| async move {
+        let call_started = Instant::now();
+        // The deadline covers every retry of this one service.
+        let outcome = with_timeout(
+            Duration::from_millis(500),
+            retry_with_backoff(policy, |_attempt| api.fetch()),
+        )
+        .await;
+        (api, outcome, call_started.elapsed())
+    });
+    let results = join_all_limited(calls, 3).await;
+    let total = started.elapsed();
+
+    for (api, outcome, took) in &results {
+        let attempts = api.calls.get();
+        match outcome {
+            Ok(body) => println!(
+                "{:<10} ok    {took:>10.0?}  attempts {attempts}  {body}",
+                api.name
+            ),
+            Err(e) => println!(
+                "{:<10} FAIL  {took:>10.0?}  attempts {attempts}  {e}",
+                api.name
+            ),
+        }
+    }
+    let ok = results.iter().filter(|(_, r, _)| r.is_ok()).count();
+    println!(
+        "\n{ok}/{} succeeded, total wall-clock {total:.0?}",
+        results.len()
+    );
+}
+
+pub fn run() {
+    let rt = tokio::runtime::Runtime::new().expect("failed to build tokio runtime");
+    rt.block_on(run_inner());
+}
@@ -17,5 +17,7 @@ pub mod ex13_spawn_blocking;
 pub mod ex14_runtime_builder;
 pub mod ex15_cancellation;
 pub mod ex16_joinset;
+pub mod ex17_orchestration;
 
 pub mod helpers;
+pub mod orchestration;
@@ -0,0 +1,201 @@
+//! Small task-orchestration toolkit: bounded `join_all`, per-task timeouts, and retry with backoff.
+//!
+//! Everything here composes plain futures — nothing is spawned — so the helpers
+//! work with borrowed data and with `!Send` futures. Timers come from tokio, so
+//! the futures must be polled inside a tokio runtime.
+
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use tokio::time::{sleep, timeout};
+
+/// Run `futures` with at most `limit` in flight at once; results keep the input order.
+///
+/// A `limit` of 0 is treated as 1, so the futures still run (one at a time).
+pub async fn join_all_limited<I>(futures: I, limit: usize) -> Vec<<I::Item as Future>::Output>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    // `buffered` polls up to `limit` futures concurrently and yields results in order.
+    stream::iter(futures).buffered(limit.max(1)).collect().await
+}
+
+/// Why a guarded task did not produce a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError<E> {
+    /// The task was still running when its deadline passed (it has been dropped).
+    TimedOut(Duration),
+    /// The task finished with its own error.
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for TaskError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::TimedOut(limit) => write!(f, "timed out after {limit:?}"),
+            TaskError::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for TaskError<E> {}
+
+/// Await a fallible `task`, giving up after `limit`; both failure modes end up in one error type.
+pub async fn with_timeout<T, E, F>(limit: Duration, task: F) -> Result<T, TaskError<E>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    match timeout(limit, task).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(TaskError::Failed(e)),
+        Err(_) => Err(TaskError::TimedOut(limit)),
+    }
+}
+
+/// Exponential backoff: wait `initial_delay`, then `× multiplier` after each failure, capped at `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Total tries, including the first one (at least 1 is always made).
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub multiplier: f64,
+    pub max_delay: Duration,
+}
+
+impl Default for Backoff {
+    /// 4 attempts waiting 100 ms, 200 ms, 400 ms in between.
+    fn default() -> Self {
+        Backoff {
+            max_attempts: 4,
+            initial_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Backoff {
+    /// Pause after failed attempt number `attempt` (1-based).
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+/// Call `operation` until it succeeds or `policy.max_attempts` is used up, sleeping between tries.
+///
+/// `operation` receives the 1-based attempt number and must build a fresh future
+/// each time (a future can only be awaited once). The last error is returned.
+pub async fn retry_with_backoff<T, E, F, Fut>(policy: Backoff, mut operation: F) -> Result<T, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= policy.max_attempts => return Err(e),
+            Err(_) => {
+                sleep(policy.delay_after(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use tokio::time::Instant;
+
+    use super::*;
+
+    // Paused-clock tests: tokio auto-advances time when every task is asleep,
+    // so sleeps are instant yet elapsed times are still exact.
+
+    #[tokio::test(start_paused = true)]
+    async fn join_all_limited_caps_concurrency_and_keeps_order() {
+        let running = Cell::new(0);
+        let peak = Cell::new(0);
+        let started = Instant::now();
+        let tasks = (0..6u64).map(|i| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                running.set(running.get() + 1);
+                peak.set(peak.get().max(running.get()));
+                sleep(Duration::from_millis(100 - i * 10)).await;
+                running.set(running.get() - 1);
+                i
+            }
+        });
+        let results = join_all_limited(tasks, 2).await;
+        assert_eq!(results, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(peak.get(), 2);
+        // Six tasks of 100..50 ms, two lanes: far less than running them one by one.
+        assert!(started.elapsed() < Duration::from_millis(450));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn with_timeout_distinguishes_failure_from_timeout() {
+        let slow = async {
+            sleep(Duration::from_secs(5)).await;
+            Ok::<_, String>(1)
+        };
+        let failing = async { Err::<u8, _>("boom".to_string()) };
+        assert_eq!(
+            with_timeout(Duration::from_secs(1), slow).await,
+            Err(TaskError::TimedOut(Duration::from_secs(1)))
+        );
+        assert_eq!(
+            with_timeout(Duration::from_secs(1), failing).await,
+            Err(TaskError::Failed("boom".to_string()))
+        );
+        assert_eq!(
+            with_timeout(Duration::from_secs(1), async { Ok::<_, ()>(7) }).await,
+            Ok(7)
+        );
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        let policy = Backoff {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            multiplier: 3.0,
+            max_delay: Duration::from_secs(1),
+        };
+        assert_eq!(policy.delay_after(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_after(2), Duration::from_millis(300));
+        assert_eq!(policy.delay_after(3), Duration::from_millis(900));
+        assert_eq!(policy.delay_after(4), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_succeeds_after_transient_failures() {
+        let started = Instant::now();
+        let result = retry_with_backoff(Backoff::default(), |attempt| async move {
+            if attempt < 3 { Err(attempt) } else { Ok("ok") }
+        })
+        .await;
+        assert_eq!(result, Ok("ok"));
+        // Waited 100 ms + 200 ms between the three attempts.
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_gives_up_with_the_last_error() {
+        let calls = Cell::new(0);
+        let result: Result<(), u32> = retry_with_backoff(Backoff::default(), |attempt| {
+            calls.set(calls.get() + 1);
+            async move { Err(attempt) }
+        })
+        .await;
+        assert_eq!(result, Err(4));
+        assert_eq!(calls.get(), 4);
+    }
+}
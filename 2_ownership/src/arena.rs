@@ -0,0 +1,325 @@
+//! Two ways to let many values point at each other without `Rc`:
+//!
+//! - [`Arena<T>`] hands out plain `&T` references that all live as long as the
+//!   arena. Values are never moved or freed individually, so nodes can borrow each
+//!   other freely (even in cycles, via `Cell`/`RefCell` fields).
+//! - [`GenArena<T>`] stores values in slots addressed by a copyable [`Index`].
+//!   Removed slots are reused, and each reuse bumps the slot's generation, so an
+//!   index to a removed value reports "gone" instead of silently reading the new one.
+
+use std::cell::RefCell;
+use std::fmt;
+
+/// Capacity of the first chunk; every later chunk doubles the previous one.
+const FIRST_CHUNK: usize = 8;
+
+/// A typed bump arena: `alloc` takes `&self` and returns a reference valid for the arena's lifetime.
+///
+/// Values are stored in chunks. A chunk never grows past its capacity, so pushing
+/// never reallocates it and references into it stay valid; a full chunk is kept as
+/// is and a new, bigger one is started. Everything is dropped together with the arena.
+pub struct Arena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena::with_capacity(FIRST_CHUNK)
+    }
+
+    /// Arena whose first chunk holds `capacity` values before another chunk is needed.
+    pub fn with_capacity(capacity: usize) -> Arena<T> {
+        Arena {
+            chunks: RefCell::new(vec![Vec::with_capacity(capacity.max(1))]),
+        }
+    }
+
+    /// Move `value` into the arena and borrow it for as long as the arena lives.
+    pub fn alloc(&self, value: T) -> &T {
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks.last().expect("an arena always has a chunk");
+        if last.len() == last.capacity() {
+            let next = last.capacity() * 2;
+            chunks.push(Vec::with_capacity(next));
+        }
+        let chunk = chunks.last_mut().expect("an arena always has a chunk");
+        chunk.push(value);
+        let ptr: *const T = chunk.last().expect("just pushed");
+        // SAFETY: `ptr` points into a chunk's heap buffer. Chunks are only ever pushed
+        // to below their capacity, so the buffer is never reallocated, and neither the
+        // chunks nor their values are removed or handed out mutably while `&self` is
+        // borrowed. The value therefore stays put and unaliased-by-`&mut` for the
+        // lifetime of the returned reference.
+        unsafe { &*ptr }
+    }
+
+    /// Number of values allocated so far.
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Consume the arena and return every value in allocation order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.chunks.into_inner().into_iter().flatten().collect()
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl<T> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena").field("len", &self.len()).finish()
+    }
+}
+
+/// Handle to a value in a [`GenArena`]: a slot number plus the generation it was issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    slot: usize,
+    generation: u32,
+}
+
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}v{}", self.slot, self.generation)
+    }
+}
+
+#[derive(Debug)]
+enum Slot<T> {
+    Occupied {
+        generation: u32,
+        value: T,
+    },
+    // `next_free` chains the free list through vacant slots.
+    Vacant {
+        generation: u32,
+        next_free: Option<usize>,
+    },
+}
+
+/// Slot storage addressed by generational [`Index`]es, with O(1) insert, lookup, and remove.
+#[derive(Debug)]
+pub struct GenArena<T> {
+    slots: Vec<Slot<T>>,
+    free_head: Option<usize>,
+    len: usize,
+}
+
+impl<T> GenArena<T> {
+    pub fn new() -> GenArena<T> {
+        GenArena {
+            slots: Vec::new(),
+            free_head: None,
+            len: 0,
+        }
+    }
+
+    /// Store `value`, reusing a freed slot when there is one.
+    pub fn insert(&mut self, value: T) -> Index {
+        self.len += 1;
+        match self.free_head {
+            Some(slot) => {
+                let Slot::Vacant {
+                    generation,
+                    next_free,
+                } = self.slots[slot]
+                else {
+                    unreachable!("free list only links vacant slots");
+                };
+                self.free_head = next_free;
+                self.slots[slot] = Slot::Occupied { generation, value };
+                Index { slot, generation }
+            }
+            None => {
+                self.slots.push(Slot::Occupied {
+                    generation: 0,
+                    value,
+                });
+                Index {
+                    slot: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Remove and return the value at `index`; `None` if it was already removed.
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        if !self.contains(index) {
+            return None;
+        }
+        let vacant = Slot::Vacant {
+            generation: index.generation.wrapping_add(1),
+            next_free: self.free_head,
+        };
+        let Slot::Occupied { value, .. } = std::mem::replace(&mut self.slots[index.slot], vacant)
+        else {
+            unreachable!("`contains` checked the slot is occupied");
+        };
+        self.free_head = Some(index.slot);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// `true` while the value `index` was issued for is still stored.
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.slots.get(index.slot)? {
+            Slot::Occupied { generation, value } if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.slots.get_mut(index.slot)? {
+            Slot::Occupied { generation, value } if *generation == index.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Live values with their indices, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Slot::Occupied { generation, value } => Some((
+                    Index {
+                        slot,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Slot::Vacant { .. } => None,
+            })
+    }
+}
+
+impl<T> Default for GenArena<T> {
+    fn default() -> Self {
+        GenArena::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn alloc_returns_references_that_outlive_later_allocs() {
+        let arena = Arena::with_capacity(2);
+        let first = arena.alloc(String::from("first"));
+        // Force several new chunks; `first` must still point at its value.
+        let rest: Vec<&String> = (0..100).map(|i| arena.alloc(i.to_string())).collect();
+        assert_eq!(first, "first");
+        assert_eq!(rest[99], "99");
+        assert_eq!(arena.len(), 101);
+    }
+
+    #[test]
+    fn into_vec_keeps_allocation_order() {
+        let arena = Arena::with_capacity(1);
+        for i in 0..10 {
+            arena.alloc(i);
+        }
+        assert_eq!(arena.into_vec(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn arena_nodes_can_form_cycles() {
+        struct Node<'a> {
+            name: &'static str,
+            next: Cell<Option<&'a Node<'a>>>,
+        }
+        let arena = Arena::new();
+        let a = arena.alloc(Node {
+            name: "a",
+            next: Cell::new(None),
+        });
+        let b = arena.alloc(Node {
+            name: "b",
+            next: Cell::new(Some(a)),
+        });
+        a.next.set(Some(b));
+        assert_eq!(a.next.get().unwrap().next.get().unwrap().name, "a");
+    }
+
+    #[test]
+    fn values_are_dropped_with_the_arena() {
+        struct DropCounter<'a>(&'a RefCell<usize>);
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                *self.0.borrow_mut() += 1;
+            }
+        }
+        let drops = RefCell::new(0);
+        {
+            let arena = Arena::with_capacity(3);
+            for _ in 0..10 {
+                arena.alloc(DropCounter(&drops));
+            }
+            assert_eq!(*drops.borrow(), 0);
+        }
+        assert_eq!(*drops.borrow(), 10);
+    }
+
+    #[test]
+    fn gen_arena_insert_get_remove() {
+        let mut pool = GenArena::new();
+        let a = pool.insert("a");
+        let b = pool.insert("b");
+        assert_eq!(pool.get(a), Some(&"a"));
+        assert_eq!(pool.len(), 2);
+
+        assert_eq!(pool.remove(a), Some("a"));
+        assert_eq!(pool.remove(a), None);
+        assert_eq!(pool.get(a), None);
+        assert_eq!(pool.get(b), Some(&"b"));
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn stale_index_does_not_see_the_slot_reuse() {
+        let mut pool = GenArena::new();
+        let old = pool.insert(1);
+        pool.remove(old);
+        let new = pool.insert(2);
+        assert_eq!(old.slot, new.slot, "freed slot is reused");
+        assert_ne!(old, new);
+        assert_eq!(pool.get(old), None);
+        assert_eq!(pool.get_mut(old), None);
+        assert_eq!(pool.get(new), Some(&2));
+    }
+
+    #[test]
+    fn iter_skips_removed_values() {
+        let mut pool = GenArena::new();
+        let ids: Vec<Index> = (0..5).map(|i| pool.insert(i)).collect();
+        pool.remove(ids[1]);
+        pool.remove(ids[3]);
+        *pool.get_mut(ids[4]).unwrap() *= 10;
+        let live: Vec<i32> = pool.iter().map(|(_, v)| *v).collect();
+        assert_eq!(live, [0, 2, 40]);
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    ownership::ex04_arena_graph::run();
+}
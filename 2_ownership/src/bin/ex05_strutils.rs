@@ -0,0 +1,3 @@
+fn main() {
+    ownership::ex05_strutils::run();
+}
@@ -0,0 +1,106 @@
+//! Topic 4 — Building a graph without `Rc`: arena references and generational indices.
+//!
+//! Run via: `cargo run --bin ex04_arena_graph`
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use crate::arena::{Arena, GenArena, Index};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+/// A city whose roads borrow other cities from the same arena.
+struct City<'a> {
+    name: &'static str,
+    roads: RefCell<Vec<&'a City<'a>>>,
+}
+
+impl<'a> City<'a> {
+    fn new(name: &'static str) -> City<'a> {
+        City {
+            name,
+            roads: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn connect(&'a self, other: &'a City<'a>) {
+        self.roads.borrow_mut().push(other);
+        other.roads.borrow_mut().push(self);
+    }
+}
+
+// Depth-first walk; cycles are fine because we only ever hold shared borrows.
+fn reachable<'a>(start: &'a City<'a>) -> Vec<&'static str> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![start];
+    let mut order = Vec::new();
+    while let Some(city) = stack.pop() {
+        if !seen.insert(city.name) {
+            continue;
+        }
+        order.push(city.name);
+        stack.extend(city.roads.borrow().iter().rev());
+    }
+    order
+}
+
+/// A station that refers to its neighbours by `Index`, so it can be removed later.
+struct Station {
+    name: &'static str,
+    links: Vec<Index>,
+}
+
+pub fn run() {
+    section("Arena<T>: nodes borrow each other");
+    let arena = Arena::new();
+    let seoul = arena.alloc(City::new("Seoul"));
+    let busan = arena.alloc(City::new("Busan"));
+    let daegu = arena.alloc(City::new("Daegu"));
+    let jeju = arena.alloc(City::new("Jeju"));
+    seoul.connect(daegu);
+    daegu.connect(busan);
+    busan.connect(seoul); // a cycle — no Rc, no Weak, no leak
+    println!("{} cities in the arena", arena.len());
+    println!("reachable from Seoul: {:?}", reachable(seoul));
+    println!("reachable from Jeju:  {:?}", reachable(jeju));
+    // Everything is freed at once when `arena` goes out of scope.
+
+    section("GenArena<T>: removable nodes, checked links");
+    let mut line = GenArena::new();
+    let a = line.insert(Station {
+        name: "City Hall",
+        links: Vec::new(),
+    });
+    let b = line.insert(Station {
+        name: "Euljiro",
+        links: vec![a],
+    });
+    let c = line.insert(Station {
+        name: "Dongdaemun",
+        links: vec![b],
+    });
+    line.get_mut(a).expect("just inserted").links.push(b);
+    line.get_mut(b).expect("just inserted").links.push(c);
+
+    let removed = line.remove(b).expect("Euljiro is stored");
+    println!("closed {} (was {b})", removed.name);
+    let d = line.insert(Station {
+        name: "Jongno",
+        links: vec![a],
+    });
+    println!("opened Jongno at {d} — same slot, new generation");
+
+    for (index, station) in line.iter() {
+        let links: Vec<String> = station
+            .links
+            .iter()
+            .map(|&link| match line.get(link) {
+                Some(other) => other.name.to_string(),
+                None => format!("{link} (closed)"),
+            })
+            .collect();
+        println!("{index} {:<11} -> {}", station.name, links.join(", "));
+    }
+}
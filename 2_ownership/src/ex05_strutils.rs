@@ -0,0 +1,41 @@
+//! Topic 5 — Borrowed `&str` results and UTF-8: `first_word` that also works on Korean text.
+//!
+//! Run via: `cargo run --bin ex05_strutils`
+
+use crate::strutils::{first_word, last_word, sentences, slice_chars, truncate_bytes, words};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+pub fn run() {
+    let english = String::from("Ownership is Rust's most unique feature. It lets Rust skip a GC!");
+    let korean = String::from("소유권은 러스트의 핵심입니다. 가비지 컬렉터가 없어요!");
+
+    section("first_word / last_word borrow from the String");
+    for text in [&english, &korean] {
+        // Both results are slices into `text`; no new String is allocated.
+        println!(
+            "first = {:?}, last = {:?}",
+            first_word(text),
+            last_word(text)
+        );
+    }
+
+    section("words and sentences");
+    println!("words: {:?}", words(&english).collect::<Vec<_>>());
+    for sentence in sentences(&korean) {
+        println!("sentence: {sentence}");
+    }
+
+    section("Slicing by bytes vs by characters");
+    println!(
+        "korean has {} bytes, {} chars",
+        korean.len(),
+        korean.chars().count()
+    );
+    println!("&korean[..4] would panic: byte 4 is inside '소' (3 bytes)");
+    println!("truncate_bytes(.., 4)  = {:?}", truncate_bytes(&korean, 4));
+    println!("truncate_bytes(.., 12) = {:?}", truncate_bytes(&korean, 12));
+    println!("slice_chars(.., 5, 9)  = {:?}", slice_chars(&korean, 5, 9));
+}
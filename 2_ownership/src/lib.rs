@@ -1,7 +1,11 @@
-//! Chapter 2 — ownership: shared string helpers and topic modules (`ex01_*`, …).
+//! Chapter 2 — ownership: shared string helpers (`fns`, UTF-8-safe `strutils`), a reusable arena, and topic modules (`ex01_*`, …).
 
 pub mod ex01_strings;
 pub mod ex02_mut_clear;
 pub mod ex03_borrow_shadow;
+pub mod ex04_arena_graph;
+pub mod ex05_strutils;
 
+pub mod arena;
 pub mod fns;
+pub mod strutils;
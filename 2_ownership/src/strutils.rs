@@ -0,0 +1,186 @@
+//! String-slice helpers that are safe on any UTF-8 input.
+//!
+//! The classic `first_word` scans `as_bytes()` and slices at a byte index. That
+//! only works because `b' '` is one byte; the moment you slice at an arbitrary
+//! byte count (`&s[..3]`, "the first 10 bytes") on text like `"안녕하세요"`, the
+//! index can land inside a 3-byte character and the slice panics. Every function
+//! here either splits on whole characters or snaps byte positions back to the
+//! nearest character boundary first.
+
+/// Characters that end a sentence for [`sentences`].
+const SENTENCE_ENDS: [char; 4] = ['.', '!', '?', '。'];
+
+/// The text before the first whitespace, or the whole string if there is none.
+///
+/// Leading whitespace is skipped, so `first_word("  hi there")` is `"hi"`.
+pub fn first_word(s: &str) -> &str {
+    s.split_whitespace().next().unwrap_or("")
+}
+
+/// The text after the last whitespace; `""` for an empty or all-whitespace string.
+pub fn last_word(s: &str) -> &str {
+    s.split_whitespace().next_back().unwrap_or("")
+}
+
+/// Words split on whitespace, with punctuation trimmed from both ends.
+///
+/// Inner punctuation stays (`"don't"`, `"e-mail"`); tokens that are only punctuation are dropped.
+pub fn words(s: &str) -> impl Iterator<Item = &str> {
+    s.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+}
+
+/// Sentences ending in `.`, `!`, `?`, or `。`, each trimmed and keeping its terminator.
+///
+/// Trailing text without a terminator is returned as a final sentence.
+pub fn sentences(s: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !SENTENCE_ENDS.contains(&c) {
+            continue;
+        }
+        // Keep runs like "?!" or "..." together with their sentence.
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if !SENTENCE_ENDS.contains(&next) {
+                break;
+            }
+            end = j + next.len_utf8();
+            chars.next();
+        }
+        push_trimmed(&mut out, &s[start..end]);
+        start = end;
+    }
+    push_trimmed(&mut out, &s[start..]);
+    out
+}
+
+fn push_trimmed<'a>(out: &mut Vec<&'a str>, piece: &'a str) {
+    let piece = piece.trim();
+    if !piece.is_empty() {
+        out.push(piece);
+    }
+}
+
+/// Largest char boundary `<= index` (clamped to `s.len()`).
+pub fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    (0..=index)
+        .rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// `&s[start..end]` by byte offsets, with both ends moved back to a char boundary and clamped.
+///
+/// Never panics: `slice_bytes("한국어", 1, 5)` is `"한"` (bytes 0..3) rather than a panic.
+pub fn slice_bytes(s: &str, start: usize, end: usize) -> &str {
+    let end = floor_char_boundary(s, end);
+    let start = floor_char_boundary(s, start).min(end);
+    &s[start..end]
+}
+
+/// The longest prefix of `s` that fits in `max_bytes` without splitting a character.
+pub fn truncate_bytes(s: &str, max_bytes: usize) -> &str {
+    slice_bytes(s, 0, max_bytes)
+}
+
+/// Characters `start..end` (counted in `char`s, not bytes), clamped to the string.
+pub fn slice_chars(s: &str, start: usize, end: usize) -> &str {
+    let from = char_offset(s, start);
+    let to = char_offset(s, end.max(start));
+    &s[from..to]
+}
+
+// Byte offset where the `n`th char starts, or `s.len()` past the end.
+fn char_offset(s: &str, n: usize) -> usize {
+    s.char_indices().nth(n).map_or(s.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KOREAN: &str = "안녕하세요 러스트 세계";
+
+    #[test]
+    fn first_and_last_word() {
+        assert_eq!(first_word("hello world"), "hello");
+        assert_eq!(first_word("  leading space"), "leading");
+        assert_eq!(first_word("single"), "single");
+        assert_eq!(first_word(""), "");
+        assert_eq!(last_word("hello big world"), "world");
+        assert_eq!(last_word("   "), "");
+    }
+
+    #[test]
+    fn first_and_last_word_on_multibyte_text() {
+        assert_eq!(first_word(KOREAN), "안녕하세요");
+        assert_eq!(last_word(KOREAN), "세계");
+        // Non-ASCII whitespace (ideographic space) also separates words.
+        assert_eq!(first_word("こんにちは\u{3000}世界"), "こんにちは");
+    }
+
+    #[test]
+    fn words_trim_punctuation() {
+        let got: Vec<_> = words("Hello, world! Don't panic -- it's fine.").collect();
+        assert_eq!(got, ["Hello", "world", "Don't", "panic", "it's", "fine"]);
+        let got: Vec<_> = words("「안녕」, 러스트!").collect();
+        assert_eq!(got, ["안녕", "러스트"]);
+    }
+
+    #[test]
+    fn sentences_keep_their_terminators() {
+        assert_eq!(
+            sentences("Hi there. How are you?! Fine... thanks"),
+            ["Hi there.", "How are you?!", "Fine...", "thanks"]
+        );
+        assert_eq!(
+            sentences("안녕하세요. 반갑습니다! 러스트 좋아요?"),
+            ["안녕하세요.", "반갑습니다!", "러스트 좋아요?"]
+        );
+        assert_eq!(
+            sentences("今日は晴れ。明日は雨。"),
+            ["今日は晴れ。", "明日は雨。"]
+        );
+        assert!(sentences("  ").is_empty());
+    }
+
+    #[test]
+    fn byte_slicing_snaps_to_char_boundaries() {
+        // Each Hangul syllable is 3 bytes; `&KOREAN[..4]` would panic.
+        assert!(!KOREAN.is_char_boundary(4));
+        assert_eq!(truncate_bytes(KOREAN, 4), "안");
+        assert_eq!(truncate_bytes(KOREAN, 6), "안녕");
+        assert_eq!(truncate_bytes(KOREAN, 1000), KOREAN);
+        assert_eq!(truncate_bytes(KOREAN, 0), "");
+        assert_eq!(slice_bytes("한국어", 1, 5), "한");
+        assert_eq!(slice_bytes("한국어", 4, 9), "국어");
+        assert_eq!(slice_bytes("abc", 2, 1), "");
+        // Emoji are 4 bytes; ASCII indices behave exactly like `&s[a..b]`.
+        assert_eq!(truncate_bytes("🦀rust", 3), "");
+        assert_eq!(slice_bytes("hello", 1, 4), &"hello"[1..4]);
+    }
+
+    #[test]
+    fn floor_char_boundary_never_exceeds_the_length() {
+        assert_eq!(floor_char_boundary("한", 2), 0);
+        assert_eq!(floor_char_boundary("한", 3), 3);
+        assert_eq!(floor_char_boundary("한", 99), 3);
+    }
+
+    #[test]
+    fn char_slicing_counts_characters() {
+        assert_eq!(slice_chars(KOREAN, 0, 2), "안녕");
+        assert_eq!(slice_chars(KOREAN, 6, 9), "러스트");
+        assert_eq!(slice_chars(KOREAN, 10, 100), "세계");
+        assert_eq!(slice_chars(KOREAN, 100, 200), "");
+        assert_eq!(slice_chars("abc", 2, 2), "");
+        assert_eq!(slice_chars("abc", 2, 1), "");
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    functions::ex03_calculator::run();
+}
@@ -0,0 +1,377 @@
+//! `Calculator`: evaluates parsed [`Statement`]s against variables and functions.
+//!
+//! Every function — built-in or typed in at the prompt — is stored the same way:
+//! a boxed closure plus its arity. `sq(x) = x * x` becomes a closure that owns the
+//! parameter names and body and evaluates them on each call.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::expr::{BinOp, Expr, ParseError, Statement, parse_statement};
+
+/// How deep user functions may call each other before evaluation gives up.
+pub const MAX_CALL_DEPTH: usize = 64;
+
+/// Everything that can go wrong while evaluating one line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    Parse(ParseError),
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    DivisionByZero,
+    /// The result was NaN or infinite, e.g. `sqrt(-1)` or `10 ^ 400`.
+    NotFinite,
+    /// A built-in name (`pi`, `sqrt`, …) cannot be redefined.
+    ReadOnly(String),
+    TooDeep,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::Parse(e) => write!(f, "parse error: {e}"),
+            CalcError::UnknownVariable(name) => write!(f, "unknown variable `{name}`"),
+            CalcError::UnknownFunction(name) => write!(f, "unknown function `{name}`"),
+            CalcError::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{name}` takes {expected} argument(s), but {found} were given"
+            ),
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::NotFinite => write!(f, "result is not a finite number"),
+            CalcError::ReadOnly(name) => write!(f, "`{name}` is built in and cannot be redefined"),
+            CalcError::TooDeep => write!(
+                f,
+                "function calls nested deeper than {MAX_CALL_DEPTH} (runaway recursion?)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+impl From<ParseError> for CalcError {
+    fn from(e: ParseError) -> Self {
+        CalcError::Parse(e)
+    }
+}
+
+/// Signature shared by every callable: the calculator (for globals and nested calls) and the arguments.
+pub type FnBody = dyn Fn(&Calculator, &[f64]) -> Result<f64, CalcError>;
+
+struct Function {
+    arity: usize,
+    builtin: bool,
+    body: Box<FnBody>,
+}
+
+/// What a successfully evaluated line did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Value(f64),
+    Assigned { name: String, value: f64 },
+    Defined { name: String, arity: usize },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Value(value) => write!(f, "{value}"),
+            Outcome::Assigned { name, value } => write!(f, "{name} = {value}"),
+            Outcome::Defined { name, arity } => write!(f, "defined {name}/{arity}"),
+        }
+    }
+}
+
+/// Variables, functions, and the last result (`ans`) for a calculator session.
+pub struct Calculator {
+    variables: BTreeMap<String, f64>,
+    constants: BTreeMap<&'static str, f64>,
+    functions: BTreeMap<String, Function>,
+    depth: Cell<usize>,
+}
+
+impl Calculator {
+    /// Calculator with `pi`, `e`, `ans`, and the usual math functions.
+    pub fn new() -> Calculator {
+        let mut calc = Calculator {
+            variables: BTreeMap::from([("ans".to_string(), 0.0)]),
+            constants: BTreeMap::from([("pi", std::f64::consts::PI), ("e", std::f64::consts::E)]),
+            functions: BTreeMap::new(),
+            depth: Cell::new(0),
+        };
+        let unary = [
+            ("sqrt", f64::sqrt as fn(f64) -> f64),
+            ("abs", f64::abs),
+            ("sin", f64::sin),
+            ("cos", f64::cos),
+            ("tan", f64::tan),
+            ("ln", f64::ln),
+            ("exp", f64::exp),
+            ("floor", f64::floor),
+            ("round", f64::round),
+        ];
+        for (name, f) in unary {
+            calc.add_builtin(name, 1, move |_, args| Ok(f(args[0])));
+        }
+        calc.add_builtin("min", 2, |_, args| Ok(args[0].min(args[1])));
+        calc.add_builtin("max", 2, |_, args| Ok(args[0].max(args[1])));
+        calc
+    }
+
+    fn add_builtin(
+        &mut self,
+        name: &str,
+        arity: usize,
+        body: impl Fn(&Calculator, &[f64]) -> Result<f64, CalcError> + 'static,
+    ) {
+        self.functions.insert(
+            name.to_string(),
+            Function {
+                arity,
+                builtin: true,
+                body: Box::new(body),
+            },
+        );
+    }
+
+    /// Register a Rust closure as a calculator function taking `arity` arguments.
+    ///
+    /// The closure may capture whatever it likes; it is called with exactly `arity` arguments.
+    pub fn define_fn(
+        &mut self,
+        name: &str,
+        arity: usize,
+        body: impl Fn(&Calculator, &[f64]) -> Result<f64, CalcError> + 'static,
+    ) -> Result<(), CalcError> {
+        if self.functions.get(name).is_some_and(|f| f.builtin) {
+            return Err(CalcError::ReadOnly(name.to_string()));
+        }
+        self.functions.insert(
+            name.to_string(),
+            Function {
+                arity,
+                builtin: false,
+                body: Box::new(body),
+            },
+        );
+        Ok(())
+    }
+
+    /// Parse and run one line. A plain expression also updates `ans`.
+    pub fn eval_line(&mut self, line: &str) -> Result<Outcome, CalcError> {
+        match parse_statement(line)? {
+            Statement::Expr(expr) => {
+                let value = self.eval(&expr, &HashMap::new())?;
+                self.variables.insert("ans".to_string(), value);
+                Ok(Outcome::Value(value))
+            }
+            Statement::Assign { name, value } => {
+                if self.constants.contains_key(name.as_str()) {
+                    return Err(CalcError::ReadOnly(name));
+                }
+                let value = self.eval(&value, &HashMap::new())?;
+                self.variables.insert(name.clone(), value);
+                Ok(Outcome::Assigned { name, value })
+            }
+            Statement::Define { name, params, body } => {
+                let arity = params.len();
+                // The closure owns its parameter names and body; free variables are
+                // looked up in the calculator at call time, not captured now.
+                self.define_fn(&name, arity, move |calc, args| {
+                    let locals = params
+                        .iter()
+                        .map(String::as_str)
+                        .zip(args.iter().copied())
+                        .collect();
+                    calc.eval(&body, &locals)
+                })?;
+                Ok(Outcome::Defined { name, arity })
+            }
+        }
+    }
+
+    /// Evaluate `expr`, resolving names in `locals` (function parameters) before globals.
+    pub fn eval(&self, expr: &Expr, locals: &HashMap<&str, f64>) -> Result<f64, CalcError> {
+        let value = match expr {
+            Expr::Number(n) => *n,
+            Expr::Var(name) => locals
+                .get(name.as_str())
+                .or_else(|| self.constants.get(name.as_str()))
+                .or_else(|| self.variables.get(name))
+                .copied()
+                .ok_or_else(|| CalcError::UnknownVariable(name.clone()))?,
+            Expr::Neg(inner) => -self.eval(inner, locals)?,
+            Expr::Binary { op, lhs, rhs } => {
+                let (a, b) = (self.eval(lhs, locals)?, self.eval(rhs, locals)?);
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div | BinOp::Rem if b == 0.0 => return Err(CalcError::DivisionByZero),
+                    BinOp::Div => a / b,
+                    BinOp::Rem => a % b,
+                    BinOp::Pow => a.powf(b),
+                }
+            }
+            Expr::Call { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg, locals))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(name, &args)?
+            }
+        };
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(CalcError::NotFinite)
+        }
+    }
+
+    /// Call the function `name` with already-evaluated arguments.
+    pub fn call(&self, name: &str, args: &[f64]) -> Result<f64, CalcError> {
+        let function = self
+            .functions
+            .get(name)
+            .ok_or_else(|| CalcError::UnknownFunction(name.to_string()))?;
+        if args.len() != function.arity {
+            return Err(CalcError::WrongArity {
+                name: name.to_string(),
+                expected: function.arity,
+                found: args.len(),
+            });
+        }
+        let depth = self.depth.get();
+        if depth >= MAX_CALL_DEPTH {
+            return Err(CalcError::TooDeep);
+        }
+        self.depth.set(depth + 1);
+        let result = (function.body)(self, args);
+        self.depth.set(depth);
+        result
+    }
+
+    /// User variables (including `ans`) in name order.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Every function name with its arity and whether it is built in, in name order.
+    pub fn functions(&self) -> impl Iterator<Item = (&str, usize, bool)> {
+        self.functions
+            .iter()
+            .map(|(name, f)| (name.as_str(), f.arity, f.builtin))
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Calculator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(calc: &mut Calculator, line: &str) -> f64 {
+        match calc.eval_line(line) {
+            Ok(Outcome::Value(v)) => v,
+            other => panic!("{line:?} gave {other:?}"),
+        }
+    }
+
+    #[test]
+    fn evaluates_with_precedence() {
+        let mut calc = Calculator::new();
+        assert_eq!(value(&mut calc, "1 + 2 * 3"), 7.0);
+        assert_eq!(value(&mut calc, "(1 + 2) * 3"), 9.0);
+        assert_eq!(value(&mut calc, "-2 ^ 2"), -4.0);
+        assert_eq!(value(&mut calc, "2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(value(&mut calc, "7 % 4"), 3.0);
+    }
+
+    #[test]
+    fn variables_and_ans_carry_over() {
+        let mut calc = Calculator::new();
+        calc.eval_line("rate = 0.25").unwrap();
+        assert_eq!(value(&mut calc, "200 * rate"), 50.0);
+        assert_eq!(value(&mut calc, "ans + 1"), 51.0);
+        assert_eq!(
+            calc.eval_line("pi = 3"),
+            Err(CalcError::ReadOnly("pi".into()))
+        );
+    }
+
+    #[test]
+    fn user_functions_see_params_before_globals() {
+        let mut calc = Calculator::new();
+        calc.eval_line("x = 100").unwrap();
+        calc.eval_line("hyp(x, y) = sqrt(x^2 + y^2)").unwrap();
+        assert_eq!(value(&mut calc, "hyp(3, 4)"), 5.0);
+
+        // Free variables are read at call time.
+        calc.eval_line("scaled(v) = v * k").unwrap();
+        calc.eval_line("k = 3").unwrap();
+        assert_eq!(value(&mut calc, "scaled(2)"), 6.0);
+    }
+
+    #[test]
+    fn rust_closures_can_be_registered() {
+        let mut calc = Calculator::new();
+        let tax = 0.1;
+        calc.define_fn("gross", 1, move |_, args| Ok(args[0] * (1.0 + tax)))
+            .unwrap();
+        assert!((value(&mut calc, "gross(200)") - 220.0).abs() < 1e-9);
+        assert_eq!(
+            calc.define_fn("sqrt", 1, |_, _| Ok(0.0)),
+            Err(CalcError::ReadOnly("sqrt".into()))
+        );
+    }
+
+    #[test]
+    fn reports_evaluation_errors() {
+        let mut calc = Calculator::new();
+        assert_eq!(calc.eval_line("1 / 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(calc.eval_line("sqrt(-1)"), Err(CalcError::NotFinite));
+        assert_eq!(
+            calc.eval_line("y + 1"),
+            Err(CalcError::UnknownVariable("y".into()))
+        );
+        assert_eq!(
+            calc.eval_line("nope(1)"),
+            Err(CalcError::UnknownFunction("nope".into()))
+        );
+        assert_eq!(
+            calc.eval_line("max(1)"),
+            Err(CalcError::WrongArity {
+                name: "max".into(),
+                expected: 2,
+                found: 1
+            })
+        );
+        assert!(matches!(calc.eval_line("1 +"), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn runaway_recursion_is_stopped() {
+        let mut calc = Calculator::new();
+        calc.eval_line("f(x) = f(x + 1)").unwrap();
+        assert_eq!(calc.eval_line("f(0)"), Err(CalcError::TooDeep));
+        // The depth counter is reset, so later calls still work.
+        calc.eval_line("f(x) = x").unwrap();
+        assert_eq!(value(&mut calc, "f(5)"), 5.0);
+    }
+}
@@ -0,0 +1,60 @@
+//! Topic 3 — An expression calculator REPL: parsing, variables, and closures stored as functions.
+//!
+//! Run via: `cargo run --bin ex03_calculator` (reads lines from stdin; `:help` lists commands).
+
+use std::io::{self, BufRead, Write};
+
+use crate::calc::{CalcError, Calculator};
+
+const HELP: &str = "\
+  1 + 2 * 3          evaluate (result is stored in `ans`)
+  r = 2.5            assign a variable
+  area(r) = pi*r^2   define a function
+  :vars  :fns        list variables / functions
+  :help  :quit       this text / leave (Ctrl-D works too)";
+
+pub fn run() {
+    let mut calc = Calculator::new();
+    // A closure from Rust sits next to the ones typed at the prompt.
+    let vat = 0.1;
+    calc.define_fn("with_vat", 1, move |_, args| Ok(args[0] * (1.0 + vat)))
+        .expect("`with_vat` is not a built-in");
+
+    println!("calculator — type :help for commands");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("flush stdout");
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        let line = line.trim();
+        match line {
+            "" => continue,
+            ":quit" | ":q" => break,
+            ":help" => println!("{HELP}"),
+            ":vars" => {
+                for (name, value) in calc.variables() {
+                    println!("  {name} = {value}");
+                }
+            }
+            ":fns" => {
+                for (name, arity, builtin) in calc.functions() {
+                    let origin = if builtin { "built-in" } else { "user" };
+                    println!("  {name}/{arity} ({origin})");
+                }
+            }
+            _ => match calc.eval_line(line) {
+                Ok(outcome) => println!("{outcome}"),
+                Err(CalcError::Parse(e)) => {
+                    // Point at the offending column under the prompt's `> `.
+                    println!("  {}^", " ".repeat(e.at));
+                    println!("error: {}", e.message);
+                }
+                Err(e) => println!("error: {e}"),
+            },
+        }
+    }
+}
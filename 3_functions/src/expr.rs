@@ -0,0 +1,530 @@
+//! Tokenizer and recursive-descent parser for calculator input.
+//!
+//! Grammar, loosest binding first (`^` is right-associative and binds tighter
+//! than unary minus, so `-2^2` is `-4`):
+//!
+//! ```text
+//! statement := ident "=" expr                      assign a variable
+//!            | ident "(" params ")" "=" expr       define a function
+//!            | expr
+//! expr      := term (("+" | "-") term)*
+//! term      := unary (("*" | "/" | "%") unary)*
+//! unary     := "-" unary | power
+//! power     := atom ("^" unary)?
+//! atom      := number | ident | ident "(" args ")" | "(" expr ")"
+//! ```
+
+use std::fmt;
+
+/// One lexical token, tagged with the byte offset where it starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub at: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+    Equals,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number(n) => write!(f, "{n}"),
+            TokenKind::Ident(name) => write!(f, "{name}"),
+            TokenKind::Plus => write!(f, "+"),
+            TokenKind::Minus => write!(f, "-"),
+            TokenKind::Star => write!(f, "*"),
+            TokenKind::Slash => write!(f, "/"),
+            TokenKind::Percent => write!(f, "%"),
+            TokenKind::Caret => write!(f, "^"),
+            TokenKind::LParen => write!(f, "("),
+            TokenKind::RParen => write!(f, ")"),
+            TokenKind::Comma => write!(f, ","),
+            TokenKind::Equals => write!(f, "="),
+        }
+    }
+}
+
+/// A tokenizing or parsing failure at byte offset `at` of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub at: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, at: usize) -> ParseError {
+        ParseError {
+            message: message.into(),
+            at,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.at + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Split `input` into tokens. Identifiers are ASCII letters, digits, and `_`, not starting with a digit.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(at, c)) = chars.peek() {
+        let single = match c {
+            '+' => Some(TokenKind::Plus),
+            '-' => Some(TokenKind::Minus),
+            '*' => Some(TokenKind::Star),
+            '/' => Some(TokenKind::Slash),
+            '%' => Some(TokenKind::Percent),
+            '^' => Some(TokenKind::Caret),
+            '(' => Some(TokenKind::LParen),
+            ')' => Some(TokenKind::RParen),
+            ',' => Some(TokenKind::Comma),
+            '=' => Some(TokenKind::Equals),
+            _ => None,
+        };
+        if let Some(kind) = single {
+            chars.next();
+            tokens.push(Token { kind, at });
+            continue;
+        }
+
+        // Consume the run of chars accepted by `keep` and return it as a slice of `input`.
+        let mut take_while = |keep: fn(char) -> bool| {
+            let mut end = at;
+            while let Some(&(i, c)) = chars.peek() {
+                if !keep(c) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            &input[at..end]
+        };
+
+        let kind = if c.is_whitespace() {
+            take_while(char::is_whitespace);
+            continue;
+        } else if c.is_ascii_digit() || c == '.' {
+            let text = take_while(|c| c.is_ascii_digit() || c == '.');
+            let n = text
+                .parse()
+                .map_err(|_| ParseError::new(format!("invalid number `{text}`"), at))?;
+            TokenKind::Number(n)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            TokenKind::Ident(take_while(|c| c.is_ascii_alphanumeric() || c == '_').to_string())
+        } else {
+            return Err(ParseError::new(format!("unexpected character `{c}`"), at));
+        };
+        tokens.push(Token { kind, at });
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+/// Expression tree produced by [`parse_statement`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary {
+        op: BinOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+/// One line of calculator input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Expr(Expr),
+    Assign {
+        name: String,
+        value: Expr,
+    },
+    Define {
+        name: String,
+        params: Vec<String>,
+        body: Expr,
+    },
+}
+
+/// Tokenize and parse one line of input.
+pub fn parse_statement(input: &str) -> Result<Statement, ParseError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        end: input.len(),
+    };
+    let lhs = parser.expr()?;
+    let statement = if parser.eat(&TokenKind::Equals) {
+        let equals_at = parser.tokens[parser.pos - 1].at;
+        let rhs = parser.expr()?;
+        into_binding(lhs, rhs, equals_at)?
+    } else {
+        Statement::Expr(lhs)
+    };
+    match parser.peek() {
+        None => Ok(statement),
+        Some(token) => Err(ParseError::new(
+            format!("unexpected `{}`", token.kind),
+            token.at,
+        )),
+    }
+}
+
+// `name = rhs` assigns; `name(a, b) = rhs` defines. Anything else may not appear left of `=`.
+fn into_binding(lhs: Expr, rhs: Expr, equals_at: usize) -> Result<Statement, ParseError> {
+    match lhs {
+        Expr::Var(name) => Ok(Statement::Assign { name, value: rhs }),
+        Expr::Call { name, args } => {
+            let mut params = Vec::with_capacity(args.len());
+            for arg in args {
+                let Expr::Var(param) = arg else {
+                    return Err(ParseError::new(
+                        "function parameters must be plain names",
+                        equals_at,
+                    ));
+                };
+                if params.contains(&param) {
+                    return Err(ParseError::new(
+                        format!("parameter `{param}` appears twice"),
+                        equals_at,
+                    ));
+                }
+                params.push(param);
+            }
+            Ok(Statement::Define {
+                name,
+                params,
+                body: rhs,
+            })
+        }
+        _ => Err(ParseError::new(
+            "only a name or a function signature can be assigned to",
+            equals_at,
+        )),
+    }
+}
+
+struct Parser<'t> {
+    tokens: &'t [Token],
+    pos: usize,
+    // Reported as the position of "unexpected end of input".
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<&Token, ParseError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| ParseError::new("unexpected end of input", self.end))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if self.peek().is_some_and(|t| &t.kind == kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> Result<(), ParseError> {
+        let token = self.next()?;
+        if &token.kind == kind {
+            Ok(())
+        } else {
+            Err(ParseError::new(
+                format!("expected `{kind}`, found `{}`", token.kind),
+                token.at,
+            ))
+        }
+    }
+
+    // Left-associative chain: `next_level (op next_level)*` for the operators in `ops`.
+    fn binary_chain(
+        &mut self,
+        ops: &[(TokenKind, BinOp)],
+        next_level: fn(&mut Self) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        let mut lhs = next_level(self)?;
+        'chain: loop {
+            for (kind, op) in ops {
+                if self.eat(kind) {
+                    let rhs = next_level(self)?;
+                    lhs = Expr::Binary {
+                        op: *op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    };
+                    continue 'chain;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        self.binary_chain(
+            &[
+                (TokenKind::Plus, BinOp::Add),
+                (TokenKind::Minus, BinOp::Sub),
+            ],
+            Self::term,
+        )
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        self.binary_chain(
+            &[
+                (TokenKind::Star, BinOp::Mul),
+                (TokenKind::Slash, BinOp::Div),
+                (TokenKind::Percent, BinOp::Rem),
+            ],
+            Self::unary,
+        )
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat(&TokenKind::Minus) {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.atom()?;
+        if self.eat(&TokenKind::Caret) {
+            // `unary` (not `atom`) on the right makes `2^-1` work and `^` right-associative.
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary {
+                op: BinOp::Pow,
+                lhs: Box::new(base),
+                rhs: Box::new(exponent),
+            });
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        let token = self.next()?.clone();
+        match token.kind {
+            TokenKind::Number(n) => Ok(Expr::Number(n)),
+            TokenKind::Ident(name) => {
+                if !self.eat(&TokenKind::LParen) {
+                    return Ok(Expr::Var(name));
+                }
+                let mut args = Vec::new();
+                if !self.eat(&TokenKind::RParen) {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(&TokenKind::RParen) {
+                            break;
+                        }
+                        self.expect(&TokenKind::Comma)?;
+                    }
+                }
+                Ok(Expr::Call { name, args })
+            }
+            TokenKind::LParen => {
+                let inner = self.expr()?;
+                self.expect(&TokenKind::RParen)?;
+                Ok(inner)
+            }
+            other => Err(ParseError::new(format!("unexpected `{other}`"), token.at)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(n: f64) -> Box<Expr> {
+        Box::new(Expr::Number(n))
+    }
+
+    fn bin(op: BinOp, lhs: Box<Expr>, rhs: Box<Expr>) -> Box<Expr> {
+        Box::new(Expr::Binary { op, lhs, rhs })
+    }
+
+    fn parse_expr(input: &str) -> Expr {
+        match parse_statement(input).unwrap() {
+            Statement::Expr(expr) => expr,
+            other => panic!("expected an expression, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tokenize_records_kinds_and_offsets() {
+        let tokens = tokenize("x1 = 2.5*(y)").unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Ident("x1".into()),
+                TokenKind::Equals,
+                TokenKind::Number(2.5),
+                TokenKind::Star,
+                TokenKind::LParen,
+                TokenKind::Ident("y".into()),
+                TokenKind::RParen,
+            ]
+        );
+        let offsets: Vec<_> = tokens.iter().map(|t| t.at).collect();
+        assert_eq!(offsets, [0, 3, 5, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn tokenize_rejects_bad_input() {
+        assert_eq!(
+            tokenize("1 + $").unwrap_err(),
+            ParseError::new("unexpected character `$`", 4)
+        );
+        assert_eq!(
+            tokenize("1.2.3").unwrap_err(),
+            ParseError::new("invalid number `1.2.3`", 0)
+        );
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(
+            parse_expr("1 + 2 * 3"),
+            *bin(BinOp::Add, num(1.0), bin(BinOp::Mul, num(2.0), num(3.0)))
+        );
+    }
+
+    #[test]
+    fn subtraction_is_left_associative() {
+        assert_eq!(
+            parse_expr("8 - 3 - 1"),
+            *bin(BinOp::Sub, bin(BinOp::Sub, num(8.0), num(3.0)), num(1.0))
+        );
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_negation() {
+        assert_eq!(
+            parse_expr("2 ^ 3 ^ 2"),
+            *bin(BinOp::Pow, num(2.0), bin(BinOp::Pow, num(3.0), num(2.0)))
+        );
+        assert_eq!(
+            parse_expr("-2 ^ 2"),
+            Expr::Neg(bin(BinOp::Pow, num(2.0), num(2.0)))
+        );
+        assert_eq!(
+            parse_expr("2 ^ -1"),
+            *bin(BinOp::Pow, num(2.0), Box::new(Expr::Neg(num(1.0))))
+        );
+    }
+
+    #[test]
+    fn parses_calls_with_any_number_of_arguments() {
+        assert_eq!(
+            parse_expr("max(1, x + 1)"),
+            Expr::Call {
+                name: "max".into(),
+                args: vec![
+                    Expr::Number(1.0),
+                    *bin(BinOp::Add, Box::new(Expr::Var("x".into())), num(1.0)),
+                ],
+            }
+        );
+        assert_eq!(
+            parse_expr("now()"),
+            Expr::Call {
+                name: "now".into(),
+                args: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn parses_assignments_and_definitions() {
+        assert_eq!(
+            parse_statement("rate = 0.5").unwrap(),
+            Statement::Assign {
+                name: "rate".into(),
+                value: Expr::Number(0.5),
+            }
+        );
+        assert_eq!(
+            parse_statement("sq(x) = x * x").unwrap(),
+            Statement::Define {
+                name: "sq".into(),
+                params: vec!["x".into()],
+                body: *bin(
+                    BinOp::Mul,
+                    Box::new(Expr::Var("x".into())),
+                    Box::new(Expr::Var("x".into()))
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_where_parsing_failed() {
+        let cases = [
+            ("1 +", "unexpected end of input", 3),
+            ("(1 + 2", "unexpected end of input", 6),
+            ("1 2", "unexpected `2`", 2),
+            ("f(1, 2 3)", "expected `,`, found `3`", 7),
+            (
+                "3 = 4",
+                "only a name or a function signature can be assigned to",
+                2,
+            ),
+            ("f(x + 1) = x", "function parameters must be plain names", 9),
+            ("f(x, x) = x", "parameter `x` appears twice", 8),
+            ("* 2", "unexpected `*`", 0),
+        ];
+        for (input, message, at) in cases {
+            assert_eq!(
+                parse_statement(input).unwrap_err(),
+                ParseError::new(message, at),
+                "{input:?}"
+            );
+        }
+    }
+}
@@ -1,6 +1,9 @@
-//! Chapter 3 — functions: tuple/block demos and closures (`ex01_*`, `ex02_*`).
+//! Chapter 3 — functions: tuple/block demos, closures, and a calculator REPL (`ex01_*` … `ex03_*`).
 
 pub mod ex01_tuple_block;
 pub mod ex02_closures;
+pub mod ex03_calculator;
 
+pub mod calc;
+pub mod expr;
 pub mod fns;
@@ -0,0 +1,317 @@
+//! `BankAccount`: a named-field struct whose operations return `Result`.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::BankError;
+use crate::interest::AccountKind;
+use crate::ledger::{Ledger, TransactionKind};
+use crate::money::{Currency, Money};
+use crate::overdraft::OverdraftPolicy;
+
+/// A simple bank account owned by one person.
+///
+/// The balance, active flag, and ledger are private so every change goes
+/// through the checked methods below and is recorded in the ledger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BankAccount {
+    pub owner: String,
+    balance: Money,
+    is_active: bool,
+    kind: AccountKind,
+    annual_rate: f64,
+    // `default` keeps bank files written before overdrafts existed loadable.
+    #[serde(default)]
+    overdraft: OverdraftPolicy,
+    ledger: Ledger,
+}
+
+impl BankAccount {
+    /// Open an active checking account; the opening balance is the first ledger entry
+    /// and fixes the account's currency.
+    pub fn new(owner: impl Into<String>, balance: Money) -> BankAccount {
+        BankAccount::with_kind(owner, balance, AccountKind::Checking)
+    }
+
+    /// Open an active account of `kind`, starting at that kind's default interest rate.
+    pub fn with_kind(owner: impl Into<String>, balance: Money, kind: AccountKind) -> BankAccount {
+        let mut ledger = Ledger::new(balance.currency());
+        ledger.record(TransactionKind::Opening, balance, Utc::now());
+        BankAccount {
+            owner: owner.into(),
+            balance,
+            is_active: true,
+            kind,
+            annual_rate: kind.default_annual_rate(),
+            overdraft: OverdraftPolicy::Deny,
+            ledger,
+        }
+    }
+
+    pub fn balance(&self) -> Money {
+        self.balance
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.balance.currency()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn kind(&self) -> AccountKind {
+        self.kind
+    }
+
+    /// Yearly interest rate as a fraction (`0.02` = 2 %).
+    pub fn annual_rate(&self) -> f64 {
+        self.annual_rate
+    }
+
+    /// Override the yearly interest rate; negative or non-finite rates are refused.
+    pub fn set_annual_rate(&mut self, rate: f64) -> Result<(), BankError> {
+        if !rate.is_finite() || rate < 0.0 {
+            return Err(BankError::InvalidRate(rate));
+        }
+        self.annual_rate = rate;
+        Ok(())
+    }
+
+    pub fn overdraft_policy(&self) -> OverdraftPolicy {
+        self.overdraft
+    }
+
+    /// Change how debits beyond the balance are handled. The policy's limit and
+    /// fee must be non-negative and in the account's currency.
+    pub fn set_overdraft_policy(&mut self, policy: OverdraftPolicy) -> Result<(), BankError> {
+        policy.validate(self.balance)?;
+        self.overdraft = policy;
+        Ok(())
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Balance as it stood at `at`, replayed from the ledger.
+    pub fn balance_at(&self, at: DateTime<Utc>) -> Money {
+        self.ledger.balance_at(at)
+    }
+
+    /// Printable statement: owner header followed by the ledger rows.
+    pub fn statement(&self) -> String {
+        format!("Statement for {}\n{}", self.owner, self.ledger.statement())
+    }
+
+    /// Take `amount` out of the account.
+    pub fn withdraw(&mut self, amount: Money) -> Result<(), BankError> {
+        self.post(TransactionKind::Withdrawal, amount, Utc::now())
+    }
+
+    /// Put `amount` into the account.
+    pub fn deposit(&mut self, amount: Money) -> Result<(), BankError> {
+        self.post(TransactionKind::Deposit, amount, Utc::now())
+    }
+
+    pub fn activate(&mut self) {
+        self.is_active = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.is_active = false;
+    }
+
+    /// Copy of this account under a new owner, built with struct update syntax.
+    pub fn with_owner(self, owner: impl Into<String>) -> BankAccount {
+        BankAccount {
+            owner: owner.into(),
+            ..self
+        }
+    }
+
+    // Validate, apply, and record one balance change. Every mutation of `balance` goes through here.
+    // A debit that overdraws the account also records the policy's fee as its own entry.
+    pub(crate) fn post(
+        &mut self,
+        kind: TransactionKind,
+        amount: Money,
+        at: DateTime<Utc>,
+    ) -> Result<(), BankError> {
+        self.check_operation(amount)?;
+        if kind.is_credit() {
+            self.balance = self.balance.checked_add(amount)?;
+            self.ledger.record(kind, amount, at);
+            return Ok(());
+        }
+
+        let fee = self.overdraft.fee_for(self.balance, amount)?;
+        self.balance = self.balance.checked_sub(amount)?.checked_sub(fee)?;
+        self.ledger.record(kind, amount, at);
+        if !fee.is_zero() {
+            self.ledger.record(TransactionKind::OverdraftFee, fee, at);
+        }
+        Ok(())
+    }
+
+    // Shared guard for every posting: the account must be active and the amount
+    // non-negative and in the account's currency.
+    pub(crate) fn check_operation(&self, amount: Money) -> Result<(), BankError> {
+        if !self.is_active {
+            return Err(BankError::InactiveAccount {
+                owner: self.owner.clone(),
+            });
+        }
+        self.balance.same_currency(amount)?;
+        if amount.is_negative() {
+            return Err(BankError::NegativeAmount(amount));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BankAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Balance for {} is {}, is active: {}",
+            self.owner, self.balance, self.is_active
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+
+    #[test]
+    fn deposit_and_withdraw_update_balance() {
+        let mut account = BankAccount::new("Alice", usd(100));
+        account.deposit(usd(50)).unwrap();
+        account.withdraw(usd(30)).unwrap();
+        assert_eq!(account.balance(), usd(120));
+    }
+
+    #[test]
+    fn withdraw_more_than_balance_is_refused() {
+        let mut account = BankAccount::new("Alice", usd(10));
+        let err = account.withdraw(usd(25)).unwrap_err();
+        assert_eq!(
+            err,
+            BankError::InsufficientFunds {
+                balance: usd(10),
+                requested: usd(25)
+            }
+        );
+        assert_eq!(account.balance(), usd(10));
+    }
+
+    #[test]
+    fn withdraw_entire_balance_is_allowed() {
+        let mut account = BankAccount::new("Alice", usd(10));
+        account.withdraw(usd(10)).unwrap();
+        assert_eq!(account.balance(), usd(0));
+    }
+
+    #[test]
+    fn inactive_account_rejects_operations() {
+        let mut account = BankAccount::new("Bob", usd(10));
+        account.deactivate();
+        let inactive = BankError::InactiveAccount {
+            owner: "Bob".to_string(),
+        };
+        assert_eq!(account.deposit(usd(1)), Err(inactive.clone()));
+        assert_eq!(account.withdraw(usd(1)), Err(inactive));
+
+        account.activate();
+        assert!(account.deposit(usd(1)).is_ok());
+    }
+
+    #[test]
+    fn negative_amounts_are_rejected() {
+        let mut account = BankAccount::new("Carol", usd(10));
+        assert_eq!(
+            account.deposit(usd(-5)),
+            Err(BankError::NegativeAmount(usd(-5)))
+        );
+        assert_eq!(
+            account.withdraw(usd(-1)),
+            Err(BankError::NegativeAmount(usd(-1)))
+        );
+        assert_eq!(account.balance(), usd(10));
+    }
+
+    #[test]
+    fn other_currencies_are_rejected() {
+        let mut account = BankAccount::new("Carol", usd(10));
+        let err = account
+            .deposit(Money::from_major(5, Currency::EUR))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BankError::CurrencyMismatch {
+                expected: Currency::USD,
+                found: Currency::EUR
+            }
+        );
+    }
+
+    #[test]
+    fn ten_dimes_make_exactly_one_dollar() {
+        let mut account = BankAccount::new("Frank", usd(0));
+        let dime = Money::parse("0.10", Currency::USD).unwrap();
+        for _ in 0..10 {
+            account.deposit(dime).unwrap();
+        }
+        assert_eq!(account.balance(), usd(1));
+        for _ in 0..10 {
+            account.withdraw(dime).unwrap();
+        }
+        assert!(account.balance().is_zero());
+    }
+
+    #[test]
+    fn operations_are_recorded_in_the_ledger() {
+        let mut account = BankAccount::new("Erin", usd(100));
+        account.deposit(usd(25)).unwrap();
+        account.withdraw(usd(10)).unwrap();
+        let kinds: Vec<_> = account
+            .ledger()
+            .entries()
+            .iter()
+            .map(|tx| &tx.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                &TransactionKind::Opening,
+                &TransactionKind::Deposit,
+                &TransactionKind::Withdrawal
+            ]
+        );
+        assert_eq!(account.balance_at(Utc::now()), account.balance());
+    }
+
+    #[test]
+    fn refused_operations_are_not_recorded() {
+        let mut account = BankAccount::new("Erin", usd(10));
+        assert!(account.withdraw(usd(50)).is_err());
+        assert_eq!(account.ledger().entries().len(), 1);
+    }
+
+    #[test]
+    fn with_owner_keeps_balance_and_status() {
+        let mut account = BankAccount::new("Dave", usd(42));
+        account.deactivate();
+        let renamed = account.with_owner("Fake Account");
+        assert_eq!(renamed.owner, "Fake Account");
+        assert_eq!(renamed.balance(), usd(42));
+        assert!(!renamed.is_active());
+    }
+}
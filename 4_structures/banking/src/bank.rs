@@ -0,0 +1,220 @@
+//! A collection of accounts that can be saved to and loaded from a JSON file.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::account::BankAccount;
+use crate::error::BankError;
+use crate::money::Money;
+use crate::transfer::transfer;
+
+/// Every account the bank knows about. Accounts are addressed by a 1-based
+/// account number: the first account opened is number 1.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bank {
+    accounts: Vec<BankAccount>,
+}
+
+impl Bank {
+    pub fn new() -> Bank {
+        Bank::default()
+    }
+
+    /// Add `account` and return its account number.
+    pub fn open(&mut self, account: BankAccount) -> usize {
+        self.accounts.push(account);
+        self.accounts.len()
+    }
+
+    pub fn accounts(&self) -> &[BankAccount] {
+        &self.accounts
+    }
+
+    pub fn get(&self, number: usize) -> Result<&BankAccount, BankError> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.accounts.get(index))
+            .ok_or(BankError::UnknownAccount(number))
+    }
+
+    pub fn get_mut(&mut self, number: usize) -> Result<&mut BankAccount, BankError> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.accounts.get_mut(index))
+            .ok_or(BankError::UnknownAccount(number))
+    }
+
+    /// [`transfer`] between two account numbers of this bank.
+    pub fn transfer(&mut self, from: usize, to: usize, amount: Money) -> Result<(), BankError> {
+        if from == to {
+            return Err(BankError::SameAccount(from));
+        }
+        self.get(from)?;
+        self.get(to)?;
+        // Split the Vec so we can hold two `&mut` into it at once.
+        let (low, high) = (from.min(to) - 1, from.max(to) - 1);
+        let (head, tail) = self.accounts.split_at_mut(high);
+        let (low_account, high_account) = (&mut head[low], &mut tail[0]);
+        if from < to {
+            transfer(low_account, high_account, amount)
+        } else {
+            transfer(high_account, low_account, amount)
+        }
+    }
+
+    /// Write every account (with its ledger) to `path` as pretty JSON.
+    ///
+    /// The JSON goes to a sibling temp file first, which is then renamed over
+    /// `path`, so a crash mid-write never leaves a truncated bank file behind.
+    pub fn save(&self, path: &Path) -> Result<(), StoreError> {
+        let tmp = path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read a bank from `path`; a missing file is an empty bank.
+    pub fn load(path: &Path) -> Result<Bank, StoreError> {
+        if !path.exists() {
+            return Ok(Bank::new());
+        }
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Why saving or loading a [`Bank`] failed.
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "bank file I/O failed: {e}"),
+            StoreError::Json(e) => write!(f, "bank file is not valid JSON: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Io(e) => Some(e),
+            StoreError::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(e: serde_json::Error) -> Self {
+        StoreError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+    use crate::interest::{AccountKind, Period};
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("banking-{}-{name}.json", std::process::id()))
+    }
+
+    fn sample_bank() -> Bank {
+        let mut bank = Bank::new();
+        bank.open(BankAccount::new("Alice", usd(100)));
+        let savings = bank.open(BankAccount::with_kind("Bob", usd(50), AccountKind::Savings));
+        bank.get_mut(savings).unwrap().accrue(Period::Year).unwrap();
+        bank.transfer(1, 2, usd(25)).unwrap();
+        bank
+    }
+
+    #[test]
+    fn save_then_load_round_trips_accounts_and_ledgers() {
+        let path = temp_file("round-trip");
+        let bank = sample_bank();
+        bank.save(&path).unwrap();
+
+        let loaded = Bank::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, bank);
+        assert_eq!(loaded.get(2).unwrap().ledger().entries().len(), 3);
+        assert_eq!(loaded.get(2).unwrap().kind(), AccountKind::Savings);
+    }
+
+    #[test]
+    fn missing_file_loads_an_empty_bank() {
+        let bank = Bank::load(&temp_file("does-not-exist")).unwrap();
+        assert!(bank.accounts().is_empty());
+    }
+
+    #[test]
+    fn corrupt_file_is_a_json_error() {
+        let path = temp_file("corrupt");
+        fs::write(&path, "{ not json").unwrap();
+        let err = Bank::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(err, StoreError::Json(_)));
+    }
+
+    #[test]
+    fn account_numbers_are_one_based() {
+        let bank = sample_bank();
+        assert_eq!(bank.get(1).unwrap().owner, "Alice");
+        assert_eq!(bank.get(0), Err(BankError::UnknownAccount(0)));
+        assert_eq!(bank.get(3), Err(BankError::UnknownAccount(3)));
+    }
+
+    #[test]
+    fn transfer_works_in_both_directions() {
+        let mut bank = sample_bank();
+        let (alice, bob) = (
+            bank.get(1).unwrap().balance(),
+            bank.get(2).unwrap().balance(),
+        );
+        bank.transfer(2, 1, usd(10)).unwrap();
+        assert_eq!(
+            bank.get(1).unwrap().balance(),
+            alice.checked_add(usd(10)).unwrap()
+        );
+        assert_eq!(
+            bank.get(2).unwrap().balance(),
+            bob.checked_sub(usd(10)).unwrap()
+        );
+    }
+
+    #[test]
+    fn transfer_to_the_same_account_number_is_refused() {
+        let mut bank = sample_bank();
+        let before = bank.clone();
+        assert_eq!(bank.transfer(1, 1, usd(5)), Err(BankError::SameAccount(1)));
+        assert_eq!(
+            bank.transfer(1, 9, usd(5)),
+            Err(BankError::UnknownAccount(9))
+        );
+        assert_eq!(bank, before);
+    }
+}
@@ -0,0 +1,78 @@
+//! Error type for refused account operations and settings.
+
+use std::fmt;
+
+use crate::money::{Currency, Money};
+
+/// Why a deposit, withdrawal, transfer, or setting change was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BankError {
+    /// The withdrawal is larger than the current balance (and overdrafts are denied).
+    InsufficientFunds { balance: Money, requested: Money },
+    /// The withdrawal plus its overdraft fee would go past the overdraft limit.
+    OverdraftLimitExceeded {
+        limit: Money,
+        balance: Money,
+        requested: Money,
+    },
+    /// The account is deactivated and accepts no operations.
+    InactiveAccount { owner: String },
+    /// The amount is negative.
+    NegativeAmount(Money),
+    /// The interest rate is negative (or not a finite number).
+    InvalidRate(f64),
+    /// No account has this account number.
+    UnknownAccount(usize),
+    /// A transfer named the same account number on both sides.
+    SameAccount(usize),
+    /// Two amounts in different currencies were combined.
+    CurrencyMismatch { expected: Currency, found: Currency },
+    /// The result does not fit in the minor-unit counter.
+    Overflow,
+    /// Text that is not a decimal amount in the account's currency.
+    InvalidAmount(String),
+    /// A currency code the bank does not support.
+    UnknownCurrency(String),
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankError::InsufficientFunds { balance, requested } => {
+                write!(
+                    f,
+                    "insufficient funds: balance {balance}, requested {requested}"
+                )
+            }
+            BankError::OverdraftLimitExceeded {
+                limit,
+                balance,
+                requested,
+            } => write!(
+                f,
+                "overdraft limit {limit} exceeded: balance {balance}, requested {requested}"
+            ),
+            BankError::InactiveAccount { owner } => {
+                write!(f, "account for {owner} is inactive")
+            }
+            BankError::NegativeAmount(amount) => {
+                write!(f, "amount must not be negative, got {amount}")
+            }
+            BankError::InvalidRate(rate) => {
+                write!(f, "interest rate must be a non-negative number, got {rate}")
+            }
+            BankError::UnknownAccount(number) => write!(f, "no account number {number}"),
+            BankError::SameAccount(number) => {
+                write!(f, "cannot transfer from account {number} to itself")
+            }
+            BankError::CurrencyMismatch { expected, found } => {
+                write!(f, "currency mismatch: expected {expected}, got {found}")
+            }
+            BankError::Overflow => write!(f, "amount is too large"),
+            BankError::InvalidAmount(raw) => write!(f, "`{raw}` is not a valid amount"),
+            BankError::UnknownCurrency(code) => write!(f, "unknown currency `{code}`"),
+        }
+    }
+}
+
+impl std::error::Error for BankError {}
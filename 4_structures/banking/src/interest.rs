@@ -0,0 +1,179 @@
+//! Account types, interest rates, and periodic interest accrual.
+
+use std::fmt;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::account::BankAccount;
+use crate::error::BankError;
+use crate::ledger::TransactionKind;
+use crate::money::Money;
+
+/// Which product an account is: decides the default interest rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AccountKind {
+    /// Everyday account; pays no interest unless configured otherwise.
+    #[default]
+    Checking,
+    /// Interest-bearing account.
+    Savings,
+}
+
+impl AccountKind {
+    /// Annual rate a new account of this kind starts with (`0.02` = 2 % per year).
+    pub fn default_annual_rate(self) -> f64 {
+        match self {
+            AccountKind::Checking => 0.0,
+            AccountKind::Savings => 0.02,
+        }
+    }
+}
+
+impl fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountKind::Checking => write!(f, "checking"),
+            AccountKind::Savings => write!(f, "savings"),
+        }
+    }
+}
+
+/// Length of one accrual step, as a fraction of a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Month,
+    Year,
+}
+
+impl Period {
+    pub fn fraction_of_year(self) -> f64 {
+        match self {
+            Period::Day => 1.0 / 365.0,
+            Period::Month => 1.0 / 12.0,
+            Period::Year => 1.0,
+        }
+    }
+}
+
+impl BankAccount {
+    /// Post one period of interest on the current balance and return the amount posted.
+    ///
+    /// Interest is rounded to the nearest minor unit and credited to the balance,
+    /// so calling this repeatedly compounds: twelve `Period::Month` accruals at
+    /// 12 % grow 1000.00 to 1126.84. Nothing is posted when the interest rounds
+    /// to zero (no rate, a tiny balance, or no positive balance).
+    pub fn accrue(&mut self, period: Period) -> Result<Money, BankError> {
+        if !self.is_active() {
+            return Err(BankError::InactiveAccount {
+                owner: self.owner.clone(),
+            });
+        }
+        let balance = self.balance();
+        if balance.is_negative() {
+            return Ok(Money::zero(balance.currency()));
+        }
+        let interest = balance.apply_rate(self.annual_rate() * period.fraction_of_year());
+        if !interest.is_zero() {
+            self.post(TransactionKind::Interest, interest, Utc::now())?;
+        }
+        Ok(interest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+
+    fn cents(minor: i64) -> Money {
+        Money::new(minor, Currency::USD)
+    }
+
+    #[test]
+    fn kinds_start_with_their_default_rate() {
+        let checking = BankAccount::new("Alice", usd(100));
+        let savings = BankAccount::with_kind("Alice", usd(100), AccountKind::Savings);
+        assert_eq!(checking.kind(), AccountKind::Checking);
+        assert_eq!(checking.annual_rate(), 0.0);
+        assert_eq!(savings.annual_rate(), 0.02);
+    }
+
+    #[test]
+    fn yearly_accrual_posts_simple_interest() {
+        let mut account = BankAccount::with_kind("Alice", usd(1000), AccountKind::Savings);
+        let posted = account.accrue(Period::Year).unwrap();
+        assert_eq!(posted, usd(20));
+        assert_eq!(account.balance(), usd(1020));
+        assert_eq!(
+            account.ledger().entries().last().unwrap().kind,
+            TransactionKind::Interest
+        );
+    }
+
+    #[test]
+    fn monthly_accrual_compounds() {
+        let mut account = BankAccount::with_kind("Alice", usd(1000), AccountKind::Savings);
+        account.set_annual_rate(0.12).unwrap();
+        for _ in 0..12 {
+            account.accrue(Period::Month).unwrap();
+        }
+        // 1000 * (1 + 0.12 / 12)^12 = 1126.825…, rounded to the cent every month.
+        assert_eq!(account.balance(), cents(112_684));
+        assert_eq!(account.ledger().entries().len(), 13);
+    }
+
+    #[test]
+    fn daily_compounding_beats_yearly() {
+        let mut daily = BankAccount::with_kind("Alice", usd(1_000_000), AccountKind::Savings);
+        let mut yearly = daily.clone();
+        for _ in 0..365 {
+            daily.accrue(Period::Day).unwrap();
+        }
+        yearly.accrue(Period::Year).unwrap();
+        // 1_000_000 * (1 + 0.02 / 365)^365 = 1_020_200.781…
+        assert_eq!(daily.balance(), cents(102_020_078));
+        assert_eq!(yearly.balance(), usd(1_020_000));
+    }
+
+    #[test]
+    fn interest_below_one_cent_posts_nothing() {
+        let mut account = BankAccount::with_kind("Bob", cents(10), AccountKind::Savings);
+        assert!(account.accrue(Period::Day).unwrap().is_zero());
+        assert_eq!(account.ledger().entries().len(), 1);
+    }
+
+    #[test]
+    fn zero_rate_posts_nothing() {
+        let mut account = BankAccount::new("Bob", usd(500));
+        assert!(account.accrue(Period::Year).unwrap().is_zero());
+        assert_eq!(account.ledger().entries().len(), 1);
+    }
+
+    #[test]
+    fn inactive_account_does_not_accrue() {
+        let mut account = BankAccount::with_kind("Bob", usd(500), AccountKind::Savings);
+        account.deactivate();
+        assert!(matches!(
+            account.accrue(Period::Year),
+            Err(BankError::InactiveAccount { .. })
+        ));
+        assert_eq!(account.balance(), usd(500));
+    }
+
+    #[test]
+    fn invalid_rates_are_rejected() {
+        let mut account = BankAccount::new("Bob", usd(500));
+        assert_eq!(
+            account.set_annual_rate(-0.01),
+            Err(BankError::InvalidRate(-0.01))
+        );
+        assert!(account.set_annual_rate(f64::NAN).is_err());
+        assert_eq!(account.annual_rate(), 0.0);
+    }
+}
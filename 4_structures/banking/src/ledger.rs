@@ -0,0 +1,192 @@
+//! Per-account transaction history: every balance change is recorded with a timestamp.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Currency, Money};
+
+/// What kind of balance change a [`Transaction`] records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransactionKind {
+    /// Opening balance when the account was created.
+    Opening,
+    Deposit,
+    Withdrawal,
+    /// Money received from another account's owner.
+    TransferIn {
+        from: String,
+    },
+    /// Money sent to another account's owner.
+    TransferOut {
+        to: String,
+    },
+    /// Interest credited by [`BankAccount::accrue`](crate::BankAccount::accrue).
+    Interest,
+    /// Fee charged by an [`OverdraftPolicy`](crate::OverdraftPolicy) for going below zero.
+    OverdraftFee,
+}
+
+impl TransactionKind {
+    /// `true` when this kind adds money to the account.
+    pub fn is_credit(&self) -> bool {
+        matches!(
+            self,
+            TransactionKind::Opening
+                | TransactionKind::Deposit
+                | TransactionKind::TransferIn { .. }
+                | TransactionKind::Interest
+        )
+    }
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionKind::Opening => write!(f, "opening balance"),
+            TransactionKind::Deposit => write!(f, "deposit"),
+            TransactionKind::Withdrawal => write!(f, "withdrawal"),
+            TransactionKind::TransferIn { from } => write!(f, "transfer from {from}"),
+            TransactionKind::TransferOut { to } => write!(f, "transfer to {to}"),
+            TransactionKind::Interest => write!(f, "interest"),
+            TransactionKind::OverdraftFee => write!(f, "overdraft fee"),
+        }
+    }
+}
+
+/// One recorded balance change. `amount` is always non-negative; the kind gives the sign.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub kind: TransactionKind,
+    pub amount: Money,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Transaction {
+    /// `amount` with a sign: positive for credits, negative for debits.
+    pub fn signed_amount(&self) -> Money {
+        if self.kind.is_credit() {
+            self.amount
+        } else {
+            self.amount.negated()
+        }
+    }
+}
+
+/// Append-only list of transactions in one currency, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    currency: Currency,
+    entries: Vec<Transaction>,
+}
+
+impl Ledger {
+    pub fn new(currency: Currency) -> Ledger {
+        Ledger {
+            currency,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn record(&mut self, kind: TransactionKind, amount: Money, timestamp: DateTime<Utc>) {
+        self.entries.push(Transaction {
+            kind,
+            amount,
+            timestamp,
+        });
+    }
+
+    pub fn entries(&self) -> &[Transaction] {
+        &self.entries
+    }
+
+    /// Balance as of `at`: the sum of every transaction recorded at or before that instant.
+    ///
+    /// Entries are appended in time order, so every partial sum is a balance the
+    /// account already held (and checked) — plain `i64` addition cannot overflow here.
+    pub fn balance_at(&self, at: DateTime<Utc>) -> Money {
+        let minor = self
+            .entries
+            .iter()
+            .filter(|tx| tx.timestamp <= at)
+            .map(|tx| tx.signed_amount().minor_units())
+            .sum();
+        Money::new(minor, self.currency)
+    }
+
+    /// Multi-line statement with one row per transaction and a running balance.
+    pub fn statement(&self) -> String {
+        let mut out = format!(
+            "{:<19}  {:<30}  {:>16}  {:>16}\n",
+            "date", "description", "amount", "balance"
+        );
+        let mut running = 0;
+        for tx in &self.entries {
+            running += tx.signed_amount().minor_units();
+            out.push_str(&format!(
+                "{:<19}  {:<30}  {:>16}  {:>16}\n",
+                tx.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                tx.kind.to_string(),
+                tx.signed_amount(),
+                Money::new(running, self.currency)
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, second).unwrap()
+    }
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+
+    #[test]
+    fn balance_at_only_counts_earlier_entries() {
+        let mut ledger = Ledger::new(Currency::USD);
+        ledger.record(TransactionKind::Opening, usd(100), at(0));
+        ledger.record(TransactionKind::Deposit, usd(50), at(10));
+        ledger.record(TransactionKind::Withdrawal, usd(30), at(20));
+
+        assert_eq!(ledger.balance_at(at(0)), usd(100));
+        assert_eq!(ledger.balance_at(at(15)), usd(150));
+        assert_eq!(ledger.balance_at(at(20)), usd(120));
+    }
+
+    #[test]
+    fn balance_before_first_entry_is_zero() {
+        let mut ledger = Ledger::new(Currency::USD);
+        ledger.record(TransactionKind::Opening, usd(100), at(5));
+        assert_eq!(ledger.balance_at(at(0)), usd(0));
+    }
+
+    #[test]
+    fn statement_lists_each_entry_with_running_balance() {
+        let mut ledger = Ledger::new(Currency::USD);
+        ledger.record(TransactionKind::Opening, usd(100), at(0));
+        ledger.record(
+            TransactionKind::TransferOut {
+                to: "Mom".to_string(),
+            },
+            usd(40),
+            at(1),
+        );
+        let statement = ledger.statement();
+        assert_eq!(statement.lines().count(), 3);
+        assert!(statement.contains("transfer to Mom"));
+        assert!(statement.contains("-40.00 USD"));
+        assert!(statement.lines().last().unwrap().ends_with("60.00 USD"));
+    }
+}
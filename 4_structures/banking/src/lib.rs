@@ -0,0 +1,35 @@
+//! Small banking library shared by the chapter 4 struct demos.
+//!
+//! # How this crate is organized
+//!
+//! - [`bank`] — [`Bank`], a numbered collection of accounts saved to / loaded from JSON.
+//! - [`account`] — the [`BankAccount`] struct and its checked operations.
+//! - [`error`] — [`BankError`], returned by every operation that can be refused.
+//! - [`interest`] — [`AccountKind`] (checking/savings), rates, and [`BankAccount::accrue`].
+//! - [`money`] — exact [`Money`] amounts (integer minor units + [`Currency`]).
+//! - [`ledger`] — timestamped [`Transaction`] history kept on every account.
+//! - [`overdraft`] — per-account [`OverdraftPolicy`] (deny, or allow up to a limit for a fee).
+//! - [`transfer`](mod@transfer) — all-or-nothing [`transfer()`] between two accounts.
+//!
+//! # How `structs` imports this crate
+//!
+//! `4_structures/Cargo.toml` lists `banking = { path = "banking" }`, so the demos
+//! write `use banking::BankAccount;`.
+
+pub mod account;
+pub mod bank;
+pub mod error;
+pub mod interest;
+pub mod ledger;
+pub mod money;
+pub mod overdraft;
+pub mod transfer;
+
+pub use account::BankAccount;
+pub use bank::{Bank, StoreError};
+pub use error::BankError;
+pub use interest::{AccountKind, Period};
+pub use ledger::{Ledger, Transaction, TransactionKind};
+pub use money::{Currency, Money};
+pub use overdraft::OverdraftPolicy;
+pub use transfer::transfer;
@@ -0,0 +1,269 @@
+//! `Money`: an exact amount stored as integer minor units (cents) plus a currency.
+//!
+//! Balances never touch `f64`, so `0.1 + 0.2` style rounding drift cannot creep
+//! into an account. The only place a float appears is [`Money::apply_rate`],
+//! which rounds its result back to a whole minor unit straight away.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BankError;
+
+/// ISO 4217 currencies the bank supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    USD,
+    EUR,
+    KRW,
+}
+
+impl Currency {
+    /// Three-letter ISO code, e.g. `"USD"`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::KRW => "KRW",
+        }
+    }
+
+    /// Digits after the decimal point: 2 for cents, 0 for the won.
+    pub fn minor_digits(self) -> u32 {
+        match self {
+            Currency::USD | Currency::EUR => 2,
+            Currency::KRW => 0,
+        }
+    }
+
+    /// Minor units in one major unit (100 for USD, 1 for KRW).
+    pub fn minor_per_major(self) -> i64 {
+        10_i64.pow(self.minor_digits())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = BankError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "USD" => Ok(Currency::USD),
+            "EUR" => Ok(Currency::EUR),
+            "KRW" => Ok(Currency::KRW),
+            _ => Err(BankError::UnknownCurrency(s.to_string())),
+        }
+    }
+}
+
+/// An exact amount of one currency, counted in minor units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// `minor` minor units of `currency`: `Money::new(1234, USD)` is 12.34 USD.
+    pub fn new(minor: i64, currency: Currency) -> Money {
+        Money { minor, currency }
+    }
+
+    /// A whole number of major units: `Money::from_major(12, USD)` is 12.00 USD.
+    pub fn from_major(major: i64, currency: Currency) -> Money {
+        Money::new(major * currency.minor_per_major(), currency)
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::new(0, currency)
+    }
+
+    /// Parse a decimal string such as `"12.5"` or `"-3.07"` exactly, without going through `f64`.
+    pub fn parse(raw: &str, currency: Currency) -> Result<Money, BankError> {
+        let invalid = || BankError::InvalidAmount(raw.to_string());
+        let trimmed = raw.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let scale = currency.minor_digits() as usize;
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) || fraction.len() > scale
+        {
+            return Err(invalid());
+        }
+
+        let whole: i64 = whole.parse().map_err(|_| invalid())?;
+        let fraction: i64 = if fraction.is_empty() {
+            0
+        } else {
+            // Right-pad so "12.5" means 50 cents, not 5.
+            format!("{fraction:0<scale$}")
+                .parse()
+                .map_err(|_| invalid())?
+        };
+        let minor = whole
+            .checked_mul(currency.minor_per_major())
+            .and_then(|m| m.checked_add(fraction))
+            .ok_or(BankError::Overflow)?;
+        Ok(Money::new(if negative { -minor } else { minor }, currency))
+    }
+
+    pub fn minor_units(self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.minor == 0
+    }
+
+    /// Same amount with the sign flipped.
+    pub fn negated(self) -> Money {
+        Money::new(-self.minor, self.currency)
+    }
+
+    /// `self + other`, refusing mixed currencies and `i64` overflow.
+    pub fn checked_add(self, other: Money) -> Result<Money, BankError> {
+        self.same_currency(other)?;
+        self.minor
+            .checked_add(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(BankError::Overflow)
+    }
+
+    /// `self - other`, refusing mixed currencies and `i64` overflow.
+    pub fn checked_sub(self, other: Money) -> Result<Money, BankError> {
+        self.same_currency(other)?;
+        self.minor
+            .checked_sub(other.minor)
+            .map(|minor| Money::new(minor, self.currency))
+            .ok_or(BankError::Overflow)
+    }
+
+    /// `self * rate`, rounded to the nearest minor unit (used for interest).
+    pub fn apply_rate(self, rate: f64) -> Money {
+        Money::new((self.minor as f64 * rate).round() as i64, self.currency)
+    }
+
+    /// `Ok` when both amounts share a currency.
+    pub fn same_currency(self, other: Money) -> Result<(), BankError> {
+        if self.currency != other.currency {
+            return Err(BankError::CurrencyMismatch {
+                expected: self.currency,
+                found: other.currency,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// `12.34 USD`, `-0.05 EUR`, `1500 KRW`. Width/alignment flags apply to the whole string.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let per_major = self.currency.minor_per_major();
+        let whole = self.minor.unsigned_abs() / per_major as u64;
+        let text = match self.currency.minor_digits() {
+            0 => format!("{sign}{whole} {}", self.currency),
+            digits => {
+                let fraction = self.minor.unsigned_abs() % per_major as u64;
+                let width = digits as usize;
+                format!("{sign}{whole}.{fraction:0width$} {}", self.currency)
+            }
+        };
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(minor: i64) -> Money {
+        Money::new(minor, Currency::USD)
+    }
+
+    #[test]
+    fn display_uses_the_currency_scale() {
+        assert_eq!(usd(1234).to_string(), "12.34 USD");
+        assert_eq!(usd(-5).to_string(), "-0.05 USD");
+        assert_eq!(
+            Money::from_major(1500, Currency::KRW).to_string(),
+            "1500 KRW"
+        );
+        assert_eq!(format!("{:>12}", usd(100)), "    1.00 USD");
+    }
+
+    #[test]
+    fn parse_is_exact() {
+        assert_eq!(Money::parse("12.5", Currency::USD), Ok(usd(1250)));
+        assert_eq!(Money::parse("0.07", Currency::USD), Ok(usd(7)));
+        assert_eq!(Money::parse("-3", Currency::USD), Ok(usd(-300)));
+        assert_eq!(
+            Money::parse("1500", Currency::KRW),
+            Ok(Money::new(1500, Currency::KRW))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_input() {
+        for raw in ["", "abc", "1.234", "1.2.3", ".5", "1e3", "--1"] {
+            assert!(Money::parse(raw, Currency::USD).is_err(), "{raw:?}");
+        }
+        assert!(Money::parse("10.5", Currency::KRW).is_err());
+    }
+
+    #[test]
+    fn tenths_add_up_exactly() {
+        // 0.1 + 0.2 == 0.3 holds here, unlike with f64.
+        let sum = Money::parse("0.1", Currency::USD)
+            .unwrap()
+            .checked_add(Money::parse("0.2", Currency::USD).unwrap())
+            .unwrap();
+        assert_eq!(sum, Money::parse("0.3", Currency::USD).unwrap());
+    }
+
+    #[test]
+    fn checked_arithmetic_refuses_mixed_currencies_and_overflow() {
+        let eur = Money::from_major(1, Currency::EUR);
+        assert_eq!(
+            usd(100).checked_add(eur),
+            Err(BankError::CurrencyMismatch {
+                expected: Currency::USD,
+                found: Currency::EUR
+            })
+        );
+        assert_eq!(usd(i64::MAX).checked_add(usd(1)), Err(BankError::Overflow));
+        assert_eq!(usd(i64::MIN).checked_sub(usd(1)), Err(BankError::Overflow));
+        assert_eq!(usd(500).checked_sub(usd(700)), Ok(usd(-200)));
+    }
+
+    #[test]
+    fn apply_rate_rounds_to_the_nearest_minor_unit() {
+        assert_eq!(usd(10_000).apply_rate(0.02), usd(200));
+        assert_eq!(usd(333).apply_rate(0.01), usd(3));
+        assert_eq!(usd(350).apply_rate(0.01), usd(4));
+    }
+
+    #[test]
+    fn currency_codes_parse_case_insensitively() {
+        assert_eq!("krw".parse::<Currency>(), Ok(Currency::KRW));
+        assert!("GBP".parse::<Currency>().is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Overdraft policies: whether a debit may take an account below zero, and at what cost.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BankError;
+use crate::money::Money;
+
+/// How an account treats a debit that is larger than its balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OverdraftPolicy {
+    /// Refuse any debit that would take the balance below zero.
+    #[default]
+    Deny,
+    /// Let the balance go negative, down to `-limit`. Every debit that ends
+    /// below zero is charged `fee`, and the debit plus its fee must stay
+    /// within the limit.
+    AllowUpTo { limit: Money, fee: Money },
+}
+
+impl OverdraftPolicy {
+    /// Check that the policy's amounts are non-negative and in `currency_of`'s currency.
+    pub(crate) fn validate(self, currency_of: Money) -> Result<(), BankError> {
+        if let OverdraftPolicy::AllowUpTo { limit, fee } = self {
+            for amount in [limit, fee] {
+                currency_of.same_currency(amount)?;
+                if amount.is_negative() {
+                    return Err(BankError::NegativeAmount(amount));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fee owed for debiting `amount` from `balance`, or the reason the debit is refused.
+    ///
+    /// Returns a zero fee when the debit does not overdraw the account.
+    pub(crate) fn fee_for(self, balance: Money, amount: Money) -> Result<Money, BankError> {
+        let remaining = balance.checked_sub(amount)?;
+        if !remaining.is_negative() {
+            return Ok(Money::zero(balance.currency()));
+        }
+        match self {
+            OverdraftPolicy::Deny => Err(BankError::InsufficientFunds {
+                balance,
+                requested: amount,
+            }),
+            OverdraftPolicy::AllowUpTo { limit, fee } => {
+                let after_fee = remaining.checked_sub(fee)?;
+                if after_fee.checked_add(limit)?.is_negative() {
+                    return Err(BankError::OverdraftLimitExceeded {
+                        limit,
+                        balance,
+                        requested: amount,
+                    });
+                }
+                Ok(fee)
+            }
+        }
+    }
+}
+
+impl fmt::Display for OverdraftPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverdraftPolicy::Deny => write!(f, "no overdraft"),
+            OverdraftPolicy::AllowUpTo { limit, fee } => {
+                write!(
+                    f,
+                    "overdraft up to {limit}, fee {fee} per overdrawing debit"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::BankAccount;
+    use crate::ledger::TransactionKind;
+    use crate::money::Currency;
+    use crate::transfer::transfer;
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+
+    fn overdraft_account(balance: i64) -> BankAccount {
+        let mut account = BankAccount::new("Alice", usd(balance));
+        account
+            .set_overdraft_policy(OverdraftPolicy::AllowUpTo {
+                limit: usd(100),
+                fee: usd(25),
+            })
+            .unwrap();
+        account
+    }
+
+    fn kinds(account: &BankAccount) -> Vec<TransactionKind> {
+        account
+            .ledger()
+            .entries()
+            .iter()
+            .map(|tx| tx.kind.clone())
+            .collect()
+    }
+
+    #[test]
+    fn deny_is_the_default_and_refuses_overdrafts() {
+        let mut account = BankAccount::new("Alice", usd(50));
+        assert_eq!(account.overdraft_policy(), OverdraftPolicy::Deny);
+        assert_eq!(
+            account.withdraw(usd(60)),
+            Err(BankError::InsufficientFunds {
+                balance: usd(50),
+                requested: usd(60)
+            })
+        );
+        assert_eq!(account.balance(), usd(50));
+    }
+
+    #[test]
+    fn allow_up_to_lets_the_balance_go_negative_and_charges_a_fee() {
+        let mut account = overdraft_account(50);
+        account.withdraw(usd(60)).unwrap();
+
+        assert_eq!(account.balance(), usd(-35));
+        assert_eq!(
+            kinds(&account),
+            [
+                TransactionKind::Opening,
+                TransactionKind::Withdrawal,
+                TransactionKind::OverdraftFee
+            ]
+        );
+        assert_eq!(account.ledger().entries()[2].amount, usd(25));
+    }
+
+    #[test]
+    fn no_fee_while_the_balance_stays_positive() {
+        let mut account = overdraft_account(50);
+        account.withdraw(usd(50)).unwrap();
+        assert_eq!(account.balance(), usd(0));
+        assert_eq!(account.ledger().entries().len(), 2);
+    }
+
+    #[test]
+    fn the_fee_counts_towards_the_limit() {
+        // 50 - 130 = -80 fits the 100 limit, but the 25 fee would take it to -105.
+        let mut account = overdraft_account(50);
+        let before = account.clone();
+        assert_eq!(
+            account.withdraw(usd(130)),
+            Err(BankError::OverdraftLimitExceeded {
+                limit: usd(100),
+                balance: usd(50),
+                requested: usd(130)
+            })
+        );
+        assert_eq!(account, before);
+
+        account.withdraw(usd(125)).unwrap();
+        assert_eq!(account.balance(), usd(-100));
+    }
+
+    #[test]
+    fn every_overdrawing_debit_is_charged() {
+        let mut account = overdraft_account(0);
+        account.withdraw(usd(10)).unwrap();
+        account.withdraw(usd(10)).unwrap();
+        assert_eq!(account.balance(), usd(-70));
+        assert_eq!(account.ledger().entries().len(), 5);
+    }
+
+    #[test]
+    fn transfers_out_follow_the_policy() {
+        let mut alice = overdraft_account(10);
+        let mut bob = BankAccount::new("Bob", usd(0));
+        transfer(&mut alice, &mut bob, usd(20)).unwrap();
+
+        assert_eq!(alice.balance(), usd(-35));
+        assert_eq!(bob.balance(), usd(20));
+        assert_eq!(kinds(&alice).last(), Some(&TransactionKind::OverdraftFee));
+    }
+
+    #[test]
+    fn policy_amounts_must_match_the_account() {
+        let mut account = BankAccount::new("Alice", usd(10));
+        let euro_limit = OverdraftPolicy::AllowUpTo {
+            limit: Money::from_major(100, Currency::EUR),
+            fee: usd(5),
+        };
+        assert!(matches!(
+            account.set_overdraft_policy(euro_limit),
+            Err(BankError::CurrencyMismatch { .. })
+        ));
+        let negative_fee = OverdraftPolicy::AllowUpTo {
+            limit: usd(100),
+            fee: usd(-5),
+        };
+        assert_eq!(
+            account.set_overdraft_policy(negative_fee),
+            Err(BankError::NegativeAmount(usd(-5)))
+        );
+        assert_eq!(account.overdraft_policy(), OverdraftPolicy::Deny);
+    }
+}
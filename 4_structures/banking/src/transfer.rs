@@ -0,0 +1,164 @@
+//! Moving money between two accounts as a single all-or-nothing operation.
+
+use chrono::Utc;
+
+use crate::account::BankAccount;
+use crate::error::BankError;
+use crate::ledger::TransactionKind;
+use crate::money::Money;
+
+/// Move `amount` from `from` to `to`.
+///
+/// Either both sides are updated (and both ledgers get a matching entry with
+/// the same timestamp) or neither is: on any error the two accounts are left
+/// exactly as they were.
+///
+/// Transferring from an account to itself cannot even be written — the
+/// borrow checker refuses two `&mut` borrows of the same value:
+///
+/// ```compile_fail,E0499
+/// use banking::{BankAccount, Currency, Money, transfer};
+///
+/// let mut account = BankAccount::new("Alice", Money::from_major(100, Currency::USD));
+/// transfer(&mut account, &mut account, Money::from_major(10, Currency::USD)).unwrap();
+/// ```
+pub fn transfer(
+    from: &mut BankAccount,
+    to: &mut BankAccount,
+    amount: Money,
+) -> Result<(), BankError> {
+    // Validate the receiving side first: once the debit is posted, the credit must not fail.
+    to.check_operation(amount)?;
+    to.balance().checked_add(amount)?;
+
+    let at = Utc::now();
+    from.post(
+        TransactionKind::TransferOut {
+            to: to.owner.clone(),
+        },
+        amount,
+        at,
+    )?;
+    to.post(
+        TransactionKind::TransferIn {
+            from: from.owner.clone(),
+        },
+        amount,
+        at,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    fn usd(major: i64) -> Money {
+        Money::from_major(major, Currency::USD)
+    }
+
+    fn pair() -> (BankAccount, BankAccount) {
+        (
+            BankAccount::new("Alice", usd(100)),
+            BankAccount::new("Bob", usd(20)),
+        )
+    }
+
+    #[test]
+    fn transfer_moves_money_and_records_both_sides() {
+        let (mut alice, mut bob) = pair();
+        transfer(&mut alice, &mut bob, usd(30)).unwrap();
+
+        assert_eq!(alice.balance(), usd(70));
+        assert_eq!(bob.balance(), usd(50));
+
+        let out = alice.ledger().entries().last().unwrap();
+        let incoming = bob.ledger().entries().last().unwrap();
+        assert_eq!(
+            out.kind,
+            TransactionKind::TransferOut {
+                to: "Bob".to_string()
+            }
+        );
+        assert_eq!(
+            incoming.kind,
+            TransactionKind::TransferIn {
+                from: "Alice".to_string()
+            }
+        );
+        assert_eq!(out.timestamp, incoming.timestamp);
+    }
+
+    #[test]
+    fn insufficient_funds_leaves_both_accounts_untouched() {
+        let (mut alice, mut bob) = pair();
+        let (alice_before, bob_before) = (alice.clone(), bob.clone());
+
+        let err = transfer(&mut alice, &mut bob, usd(500)).unwrap_err();
+        assert!(matches!(err, BankError::InsufficientFunds { .. }));
+        assert_eq!(alice, alice_before);
+        assert_eq!(bob, bob_before);
+    }
+
+    #[test]
+    fn inactive_sender_is_refused() {
+        let (mut alice, mut bob) = pair();
+        alice.deactivate();
+        let bob_before = bob.clone();
+
+        let err = transfer(&mut alice, &mut bob, usd(10)).unwrap_err();
+        assert!(matches!(err, BankError::InactiveAccount { owner } if owner == "Alice"));
+        assert_eq!(bob, bob_before);
+    }
+
+    #[test]
+    fn inactive_receiver_does_not_debit_the_sender() {
+        let (mut alice, mut bob) = pair();
+        bob.deactivate();
+        let alice_before = alice.clone();
+
+        let err = transfer(&mut alice, &mut bob, usd(10)).unwrap_err();
+        assert!(matches!(err, BankError::InactiveAccount { owner } if owner == "Bob"));
+        assert_eq!(alice, alice_before);
+    }
+
+    #[test]
+    fn overflowing_the_receiver_does_not_debit_the_sender() {
+        let (mut alice, _) = pair();
+        let mut rich = BankAccount::new("Carol", Money::new(i64::MAX - 100, Currency::USD));
+        let (alice_before, rich_before) = (alice.clone(), rich.clone());
+
+        let err = transfer(&mut alice, &mut rich, usd(10)).unwrap_err();
+        assert_eq!(err, BankError::Overflow);
+        assert_eq!(alice, alice_before);
+        assert_eq!(rich, rich_before);
+    }
+
+    #[test]
+    fn negative_amount_is_refused() {
+        let (mut alice, mut bob) = pair();
+        let err = transfer(&mut alice, &mut bob, usd(-5)).unwrap_err();
+        assert_eq!(err, BankError::NegativeAmount(usd(-5)));
+        assert_eq!(alice.balance(), usd(100));
+        assert_eq!(bob.balance(), usd(20));
+    }
+
+    #[test]
+    fn transferring_the_whole_balance_empties_the_sender() {
+        let (mut alice, mut bob) = pair();
+        transfer(&mut alice, &mut bob, usd(100)).unwrap();
+        assert_eq!(alice.balance(), usd(0));
+        assert_eq!(bob.balance(), usd(120));
+    }
+
+    #[test]
+    fn transfer_between_accounts_with_the_same_owner_is_allowed() {
+        // Self-transfer of one account is a compile error (see the doc example);
+        // two separate accounts held by one person are just two accounts.
+        let mut checking = BankAccount::new("Alice", usd(50));
+        let mut savings = BankAccount::new("Alice", usd(0));
+        transfer(&mut checking, &mut savings, usd(20)).unwrap();
+        assert_eq!(checking.balance(), usd(30));
+        assert_eq!(savings.balance(), usd(20));
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex04_bank_errors::run();
+}
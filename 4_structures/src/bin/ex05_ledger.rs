@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex05_ledger::run();
+}
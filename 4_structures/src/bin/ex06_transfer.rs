@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex06_transfer::run();
+}
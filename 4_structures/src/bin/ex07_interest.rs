@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex07_interest::run();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex08_bank_cli::run();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    structs::ex09_overdraft::run();
+}
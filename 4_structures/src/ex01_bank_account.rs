@@ -1,33 +1,37 @@
-//! Topic 1 — Named-field struct `BankAccount`, methods, struct update syntax, and moves.
+//! Topic 1 — Named-field struct `BankAccount` (from the `banking` crate), methods, struct update syntax, and moves.
 //!
 //! Run via: `cargo run --bin ex01_bank_account`
 
-use crate::bank_account::{BankAccount, build_fake_account};
+use banking::BankAccount;
+
+use crate::helpers::usd;
 
 pub fn run() {
-    let mut my_account = BankAccount::new("Sooyoung Kim".to_string(), 1500.0);
-    let mut mom_account = BankAccount {
-        owner: "Mom".to_string(),
-        balance: 0.0,
-        is_active: true,
-    };
+    let mut my_account = BankAccount::new("Sooyoung Kim", usd(1500));
+    let mut mom_account = BankAccount::new("Mom", usd(0));
 
     my_account.activate();
     mom_account.activate();
 
-    my_account.check_balance();
-    mom_account.check_balance();
+    println!("{my_account}");
+    println!("{mom_account}");
 
-    my_account.withdraw(500.0);
-    mom_account.deposit(500.0);
+    if my_account.withdraw(usd(500)).is_ok() {
+        println!("Withdrew 500 from {}", my_account.owner);
+    }
+    if mom_account.deposit(usd(500)).is_ok() {
+        println!("Deposited 500 to {}", mom_account.owner);
+    }
 
-    my_account.check_balance();
-    mom_account.check_balance();
+    println!("{my_account}");
+    println!("{mom_account}");
 
     my_account.owner = "Daniel Kim".to_string();
     my_account.deactivate();
-    my_account.check_balance();
+    println!("Deactivated account for {}", my_account.owner);
+    println!("{my_account}");
 
-    let fake_account = build_fake_account(my_account);
-    fake_account.check_balance();
+    // `with_owner` uses struct update syntax (`..self`) and takes `my_account` by value.
+    let fake_account = my_account.with_owner("Fake Account");
+    println!("{fake_account}");
 }
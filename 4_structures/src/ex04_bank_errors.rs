@@ -0,0 +1,38 @@
+//! Topic 4 — `Result`-returning methods: refused withdrawals and deposits surface as `BankError`.
+//!
+//! Run via: `cargo run --bin ex04_bank_errors`
+
+use banking::{BankAccount, BankError};
+
+use crate::helpers::{section, usd};
+
+pub fn run() {
+    let mut account = BankAccount::new("Sooyoung Kim", usd(100));
+
+    section("Ok path");
+    match account.withdraw(usd(40)) {
+        Ok(()) => println!("withdrew 40 → {account}"),
+        Err(e) => println!("unexpected error: {e}"),
+    }
+
+    section("Insufficient funds");
+    match account.withdraw(usd(1_000)) {
+        Ok(()) => println!("unexpected success"),
+        Err(BankError::InsufficientFunds { balance, requested }) => {
+            println!("refused: wanted {requested}, only {balance} available");
+        }
+        Err(other) => println!("other error: {other}"),
+    }
+
+    section("Negative amount");
+    if let Err(e) = account.deposit(usd(-5)) {
+        println!("refused: {e}");
+    }
+
+    section("Inactive account");
+    account.deactivate();
+    if let Err(e) = account.deposit(usd(10)) {
+        println!("refused: {e}");
+    }
+    println!("balance is unchanged after every refusal → {account}");
+}
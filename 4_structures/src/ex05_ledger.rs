@@ -0,0 +1,43 @@
+//! Topic 5 — A struct that owns a `Vec` of other structs: each account keeps its own transaction ledger.
+//!
+//! Run via: `cargo run --bin ex05_ledger`
+
+use std::thread;
+use std::time::Duration;
+
+use banking::BankAccount;
+use chrono::Utc;
+
+use crate::helpers::{section, usd};
+
+pub fn run() {
+    let mut account = BankAccount::new("Sooyoung Kim", usd(1000));
+
+    section("Record a few transactions");
+    account
+        .deposit(usd(250))
+        .expect("active account accepts deposits");
+    account
+        .withdraw(usd(100))
+        .expect("balance covers the withdrawal");
+
+    // Remember "now", then keep going so the snapshot and the live balance differ.
+    thread::sleep(Duration::from_millis(10));
+    let checkpoint = Utc::now();
+    thread::sleep(Duration::from_millis(10));
+
+    account
+        .withdraw(usd(400))
+        .expect("balance covers the withdrawal");
+    if let Err(e) = account.withdraw(usd(5_000)) {
+        println!("refused (not recorded): {e}");
+    }
+    println!("{} entries in the ledger", account.ledger().entries().len());
+
+    section("Balance at a point in time");
+    println!("at checkpoint: {}", account.balance_at(checkpoint));
+    println!("right now:     {}", account.balance());
+
+    section("Statement");
+    print!("{}", account.statement());
+}
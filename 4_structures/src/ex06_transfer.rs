@@ -0,0 +1,35 @@
+//! Topic 6 — Two `&mut` borrows at once: an all-or-nothing transfer between two accounts.
+//!
+//! Run via: `cargo run --bin ex06_transfer`
+
+use banking::{BankAccount, transfer};
+
+use crate::helpers::{section, usd};
+
+pub fn run() {
+    let mut mine = BankAccount::new("Sooyoung Kim", usd(1000));
+    let mut moms = BankAccount::new("Mom", usd(0));
+
+    section("Successful transfer");
+    match transfer(&mut mine, &mut moms, usd(300)) {
+        Ok(()) => println!("sent 300"),
+        Err(e) => println!("transfer failed: {e}"),
+    }
+    println!("{mine}");
+    println!("{moms}");
+
+    section("Refused transfer leaves both sides untouched");
+    moms.deactivate();
+    if let Err(e) = transfer(&mut mine, &mut moms, usd(100)) {
+        println!("transfer failed: {e}");
+    }
+    println!("{mine}");
+    println!("{moms}");
+
+    // `transfer(&mut mine, &mut mine, usd(10))` would not compile:
+    // the borrow checker forbids two mutable borrows of the same account.
+
+    section("Both ledgers");
+    print!("{}", mine.statement());
+    print!("{}", moms.statement());
+}
@@ -0,0 +1,53 @@
+//! Topic 7 — A struct field holding an enum: checking vs savings accounts accruing (compound) interest.
+//!
+//! Run via: `cargo run --bin ex07_interest`
+
+use banking::{AccountKind, BankAccount, Period};
+
+use crate::helpers::{section, usd};
+
+pub fn run() {
+    let mut checking = BankAccount::new("Sooyoung Kim", usd(1000));
+    let mut savings = BankAccount::with_kind("Sooyoung Kim", usd(1000), AccountKind::Savings);
+
+    section("Default rates per account kind");
+    for account in [&checking, &savings] {
+        println!(
+            "{} account: {:.1} % per year",
+            account.kind(),
+            account.annual_rate() * 100.0
+        );
+    }
+
+    section("Twelve monthly accruals");
+    for month in 1..=12 {
+        let earned = savings
+            .accrue(Period::Month)
+            .expect("savings account is active");
+        checking
+            .accrue(Period::Month)
+            .expect("checking account is active");
+        println!(
+            "month {month:>2}: +{earned} → savings {}",
+            savings.balance()
+        );
+    }
+    println!("checking after a year: {}", checking.balance());
+    println!("savings after a year:  {}", savings.balance());
+
+    section("Compounding vs one yearly accrual");
+    let mut yearly = BankAccount::with_kind("Compare", usd(1000), AccountKind::Savings);
+    yearly.accrue(Period::Year).expect("account is active");
+    println!("monthly compounding: {}", savings.balance());
+    println!("single yearly post:  {}", yearly.balance());
+
+    section("Custom rate");
+    savings.set_annual_rate(0.05).expect("5 % is a valid rate");
+    savings
+        .accrue(Period::Year)
+        .expect("savings account is active");
+    println!("after a year at 5 %: {}", savings.balance());
+    if let Err(e) = savings.set_annual_rate(-0.01) {
+        println!("refused: {e}");
+    }
+}
@@ -0,0 +1,154 @@
+//! Topic 8 — Structs that outlive the process: a tiny bank CLI whose accounts are saved to `bank.json`.
+//!
+//! Run via: `cargo run --bin ex08_bank_cli -- <command> [args…]` (no command lists accounts)
+
+use std::env;
+use std::error::Error;
+use std::path::Path;
+use std::process;
+
+use banking::{AccountKind, Bank, BankAccount, Currency, Money, OverdraftPolicy, Period};
+
+const FILE_PATH: &str = "bank.json";
+
+const USAGE: &str = "\
+usage: ex08_bank_cli <command> [args…]
+
+  list                              show every account (default)
+  open <owner> [balance] [checking|savings] [USD|EUR|KRW]
+                                    open an account (default: checking, USD)
+  deposit <number> <amount>
+  withdraw <number> <amount>
+  transfer <from> <to> <amount>
+  accrue <day|month|year>           post interest on every account
+  statement <number>
+  overdraft <number> deny | overdraft <number> <limit> <fee>
+  activate <number> | deactivate <number>";
+
+pub fn run() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = Path::new(FILE_PATH);
+
+    let result = Bank::load(path)
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|mut bank| {
+            let changed = execute(&mut bank, &args)?;
+            if changed {
+                bank.save(path)?;
+                println!("(saved to {FILE_PATH})");
+            }
+            Ok(())
+        });
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        eprintln!("\n{USAGE}");
+        process::exit(1);
+    }
+}
+
+// Apply one command to `bank`. Returns `true` when the bank changed and must be saved.
+fn execute(bank: &mut Bank, args: &[String]) -> Result<bool, Box<dyn Error>> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] | ["list"] => {
+            list(bank);
+            Ok(false)
+        }
+        ["open", owner, rest @ ..] => {
+            let kind = match rest.get(1) {
+                Some(&"savings") => AccountKind::Savings,
+                Some(&"checking") | None => AccountKind::Checking,
+                Some(other) => return Err(format!("unknown account kind `{other}`").into()),
+            };
+            let currency: Currency = match rest.get(2) {
+                Some(code) => code.parse()?,
+                None => Currency::USD,
+            };
+            let balance = match rest.first() {
+                Some(raw) => Money::parse(raw, currency)?,
+                None => Money::zero(currency),
+            };
+            let number = bank.open(BankAccount::with_kind(*owner, balance, kind));
+            println!("opened {kind} account #{number} for {owner}");
+            Ok(true)
+        }
+        ["deposit", number, amount] => {
+            let account = bank.get_mut(parse_number(number)?)?;
+            account.deposit(Money::parse(amount, account.currency())?)?;
+            println!("{account}");
+            Ok(true)
+        }
+        ["withdraw", number, amount] => {
+            let account = bank.get_mut(parse_number(number)?)?;
+            account.withdraw(Money::parse(amount, account.currency())?)?;
+            println!("{account}");
+            Ok(true)
+        }
+        ["transfer", from, to, amount] => {
+            let from = parse_number(from)?;
+            // Amounts are read in the sender's currency; a mismatch is refused by the bank.
+            let amount = Money::parse(amount, bank.get(from)?.currency())?;
+            bank.transfer(from, parse_number(to)?, amount)?;
+            list(bank);
+            Ok(true)
+        }
+        ["accrue", period] => {
+            let period = match *period {
+                "day" => Period::Day,
+                "month" => Period::Month,
+                "year" => Period::Year,
+                other => return Err(format!("unknown period `{other}`").into()),
+            };
+            for number in 1..=bank.accounts().len() {
+                let account = bank.get_mut(number)?;
+                // Inactive accounts simply do not earn interest this round.
+                if let Ok(earned) = account.accrue(period) {
+                    println!("#{number} {}: +{earned}", account.owner);
+                }
+            }
+            Ok(true)
+        }
+        ["statement", number] => {
+            print!("{}", bank.get(parse_number(number)?)?.statement());
+            Ok(false)
+        }
+        ["overdraft", number, "deny"] => {
+            bank.get_mut(parse_number(number)?)?
+                .set_overdraft_policy(OverdraftPolicy::Deny)?;
+            Ok(true)
+        }
+        ["overdraft", number, limit, fee] => {
+            let account = bank.get_mut(parse_number(number)?)?;
+            let currency = account.currency();
+            account.set_overdraft_policy(OverdraftPolicy::AllowUpTo {
+                limit: Money::parse(limit, currency)?,
+                fee: Money::parse(fee, currency)?,
+            })?;
+            Ok(true)
+        }
+        ["activate", number] => {
+            bank.get_mut(parse_number(number)?)?.activate();
+            Ok(true)
+        }
+        ["deactivate", number] => {
+            bank.get_mut(parse_number(number)?)?.deactivate();
+            Ok(true)
+        }
+        _ => Err(format!("unrecognised command: {}", args.join(" ")).into()),
+    }
+}
+
+fn list(bank: &Bank) {
+    if bank.accounts().is_empty() {
+        println!("no accounts yet — try `open <owner> <balance>`");
+    }
+    for (index, account) in bank.accounts().iter().enumerate() {
+        println!("#{} [{}] {account}", index + 1, account.kind());
+    }
+}
+
+fn parse_number(raw: &str) -> Result<usize, Box<dyn Error>> {
+    raw.parse()
+        .map_err(|_| format!("`{raw}` is not an account number").into())
+}
@@ -0,0 +1,39 @@
+//! Topic 9 — Enum with data as a struct setting: per-account overdraft policies enforced on every withdrawal.
+//!
+//! Run via: `cargo run --bin ex09_overdraft`
+
+use banking::{BankAccount, OverdraftPolicy};
+
+use crate::helpers::{section, usd};
+
+pub fn run() {
+    section("Deny (default)");
+    let mut strict = BankAccount::new("Strict", usd(50));
+    println!("policy: {}", strict.overdraft_policy());
+    if let Err(e) = strict.withdraw(usd(80)) {
+        println!("refused: {e}");
+    }
+
+    section("Allow up to 100 with a 25 fee");
+    let mut flexible = BankAccount::new("Flexible", usd(50));
+    flexible
+        .set_overdraft_policy(OverdraftPolicy::AllowUpTo {
+            limit: usd(100),
+            fee: usd(25),
+        })
+        .expect("limit and fee are in USD");
+    println!("policy: {}", flexible.overdraft_policy());
+
+    flexible
+        .withdraw(usd(60))
+        .expect("within the overdraft limit");
+    println!("after withdrawing 60: {flexible}");
+
+    // -35 - 50 - 25 fee = -110, past the -100 limit.
+    if let Err(e) = flexible.withdraw(usd(50)) {
+        println!("refused: {e}");
+    }
+
+    section("The fee is its own ledger entry");
+    print!("{}", flexible.statement());
+}
@@ -0,0 +1,13 @@
+//! Small helpers shared by the chapter 4 demos.
+
+use banking::{Currency, Money};
+
+/// Print a `=== title ===` banner between demo sections.
+pub fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+/// Whole US dollars as exact `Money` (`usd(12)` is 12.00 USD).
+pub fn usd(major: i64) -> Money {
+    Money::from_major(major, Currency::USD)
+}
@@ -1,8 +1,15 @@
 //! Chapter 4 — structs: type definitions plus topic demos (`ex01_*`, …).
+//! `BankAccount` lives in the sibling `banking` library crate (`./banking`).
 
 pub mod ex01_bank_account;
 pub mod ex02_tuple_struct;
 pub mod ex03_unit_struct;
+pub mod ex04_bank_errors;
+pub mod ex05_ledger;
+pub mod ex06_transfer;
+pub mod ex07_interest;
+pub mod ex08_bank_cli;
+pub mod ex09_overdraft;
 
-pub mod bank_account;
+pub mod helpers;
 pub mod position;
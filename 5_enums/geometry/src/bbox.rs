@@ -0,0 +1,62 @@
+//! Axis-aligned bounding boxes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::shapes::Point;
+
+/// The smallest axis-aligned rectangle containing a shape.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    /// Corner with the smallest `x` and `y`.
+    pub min: Point,
+    /// Corner with the largest `x` and `y`.
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// Box around every point in `points`, or `None` when there are no points.
+    pub fn from_points(points: &[Point]) -> Option<BoundingBox> {
+        let (&first, rest) = points.split_first()?;
+        let mut bbox = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for &(x, y) in rest {
+            bbox.min = (bbox.min.0.min(x), bbox.min.1.min(y));
+            bbox.max = (bbox.max.0.max(x), bbox.max.1.max(y));
+        }
+        Some(bbox)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.0 - self.min.0
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.1 - self.min.1
+    }
+
+    /// `true` when `p` is inside the box or within `slack` of its edges.
+    pub fn contains(&self, p: Point, slack: f64) -> bool {
+        p.0 >= self.min.0 - slack
+            && p.0 <= self.max.0 + slack
+            && p.1 >= self.min.1 - slack
+            && p.1 <= self.max.1 + slack
+    }
+
+    /// `true` when the two boxes share at least one point (touching counts).
+    pub fn overlaps(&self, other: &BoundingBox) -> bool {
+        self.min.0 <= other.max.0
+            && other.min.0 <= self.max.0
+            && self.min.1 <= other.max.1
+            && other.min.1 <= self.max.1
+    }
+
+    /// Smallest box containing both `self` and `other`.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Small 2D geometry library grown out of the chapter 5 `Shapes` enum.
+//!
+//! # How this crate is organized
+//!
+//! - [`shapes`] — the [`Shapes`] enum (circle, rectangle, triangle, polygon, ellipse)
+//!   with `area()`, `perimeter()`, and `bounding_box()`.
+//! - [`bbox`] — the axis-aligned [`BoundingBox`] returned by `bounding_box()`.
+//! - [`transform`] — `translate()`, `scale()`, and `rotate()` returning moved copies.
+//! - [`predicates`] — `contains_point()` and shape–shape `intersects()`.
+//! - [`svg`] — [`StyledShape`] (shape + fill/stroke [`Style`]) and [`to_svg`] /
+//!   [`write_svg`] for exporting a scene as an SVG file.
+//!
+//! Every type derives `Serialize`/`Deserialize`, so shapes round-trip through JSON
+//! (or any other serde format) as `{"shape": "circle", "radius": 1.0, ...}`.
+//!
+//! # How `enums` imports this crate
+//!
+//! `5_enums/Cargo.toml` lists `geometry = { path = "geometry" }`, so the demos
+//! write `use geometry::Shapes;`.
+
+pub mod bbox;
+pub mod predicates;
+pub mod shapes;
+pub mod svg;
+pub mod transform;
+
+#[cfg(test)]
+mod testing;
+
+pub use bbox::BoundingBox;
+pub use shapes::{Point, Shapes};
+pub use svg::{Style, StyledShape, to_svg, write_svg};
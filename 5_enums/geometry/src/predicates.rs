@@ -0,0 +1,354 @@
+//! Geometric predicates: point containment and shape–shape intersection.
+//!
+//! Shapes are treated as solid regions, so a shape "intersects" another when
+//! they share at least one point — overlapping, touching, or one inside the
+//! other. Points on a boundary count as contained.
+
+use crate::shapes::{Point, Shapes};
+use crate::transform::rotate_point;
+
+/// Slack for boundary tests, so points on an edge survive float rounding.
+const EPSILON: f64 = 1e-9;
+
+/// Vertices used to approximate an ellipse when it is tested against another ellipse.
+const ELLIPSE_SEGMENTS: usize = 256;
+
+impl Shapes {
+    /// `true` when `point` lies inside the shape or on its boundary.
+    pub fn contains_point(&self, point: Point) -> bool {
+        match self.region() {
+            Region::Polygon(vertices) => polygon_contains(&vertices, point),
+            Region::Ellipse(ellipse) => {
+                let (x, y) = ellipse.to_unit(point);
+                x * x + y * y <= 1.0 + EPSILON
+            }
+        }
+    }
+
+    /// `true` when the two shapes share at least one point.
+    ///
+    /// Exact (up to float rounding) for every pair except two non-circular
+    /// ellipses, which are compared as fine inscribed polygons and can miss a
+    /// contact thinner than about 0.01 % of their size.
+    pub fn intersects(&self, other: &Shapes) -> bool {
+        if let (Some(a), Some(b)) = (self.bounding_box(), other.bounding_box())
+            && !a.overlaps(&b)
+        {
+            return false;
+        }
+        match (self.region(), other.region()) {
+            (Region::Polygon(a), Region::Polygon(b)) => polygons_intersect(&a, &b),
+            (Region::Ellipse(e), Region::Polygon(p)) | (Region::Polygon(p), Region::Ellipse(e)) => {
+                // Map the polygon into the space where `e` is the unit circle;
+                // the affine map keeps "do they touch" unchanged.
+                let mapped: Vec<Point> = p.iter().map(|&v| e.to_unit(v)).collect();
+                unit_circle_meets_polygon(&mapped)
+            }
+            (Region::Ellipse(a), Region::Ellipse(b)) => {
+                if a.is_circle() && b.is_circle() {
+                    let gap = distance(a.center, b.center);
+                    gap <= a.radius_x + b.radius_x + EPSILON
+                } else {
+                    polygons_intersect(&a.to_polygon(), &b.to_polygon())
+                }
+            }
+        }
+    }
+
+    // Every variant is either a polygon or a (possibly rotated) ellipse.
+    fn region(&self) -> Region {
+        match self {
+            Shapes::Circle { radius, center } => Region::Ellipse(Ellipse {
+                center: *center,
+                radius_x: *radius,
+                radius_y: *radius,
+                rotation: 0.0,
+            }),
+            Shapes::Rectangle {
+                width,
+                height,
+                origin: (x, y),
+            } => Region::Polygon(vec![
+                (*x, *y),
+                (x + width, *y),
+                (x + width, y + height),
+                (*x, y + height),
+            ]),
+            Shapes::Triangle { a, b, c } => Region::Polygon(vec![*a, *b, *c]),
+            Shapes::Polygon { vertices } => Region::Polygon(vertices.clone()),
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => Region::Ellipse(Ellipse {
+                center: *center,
+                radius_x: *radius_x,
+                radius_y: *radius_y,
+                rotation: *rotation,
+            }),
+        }
+    }
+}
+
+enum Region {
+    Polygon(Vec<Point>),
+    Ellipse(Ellipse),
+}
+
+struct Ellipse {
+    center: Point,
+    radius_x: f64,
+    radius_y: f64,
+    rotation: f64,
+}
+
+impl Ellipse {
+    fn is_circle(&self) -> bool {
+        self.radius_x == self.radius_y
+    }
+
+    // Coordinates of `p` in the frame where this ellipse is the unit circle at the origin.
+    fn to_unit(&self, p: Point) -> Point {
+        let (x, y) = rotate_point(p, -self.rotation, self.center);
+        (
+            (x - self.center.0) / self.radius_x,
+            (y - self.center.1) / self.radius_y,
+        )
+    }
+
+    fn to_polygon(&self) -> Vec<Point> {
+        (0..ELLIPSE_SEGMENTS)
+            .map(|i| {
+                let t = i as f64 / ELLIPSE_SEGMENTS as f64 * std::f64::consts::TAU;
+                let on_axes = (
+                    self.center.0 + self.radius_x * t.cos(),
+                    self.center.1 + self.radius_y * t.sin(),
+                );
+                rotate_point(on_axes, self.rotation, self.center)
+            })
+            .collect()
+    }
+}
+
+fn distance(p: Point, q: Point) -> f64 {
+    (q.0 - p.0).hypot(q.1 - p.1)
+}
+
+// Edges of a closed polygon, including the one from the last vertex back to the first.
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    let n = vertices.len();
+    (0..n).map(move |i| (vertices[i], vertices[(i + 1) % n]))
+}
+
+fn distance_to_segment(p: Point, (a, b): (Point, Point)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    distance(p, (a.0 + t * dx, a.1 + t * dy))
+}
+
+// Boundary-inclusive even–odd test; works for concave polygons too.
+fn polygon_contains(vertices: &[Point], p: Point) -> bool {
+    if edges(vertices).any(|edge| distance_to_segment(p, edge) <= EPSILON) {
+        return true;
+    }
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if (a.1 > p.1) != (b.1 > p.1) {
+            let crossing_x = a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0);
+            if p.0 < crossing_x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// Twice the signed area of triangle `a b c`: positive when it turns left.
+fn orientation(a: Point, b: Point, c: Point) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn segments_intersect((a, b): (Point, Point), (c, d): (Point, Point)) -> bool {
+    let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+    let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+    if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+        return true;
+    }
+    // Touching or collinear: some endpoint lies on the other segment.
+    distance_to_segment(c, (a, b)) <= EPSILON
+        || distance_to_segment(d, (a, b)) <= EPSILON
+        || distance_to_segment(a, (c, d)) <= EPSILON
+        || distance_to_segment(b, (c, d)) <= EPSILON
+}
+
+// Two polygons overlap when their edges cross or one sits entirely inside the other.
+fn polygons_intersect(a: &[Point], b: &[Point]) -> bool {
+    let (Some(&first_a), Some(&first_b)) = (a.first(), b.first()) else {
+        return false;
+    };
+    edges(a).any(|ea| edges(b).any(|eb| segments_intersect(ea, eb)))
+        || polygon_contains(b, first_a)
+        || polygon_contains(a, first_b)
+}
+
+fn unit_circle_meets_polygon(vertices: &[Point]) -> bool {
+    !vertices.is_empty()
+        && (polygon_contains(vertices, (0.0, 0.0))
+            || edges(vertices).any(|edge| distance_to_segment((0.0, 0.0), edge) <= 1.0 + EPSILON))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::{arb_point, arb_shape};
+
+    fn circle(center: Point, radius: f64) -> Shapes {
+        Shapes::Circle { radius, center }
+    }
+
+    fn square(origin: Point, side: f64) -> Shapes {
+        Shapes::Rectangle {
+            width: side,
+            height: side,
+            origin,
+        }
+    }
+
+    #[test]
+    fn boundary_points_are_contained() {
+        let tri = Shapes::Triangle {
+            a: (0.0, 0.0),
+            b: (4.0, 0.0),
+            c: (0.0, 4.0),
+        };
+        assert!(tri.contains_point((0.0, 0.0)));
+        assert!(tri.contains_point((2.0, 2.0)));
+        assert!(tri.contains_point((1.0, 1.0)));
+        assert!(!tri.contains_point((2.1, 2.1)));
+        assert!(circle((0.0, 0.0), 1.0).contains_point((0.0, -1.0)));
+    }
+
+    #[test]
+    fn concave_polygon_excludes_its_notch() {
+        // A "U" shape: the notch between the arms is outside.
+        let u = Shapes::Polygon {
+            vertices: vec![
+                (0.0, 0.0),
+                (3.0, 0.0),
+                (3.0, 3.0),
+                (2.0, 3.0),
+                (2.0, 1.0),
+                (1.0, 1.0),
+                (1.0, 3.0),
+                (0.0, 3.0),
+            ],
+        };
+        assert!(u.contains_point((0.5, 2.5)));
+        assert!(!u.contains_point((1.5, 2.0)));
+    }
+
+    #[test]
+    fn rotated_ellipse_containment_follows_its_long_axis() {
+        let upright = Shapes::Ellipse {
+            center: (0.0, 0.0),
+            radius_x: 3.0,
+            radius_y: 1.0,
+            rotation: FRAC_PI_2,
+        };
+        assert!(upright.contains_point((0.0, 2.5)));
+        assert!(!upright.contains_point((2.5, 0.0)));
+    }
+
+    #[test]
+    fn circles_touching_at_one_point_intersect() {
+        assert!(circle((0.0, 0.0), 1.0).intersects(&circle((2.0, 0.0), 1.0)));
+        assert!(!circle((0.0, 0.0), 1.0).intersects(&circle((2.1, 0.0), 1.0)));
+    }
+
+    #[test]
+    fn nested_shapes_intersect() {
+        let big = square((0.0, 0.0), 10.0);
+        assert!(big.intersects(&square((4.0, 4.0), 1.0)));
+        assert!(big.intersects(&circle((5.0, 5.0), 1.0)));
+        assert!(circle((5.0, 5.0), 1.0).intersects(&big));
+    }
+
+    #[test]
+    fn circle_near_a_square_corner() {
+        let unit = square((0.0, 0.0), 1.0);
+        // The corner (1, 1) is sqrt(2) ≈ 1.414 away from (2, 2).
+        assert!(unit.intersects(&circle((2.0, 2.0), 1.5)));
+        assert!(!unit.intersects(&circle((2.0, 2.0), 1.4)));
+    }
+
+    #[test]
+    fn ellipses_along_their_long_axes() {
+        let a = Shapes::Ellipse {
+            center: (0.0, 0.0),
+            radius_x: 3.0,
+            radius_y: 1.0,
+            rotation: 0.0,
+        };
+        let b = a.translate(5.9, 0.0);
+        let c = a.translate(0.0, 2.1);
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+    }
+
+    #[test]
+    fn empty_polygon_touches_nothing() {
+        let empty = Shapes::Polygon { vertices: vec![] };
+        assert!(!empty.contains_point((0.0, 0.0)));
+        assert!(!empty.intersects(&square((0.0, 0.0), 1.0)));
+    }
+
+    proptest! {
+        #[test]
+        fn every_shape_intersects_itself(shape in arb_shape()) {
+            prop_assert!(shape.intersects(&shape));
+        }
+
+        #[test]
+        fn intersection_is_symmetric(a in arb_shape(), b in arb_shape()) {
+            prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+        }
+
+        #[test]
+        fn contained_points_lie_in_the_bounding_box(shape in arb_shape(), p in arb_point()) {
+            if shape.contains_point(p) {
+                prop_assert!(shape.bounding_box().unwrap().contains(p, 1e-6));
+            }
+        }
+
+        #[test]
+        fn containment_moves_with_the_shape(
+            shape in arb_shape(),
+            p in arb_point(),
+            (dx, dy) in arb_point(),
+        ) {
+            let moved = shape.translate(dx, dy);
+            prop_assert_eq!(shape.contains_point(p), moved.contains_point((p.0 + dx, p.1 + dy)));
+        }
+
+        #[test]
+        fn a_shape_meets_any_circle_around_one_of_its_points(
+            shape in arb_shape(),
+            p in arb_point(),
+            radius in 0.1..5.0,
+        ) {
+            if shape.contains_point(p) {
+                prop_assert!(shape.intersects(&circle(p, radius)));
+            }
+        }
+    }
+}
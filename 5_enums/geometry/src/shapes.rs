@@ -0,0 +1,285 @@
+//! The `Shapes` enum: one variant per kind of 2D shape, each carrying its own data.
+
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bbox::BoundingBox;
+
+/// A point in the plane as `(x, y)`.
+pub type Point = (f64, f64);
+
+/// A 2D shape placed in the plane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Shapes {
+    Circle {
+        radius: f64,
+        center: Point,
+    },
+    /// Axis-aligned rectangle; `origin` is its bottom-left corner.
+    Rectangle {
+        width: f64,
+        height: f64,
+        #[serde(default)]
+        origin: Point,
+    },
+    Triangle {
+        a: Point,
+        b: Point,
+        c: Point,
+    },
+    /// Closed polygon through `vertices` in order (the last vertex joins the first).
+    Polygon {
+        vertices: Vec<Point>,
+    },
+    /// Ellipse with semi-axes `radius_x` and `radius_y`, turned counter-clockwise
+    /// by `rotation` radians around its center (`0.0` = axis-aligned).
+    Ellipse {
+        center: Point,
+        radius_x: f64,
+        radius_y: f64,
+        #[serde(default)]
+        rotation: f64,
+    },
+}
+
+impl Shapes {
+    pub fn area(&self) -> f64 {
+        match self {
+            Shapes::Circle { radius, .. } => radius * radius * PI,
+            Shapes::Rectangle { width, height, .. } => width * height,
+            Shapes::Triangle { a, b, c } => shoelace(&[*a, *b, *c]),
+            Shapes::Polygon { vertices } => shoelace(vertices),
+            Shapes::Ellipse {
+                radius_x, radius_y, ..
+            } => PI * radius_x * radius_y,
+        }
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        match self {
+            Shapes::Circle { radius, .. } => 2.0 * PI * radius,
+            Shapes::Rectangle { width, height, .. } => 2.0 * (width + height),
+            Shapes::Triangle { a, b, c } => distance(*a, *b) + distance(*b, *c) + distance(*c, *a),
+            Shapes::Polygon { vertices } => closed_path_length(vertices),
+            Shapes::Ellipse {
+                radius_x, radius_y, ..
+            } => {
+                // Ramanujan's second approximation: exact for circles, within
+                // about 0.04 % even for very flat ellipses.
+                let (a, b) = (*radius_x, *radius_y);
+                let h = ((a - b) / (a + b)).powi(2);
+                PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+            }
+        }
+    }
+
+    /// Smallest axis-aligned box containing the shape, or `None` for a polygon with no vertices.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        match self {
+            Shapes::Circle { radius, center } => Some(box_around(*center, *radius, *radius)),
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            } => BoundingBox::from_points(&[*origin, (origin.0 + width, origin.1 + height)]),
+            Shapes::Triangle { a, b, c } => BoundingBox::from_points(&[*a, *b, *c]),
+            Shapes::Polygon { vertices } => BoundingBox::from_points(vertices),
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => {
+                // Extent of a rotated ellipse along each axis.
+                let (sin, cos) = rotation.sin_cos();
+                let half_width = (radius_x * cos).hypot(radius_y * sin);
+                let half_height = (radius_x * sin).hypot(radius_y * cos);
+                Some(box_around(*center, half_width, half_height))
+            }
+        }
+    }
+
+    /// Lower-case variant name, e.g. `"circle"` (matches the serde `shape` tag).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shapes::Circle { .. } => "circle",
+            Shapes::Rectangle { .. } => "rectangle",
+            Shapes::Triangle { .. } => "triangle",
+            Shapes::Polygon { .. } => "polygon",
+            Shapes::Ellipse { .. } => "ellipse",
+        }
+    }
+}
+
+fn distance(p: Point, q: Point) -> f64 {
+    (q.0 - p.0).hypot(q.1 - p.1)
+}
+
+// Area of a simple polygon via the shoelace formula; vertex order does not matter.
+fn shoelace(vertices: &[Point]) -> f64 {
+    let n = vertices.len();
+    let twice_area: f64 = (0..n)
+        .map(|i| {
+            let (p, q) = (vertices[i], vertices[(i + 1) % n]);
+            p.0 * q.1 - q.0 * p.1
+        })
+        .sum();
+    twice_area.abs() / 2.0
+}
+
+fn closed_path_length(vertices: &[Point]) -> f64 {
+    let n = vertices.len();
+    if n < 2 {
+        return 0.0;
+    }
+    (0..n)
+        .map(|i| distance(vertices[i], vertices[(i + 1) % n]))
+        .sum()
+}
+
+fn box_around(center: Point, half_width: f64, half_height: f64) -> BoundingBox {
+    BoundingBox {
+        min: (center.0 - half_width, center.1 - half_height),
+        max: (center.0 + half_width, center.1 + half_height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn unit_square() -> Shapes {
+        Shapes::Polygon {
+            vertices: vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+        }
+    }
+
+    #[test]
+    fn circle_measures() {
+        let circle = Shapes::Circle {
+            radius: 2.0,
+            center: (1.0, 1.0),
+        };
+        assert_close(circle.area(), 4.0 * PI);
+        assert_close(circle.perimeter(), 4.0 * PI);
+        let bbox = circle.bounding_box().unwrap();
+        assert_eq!(bbox.min, (-1.0, -1.0));
+        assert_eq!(bbox.max, (3.0, 3.0));
+    }
+
+    #[test]
+    fn rectangle_measures() {
+        let rect = Shapes::Rectangle {
+            width: 3.0,
+            height: 2.0,
+            origin: (1.0, 1.0),
+        };
+        assert_close(rect.area(), 6.0);
+        assert_close(rect.perimeter(), 10.0);
+        assert_eq!(rect.bounding_box().unwrap().max, (4.0, 3.0));
+    }
+
+    #[test]
+    fn right_triangle_measures() {
+        let tri = Shapes::Triangle {
+            a: (0.0, 0.0),
+            b: (3.0, 0.0),
+            c: (0.0, 4.0),
+        };
+        assert_close(tri.area(), 6.0);
+        assert_close(tri.perimeter(), 12.0);
+        let bbox = tri.bounding_box().unwrap();
+        assert_close(bbox.width(), 3.0);
+        assert_close(bbox.height(), 4.0);
+    }
+
+    #[test]
+    fn polygon_area_ignores_winding_direction() {
+        let square = unit_square();
+        let Shapes::Polygon { mut vertices } = square.clone() else {
+            unreachable!()
+        };
+        vertices.reverse();
+        let reversed = Shapes::Polygon { vertices };
+        assert_close(square.area(), 1.0);
+        assert_close(reversed.area(), 1.0);
+        assert_close(square.perimeter(), 4.0);
+    }
+
+    #[test]
+    fn empty_polygon_has_no_bounding_box() {
+        let empty = Shapes::Polygon { vertices: vec![] };
+        assert_eq!(empty.bounding_box(), None);
+        assert_close(empty.area(), 0.0);
+        assert_close(empty.perimeter(), 0.0);
+    }
+
+    #[test]
+    fn ellipse_with_equal_radii_is_a_circle() {
+        let ellipse = Shapes::Ellipse {
+            center: (0.0, 0.0),
+            radius_x: 2.0,
+            radius_y: 2.0,
+            rotation: 0.0,
+        };
+        assert_close(ellipse.area(), 4.0 * PI);
+        assert_close(ellipse.perimeter(), 4.0 * PI);
+    }
+
+    #[test]
+    fn flat_ellipse_perimeter_approaches_four_times_the_long_axis() {
+        let ellipse = Shapes::Ellipse {
+            center: (0.0, 0.0),
+            radius_x: 1.0,
+            radius_y: 1e-9,
+            rotation: 0.0,
+        };
+        // Ramanujan gives 14π/11 ≈ 3.998 in the limit, within 0.04 % of the true 4.
+        assert!((ellipse.perimeter() - 4.0).abs() < 2e-3);
+        assert_eq!(ellipse.bounding_box().unwrap().max.0, 1.0);
+    }
+
+    #[test]
+    fn shapes_round_trip_through_json() {
+        let shapes = vec![
+            Shapes::Circle {
+                radius: 1.0,
+                center: (0.0, 0.0),
+            },
+            unit_square(),
+            Shapes::Ellipse {
+                center: (2.0, 1.0),
+                radius_x: 3.0,
+                radius_y: 1.0,
+                rotation: 0.0,
+            },
+        ];
+        let json = serde_json::to_string(&shapes).unwrap();
+        assert!(json.contains(r#""shape":"circle""#));
+        let back: Vec<Shapes> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, shapes);
+    }
+
+    #[test]
+    fn rectangle_origin_defaults_to_zero_when_missing() {
+        let rect: Shapes =
+            serde_json::from_str(r#"{"shape":"rectangle","width":2.0,"height":1.0}"#).unwrap();
+        assert_eq!(
+            rect,
+            Shapes::Rectangle {
+                width: 2.0,
+                height: 1.0,
+                origin: (0.0, 0.0)
+            }
+        );
+    }
+}
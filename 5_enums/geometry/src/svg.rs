@@ -0,0 +1,225 @@
+//! SVG export: render styled shapes into a standalone SVG document.
+//!
+//! Shapes use maths coordinates (y grows upwards) while SVG's y axis points
+//! down, so the output wraps everything in a `scale(1, -1)` group and picks a
+//! `viewBox` around the scene's bounding box.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bbox::BoundingBox;
+use crate::shapes::{Point, Shapes};
+
+/// Padding added around the scene's bounding box, in shape units.
+const MARGIN: f64 = 1.0;
+
+/// How a shape is painted: any SVG paint string (`"red"`, `"#ff8800"`, `"none"`) for fill and stroke.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    pub fill: String,
+    pub stroke: String,
+    pub stroke_width: f64,
+}
+
+impl Style {
+    /// Filled with `fill`, outlined in black.
+    pub fn filled(fill: impl Into<String>) -> Style {
+        Style {
+            fill: fill.into(),
+            ..Style::default()
+        }
+    }
+}
+
+/// Unfilled, 1-unit black outline.
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: "none".to_string(),
+            stroke: "black".to_string(),
+            stroke_width: 1.0,
+        }
+    }
+}
+
+/// A shape together with the style it is drawn with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StyledShape {
+    pub shape: Shapes,
+    #[serde(default)]
+    pub style: Style,
+}
+
+impl StyledShape {
+    pub fn new(shape: Shapes, style: Style) -> StyledShape {
+        StyledShape { shape, style }
+    }
+}
+
+/// Render `shapes` as an SVG document, drawn in order (later shapes on top).
+pub fn to_svg(shapes: &[StyledShape]) -> String {
+    let bbox = shapes
+        .iter()
+        .filter_map(|s| s.shape.bounding_box())
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or(BoundingBox {
+            min: (0.0, 0.0),
+            max: (0.0, 0.0),
+        });
+    let (width, height) = (bbox.width() + 2.0 * MARGIN, bbox.height() + 2.0 * MARGIN);
+    // After flipping y, the scene spans -max.y..-min.y vertically.
+    let (view_x, view_y) = (bbox.min.0 - MARGIN, -bbox.max.1 - MARGIN);
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{view_x} {view_y} {width} {height}\">\n"
+    );
+    out.push_str("  <g transform=\"scale(1, -1)\">\n");
+    for styled in shapes {
+        out.push_str("    ");
+        out.push_str(&element(&styled.shape));
+        out.push_str(&format!(
+            " fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\" />\n",
+            escape(&styled.style.fill),
+            escape(&styled.style.stroke),
+            styled.style.stroke_width
+        ));
+    }
+    out.push_str("  </g>\n</svg>\n");
+    out
+}
+
+/// Render `shapes` with [`to_svg`] and write the document to `path`.
+pub fn write_svg(path: &Path, shapes: &[StyledShape]) -> io::Result<()> {
+    fs::write(path, to_svg(shapes))
+}
+
+// Opening tag plus geometry attributes; the caller appends the style and closes it.
+fn element(shape: &Shapes) -> String {
+    match shape {
+        Shapes::Circle { radius, center } => {
+            format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{radius}\"",
+                center.0, center.1
+            )
+        }
+        Shapes::Rectangle {
+            width,
+            height,
+            origin,
+        } => format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{width}\" height=\"{height}\"",
+            origin.0, origin.1
+        ),
+        Shapes::Triangle { a, b, c } => polygon(&[*a, *b, *c]),
+        Shapes::Polygon { vertices } => polygon(vertices),
+        Shapes::Ellipse {
+            center,
+            radius_x,
+            radius_y,
+            rotation,
+        } => {
+            let mut tag = format!(
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{radius_x}\" ry=\"{radius_y}\"",
+                center.0, center.1
+            );
+            if *rotation != 0.0 {
+                // Inside the flipped group a positive SVG angle is counter-clockwise, like ours.
+                tag.push_str(&format!(
+                    " transform=\"rotate({} {} {})\"",
+                    rotation.to_degrees(),
+                    center.0,
+                    center.1
+                ));
+            }
+            tag
+        }
+    }
+}
+
+fn polygon(vertices: &[Point]) -> String {
+    let points: Vec<String> = vertices.iter().map(|(x, y)| format!("{x},{y}")).collect();
+    format!("<polygon points=\"{}\"", points.join(" "))
+}
+
+// Paint strings end up inside double-quoted attributes.
+fn escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_variant_maps_to_its_svg_element() {
+        let scene = [
+            Shapes::Circle {
+                radius: 1.0,
+                center: (0.0, 0.0),
+            },
+            Shapes::Rectangle {
+                width: 2.0,
+                height: 1.0,
+                origin: (1.0, 1.0),
+            },
+            Shapes::Triangle {
+                a: (0.0, 0.0),
+                b: (1.0, 0.0),
+                c: (0.0, 1.0),
+            },
+            Shapes::Ellipse {
+                center: (0.0, 0.0),
+                radius_x: 2.0,
+                radius_y: 1.0,
+                rotation: 0.0,
+            },
+        ]
+        .map(|shape| StyledShape::new(shape, Style::default()));
+        let svg = to_svg(&scene);
+        assert!(svg.contains(r#"<circle cx="0" cy="0" r="1""#));
+        assert!(svg.contains(r#"<rect x="1" y="1" width="2" height="1""#));
+        assert!(svg.contains(r#"<polygon points="0,0 1,0 0,1""#));
+        assert!(svg.contains(r#"<ellipse cx="0" cy="0" rx="2" ry="1""#));
+        assert_eq!(svg.matches(r#"stroke="black""#).count(), 4);
+    }
+
+    #[test]
+    fn view_box_covers_the_scene_with_a_margin() {
+        let scene = [StyledShape::new(
+            Shapes::Rectangle {
+                width: 4.0,
+                height: 2.0,
+                origin: (1.0, 3.0),
+            },
+            Style::filled("red"),
+        )];
+        let svg = to_svg(&scene);
+        // x: 1..5, y: 3..5 flipped to -5..-3, plus one unit on every side.
+        assert!(svg.contains(r#"viewBox="0 -6 6 4""#));
+        assert!(svg.contains(r#"fill="red""#));
+    }
+
+    #[test]
+    fn style_strings_are_escaped() {
+        let scene = [StyledShape::new(
+            Shapes::Circle {
+                radius: 1.0,
+                center: (0.0, 0.0),
+            },
+            Style::filled("\"><script>"),
+        )];
+        assert!(!to_svg(&scene).contains("<script>"));
+    }
+
+    #[test]
+    fn empty_scene_is_still_a_valid_document() {
+        let svg = to_svg(&[]);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
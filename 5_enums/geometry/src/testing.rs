@@ -0,0 +1,40 @@
+//! Shared helpers for the unit and property tests in this crate.
+
+use proptest::prelude::*;
+
+use crate::shapes::{Point, Shapes};
+
+/// Equal up to a relative tolerance (absolute near zero).
+pub fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0)
+}
+
+pub fn arb_point() -> impl Strategy<Value = Point> {
+    (-50.0..50.0, -50.0..50.0)
+}
+
+/// Any variant with finite, non-degenerate sizes; polygons have 3 to 8 vertices.
+pub fn arb_shape() -> impl Strategy<Value = Shapes> {
+    let length = 0.5..20.0;
+    prop_oneof![
+        (length.clone(), arb_point())
+            .prop_map(|(radius, center)| Shapes::Circle { radius, center }),
+        (length.clone(), length.clone(), arb_point()).prop_map(|(width, height, origin)| {
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            }
+        }),
+        (arb_point(), arb_point(), arb_point()).prop_map(|(a, b, c)| Shapes::Triangle { a, b, c }),
+        prop::collection::vec(arb_point(), 3..8).prop_map(|vertices| Shapes::Polygon { vertices }),
+        (arb_point(), length.clone(), length, -3.2..3.2).prop_map(
+            |(center, radius_x, radius_y, rotation)| Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            }
+        ),
+    ]
+}
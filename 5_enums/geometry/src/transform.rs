@@ -0,0 +1,252 @@
+//! Rigid and similarity transforms: `translate`, `scale`, and `rotate` return a moved copy.
+//!
+//! Rectangles are axis-aligned by definition, so rotating one yields a
+//! four-vertex [`Shapes::Polygon`]; every other variant keeps its kind.
+
+use crate::shapes::{Point, Shapes};
+
+impl Shapes {
+    /// Copy moved by `(dx, dy)`.
+    pub fn translate(&self, dx: f64, dy: f64) -> Shapes {
+        self.map_points(|(x, y)| (x + dx, y + dy))
+    }
+
+    /// Copy scaled by `factor` around `about`; lengths grow by `|factor|`, areas by `factor²`.
+    ///
+    /// A negative factor mirrors the shape through `about` (a half turn).
+    pub fn scale(&self, factor: f64, about: Point) -> Shapes {
+        let scaled = self.map_points(|(x, y)| {
+            (
+                about.0 + (x - about.0) * factor,
+                about.1 + (y - about.1) * factor,
+            )
+        });
+        match scaled {
+            Shapes::Circle { radius, center } => Shapes::Circle {
+                radius: radius * factor.abs(),
+                center,
+            },
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            } => {
+                // With a negative factor the mapped origin is the top-right corner.
+                let (width, height) = (width * factor.abs(), height * factor.abs());
+                let origin = if factor < 0.0 {
+                    (origin.0 - width, origin.1 - height)
+                } else {
+                    origin
+                };
+                Shapes::Rectangle {
+                    width,
+                    height,
+                    origin,
+                }
+            }
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => Shapes::Ellipse {
+                center,
+                radius_x: radius_x * factor.abs(),
+                radius_y: radius_y * factor.abs(),
+                rotation,
+            },
+            polygonal => polygonal,
+        }
+    }
+
+    /// Copy turned counter-clockwise by `angle` radians around `about`.
+    pub fn rotate(&self, angle: f64, about: Point) -> Shapes {
+        let turn = |p: Point| rotate_point(p, angle, about);
+        match self {
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            } => {
+                let (x, y) = *origin;
+                Shapes::Polygon {
+                    vertices: [
+                        (x, y),
+                        (x + width, y),
+                        (x + width, y + height),
+                        (x, y + height),
+                    ]
+                    .map(turn)
+                    .to_vec(),
+                }
+            }
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => Shapes::Ellipse {
+                center: turn(*center),
+                radius_x: *radius_x,
+                radius_y: *radius_y,
+                rotation: rotation + angle,
+            },
+            other => other.map_points(turn),
+        }
+    }
+
+    // Apply `f` to every stored point (centers, corners, vertices), leaving sizes alone.
+    fn map_points(&self, f: impl Fn(Point) -> Point) -> Shapes {
+        match self {
+            Shapes::Circle { radius, center } => Shapes::Circle {
+                radius: *radius,
+                center: f(*center),
+            },
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            } => Shapes::Rectangle {
+                width: *width,
+                height: *height,
+                origin: f(*origin),
+            },
+            Shapes::Triangle { a, b, c } => Shapes::Triangle {
+                a: f(*a),
+                b: f(*b),
+                c: f(*c),
+            },
+            Shapes::Polygon { vertices } => Shapes::Polygon {
+                vertices: vertices.iter().copied().map(f).collect(),
+            },
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => Shapes::Ellipse {
+                center: f(*center),
+                radius_x: *radius_x,
+                radius_y: *radius_y,
+                rotation: *rotation,
+            },
+        }
+    }
+}
+
+/// `p` turned counter-clockwise by `angle` radians around `about`.
+pub(crate) fn rotate_point(p: Point, angle: f64, about: Point) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    let (dx, dy) = (p.0 - about.0, p.1 - about.1);
+    (about.0 + dx * cos - dy * sin, about.1 + dx * sin + dy * cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::{approx_eq, arb_point, arb_shape};
+
+    #[test]
+    fn quarter_turn_of_a_rectangle_is_a_polygon() {
+        let rect = Shapes::Rectangle {
+            width: 2.0,
+            height: 1.0,
+            origin: (0.0, 0.0),
+        };
+        let turned = rect.rotate(FRAC_PI_2, (0.0, 0.0));
+        let bbox = turned.bounding_box().unwrap();
+        assert_eq!(turned.name(), "polygon");
+        assert!(approx_eq(bbox.min.0, -1.0) && approx_eq(bbox.max.1, 2.0));
+    }
+
+    #[test]
+    fn negative_scale_keeps_rectangles_well_formed() {
+        let rect = Shapes::Rectangle {
+            width: 2.0,
+            height: 1.0,
+            origin: (1.0, 1.0),
+        };
+        let flipped = rect.scale(-2.0, (0.0, 0.0));
+        assert_eq!(
+            flipped,
+            Shapes::Rectangle {
+                width: 4.0,
+                height: 2.0,
+                origin: (-6.0, -4.0)
+            }
+        );
+    }
+
+    #[test]
+    fn rotating_an_ellipse_accumulates_its_angle() {
+        let ellipse = Shapes::Ellipse {
+            center: (1.0, 0.0),
+            radius_x: 3.0,
+            radius_y: 1.0,
+            rotation: 0.0,
+        };
+        let Shapes::Ellipse {
+            center, rotation, ..
+        } = ellipse.rotate(PI, (0.0, 0.0)).rotate(FRAC_PI_2, (0.0, 0.0))
+        else {
+            unreachable!()
+        };
+        assert!(approx_eq(center.0, 0.0) && approx_eq(center.1, -1.0));
+        assert!(approx_eq(rotation, 1.5 * PI));
+    }
+
+    proptest! {
+        #[test]
+        fn translation_preserves_measures(shape in arb_shape(), (dx, dy) in arb_point()) {
+            let moved = shape.translate(dx, dy);
+            prop_assert!(approx_eq(moved.area(), shape.area()));
+            prop_assert!(approx_eq(moved.perimeter(), shape.perimeter()));
+        }
+
+        #[test]
+        fn translation_moves_the_bounding_box(shape in arb_shape(), (dx, dy) in arb_point()) {
+            let before = shape.bounding_box().unwrap();
+            let after = shape.translate(dx, dy).bounding_box().unwrap();
+            prop_assert!(approx_eq(after.min.0, before.min.0 + dx));
+            prop_assert!(approx_eq(after.max.1, before.max.1 + dy));
+        }
+
+        #[test]
+        fn rotation_preserves_measures(
+            shape in arb_shape(),
+            angle in -2.0 * PI..2.0 * PI,
+            about in arb_point(),
+        ) {
+            let turned = shape.rotate(angle, about);
+            prop_assert!(approx_eq(turned.area(), shape.area()));
+            prop_assert!(approx_eq(turned.perimeter(), shape.perimeter()));
+        }
+
+        #[test]
+        fn scaling_multiplies_lengths_and_areas(
+            shape in arb_shape(),
+            factor in prop_oneof![-4.0..-0.25, 0.25..4.0],
+            about in arb_point(),
+        ) {
+            let scaled = shape.scale(factor, about);
+            prop_assert!(approx_eq(scaled.area(), shape.area() * factor * factor));
+            prop_assert!(approx_eq(scaled.perimeter(), shape.perimeter() * factor.abs()));
+        }
+
+        #[test]
+        fn rotating_there_and_back_restores_the_bounding_box(
+            shape in arb_shape(),
+            angle in -PI..PI,
+            about in arb_point(),
+        ) {
+            let before = shape.bounding_box().unwrap();
+            let after = shape.rotate(angle, about).rotate(-angle, about).bounding_box().unwrap();
+            prop_assert!(approx_eq(after.min.0, before.min.0) && approx_eq(after.min.1, before.min.1));
+            prop_assert!(approx_eq(after.max.0, before.max.0) && approx_eq(after.max.1, before.max.1));
+        }
+    }
+}
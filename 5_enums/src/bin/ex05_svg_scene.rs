@@ -0,0 +1,3 @@
+fn main() {
+    enums::ex05_svg_scene::run();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    enums::ex06_shape_queries::run();
+}
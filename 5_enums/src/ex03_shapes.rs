@@ -1,28 +1,70 @@
-use crate::shapes::Shapes;
+use geometry::Shapes;
 
 pub fn run() {
-    let arbitrary_shape = Shapes::Circle {
-        radius: 10.0,
-        center: (0.0, 0.0),
-    };
-    let _rect = Shapes::Rectangle {
-        width: 10.0,
-        height: 20.0,
-    };
+    let shapes = [
+        Shapes::Circle {
+            radius: 10.0,
+            center: (0.0, 0.0),
+        },
+        Shapes::Rectangle {
+            width: 10.0,
+            height: 20.0,
+            origin: (0.0, 0.0),
+        },
+        Shapes::Triangle {
+            a: (0.0, 0.0),
+            b: (3.0, 0.0),
+            c: (0.0, 4.0),
+        },
+        Shapes::Polygon {
+            vertices: vec![(0.0, 0.0), (4.0, 0.0), (5.0, 3.0), (1.0, 3.0)],
+        },
+        Shapes::Ellipse {
+            center: (2.0, 1.0),
+            radius_x: 4.0,
+            radius_y: 2.0,
+            rotation: 0.0,
+        },
+    ];
 
-    let shape_area = arbitrary_shape.area();
-    match arbitrary_shape {
-        Shapes::Circle { radius, center } => {
-            println!(
-                "Circle: radius: {}, center: {:?}, area: {}",
-                radius, center, shape_area
-            );
-        }
-        Shapes::Rectangle { width, height } => {
-            println!(
-                "Rectangle: width: {}, height: {}, area: {}",
-                width, height, shape_area
-            );
+    for shape in &shapes {
+        match shape {
+            Shapes::Circle { radius, center } => {
+                println!("Circle: radius: {}, center: {:?}", radius, center);
+            }
+            Shapes::Rectangle {
+                width,
+                height,
+                origin,
+            } => {
+                println!(
+                    "Rectangle: width: {}, height: {}, origin: {:?}",
+                    width, height, origin
+                );
+            }
+            Shapes::Triangle { a, b, c } => {
+                println!("Triangle: a: {:?}, b: {:?}, c: {:?}", a, b, c);
+            }
+            Shapes::Polygon { vertices } => {
+                println!("Polygon: {} vertices", vertices.len());
+            }
+            Shapes::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                ..
+            } => {
+                println!(
+                    "Ellipse: center: {:?}, radii: {} x {}",
+                    center, radius_x, radius_y
+                );
+            }
         }
+        println!(
+            "  area: {:.2}, perimeter: {:.2}, bounding box: {:?}",
+            shape.area(),
+            shape.perimeter(),
+            shape.bounding_box()
+        );
     }
 }
@@ -0,0 +1,104 @@
+//! Topic 5 — Enums as data: build a scene of `Shapes` and export it as an SVG file.
+//!
+//! Run via: `cargo run --bin ex05_svg_scene [out.svg]` (defaults to `scene.svg`)
+
+use std::env;
+use std::path::PathBuf;
+
+use geometry::{Shapes, Style, StyledShape, write_svg};
+
+pub fn run() {
+    let path = PathBuf::from(
+        env::args()
+            .nth(1)
+            .unwrap_or_else(|| "scene.svg".to_string()),
+    );
+
+    let scene = vec![
+        // Ground and a house: walls, roof, door.
+        StyledShape::new(
+            Shapes::Rectangle {
+                width: 60.0,
+                height: 8.0,
+                origin: (0.0, 0.0),
+            },
+            Style::filled("#7cb342"),
+        ),
+        StyledShape::new(
+            Shapes::Rectangle {
+                width: 20.0,
+                height: 14.0,
+                origin: (10.0, 8.0),
+            },
+            Style::filled("#ffe0b2"),
+        ),
+        StyledShape::new(
+            Shapes::Triangle {
+                a: (8.0, 22.0),
+                b: (32.0, 22.0),
+                c: (20.0, 32.0),
+            },
+            Style::filled("#c62828"),
+        ),
+        StyledShape::new(
+            Shapes::Rectangle {
+                width: 4.0,
+                height: 8.0,
+                origin: (18.0, 8.0),
+            },
+            Style::filled("#6d4c41"),
+        ),
+        // Sun, a cloud, and a kite, each with its own outline colour.
+        StyledShape::new(
+            Shapes::Circle {
+                radius: 5.0,
+                center: (50.0, 34.0),
+            },
+            Style {
+                fill: "#fdd835".to_string(),
+                stroke: "#f57f17".to_string(),
+                stroke_width: 0.5,
+            },
+        ),
+        StyledShape::new(
+            Shapes::Ellipse {
+                center: (36.0, 40.0),
+                radius_x: 7.0,
+                radius_y: 2.5,
+                rotation: 0.0,
+            },
+            Style {
+                fill: "white".to_string(),
+                stroke: "#90a4ae".to_string(),
+                stroke_width: 0.3,
+            },
+        ),
+        StyledShape::new(
+            Shapes::Polygon {
+                vertices: vec![(44.0, 22.0), (47.0, 26.0), (44.0, 32.0), (41.0, 26.0)],
+            },
+            Style {
+                fill: "#42a5f5".to_string(),
+                stroke: "#0d47a1".to_string(),
+                stroke_width: 0.3,
+            },
+        ),
+    ];
+
+    for styled in &scene {
+        println!(
+            "{:<9} area {:>7.2}  fill {}",
+            styled.shape.name(),
+            styled.shape.area(),
+            styled.style.fill
+        );
+    }
+
+    match write_svg(&path, &scene) {
+        Ok(()) => println!("wrote {} shapes to {}", scene.len(), path.display()),
+        Err(e) => {
+            eprintln!("failed to write {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
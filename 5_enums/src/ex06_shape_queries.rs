@@ -0,0 +1,50 @@
+//! Topic 6 — Matching on enum pairs: point containment, overlap tests, and transforms on `Shapes`.
+//!
+//! Run via: `cargo run --bin ex06_shape_queries`
+
+use std::f64::consts::FRAC_PI_4;
+
+use geometry::Shapes;
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+pub fn run() {
+    let table = Shapes::Rectangle {
+        width: 4.0,
+        height: 2.0,
+        origin: (0.0, 0.0),
+    };
+    let plate = Shapes::Circle {
+        radius: 1.0,
+        center: (5.5, 1.0),
+    };
+
+    section("contains_point");
+    for point in [(1.0, 1.0), (4.0, 2.0), (5.0, 1.0)] {
+        println!(
+            "{point:?}: table {}, plate {}",
+            table.contains_point(point),
+            plate.contains_point(point)
+        );
+    }
+
+    section("intersects");
+    println!("plate on table? {}", plate.intersects(&table));
+    let slid = plate.translate(-1.0, 0.0);
+    println!("after sliding 1 left? {}", slid.intersects(&table));
+
+    section("transforms");
+    let turned = table.rotate(FRAC_PI_4, (2.0, 1.0));
+    println!("rotated 45° about its center: {}", turned.name());
+    println!("  area {:.2} (unchanged)", turned.area());
+    println!("  bounding box {:?}", turned.bounding_box());
+    let doubled = plate.scale(2.0, (5.5, 1.0));
+    println!(
+        "plate scaled x2: area {:.2} -> {:.2}, hits rotated table? {}",
+        plate.area(),
+        doubled.area(),
+        doubled.intersects(&turned)
+    );
+}
@@ -4,8 +4,9 @@ pub mod ex01_ip_struct;
 pub mod ex02_simple_ip;
 pub mod ex03_shapes;
 pub mod ex04_pet;
+pub mod ex05_svg_scene;
+pub mod ex06_shape_queries;
 
 pub mod ip;
 pub mod pet;
-pub mod shapes;
 pub mod simple_ip;
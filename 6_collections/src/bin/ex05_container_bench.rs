@@ -0,0 +1,3 @@
+fn main() {
+    collections::ex05_container_bench::run();
+}
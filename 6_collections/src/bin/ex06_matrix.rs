@@ -0,0 +1,3 @@
+fn main() {
+    collections::ex06_matrix::run();
+}
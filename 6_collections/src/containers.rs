@@ -0,0 +1,146 @@
+//! Workloads for comparing `Vec`, `VecDeque`, `HashMap`, and `BTreeMap` on the same data.
+//!
+//! Every container holds the keys `0..n` (maps store `key -> key * 2`), so the
+//! three operations below do the same logical work whichever container runs them.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+/// Container sizes the benchmark sweeps over.
+pub const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Lookups performed per `Operation::Lookup` run, regardless of size.
+pub const LOOKUPS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Vec,
+    VecDeque,
+    HashMap,
+    BTreeMap,
+}
+
+impl Container {
+    pub const ALL: [Container; 4] = [
+        Container::Vec,
+        Container::VecDeque,
+        Container::HashMap,
+        Container::BTreeMap,
+    ];
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Container::Vec => "Vec",
+            Container::VecDeque => "VecDeque",
+            Container::HashMap => "HashMap",
+            Container::BTreeMap => "BTreeMap",
+        };
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Build the container from empty by inserting `n` keys one at a time.
+    Insert,
+    /// Search for [`LOOKUPS`] keys, about half of which are missing.
+    Lookup,
+    /// Visit every value once and sum them.
+    Iterate,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 3] = [Operation::Insert, Operation::Lookup, Operation::Iterate];
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Insert => "insert",
+            Operation::Lookup => "lookup",
+            Operation::Iterate => "iterate",
+        };
+        f.pad(name)
+    }
+}
+
+/// A container already holding `0..n`, ready for lookups or iteration.
+pub enum Filled {
+    Vec(Vec<u64>),
+    VecDeque(VecDeque<u64>),
+    HashMap(HashMap<u64, u64>),
+    BTreeMap(BTreeMap<u64, u64>),
+}
+
+impl Filled {
+    /// Insert the keys `0..n` one at a time (this is the `Insert` workload).
+    pub fn build(container: Container, n: usize) -> Filled {
+        let keys = 0..n as u64;
+        match container {
+            Container::Vec => {
+                let mut v = Vec::new();
+                keys.for_each(|k| v.push(k));
+                Filled::Vec(v)
+            }
+            Container::VecDeque => {
+                let mut v = VecDeque::new();
+                keys.for_each(|k| v.push_back(k));
+                Filled::VecDeque(v)
+            }
+            Container::HashMap => {
+                let mut m = HashMap::new();
+                keys.for_each(|k| {
+                    m.insert(k, k * 2);
+                });
+                Filled::HashMap(m)
+            }
+            Container::BTreeMap => {
+                let mut m = BTreeMap::new();
+                keys.for_each(|k| {
+                    m.insert(k, k * 2);
+                });
+                Filled::BTreeMap(m)
+            }
+        }
+    }
+
+    /// How many of `keys` are present. Sequences have no index by value, so
+    /// `Vec` and `VecDeque` scan linearly — the cost the comparison is meant to show.
+    pub fn count_hits(&self, keys: &[u64]) -> usize {
+        keys.iter()
+            .filter(|key| match self {
+                Filled::Vec(v) => v.contains(key),
+                Filled::VecDeque(v) => v.contains(key),
+                Filled::HashMap(m) => m.contains_key(key),
+                Filled::BTreeMap(m) => m.contains_key(key),
+            })
+            .count()
+    }
+
+    /// Sum of every stored value.
+    pub fn sum(&self) -> u64 {
+        match self {
+            Filled::Vec(v) => v.iter().sum(),
+            Filled::VecDeque(v) => v.iter().sum(),
+            Filled::HashMap(m) => m.values().sum(),
+            Filled::BTreeMap(m) => m.values().sum(),
+        }
+    }
+}
+
+/// [`LOOKUPS`] pseudo-random keys in `0..2n`, so roughly half miss. Deterministic, so
+/// every container is asked the same questions.
+pub fn lookup_keys(n: usize) -> Vec<u64> {
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..LOOKUPS)
+        .map(|_| {
+            // xorshift64: fast, dependency-free, and good enough to scatter the keys.
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % (2 * n as u64)
+        })
+        .collect()
+}
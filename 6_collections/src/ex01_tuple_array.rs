@@ -1,4 +1,5 @@
 use crate::fns::get_tuple;
+use crate::matrix::Matrix;
 
 pub fn run() {
     let values = ("Sooyoung", "Kim", 28);
@@ -19,4 +20,9 @@ pub fn run() {
     let num_ver: [i32; 3] = [1, 2, 3];
     let twice_num_ver = num_ver.map(|x| x * 2);
     println!("twice_num_ver: {:?}", twice_num_ver);
+
+    // A fixed-size 2D array; `Matrix` takes the rows and prints them as a grid.
+    let grid: [[i32; 3]; 2] = [[1, 2, 3], [4, 5, 6]];
+    let grid = Matrix::from_rows(grid.map(Vec::from).to_vec()).expect("rows have equal length");
+    print!("grid:\n{grid}");
 }
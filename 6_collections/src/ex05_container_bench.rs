@@ -0,0 +1,149 @@
+//! Topic 5 — Choosing a collection: Criterion benchmarks of `Vec`, `VecDeque`, `HashMap`, and `BTreeMap`.
+//!
+//! Run via: `cargo run --release --bin ex05_container_bench [-- --quick]`
+//!
+//! Criterion measures every (operation, container, size) combination and saves
+//! its statistics under `target/criterion/`; afterwards this demo reads the mean
+//! of each run back from `estimates.json` and prints one comparison table.
+
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use criterion::{BenchmarkId, Criterion};
+
+use crate::containers::{Container, Filled, Operation, SIZES, lookup_keys};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+pub fn run() {
+    let quick = env::args().any(|arg| arg == "--quick");
+    // Criterion's minimum is 10 samples; `--quick` trades precision for a shorter run.
+    let (warm_up, measure, samples) = if quick {
+        (Duration::from_millis(100), Duration::from_millis(300), 10)
+    } else {
+        (Duration::from_millis(500), Duration::from_secs(2), 20)
+    };
+    let mut criterion = Criterion::default()
+        .warm_up_time(warm_up)
+        .measurement_time(measure)
+        .sample_size(samples);
+
+    section("Criterion runs");
+    for operation in Operation::ALL {
+        let mut group = criterion.benchmark_group(operation.to_string());
+        for container in Container::ALL {
+            for n in SIZES {
+                let id = BenchmarkId::new(container.to_string(), n);
+                match operation {
+                    Operation::Insert => {
+                        group.bench_function(id, |b| {
+                            b.iter(|| Filled::build(container, black_box(n)))
+                        });
+                    }
+                    Operation::Lookup => {
+                        let filled = Filled::build(container, n);
+                        let keys = lookup_keys(n);
+                        group
+                            .bench_function(id, |b| b.iter(|| filled.count_hits(black_box(&keys))));
+                    }
+                    Operation::Iterate => {
+                        let filled = Filled::build(container, n);
+                        group.bench_function(id, |b| b.iter(|| black_box(&filled).sum()));
+                    }
+                }
+            }
+        }
+        group.finish();
+    }
+
+    section("Summary (mean time per run)");
+    print_summary();
+    println!();
+    println!("lookup = 100 searches, about half for missing keys; Vec/VecDeque scan linearly.");
+    println!("Full reports: {}", criterion_home().display());
+}
+
+fn print_summary() {
+    print!("{:<8} {:>7}", "op", "n");
+    for container in Container::ALL {
+        print!(" {container:>11}");
+    }
+    println!("  fastest");
+
+    for operation in Operation::ALL {
+        for n in SIZES {
+            print!("{operation:<8} {n:>7}");
+            let means: Vec<Option<f64>> = Container::ALL
+                .iter()
+                .map(|&container| mean_nanos(operation, container, n))
+                .collect();
+            for mean in &means {
+                match mean {
+                    Some(ns) => print!(" {:>11}", format_nanos(*ns)),
+                    None => print!(" {:>11}", "-"),
+                }
+            }
+            let fastest = Container::ALL
+                .iter()
+                .zip(&means)
+                .filter_map(|(container, mean)| mean.map(|ns| (container, ns)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match fastest {
+                Some((container, _)) => println!("  {container}"),
+                None => println!(),
+            }
+        }
+    }
+}
+
+// Where Criterion writes results: the same lookup order Criterion itself uses
+// (`CRITERION_HOME`, then `$CARGO_TARGET_DIR/criterion`, then the target directory
+// reported by `cargo metadata` — the workspace's `target/` at the repo root).
+fn criterion_home() -> PathBuf {
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(metadata_target_dir)
+        .unwrap_or_else(|| PathBuf::from("target"));
+    target.join("criterion")
+}
+
+fn metadata_target_dir() -> Option<PathBuf> {
+    let output = Command::new(env::var_os("CARGO")?)
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    metadata["target_directory"].as_str().map(PathBuf::from)
+}
+
+// Mean from `<group>/<function>/<size>/new/estimates.json`, in nanoseconds.
+fn mean_nanos(operation: Operation, container: Container, n: usize) -> Option<f64> {
+    let path = criterion_home()
+        .join(operation.to_string())
+        .join(container.to_string())
+        .join(n.to_string())
+        .join("new/estimates.json");
+    let raw = fs::read_to_string(path).ok()?;
+    let estimates: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    estimates["mean"]["point_estimate"].as_f64()
+}
+
+fn format_nanos(ns: f64) -> String {
+    if ns < 1_000.0 {
+        format!("{ns:.0} ns")
+    } else if ns < 1_000_000.0 {
+        format!("{:.1} µs", ns / 1_000.0)
+    } else {
+        format!("{:.2} ms", ns / 1_000_000.0)
+    }
+}
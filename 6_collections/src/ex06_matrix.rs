@@ -0,0 +1,46 @@
+//! Topic 6 — `Matrix<T>`: a `Vec`-backed 2D collection with `+`, `*`, transpose, and Display.
+//!
+//! Run via: `cargo run --bin ex06_matrix`
+
+use crate::matrix::Matrix;
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+pub fn run() {
+    section("Building");
+    let a = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).expect("rows have equal length");
+    let b = Matrix::from_vec(3, 2, vec![7, 8, 9, 10, 11, 12]).expect("3 * 2 elements");
+    println!("a is {}x{}:\n{a}", a.rows(), a.cols());
+    println!("b is {}x{}:\n{b}", b.rows(), b.cols());
+    if let Err(e) = Matrix::from_rows(vec![vec![1, 2], vec![3]]) {
+        println!("ragged rows: {e}");
+    }
+
+    section("Transpose and indexing");
+    let t = a.transpose();
+    println!("aᵀ:\n{t}");
+    println!("a[(1, 2)] = {}, aᵀ[(2, 1)] = {}", a[(1, 2)], t[(2, 1)]);
+
+    section("Operators");
+    println!("a + a:\n{}", &a + &a);
+    println!("a * b:\n{}", &a * &b);
+    println!("a * 10:\n{}", &a * 10);
+    let i: Matrix<i32> = Matrix::identity(3);
+    println!("a * I == a: {}", &a * &i == a);
+    match a.checked_mul(&a) {
+        Ok(m) => println!("{m}"),
+        Err(e) => println!("a * a: {e}"),
+    }
+
+    section("Floats with a precision flag");
+    let angle = std::f64::consts::FRAC_PI_6;
+    let rotation = Matrix::from_rows(vec![
+        vec![angle.cos(), -angle.sin()],
+        vec![angle.sin(), angle.cos()],
+    ])
+    .expect("rows have equal length");
+    let point = Matrix::from_vec(2, 1, vec![1.0, 0.0]).expect("2 * 1 elements");
+    println!("rotate (1, 0) by 30°:\n{:.3}", &rotation * &point);
+}
@@ -4,4 +4,9 @@ pub mod ex01_tuple_array;
 pub mod ex02_vec;
 pub mod ex03_hashmap;
 pub mod ex04_iterators;
+pub mod ex05_container_bench;
+pub mod ex06_matrix;
+
+pub mod containers;
 pub mod fns;
+pub mod matrix;
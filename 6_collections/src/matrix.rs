@@ -0,0 +1,401 @@
+//! `Matrix<T>`: a dense, row-major matrix whose size is chosen at runtime.
+//!
+//! The elements live in one `Vec<T>` of length `rows * cols`; element `(r, c)` is
+//! at `r * cols + c`. `+` and `*` panic on mismatched shapes the way slice indexing
+//! panics out of bounds; [`Matrix::checked_add`] and [`Matrix::checked_mul`] return
+//! a [`MatrixError`] instead.
+
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// Why a matrix could not be built or combined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    /// `from_vec` got a buffer whose length is not `rows * cols`.
+    WrongLength { expected: usize, found: usize },
+    /// `from_rows` got rows of different lengths.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Operand shapes do not fit the operation.
+    ShapeMismatch {
+        op: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::WrongLength { expected, found } => {
+                write!(f, "expected {expected} elements, found {found}")
+            }
+            MatrixError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} columns, expected {expected}"),
+            MatrixError::ShapeMismatch { op, left, right } => write!(
+                f,
+                "cannot {op} a {}x{} matrix and a {}x{} matrix",
+                left.0, left.1, right.0, right.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Wrap a row-major buffer; `data.len()` must be `rows * cols`.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Matrix<T>, MatrixError> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::WrongLength {
+                expected: rows * cols,
+                found: data.len(),
+            });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    /// Build from nested rows, e.g. `vec![vec![1, 2], vec![3, 4]]`. Every row must be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Matrix<T>, MatrixError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        let row_count = rows.len();
+        let mut data = Vec::with_capacity(row_count * cols);
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != cols {
+                return Err(MatrixError::Ragged {
+                    row: index,
+                    expected: cols,
+                    found: row.len(),
+                });
+            }
+            data.extend(row);
+        }
+        Ok(Matrix {
+            rows: row_count,
+            cols,
+            data,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    /// Row `r` as a slice. Panics if `r` is out of range.
+    pub fn row(&self, r: usize) -> &[T] {
+        assert!(r < self.rows, "row {r} out of range for {} rows", self.rows);
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// Iterate over the rows as slices, top to bottom.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` keeps `chunks` happy for 0-column matrices (which have no data anyway).
+        self.data.chunks(self.cols.max(1))
+    }
+
+    /// Apply `f` to every element, keeping the shape.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Matrix<U> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// `rows x cols` matrix with every element set to `value`.
+    pub fn filled(rows: usize, cols: usize, value: T) -> Matrix<T> {
+        Matrix {
+            rows,
+            cols,
+            data: vec![value; rows * cols],
+        }
+    }
+
+    /// Rows become columns: element `(r, c)` moves to `(c, r)`.
+    pub fn transpose(&self) -> Matrix<T> {
+        let data = (0..self.cols)
+            .flat_map(|c| (0..self.rows).map(move |r| (r, c)))
+            .map(|(r, c)| self[(r, c)].clone())
+            .collect();
+        Matrix {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+}
+
+impl<T: Clone + Default> Matrix<T> {
+    /// `rows x cols` matrix of `T::default()` (zero for the numeric types).
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::filled(rows, cols, T::default())
+    }
+}
+
+impl<T: Clone + Default + From<u8>> Matrix<T> {
+    /// `n x n` identity matrix.
+    pub fn identity(n: usize) -> Matrix<T> {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::from(1);
+        }
+        m
+    }
+}
+
+impl<T: Clone + Add<Output = T>> Matrix<T> {
+    /// Element-wise sum, or [`MatrixError::ShapeMismatch`] if the shapes differ.
+    pub fn checked_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        if self.shape() != other.shape() {
+            return Err(MatrixError::ShapeMismatch {
+                op: "add",
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(a, b)| a.clone() + b.clone())
+            .collect();
+        Ok(Matrix { data, ..*self })
+    }
+}
+
+impl<T: Clone + Default + Add<Output = T> + Mul<Output = T>> Matrix<T> {
+    /// Matrix product, or [`MatrixError::ShapeMismatch`] unless `self.cols() == other.rows()`.
+    pub fn checked_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::ShapeMismatch {
+                op: "multiply",
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+        let mut out = Matrix::zeros(self.rows, other.cols);
+        for r in 0..self.rows {
+            for c in 0..other.cols {
+                out[(r, c)] = (0..self.cols).fold(T::default(), |acc, k| {
+                    acc + self[(r, k)].clone() * other[(k, c)].clone()
+                });
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    /// `m[(row, col)]`; panics when out of range.
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of range for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of range for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// `&a + &b`: element-wise sum. Panics if the shapes differ.
+impl<T: Clone + Add<Output = T>> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.checked_add(rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: Clone + Add<Output = T>> Add for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: Matrix<T>) -> Matrix<T> {
+        &self + &rhs
+    }
+}
+
+/// `&a * &b`: matrix product. Panics unless `a.cols() == b.rows()`.
+impl<T: Clone + Default + Add<Output = T> + Mul<Output = T>> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.checked_mul(rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: Clone + Default + Add<Output = T> + Mul<Output = T>> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        &self * &rhs
+    }
+}
+
+/// `&m * k`: multiply every element by the scalar `k`.
+impl<T: Clone + Mul<Output = T>> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, k: T) -> Matrix<T> {
+        self.map(|x| x.clone() * k.clone())
+    }
+}
+
+/// One row per line, columns right-aligned to the widest element.
+///
+/// A precision flag is passed on to every element: `format!("{m:.2}")`.
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self
+            .data
+            .iter()
+            .map(|x| match f.precision() {
+                Some(p) => format!("{x:.p$}"),
+                None => x.to_string(),
+            })
+            .collect();
+        let width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+        for r in 0..self.rows {
+            let row = &cells[r * self.cols..(r + 1) * self.cols];
+            let row: Vec<String> = row.iter().map(|c| format!("{c:>width$}")).collect();
+            let (open, close) = match (r, self.rows) {
+                (_, 1) => ("[", "]"),
+                (0, _) => ("⎡", "⎤"),
+                (r, n) if r == n - 1 => ("⎣", "⎦"),
+                _ => ("⎢", "⎥"),
+            };
+            writeln!(f, "{open} {} {close}", row.join("  "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(rows: Vec<Vec<i32>>) -> Matrix<i32> {
+        Matrix::from_rows(rows).unwrap()
+    }
+
+    #[test]
+    fn constructors_check_their_input() {
+        assert_eq!(
+            Matrix::from_vec(2, 2, vec![1, 2, 3]),
+            Err(MatrixError::WrongLength {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Matrix::from_rows(vec![vec![1, 2], vec![3]]),
+            Err(MatrixError::Ragged {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        let a = Matrix::from_vec(2, 3, (1..=6).collect()).unwrap();
+        assert_eq!(a, m(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+        assert_eq!(a.shape(), (2, 3));
+        assert_eq!(a.row(1), [4, 5, 6]);
+        assert_eq!(a.get(1, 2), Some(&6));
+        assert_eq!(a.get(2, 0), None);
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let a = m(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let t = a.transpose();
+        assert_eq!(t, m(vec![vec![1, 4], vec![2, 5], vec![3, 6]]));
+        assert_eq!(t.transpose(), a);
+    }
+
+    #[test]
+    fn add_is_element_wise() {
+        let a = m(vec![vec![1, 2], vec![3, 4]]);
+        let b = m(vec![vec![10, 20], vec![30, 40]]);
+        assert_eq!(&a + &b, m(vec![vec![11, 22], vec![33, 44]]));
+        assert!(matches!(
+            a.checked_add(&m(vec![vec![1, 2]])),
+            Err(MatrixError::ShapeMismatch { op: "add", .. })
+        ));
+    }
+
+    #[test]
+    fn mul_is_the_matrix_product() {
+        let a = m(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = m(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!(&a * &b, m(vec![vec![58, 64], vec![139, 154]]));
+        assert_eq!(&a * &Matrix::identity(3), a);
+        assert_eq!(&a * 2, m(vec![vec![2, 4, 6], vec![8, 10, 12]]));
+        assert_eq!(
+            a.checked_mul(&a),
+            Err(MatrixError::ShapeMismatch {
+                op: "multiply",
+                left: (2, 3),
+                right: (2, 3)
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add a 2x2 matrix and a 1x2 matrix")]
+    fn add_operator_panics_on_shape_mismatch() {
+        let _ = m(vec![vec![1, 2], vec![3, 4]]) + m(vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn display_aligns_columns_and_honours_precision() {
+        let a = m(vec![vec![1, -20], vec![300, 4]]);
+        assert_eq!(a.to_string(), "⎡   1  -20 ⎤\n⎣ 300    4 ⎦\n");
+        let f = Matrix::from_rows(vec![vec![0.5, 2.0]]).unwrap();
+        assert_eq!(format!("{f:.2}"), "[ 0.50  2.00 ]\n");
+    }
+}
@@ -0,0 +1,19 @@
+//! Extension traits for `Option` and `Result`, shared by the chapter 7 demos.
+//!
+//! # How this crate is organized
+//!
+//! - [`option`] — [`OptionExt`] (`tap_some`, `tap_none`, `zip3`) and
+//!   [`NonEmptyExt`] (`non_empty` for optional strings).
+//! - [`result`] — [`ResultExt`] (`tap_err`, `ok_or_log`).
+//!
+//! # How callers import this crate
+//!
+//! `7_optionals/Cargo.toml` lists `ext = { path = "ext" }`; bring the traits into
+//! scope with `use ext::{OptionExt, ResultExt};` and the methods appear on every
+//! `Option` / `Result`.
+
+pub mod option;
+pub mod result;
+
+pub use option::{NonEmptyExt, OptionExt};
+pub use result::ResultExt;
@@ -0,0 +1,88 @@
+//! Combinators on `Option<T>`: side effects that pass the value through, and a three-way zip.
+
+/// Extra methods on every `Option<T>`.
+pub trait OptionExt<T>: Sized {
+    /// Run `f` on the value when it is `Some`, then return `self` unchanged.
+    fn tap_some(self, f: impl FnOnce(&T)) -> Self;
+
+    /// Run `f` when the option is `None`, then return `self` unchanged.
+    ///
+    /// Handy for logging a missing value in the middle of a chain.
+    fn tap_none(self, f: impl FnOnce()) -> Self;
+
+    /// `Some((a, b, c))` when all three are `Some`; like [`Option::zip`] for three values.
+    fn zip3<U, V>(self, b: Option<U>, c: Option<V>) -> Option<(T, U, V)>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn tap_some(self, f: impl FnOnce(&T)) -> Self {
+        if let Some(value) = &self {
+            f(value);
+        }
+        self
+    }
+
+    fn tap_none(self, f: impl FnOnce()) -> Self {
+        if self.is_none() {
+            f();
+        }
+        self
+    }
+
+    fn zip3<U, V>(self, b: Option<U>, c: Option<V>) -> Option<(T, U, V)> {
+        Some((self?, b?, c?))
+    }
+}
+
+/// `non_empty` for optional strings: treat `Some("")` and whitespace-only text as missing.
+pub trait NonEmptyExt<'a> {
+    /// `None` when the text is absent or blank; otherwise the text with surrounding whitespace trimmed.
+    fn non_empty(self) -> Option<&'a str>;
+}
+
+impl<'a> NonEmptyExt<'a> for Option<&'a str> {
+    fn non_empty(self) -> Option<&'a str> {
+        self.map(str::trim).filter(|s| !s.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn tap_some_sees_the_value_and_passes_it_through() {
+        let seen = Cell::new(0);
+        assert_eq!(Some(7).tap_some(|v| seen.set(*v)), Some(7));
+        assert_eq!(seen.get(), 7);
+
+        assert_eq!(None::<i32>.tap_some(|_| seen.set(-1)), None);
+        assert_eq!(seen.get(), 7);
+    }
+
+    #[test]
+    fn tap_none_only_runs_for_none() {
+        let calls = Cell::new(0);
+        assert_eq!(Some(1).tap_none(|| calls.set(calls.get() + 1)), Some(1));
+        assert_eq!(calls.get(), 0);
+        assert_eq!(None::<i32>.tap_none(|| calls.set(calls.get() + 1)), None);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn zip3_needs_all_three() {
+        assert_eq!(Some(1).zip3(Some("b"), Some('c')), Some((1, "b", 'c')));
+        assert_eq!(Some(1).zip3(None::<&str>, Some('c')), None);
+        assert_eq!(None::<i32>.zip3(Some("b"), Some('c')), None);
+        assert_eq!(Some(1).zip3(Some("b"), None::<char>), None);
+    }
+
+    #[test]
+    fn non_empty_drops_blank_text() {
+        assert_eq!(Some("  BMW ").non_empty(), Some("BMW"));
+        assert_eq!(Some("").non_empty(), None);
+        assert_eq!(Some(" \t").non_empty(), None);
+        assert_eq!(None.non_empty(), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Combinators on `Result<T, E>`: inspect an error in place, or log it and fall back to `Option`.
+//!
+//! Logging goes through `tracing`, so the binary decides where (and whether) it lands.
+
+use std::fmt::Display;
+
+/// Extra methods on every `Result<T, E>`.
+pub trait ResultExt<T, E>: Sized {
+    /// Run `f` on the error when it is `Err`, then return `self` unchanged.
+    fn tap_err(self, f: impl FnOnce(&E)) -> Self;
+
+    /// Like [`Result::ok`], but log `{context}: {error}` as a `tracing` warning
+    /// instead of silently dropping the error.
+    fn ok_or_log(self, context: &str) -> Option<T>
+    where
+        E: Display;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    fn tap_err(self, f: impl FnOnce(&E)) -> Self {
+        if let Err(err) = &self {
+            f(err);
+        }
+        self
+    }
+
+    fn ok_or_log(self, context: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        self.tap_err(|err| tracing::warn!("{context}: {err}")).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn tap_err_sees_the_error_and_passes_it_through() {
+        let seen = RefCell::new(String::new());
+        let parsed = "x1"
+            .parse::<i32>()
+            .tap_err(|e| *seen.borrow_mut() = e.to_string());
+        assert!(parsed.is_err());
+        assert_eq!(*seen.borrow(), "invalid digit found in string");
+
+        seen.borrow_mut().clear();
+        assert_eq!(
+            "12".parse::<i32>().tap_err(|_| seen.borrow_mut().push('!')),
+            Ok(12)
+        );
+        assert!(seen.borrow().is_empty());
+    }
+
+    #[test]
+    fn ok_or_log_keeps_ok_values_and_drops_errors() {
+        assert_eq!("42".parse::<i32>().ok_or_log("parsing age"), Some(42));
+        assert_eq!("forty".parse::<i32>().ok_or_log("parsing age"), None);
+    }
+}
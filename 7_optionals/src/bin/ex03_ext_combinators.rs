@@ -0,0 +1,3 @@
+fn main() {
+    optionals::ex03_ext_combinators::run();
+}
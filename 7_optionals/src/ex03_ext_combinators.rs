@@ -0,0 +1,74 @@
+//! Topic 3 — Extension traits: `tap_none`, `ok_or_log`, `zip3` and friends from the `ext` crate.
+//!
+//! Run via: `cargo run --bin ex03_ext_combinators`
+
+use ext::{NonEmptyExt, OptionExt, ResultExt};
+
+use crate::guest::Guest;
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+// `boarding_summary` from `guest.rs`, rewritten with `zip3` and a log line for what is missing.
+fn boarding_card(guest: &Guest) -> Option<String> {
+    let seat = guest
+        .seat
+        .as_deref()
+        .tap_none(|| println!("  {} has no seat yet", guest.name));
+    let meal = guest
+        .meal
+        .as_deref()
+        .tap_none(|| println!("  {} has not picked a meal", guest.name));
+    Some(guest.name.as_str())
+        .zip3(seat, meal)
+        .map(|(name, seat, meal)| format!("{name} @ {seat} ({meal})"))
+}
+
+pub fn run() {
+    section("tap_some / tap_none");
+    let found = ["alpha", "beta"]
+        .iter()
+        .find(|s| s.starts_with('b'))
+        .tap_some(|s| println!("found {s}"));
+    let missing = ["alpha", "beta"]
+        .iter()
+        .find(|s| s.starts_with('z'))
+        .tap_none(|| println!("nothing starts with 'z'"));
+    println!("found = {found:?}, missing = {missing:?}");
+
+    // `ok_or_log` warns through `tracing`, and this chapter installs no
+    // subscriber, so `tap_err` prints what it drops.
+    section("ok_or_log");
+    let ages: Vec<u32> = ["20", "thirty", "41", "-3"]
+        .iter()
+        .filter_map(|raw| {
+            raw.parse::<u32>()
+                .tap_err(|err| println!("  dropping {raw:?}: {err}"))
+                .ok_or_log(&format!("age {raw:?}"))
+        })
+        .collect();
+    println!("parsed ages: {ages:?}");
+
+    section("zip3");
+    let guests = [
+        Guest {
+            name: "Alex".into(),
+            seat: Some("12A".into()),
+            meal: Some("vegetarian".into()),
+        },
+        Guest {
+            name: "Sam".into(),
+            seat: None,
+            meal: Some("fish".into()),
+        },
+    ];
+    for guest in &guests {
+        println!("boarding_card({}) = {:?}", guest.name, boarding_card(guest));
+    }
+
+    section("non_empty");
+    for raw in [Some("  12B "), Some(""), Some("   "), None] {
+        println!("{raw:?}.non_empty() = {:?}", raw.non_empty());
+    }
+}
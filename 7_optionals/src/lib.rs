@@ -2,4 +2,5 @@
 
 pub mod ex01_option_ops;
 pub mod ex02_guest_composition;
+pub mod ex03_ext_combinators;
 pub mod guest;
@@ -0,0 +1,133 @@
+//! Extension traits that attach context while converting into the crate's errors.
+//!
+//! They play the role `anyhow::Context` plays elsewhere, but produce typed
+//! variants callers can match on instead of an opaque string.
+
+use std::error::Error;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{AppError, ValidationError};
+
+/// Turn an `io::Error` into [`AppError::Io`] naming what was being done to which path.
+pub trait IoContext<T> {
+    fn io_context(self, action: &'static str, path: impl AsRef<Path>) -> Result<T, AppError>;
+}
+
+impl<T> IoContext<T> for Result<T, io::Error> {
+    fn io_context(self, action: &'static str, path: impl AsRef<Path>) -> Result<T, AppError> {
+        self.map_err(|source| AppError::Io {
+            action,
+            path: path.as_ref().to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Turn any (de)serialization error into [`AppError::Data`] for `path`.
+pub trait DataContext<T> {
+    fn data_context(self, action: &'static str, path: impl AsRef<Path>) -> Result<T, AppError>;
+}
+
+impl<T, E: Error + Send + Sync + 'static> DataContext<T> for Result<T, E> {
+    fn data_context(self, action: &'static str, path: impl AsRef<Path>) -> Result<T, AppError> {
+        self.map_err(|source| AppError::Data {
+            action,
+            path: path.as_ref().to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+}
+
+/// Turn a missing value into [`ValidationError::Missing`].
+pub trait Required<T> {
+    fn required(self, field: &'static str) -> Result<T, ValidationError>;
+}
+
+impl<T> Required<T> for Option<T> {
+    fn required(self, field: &'static str) -> Result<T, ValidationError> {
+        self.ok_or(ValidationError::Missing { field })
+    }
+}
+
+/// Parse a user-typed `raw` value for `field`; blank input is `Missing`, junk is `Invalid`.
+pub fn parse_field<T: FromStr>(
+    field: &'static str,
+    raw: &str,
+    expected: &'static str,
+) -> Result<T, ValidationError> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(ValidationError::Missing { field });
+    }
+    trimmed.parse().map_err(|_| ValidationError::Invalid {
+        field,
+        expected,
+        value: raw.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report;
+
+    #[test]
+    fn io_context_names_the_action_and_path() {
+        let missing = std::fs::read_to_string("/definitely/not/here.txt");
+        let err = missing
+            .io_context("reading", "/definitely/not/here.txt")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::Io {
+                action: "reading",
+                ..
+            }
+        ));
+        assert!(
+            err.to_string()
+                .starts_with("reading /definitely/not/here.txt")
+        );
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn data_context_keeps_the_parser_error_as_source() {
+        let parsed: Result<Vec<u32>, _> = serde_json::from_str("[1, 2,");
+        let err = parsed.data_context("parsing", "numbers.json").unwrap_err();
+        assert_eq!(err.to_string(), "parsing numbers.json");
+        assert!(report(&err).starts_with("parsing numbers.json: EOF"));
+    }
+
+    #[test]
+    fn required_and_parse_field_produce_validation_errors() {
+        assert_eq!(
+            None::<u8>.required("name"),
+            Err(ValidationError::Missing { field: "name" })
+        );
+        assert_eq!(
+            parse_field::<usize>("quantity", " 12 ", "a whole number"),
+            Ok(12)
+        );
+        assert_eq!(
+            parse_field::<usize>("quantity", "", "a whole number"),
+            Err(ValidationError::Missing { field: "quantity" })
+        );
+        assert_eq!(
+            parse_field::<usize>("quantity", "-3", "a whole number")
+                .unwrap_err()
+                .to_string(),
+            r#"quantity must be a whole number, got "-3""#
+        );
+    }
+
+    #[test]
+    fn validation_errors_convert_with_question_mark() {
+        fn check(raw: &str) -> crate::Result<f64> {
+            Ok(parse_field("price", raw, "a number")?)
+        }
+        assert!(matches!(check("abc"), Err(AppError::Validation(_))));
+    }
+}
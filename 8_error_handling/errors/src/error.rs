@@ -0,0 +1,57 @@
+//! The error hierarchy: [`AppError`] at the top, [`ValidationError`] for user input.
+
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Everything an app in this repo can fail with.
+///
+/// Variants that wrap a foreign error keep it as their `source()`, so
+/// [`report`](crate::report()) can print the whole chain.
+#[derive(Debug, Error)]
+pub enum AppError {
+    /// A filesystem operation failed; `action` reads like "opening" or "writing to".
+    #[error("{action} {}", path.display())]
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A file's contents could not be (de)serialized; `action` reads like "parsing".
+    #[error("{action} {}", path.display())]
+    Data {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: Box<dyn Error + Send + Sync>,
+    },
+
+    /// User input was rejected.
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+
+    /// The command line was malformed; the message is the usage text.
+    #[error("{0}")]
+    Usage(String),
+}
+
+/// Input that failed a check. Cheap to clone and compare, so it is easy to test.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("{field} is required")]
+    Missing { field: &'static str },
+
+    #[error("{field} must be {expected}, got {value:?}")]
+    Invalid {
+        field: &'static str,
+        expected: &'static str,
+        value: String,
+    },
+
+    #[error("division by zero")]
+    DivisionByZero,
+}
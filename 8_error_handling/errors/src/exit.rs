@@ -0,0 +1,171 @@
+//! Process exit codes shared by every binary in the repo.
+//!
+//! | Code | [`Exit`]   | Meaning |
+//! |------|------------|---------|
+//! | 0    | `Success`  | Finished normally. |
+//! | 1    | `Failure`  | Anything not covered below (a bug, a failed child process, a benchmark regression). |
+//! | 2    | `Usage`    | Bad command line or rejected input. Clap exits with 2 on its own usage errors too. |
+//! | 3    | `Io`       | A file or directory could not be read or written. |
+//! | 4    | `Network`  | An HTTP request or a socket failed. |
+//! | 5    | `Data`     | A file, response, or message could not be parsed. |
+//!
+//! Binaries print [`report()`](crate::report()) and exit with [`exit_for`] (or
+//! [`exit_for_with`] when the crate has its own error type):
+//!
+//! ```no_run
+//! use std::process::ExitCode;
+//!
+//! fn run() -> errors::Result<()> {
+//!     Err(errors::ValidationError::Missing { field: "name" }.into())
+//! }
+//!
+//! fn main() -> ExitCode {
+//!     match run() {
+//!         Ok(()) => ExitCode::SUCCESS,
+//!         Err(e) => {
+//!             eprintln!("error: {}", errors::report(&e));
+//!             errors::exit_for(&e).into()
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::error::Error;
+use std::io;
+use std::process::ExitCode;
+
+use crate::error::{AppError, ValidationError};
+
+/// Why a process stopped; see the [module docs](self) for the numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Success,
+    Failure,
+    Usage,
+    Io,
+    Network,
+    Data,
+}
+
+impl Exit {
+    /// The numeric process exit code.
+    pub fn code(self) -> u8 {
+        match self {
+            Exit::Success => 0,
+            Exit::Failure => 1,
+            Exit::Usage => 2,
+            Exit::Io => 3,
+            Exit::Network => 4,
+            Exit::Data => 5,
+        }
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> ExitCode {
+        ExitCode::from(exit.code())
+    }
+}
+
+/// An error that knows which [`Exit`] it should end the process with.
+pub trait ExitStatus {
+    fn exit(&self) -> Exit;
+}
+
+impl ExitStatus for AppError {
+    fn exit(&self) -> Exit {
+        match self {
+            AppError::Io { .. } => Exit::Io,
+            AppError::Data { .. } => Exit::Data,
+            AppError::Validation(_) | AppError::Usage(_) => Exit::Usage,
+        }
+    }
+}
+
+impl ExitStatus for ValidationError {
+    fn exit(&self) -> Exit {
+        Exit::Usage
+    }
+}
+
+impl ExitStatus for io::Error {
+    fn exit(&self) -> Exit {
+        Exit::Io
+    }
+}
+
+/// The [`Exit`] for `err`: the first error in its `source()` chain that is one
+/// of this crate's types or an [`io::Error`] decides, otherwise [`Exit::Failure`].
+pub fn exit_for(err: &(dyn Error + 'static)) -> Exit {
+    exit_for_with(err, |_| None)
+}
+
+/// Like [`exit_for`], asking `classify` first about every error in the chain, so a
+/// crate can map its own error types (`|e| e.downcast_ref::<MyError>().map(MyError::exit)`).
+pub fn exit_for_with(
+    err: &(dyn Error + 'static),
+    classify: impl Fn(&(dyn Error + 'static)) -> Option<Exit>,
+) -> Exit {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        let known = classify(err)
+            .or_else(|| err.downcast_ref::<AppError>().map(ExitStatus::exit))
+            .or_else(|| err.downcast_ref::<ValidationError>().map(ExitStatus::exit))
+            .or_else(|| err.downcast_ref::<io::Error>().map(ExitStatus::exit));
+        if let Some(exit) = known {
+            return exit;
+        }
+        next = err.source();
+    }
+    Exit::Failure
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Wrapper(io::Error);
+
+    impl fmt::Display for Wrapper {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("wrapper")
+        }
+    }
+
+    impl Error for Wrapper {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn app_errors_map_by_variant() {
+        let io = AppError::Io {
+            action: "opening",
+            path: "notes.txt".into(),
+            source: io::Error::other("denied"),
+        };
+        assert_eq!(exit_for(&io), Exit::Io);
+        let invalid = AppError::from(ValidationError::Missing { field: "name" });
+        assert_eq!(exit_for(&invalid), Exit::Usage);
+        assert_eq!(Exit::Data.code(), 5);
+    }
+
+    #[test]
+    fn the_chain_is_searched_and_unknown_errors_are_failures() {
+        let wrapped = Wrapper(io::Error::other("disk full"));
+        assert_eq!(exit_for(&wrapped), Exit::Io);
+        let unknown = fmt::Error;
+        assert_eq!(exit_for(&unknown), Exit::Failure);
+    }
+
+    #[test]
+    fn classify_wins_over_the_built_in_types() {
+        let wrapped = Wrapper(io::Error::other("socket closed"));
+        let exit = exit_for_with(&wrapped, |e| e.is::<Wrapper>().then_some(Exit::Network));
+        assert_eq!(exit, Exit::Network);
+    }
+}
@@ -0,0 +1,34 @@
+//! Shared error types for the chapter 8 demos and the small project crates.
+//!
+//! # How this crate is organized
+//!
+//! - [`error`] — [`AppError`], the top-level error an app returns, and
+//!   [`ValidationError`] for bad user input. Both derive `thiserror::Error`.
+//! - [`context`] — extension traits ([`IoContext`], [`DataContext`], [`Required`])
+//!   that turn foreign errors and `None`s into [`AppError`] / [`ValidationError`]
+//!   with the file path or field name attached, plus [`parse_field`].
+//! - [`report`](mod@report) — [`report()`] renders an error and its `source()` chain on one line.
+//! - [`exit`] — [`Exit`], the repo-wide process exit codes, and [`exit_for`], which
+//!   picks one for any error chain.
+//!
+//! # The strategy, end to end
+//!
+//! Library code returns [`Result<T>`](Result) (`Result<T, AppError>`), adding
+//! context at the point a foreign error enters (`.io_context("opening", path)?`).
+//! `main` matches on the result, prints [`report()`], and exits with
+//! [`exit_for`]'s code. Other library crates follow the same shape with their own
+//! `thiserror` enum (implementing [`ExitStatus`]); `anyhow` only appears in
+//! binaries and app-only crates such as `xtask` and `til`.
+
+pub mod context;
+pub mod error;
+pub mod exit;
+pub mod report;
+
+pub use context::{DataContext, IoContext, Required, parse_field};
+pub use error::{AppError, ValidationError};
+pub use exit::{Exit, ExitStatus, exit_for, exit_for_with};
+pub use report::report;
+
+/// `Result` with [`AppError`] as the default error type.
+pub type Result<T, E = AppError> = std::result::Result<T, E>;
@@ -0,0 +1,39 @@
+//! One-line rendering of an error together with its `source()` chain.
+
+use std::error::Error;
+
+/// `"outer: middle: root cause"` — each error's `Display`, outermost first.
+pub fn report(err: &(dyn Error + 'static)) -> String {
+    let mut out = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        out.push_str(": ");
+        out.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::error::{AppError, ValidationError};
+
+    #[test]
+    fn report_walks_the_source_chain() {
+        let err = AppError::Io {
+            action: "opening",
+            path: "notes.txt".into(),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"),
+        };
+        assert_eq!(report(&err), "opening notes.txt: permission denied");
+    }
+
+    #[test]
+    fn transparent_variants_report_the_inner_message_once() {
+        let err = AppError::from(ValidationError::DivisionByZero);
+        assert_eq!(report(&err), "division by zero");
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    error_handling::ex04_error_hierarchy::run();
+}
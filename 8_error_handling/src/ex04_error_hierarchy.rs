@@ -0,0 +1,60 @@
+//! Topic 4 — A typed error hierarchy: `thiserror` enums, context helpers, and `?` conversions from the `errors` crate.
+//!
+//! Run via: `cargo run --bin ex04_error_hierarchy`
+
+use std::fs;
+
+use errors::{AppError, IoContext, Required, ValidationError, parse_field, report};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+// Same job as `fns::divide_result`, but the error is a matchable enum instead of a `String`.
+fn divide(numerator: f64, denominator: f64) -> Result<f64, ValidationError> {
+    if denominator == 0.0 {
+        return Err(ValidationError::DivisionByZero);
+    }
+    Ok(numerator / denominator)
+}
+
+// `?` converts `ValidationError` into `AppError` through the `#[from]` attribute.
+fn average(raw_total: &str, raw_count: &str) -> errors::Result<f64> {
+    let total: f64 = parse_field("total", raw_total, "a number")?;
+    let count: f64 = parse_field("count", raw_count, "a number")?;
+    Ok(divide(total, count)?)
+}
+
+fn read_config(path: &str) -> errors::Result<String> {
+    fs::read_to_string(path).io_context("reading", path)
+}
+
+pub fn run() {
+    section("ValidationError");
+    println!("{:?}", divide(10.0, 4.0));
+    println!("{:?}", divide(10.0, 0.0));
+    println!("{:?}", None::<&str>.required("name"));
+
+    section("? converts into AppError");
+    for (total, count) in [("10", "4"), ("10", "0"), ("ten", "4"), ("10", " ")] {
+        match average(total, count) {
+            Ok(avg) => println!("average({total:?}, {count:?}) = {avg}"),
+            Err(e) => println!("average({total:?}, {count:?}) failed: {e}"),
+        }
+    }
+
+    section("Matching on variants");
+    match read_config("missing-config.toml") {
+        Ok(text) => println!("read {} bytes", text.len()),
+        Err(AppError::Io { source, path, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+            println!("{} not found, using defaults", path.display());
+        }
+        Err(e) => println!("unexpected: {e}"),
+    }
+
+    section("report() prints the source chain");
+    if let Err(e) = read_config("missing-config.toml") {
+        println!("Display only: {e}");
+        println!("report():     {}", report(&e));
+    }
+}
@@ -3,4 +3,5 @@
 pub mod ex01_divide;
 pub mod ex02_result_helpers;
 pub mod ex03_full_name;
+pub mod ex04_error_hierarchy;
 pub mod fns;
@@ -0,0 +1,3 @@
+fn main() {
+    lifetimes::ex04_tokenizer::run();
+}
@@ -0,0 +1,48 @@
+//! Topic 4 — Lifetimes in a real API: a zero-copy tokenizer whose tokens borrow from the input.
+//!
+//! Run via: `cargo run --bin ex04_tokenizer`
+
+use crate::tokenizer::{TokenIterExt, Tokenizer};
+
+fn section(title: &str) {
+    println!("\n=== {title} ===\n");
+}
+
+// Elision ties the returned slice to `text` (the only input reference), so no `String` is needed.
+fn longest_word(text: &str) -> Option<&str> {
+    Tokenizer::new(text).words().longest()
+}
+
+pub fn run() {
+    let article = String::from(
+        "Rust's borrow checker isn't magic. It tracks how long each reference lives! \
+         Lifetimes such as 'a name those spans. Does that help?",
+    );
+    let tok = Tokenizer::new(&article);
+
+    section("words");
+    let words: Vec<&str> = tok.words().collect();
+    println!("{} words: {:?}", words.len(), words);
+    println!("longest: {:?}", longest_word(&article));
+    for token in tok.words().spanned(&article).take(3) {
+        println!("{:>10} at bytes {:?}", token.text, token.span);
+    }
+
+    section("sentences");
+    for (i, sentence) in tok.sentences().enumerate() {
+        println!("{}. {sentence}", i + 1);
+    }
+
+    section("csv");
+    let csv =
+        "name,city,quote\nAlice,Seoul,\"Hello, world\"\nBob,\"New York\",\"He said \"\"hi\"\"\"\n";
+    for record in Tokenizer::new(csv).csv_records() {
+        let values: Vec<_> = record.map(|field| field.value()).collect();
+        println!("{values:?}");
+    }
+
+    // Tokens keep `article` borrowed: uncommenting the `drop` below fails to compile
+    // because `words` is still used afterwards.
+    // drop(article);
+    println!("\nfirst word still usable: {}", words[0]);
+}
@@ -3,4 +3,6 @@
 pub mod ex01_owned_static;
 pub mod ex02_random_name;
 pub mod ex03_person_elision;
+pub mod ex04_tokenizer;
 pub mod lifetime_fns;
+pub mod tokenizer;
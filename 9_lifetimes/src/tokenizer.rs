@@ -0,0 +1,333 @@
+//! Zero-copy tokenizer: every token is a `&'a str` slice of the caller's input buffer.
+//!
+//! Nothing is allocated per token. The `'a` on [`Tokenizer<'a>`] and on each
+//! iterator ties every returned slice to the input, so the compiler rejects
+//! code that drops the buffer while tokens are still in use, yet the tokens
+//! outlive the tokenizer and iterators that produced them.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::str::CharIndices;
+
+/// Entry point: wraps an input buffer and hands out token iterators over it.
+#[derive(Debug, Clone, Copy)]
+pub struct Tokenizer<'a> {
+    input: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Tokenizer<'a> {
+        Tokenizer { input }
+    }
+
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    /// Runs of letters and digits; an apostrophe between letters stays inside (`don't`).
+    pub fn words(&self) -> Words<'a> {
+        Words {
+            input: self.input,
+            chars: self.input.char_indices(),
+        }
+    }
+
+    /// Sentences ending in `.`, `!`, or `?` followed by whitespace or the end of input,
+    /// trimmed of surrounding whitespace. Trailing text without a terminator is the last sentence.
+    pub fn sentences(&self) -> Sentences<'a> {
+        Sentences { rest: self.input }
+    }
+
+    /// One [`CsvFields`] iterator per non-empty line.
+    pub fn csv_records(&self) -> impl Iterator<Item = CsvFields<'a>> + 'a {
+        self.input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(CsvFields::new)
+    }
+}
+
+/// Iterator returned by [`Tokenizer::words`].
+pub struct Words<'a> {
+    input: &'a str,
+    chars: CharIndices<'a>,
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let start = loop {
+            let (i, c) = self.chars.next()?;
+            if c.is_alphanumeric() {
+                break i;
+            }
+        };
+        let mut end = self.input.len();
+        while let Some((i, c)) = self.chars.clone().next() {
+            let keeps_going = c.is_alphanumeric()
+                || (c == '\''
+                    && self.input[i + 1..]
+                        .chars()
+                        .next()
+                        .is_some_and(char::is_alphanumeric));
+            if !keeps_going {
+                end = i;
+                break;
+            }
+            self.chars.next();
+        }
+        Some(&self.input[start..end])
+    }
+}
+
+/// Iterator returned by [`Tokenizer::sentences`].
+pub struct Sentences<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.rest = self.rest.trim_start();
+        if self.rest.is_empty() {
+            return None;
+        }
+        let mut chars = self.rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let ends_here = matches!(c, '.' | '!' | '?')
+                && chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+            if ends_here {
+                let (sentence, rest) = self.rest.split_at(i + c.len_utf8());
+                self.rest = rest;
+                return Some(sentence);
+            }
+        }
+        let sentence = self.rest.trim_end();
+        self.rest = "";
+        Some(sentence)
+    }
+}
+
+/// One CSV field, borrowed from the line it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvField<'a> {
+    raw: &'a str,
+    quoted: bool,
+}
+
+impl<'a> CsvField<'a> {
+    /// Text between the delimiters (without surrounding quotes); `""` escapes are left as is.
+    pub fn raw(&self) -> &'a str {
+        self.raw
+    }
+
+    /// The field's value: borrowed unless a `""` escape has to be collapsed to `"`.
+    pub fn value(&self) -> Cow<'a, str> {
+        if self.quoted && self.raw.contains("\"\"") {
+            Cow::Owned(self.raw.replace("\"\"", "\""))
+        } else {
+            Cow::Borrowed(self.raw)
+        }
+    }
+}
+
+/// Fields of one CSV line, split on commas outside double quotes.
+///
+/// Quoted fields may contain commas and `""` escapes but not newlines.
+pub struct CsvFields<'a> {
+    rest: Option<&'a str>,
+}
+
+impl<'a> CsvFields<'a> {
+    pub fn new(line: &'a str) -> CsvFields<'a> {
+        CsvFields {
+            rest: Some(line.strip_suffix('\r').unwrap_or(line)),
+        }
+    }
+}
+
+impl<'a> Iterator for CsvFields<'a> {
+    type Item = CsvField<'a>;
+
+    fn next(&mut self) -> Option<CsvField<'a>> {
+        let rest = self.rest?;
+        if let Some(body) = rest.strip_prefix('"') {
+            // Find the closing quote, skipping `""` pairs.
+            let mut i = 0;
+            let bytes = body.as_bytes();
+            while i < bytes.len() {
+                if bytes[i] == b'"' {
+                    if bytes.get(i + 1) == Some(&b'"') {
+                        i += 2;
+                        continue;
+                    }
+                    let after = &body[i + 1..];
+                    self.rest = after.find(',').map(|comma| &after[comma + 1..]);
+                    return Some(CsvField {
+                        raw: &body[..i],
+                        quoted: true,
+                    });
+                }
+                i += 1;
+            }
+            // Unterminated quote: the rest of the line is the field.
+            self.rest = None;
+            return Some(CsvField {
+                raw: body,
+                quoted: true,
+            });
+        }
+        let (field, tail) = match rest.split_once(',') {
+            Some((field, tail)) => (field, Some(tail)),
+            None => (rest, None),
+        };
+        self.rest = tail;
+        Some(CsvField {
+            raw: field,
+            quoted: false,
+        })
+    }
+}
+
+/// A token plus its byte range in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<'a> {
+    pub text: &'a str,
+    pub span: Range<usize>,
+}
+
+/// Adapters for any iterator of borrowed tokens.
+pub trait TokenIterExt<'a>: Iterator<Item = &'a str> + Sized {
+    /// The longest token (the first one on ties). The result borrows from the
+    /// input, not from the iterator, so it outlives the iteration.
+    fn longest(self) -> Option<&'a str> {
+        self.fold(None, |best: Option<&'a str>, token| match best {
+            Some(b) if b.len() >= token.len() => Some(b),
+            _ => Some(token),
+        })
+    }
+
+    /// Attach each token's byte range in `source`. Tokens must be slices of `source`
+    /// (as every tokenizer here produces); the range is recovered from the pointers.
+    fn spanned(self, source: &'a str) -> impl Iterator<Item = Spanned<'a>> {
+        let base = source.as_ptr() as usize;
+        self.map(move |text| {
+            let start = text.as_ptr() as usize - base;
+            debug_assert!(
+                start + text.len() <= source.len(),
+                "token is not a slice of source"
+            );
+            Spanned {
+                text,
+                span: start..start + text.len(),
+            }
+        })
+    }
+
+    /// Keep tokens equal to `word`, ignoring ASCII case.
+    fn matching(self, word: &'a str) -> impl Iterator<Item = &'a str> {
+        self.filter(move |token| token.eq_ignore_ascii_case(word))
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> TokenIterExt<'a> for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(line: &str) -> Vec<Cow<'_, str>> {
+        CsvFields::new(line).map(|f| f.value()).collect()
+    }
+
+    #[test]
+    fn words_skip_punctuation_but_keep_inner_apostrophes() {
+        let tok = Tokenizer::new("Don't panic -- it's 42, 'quoted' café!");
+        let words: Vec<_> = tok.words().collect();
+        assert_eq!(words, ["Don't", "panic", "it's", "42", "quoted", "café"]);
+    }
+
+    #[test]
+    fn words_of_empty_or_blank_input() {
+        assert_eq!(Tokenizer::new("").words().count(), 0);
+        assert_eq!(Tokenizer::new(" ... !! ").words().count(), 0);
+    }
+
+    #[test]
+    fn sentences_split_on_terminators_followed_by_space() {
+        let tok = Tokenizer::new("  Hello there! Version 1.5 is out. Is it?Yes.  Trailing bit ");
+        let sentences: Vec<_> = tok.sentences().collect();
+        assert_eq!(
+            sentences,
+            [
+                "Hello there!",
+                "Version 1.5 is out.",
+                "Is it?Yes.",
+                "Trailing bit"
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_fields_handle_quotes_commas_and_escapes() {
+        assert_eq!(fields("a,b,,c"), ["a", "b", "", "c"]);
+        assert_eq!(
+            fields(r#"1,"Kim, Sooyoung","say ""hi""",end"#),
+            ["1", "Kim, Sooyoung", r#"say "hi""#, "end"]
+        );
+        assert_eq!(fields("trailing,"), ["trailing", ""]);
+        assert_eq!(fields("crlf,line\r"), ["crlf", "line"]);
+    }
+
+    #[test]
+    fn csv_values_borrow_unless_unescaping() {
+        let line = r#"plain,"quoted","esc""aped""#;
+        let values: Vec<_> = CsvFields::new(line).map(|f| f.value()).collect();
+        assert!(matches!(values[0], Cow::Borrowed("plain")));
+        assert!(matches!(values[1], Cow::Borrowed("quoted")));
+        assert!(matches!(values[2], Cow::Owned(_)));
+    }
+
+    #[test]
+    fn csv_records_skip_blank_lines() {
+        let tok = Tokenizer::new("name,qty\n\napple,3\npear,5\n");
+        let rows: Vec<Vec<&str>> = tok
+            .csv_records()
+            .map(|r| r.map(|f| f.raw()).collect())
+            .collect();
+        assert_eq!(rows, [["name", "qty"], ["apple", "3"], ["pear", "5"]]);
+    }
+
+    #[test]
+    fn tokens_are_slices_of_the_input() {
+        let input = String::from("alpha beta");
+        let first = Tokenizer::new(&input).words().next().unwrap();
+        assert_eq!(first.as_ptr(), input.as_ptr());
+    }
+
+    #[test]
+    fn tokens_outlive_the_tokenizer_and_iterator() {
+        let input = String::from("short longest mid");
+        let longest = {
+            let tok = Tokenizer::new(&input);
+            tok.words().longest()
+        };
+        assert_eq!(longest, Some("longest"));
+    }
+
+    #[test]
+    fn spanned_recovers_byte_offsets() {
+        let input = "ab, cd";
+        let spans: Vec<_> = Tokenizer::new(input).words().spanned(input).collect();
+        assert_eq!(spans[1].span, 4..6);
+        assert_eq!(&input[spans[1].span.clone()], spans[1].text);
+    }
+
+    #[test]
+    fn matching_ignores_ascii_case() {
+        let tok = Tokenizer::new("The cat saw the other THE.");
+        assert_eq!(tok.words().matching("the").count(), 3);
+    }
+}
@@ -0,0 +1,39 @@
+//! Inventory: pricing form input, and saving/loading the JSON store.
+
+use std::hint::black_box;
+
+use benches::fixtures::{inventory, scratch_file};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use project_inventory_system::{
+    load_products_from_file, product_from_input, save_products_to_file,
+};
+
+fn storage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inventory");
+    group.bench_function("price_input", |b| {
+        b.iter(|| {
+            product_from_input(
+                black_box("widget"),
+                black_box("12"),
+                black_box("9.99"),
+                0.10,
+            )
+        })
+    });
+    for n in [10, 1_000] {
+        let products = inventory(n);
+        let path = scratch_file(&format!("inventory-{n}.json"));
+        group.bench_with_input(BenchmarkId::new("save", n), &products, |b, products| {
+            b.iter(|| save_products_to_file(&path, products).expect("save"))
+        });
+        save_products_to_file(&path, &products).expect("save");
+        group.bench_with_input(BenchmarkId::new("load", n), &path, |b, path| {
+            b.iter(|| load_products_from_file(path).expect("load"))
+        });
+        let _ = std::fs::remove_file(&path);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, storage);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+//! Notes: appending a timestamp marker (open, format, write, close).
+
+use benches::fixtures::scratch_file;
+use criterion::{Criterion, criterion_group, criterion_main};
+use project_notes::append_timestamp;
+
+fn append(c: &mut Criterion) {
+    let path = scratch_file("notes.txt");
+    let mut group = c.benchmark_group("notes");
+    group.bench_function("append_timestamp", |b| {
+        b.iter(|| append_timestamp(&path).expect("append"))
+    });
+    group.finish();
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group!(benches, append);
+criterion_main!(benches);
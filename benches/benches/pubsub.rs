@@ -0,0 +1,45 @@
+//! Pub/sub: encoding and decoding legacy single-frame `"<topic> <json>"` messages, and the
+//! MessagePack and protobuf payloads that replace the JSON with `--format`.
+
+use std::hint::black_box;
+
+use benches::fixtures::hand_state;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use project_robot_joint_pubsub::wire::{self, Format};
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pubsub");
+    // One joint (headless publisher) and a full hand (GUI publisher).
+    for joints in [1, 20] {
+        let state = hand_state(joints, 42);
+        let message = wire::encode("robot_joints", &state);
+        group.bench_with_input(BenchmarkId::new("encode", joints), &state, |b, state| {
+            b.iter(|| wire::encode(black_box("robot_joints"), state))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decode", joints),
+            &message,
+            |b, message| b.iter(|| wire::decode(black_box(message)).expect("decode")),
+        );
+        for (name, format) in [
+            ("msgpack", Format::MessagePack),
+            ("protobuf", Format::Protobuf),
+        ] {
+            let payload = format.encode(&state);
+            group.bench_with_input(
+                BenchmarkId::new(format!("encode_{name}"), joints),
+                &state,
+                |b, state| b.iter(|| format.encode(black_box(state))),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("decode_{name}"), joints),
+                &payload,
+                |b, payload| b.iter(|| format.decode(black_box(payload)).expect("decode")),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, serialization);
+criterion_main!(benches);
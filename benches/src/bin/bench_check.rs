@@ -0,0 +1,106 @@
+//! `bench-check`: compare the latest `cargo bench` results with the committed baseline.
+//!
+//! Exit code 1 when any benchmark is slower than the tolerance allows, 3 when the
+//! results or the baseline cannot be read or written (see `errors::exit`).
+
+use std::process::ExitCode;
+
+use benches::{
+    baseline_path, collect_means, compare, criterion_home, format_nanos, load_baseline,
+    save_baseline,
+};
+use clap::Parser;
+use errors::Exit;
+
+#[derive(Parser)]
+#[command(
+    name = "bench-check",
+    about = "Compare benchmark means against baselines/baseline.json"
+)]
+struct Cli {
+    /// Allowed slowdown as a fraction before a benchmark counts as a regression.
+    #[arg(long, default_value_t = 0.25)]
+    tolerance: f64,
+    /// Record the current results in the baseline instead of comparing.
+    #[arg(long)]
+    update: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let home = criterion_home();
+    let baseline_file = baseline_path();
+    let current = match collect_means(&home) {
+        Ok(current) if !current.is_empty() => current,
+        Ok(_) => {
+            eprintln!(
+                "error: no results under {}; run `cargo bench -p benches` first",
+                home.display()
+            );
+            return ExitCode::FAILURE;
+        }
+        Err(err) => {
+            eprintln!("error: reading {}: {err}", home.display());
+            return Exit::Io.into();
+        }
+    };
+
+    let baseline = match load_baseline(&baseline_file) {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("error: reading {}: {err}", baseline_file.display());
+            return Exit::Io.into();
+        }
+    };
+
+    if cli.update {
+        // Keep entries that were not re-run (e.g. `cargo bench -p benches --bench notes`).
+        let mut updated = baseline;
+        updated.extend(current.iter().map(|(id, ns)| (id.clone(), *ns)));
+        return match save_baseline(&baseline_file, &updated) {
+            Ok(()) => {
+                println!(
+                    "Recorded {} benchmarks in {}",
+                    current.len(),
+                    baseline_file.display()
+                );
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: writing {}: {err}", baseline_file.display());
+                Exit::Io.into()
+            }
+        };
+    }
+
+    println!(
+        "{:<36} {:>11} {:>11} {:>8}",
+        "benchmark", "baseline", "current", "change"
+    );
+    for (id, &now) in &current {
+        match baseline.get(id) {
+            Some(&before) => println!(
+                "{id:<36} {:>11} {:>11} {:>+7.1}%",
+                format_nanos(before),
+                format_nanos(now),
+                (now / before - 1.0) * 100.0
+            ),
+            None => println!("{id:<36} {:>11} {:>11}", "-", format_nanos(now)),
+        }
+    }
+
+    let regressions = compare(&baseline, &current, cli.tolerance);
+    if regressions.is_empty() {
+        println!("\nNo regressions beyond {:.0}%.", cli.tolerance * 100.0);
+        return ExitCode::SUCCESS;
+    }
+    println!();
+    for regression in &regressions {
+        println!(
+            "REGRESSION {}: {:.0}% slower",
+            regression.id,
+            regression.slowdown() * 100.0
+        );
+    }
+    ExitCode::FAILURE
+}
@@ -0,0 +1,45 @@
+//! Inputs shared by the benchmarks, so each one measures the same data.
+
+use std::path::PathBuf;
+
+use project_inventory_system::{Product, product_from_input};
+
+/// `n` priced products with varied names and quantities.
+pub fn inventory(n: usize) -> Vec<Product> {
+    (0..n)
+        .map(|i| {
+            let quantity = (i % 50 + 1).to_string();
+            product_from_input(&format!("widget-{i}"), &quantity, "9.99", 0.10)
+                .expect("fixture product is valid")
+        })
+        .collect()
+}
+
+/// A per-process path in the temp dir for benchmarks that touch files.
+pub fn scratch_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("benches-{}-{name}", std::process::id()))
+}
+
+/// A hand-sized state: `joints` joints at sequence number `sequence`, stamped
+/// with an epoch-nanosecond timestamp as a publisher would.
+pub fn hand_state(joints: usize, sequence: u64) -> project_robot_joint_pubsub::RobotState {
+    use project_robot_joint_pubsub::{JointAngles, RobotState};
+
+    let timestamp = 1_760_000_000_000_000_000 + sequence;
+    RobotState {
+        timestamp,
+        robot_id: "pro_hand".to_string(),
+        joints: (0..joints)
+            .map(|i| JointAngles {
+                timestamp,
+                joint_name: format!("L/j{i}_MCP"),
+                angle_rad: i as f64 * 0.05,
+                velocity: 0.1,
+                torque: 0.0,
+                qpos: Vec::new(),
+            })
+            .collect(),
+        sent_at_us: None,
+        sequence: Some(sequence),
+    }
+}
@@ -0,0 +1,214 @@
+//! Shared plumbing for the workspace benchmarks and their regression check.
+//!
+//! The benchmarks themselves live in `benches/` (one file per project) and draw
+//! their inputs from [`fixtures`]. Each
+//! writes Criterion results under [`criterion_home`]; `bench-check` then reads
+//! the mean of every benchmark in [`GROUPS`] with [`collect_means`] and
+//! [`compare`]s it against the committed `baselines/baseline.json`.
+
+pub mod fixtures;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Criterion group names owned by this crate; other results in the same
+/// directory (e.g. the chapter 6 demo) are ignored.
+pub const GROUPS: [&str; 3] = ["inventory", "notes", "pubsub"];
+
+/// Committed baseline, relative to this crate.
+pub const BASELINE_FILE: &str = "baselines/baseline.json";
+
+/// Mean time per iteration in nanoseconds, keyed by benchmark id
+/// (`"inventory/save/100"`).
+pub type Means = BTreeMap<String, f64>;
+
+/// A benchmark that got slower than the baseline allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub id: String,
+    pub baseline_ns: f64,
+    pub current_ns: f64,
+}
+
+impl Regression {
+    /// How much slower, as a fraction (`0.30` = 30% slower).
+    pub fn slowdown(&self) -> f64 {
+        self.current_ns / self.baseline_ns - 1.0
+    }
+}
+
+/// Where Criterion writes results: `CRITERION_HOME`, then
+/// `$CARGO_TARGET_DIR/criterion`, then the target directory reported by
+/// `cargo metadata` (the workspace `target/`), the same order Criterion uses.
+pub fn criterion_home() -> PathBuf {
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(metadata_target_dir)
+        .unwrap_or_else(|| PathBuf::from("target"));
+    target.join("criterion")
+}
+
+fn metadata_target_dir() -> Option<PathBuf> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    metadata["target_directory"].as_str().map(PathBuf::from)
+}
+
+/// The baseline file's absolute path.
+pub fn baseline_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(BASELINE_FILE)
+}
+
+/// Latest mean of every benchmark under `home` that belongs to [`GROUPS`].
+///
+/// Criterion keeps each run in `<id>/new/estimates.json`.
+pub fn collect_means(home: &Path) -> io::Result<Means> {
+    let mut means = Means::new();
+    for group in GROUPS {
+        let dir = home.join(group);
+        if dir.is_dir() {
+            walk(&dir, group, &mut means)?;
+        }
+    }
+    Ok(means)
+}
+
+fn walk(dir: &Path, id: &str, means: &mut Means) -> io::Result<()> {
+    let estimates = dir.join("new").join("estimates.json");
+    if estimates.is_file() {
+        let raw = fs::read_to_string(&estimates)?;
+        let parsed: serde_json::Value = serde_json::from_str(&raw)?;
+        if let Some(mean) = parsed["mean"]["point_estimate"].as_f64() {
+            means.insert(id.to_string(), mean);
+        }
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // `report` holds Criterion's HTML, not results.
+        if entry.file_type()?.is_dir() && name != "report" {
+            walk(&entry.path(), &format!("{id}/{name}"), means)?;
+        }
+    }
+    Ok(())
+}
+
+/// Benchmarks present in both maps whose mean grew by more than `tolerance`
+/// (a fraction: `0.25` allows 25% slower), slowest first.
+pub fn compare(baseline: &Means, current: &Means, tolerance: f64) -> Vec<Regression> {
+    let mut regressions: Vec<Regression> = current
+        .iter()
+        .filter_map(|(id, &current_ns)| {
+            let &baseline_ns = baseline.get(id)?;
+            (current_ns > baseline_ns * (1.0 + tolerance)).then(|| Regression {
+                id: id.clone(),
+                baseline_ns,
+                current_ns,
+            })
+        })
+        .collect();
+    regressions.sort_by(|a, b| b.slowdown().total_cmp(&a.slowdown()));
+    regressions
+}
+
+/// Read a baseline file; a missing file is an empty baseline.
+pub fn load_baseline(path: &Path) -> io::Result<Means> {
+    match fs::read_to_string(path) {
+        Ok(raw) => Ok(serde_json::from_str(&raw)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Means::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write `means` as pretty JSON (sorted by id, so diffs stay small).
+pub fn save_baseline(path: &Path, means: &Means) -> io::Result<()> {
+    let rounded: Means = means
+        .iter()
+        .map(|(id, ns)| (id.clone(), ns.round()))
+        .collect();
+    fs::write(path, serde_json::to_string_pretty(&rounded)? + "\n")
+}
+
+/// `1.2 µs`-style rendering of a nanosecond count.
+pub fn format_nanos(ns: f64) -> String {
+    if ns < 1_000.0 {
+        format!("{ns:.0} ns")
+    } else if ns < 1_000_000.0 {
+        format!("{:.1} µs", ns / 1_000.0)
+    } else {
+        format!("{:.2} ms", ns / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn means(pairs: &[(&str, f64)]) -> Means {
+        pairs.iter().map(|(id, ns)| (id.to_string(), *ns)).collect()
+    }
+
+    #[test]
+    fn compare_flags_only_slowdowns_past_the_tolerance() {
+        let baseline = means(&[("a", 100.0), ("b", 100.0), ("c", 100.0), ("gone", 1.0)]);
+        let current = means(&[("a", 110.0), ("b", 150.0), ("c", 130.0), ("new", 5.0)]);
+        let ids: Vec<_> = compare(&baseline, &current, 0.25)
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, ["b", "c"]);
+    }
+
+    #[test]
+    fn collects_means_from_criterion_layout() {
+        let home = env::temp_dir().join(format!("benches-test-{}", std::process::id()));
+        let write = |id: &str, mean: f64| {
+            let dir = home.join(id).join("new");
+            fs::create_dir_all(&dir).unwrap();
+            let json = format!(r#"{{"mean":{{"point_estimate":{mean}}}}}"#);
+            fs::write(dir.join("estimates.json"), json).unwrap();
+        };
+        write("inventory/save/100", 1500.0);
+        write("notes/append_timestamp", 900.0);
+        write("Insert/Vec/10", 1.0); // someone else's group
+        fs::create_dir_all(home.join("inventory/report")).unwrap();
+
+        let got = collect_means(&home).unwrap();
+        fs::remove_dir_all(&home).unwrap();
+        assert_eq!(
+            got,
+            means(&[
+                ("inventory/save/100", 1500.0),
+                ("notes/append_timestamp", 900.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn baseline_round_trips_rounded() {
+        let path = env::temp_dir().join(format!("benches-baseline-{}.json", std::process::id()));
+        save_baseline(&path, &means(&[("x", 12.6)])).unwrap();
+        let loaded = load_baseline(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, means(&[("x", 13.0)]));
+        assert!(
+            load_baseline(Path::new("/no/such/baseline.json"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Reading TOML config files into plain structs.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use errors::{DataContext, IoContext, Result};
+use serde::de::DeserializeOwned;
+
+/// Parse the TOML file at `path` into `T`.
+///
+/// A missing file is not an error: it yields `T::default()`, so an app runs with
+/// built-in defaults until someone writes a config. Mark fields `#[serde(default)]`
+/// (or the whole struct) so a config may set only the keys it cares about.
+pub fn load_toml<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(err).io_context("reading", path),
+    };
+    toml::from_str(&raw).data_context("parsing", path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(default)]
+    struct Demo {
+        name: String,
+        retries: u32,
+    }
+
+    impl Default for Demo {
+        fn default() -> Self {
+            Demo {
+                name: "demo".to_string(),
+                retries: 3,
+            }
+        }
+    }
+
+    // A per-test file in the OS temp dir, removed on drop.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn with(name: &str, contents: &str) -> TempFile {
+            let path = std::env::temp_dir().join(format!("common-{}-{name}", std::process::id()));
+            fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn missing_file_means_defaults() {
+        let demo: Demo = load_toml(Path::new("/definitely/not/here.toml")).unwrap();
+        assert_eq!(demo, Demo::default());
+    }
+
+    #[test]
+    fn partial_file_keeps_other_defaults() {
+        let file = TempFile::with("partial.toml", "retries = 7\n");
+        let demo: Demo = load_toml(&file.0).unwrap();
+        assert_eq!(
+            demo,
+            Demo {
+                name: "demo".to_string(),
+                retries: 7
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_file_names_the_path() {
+        let file = TempFile::with("broken.toml", "retries = \"many\"\n");
+        let err = load_toml::<Demo>(&file.0).unwrap_err();
+        let message = errors::report(&err);
+        assert!(message.starts_with("parsing "), "{message}");
+        assert!(message.contains("broken.toml"), "{message}");
+    }
+}
@@ -0,0 +1,41 @@
+//! Plumbing shared by the project crates: where files live, how config is read,
+//! and how logging is switched on.
+//!
+//! # How this crate is organized
+//!
+//! - [`paths`] — [`AppDirs`]: per-app config and data directories following the
+//!   XDG base-directory spec (`~/.config/<app>`, `~/.local/share/<app>`).
+//! - [`config`] — [`load_toml`]: read a TOML file into any `Deserialize + Default`
+//!   type, where a missing file means "all defaults".
+//! - [`logging`] — [`init_logging`]: a `tracing` subscriber writing to stderr or a
+//!   log file, filtered by `RUST_LOG` or an app-supplied default, as text or as
+//!   JSON lines (`LOG_FORMAT=json`).
+//!
+//! Failures are the repo's shared [`errors::AppError`], with the path attached.
+//!
+//! # Typical startup
+//!
+//! ```no_run
+//! use common::{AppDirs, LogOutput, init_logging};
+//!
+//! #[derive(Default, serde::Deserialize)]
+//! struct Config {
+//!     greeting: Option<String>,
+//! }
+//!
+//! # fn main() -> errors::Result<()> {
+//! let dirs = AppDirs::new("demo")?;
+//! let config: Config = dirs.load_config()?;
+//! init_logging("info", LogOutput::Stderr)?;
+//! tracing::info!(greeting = ?config.greeting, "started");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod config;
+pub mod logging;
+pub mod paths;
+
+pub use config::load_toml;
+pub use logging::{LogFormat, LogOutput, init_logging, init_logging_as};
+pub use paths::AppDirs;
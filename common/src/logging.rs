@@ -0,0 +1,163 @@
+//! One-call `tracing` setup shared by every binary.
+//!
+//! Every binary follows the same convention, so logs from any of them can be
+//! filtered and collected the same way:
+//!
+//! - `RUST_LOG` picks what is logged (`RUST_LOG=debug`, `RUST_LOG=warn,publisher=debug`);
+//!   without it each binary uses its own default, normally `"info"`.
+//! - `LOG_FORMAT=json` switches to one JSON object per line for log aggregators;
+//!   `LOG_FORMAT=text` (or unset) is the human-readable format.
+//! - Logs never go to stdout, which stays free for the program's own output.
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use errors::{AppError, IoContext, Result, ValidationError};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Environment variable that selects the [`LogFormat`].
+pub const LOG_FORMAT_VAR: &str = "LOG_FORMAT";
+
+/// Where log lines go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogOutput {
+    /// Standard error, with colours when it is a terminal.
+    Stderr,
+    /// Appended to this file (parent directories must exist). Use this for TUIs,
+    /// where stderr output would garble the screen.
+    File(PathBuf),
+}
+
+/// How each log line is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `2026-10-15T13:00:39Z  INFO publisher: message key=value`.
+    #[default]
+    Text,
+    /// `{"timestamp":…,"level":"INFO","fields":{"message":…},"target":…}`.
+    Json,
+}
+
+impl LogFormat {
+    /// Read [`LOG_FORMAT_VAR`]; unset or empty means [`LogFormat::Text`].
+    pub fn from_env() -> Result<LogFormat> {
+        LogFormat::from_var(std::env::var(LOG_FORMAT_VAR).ok())
+    }
+
+    /// Like [`LogFormat::from_env`], with the variable's value supplied (handy in tests).
+    pub fn from_var(value: Option<String>) -> Result<LogFormat> {
+        match value.as_deref().map(str::trim) {
+            None | Some("") => Ok(LogFormat::Text),
+            Some(value) => value.parse(),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<LogFormat> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(ValidationError::Invalid {
+                field: LOG_FORMAT_VAR,
+                expected: "\"text\" or \"json\"",
+                value: s.to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// Install a global `tracing` subscriber in the format chosen by `LOG_FORMAT`.
+///
+/// `RUST_LOG` wins when it is set; otherwise `default_filter` is used (`"info"`,
+/// `"warn,publisher=debug"`, …). Only the first call in a process takes effect;
+/// later calls are ignored, so libraries and tests may call this defensively.
+pub fn init_logging(default_filter: &str, output: LogOutput) -> Result<()> {
+    init_logging_as(default_filter, output, LogFormat::from_env()?)
+}
+
+/// Like [`init_logging`], with the format chosen by the caller instead of `LOG_FORMAT`.
+pub fn init_logging_as(default_filter: &str, output: LogOutput, format: LogFormat) -> Result<()> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let (writer, ansi) = match output {
+        LogOutput::Stderr => (
+            BoxMakeWriter::new(std::io::stderr),
+            std::io::stderr().is_terminal(),
+        ),
+        LogOutput::File(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .io_context("opening log file", &path)?;
+            (BoxMakeWriter::new(Mutex::new(file)), false)
+        }
+    };
+    let layer = match format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_ansi(ansi)
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(writer)
+            .boxed(),
+    };
+    // `try_init` only fails when a subscriber is already installed; that is fine.
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(layer)
+        .try_init();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_or_empty_means_text() {
+        assert_eq!(LogFormat::from_var(None).unwrap(), LogFormat::Text);
+        assert_eq!(
+            LogFormat::from_var(Some(" ".to_string())).unwrap(),
+            LogFormat::Text
+        );
+    }
+
+    #[test]
+    fn parses_either_case() {
+        assert_eq!(
+            LogFormat::from_var(Some("JSON".to_string())).unwrap(),
+            LogFormat::Json
+        );
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert_eq!(LogFormat::Json.to_string(), "json");
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        let err = LogFormat::from_var(Some("yaml".to_string())).unwrap_err();
+        assert!(err.to_string().contains("LOG_FORMAT"), "{err}");
+    }
+}
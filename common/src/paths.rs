@@ -0,0 +1,145 @@
+//! XDG base directories for one application.
+//!
+//! `$XDG_CONFIG_HOME/<app>` (default `~/.config/<app>`) holds `config.toml`;
+//! `$XDG_DATA_HOME/<app>` (default `~/.local/share/<app>`) holds everything the
+//! app writes. Per the spec, relative `XDG_*` values are ignored.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use errors::{IoContext, Result, ValidationError};
+use serde::de::DeserializeOwned;
+
+use crate::config::load_toml;
+
+/// File name looked up inside [`AppDirs::config_dir`].
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Where one application keeps its config and data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirs {
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+}
+
+impl AppDirs {
+    /// Resolve the directories for `app` from the process environment.
+    ///
+    /// Nothing is created on disk; see [`AppDirs::ensure_data_dir`].
+    pub fn new(app: &str) -> Result<AppDirs> {
+        AppDirs::from_env(app, |key| env::var_os(key).map(PathBuf::from))
+    }
+
+    /// Like [`AppDirs::new`], with the environment supplied by `var` (handy in tests).
+    pub fn from_env(app: &str, var: impl Fn(&str) -> Option<PathBuf>) -> Result<AppDirs> {
+        let home = || {
+            var("HOME")
+                .filter(|home| home.is_absolute())
+                .ok_or(ValidationError::Missing {
+                    field: "HOME (or XDG_CONFIG_HOME / XDG_DATA_HOME)",
+                })
+        };
+        let base = |key: &str, fallback: &str| -> Result<PathBuf> {
+            match var(key).filter(|dir| dir.is_absolute()) {
+                Some(dir) => Ok(dir),
+                None => Ok(home()?.join(fallback)),
+            }
+        };
+        Ok(AppDirs {
+            config_dir: base("XDG_CONFIG_HOME", ".config")?.join(app),
+            data_dir: base("XDG_DATA_HOME", ".local/share")?.join(app),
+        })
+    }
+
+    /// Directories rooted somewhere explicit, e.g. a temp dir in tests.
+    pub fn with_dirs(config_dir: impl Into<PathBuf>, data_dir: impl Into<PathBuf>) -> AppDirs {
+        AppDirs {
+            config_dir: config_dir.into(),
+            data_dir: data_dir.into(),
+        }
+    }
+
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// `<config_dir>/config.toml`.
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join(CONFIG_FILE)
+    }
+
+    /// `<data_dir>/<name>`; the directory may not exist yet.
+    pub fn data_file(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.data_dir.join(name)
+    }
+
+    /// Create the data directory (and parents) if needed and return it.
+    pub fn ensure_data_dir(&self) -> Result<&Path> {
+        fs::create_dir_all(&self.data_dir).io_context("creating", &self.data_dir)?;
+        Ok(&self.data_dir)
+    }
+
+    /// Read [`AppDirs::config_file`] into `T`; a missing file yields `T::default()`.
+    pub fn load_config<T: DeserializeOwned + Default>(&self) -> Result<T> {
+        load_toml(&self.config_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<PathBuf> {
+        let map: HashMap<String, PathBuf> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), PathBuf::from(v)))
+            .collect();
+        move |key| map.get(key).cloned()
+    }
+
+    #[test]
+    fn falls_back_to_home() {
+        let dirs = AppDirs::from_env("notes", env_of(&[("HOME", "/home/kim")])).unwrap();
+        assert_eq!(dirs.config_dir(), Path::new("/home/kim/.config/notes"));
+        assert_eq!(dirs.data_dir(), Path::new("/home/kim/.local/share/notes"));
+        assert_eq!(
+            dirs.config_file(),
+            Path::new("/home/kim/.config/notes/config.toml")
+        );
+        assert_eq!(
+            dirs.data_file("notes.txt"),
+            Path::new("/home/kim/.local/share/notes/notes.txt")
+        );
+    }
+
+    #[test]
+    fn xdg_variables_win_when_absolute() {
+        let env = env_of(&[
+            ("HOME", "/home/kim"),
+            ("XDG_CONFIG_HOME", "/etc/xdg-kim"),
+            ("XDG_DATA_HOME", "relative/ignored"),
+        ]);
+        let dirs = AppDirs::from_env("inventory", env).unwrap();
+        assert_eq!(dirs.config_dir(), Path::new("/etc/xdg-kim/inventory"));
+        assert_eq!(
+            dirs.data_dir(),
+            Path::new("/home/kim/.local/share/inventory")
+        );
+    }
+
+    #[test]
+    fn no_home_is_an_error_only_when_needed() {
+        assert!(AppDirs::from_env("x", env_of(&[])).is_err());
+        let env = env_of(&[("XDG_CONFIG_HOME", "/c"), ("XDG_DATA_HOME", "/d")]);
+        assert_eq!(
+            AppDirs::from_env("x", env).unwrap(),
+            AppDirs::with_dirs("/c/x", "/d/x")
+        );
+    }
+}
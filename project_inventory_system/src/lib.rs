@@ -3,40 +3,129 @@
 //! Provides a small terminal UI for adding, listing, and deleting products
 //! in a JSON-backed inventory file. The entry point is [`run`], which is
 //! called by `main.rs` after setting up the terminal.
+//!
+//! Errors use the repo's shared [`errors`] crate: form input is checked into
+//! [`ValidationError`]s shown in a dialog, and file problems become
+//! [`errors::AppError`]s carrying the action and path.
+//!
+//! Settings come from `~/.config/inventory/config.toml` via the shared [`common`]
+//! crate (see [`Config`]). Products are stored in `~/.local/share/inventory/`
+//! unless the config points elsewhere, and `tracing` output goes to
+//! `inventory.log` next to them, because the TUI owns the terminal.
+//!
+//! The pricing and storage steps ([`product_from_input`],
+//! [`save_products_to_file`], [`load_products_from_file`]) are public so the
+//! workspace `benches` crate can measure them without the UI.
 
-use std::fs::{File, OpenOptions};
-use std::io::Read;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use anyhow::{Context, Result};
+use common::{AppDirs, LogOutput, init_logging};
+
 use cursive::traits::{Nameable, Resizable};
 use cursive::views::{Dialog, EditView, ListView};
 use cursive::{Cursive, CursiveExt};
+use errors::{DataContext, IoContext, Result, ValidationError, parse_field, report};
 use serde::{Deserialize, Serialize};
 
-const FILE_PATH: &str = "inventory.json";
-const SALES_TAX_RATE: f64 = 0.10;
+/// Name used for the XDG config and data directories.
+const APP_NAME: &str = "inventory";
+const DEFAULT_FILE: &str = "inventory.json";
+const LOG_FILE: &str = "inventory.log";
+
+/// `config.toml` for the inventory app; every key is optional.
+///
+/// ```toml
+/// sales_tax_rate = 0.08
+/// inventory_file = "/srv/shop/inventory.json"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Fraction of the unit price added as tax (`0.10` = 10%).
+    pub sales_tax_rate: f64,
+    /// Where products are stored; defaults to `inventory.json` in the data dir.
+    pub inventory_file: Option<PathBuf>,
+}
 
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            sales_tax_rate: 0.10,
+            inventory_file: None,
+        }
+    }
+}
+
+impl Config {
+    // Reject settings that would make every saved price wrong.
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !self.sales_tax_rate.is_finite() || self.sales_tax_rate < 0.0 {
+            return Err(ValidationError::Invalid {
+                field: "sales_tax_rate",
+                expected: "a non-negative number",
+                value: self.sales_tax_rate.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// One inventory line as stored in the JSON file.
 #[derive(Serialize, Deserialize, Clone, Debug)]
-struct Product {
-    product_type: String,
-    quantity: usize,
-    price_per_unit: f64,
-    sales_tax: f64,
-    total_price: f64,
+pub struct Product {
+    pub product_type: String,
+    pub quantity: usize,
+    pub price_per_unit: f64,
+    /// Tax on one unit, at the rate configured when the product was saved.
+    pub sales_tax: f64,
+    pub total_price: f64,
 }
 
 /// Run the inventory TUI event loop until the user quits.
+///
+/// Fails before opening the UI if the config is invalid, or if the inventory
+/// file exists but cannot be read or parsed, rather than starting empty and
+/// overwriting it on the next save.
 pub fn run() -> Result<()> {
+    let dirs = AppDirs::new(APP_NAME)?;
+    let config: Config = dirs.load_config()?;
+    config.validate()?;
+    let data_dir = dirs.ensure_data_dir()?;
+    init_logging("info", LogOutput::File(data_dir.join(LOG_FILE)))?;
+
+    let file = Arc::new(
+        config
+            .inventory_file
+            .clone()
+            .unwrap_or_else(|| dirs.data_file(DEFAULT_FILE)),
+    );
+    let products = load_products_from_file(&file)?;
+    tracing::info!(
+        count = products.len(),
+        file = %file.display(),
+        tax_rate = config.sales_tax_rate,
+        "inventory loaded"
+    );
+    let products = Arc::new(Mutex::new(products));
     let mut siv = Cursive::default();
-    let products = Arc::new(Mutex::new(load_products_from_file()));
 
-    siv.add_layer(build_main_dialog(Arc::clone(&products)));
+    siv.add_layer(build_main_dialog(
+        Arc::clone(&products),
+        file,
+        config.sales_tax_rate,
+    ));
     siv.run();
     Ok(())
 }
 
-fn build_main_dialog(products: Arc<Mutex<Vec<Product>>>) -> Dialog {
+fn build_main_dialog(
+    products: Arc<Mutex<Vec<Product>>>,
+    file: Arc<PathBuf>,
+    tax_rate: f64,
+) -> Dialog {
     Dialog::new()
         .title("Inventory Management")
         .content(
@@ -50,7 +139,8 @@ fn build_main_dialog(products: Arc<Mutex<Vec<Product>>>) -> Dialog {
         )
         .button("Save", {
             let products = Arc::clone(&products);
-            move |s| handle_save(s, &products)
+            let file = Arc::clone(&file);
+            move |s| handle_save(s, &products, &file, tax_rate)
         })
         .button("Show All", {
             let products = Arc::clone(&products);
@@ -58,43 +148,22 @@ fn build_main_dialog(products: Arc<Mutex<Vec<Product>>>) -> Dialog {
         })
         .button("Delete by ID", {
             let products = Arc::clone(&products);
-            move |s| handle_delete(s, &products)
+            move |s| handle_delete(s, &products, &file)
         })
         .button("Quit", |s| s.quit())
 }
 
-fn handle_save(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
-    let Some(product_type) = read_field(s, "product_type") else {
-        return;
-    };
-    let quantity = read_field(s, "quantity")
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(0);
-    let price_per_unit = read_field(s, "price_per_unit")
-        .and_then(|v| v.parse::<f64>().ok())
-        .unwrap_or(0.0);
-
-    if product_type.is_empty() {
-        s.add_layer(Dialog::info("Error: Please enter a product type."));
-        return;
-    }
-    if quantity == 0 {
-        s.add_layer(Dialog::info("Error: Please enter a valid quantity."));
-        return;
-    }
-    if price_per_unit == 0.0 {
-        s.add_layer(Dialog::info("Error: Please enter a valid price."));
-        return;
-    }
-
-    let sales_tax = SALES_TAX_RATE * price_per_unit;
-    let total_price = (price_per_unit + sales_tax) * quantity as f64;
-    let product = Product {
-        product_type,
-        quantity,
-        price_per_unit,
-        sales_tax,
-        total_price,
+fn handle_save(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>, file: &Path, tax_rate: f64) {
+    let product_type = read_field(s, "product_type").unwrap_or_default();
+    let quantity = read_field(s, "quantity").unwrap_or_default();
+    let price_per_unit = read_field(s, "price_per_unit").unwrap_or_default();
+    let product = match product_from_input(&product_type, &quantity, &price_per_unit, tax_rate) {
+        Ok(product) => product,
+        Err(err) => {
+            tracing::debug!(%err, "rejected product form");
+            s.add_layer(Dialog::info(format!("Error: {err}.")));
+            return;
+        }
     };
 
     let mut store = match products.lock() {
@@ -104,10 +173,17 @@ fn handle_save(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
             return;
         }
     };
+    tracing::info!(product = %product.product_type, quantity = product.quantity, "adding product");
     store.push(product);
-    match save_products_to_file(&store) {
+    match save_products_to_file(file, &store) {
         Ok(()) => s.add_layer(Dialog::info("Product saved successfully!")),
-        Err(err) => s.add_layer(Dialog::info(format!("Error saving product: {err}"))),
+        Err(err) => {
+            tracing::error!(error = %report(&err), "save failed");
+            s.add_layer(Dialog::info(format!(
+                "Error saving product: {}",
+                report(&err)
+            )))
+        }
     }
 }
 
@@ -137,9 +213,10 @@ fn handle_show_all(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
     s.add_layer(Dialog::info(output));
 }
 
-fn handle_delete(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
+fn handle_delete(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>, file: &Arc<PathBuf>) {
     let id_input = EditView::new().with_name("delete_id").min_width(10);
     let products = Arc::clone(products);
+    let file = Arc::clone(file);
     s.add_layer(
         Dialog::new()
             .title("Delete Product")
@@ -161,11 +238,16 @@ fn handle_delete(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
                     s.add_layer(Dialog::info("Error: Invalid product ID."));
                     return;
                 }
-                store.remove(id - 1);
-                match save_products_to_file(&store) {
+                let removed = store.remove(id - 1);
+                tracing::info!(id, product = %removed.product_type, "deleting product");
+                match save_products_to_file(&file, &store) {
                     Ok(()) => s.add_layer(Dialog::info("Product deleted successfully!")),
                     Err(err) => {
-                        s.add_layer(Dialog::info(format!("Error deleting product: {err}")));
+                        tracing::error!(error = %report(&err), "save after delete failed");
+                        s.add_layer(Dialog::info(format!(
+                            "Error deleting product: {}",
+                            report(&err)
+                        )));
                     }
                 }
             })
@@ -175,30 +257,97 @@ fn handle_delete(s: &mut Cursive, products: &Arc<Mutex<Vec<Product>>>) {
     );
 }
 
+/// Check the three form fields and price the product, or say which field is wrong.
+pub fn product_from_input(
+    product_type: &str,
+    quantity: &str,
+    price_per_unit: &str,
+    tax_rate: f64,
+) -> Result<Product, ValidationError> {
+    let product_type = product_type.trim();
+    if product_type.is_empty() {
+        return Err(ValidationError::Missing {
+            field: "product type",
+        });
+    }
+    let positive_count = "a positive whole number";
+    let count: usize = parse_field("quantity", quantity, positive_count)?;
+    if count == 0 {
+        return Err(ValidationError::Invalid {
+            field: "quantity",
+            expected: positive_count,
+            value: quantity.to_string(),
+        });
+    }
+    let positive_price = "a positive number";
+    let price: f64 = parse_field("price per unit", price_per_unit, positive_price)?;
+    if !price.is_finite() || price <= 0.0 {
+        return Err(ValidationError::Invalid {
+            field: "price per unit",
+            expected: positive_price,
+            value: price_per_unit.to_string(),
+        });
+    }
+
+    let sales_tax = tax_rate * price;
+    Ok(Product {
+        product_type: product_type.to_string(),
+        quantity: count,
+        price_per_unit: price,
+        sales_tax,
+        total_price: (price + sales_tax) * count as f64,
+    })
+}
+
 fn read_field(s: &mut Cursive, name: &str) -> Option<String> {
     s.call_on_name(name, |view: &mut EditView| view.get_content())
         .map(|content| content.to_string())
 }
 
-fn save_products_to_file(products: &[Product]) -> Result<()> {
+/// Overwrite `path` with `products` as pretty-printed JSON.
+pub fn save_products_to_file(path: &Path, products: &[Product]) -> Result<()> {
     let file = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
-        .open(FILE_PATH)
-        .with_context(|| format!("opening {FILE_PATH} for writing"))?;
-    serde_json::to_writer_pretty(file, products)
-        .with_context(|| format!("serializing inventory to {FILE_PATH}"))?;
-    Ok(())
+        .open(path)
+        .io_context("opening for writing", path)?;
+    serde_json::to_writer_pretty(file, products).data_context("serializing inventory to", path)
 }
 
-fn load_products_from_file() -> Vec<Product> {
-    let Ok(mut file) = File::open(FILE_PATH) else {
-        return Vec::new();
+/// Read the inventory at `path`.
+///
+/// A missing file is an empty inventory; an unreadable or corrupt one is an error.
+pub fn load_products_from_file(path: &Path) -> Result<Vec<Product>> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).io_context("reading", path),
     };
-    let mut data = String::new();
-    if file.read_to_string(&mut data).is_err() {
-        return Vec::new();
+    serde_json::from_str(&data).data_context("parsing", path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_uses_the_configured_tax_rate() {
+        let product = product_from_input("widget", "2", "10", 0.25).unwrap();
+        assert_eq!(product.sales_tax, 2.5);
+        assert_eq!(product.total_price, 25.0);
+    }
+
+    #[test]
+    fn config_defaults_and_validation() {
+        let config: Config = toml::from_str("inventory_file = \"stock.json\"").unwrap();
+        assert_eq!(config.sales_tax_rate, 0.10);
+        assert_eq!(config.inventory_file, Some(PathBuf::from("stock.json")));
+        assert!(config.validate().is_ok());
+        let negative = Config {
+            sales_tax_rate: -0.1,
+            ..Config::default()
+        };
+        assert!(negative.validate().is_err());
     }
-    serde_json::from_str(&data).unwrap_or_default()
 }
@@ -1,8 +1,18 @@
 //! Binary entry point for the inventory TUI.
 //!
 //! Delegates to [`project_inventory_system::run`]; the binary is intentionally
-//! kept small so the bulk of the logic stays testable from `lib.rs`.
+//! kept small so the bulk of the logic stays testable from `lib.rs`. A startup
+//! error is printed with its cause chain via [`errors::report`] and exits with
+//! the repo-wide code from [`errors::exit_for`] (2 bad config, 3 I/O, 5 parse).
 
-fn main() -> anyhow::Result<()> {
-    project_inventory_system::run()
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match project_inventory_system::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", errors::report(&e));
+            errors::exit_for(&e).into()
+        }
+    }
 }
@@ -2,16 +2,53 @@
 //!
 //! Fetches the public NHTSA "get all manufacturers" endpoint and prints every
 //! manufacturer whose name, common name, or country contains the user-supplied
-//! keyword. The HTTP fetch and filtering loop live here in [`run`]; the
-//! binary entry point in `main.rs` only parses argv.
+//! keyword. Matches go to stdout; progress and skipped records are `tracing`
+//! events on stderr. The HTTP fetch and filtering loop live here in [`run`]; the
+//! binary entry point in `main.rs` only parses [`ManufacturersArgs`], which the
+//! repo-wide `til manufacturers` launcher reuses.
 
-use anyhow::{Context, Result, anyhow};
-use serde_json::Value;
+use clap::Args;
+use errors::{Exit, ExitStatus};
+use ext::{NonEmptyExt, OptionExt};
+use serde_json::{Map, Value};
+use thiserror::Error;
 
 /// NHTSA endpoint that returns every registered manufacturer as JSON.
 pub const API_URL: &str = "https://vpic.nhtsa.dot.gov/api/vehicles/getallmanufacturers?format=json";
 
+/// Why a lookup failed.
+#[derive(Debug, Error)]
+pub enum ManufacturersError {
+    /// The HTTP request did not complete (DNS, TLS, connection, or error status).
+    #[error("HTTP request to the NHTSA API failed")]
+    Request(#[source] reqwest::Error),
+    /// The response body was not JSON.
+    #[error("decoding the NHTSA response as JSON")]
+    Decode(#[source] reqwest::Error),
+    /// The JSON did not have the expected structure.
+    #[error("unexpected API shape: {0}")]
+    Shape(&'static str),
+}
+
+impl ExitStatus for ManufacturersError {
+    fn exit(&self) -> Exit {
+        match self {
+            ManufacturersError::Request(_) => Exit::Network,
+            ManufacturersError::Decode(_) | ManufacturersError::Shape(_) => Exit::Data,
+        }
+    }
+}
+
+/// Command-line arguments for the manufacturer lookup.
+#[derive(Debug, Clone, Args)]
+pub struct ManufacturersArgs {
+    /// Case-sensitive text matched against name, common name, and country
+    pub keyword: String,
+}
+
 /// View into a single manufacturer record borrowed from the parsed JSON.
+///
+/// Fields the API leaves `null`, empty, or blank are `None`.
 pub struct Manufacturer<'a> {
     /// Official manufacturer name (`Mfr_Name`).
     pub name: Option<&'a str>,
@@ -29,13 +66,28 @@ pub trait Contains {
 
 impl<'a> Contains for Manufacturer<'a> {
     fn contains(&self, keyword: &str) -> bool {
-        self.name.unwrap_or_default().contains(keyword)
-            || self.common_name.unwrap_or_default().contains(keyword)
-            || self.country.unwrap_or_default().contains(keyword)
+        self.fields().any(|field| field.contains(keyword))
     }
 }
 
 impl<'a> Manufacturer<'a> {
+    /// Borrow the searchable fields out of one `Results[]` entry.
+    pub fn from_json(obj: &'a Map<String, Value>) -> Manufacturer<'a> {
+        let field = |key: &str| obj.get(key).and_then(Value::as_str).non_empty();
+        Manufacturer {
+            name: field("Mfr_Name"),
+            common_name: field("Mfr_CommonName"),
+            country: field("Country"),
+        }
+    }
+
+    /// The fields that are present, in display order.
+    pub fn fields(&self) -> impl Iterator<Item = &'a str> {
+        [self.name, self.common_name, self.country]
+            .into_iter()
+            .flatten()
+    }
+
     /// Print all fields, one per line, in a stable human-readable format.
     pub fn print_description(&self) {
         println!("Manufacturer: {}", self.name.unwrap_or_default());
@@ -45,40 +97,81 @@ impl<'a> Manufacturer<'a> {
 }
 
 /// Fetch the manufacturers feed and print every record matching `keyword`.
-pub async fn run(keyword: &str) -> Result<()> {
-    println!("Keyword: {keyword}");
+pub async fn run(keyword: &str) -> Result<(), ManufacturersError> {
+    tracing::info!(keyword, url = API_URL, "searching manufacturers");
 
     let response: Value = reqwest::get(API_URL)
         .await
-        .context("HTTP request to NHTSA API failed")?
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(ManufacturersError::Request)?
         .json::<Value>()
         .await
-        .context("decoding NHTSA response as JSON")?;
+        .map_err(ManufacturersError::Decode)?;
 
     let results = response
         .get("Results")
         .and_then(Value::as_array)
-        .ok_or_else(|| anyhow!("unexpected API shape: missing `Results` array"))?;
+        .ok_or(ManufacturersError::Shape("missing `Results` array"))?;
+    tracing::debug!(records = results.len(), "fetched manufacturers");
 
-    let mut found_any = false;
+    let mut matches = 0usize;
     for item in results {
-        let obj = item
-            .as_object()
-            .ok_or_else(|| anyhow!("unexpected API shape: manufacturer entry is not an object"))?;
-        let manufacturer = Manufacturer {
-            name: obj.get("Mfr_Name").and_then(Value::as_str),
-            common_name: obj.get("Mfr_CommonName").and_then(Value::as_str),
-            country: obj.get("Country").and_then(Value::as_str),
+        // One malformed record should not hide the rest of the feed: warn and move on.
+        let Some(obj) = item.as_object().tap_none(
+            || tracing::warn!(entry = %item, "skipping manufacturer entry that is not an object"),
+        ) else {
+            continue;
         };
+        let manufacturer = Manufacturer::from_json(obj);
         if manufacturer.contains(keyword) {
             manufacturer.print_description();
             println!();
-            found_any = true;
+            matches += 1;
         }
     }
 
-    if !found_any {
+    tracing::info!(keyword, matches, "search finished");
+    if matches == 0 {
         println!("No manufacturers found matching '{keyword}'");
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn blank_and_null_fields_are_missing() {
+        let obj = record(json!({
+            "Mfr_Name": "BAYERISCHE MOTOREN WERKE AG (BMW AG)",
+            "Mfr_CommonName": null,
+            "Country": "  ",
+        }));
+        let manufacturer = Manufacturer::from_json(&obj);
+        assert_eq!(manufacturer.common_name, None);
+        assert_eq!(manufacturer.country, None);
+        assert_eq!(
+            manufacturer.fields().collect::<Vec<_>>(),
+            ["BAYERISCHE MOTOREN WERKE AG (BMW AG)"]
+        );
+    }
+
+    #[test]
+    fn contains_searches_every_present_field() {
+        let obj = record(json!({
+            "Mfr_Name": "TESLA, INC.",
+            "Mfr_CommonName": "Tesla",
+            "Country": "UNITED STATES (USA)",
+        }));
+        let manufacturer = Manufacturer::from_json(&obj);
+        assert!(manufacturer.contains("Tesla"));
+        assert!(manufacturer.contains("USA"));
+        assert!(!manufacturer.contains("GERMANY"));
+    }
+}
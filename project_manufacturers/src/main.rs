@@ -1,18 +1,39 @@
 //! Binary entry point for the NHTSA manufacturer lookup CLI.
 //!
-//! Parses one positional argument (the search keyword) and hands off to
-//! [`project_manufacturers::run`].
+//! Parses [`project_manufacturers::ManufacturersArgs`] (the search keyword), sets
+//! up logging, and hands off to [`project_manufacturers::run`]. Errors are printed
+//! with their cause chain via [`errors::report`] and exit with the repo-wide code
+//! (4 network, 5 unexpected response).
 
-use std::env;
+use std::process::ExitCode;
 
-use anyhow::Result;
+use clap::Parser;
+use common::{LogOutput, init_logging};
+use errors::ExitStatus;
+use project_manufacturers::ManufacturersArgs;
+
+#[derive(Parser)]
+#[command(
+    name = "project_manufacturers",
+    about = "Find NHTSA manufacturers by keyword"
+)]
+struct Cli {
+    #[command(flatten)]
+    args: ManufacturersArgs,
+}
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Usage: cargo run -- <manufacturer>");
-        return Ok(());
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = init_logging("info", LogOutput::Stderr) {
+        eprintln!("error: {}", errors::report(&e));
+        return errors::exit_for(&e).into();
+    }
+    match project_manufacturers::run(&cli.args.keyword).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", errors::report(&e));
+            e.exit().into()
+        }
     }
-    project_manufacturers::run(&args[1]).await
 }
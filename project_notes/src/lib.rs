@@ -4,13 +4,68 @@
 //! in append mode (creating it if missing) and writes `<--YYYY-MM-DD HH:MM:SS-->`
 //! followed by a blank line. Useful as a tiny "now" marker in a running notes
 //! file.
+//!
+//! Failures are reported as [`errors::AppError`] (the repo's shared error type),
+//! with the action and file path attached via [`errors::IoContext`].
+//!
+//! Which file to stamp is decided by [`target_file`]: the CLI argument, else
+//! `default_file` from `~/.config/notes/config.toml` ([`Config`]), else
+//! `notes.txt` in the XDG data dir (both via the shared [`common`] crate).
+//!
+//! [`NotesArgs`] is the command line, shared by this crate's binary and the
+//! repo-wide `til notes` launcher; [`run`] does the whole job for either.
 
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
 use chrono::Local;
+use clap::Args;
+use common::AppDirs;
+use errors::{IoContext, Result};
+use serde::Deserialize;
+
+/// Name used for the XDG config and data directories.
+pub const APP_NAME: &str = "notes";
+const DATA_FILE: &str = "notes.txt";
+
+/// `config.toml` for the notes CLI.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// File to stamp when no filename is given on the command line.
+    pub default_file: Option<PathBuf>,
+}
+
+/// Command-line arguments for the notes CLI.
+#[derive(Debug, Clone, Default, Args)]
+pub struct NotesArgs {
+    /// File to append to (default: config `default_file`, else the data dir)
+    pub filename: Option<PathBuf>,
+}
+
+/// Resolve the target file from `args` and the user's config, then stamp it.
+///
+/// Expects logging to be set up by the caller.
+pub fn run(args: &NotesArgs) -> Result<()> {
+    let dirs = AppDirs::new(APP_NAME)?;
+    let config: Config = dirs.load_config()?;
+    tracing::debug!(config = %dirs.config_file().display(), ?config, "loaded config");
+    let filename = target_file(args.filename.clone(), &config, &dirs)?;
+    append_timestamp(&filename)?;
+    tracing::info!(file = %filename.display(), "appended timestamp");
+    Ok(())
+}
+
+/// The file to append to: `arg` if given, else the configured default, else
+/// `notes.txt` in the data dir (which is created so the append can succeed).
+pub fn target_file(arg: Option<PathBuf>, config: &Config, dirs: &AppDirs) -> Result<PathBuf> {
+    if let Some(path) = arg.or_else(|| config.default_file.clone()) {
+        return Ok(path);
+    }
+    dirs.ensure_data_dir()?;
+    Ok(dirs.data_file(DATA_FILE))
+}
 
 /// Append a timestamp marker to `filename`, creating the file if necessary.
 pub fn append_timestamp(filename: &Path) -> Result<()> {
@@ -19,11 +74,41 @@ pub fn append_timestamp(filename: &Path) -> Result<()> {
         .create(true)
         .append(true)
         .open(filename)
-        .with_context(|| format!("opening {} for append", filename.display()))?;
+        .io_context("opening for append", filename)?;
     file.write_all(b"<--")
         .and_then(|()| file.write_all(now_string.as_bytes()))
         .and_then(|()| file.write_all(b"-->"))
         .and_then(|()| file.write_all(b"\n\n"))
-        .with_context(|| format!("writing timestamp to {}", filename.display()))?;
+        .io_context("writing timestamp to", filename)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argument_then_config_then_data_dir() {
+        let data = std::env::temp_dir().join(format!("notes-test-{}", std::process::id()));
+        let dirs = AppDirs::with_dirs("/unused/config", &data);
+        let config = Config {
+            default_file: Some(PathBuf::from("journal.txt")),
+        };
+
+        let arg = Some(PathBuf::from("today.txt"));
+        assert_eq!(
+            target_file(arg, &config, &dirs).unwrap(),
+            Path::new("today.txt")
+        );
+        assert_eq!(
+            target_file(None, &config, &dirs).unwrap(),
+            Path::new("journal.txt")
+        );
+        assert_eq!(
+            target_file(None, &Config::default(), &dirs).unwrap(),
+            data.join("notes.txt")
+        );
+        assert!(data.is_dir());
+        std::fs::remove_dir_all(&data).unwrap();
+    }
+}
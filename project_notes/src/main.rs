@@ -1,20 +1,35 @@
 //! Binary entry point for the notes timestamp appender.
 //!
-//! Parses one positional argument (the target filename) and hands off to
-//! [`project_notes::append_timestamp`].
+//! Parses [`project_notes::NotesArgs`] (an optional target filename), sets up
+//! logging, and hands off to [`project_notes::run`]. Errors are printed with
+//! their cause chain via [`errors::report`] and exit with the repo-wide code
+//! from [`errors::exit_for`] (2 bad input, 3 I/O).
 
-use std::env;
-use std::path::PathBuf;
+use std::process::ExitCode;
 
-use anyhow::Result;
+use clap::Parser;
+use common::{LogOutput, init_logging};
+use project_notes::NotesArgs;
 
-fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: cargo run -- <filename>");
-        std::process::exit(1);
+#[derive(Parser)]
+#[command(
+    name = "project_notes",
+    about = "Append a local-time marker to a notes file"
+)]
+struct Cli {
+    #[command(flatten)]
+    args: NotesArgs,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result =
+        init_logging("info", LogOutput::Stderr).and_then(|()| project_notes::run(&cli.args));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", errors::report(&e));
+            errors::exit_for(&e).into()
+        }
     }
-    let filename = PathBuf::from(&args[1]);
-    println!("filename: {}", filename.display());
-    project_notes::append_timestamp(&filename)
 }
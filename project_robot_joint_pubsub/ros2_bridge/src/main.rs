@@ -0,0 +1,209 @@
+//! `ros2_bridge`: forward joint states between the ZMQ pub/sub and a ROS 2 graph.
+//!
+//! - `--direction zmq-to-ros` subscribes to `RobotState` on ZMQ and publishes
+//!   `sensor_msgs/JointState` on `--ros-topic`, so ROS tools see the robot.
+//! - `--direction ros-to-zmq` subscribes to `JointState` on ROS and publishes
+//!   `RobotState` on ZMQ, so a ROS stack can drive the MuJoCo subscriber.
+//!
+//! The field mapping lives in `project_robot_joint_pubsub::joint_state`; this
+//! binary only copies it into the generated ROS message types. Everything from
+//! `--ros-args` on is handed to ROS untouched (`--ros-args -r __node:=left_bridge`).
+
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::ConfigArgs;
+use project_robot_joint_pubsub::joint_state::{self, JointState};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::{sim, Format, Publisher, Subscriber, ZmqTransport};
+use sensor_msgs::msg::JointState as RosJointState;
+
+/// How long one ZMQ receive waits before checking whether ROS is shutting down.
+const POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Direction {
+    /// ZMQ `RobotState` → ROS `JointState`.
+    ZmqToRos,
+    /// ROS `JointState` → ZMQ `RobotState`.
+    RosToZmq,
+}
+
+#[derive(Parser)]
+#[command(name = "ros2_bridge")]
+#[command(about = "Bridge RobotState ZMQ messages to and from ROS 2 sensor_msgs/JointState")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Which way messages flow
+    #[arg(long, value_enum)]
+    direction: Direction,
+    /// ROS 2 topic carrying `sensor_msgs/JointState`
+    #[arg(long, default_value = "/joint_states")]
+    ros_topic: String,
+    /// ZMQ topic (default: config `topic`, else robot_joints)
+    #[arg(long)]
+    zmq_topic: Option<String>,
+    /// ZMQ address to connect to with `zmq-to-ros` (default: config `connect`)
+    #[arg(long)]
+    connect: Option<String>,
+    /// ZMQ address to bind with `ros-to-zmq` (default: config `bind`)
+    #[arg(long)]
+    bind: Option<String>,
+    /// Payload encoding for `ros-to-zmq` (default: config `format`, else json);
+    /// `zmq-to-ros` detects it
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// With `zmq-to-ros`, also accept single-frame "<topic> <json>" messages from
+    /// older publishers
+    #[arg(long)]
+    legacy_frames: bool,
+    /// `robot_id` for `ros-to-zmq` messages whose `header.frame_id` is empty
+    #[arg(long, default_value = sim::ROBOT_ID)]
+    robot_id: String,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    let (ours, ros) = split_ros_args(std::env::args());
+    match run(Cli::parse_from(ours), ros) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+/// Split argv at `--ros-args`: the bridge's own flags, and the program name
+/// followed by everything ROS should see.
+fn split_ros_args(args: impl Iterator<Item = String>) -> (Vec<String>, Vec<String>) {
+    let mut ours: Vec<String> = args.collect();
+    let program = ours.first().cloned().unwrap_or_default();
+    let ros_start = ours
+        .iter()
+        .position(|arg| arg == "--ros-args")
+        .unwrap_or(ours.len());
+    let mut ros = vec![program];
+    ros.extend(ours.drain(ros_start..));
+    (ours, ros)
+}
+
+fn run(cli: Cli, ros_args: Vec<String>) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let topic = cli.zmq_topic.clone().unwrap_or(config.topic.clone());
+
+    let context = rclrs::Context::new(ros_args)?;
+    let node = rclrs::create_node(&context, "ros2_bridge")?;
+    match cli.direction {
+        Direction::ZmqToRos => {
+            let connect = cli.connect.clone().unwrap_or(config.connect);
+            zmq_to_ros(&context, &node, &cli, &connect, &topic)
+        }
+        Direction::RosToZmq => {
+            let bind = cli.bind.clone().unwrap_or(config.bind);
+            let format = cli.format.unwrap_or(config.format);
+            ros_to_zmq(node, &cli, &bind, &topic, format)
+        }
+    }
+}
+
+fn zmq_to_ros(
+    context: &rclrs::Context,
+    node: &rclrs::Node,
+    cli: &Cli,
+    connect: &str,
+    topic: &str,
+) -> Result<()> {
+    let mut transport = ZmqTransport::connect(&zmq::Context::new(), connect)?;
+    if cli.legacy_frames {
+        transport = transport.accept_legacy_frames();
+    }
+    let subscriber = Subscriber::new(transport, topic)?;
+    let publisher =
+        node.create_publisher::<RosJointState>(&cli.ros_topic, rclrs::QOS_PROFILE_DEFAULT)?;
+    tracing::info!(%connect, zmq_topic = %topic, ros_topic = %cli.ros_topic, "bridging ZMQ to ROS 2");
+
+    while context.ok() {
+        match subscriber.recv(POLL) {
+            Ok(Some(state)) => {
+                let (sec, nanosec) = joint_state::stamp(state.timestamp);
+                let msg = joint_state::to_joint_state(&state, sec, nanosec);
+                publisher.publish(to_ros(msg))?;
+                tracing::debug!(timestamp = state.timestamp, "forwarded to ROS");
+            }
+            Ok(None) => {}
+            Err(e) if e.exit() == Exit::Network => return Err(e.into()),
+            Err(e) => tracing::warn!(error = %errors::report(&e), "skipping message"),
+        }
+    }
+    Ok(())
+}
+
+fn ros_to_zmq(
+    node: Arc<rclrs::Node>,
+    cli: &Cli,
+    bind: &str,
+    topic: &str,
+    format: Format,
+) -> Result<()> {
+    let transport = ZmqTransport::bind(&zmq::Context::new(), bind)?;
+    let publisher = Publisher::new(transport, topic).with_format(format);
+    let robot_id = cli.robot_id.clone();
+    let mut sequence = 0u64;
+    // The callback owns the publisher; `spin` runs it on this thread.
+    let _subscription = node.create_subscription::<RosJointState, _>(
+        &cli.ros_topic,
+        rclrs::QOS_PROFILE_DEFAULT,
+        move |msg: RosJointState| {
+            sequence += 1;
+            match joint_state::from_joint_state(&from_ros(msg), &robot_id, sequence) {
+                Ok(state) => {
+                    if let Err(e) = publisher.publish(&state) {
+                        tracing::error!(error = %errors::report(&e), "ZMQ publish failed");
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "skipping JointState"),
+            }
+        },
+    )?;
+    tracing::info!(%bind, zmq_topic = %topic, ros_topic = %cli.ros_topic, "bridging ROS 2 to ZMQ");
+    rclrs::spin(node)?;
+    Ok(())
+}
+
+/// Now as ROS `builtin_interfaces/Time` fields.
+fn to_ros(msg: JointState) -> RosJointState {
+    RosJointState {
+        header: std_msgs::msg::Header {
+            stamp: builtin_interfaces::msg::Time {
+                sec: msg.stamp_sec,
+                nanosec: msg.stamp_nanosec,
+            },
+            frame_id: msg.frame_id,
+        },
+        name: msg.name,
+        position: msg.position,
+        velocity: msg.velocity,
+        effort: msg.effort,
+    }
+}
+
+fn from_ros(msg: RosJointState) -> JointState {
+    JointState {
+        stamp_sec: msg.header.stamp.sec,
+        stamp_nanosec: msg.header.stamp.nanosec,
+        frame_id: msg.header.frame_id,
+        name: msg.name,
+        position: msg.position,
+        velocity: msg.velocity,
+        effort: msg.effort,
+    }
+}
@@ -0,0 +1,172 @@
+//! Command-line flags the binaries share, each layered over [`Config`]: a flag
+//! given wins, otherwise the config file's (or `ROBOT_PUBSUB_*`) value does.
+//! `#[command(flatten)]` the groups a binary needs into its `Cli`, so the same
+//! flag reads and resolves the same way everywhere.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Args;
+
+use crate::{Config, Format, TransportKind};
+
+/// `--config`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+pub struct ConfigArgs {
+    /// Settings file (default: $ROBOT_PUBSUB_CONFIG, else ~/.config/robot_joint_pubsub/config.toml)
+    #[arg(long, value_name = "FILE", global = true)]
+    pub config: Option<PathBuf>,
+}
+
+impl ConfigArgs {
+    /// The settings `--config` names, or the default file's.
+    pub fn load(&self) -> errors::Result<Config> {
+        Config::load(self.config.as_deref())
+    }
+}
+
+/// `--transport` and the MQTT `--broker`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+pub struct TransportArgs {
+    /// Transport to use (default: config `transport`, else zmq)
+    #[arg(long, value_enum)]
+    pub transport: Option<TransportKind>,
+    /// MQTT broker as host[:port] (default: config `broker`, else localhost:1883)
+    #[arg(long)]
+    pub broker: Option<String>,
+}
+
+impl TransportArgs {
+    pub fn kind(&self, config: &Config) -> TransportKind {
+        self.transport.unwrap_or(config.transport)
+    }
+
+    pub fn broker(&self, config: &Config) -> String {
+        self.broker.clone().unwrap_or_else(|| config.broker.clone())
+    }
+}
+
+/// Where and how a publisher sends: `--bind`, `--format`, `--split-by-finger`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+pub struct PublishArgs {
+    /// ZMQ bind address, or >ENDPOINT to connect to a `broker` (default: config `bind`, else tcp://*:5555)
+    #[arg(short, long)]
+    pub bind: Option<String>,
+    /// Payload encoding (default: config `format`, else json)
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
+    /// Publish each finger on its own sub-topic (<topic>/thumb, <topic>/index, ...)
+    #[arg(long)]
+    pub split_by_finger: bool,
+}
+
+impl PublishArgs {
+    pub fn bind(&self, config: &Config) -> String {
+        self.bind.clone().unwrap_or_else(|| config.bind.clone())
+    }
+
+    pub fn format(&self, config: &Config) -> Format {
+        self.format.unwrap_or(config.format)
+    }
+}
+
+/// `--heartbeat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Args)]
+pub struct HeartbeatArgs {
+    /// Heartbeat interval in milliseconds, 0 for none (default: config `heartbeat_ms`, else 1000)
+    #[arg(long, value_name = "MS")]
+    pub heartbeat: Option<u64>,
+}
+
+impl HeartbeatArgs {
+    pub fn millis(&self, config: &Config) -> u64 {
+        self.heartbeat.unwrap_or(config.heartbeat_ms)
+    }
+
+    /// The interval between heartbeats, or `None` when turned off.
+    pub fn interval(&self, config: &Config) -> Option<Duration> {
+        Some(self.millis(config))
+            .filter(|&ms| ms > 0)
+            .map(Duration::from_millis)
+    }
+}
+
+/// The ZMQ `--connect` address of a single-publisher receiver.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+pub struct ConnectArgs {
+    /// ZMQ connect address (default: config `connect`, else tcp://localhost:5555)
+    #[arg(short, long)]
+    pub connect: Option<String>,
+}
+
+impl ConnectArgs {
+    pub fn connect(&self, config: &Config) -> String {
+        self.connect
+            .clone()
+            .unwrap_or_else(|| config.connect.clone())
+    }
+}
+
+/// How a receiver decodes: `--format` and `--legacy-frames`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Args)]
+pub struct DecodeArgs {
+    /// Decode payloads only as this format (default: detect each one)
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
+    /// Also accept single-frame "<topic> <json>" ZMQ messages from older publishers
+    #[arg(long)]
+    pub legacy_frames: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        transport: TransportArgs,
+        #[command(flatten)]
+        publish: PublishArgs,
+        #[command(flatten)]
+        heartbeat: HeartbeatArgs,
+    }
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["bin"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn flags_left_out_come_from_the_config() {
+        let config = Config {
+            bind: "tcp://*:6000".into(),
+            broker: "mqtt.lab:1883".into(),
+            transport: TransportKind::Mqtt,
+            format: Format::MessagePack,
+            heartbeat_ms: 250,
+            ..Config::default()
+        };
+        let cli = parse(&[]);
+        assert_eq!(cli.transport.kind(&config), TransportKind::Mqtt);
+        assert_eq!(cli.transport.broker(&config), "mqtt.lab:1883");
+        assert_eq!(cli.publish.bind(&config), "tcp://*:6000");
+        assert_eq!(cli.publish.format(&config), Format::MessagePack);
+        assert_eq!(
+            cli.heartbeat.interval(&config),
+            Some(Duration::from_millis(250))
+        );
+
+        let cli = parse(&[
+            "--transport",
+            "zmq",
+            "-b",
+            "tcp://*:7000",
+            "--heartbeat",
+            "0",
+        ]);
+        assert_eq!(cli.transport.kind(&config), TransportKind::Zmq);
+        assert_eq!(cli.publish.bind(&config), "tcp://*:7000");
+        assert_eq!(cli.heartbeat.interval(&config), None);
+    }
+}
@@ -0,0 +1,55 @@
+//! ZMQ broker: one well-known pair of endpoints that every publisher and
+//! subscriber connects to, instead of each publisher binding a port of its own.
+//!
+//! Publishers (`publisher`, the GUI, `publisher_gamepad`, `playback`, …)
+//! connect to `--frontend` by binding a `>`-prefixed endpoint,
+//! `--bind '>tcp://broker-host:5559'`; subscribers (the viewer, `recorder`,
+//! `subscriber_tui`, …) connect to `--backend` as they would to a publisher,
+//! `--connect tcp://broker-host:5560`. Subscriptions travel back through the
+//! broker, so each subscriber still only receives the topics it asked for.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::ZmqBroker;
+use std::process::ExitCode;
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "broker")]
+#[command(about = "Forward robot joint angles from many ZMQ publishers to many subscribers")]
+struct Cli {
+    /// Address publishers connect to (XSUB)
+    #[arg(long, default_value = "tcp://*:5559")]
+    frontend: String,
+    /// Address subscribers connect to (XPUB)
+    #[arg(long, default_value = "tcp://*:5560")]
+    backend: String,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let broker = ZmqBroker::bind(&Context::new(), &cli.frontend, &cli.backend)
+        .with_context(|| format!("binding {} and {}", cli.frontend, cli.backend))?;
+    tracing::info!(
+        frontend = %cli.frontend,
+        backend = %cli.backend,
+        "forwarding; publishers connect to the frontend, subscribers to the backend"
+    );
+    // Runs until the process is interrupted.
+    broker.run()?;
+    Ok(())
+}
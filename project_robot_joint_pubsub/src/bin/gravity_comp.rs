@@ -0,0 +1,200 @@
+//! Gravity compensation binary: subscribes to robot joint angles via ZMQ or MQTT
+//! and republishes each state with the torques that hold its pose against gravity.
+//!
+//! Behind the scenes (high level):
+//! - Loads the MJCF model headlessly (no viewer window).
+//! - For each `RobotState`, sets the joints' positions (every coordinate of ball
+//!   and free joints that carry them), zero velocities and accelerations, and runs `mj_inverse`;
+//!   each joint's `qfrc_inverse` is the torque it needs to hold still.
+//! - Publishes the state with those torques on `<topic>_gravity` (see `gravity`
+//!   in the library), with heartbeats as the headless publisher sends them.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, ConnectArgs, HeartbeatArgs, TransportArgs};
+use project_robot_joint_pubsub::gravity;
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    Format, MqttTransport, Publisher, ShmTransport, Subscriber, Transport, TransportKind,
+    ZmqTransport,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use zmq::Context;
+
+use mujoco_rs::prelude::*;
+
+/// How long one receive waits, i.e. how often heartbeats get a chance to go out.
+const POLL: Duration = Duration::from_millis(100);
+
+#[derive(Parser)]
+#[command(name = "gravity_comp")]
+#[command(about = "Republish robot joint angles with gravity-compensation torques")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    connect: ConnectArgs,
+    /// ZMQ bind address for the compensated states
+    #[arg(short, long, default_value = "tcp://*:5558")]
+    bind: String,
+    /// Topic to compensate (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    /// Topic to publish on (default: <topic>_gravity)
+    #[arg(long)]
+    out_topic: Option<String>,
+    /// Payload encoding of the compensated states (default: config `format`, else json)
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// MJCF model path (supports `<include/>`)
+    /// (default: config `model`, else pro-models/example/scenes/left_hand_scene.xml)
+    #[arg(long)]
+    model: Option<PathBuf>,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let out_topic = cli.out_topic.unwrap_or_else(|| gravity::topic(&topic));
+    let format = cli.format.unwrap_or(config.format);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    let model_path = cli.model.unwrap_or_else(|| config.model.clone());
+    let model_path = std::fs::canonicalize(&model_path)
+        .with_context(|| format!("resolving model path '{}'", model_path.display()))?;
+    let model = MjModel::from_xml(&model_path)
+        .map_err(|e| anyhow::anyhow!("Failed to load MJCF '{}': {:?}", model_path.display(), e))?;
+    let mut data = MjData::new(&model);
+
+    let mut joint_cache: HashMap<String, MjJointDataInfo> = HashMap::new();
+    for id in 0..model.ffi().njnt.max(0) {
+        let Some(name) = model.id_to_name(MjtObj::mjOBJ_JOINT, id) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+        if let Some(info) = data.joint(name) {
+            joint_cache.insert(name.to_string(), info);
+        }
+    }
+
+    let (receiver, sender): (Box<dyn Transport>, Box<dyn Transport>) = match transport {
+        TransportKind::Zmq => {
+            let connect = cli.connect.connect(&config);
+            tracing::info!(
+                %connect,
+                bind = %cli.bind,
+                %topic,
+                %out_topic,
+                "starting gravity compensation"
+            );
+            let ctx = Context::new();
+            (
+                Box::new(ZmqTransport::connect(&ctx, &connect)?),
+                Box::new(ZmqTransport::bind(&ctx, &cli.bind)?),
+            )
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, %out_topic, "starting gravity compensation over MQTT");
+            (
+                Box::new(MqttTransport::connect(&broker)?),
+                Box::new(MqttTransport::connect(&broker)?),
+            )
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, %out_topic, "starting gravity compensation over shared memory");
+            (
+                Box::new(ShmTransport::open(&topic)?),
+                Box::new(ShmTransport::create(&out_topic)?),
+            )
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, %out_topic, "starting gravity compensation over Zenoh");
+            (open_zenoh(&config.zenoh)?, open_zenoh(&config.zenoh)?)
+        }
+    };
+    let subscriber = Subscriber::new(receiver, &topic)?;
+    let publisher = Publisher::new(sender, &out_topic).with_format(format);
+    tracing::info!(
+        model = %model_path.display(),
+        joints = joint_cache.len(),
+        "compensating gravity"
+    );
+
+    let mut unknown = HashSet::new();
+    let mut last_heartbeat: Option<Instant> = None;
+    loop {
+        match subscriber.recv(POLL) {
+            Ok(Some(state)) => {
+                for joint in &state.joints {
+                    match joint_cache.get(&joint.joint_name) {
+                        Some(joint_info) => joint_info
+                            .view_mut(&mut data)
+                            .qpos
+                            .iter_mut()
+                            .zip(joint.positions())
+                            .for_each(|(qpos, &position)| *qpos = position),
+                        None => {
+                            if unknown.insert(joint.joint_name.clone()) {
+                                tracing::warn!(
+                                    joint = %joint.joint_name,
+                                    "joint not in the model; passing its torque through"
+                                );
+                            }
+                        }
+                    }
+                }
+                // At rest, the force that holds the pose is what counters gravity (and springs).
+                data.qvel_mut().fill(0.0);
+                data.qacc_mut().fill(0.0);
+                data.inverse();
+                let compensated = gravity::compensated(&state, |name| {
+                    let joint_info = joint_cache.get(name)?;
+                    joint_info.view(&data).qfrc_inverse.first().copied()
+                });
+                publisher.publish(&compensated)?;
+                tracing::debug!(timestamp = state.timestamp, "compensated");
+            }
+            Ok(None) => {}
+            Err(e) if e.exit() == Exit::Network => {
+                tracing::error!(error = %errors::report(&e), "receive error");
+            }
+            Err(e) => {
+                tracing::warn!(error = %errors::report(&e), "skipping message");
+            }
+        }
+
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+    }
+}
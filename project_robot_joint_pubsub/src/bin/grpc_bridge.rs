@@ -0,0 +1,184 @@
+//! gRPC bridge: serves the `RobotJoints` gRPC service for gRPC-first tooling and
+//! bridges it to ZMQ or MQTT, in both directions.
+//!
+//! Behind the scenes (high level):
+//! - Subscribes to `--state-topic` (default `robot_joints_actual`, where
+//!   `subscriber --publish-feedback` sends the simulated joints) and streams every
+//!   state it receives to each `StreamStates` caller (see `grpc` in the library).
+//! - Publishes every `JointCommand` a `SendCommands` caller sends as a `RobotState`
+//!   on `--topic`, as the headless publisher would, so the subscriber follows it.
+//! - Heartbeats go out as the headless publisher sends them, commands or not, echoing
+//!   the subscriber's on `--state-topic` so it can tell how far apart the clocks are.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, TransportArgs};
+use project_robot_joint_pubsub::clock::ClockSync;
+use project_robot_joint_pubsub::grpc::{GrpcService, DEFAULT_BACKLOG};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    feedback, sim, AsyncSubscriber, Format, MqttTransport, Publisher, ShmTransport, Subscriber,
+    Transport, TransportKind, ZmqTransport,
+};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "grpc_bridge")]
+#[command(about = "Serve robot joint states and take joint commands over gRPC")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Address the gRPC server listens on
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    grpc_listen: String,
+    /// States a StreamStates caller may fall behind by before its stream ends
+    #[arg(long, default_value_t = DEFAULT_BACKLOG)]
+    backlog: usize,
+    /// `robot_id` on commands that name none
+    #[arg(long, default_value = sim::ROBOT_ID)]
+    robot_id: String,
+    #[command(flatten)]
+    transport: TransportArgs,
+    /// ZMQ bind address for commands, or >ENDPOINT to connect to a `broker` (default: config `bind`, else tcp://*:5555)
+    #[arg(short, long)]
+    bind: Option<String>,
+    /// Topic to publish commands on (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    /// Payload encoding of the commands (default: config `format`, else json)
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Topic whose states StreamStates serves
+    #[arg(long, default_value = feedback::TOPIC)]
+    state_topic: String,
+    /// ZMQ endpoint to receive --state-topic from; unused with --transport shm, which reads its ring
+    #[arg(long, value_name = "ENDPOINT", default_value = "tcp://localhost:5561")]
+    state_connect: String,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let format = cli.format.unwrap_or(config.format);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    let (receiver, sender): (Box<dyn Transport>, Box<dyn Transport>) = match transport {
+        TransportKind::Zmq => {
+            let bind = cli.bind.unwrap_or(config.bind);
+            tracing::info!(
+                %bind,
+                state_connect = %cli.state_connect,
+                %topic,
+                state_topic = %cli.state_topic,
+                "starting gRPC bridge"
+            );
+            let ctx = Context::new();
+            (
+                Box::new(ZmqTransport::connect(&ctx, &cli.state_connect)?),
+                Box::new(ZmqTransport::bind(&ctx, &bind)?),
+            )
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, state_topic = %cli.state_topic, "starting gRPC bridge over MQTT");
+            (
+                Box::new(MqttTransport::connect(&broker)?),
+                Box::new(MqttTransport::connect(&broker)?),
+            )
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, state_topic = %cli.state_topic, "starting gRPC bridge over shared memory");
+            (
+                Box::new(ShmTransport::open(&cli.state_topic)?),
+                Box::new(ShmTransport::create(&topic)?),
+            )
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, state_topic = %cli.state_topic, "starting gRPC bridge over Zenoh");
+            (open_zenoh(&config.zenoh)?, open_zenoh(&config.zenoh)?)
+        }
+    };
+    let clock = Arc::new(ClockSync::default());
+    let subscriber =
+        Subscriber::new(receiver, &cli.state_topic)?.with_clock_sync(Arc::clone(&clock));
+    let publisher = Publisher::new(sender, &topic)
+        .with_format(format)
+        .with_clock_sync(clock);
+
+    let listener = TcpListener::bind(&cli.grpc_listen)
+        .await
+        .with_context(|| format!("listening on {}", cli.grpc_listen))?;
+    tracing::info!(
+        grpc_listen = %listener.local_addr()?,
+        robot_id = %cli.robot_id,
+        ?format,
+        "serving RobotJoints"
+    );
+    let (service, mut commands) = GrpcService::new(cli.backlog, &cli.robot_id);
+    let server = service.clone().serve(listener);
+    tokio::pin!(server);
+
+    // Without heartbeats the tick below is never polled, so its period does not matter.
+    let mut heartbeats = tokio::time::interval(heartbeat.unwrap_or(Duration::from_secs(1)));
+    heartbeats.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // `GrpcService::send` never waits, so streaming keeps up with receiving.
+    let mut states = AsyncSubscriber::spawn(subscriber);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            received = states.recv() => match received.context("receiving thread died")? {
+                Ok(state) => {
+                    let clients = service.send(&state);
+                    tracing::trace!(timestamp = state.timestamp, clients, "streamed");
+                }
+                Err(e) if e.exit() == Exit::Network => {
+                    tracing::error!(error = %errors::report(&e), "receive error");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %errors::report(&e), "skipping message");
+                }
+            },
+            Some(command) = commands.recv() => {
+                publisher.publish(&command)?;
+                tracing::debug!(
+                    sequence = command.sequence,
+                    robot_id = %command.robot_id,
+                    joints = command.joints.len(),
+                    "published command"
+                );
+            }
+            _ = heartbeats.tick(), if heartbeat.is_some() => publisher.heartbeat()?,
+            served = &mut server => return served.context("serving gRPC"),
+            signal = &mut ctrl_c => {
+                signal.context("waiting for Ctrl-C")?;
+                tracing::info!("bridge stopped");
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Model info binary: loads an MJCF headlessly and reports what is in it, for
+//! writing the configs that name its joints.
+//!
+//! - `list-joints` prints each joint's name, type, DoF count, and range (see
+//!   `joint_table` in the library), so `--filter-prefix`, `--joints`,
+//!   `--joint-map`, and preset files can be filled in without reading the XML.
+//!   With `--prefix`, only the joints the GUI publisher's `--filter-prefix` would
+//!   show; with `--json`, one array for scripts instead of a table.
+//! - `validate` reports joints without limits, repeated names, and actuators
+//!   that drive no named joint (see `model_check` in the library). With
+//!   `--presets`, `--gamepad-map`, or `--udp-layout`, it also reports joints
+//!   those files name that the model lacks. It exits with 5 on any error, or
+//!   on warnings too with `--strict`, so a script can run it before a session.
+
+use anyhow::{Context as _, Result};
+use clap::{Args, Parser, Subcommand};
+use errors::Exit;
+use project_robot_joint_pubsub::args::ConfigArgs;
+use project_robot_joint_pubsub::gamepad::GamepadMap;
+use project_robot_joint_pubsub::joint_filter::JointFilter;
+use project_robot_joint_pubsub::joint_table::{self, JointKind, JointRow};
+use project_robot_joint_pubsub::model_check::{self, ActuatorRow};
+use project_robot_joint_pubsub::presets::Presets;
+use project_robot_joint_pubsub::udp_bridge::PacketLayout;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use mujoco_rs::prelude::*;
+
+#[derive(Parser)]
+#[command(name = "model_info")]
+#[command(about = "Report the joints of a MuJoCo model and check it")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// MJCF model path (supports `<include/>`)
+    /// (default: config `model`, else pro-models/example/scenes/left_hand_scene.xml)
+    #[arg(long, global = true)]
+    model: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print every joint's name, type, DoF count, and range
+    ListJoints(ListJointsArgs),
+    /// Check joint limits, names, and actuators, and the joints other files name
+    Validate(ValidateArgs),
+}
+
+#[derive(Args)]
+struct ListJointsArgs {
+    /// Only joints whose names start with one of these (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    prefix: Vec<String>,
+    /// Print a JSON array instead of a table
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    /// Presets file whose poses should name only model joints
+    #[arg(long, value_name = "FILE")]
+    presets: Option<PathBuf>,
+    /// Gamepad map whose bindings should name only model joints
+    #[arg(long, value_name = "FILE")]
+    gamepad_map: Option<PathBuf>,
+    /// UDP packet layout whose fields should name only model joints
+    #[arg(long, value_name = "FILE")]
+    udp_layout: Option<PathBuf>,
+    /// Fail on warnings (unlimited joints) as well as errors
+    #[arg(long)]
+    strict: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(exit) => exit.into(),
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<Exit> {
+    let config = cli.config.load()?;
+    let model = load_model(&cli.model.unwrap_or(config.model))?;
+    match cli.command {
+        Command::ListJoints(args) => list_joints(&model, args).map(|()| Exit::Success),
+        Command::Validate(args) => validate(&model, args),
+    }
+}
+
+fn load_model(path: &Path) -> Result<MjModel> {
+    let path = std::fs::canonicalize(path)
+        .with_context(|| format!("resolving model path '{}'", path.display()))?;
+    MjModel::from_xml(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to load MJCF '{}': {:?}", path.display(), e))
+}
+
+fn list_joints(model: &MjModel, args: ListJointsArgs) -> Result<()> {
+    let filter = JointFilter::new(Vec::new(), args.prefix);
+    let rows: Vec<JointRow> = joint_rows(model)
+        .into_iter()
+        .filter(|row| filter.selects(&row.name))
+        .collect();
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        print!("{}", joint_table::render(&rows));
+    }
+    Ok(())
+}
+
+/// Every joint of `model`, in id order.
+fn joint_rows(model: &MjModel) -> Vec<JointRow> {
+    let (jnt_type, limited, range) = (model.jnt_type(), model.jnt_limited(), model.jnt_range());
+    (0..model.ffi().njnt.max(0) as usize)
+        .map(|id| JointRow {
+            name: model
+                .id_to_name(MjtObj::mjOBJ_JOINT, id as i32)
+                .unwrap_or_default()
+                .to_string(),
+            kind: match jnt_type[id] {
+                MjtJoint::mjJNT_FREE => JointKind::Free,
+                MjtJoint::mjJNT_BALL => JointKind::Ball,
+                MjtJoint::mjJNT_SLIDE => JointKind::Slide,
+                MjtJoint::mjJNT_HINGE => JointKind::Hinge,
+            },
+            range: limited[id].then(|| (range[id][0] as f64, range[id][1] as f64)),
+        })
+        .collect()
+}
+
+/// Every actuator of `model`, in id order.
+fn actuator_rows(model: &MjModel) -> Vec<ActuatorRow> {
+    model
+        .actuator_trntype()
+        .iter()
+        .zip(model.actuator_trnid())
+        .enumerate()
+        .map(|(id, (trn, target))| ActuatorRow {
+            name: model
+                .id_to_name(MjtObj::mjOBJ_ACTUATOR, id as i32)
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| format!("#{id}"), str::to_string),
+            joint: (*trn == MjtTrn::mjTRN_JOINT)
+                .then(|| model.id_to_name(MjtObj::mjOBJ_JOINT, target[0]))
+                .flatten()
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+        })
+        .collect()
+}
+
+fn validate(model: &MjModel, args: ValidateArgs) -> Result<Exit> {
+    let joints = joint_rows(model);
+    let actuators = actuator_rows(model);
+    let mut problems = model_check::check_model(&joints, &actuators);
+
+    if let Some(path) = &args.presets {
+        // `Presets::load` takes a missing file as no presets; here it is a typo.
+        std::fs::metadata(path).with_context(|| format!("reading {}", path.display()))?;
+        for (name, pose) in Presets::load(path)?.iter() {
+            let file = format!("{} (preset {name})", path.display());
+            let names = pose.keys().map(String::as_str);
+            problems.extend(model_check::check_references(&joints, &file, names));
+        }
+    }
+    if let Some(path) = &args.gamepad_map {
+        let names = GamepadMap::load(path)?.joint_names();
+        let names = names.iter().map(String::as_str);
+        let file = path.display().to_string();
+        problems.extend(model_check::check_references(&joints, &file, names));
+    }
+    if let Some(path) = &args.udp_layout {
+        let layout = PacketLayout::load(path)?;
+        let names = layout.joints.iter().map(String::as_str);
+        let names = names.filter(|name| !name.is_empty());
+        let file = path.display().to_string();
+        problems.extend(model_check::check_references(&joints, &file, names));
+    }
+
+    let errors = problems.iter().filter(|problem| problem.is_error()).count();
+    let warnings = problems.len() - errors;
+    for problem in &problems {
+        let level = if problem.is_error() {
+            "error"
+        } else {
+            "warning"
+        };
+        println!("{level}: {problem}");
+    }
+    println!(
+        "{} joints, {} actuators: {errors} errors, {warnings} warnings",
+        joints.len(),
+        actuators.len()
+    );
+    Ok(if errors > 0 || (args.strict && warnings > 0) {
+        Exit::Data
+    } else {
+        Exit::Success
+    })
+}
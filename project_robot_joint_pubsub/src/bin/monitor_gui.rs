@@ -0,0 +1,287 @@
+//! Monitor GUI binary: subscribes to robot joint angles via ZMQ or MQTT and plots
+//! them live (egui_plot), to check a motion is smooth rather than reading floats.
+//!
+//! ## What this does
+//! - Receives `RobotState`s on a thread of its own, like the recorder.
+//! - Keeps the last `--window` seconds of them (see `history` in the library) and
+//!   plots the checked joints' angles or velocities against receive time.
+//! - **Pause** freezes the plot (states arriving meanwhile are dropped);
+//!   **Export CSV** writes the window's checked joints to `--export`.
+//!
+//! No MuJoCo needed: build with `--features monitor`.
+
+use anyhow::Result;
+use clap::Parser;
+use common::LogOutput;
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, ConnectArgs, DecodeArgs, TransportArgs};
+use project_robot_joint_pubsub::history::{History, Quantity};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::{open_zenoh, ring_path};
+use project_robot_joint_pubsub::{
+    MqttTransport, RobotState, ShmTransport, Subscriber, Transport, TransportKind, ZmqTransport,
+};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use zmq::Context;
+
+/// How long one receive waits, i.e. how quickly the receiving thread notices the
+/// window has closed.
+const POLL: Duration = Duration::from_millis(100);
+
+#[derive(Parser)]
+#[command(name = "monitor_gui")]
+#[command(about = "Plot received robot joint angles and velocities live")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    connect: ConnectArgs,
+    /// Topic to plot (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    decode: DecodeArgs,
+    /// Seconds of history to plot
+    #[arg(long, value_name = "SECONDS", default_value_t = 10.0)]
+    window: f64,
+    /// Joints to plot at first, comma-separated (default: config `joints`, else
+    /// every joint received)
+    #[arg(long, value_delimiter = ',')]
+    joints: Vec<String>,
+    /// File that Export CSV writes
+    #[arg(long, value_name = "FILE", default_value = "joint_history.csv")]
+    export: PathBuf,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+struct MonitorApp {
+    states: Receiver<(RobotState, Instant)>,
+    history: History,
+    paused: bool,
+    quantity: Quantity,
+    /// Joints checked for plotting and export.
+    selected: BTreeSet<String>,
+    /// Joints listed so far, so only new ones are checked automatically.
+    known: BTreeSet<String>,
+    /// Check every joint as it first arrives (no `--joints`).
+    select_new: bool,
+    export_path: String,
+    export_note: String,
+}
+
+impl MonitorApp {
+    fn new(cli: &Cli, joints: Vec<String>, states: Receiver<(RobotState, Instant)>) -> Self {
+        MonitorApp {
+            states,
+            history: History::new(Duration::from_secs_f64(cli.window.max(0.1))),
+            paused: false,
+            quantity: Quantity::Angle,
+            select_new: joints.is_empty(),
+            selected: joints.into_iter().collect(),
+            known: BTreeSet::new(),
+            export_path: cli.export.display().to_string(),
+            export_note: String::new(),
+        }
+    }
+
+    /// Move what the receiving thread has heard into the history.
+    fn drain(&mut self) {
+        for (state, at) in self.states.try_iter() {
+            if !self.paused {
+                self.history.push(state, at);
+            }
+        }
+        for joint in self.history.joint_names() {
+            if !self.known.contains(joint) {
+                self.known.insert(joint.to_string());
+                if self.select_new {
+                    self.selected.insert(joint.to_string());
+                }
+            }
+        }
+    }
+
+    fn export(&mut self) {
+        let joints: Vec<&str> = self.selected.iter().map(String::as_str).collect();
+        let path = Path::new(&self.export_path);
+        self.export_note = match self.history.export(path, &joints) {
+            Ok(()) => format!("wrote {} rows to {}", self.history.len(), path.display()),
+            Err(e) => errors::report(&e),
+        };
+    }
+}
+
+impl eframe::App for MonitorApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.drain();
+
+        egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let label = if self.paused { "Resume" } else { "Pause" };
+                if ui.button(label).clicked() {
+                    self.paused = !self.paused;
+                }
+                if ui.button("Clear").clicked() {
+                    self.history.clear();
+                }
+                ui.separator();
+                ui.radio_value(&mut self.quantity, Quantity::Angle, "Angle (rad)");
+                ui.radio_value(&mut self.quantity, Quantity::Velocity, "Velocity (rad/s)");
+                ui.separator();
+                let mut window = self.history.window().as_secs_f64();
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut window)
+                            .range(0.1..=600.0)
+                            .speed(0.1)
+                            .suffix(" s"),
+                    )
+                    .changed()
+                {
+                    self.history.set_window(Duration::from_secs_f64(window));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(220.0));
+                if ui.button("Export CSV").clicked() {
+                    self.export();
+                }
+                ui.label(&self.export_note);
+            });
+        });
+
+        egui::SidePanel::left("joints").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("All").clicked() {
+                    self.selected.extend(self.known.iter().cloned());
+                }
+                if ui.button("None").clicked() {
+                    self.selected.clear();
+                }
+            });
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for joint in &self.known {
+                    let mut checked = self.selected.contains(joint);
+                    if ui.checkbox(&mut checked, joint).changed() {
+                        if checked {
+                            self.selected.insert(joint.clone());
+                        } else {
+                            self.selected.remove(joint);
+                        }
+                    }
+                }
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.history.is_empty() {
+                ui.label("Waiting for states…");
+            }
+            Plot::new("joint_plot")
+                .legend(Legend::default())
+                .x_axis_label("time (s)")
+                .show(ui, |plot_ui| {
+                    for joint in &self.selected {
+                        let points: PlotPoints =
+                            self.history.points(joint, self.quantity).collect();
+                        plot_ui.line(Line::new(points).name(joint));
+                    }
+                });
+        });
+
+        // The receiving thread wakes us for new states; this keeps a paused or
+        // silent plot responsive.
+        ctx.request_repaint_after(Duration::from_millis(100));
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.clone().unwrap_or_else(|| config.topic.clone());
+    let joints = if cli.joints.is_empty() {
+        config.joints.clone()
+    } else {
+        cli.joints.clone()
+    };
+    let source = match transport {
+        TransportKind::Zmq => cli.connect.connect(&config),
+        TransportKind::Mqtt => cli.transport.broker(&config),
+        TransportKind::Shm => ring_path(&topic).display().to_string(),
+        TransportKind::Zenoh if config.zenoh.is_empty() => "Zenoh scouting".to_string(),
+        TransportKind::Zenoh => config.zenoh.join(","),
+    };
+    let receiver: Box<dyn Transport> = match transport {
+        TransportKind::Zmq => {
+            let mut zmq = ZmqTransport::connect(&Context::new(), &source)?;
+            if cli.decode.legacy_frames {
+                zmq = zmq.accept_legacy_frames();
+            }
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+        TransportKind::Shm => Box::new(ShmTransport::open(&topic)?),
+        TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+    };
+    let mut subscriber = Subscriber::new(receiver, &topic)?;
+    if let Some(format) = cli.decode.format {
+        subscriber = subscriber.with_format(format);
+    }
+    tracing::info!(?transport, %source, %topic, "starting monitor");
+
+    let native_options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([1000.0, 600.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "ProHand Joint Monitor (egui)",
+        native_options,
+        Box::new(move |cc| {
+            let (sender, states) = mpsc::channel();
+            let ctx = cc.egui_ctx.clone();
+            // Subscribers block, so receive on a thread of our own; it ends once
+            // the window, and with it `states`, is gone.
+            std::thread::spawn(move || loop {
+                match subscriber.recv(POLL) {
+                    Ok(Some(state)) => {
+                        if sender.send((state, Instant::now())).is_err() {
+                            break;
+                        }
+                        ctx.request_repaint();
+                    }
+                    Ok(None) => {}
+                    Err(e) if e.exit() == Exit::Network => {
+                        tracing::error!(error = %errors::report(&e), "receive error");
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %errors::report(&e), "skipping message");
+                    }
+                }
+            });
+            Ok(Box::new(MonitorApp::new(&cli, joints, states)))
+        }),
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to start egui app: {e}"))?;
+
+    Ok(())
+}
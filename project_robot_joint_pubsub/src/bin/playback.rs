@@ -0,0 +1,154 @@
+//! Playback: republishes a `recorder` MCAP file via ZMQ or MQTT with its
+//! original timing, optionally faster or slower, from an offset, and on a loop,
+//! so a recorded session can drive the MuJoCo subscriber again.
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use common::LogOutput;
+use project_robot_joint_pubsub::args::{ConfigArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::recording::Recording;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    MqttTransport, Publisher, ShmTransport, Transport, TransportKind, ZmqTransport,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::time::{sleep, sleep_until, Instant};
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "playback")]
+#[command(about = "Republish a recorded MCAP session of robot joint angles")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Recording written by `recorder`
+    file: PathBuf,
+    /// Playback speed relative to the recording, e.g. 0.5 or 2.0
+    #[arg(long, default_value_t = 1.0, value_parser = parse_rate)]
+    rate: f64,
+    /// Start this many seconds into the recording
+    #[arg(long, default_value = "0", value_parser = parse_seconds)]
+    start_offset: Duration,
+    /// Start over from --start-offset after the last state, until interrupted
+    #[arg(long = "loop")]
+    repeat: bool,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Topic to publish on (default: the recorded topic)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let recording = Recording::open(&cli.file)?;
+    let schedule = recording.schedule(cli.start_offset, cli.rate);
+    if schedule.is_empty() {
+        bail!(
+            "{} has no states from {:?} on (it is {:?} long)",
+            cli.file.display(),
+            cli.start_offset,
+            recording.duration()
+        );
+    }
+    let topic = cli
+        .topic
+        .or_else(|| recording.metadata.get("topic").cloned())
+        .unwrap_or_else(|| config.topic.clone());
+    let format = cli.publish.format(&config);
+
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, %topic, "starting playback");
+            Box::new(ZmqTransport::bind(&Context::new(), &bind)?)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, "starting playback over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, "starting playback over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, "starting playback over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_split_by_finger(cli.publish.split_by_finger);
+
+    // Give subscribers time to connect
+    sleep(Duration::from_millis(500)).await;
+
+    tracing::info!(
+        file = %cli.file.display(),
+        states = schedule.len(),
+        rate = cli.rate,
+        start_offset_s = cli.start_offset.as_secs_f64(),
+        repeat = cli.repeat,
+        "playing"
+    );
+    // Renumbered so sequence numbers keep increasing across loops; subscribers
+    // skip states older than the last one they applied. Restamped with the
+    // time they are published, like a live publisher's.
+    let mut sequence = 0;
+    loop {
+        let started = Instant::now();
+        for (due, recorded) in &schedule {
+            sleep_until(started + *due).await;
+            sequence += 1;
+            let mut state = (*recorded).clone();
+            state.sequence = Some(sequence);
+            state.timestamp = 0;
+            for joint in &mut state.joints {
+                joint.timestamp = 0;
+            }
+            publisher.publish(&state)?;
+            tracing::debug!(sequence, recorded = recorded.order(), "published");
+        }
+        if !cli.repeat {
+            break;
+        }
+        tracing::debug!("looping");
+    }
+    tracing::info!(states = sequence, "playback finished");
+    Ok(())
+}
+
+fn parse_rate(raw: &str) -> Result<f64, String> {
+    match raw.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("expected a positive number, got {raw:?}")),
+    }
+}
+
+fn parse_seconds(raw: &str) -> Result<Duration, String> {
+    let secs: f64 = raw
+        .parse()
+        .map_err(|_| format!("expected seconds, got {raw:?}"))?;
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| format!("expected non-negative seconds, got {raw:?}"))
+}
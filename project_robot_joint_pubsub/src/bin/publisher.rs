@@ -1,84 +1,296 @@
-// Publisher binary - publishes robot joint angles via ZMQ
+// Publisher binary - publishes robot joint angles via ZMQ or an MQTT broker,
+// from the simulated robot, a CSV trajectory (`--from-csv`), or per-joint
+// waveforms (`--waveforms`), optionally serving Prometheus metrics (`--metrics`),
+// batching states sent faster than the network tick (`--batch`), skipping states
+// that barely moved (`--deadband`), and echoing a subscriber's heartbeats so it
+// can sync clocks with this one (`--clock-sync`). Over ZMQ it logs subscribers
+// connecting and disconnecting
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use clap::Parser;
-use project_robot_joint_pubsub::{JointAngles, RobotState};
-use std::time::Duration;
-use tokio::time::sleep;
+use common::LogOutput;
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::clock::ClockSync;
+use project_robot_joint_pubsub::feedback;
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::metrics::Metrics;
+use project_robot_joint_pubsub::trajectory::Trajectory;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::waveform::Waveforms;
+use project_robot_joint_pubsub::{
+    sim, Compression, ConnectionEvent, MqttTransport, Publisher, ShmTransport, Subscriber,
+    Transport, TransportKind, ZmqMonitor, ZmqTransport,
+};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::{self, sleep, MissedTickBehavior};
 use zmq::Context;
 
 #[derive(Parser)]
 #[command(name = "publisher")]
-#[command(about = "Robot joint angles publisher using Tokio and ZMQ")]
+#[command(about = "Robot joint angles publisher using Tokio and ZMQ or MQTT")]
 struct Cli {
-    /// ZMQ bind address (default: tcp://*:5555)
-    #[arg(short, long, default_value = "tcp://*:5555")]
-    bind: String,
-    /// Publishing interval in milliseconds (default: 100)
-    #[arg(short, long, default_value_t = 100)]
-    interval: u64,
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Publishing interval in milliseconds (default: config `interval_ms`, else 100)
+    #[arg(short, long)]
+    interval: Option<u64>,
+    /// Compress each payload (subscribers detect it)
+    #[arg(long, value_enum, default_value = "none")]
+    compress: Compression,
+    /// Send the states of every MS milliseconds together as one batch on
+    /// <topic>/batch, for intervals shorter than the network is worth per message
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    batch: u64,
+    /// Skip states in which no joint moved more than RAD since the last one sent,
+    /// to save bandwidth while the pose holds still (0 skips only exact repeats)
+    #[arg(long, value_name = "RAD")]
+    deadband: Option<f64>,
+    /// With --deadband, send a state at least every MS milliseconds anyway
+    #[arg(long, value_name = "MS", default_value_t = 1000, requires = "deadband")]
+    keepalive: u64,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    /// Echo the heartbeats of `subscriber --publish-feedback` at this ZMQ endpoint
+    /// in ours, so the subscriber can take the clock offset out of its latency
+    #[arg(long, value_name = "ENDPOINT")]
+    clock_sync: Option<String>,
+    /// Publish a CSV trajectory (time, then one column per joint) instead of the
+    /// simulated robot, interpolated at --interval; exits at its end
+    #[arg(long, value_name = "FILE", group = "source")]
+    from_csv: Option<PathBuf>,
+    /// Drive joints with the sine, square, or chirp waveforms in this TOML file
+    /// instead of the simulated robot, until interrupted
+    #[arg(long, value_name = "FILE", group = "source")]
+    waveforms: Option<PathBuf>,
+    /// Rename a CSV column to a joint name (repeatable or comma-separated)
+    #[arg(long, value_name = "COLUMN=JOINT", value_delimiter = ',', value_parser = parse_rename)]
+    joint_map: Vec<(String, String)>,
+    /// Start the CSV trajectory over after its last row, until interrupted
+    #[arg(long = "loop", requires = "from_csv")]
+    repeat: bool,
+    /// `robot_id` on CSV trajectory and waveform states
+    #[arg(long, default_value = sim::ROBOT_ID, requires = "source")]
+    robot_id: String,
+    /// Serve Prometheus metrics on http://ADDR/metrics, e.g. 0.0.0.0:9464
+    #[arg(long, value_name = "ADDR")]
+    metrics: Option<String>,
+    #[command(flatten)]
+    log: LogArgs,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
 
-    println!("🤖 Starting Robot Joint Angles Publisher");
-    println!("📡 Binding to: {}", cli.bind);
-    println!("⏱️  Publishing interval: {}ms", cli.interval);
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let interval = cli.interval.unwrap_or(config.interval_ms);
+    let topic = config.topic.clone();
+    let format = cli.publish.format(&config);
+    let heartbeat = cli.heartbeat.interval(&config);
+    let trajectory = cli
+        .from_csv
+        .as_deref()
+        .map(|path| load_trajectory(path, &cli.joint_map))
+        .transpose()?;
+    let waveforms = cli
+        .waveforms
+        .as_deref()
+        .map(|path| Waveforms::load(path).with_context(|| format!("loading {}", path.display())))
+        .transpose()?;
+    let metrics = cli.metrics.as_deref().map(start_metrics).transpose()?;
 
-    let ctx = Context::new();
-    let socket = ctx.socket(zmq::PUB)?;
-    socket.bind(&cli.bind)?;
+    // Over ZMQ, subscribers connecting and going are logged.
+    let mut connections = None;
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, interval_ms = interval, %topic, ?format, compress = ?cli.compress, "starting robot joint angles publisher");
+            let zmq = ZmqTransport::bind(&Context::new(), &bind)?;
+            connections = Some(zmq.monitor()?);
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, interval_ms = interval, %topic, ?format, compress = ?cli.compress, "starting robot joint angles publisher over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(interval_ms = interval, %topic, ?format, compress = ?cli.compress, "starting robot joint angles publisher over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, interval_ms = interval, %topic, ?format, compress = ?cli.compress, "starting robot joint angles publisher over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let clock = Arc::new(ClockSync::default());
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_compression(cli.compress)
+        .with_split_by_finger(cli.publish.split_by_finger)
+        .with_batch(Duration::from_millis(cli.batch))
+        .with_clock_sync(Arc::clone(&clock));
+    let publisher = match cli.deadband {
+        Some(epsilon) => publisher.with_deadband(epsilon, Duration::from_millis(cli.keepalive)),
+        None => publisher,
+    };
+    let peer = cli
+        .clock_sync
+        .as_deref()
+        .map(|endpoint| {
+            tracing::info!(%endpoint, "echoing feedback heartbeats for clock sync");
+            let transport = ZmqTransport::connect(&Context::new(), endpoint)?;
+            Subscriber::new(transport, feedback::TOPIC)
+                .map(|subscriber| subscriber.with_clock_sync(clock))
+        })
+        .transpose()?;
 
     // Give subscribers time to connect
-    println!("⏳ Waiting for subscribers to connect...");
+    tracing::info!("waiting for subscribers to connect");
     sleep(Duration::from_millis(500)).await;
 
-    let mut timestamp = 0u64;
-    let joint_name = "ball_joint";
-
-    println!("🚀 Publishing joint angles for {}...\n", joint_name);
-
-    loop {
-        timestamp += 1;
-
-        // Simulate ball position using sinusoidal motion
-        // For a free joint, we'll simulate x, y, z position changes
-        let t = timestamp as f64 * 0.01;
-        let angle_rad = t.sin() * 2.0; // Simulated angle/position component
-        let velocity = t.cos() * 0.1; // Velocity component
-        let torque = (t * 2.0).sin() * 0.5; // Torque component
+    let step = Duration::from_millis(interval);
+    if let (Some(trajectory), Some(path)) = (&trajectory, &cli.from_csv) {
+        tracing::info!(
+            csv = %path.display(),
+            joints = trajectory.joint_names().len(),
+            duration_s = trajectory.duration().as_secs_f64(),
+            "publishing trajectory"
+        );
+    } else if let (Some(waveforms), Some(path)) = (&waveforms, &cli.waveforms) {
+        tracing::info!(
+            waveforms = %path.display(),
+            joints = waveforms.joint_names().count(),
+            "publishing waveforms"
+        );
+    } else {
+        tracing::info!(joint = sim::JOINT_NAME, "publishing joint angles");
+    }
 
-        let joint = JointAngles {
-            timestamp,
-            joint_name: joint_name.to_string(),
-            angle_rad,
-            velocity,
-            torque,
+    // Sequence number at which the current pass over the trajectory started.
+    let mut pass_start = 1;
+    let mut last_heartbeat: Option<Instant> = None;
+    // Ticks keep the rate steady however long publishing takes; Ctrl-C stops
+    // between states rather than mid-send.
+    let mut ticks = time::interval(step);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    for sequence in 1.. {
+        tokio::select! {
+            _ = ticks.tick() => {}
+            signal = &mut ctrl_c => {
+                signal.context("waiting for Ctrl-C")?;
+                tracing::info!("interrupted");
+                break;
+            }
+        }
+        let robot_state = match (&trajectory, &waveforms) {
+            (Some(trajectory), _) => {
+                let mut elapsed = step * (sequence - pass_start) as u32;
+                // One tick past the end publishes the last row exactly, then stop or start over.
+                if elapsed >= trajectory.duration() + step {
+                    if !cli.repeat {
+                        tracing::info!("trajectory finished");
+                        break;
+                    }
+                    pass_start = sequence;
+                    elapsed = Duration::ZERO;
+                }
+                trajectory.state_at(elapsed.min(trajectory.duration()), sequence, &cli.robot_id)
+            }
+            (None, Some(waveforms)) => {
+                waveforms.state_at(step * (sequence - 1) as u32, sequence, &cli.robot_id)
+            }
+            (None, None) => sim::simulated_state(sequence),
         };
+        publisher.publish(&robot_state)?;
+        if let Some(metrics) = &metrics {
+            metrics.sent(&robot_state, Instant::now());
+        }
 
-        let robot_state = RobotState {
-            timestamp,
-            robot_id: "ball_robot".to_string(),
-            joints: vec![joint],
-        };
+        let joint = &robot_state.joints[0];
+        tracing::debug!(
+            sequence,
+            joint = %joint.joint_name,
+            angle_rad = joint.angle_rad,
+            velocity = joint.velocity,
+            torque = joint.torque,
+            "published"
+        );
 
-        let topic = "robot_joints";
-        let json_data = serde_json::to_string(&robot_state)?;
-        let message = format!("{} {}", topic, json_data);
+        if let Some(monitor) = &mut connections {
+            log_connections(monitor)?;
+        }
+        if let Some(peer) = &peer {
+            // Receiving is what hears the heartbeats; the feedback itself is not needed.
+            while let Ok(Some(_)) = peer.try_recv() {}
+        }
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+    }
+    publisher.flush()?;
+    Ok(())
+}
 
-        socket.send(&message, 0)?;
+/// Log the subscribers (or the broker) that connected or went since the last call.
+fn log_connections(monitor: &mut ZmqMonitor) -> Result<()> {
+    for event in monitor.poll()? {
+        let subscribers = monitor.connections();
+        match event {
+            ConnectionEvent::Up(endpoint) => {
+                tracing::info!(%endpoint, subscribers, "subscriber connected")
+            }
+            ConnectionEvent::Down(endpoint) => {
+                tracing::warn!(%endpoint, subscribers, "subscriber disconnected")
+            }
+            ConnectionEvent::Retrying(endpoint) => {
+                tracing::debug!(%endpoint, "retrying the broker")
+            }
+        }
+    }
+    Ok(())
+}
 
-        println!(
-            "📤 [{}] Published {}: angle={:.3} rad, vel={:.3} rad/s, torque={:.2} N⋅m",
-            timestamp,
-            joint_name,
-            robot_state.joints[0].angle_rad,
-            robot_state.joints[0].velocity,
-            robot_state.joints[0].torque
-        );
+fn start_metrics(addr: &str) -> Result<Metrics> {
+    let (metrics, local) = Metrics::spawn(addr).with_context(|| format!("listening on {addr}"))?;
+    tracing::info!(metrics = %format!("http://{local}/metrics"), "serving Prometheus metrics");
+    Ok(metrics)
+}
+
+fn load_trajectory(path: &Path, renames: &[(String, String)]) -> Result<Trajectory> {
+    let mut trajectory = Trajectory::load(path)?;
+    for (column, joint) in renames {
+        trajectory.rename(column, joint)?;
+    }
+    Ok(trajectory)
+}
 
-        sleep(Duration::from_millis(cli.interval)).await;
+fn parse_rename(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((column, joint)) if !column.is_empty() && !joint.is_empty() => {
+            Ok((column.to_string(), joint.to_string()))
+        }
+        _ => Err(format!("expected COLUMN=JOINT, got {raw:?}")),
     }
 }
@@ -0,0 +1,194 @@
+//! Gamepad publisher binary: steers joints with a gamepad and publishes the pose
+//! via ZMQ or an MQTT broker at a fixed rate.
+//!
+//! Behind the scenes (high level):
+//! - Reads a mapping file (see `gamepad` in the library) binding sticks, triggers,
+//!   and buttons to groups of joints.
+//! - Polls gamepads through gilrs; the one used last drives the pose, and
+//!   plugging one in or out is logged, never fatal.
+//! - Every `--interval`, advances the pose by the inputs held and publishes it,
+//!   with heartbeats as the headless publisher sends them.
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use common::LogOutput;
+use gilrs::{EventType, GamepadId, Gilrs};
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::gamepad::{Axis, Button, GamepadMap, Teleop};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    MqttTransport, Publisher, ShmTransport, Transport, TransportKind, ZmqTransport,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "publisher_gamepad")]
+#[command(about = "Robot joint angles publisher driven by a gamepad")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Mapping of sticks, triggers, and buttons to joints (TOML)
+    #[arg(long, value_name = "FILE", default_value = "gamepad_map.toml")]
+    map: PathBuf,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Publish topic (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    /// Publishing interval in milliseconds (default: config `interval_ms`, else 100)
+    #[arg(short, long)]
+    interval: Option<u64>,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let map = GamepadMap::load(&cli.map)?;
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let step = Duration::from_millis(cli.interval.unwrap_or(config.interval_ms));
+    let format = cli.publish.format(&config);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    let mut gilrs = Gilrs::new().map_err(|e| anyhow!("opening gamepad input: {e}"))?;
+    // The gamepad driving the pose: the last one to send an event.
+    let mut active: Option<GamepadId> = gilrs.gamepads().next().map(|(id, _)| id);
+    for (id, gamepad) in gilrs.gamepads() {
+        tracing::info!(%id, name = gamepad.name(), "gamepad connected");
+    }
+    if active.is_none() {
+        tracing::warn!("no gamepad connected yet; publishing the resting pose");
+    }
+
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, %topic, ?format, "starting gamepad publisher");
+            Box::new(ZmqTransport::bind(&Context::new(), &bind)?)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, ?format, "starting gamepad publisher over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, ?format, "starting gamepad publisher over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, ?format, "starting gamepad publisher over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_split_by_finger(cli.publish.split_by_finger);
+    tracing::info!(
+        map = %cli.map.display(),
+        robot_id = %map.robot_id,
+        joints = map.joint_names().len(),
+        interval_ms = step.as_millis() as u64,
+        "publishing gamepad pose"
+    );
+    let mut teleop = Teleop::new(map);
+
+    let mut last_heartbeat: Option<Instant> = None;
+    let mut last_tick = Instant::now();
+    for sequence in 1.. {
+        // Drain events so gilrs updates its state, and follow whichever pad is in use.
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected => {
+                    let name = gilrs.gamepad(event.id).name().to_string();
+                    tracing::info!(id = %event.id, %name, "gamepad connected");
+                }
+                EventType::Disconnected => {
+                    tracing::warn!(id = %event.id, "gamepad disconnected");
+                    if active == Some(event.id) {
+                        active = gilrs.gamepads().next().map(|(id, _)| id);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            active = Some(event.id);
+        }
+
+        let now = Instant::now();
+        let dt = now - last_tick;
+        last_tick = now;
+        match active.map(|id| gilrs.gamepad(id)) {
+            Some(pad) => teleop.update(
+                dt,
+                |axis| f64::from(axis_value(&pad, axis)),
+                |button| pad.is_pressed(gilrs_button(button)),
+            ),
+            None => teleop.update(dt, |_| 0.0, |_| false),
+        }
+
+        let robot_state = teleop.state(sequence);
+        publisher.publish(&robot_state)?;
+        tracing::debug!(sequence, joints = robot_state.joints.len(), "published");
+
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+
+        std::thread::sleep(step.saturating_sub(now.elapsed()));
+    }
+    Ok(())
+}
+
+fn axis_value(pad: &gilrs::Gamepad<'_>, axis: Axis) -> f32 {
+    let trigger = |button| pad.button_data(button).map_or(0.0, |data| data.value());
+    match axis {
+        Axis::LeftStickX => pad.value(gilrs::Axis::LeftStickX),
+        Axis::LeftStickY => pad.value(gilrs::Axis::LeftStickY),
+        Axis::RightStickX => pad.value(gilrs::Axis::RightStickX),
+        Axis::RightStickY => pad.value(gilrs::Axis::RightStickY),
+        // Analog triggers are buttons with a value in gilrs.
+        Axis::LeftTrigger => trigger(gilrs::Button::LeftTrigger2),
+        Axis::RightTrigger => trigger(gilrs::Button::RightTrigger2),
+    }
+}
+
+fn gilrs_button(button: Button) -> gilrs::Button {
+    match button {
+        Button::South => gilrs::Button::South,
+        Button::East => gilrs::Button::East,
+        Button::North => gilrs::Button::North,
+        Button::West => gilrs::Button::West,
+        Button::LeftBumper => gilrs::Button::LeftTrigger,
+        Button::RightBumper => gilrs::Button::RightTrigger,
+        Button::LeftThumb => gilrs::Button::LeftThumb,
+        Button::RightThumb => gilrs::Button::RightThumb,
+        Button::Select => gilrs::Button::Select,
+        Button::Start => gilrs::Button::Start,
+        Button::DpadUp => gilrs::Button::DPadUp,
+        Button::DpadDown => gilrs::Button::DPadDown,
+        Button::DpadLeft => gilrs::Button::DPadLeft,
+        Button::DpadRight => gilrs::Button::DPadRight,
+    }
+}
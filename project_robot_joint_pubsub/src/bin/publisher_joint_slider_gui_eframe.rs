@@ -1,28 +1,86 @@
 //! GUI publisher binary: publishes robot joint angles from user-controlled sliders (egui).
 //!
 //! ## What this does
-//! - Loads an MJCF model from disk (supports `<include file="..."/>`).
+//! - Loads an MJCF model from disk (supports `<include file="..."/>`), and another one
+//!   picked with "Load model…" at runtime, rebuilding the sliders without restarting
+//!   or interrupting publishing.
 //! - Enumerates MuJoCo joint names and (when available) joint limits.
-//! - Presents an egui UI with one slider per joint.
-//! - Publishes `RobotState` messages over ZMQ `PUB` at a fixed rate.
+//! - Presents an egui UI with one slider per joint, grouped by finger (thumb, index,
+//!   middle, ring, pinky, then wrist and other joints) in collapsible sections with
+//!   per-group "Zero" and "Curl" buttons. Each row also takes a typed value, shown in
+//!   radians or degrees by a global toggle (published in radians either way), and an IK tab that solves for the
+//!   joint angles placing a site or body (a fingertip, the palm) at a target position.
+//! - Publishes `RobotState` messages over ZMQ `PUB` at a fixed rate, or with `--deadband`
+//!   only when a slider moved (and every `--keepalive` milliseconds while none does).
+//! - A Generators tab drives chosen joints with a sine, square (steps), or chirp of a
+//!   set amplitude, frequency, and center (see `waveform` in the library), while the
+//!   other joints stay on their sliders.
+//! - Moves the sliders to a clicked preset over `--transition-ms` with `--easing`,
+//!   publishing the in-between poses, instead of snapping (see `transition` in the library).
+//! - Plays YAML keyframe gesture sequences in its Sequence tab, with play/pause and
+//!   loop, moving the sliders and publishing as they go (see `sequence` in the library).
+//! - Records the published states while "Record" is on and exports the take as an
+//!   MCAP recording (as the `recorder` binary writes, for `playback`) or a CSV
+//!   trajectory (for `publisher --from-csv`), so motions can be authored here.
+//! - A "Random pose" button moves the sliders to a seeded random pose within the joint
+//!   limits, optionally without abduction or near the limits (`--random-constraints`,
+//!   see `random_pose` in the library).
+//! - Saves the current pose as a named preset to a TOML file (`--presets`) and shows the
+//!   presets loaded from it as buttons next to the built-in ones (see `presets` in the library).
+//! - With `--feedback`, subscribes to measured joint states (`subscriber --publish-feedback`,
+//!   see `feedback` in the library) and shows each joint's actual angle next to its slider,
+//!   highlighting those further off than `--feedback-threshold`. Its heartbeats echo the
+//!   feedback's, so the subscriber can estimate the offset between the two clocks.
+//! - Shows how many subscribers are connected, from ZMQ's socket monitor, and logs
+//!   them connecting and disconnecting.
+//! - An E-STOP button (and Hold / Resume) sends control commands on `<topic>/control`,
+//!   which the subscriber honors by freezing or zeroing the joints until a resume.
+//! - With `--query`, answers `get_state` (the last state sent), `get_joint_names`,
+//!   and `get_model_info` on a ZMQ `REP` socket.
 //!
 //! ## Message format (wire protocol)
-//! The publisher sends a single ZMQ string message in the form:
-//! `"{topic} {json}"`, where `{json}` is a serialized `RobotState`.
-//! This matches the format used by `subscriber.rs`.
+//! Sent through the library's `Publisher` on a `ZmqTransport`, so each message is
+//! a topic frame and a payload frame, as `subscriber.rs` expects, with a JSON
+//! payload unless `--format msgpack` or `--format protobuf` is given. With
+//! `--transport shm` the same messages go into a shared-memory ring instead, for a
+//! subscriber on the same machine.
 //!
 //! ## Environments
 //! - **Dev**: run this GUI publisher and the MuJoCo subscriber locally.
 //! - **Test/CI**: keep using `publisher.rs` (headless random publisher) or `cargo test`.
 //! - **Prod**: typically not used; this is a developer control tool.
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use clap::Parser;
+use common::LogOutput;
 use eframe::egui;
 use mujoco_rs::prelude::*;
-use project_robot_joint_pubsub::{JointAngles, RobotState};
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs};
+use project_robot_joint_pubsub::clock::ClockSync;
+use project_robot_joint_pubsub::control::{ControlCommand, Status};
+use project_robot_joint_pubsub::feedback;
+use project_robot_joint_pubsub::finger;
+use project_robot_joint_pubsub::ik::{self, IkOptions, IkSolution};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::presets::{Pose, Presets};
+use project_robot_joint_pubsub::query::{ModelInfo, QueryServer, Snapshot};
+use project_robot_joint_pubsub::random_pose::{self, Constraints};
+use project_robot_joint_pubsub::recording::{Recorder, Session};
+use project_robot_joint_pubsub::sequence::{Player, Sequence, SequenceError};
+use project_robot_joint_pubsub::trajectory::Trajectory;
+use project_robot_joint_pubsub::transition::{Easing, Transition};
+use project_robot_joint_pubsub::transport::ring_path;
+use project_robot_joint_pubsub::waveform::{self, Shape, Waveform};
+use project_robot_joint_pubsub::{
+    Config, ConnectionEvent, JointAngles, Publisher, RobotState, ShmTransport, Subscriber,
+    Transport, TransportKind, ZmqMonitor, ZmqTransport,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use zmq::Context;
 
 /// Conservative fallback range (radians) for joints without limits in the model.
@@ -32,39 +90,96 @@ const DEFAULT_UNLIMITED_RANGE_RAD: std::ops::RangeInclusive<f64> = -1.5..=1.5;
 #[command(name = "publisher_joint_slider_gui_eframe")]
 #[command(about = "GUI publisher: egui sliders -> ZMQ RobotState (for MuJoCo subscriber)")]
 struct Cli {
-    /// ZMQ bind address (default: tcp://*:5555)
-    #[arg(long, default_value = "tcp://*:5555")]
-    bind: String,
+    #[command(flatten)]
+    config: ConfigArgs,
 
-    /// Publish topic prefix (default: robot_joints)
-    #[arg(long, default_value = "robot_joints")]
-    topic: String,
+    /// Transport to publish on: zmq, or shm for a subscriber on this machine (default zmq)
+    #[arg(long, value_enum)]
+    transport: Option<TransportKind>,
+    #[command(flatten)]
+    publish: PublishArgs,
+
+    /// Publish topic prefix (default: config `topic`, else robot_joints)
+    #[arg(long)]
+    topic: Option<String>,
+    /// Skip states in which no joint moved more than RAD since the last one sent,
+    /// to save bandwidth while the pose holds still (0 skips only exact repeats)
+    #[arg(long, value_name = "RAD")]
+    deadband: Option<f64>,
+    /// With --deadband, send a state at least every MS milliseconds anyway
+    #[arg(long, value_name = "MS", default_value_t = 1000, requires = "deadband")]
+    keepalive: u64,
 
     /// Publishing rate in Hz (default: 50)
     #[arg(long, default_value_t = 50)]
     publish_hz: u64,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+
+    /// Answer state queries on a ZMQ REP socket bound here, e.g. tcp://*:5556
+    #[arg(long, value_name = "ENDPOINT")]
+    query: Option<String>,
 
     /// MJCF model path (supports `<include/>`), relative to `project_robot_joint_pubsub/`
-    #[arg(long, default_value = "pro-models/example/scenes/left_hand_scene.xml")]
-    model: PathBuf,
+    /// (default: config `model`, else pro-models/example/scenes/left_hand_scene.xml)
+    #[arg(long)]
+    model: Option<PathBuf>,
+
+    /// How long a preset takes to reach, in milliseconds; 0 snaps to it
+    #[arg(long, value_name = "MS", default_value_t = 500)]
+    transition_ms: u64,
+
+    /// How preset transitions speed up and slow down
+    #[arg(long, value_enum, default_value_t = Easing::EaseInOut)]
+    easing: Easing,
+
+    /// Keyframe sequence to load into the Sequence tab at startup
+    #[arg(long, value_name = "FILE")]
+    sequence: Option<PathBuf>,
+
+    /// Presets file to load at startup and save new presets to (created on first save)
+    #[arg(long, value_name = "FILE", default_value = "hand_presets.toml")]
+    presets: PathBuf,
+
+    /// TOML constraints for "Random pose" (exclude_abduction, max_fraction, exclude)
+    #[arg(long, value_name = "FILE")]
+    random_constraints: Option<PathBuf>,
+
+    /// Show the measured joint angles on TOPIC (default robot_joints_actual, as
+    /// `subscriber --publish-feedback` sends them) next to the sliders
+    #[arg(long, value_name = "TOPIC", num_args = 0..=1, default_missing_value = feedback::TOPIC)]
+    feedback: Option<String>,
+
+    /// ZMQ endpoint to receive --feedback from; unused with --transport shm, which reads the TOPIC ring
+    #[arg(long, value_name = "ENDPOINT", default_value = "tcp://localhost:5561")]
+    feedback_connect: String,
+
+    /// Highlight joints whose measured angle is further than this from the slider, in radians
+    #[arg(long, value_name = "RAD", default_value_t = 0.05)]
+    feedback_threshold: f64,
 
     /// Robot identifier included in the published message (default: pro_hand)
     #[arg(long, default_value = "pro_hand")]
     robot_id: String,
 
     /// Optional joint name prefixes to include (repeatable or comma-separated).
-    /// If omitted, all joints in the MJCF are shown.
+    /// If omitted, config `filter_prefix`; if that is empty too, all joints in
+    /// the MJCF are shown.
     ///
     /// Examples:
     /// - `--filter-prefix finger_`
     /// - `--filter-prefix finger_,thumb_`
     #[arg(long, value_delimiter = ',', num_args = 0..)]
     filter_prefix: Vec<String>,
+    #[command(flatten)]
+    log: LogArgs,
 }
 
 #[derive(Debug, Clone)]
 struct JointControl {
     name: String,
+    /// MuJoCo joint id.
+    id: usize,
     value_rad: f64,
     min_rad: f64,
     max_rad: f64,
@@ -73,11 +188,460 @@ struct JointControl {
 
 struct GuiPublisherApp {
     cli: Cli,
-    socket: zmq::Socket,
+    publisher: Publisher<Box<dyn Transport>>,
+    /// `--bind` (or the shared-memory ring) and `--topic`, or their config
+    /// fallbacks, for the top bar.
+    bind: String,
+    topic: String,
+    heartbeat_ms: u64,
+    /// Watches subscribers connect and go, over ZMQ.
+    connections: Option<ZmqMonitor>,
+    /// Which joints get sliders, for every model loaded.
+    filter_prefix: Vec<String>,
+    /// How the last "Load model…" went.
+    model_note: String,
+    /// Show and take slider values in degrees rather than radians.
+    degrees: bool,
     publish_interval: Duration,
     last_publish: Instant,
+    last_heartbeat: Option<Instant>,
     seq: u64,
     joints: Vec<JointControl>,
+    query: Option<QueryServer>,
+    /// For `get_state`.
+    last_state: Option<RobotState>,
+    /// For `get_joint_names`, in slider order.
+    joint_names: Vec<String>,
+    model_info: ModelInfo,
+    tab: Tab,
+    ik: IkPanel,
+    /// What the control commands sent so far ask subscribers to do.
+    status: Status,
+    /// Presets loaded from `presets_path`, shown after the built-in ones.
+    presets: Presets,
+    presets_path: String,
+    /// Name for "Save current pose as preset".
+    preset_name: String,
+    /// How the last save or load went.
+    presets_note: String,
+    /// What "Random pose" may move, and the seed of the next one.
+    random: Constraints,
+    random_seed: u64,
+    /// Which seed the last random pose came from.
+    random_note: String,
+    /// The preset the sliders are on their way to, if any.
+    transition: Option<Transition>,
+    transition_ms: u64,
+    easing: Easing,
+    /// The Sequence tab's sequence and how far it has played.
+    sequence: Option<Sequence>,
+    sequence_path: String,
+    player: Player,
+    /// How the last sequence load went.
+    sequence_note: String,
+    /// Whether published states go into `take`.
+    recording: bool,
+    /// The states published since "Record" was last pressed, with their send times.
+    take: Vec<(SystemTime, RobotState)>,
+    /// How the last export went.
+    take_note: String,
+    generators: GeneratorPanel,
+    /// Measured joint states, with `--feedback`.
+    feedback: Option<Subscriber<Box<dyn Transport>>>,
+    /// The latest measured angle of each joint, and when feedback last arrived.
+    measured: HashMap<String, f64>,
+    last_feedback: Option<Instant>,
+    feedback_threshold: f64,
+}
+
+/// What "Export" writes a take as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TakeFormat {
+    Mcap,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Sliders,
+    Ik,
+    Sequence,
+    Generators,
+}
+
+/// A point IK can place: a site (e.g. a fingertip) or a body's origin (e.g. the palm).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IkPoint {
+    Site(i32),
+    Body(i32),
+}
+
+/// The IK tab: its own copy of the model's state to solve on, and the target.
+struct IkPanel {
+    data: MjData<Rc<MjModel>>,
+    /// Every site and (non-world) body, labelled for the picker.
+    points: Vec<(String, IkPoint)>,
+    selected: usize,
+    /// Where the selected point should go, in world coordinates (meters).
+    target: [f64; 3],
+    /// Slider joints IK may move (hinges and slides): index in `joints`,
+    /// `qpos` address, and dof address.
+    movable: Vec<(usize, usize, usize)>,
+    /// Degrees of freedom, the width of MuJoCo's Jacobians.
+    nv: usize,
+    last: Option<IkSolution>,
+}
+
+impl IkPanel {
+    fn new(model: Rc<MjModel>, joints: &[JointControl]) -> Self {
+        let jnt_type = model.jnt_type();
+        let (qposadr, dofadr) = (model.jnt_qposadr(), model.jnt_dofadr());
+        let movable = joints
+            .iter()
+            .enumerate()
+            .filter(|(_, j)| {
+                matches!(
+                    jnt_type[j.id],
+                    MjtJoint::mjJNT_HINGE | MjtJoint::mjJNT_SLIDE
+                )
+            })
+            .map(|(i, j)| (i, qposadr[j.id] as usize, dofadr[j.id] as usize))
+            .collect();
+
+        let named = |kind: MjtObj, id: i32| {
+            model
+                .id_to_name(kind, id)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+        };
+        let sites = (0..model.ffi().nsite).filter_map(|id| {
+            named(MjtObj::mjOBJ_SITE, id).map(|name| (format!("site {name}"), IkPoint::Site(id)))
+        });
+        let bodies = (1..model.ffi().nbody).filter_map(|id| {
+            named(MjtObj::mjOBJ_BODY, id).map(|name| (format!("body {name}"), IkPoint::Body(id)))
+        });
+        let points = sites.chain(bodies).collect();
+
+        let mut panel = IkPanel {
+            data: MjData::new(Rc::clone(&model)),
+            points,
+            selected: 0,
+            target: [0.0; 3],
+            movable,
+            nv: model.ffi().nv.max(0) as usize,
+            last: None,
+        };
+        panel.reset_target(joints);
+        panel
+    }
+
+    /// Put the model in the sliders' pose.
+    fn pose(&mut self, joints: &[JointControl]) {
+        for &(i, qposadr, _) in &self.movable {
+            self.data.qpos_mut()[qposadr] = joints[i].value_rad;
+        }
+        self.data.kinematics();
+        self.data.com_pos();
+    }
+
+    /// Move the target to where the selected point is in the sliders' pose.
+    fn reset_target(&mut self, joints: &[JointControl]) {
+        self.last = None;
+        let Some(&(_, point)) = self.points.get(self.selected) else {
+            return;
+        };
+        self.pose(joints);
+        self.target = point_position(&self.data, point);
+    }
+
+    /// Solve for the target from the sliders' pose and move the sliders there.
+    fn solve(&mut self, joints: &mut [JointControl]) {
+        let Some(&(_, point)) = self.points.get(self.selected) else {
+            return;
+        };
+        self.pose(joints);
+        let start: Vec<f64> = self
+            .movable
+            .iter()
+            .map(|&(i, _, _)| joints[i].value_rad)
+            .collect();
+        let limits: Vec<(f64, f64)> = self
+            .movable
+            .iter()
+            .map(|&(i, _, _)| (joints[i].min_rad, joints[i].max_rad))
+            .collect();
+        let (movable, data, nv) = (&self.movable, &mut self.data, self.nv);
+        let solution = ik::solve(
+            self.target,
+            &start,
+            &limits,
+            &IkOptions::default(),
+            |angles| {
+                for (&(_, qposadr, _), &angle) in movable.iter().zip(angles) {
+                    data.qpos_mut()[qposadr] = angle;
+                }
+                data.kinematics();
+                data.com_pos();
+                let full = match point {
+                    IkPoint::Site(id) => data.jac_site(true, false, id).0,
+                    IkPoint::Body(id) => data.jac_body(true, false, id).0,
+                };
+                // Keep only the columns of the joints IK moves.
+                let n = movable.len();
+                let mut jacobian = vec![0.0; 3 * n];
+                for row in 0..3 {
+                    for (column, &(_, _, dofadr)) in movable.iter().enumerate() {
+                        jacobian[row * n + column] = full[row * nv + dofadr];
+                    }
+                }
+                (point_position(data, point), jacobian)
+            },
+        );
+        for (&(i, _, _), &angle) in self.movable.iter().zip(&solution.angles) {
+            joints[i].value_rad = angle;
+        }
+        self.last = Some(solution);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, joints: &mut [JointControl]) {
+        ui.label("Drag a target position; joint angles follow and are published.");
+        if self.points.is_empty() {
+            ui.label("This model has no named sites or bodies to place.");
+            return;
+        }
+
+        let before = self.selected;
+        egui::ComboBox::from_label("Point to place")
+            .selected_text(self.points[self.selected].0.as_str())
+            .show_ui(ui, |ui| {
+                for (i, (label, _)) in self.points.iter().enumerate() {
+                    ui.selectable_value(&mut self.selected, i, label.as_str());
+                }
+            });
+        if self.selected != before {
+            self.reset_target(joints);
+        }
+
+        let mut moved = false;
+        ui.horizontal(|ui| {
+            for (axis, value) in ["x", "y", "z"].into_iter().zip(&mut self.target) {
+                ui.label(axis);
+                moved |= ui
+                    .add(
+                        egui::DragValue::new(value)
+                            .speed(0.001)
+                            .suffix(" m")
+                            .max_decimals(4),
+                    )
+                    .changed();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Target ← current pose").clicked() {
+                self.reset_target(joints);
+            }
+            if ui.button("Solve").clicked() {
+                moved = true;
+            }
+        });
+        if moved {
+            self.solve(joints);
+        }
+
+        match &self.last {
+            Some(solution) if solution.converged => ui.label(format!(
+                "Reached in {} iterations ({:.2} mm off).",
+                solution.iterations,
+                solution.error * 1e3
+            )),
+            Some(solution) => ui.colored_label(
+                egui::Color32::YELLOW,
+                format!(
+                    "Out of reach: closest pose is {:.1} mm off.",
+                    solution.error * 1e3
+                ),
+            ),
+            None => ui.label(format!("{} joints can move the point.", self.movable.len())),
+        };
+    }
+}
+
+fn point_position(data: &MjData<Rc<MjModel>>, point: IkPoint) -> [f64; 3] {
+    match point {
+        IkPoint::Site(id) => data.site_xpos()[id as usize],
+        IkPoint::Body(id) => data.xpos()[id as usize],
+    }
+}
+
+/// A joint driven by a waveform instead of its slider.
+struct Generator {
+    joint: String,
+    waveform: Waveform,
+    /// Where the waveform's time starts.
+    started: Instant,
+}
+
+/// The Generators tab: which joints follow a waveform, and the joint to add next.
+#[derive(Default)]
+struct GeneratorPanel {
+    generators: Vec<Generator>,
+    pick: String,
+}
+
+impl GeneratorPanel {
+    fn drives(&self, joint: &str) -> bool {
+        self.generators.iter().any(|g| g.joint == joint)
+    }
+
+    /// Move every generated joint to its waveform's angle at `now`, within its range.
+    fn apply(&self, joints: &mut [JointControl], now: Instant) {
+        for generator in &self.generators {
+            let t = now.duration_since(generator.started).as_secs_f64();
+            let (angle, _) = generator.waveform.sample(t);
+            for j in joints.iter_mut().filter(|j| j.name == generator.joint) {
+                j.value_rad = clamp_to_range(angle, j.min_rad, j.max_rad);
+            }
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, joints: &[JointControl], degrees: bool) {
+        ui.label("Drive single joints with a waveform; the others stay on their sliders.");
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("generator_joint")
+                .selected_text(self.pick.as_str())
+                .show_ui(ui, |ui| {
+                    let generators = &self.generators;
+                    for j in joints
+                        .iter()
+                        .filter(|j| !generators.iter().any(|g| g.joint == j.name))
+                    {
+                        ui.selectable_value(&mut self.pick, j.name.clone(), &j.name);
+                    }
+                });
+            let joint = joints.iter().find(|j| j.name == self.pick);
+            let can_add = joint.is_some() && !self.drives(&self.pick);
+            if ui
+                .add_enabled(can_add, egui::Button::new("Add generator"))
+                .clicked()
+            {
+                if let Some(joint) = joint {
+                    // Centered where the joint is, so it starts without a jump.
+                    self.generators.push(Generator {
+                        joint: joint.name.clone(),
+                        waveform: Waveform {
+                            shape: Shape::Sine,
+                            amplitude: 0.2,
+                            frequency: 0.5,
+                            phase: 0.0,
+                            offset: joint.value_rad,
+                        },
+                        started: Instant::now(),
+                    });
+                }
+            }
+        });
+
+        let mut removed = None;
+        for (i, generator) in self.generators.iter_mut().enumerate() {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(&generator.joint);
+                    let waveform = &mut generator.waveform;
+                    egui::ComboBox::from_id_salt(("generator_shape", i))
+                        .selected_text(shape_label(waveform.shape))
+                        .show_ui(ui, |ui| {
+                            for shape in [
+                                Shape::Sine,
+                                Shape::Square,
+                                Shape::Chirp {
+                                    end_frequency: 2.0,
+                                    sweep: waveform::DEFAULT_SWEEP,
+                                },
+                            ] {
+                                let selected = std::mem::discriminant(&shape)
+                                    == std::mem::discriminant(&waveform.shape);
+                                if ui.selectable_label(selected, shape_label(shape)).clicked()
+                                    && !selected
+                                {
+                                    waveform.shape = shape;
+                                }
+                            }
+                        });
+                    if ui.button("Restart").clicked() {
+                        generator.started = Instant::now();
+                    }
+                    if ui.button("Remove").clicked() {
+                        removed = Some(i);
+                    }
+                });
+                let waveform = &mut generator.waveform;
+                ui.horizontal(|ui| {
+                    ui.label("Amplitude");
+                    angle_field(ui, &mut waveform.amplitude, degrees);
+                    ui.label("Center");
+                    angle_field(ui, &mut waveform.offset, degrees);
+                    ui.label("Frequency");
+                    ui.add(
+                        egui::DragValue::new(&mut waveform.frequency)
+                            .range(0.0..=50.0)
+                            .speed(0.01)
+                            .suffix(" Hz"),
+                    );
+                    if let Shape::Chirp {
+                        end_frequency,
+                        sweep,
+                    } = &mut waveform.shape
+                    {
+                        ui.label("to");
+                        ui.add(
+                            egui::DragValue::new(end_frequency)
+                                .range(0.0..=50.0)
+                                .speed(0.01)
+                                .suffix(" Hz"),
+                        );
+                        ui.label("over");
+                        ui.add(
+                            egui::DragValue::new(sweep)
+                                .range(0.1..=600.0)
+                                .speed(0.1)
+                                .suffix(" s"),
+                        );
+                    }
+                });
+            });
+        }
+        if let Some(i) = removed {
+            // The joint stays where the waveform left it.
+            self.generators.remove(i);
+        }
+    }
+}
+
+fn shape_label(shape: Shape) -> &'static str {
+    match shape {
+        Shape::Sine => "Sine",
+        Shape::Square => "Square (steps)",
+        Shape::Chirp { .. } => "Chirp",
+    }
+}
+
+/// A field for an angle kept in radians, shown in degrees when `degrees` is set.
+fn angle_field(ui: &mut egui::Ui, rad: &mut f64, degrees: bool) {
+    let (scale, suffix, decimals, speed) = if degrees {
+        (1f64.to_degrees(), "°", 2, 0.05)
+    } else {
+        (1.0, " rad", 4, 0.001)
+    };
+    let mut shown = *rad * scale;
+    let field = ui.add(
+        egui::DragValue::new(&mut shown)
+            .speed(speed)
+            .fixed_decimals(decimals)
+            .suffix(suffix),
+    );
+    if field.changed() {
+        *rad = shown / scale;
+    }
 }
 
 /// Hand pose presets for the ProHand MJCF joint naming scheme.
@@ -107,6 +671,16 @@ enum HandPreset {
 }
 
 impl HandPreset {
+    const ALL: [HandPreset; 7] = [
+        HandPreset::Fist,
+        HandPreset::OpenHand,
+        HandPreset::Scissor,
+        HandPreset::IndexFinger,
+        HandPreset::MiddleFinger,
+        HandPreset::RingFinger,
+        HandPreset::PinkyFinger,
+    ];
+
     fn label(self) -> &'static str {
         match self {
             HandPreset::Fist => "Fist",
@@ -252,37 +826,301 @@ fn set_thumb_curled(joints: &mut [JointControl]) {
     set_joint_fraction_of_range(joints, "t3_DIP", 0.95);
 }
 
+/// The slider group of joint `name`: its finger (see `finger::of`) by the last `/`
+/// segment, so prefixed names like `L/i1_MCP` group too, or `finger::OTHER`.
+fn joint_group(name: &str) -> &'static str {
+    finger::of(name.rsplit('/').next().unwrap_or(name))
+}
+
+fn group_label(group: &str) -> &'static str {
+    match group {
+        "thumb" => "Thumb",
+        "index" => "Index",
+        "middle" => "Middle",
+        "ring" => "Ring",
+        "pinky" => "Pinky",
+        _ => "Wrist / other",
+    }
+}
+
+/// Neutral posture for every joint of `group`.
+fn set_group_neutral(joints: &mut [JointControl], group: &str) {
+    for j in joints.iter_mut().filter(|j| joint_group(&j.name) == group) {
+        let v = clamp_to_range(0.0, j.min_rad, j.max_rad);
+        j.value_rad = v;
+        j.last_sent_value_rad = v;
+    }
+}
+
+/// One joint's row: a slider and a field to type an exact value into, both in degrees
+/// when `degrees` is set, or greyed out while a generator drives the joint. The joint
+/// keeps its value in radians. With `feedback`, the measured angle and how far it is
+/// from the slider follow, in the error color past the threshold.
+fn joint_row(
+    ui: &mut egui::Ui,
+    j: &mut JointControl,
+    degrees: bool,
+    generated: bool,
+    feedback: Option<(f64, f64)>,
+) {
+    let (scale, suffix, decimals, speed) = if degrees {
+        (1f64.to_degrees(), "°", 2, 0.05)
+    } else {
+        (1.0, " rad", 4, 0.001)
+    };
+    let range = j.min_rad * scale..=j.max_rad * scale;
+    let mut shown = j.value_rad * scale;
+    ui.horizontal(|ui| {
+        if generated {
+            ui.disable();
+        }
+        let slider = ui.add(egui::Slider::new(&mut shown, range.clone()).show_value(false));
+        let field = ui.add(
+            egui::DragValue::new(&mut shown)
+                .range(range)
+                .speed(speed)
+                .fixed_decimals(decimals)
+                .suffix(suffix),
+        );
+        if generated {
+            ui.label(format!("{} (generator)", j.name));
+        } else {
+            ui.label(&j.name);
+        }
+        if slider.changed() || field.changed() {
+            j.value_rad = clamp_to_range(shown / scale, j.min_rad, j.max_rad);
+        }
+        if let Some((measured, threshold)) = feedback {
+            let error = j.value_rad - measured;
+            let text = format!(
+                "actual {:.*}{suffix} (off by {:+.*})",
+                decimals,
+                measured * scale,
+                decimals,
+                error * scale
+            );
+            let color = if error.abs() > threshold {
+                ui.visuals().error_fg_color
+            } else {
+                ui.visuals().weak_text_color()
+            };
+            ui.colored_label(color, text);
+        }
+    });
+}
+
+/// Curled posture for `group`'s finger; the wrist / other group has none.
+fn set_group_curled(joints: &mut [JointControl], group: &str) {
+    match group {
+        "thumb" => set_thumb_curled(joints),
+        "index" => set_finger_curled(joints, 'i'),
+        "middle" => set_finger_curled(joints, 'm'),
+        "ring" => set_finger_curled(joints, 'r'),
+        "pinky" => set_finger_curled(joints, 'p'),
+        _ => {}
+    }
+}
+
 impl GuiPublisherApp {
-    fn new(cli: Cli) -> Result<Self> {
-        let model_path = std::fs::canonicalize(&cli.model).map_err(|e| {
-            anyhow::anyhow!(
-                "Failed to resolve model path '{}': {e}",
-                cli.model.display()
-            )
-        })?;
-        let model = MjModel::from_xml(&model_path).map_err(|e| {
-            anyhow::anyhow!("Failed to load MJCF '{}': {:?}", model_path.display(), e)
-        })?;
+    fn new(cli: Cli, config: Config) -> Result<Self> {
+        let topic = cli.topic.clone().unwrap_or_else(|| config.topic.clone());
+        let format = cli.publish.format(&config);
+        let heartbeat_ms = cli.heartbeat.millis(&config);
+        let model_file = cli.model.clone().unwrap_or_else(|| config.model.clone());
+        let filter_prefix = if cli.filter_prefix.is_empty() {
+            config.filter_prefix.clone()
+        } else {
+            cli.filter_prefix.clone()
+        };
+
+        let (model, joints) = load_model(&model_file, &filter_prefix)?;
 
         let publish_hz = cli.publish_hz.max(1);
         let publish_interval = Duration::from_secs_f64(1.0 / publish_hz as f64);
 
-        let ctx = Context::new();
-        let socket = ctx.socket(zmq::PUB)?;
-        socket.bind(&cli.bind)?;
+        // Not the config's `transport`: that may name MQTT or Zenoh, which this publisher does not speak.
+        let transport_kind = cli.transport.unwrap_or_default();
+        let mut connections = None;
+        let (transport, bind): (Box<dyn Transport>, String) = match transport_kind {
+            TransportKind::Zmq => {
+                let bind = cli.publish.bind(&config);
+                let zmq = ZmqTransport::bind(&Context::new(), &bind)?;
+                connections = Some(zmq.monitor()?);
+                (Box::new(zmq), bind)
+            }
+            TransportKind::Shm => (
+                Box::new(ShmTransport::create(&topic)?),
+                ring_path(&topic).display().to_string(),
+            ),
+            TransportKind::Mqtt | TransportKind::Zenoh => {
+                anyhow::bail!(
+                    "the slider publisher sends over ZMQ or shared memory, not {transport_kind:?}"
+                )
+            }
+        };
+        let clock = Arc::new(ClockSync::default());
+        let publisher = Publisher::new(transport, &topic)
+            .with_format(format)
+            .with_split_by_finger(cli.publish.split_by_finger)
+            .with_clock_sync(Arc::clone(&clock));
+        let publisher = match cli.deadband {
+            Some(epsilon) => publisher.with_deadband(epsilon, Duration::from_millis(cli.keepalive)),
+            None => publisher,
+        };
+
+        tracing::info!(
+            %bind,
+            %topic,
+            publish_hz,
+            joints = joints.len(),
+            "publishing slider joint angles"
+        );
 
-        let mut joints = enumerate_joint_controls(&model, &cli.filter_prefix);
-        // Keep ordering stable and user-friendly.
-        joints.sort_by(|a, b| a.name.cmp(&b.name));
+        let query = cli
+            .query
+            .as_deref()
+            .map(|endpoint| {
+                tracing::info!(%endpoint, "answering state queries");
+                QueryServer::bind(&Context::new(), endpoint)
+            })
+            .transpose()?;
+        let joint_names = joints.iter().map(|j| j.name.clone()).collect();
+        let model_info = model_info(&model, &model_file);
+        let ik = IkPanel::new(Rc::clone(&model), &joints);
+        let feedback = cli
+            .feedback
+            .as_deref()
+            .map(|feedback_topic| {
+                let transport: Box<dyn Transport> = match transport_kind {
+                    TransportKind::Shm => {
+                        tracing::info!(topic = %feedback_topic, "receiving feedback over shared memory");
+                        Box::new(ShmTransport::open(feedback_topic)?)
+                    }
+                    _ => {
+                        tracing::info!(endpoint = %cli.feedback_connect, topic = %feedback_topic, "receiving feedback");
+                        Box::new(ZmqTransport::connect(&Context::new(), &cli.feedback_connect)?)
+                    }
+                };
+                Subscriber::new(transport, feedback_topic)
+                    .map(|subscriber| subscriber.with_clock_sync(clock))
+            })
+            .transpose()?;
+        let presets = Presets::load(&cli.presets)?;
+        let random = cli
+            .random_constraints
+            .as_deref()
+            .map(Constraints::load)
+            .transpose()?
+            .unwrap_or_default();
+        let presets_path = cli.presets.display().to_string();
+        let (transition_ms, easing) = (cli.transition_ms, cli.easing);
+        let feedback_threshold = cli.feedback_threshold;
+        let sequence_path = cli
+            .sequence
+            .as_ref()
+            .map_or("sequences/wave.yaml".to_string(), |path| {
+                path.display().to_string()
+            });
 
-        Ok(Self {
+        let mut app = Self {
             cli,
-            socket,
+            publisher,
+            bind,
+            topic,
+            heartbeat_ms,
+            connections,
+            filter_prefix,
+            model_note: String::new(),
+            degrees: false,
             publish_interval,
             last_publish: Instant::now(),
+            last_heartbeat: None,
             seq: 0,
             joints,
-        })
+            query,
+            last_state: None,
+            joint_names,
+            model_info,
+            tab: Tab::Sliders,
+            ik,
+            status: Status::default(),
+            presets,
+            presets_path,
+            preset_name: String::new(),
+            presets_note: String::new(),
+            random,
+            random_seed: 0,
+            random_note: String::new(),
+            transition: None,
+            transition_ms,
+            easing,
+            sequence: None,
+            sequence_path,
+            player: Player::default(),
+            recording: false,
+            take: Vec::new(),
+            take_note: String::new(),
+            generators: GeneratorPanel::default(),
+            feedback,
+            measured: HashMap::new(),
+            last_feedback: None,
+            feedback_threshold,
+            sequence_note: String::new(),
+        };
+        if app.cli.sequence.is_some() {
+            app.sequence = Some(app.read_sequence()?);
+        }
+        Ok(app)
+    }
+
+    /// Ask for an MJCF file and switch the sliders to its joints. Joints both
+    /// models have keep their angles; publishing goes on throughout.
+    fn pick_model(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Load MJCF model")
+            .add_filter("MJCF", &["xml"])
+            .pick_file()
+        else {
+            return;
+        };
+        match load_model(&path, &self.filter_prefix) {
+            Ok((model, mut joints)) => {
+                for j in &mut joints {
+                    if let Some(old) = self.joints.iter().find(|old| old.name == j.name) {
+                        j.value_rad = clamp_to_range(old.value_rad, j.min_rad, j.max_rad);
+                        j.last_sent_value_rad = j.value_rad;
+                    }
+                }
+                // Whatever was moving the old sliders does not know the new ones.
+                self.transition = None;
+                self.player.stop();
+                self.joint_names = joints.iter().map(|j| j.name.clone()).collect();
+                self.model_info = model_info(&model, &path);
+                self.ik = IkPanel::new(Rc::clone(&model), &joints);
+                self.joints = joints;
+                tracing::info!(model = %path.display(), joints = self.joints.len(), "loaded model");
+                self.model_note = format!("{} joints", self.joints.len());
+            }
+            Err(e) => {
+                tracing::warn!(error = %format!("{e:#}"), "loading a model failed");
+                self.model_note = format!("{e:#}");
+            }
+        }
+    }
+
+    fn heartbeat_if_due(&mut self) {
+        let every = Duration::from_millis(self.heartbeat_ms);
+        if self.heartbeat_ms == 0
+            || self
+                .last_heartbeat
+                .is_some_and(|last| last.elapsed() < every)
+        {
+            return;
+        }
+        self.last_heartbeat = Some(Instant::now());
+        if let Err(e) = self.publisher.heartbeat() {
+            tracing::warn!(error = %errors::report(&e), "ZMQ heartbeat failed");
+        }
     }
 
     fn publish_if_due(&mut self) {
@@ -301,90 +1139,672 @@ impl GuiPublisherApp {
                 let vel = (j.value_rad - j.last_sent_value_rad) / dt;
                 j.last_sent_value_rad = j.value_rad;
                 JointAngles {
-                    timestamp: self.seq,
+                    timestamp: 0,
                     joint_name: j.name.clone(),
                     angle_rad: j.value_rad,
                     velocity: vel,
                     torque: 0.0,
+                    qpos: Vec::new(),
                 }
             })
             .collect();
 
         let robot_state = RobotState {
-            timestamp: self.seq,
+            timestamp: 0,
             robot_id: self.cli.robot_id.clone(),
             joints,
+            sent_at_us: None,
+            sequence: Some(self.seq),
+        };
+
+        match self.publisher.publish(&robot_state) {
+            Ok(()) => tracing::debug!(sequence = self.seq, "published"),
+            Err(e) => {
+                tracing::warn!(error = %errors::report(&e), sequence = self.seq, "ZMQ send failed")
+            }
+        }
+        if self.recording {
+            self.take.push((SystemTime::now(), robot_state.clone()));
+        }
+        self.last_state = Some(robot_state);
+    }
+
+    /// Record / Stop, the take's length, and its exports.
+    fn take_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.recording {
+                if ui.button("Stop recording").clicked() {
+                    self.recording = false;
+                }
+            } else if ui.button("Record").clicked() {
+                self.take.clear();
+                self.take_note.clear();
+                self.recording = true;
+            }
+            let seconds = match (self.take.first(), self.take.last()) {
+                (Some((first, _)), Some((last, _))) => last
+                    .duration_since(*first)
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+                _ => 0.0,
+            };
+            ui.label(format!("{} states, {seconds:.1} s", self.take.len()));
+            ui.add_enabled_ui(!self.recording && !self.take.is_empty(), |ui| {
+                if ui.button("Export MCAP…").clicked() {
+                    self.export_take(TakeFormat::Mcap);
+                }
+                if ui.button("Export CSV…").clicked() {
+                    self.export_take(TakeFormat::Csv);
+                }
+            });
+            if !self.take_note.is_empty() {
+                ui.label(&self.take_note);
+            }
+        });
+    }
+
+    /// Ask where to, then write the take there.
+    fn export_take(&mut self, format: TakeFormat) {
+        let (name, extension) = match format {
+            TakeFormat::Mcap => ("MCAP recording", "mcap"),
+            TakeFormat::Csv => ("CSV trajectory", "csv"),
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .set_title(format!("Export {name}"))
+            .set_file_name(format!("take.{extension}"))
+            .add_filter(name, &[extension])
+            .save_file()
+        else {
+            return;
+        };
+        let written = match format {
+            TakeFormat::Mcap => self.write_mcap(&path),
+            TakeFormat::Csv => self.write_csv(&path),
+        };
+        self.take_note = match written {
+            Ok(()) => {
+                tracing::info!(path = %path.display(), states = self.take.len(), "take exported");
+                format!("Saved {}", path.display())
+            }
+            Err(e) => {
+                tracing::warn!(error = %format!("{e:#}"), "exporting the take failed");
+                format!("{e:#}")
+            }
+        };
+    }
+
+    fn write_mcap(&self, path: &Path) -> Result<()> {
+        let session = Session {
+            topic: self.topic.clone(),
+            source: self.bind.clone(),
+            model: Some(PathBuf::from(&self.model_info.path)),
+        };
+        let mut recorder = Recorder::create(path, &session)?;
+        for (sent, state) in &self.take {
+            recorder.record(state, *sent)?;
+        }
+        recorder.finish()?;
+        Ok(())
+    }
+
+    fn write_csv(&self, path: &Path) -> Result<()> {
+        let start = self
+            .take
+            .first()
+            .map_or(SystemTime::UNIX_EPOCH, |(sent, _)| *sent);
+        let samples = self
+            .take
+            .iter()
+            .map(|(sent, state)| (sent.duration_since(start).unwrap_or_default(), state));
+        Trajectory::from_states(samples)?.save(path)?;
+        Ok(())
+    }
+
+    fn send_control(&mut self, command: ControlCommand) {
+        match self.publisher.control(command) {
+            Ok(()) => {
+                tracing::warn!(?command, "control command sent");
+                self.status = self.status.after(command);
+            }
+            Err(e) => tracing::error!(error = %errors::report(&e), ?command, "ZMQ send failed"),
+        }
+    }
+
+    /// Move the sliders to `pose` (joints it does not name stay put).
+    fn apply_pose(&mut self, pose: &Pose) {
+        self.go_to(|joints| {
+            for (joint, &angle) in pose {
+                set_joint_value(joints, joint, angle);
+            }
+        });
+    }
+
+    /// Move the sliders to where `set` puts them: over `transition_ms`, publishing
+    /// on the usual cadence on the way, or with 0 at once and published immediately.
+    fn go_to(&mut self, set: impl FnOnce(&mut [JointControl])) {
+        // A preset takes the sliders from a playing sequence.
+        self.player.pause(Instant::now());
+        if self.transition_ms == 0 {
+            self.transition = None;
+            set(&mut self.joints);
+            self.publish_now();
+            return;
+        }
+        let mut target = self.joints.clone();
+        set(&mut target);
+        self.transition = Some(Transition::new(
+            self.joints.iter().map(|j| j.value_rad).collect(),
+            target.iter().map(|j| j.value_rad).collect(),
+            Instant::now(),
+            Duration::from_millis(self.transition_ms),
+            self.easing,
+        ));
+    }
+
+    /// Move to the random pose for the seed field's seed, then step the field on so the
+    /// next click gives a new pose; typing the seed back repeats one.
+    fn random_pose(&mut self) {
+        let seed = self.random_seed;
+        let joints = self
+            .joints
+            .iter()
+            .map(|j| (j.name.as_str(), j.min_rad..=j.max_rad));
+        let pose = random_pose::random_pose(joints, seed, &self.random);
+        tracing::info!(seed, joints = pose.len(), "random pose");
+        self.random_note = format!("Pose from seed {seed}");
+        self.random_seed = seed.wrapping_add(1);
+        self.apply_pose(&pose);
+    }
+
+    /// `name`'s pose: a built-in preset (by label, in any case, with `_` or `-` for
+    /// spaces) or a saved one. A built-in's pose holds only the joints it sets.
+    fn preset_pose(&self, name: &str) -> Option<Pose> {
+        let wanted = name.to_lowercase().replace(['_', '-'], " ");
+        if let Some(preset) = HandPreset::ALL
+            .into_iter()
+            .find(|p| p.label().to_lowercase() == wanted)
+        {
+            let mut joints = self.joints.clone();
+            joints.iter_mut().for_each(|j| j.value_rad = f64::NAN);
+            preset.apply(&mut joints);
+            return Some(
+                joints
+                    .into_iter()
+                    .filter(|j| !j.value_rad.is_nan())
+                    .map(|j| (j.name, j.value_rad))
+                    .collect(),
+            );
+        }
+        self.presets
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, pose)| pose.clone())
+    }
+
+    fn read_sequence(&self) -> Result<Sequence, SequenceError> {
+        Sequence::load(Path::new(&self.sequence_path), |name| {
+            self.preset_pose(name)
+        })
+    }
+
+    /// Load the Sequence tab's file, stopped at its start.
+    fn load_sequence(&mut self) {
+        self.player.stop();
+        match self.read_sequence() {
+            Ok(sequence) => {
+                self.sequence_note = format!(
+                    "{} joints over {:.1} s",
+                    sequence.joint_names().len(),
+                    sequence.duration().as_secs_f64()
+                );
+                self.sequence = Some(sequence);
+            }
+            Err(e) => {
+                self.sequence_note = errors::report(&e);
+                self.sequence = None;
+            }
+        }
+    }
+
+    /// Move the sliders to where the playing sequence is, stopping at its end.
+    fn advance_sequence(&mut self) {
+        let (Some(sequence), true) = (&self.sequence, self.player.is_playing()) else {
+            return;
+        };
+        let elapsed = self.player.elapsed(Instant::now());
+        for (name, (angle, _)) in sequence.joint_names().iter().zip(sequence.sample(elapsed)) {
+            // Unlike presets, keep `last_sent_value_rad`, so states carry the motion's velocity.
+            for j in &mut self.joints {
+                if joint_name_matches_suffix(&j.name, name) {
+                    j.value_rad = clamp_to_range(angle, j.min_rad, j.max_rad);
+                }
+            }
+        }
+        if sequence.finished(elapsed) {
+            self.player.stop();
+        }
+    }
+
+    fn sequence_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Play a YAML keyframe sequence (see sequences/ for examples).");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.sequence_path).desired_width(260.0));
+            if ui.button("Load").clicked() {
+                self.load_sequence();
+            }
+        });
+        if !self.sequence_note.is_empty() {
+            ui.label(&self.sequence_note);
+        }
+        let Some(sequence) = &mut self.sequence else {
+            return;
+        };
+        let now = Instant::now();
+        ui.horizontal(|ui| {
+            if self.player.is_playing() {
+                if ui.button("Pause").clicked() {
+                    self.player.pause(now);
+                }
+            } else if ui.button("Play").clicked() {
+                // The sequence owns the sliders while it plays.
+                self.transition = None;
+                self.player.play(now);
+            }
+            if ui.button("Stop").clicked() {
+                self.player.stop();
+            }
+            let mut looping = sequence.looping();
+            if ui.checkbox(&mut looping, "Loop").changed() {
+                sequence.set_looping(looping);
+            }
+        });
+        let duration = sequence.duration().as_secs_f64();
+        let mut elapsed = self.player.elapsed(now).as_secs_f64();
+        if sequence.looping() && duration > 0.0 {
+            elapsed %= duration;
+        }
+        let fraction = if duration > 0.0 {
+            elapsed / duration
+        } else {
+            1.0
+        };
+        ui.add(
+            egui::ProgressBar::new(fraction as f32).text(format!("{elapsed:.1} / {duration:.1} s")),
+        );
+    }
+
+    /// Move the sliders along the current transition, ending it on arrival.
+    fn advance_transition(&mut self) {
+        let Some(transition) = &self.transition else {
+            return;
+        };
+        let now = Instant::now();
+        for (j, value) in self.joints.iter_mut().zip(transition.at(now)) {
+            j.value_rad = value;
+        }
+        if transition.done(now) {
+            self.transition = None;
+        }
+    }
+
+    /// Log subscribers connecting and going since the last frame.
+    fn poll_connections(&mut self) {
+        let Some(monitor) = &mut self.connections else {
+            return;
+        };
+        let events = match monitor.poll() {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!(error = %errors::report(&e), "ZMQ monitor failed");
+                return;
+            }
+        };
+        let subscribers = monitor.connections();
+        for event in events {
+            match event {
+                ConnectionEvent::Up(endpoint) => {
+                    tracing::info!(%endpoint, subscribers, "subscriber connected")
+                }
+                ConnectionEvent::Down(endpoint) => {
+                    tracing::warn!(%endpoint, subscribers, "subscriber disconnected")
+                }
+                ConnectionEvent::Retrying(endpoint) => {
+                    tracing::debug!(%endpoint, "retrying the broker")
+                }
+            }
+        }
+    }
+
+    /// Take in every feedback state that has arrived, keeping each joint's latest angle.
+    fn poll_feedback(&mut self) {
+        let Some(feedback) = &self.feedback else {
+            return;
+        };
+        loop {
+            match feedback.try_recv() {
+                Ok(Some(state)) => {
+                    for joint in state.joints {
+                        self.measured.insert(joint.joint_name, joint.angle_rad);
+                    }
+                    self.last_feedback = Some(Instant::now());
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::warn!(error = %errors::report(&e), "skipping a feedback message");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Where feedback comes from and how fresh it is, and the highlight threshold.
+    fn feedback_ui(&mut self, ui: &mut egui::Ui) {
+        if self.feedback.is_none() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            match self.last_feedback {
+                Some(at) => ui.label(format!("Feedback: {} ms old", at.elapsed().as_millis())),
+                None => ui.label("Feedback: nothing received yet"),
+            };
+            ui.label("Highlight beyond");
+            angle_field(ui, &mut self.feedback_threshold, self.degrees);
+        });
+    }
+
+    /// Force a publish regardless of cadence so the subscriber updates instantly.
+    fn publish_now(&mut self) {
+        self.last_publish = Instant::now() - self.publish_interval;
+        self.publish_if_due();
+    }
+
+    /// Add the current pose to the presets file as `preset_name`, keeping the
+    /// presets already in it.
+    fn save_preset(&mut self) {
+        let name = self.preset_name.trim();
+        if name.is_empty() {
+            self.presets_note = "Name the preset first".to_string();
+            return;
+        }
+        let path = PathBuf::from(&self.presets_path);
+        let pose = self
+            .joints
+            .iter()
+            .map(|j| (j.name.clone(), j.value_rad))
+            .collect();
+        let saved = Presets::load(&path).and_then(|mut presets| {
+            presets.insert(name, pose);
+            presets.save(&path).map(|()| presets)
+        });
+        self.presets_note = match saved {
+            Ok(presets) => {
+                self.presets = presets;
+                format!("Saved \"{name}\" to {}", path.display())
+            }
+            Err(e) => errors::report(&e),
+        };
+    }
+
+    fn load_presets(&mut self) {
+        let path = PathBuf::from(&self.presets_path);
+        self.presets_note = match Presets::load(&path) {
+            Ok(presets) => {
+                self.presets = presets;
+                format!(
+                    "Loaded {} preset(s) from {}",
+                    self.presets.iter().count(),
+                    path.display()
+                )
+            }
+            Err(e) => errors::report(&e),
         };
+    }
 
-        let Ok(json) = serde_json::to_string(&robot_state) else {
+    fn serve_queries(&self) {
+        let Some(query) = &self.query else {
             return;
         };
-        let msg = format!("{} {}", self.cli.topic, json);
-        let _ = self.socket.send(&msg, 0);
+        let snapshot = Snapshot {
+            state: self.last_state.as_ref(),
+            joint_names: &self.joint_names,
+            model_info: &self.model_info,
+        };
+        if let Err(e) = query.serve_pending(&snapshot) {
+            tracing::warn!(error = %errors::report(&e), "answering a query failed");
+        }
     }
 }
 
 impl eframe::App for GuiPublisherApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.advance_transition();
+        self.advance_sequence();
+        // Generators win over sliders, presets, and sequences for their joints.
+        self.generators.apply(&mut self.joints, Instant::now());
         self.publish_if_due();
+        self.heartbeat_if_due();
+        self.serve_queries();
+        self.poll_feedback();
+        self.poll_connections();
 
         egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(format!("Bind: {}", self.cli.bind));
+                ui.label(format!("Bind: {}", self.bind));
+                if let Some(monitor) = &self.connections {
+                    // Through a broker, the one connection is the broker's.
+                    let peers = if self.bind.starts_with('>') {
+                        "broker"
+                    } else {
+                        "subscribers"
+                    };
+                    ui.label(format!("({} {peers})", monitor.connections()));
+                }
                 ui.separator();
-                ui.label(format!("Topic: {}", self.cli.topic));
+                ui.label(format!("Topic: {}", self.topic));
                 ui.separator();
                 ui.label(format!("Hz: {}", self.cli.publish_hz.max(1)));
                 ui.separator();
                 ui.label(format!("Robot: {}", self.cli.robot_id));
             });
+            ui.horizontal(|ui| {
+                ui.label(format!("Model: {}", self.model_info.path));
+                if ui.button("Load model…").clicked() {
+                    self.pick_model();
+                }
+                if !self.model_note.is_empty() {
+                    ui.label(&self.model_note);
+                }
+            });
+            ui.horizontal(|ui| {
+                let estop = egui::Button::new(
+                    egui::RichText::new("E-STOP")
+                        .size(24.0)
+                        .strong()
+                        .color(egui::Color32::WHITE),
+                )
+                .fill(egui::Color32::from_rgb(200, 0, 0))
+                .min_size(egui::vec2(140.0, 48.0));
+                if ui.add(estop).clicked() {
+                    self.send_control(ControlCommand::EStop);
+                }
+                if ui.button("Hold").clicked() {
+                    self.send_control(ControlCommand::Hold);
+                }
+                if ui.button("Resume").clicked() {
+                    self.send_control(ControlCommand::Resume);
+                }
+                ui.separator();
+                match self.status {
+                    Status::Running => ui.label("Subscribers follow the sliders"),
+                    Status::Held => ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "Held: subscribers ignore the sliders until Resume",
+                    ),
+                    Status::Stopped => ui.colored_label(
+                        egui::Color32::RED,
+                        "EMERGENCY STOP: subscribers ignore the sliders until Resume",
+                    ),
+                };
+            });
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Joint sliders");
-            ui.label("Move sliders to publish joint angles (radians).");
-
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, Tab::Sliders, "Joint sliders");
+                ui.selectable_value(&mut self.tab, Tab::Ik, "IK");
+                ui.selectable_value(&mut self.tab, Tab::Sequence, "Sequence");
+                ui.selectable_value(&mut self.tab, Tab::Generators, "Generators");
+            });
             ui.separator();
 
-            ui.group(|ui| {
-                ui.label("Presets (click to set sliders and publish immediately):");
-                ui.horizontal_wrapped(|ui| {
-                    let presets = [
-                        HandPreset::Fist,
-                        HandPreset::OpenHand,
-                        HandPreset::Scissor,
-                        HandPreset::IndexFinger,
-                        HandPreset::MiddleFinger,
-                        HandPreset::RingFinger,
-                        HandPreset::PinkyFinger,
-                    ];
-                    for p in presets {
-                        if ui.button(p.label()).clicked() {
-                            p.apply(&mut self.joints);
-                            // Force a publish regardless of cadence so the subscriber updates instantly.
-                            self.last_publish = Instant::now() - self.publish_interval;
-                            self.publish_if_due();
+            match self.tab {
+                Tab::Sliders => {
+                    ui.label(
+                        "Move sliders or type values to publish joint angles (sent in radians).",
+                    );
+                    ui.group(|ui| {
+                        ui.label("Presets (click to set sliders and publish immediately):");
+                        ui.horizontal_wrapped(|ui| {
+                            for p in HandPreset::ALL {
+                                if ui.button(p.label()).clicked() {
+                                    self.go_to(|joints| p.apply(joints));
+                                }
+                            }
+                        });
+                        if !self.presets.is_empty() {
+                            let mut clicked = None;
+                            ui.horizontal_wrapped(|ui| {
+                                for (name, pose) in self.presets.iter() {
+                                    if ui.button(name).clicked() {
+                                        clicked = Some(pose.clone());
+                                    }
+                                }
+                            });
+                            if let Some(pose) = clicked {
+                                self.apply_pose(&pose);
+                            }
                         }
-                    }
-                });
-            });
+                        ui.horizontal(|ui| {
+                            if ui.button("Random pose").clicked() {
+                                self.random_pose();
+                            }
+                            ui.label("seed");
+                            ui.add(egui::DragValue::new(&mut self.random_seed));
+                            ui.checkbox(&mut self.random.exclude_abduction, "Exclude abduction");
+                            ui.label("Range used");
+                            ui.add(
+                                egui::DragValue::new(&mut self.random.max_fraction)
+                                    .range(0.05..=1.0)
+                                    .speed(0.01)
+                                    .fixed_decimals(2),
+                            );
+                            if !self.random_note.is_empty() {
+                                ui.label(&self.random_note);
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.preset_name)
+                                    .hint_text("preset name")
+                                    .desired_width(120.0),
+                            );
+                            if ui.button("Save current pose as preset…").clicked() {
+                                self.save_preset();
+                            }
+                            ui.separator();
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.presets_path)
+                                    .desired_width(200.0),
+                            );
+                            if ui.button("Load presets file").clicked() {
+                                self.load_presets();
+                            }
+                        });
+                        if !self.presets_note.is_empty() {
+                            ui.label(&self.presets_note);
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("Transition:");
+                            ui.add(
+                                egui::DragValue::new(&mut self.transition_ms)
+                                    .range(0..=10_000)
+                                    .suffix(" ms"),
+                            );
+                            egui::ComboBox::from_id_salt("easing")
+                                .selected_text(self.easing.label())
+                                .show_ui(ui, |ui| {
+                                    for easing in Easing::ALL {
+                                        ui.selectable_value(
+                                            &mut self.easing,
+                                            easing,
+                                            easing.label(),
+                                        );
+                                    }
+                                });
+                            if self.transition.is_some() && ui.button("Cancel").clicked() {
+                                // Stay wherever the sliders have got to.
+                                self.transition = None;
+                            }
+                        });
+                    });
+                }
+                Tab::Ik => self.ik.ui(ui, &mut self.joints),
+                Tab::Sequence => self.sequence_ui(ui),
+                Tab::Generators => self.generators.ui(ui, &self.joints, self.degrees),
+            }
 
+            self.feedback_ui(ui);
+            // Group buttons go through `go_to` like presets, after the sliders let go of `joints`.
+            let mut group_clicked: Option<(&'static str, bool)> = None;
+            let degrees = self.degrees;
             egui::ScrollArea::vertical()
                 .auto_shrink([false; 2])
                 .show(ui, |ui| {
-                    for j in &mut self.joints {
-                        // Avoid borrowing `j` immutably while also borrowing `j.value_rad` mutably.
-                        let range = j.min_rad..=j.max_rad;
-                        ui.add(egui::Slider::new(&mut j.value_rad, range).text(&j.name));
+                    for group in finger::FINGERS.into_iter().chain([finger::OTHER]) {
+                        let count = self
+                            .joints
+                            .iter()
+                            .filter(|j| joint_group(&j.name) == group)
+                            .count();
+                        if count == 0 {
+                            continue;
+                        }
+                        egui::CollapsingHeader::new(format!("{} ({count})", group_label(group)))
+                            .id_salt(group)
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.small_button("Zero").clicked() {
+                                        group_clicked = Some((group, false));
+                                    }
+                                    if group != finger::OTHER && ui.small_button("Curl").clicked() {
+                                        group_clicked = Some((group, true));
+                                    }
+                                });
+                                for j in self
+                                    .joints
+                                    .iter_mut()
+                                    .filter(|j| joint_group(&j.name) == group)
+                                {
+                                    let generated = self.generators.drives(&j.name);
+                                    let measured = self.measured.get(&j.name).copied();
+                                    let feedback = measured.map(|m| (m, self.feedback_threshold));
+                                    joint_row(ui, j, degrees, generated, feedback);
+                                }
+                            });
                     }
                 });
+            match group_clicked {
+                Some((group, true)) => self.go_to(|joints| set_group_curled(joints, group)),
+                Some((group, false)) => self.go_to(|joints| set_group_neutral(joints, group)),
+                None => {}
+            }
 
             ui.separator();
+            self.take_ui(ui);
             ui.horizontal(|ui| {
                 if ui.button("Publish once now").clicked() {
-                    // Force a publish regardless of cadence.
-                    self.last_publish = Instant::now() - self.publish_interval;
-                    self.publish_if_due();
+                    self.publish_now();
                 }
                 if ui.button("Zero all joints").clicked() {
                     for j in &mut self.joints {
@@ -392,6 +1812,10 @@ impl eframe::App for GuiPublisherApp {
                         j.last_sent_value_rad = 0.0;
                     }
                 }
+                ui.separator();
+                ui.label("Show:");
+                ui.selectable_value(&mut self.degrees, false, "rad");
+                ui.selectable_value(&mut self.degrees, true, "deg");
             });
         });
 
@@ -400,6 +1824,31 @@ impl eframe::App for GuiPublisherApp {
     }
 }
 
+/// Load the MJCF at `path` (resolving its `<include/>`s next to it) and one
+/// slider per joint matching `filter_prefix`, sorted by name.
+fn load_model(path: &Path, filter_prefix: &[String]) -> Result<(Rc<MjModel>, Vec<JointControl>)> {
+    let model_path = std::fs::canonicalize(path)
+        .with_context(|| format!("resolving model path '{}'", path.display()))?;
+    let model =
+        Rc::new(MjModel::from_xml(&model_path).map_err(|e| {
+            anyhow::anyhow!("Failed to load MJCF '{}': {:?}", model_path.display(), e)
+        })?);
+    let mut joints = enumerate_joint_controls(&model, filter_prefix);
+    // Keep ordering stable and user-friendly.
+    joints.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok((model, joints))
+}
+
+fn model_info(model: &MjModel, path: &Path) -> ModelInfo {
+    ModelInfo {
+        path: path.display().to_string(),
+        joints: model.ffi().njnt.max(0) as usize,
+        nq: model.ffi().nq.max(0) as usize,
+        nv: model.ffi().nv.max(0) as usize,
+        timestep: model.opt().timestep,
+    }
+}
+
 fn enumerate_joint_controls(model: &MjModel, filter_prefix: &[String]) -> Vec<JointControl> {
     let njnt = model.ffi().njnt.max(0) as usize;
 
@@ -447,6 +1896,7 @@ fn enumerate_joint_controls(model: &MjModel, filter_prefix: &[String]) -> Vec<Jo
 
         out.push(JointControl {
             name: name.to_string(),
+            id,
             value_rad: 0.0,
             min_rad,
             max_rad,
@@ -457,8 +1907,20 @@ fn enumerate_joint_controls(model: &MjModel, filter_prefix: &[String]) -> Vec<Jo
     out
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    tracing::info!("starting GUI joint publisher");
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([520.0, 900.0]),
@@ -468,7 +1930,7 @@ fn main() -> Result<()> {
     eframe::run_native(
         "ProHand Joint Publisher (egui)",
         native_options,
-        Box::new(move |_cc| Ok(Box::new(GuiPublisherApp::new(cli)?))),
+        Box::new(move |_cc| Ok(Box::new(GuiPublisherApp::new(cli, config)?))),
     )
     .map_err(|e| anyhow::anyhow!("Failed to start egui app: {e}"))?;
 
@@ -0,0 +1,143 @@
+// Sequence publisher binary - plays a YAML keyframe gesture (see `sequence` in the
+// library) as robot joint angles via ZMQ or an MQTT broker; no MuJoCo needed
+
+use anyhow::Result;
+use clap::Parser;
+use common::LogOutput;
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::presets::Presets;
+use project_robot_joint_pubsub::sequence::Sequence;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    sim, MqttTransport, Publisher, ShmTransport, Transport, TransportKind, ZmqTransport,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "publisher_sequence")]
+#[command(about = "Publish a YAML keyframe gesture sequence as robot joint angles")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// YAML keyframe file, e.g. sequences/wave.yaml
+    sequence: PathBuf,
+    /// Presets file that `preset:` keyframes are looked up in (see the GUI publisher)
+    #[arg(long, value_name = "FILE", default_value = "hand_presets.toml")]
+    presets: PathBuf,
+    /// Start over after the last keyframe, until interrupted (default: the file's `loop`)
+    #[arg(long = "loop", conflicts_with = "once")]
+    repeat: bool,
+    /// Stop after the last keyframe even if the file says `loop: true`
+    #[arg(long)]
+    once: bool,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Publishing interval in milliseconds (default: config `interval_ms`, else 100)
+    #[arg(short, long)]
+    interval: Option<u64>,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    /// `robot_id` on published states
+    #[arg(long, default_value = sim::ROBOT_ID)]
+    robot_id: String,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let interval = cli.interval.unwrap_or(config.interval_ms);
+    let topic = config.topic.clone();
+    let format = cli.publish.format(&config);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    let presets = Presets::load(&cli.presets)?;
+    let mut sequence = Sequence::load(&cli.sequence, |name| {
+        presets
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, pose)| pose.clone())
+    })?;
+    if cli.repeat || cli.once {
+        sequence.set_looping(cli.repeat);
+    }
+
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, interval_ms = interval, %topic, ?format, "starting sequence publisher");
+            Box::new(ZmqTransport::bind(&Context::new(), &bind)?)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, interval_ms = interval, %topic, ?format, "starting sequence publisher over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(interval_ms = interval, %topic, ?format, "starting sequence publisher over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, interval_ms = interval, %topic, ?format, "starting sequence publisher over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_split_by_finger(cli.publish.split_by_finger);
+
+    // Give subscribers time to connect
+    tracing::info!("waiting for subscribers to connect");
+    sleep(Duration::from_millis(500)).await;
+
+    let step = Duration::from_millis(interval);
+    tracing::info!(
+        sequence = %cli.sequence.display(),
+        joints = sequence.joint_names().len(),
+        duration_s = sequence.duration().as_secs_f64(),
+        looping = sequence.looping(),
+        "publishing sequence"
+    );
+
+    let mut last_heartbeat: Option<Instant> = None;
+    for timestamp in 1.. {
+        let elapsed = step * (timestamp - 1) as u32;
+        // One tick past the end publishes the last keyframe exactly, then stop.
+        if sequence.finished(elapsed.saturating_sub(step)) {
+            tracing::info!("sequence finished");
+            break;
+        }
+        let robot_state = sequence.state_at(elapsed, timestamp, &cli.robot_id);
+        publisher.publish(&robot_state)?;
+        tracing::debug!(timestamp, elapsed_s = elapsed.as_secs_f64(), "published");
+
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+
+        sleep(step).await;
+    }
+    Ok(())
+}
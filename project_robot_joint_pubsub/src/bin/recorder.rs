@@ -0,0 +1,143 @@
+//! Recorder: subscribes to robot joint angles via ZMQ or MQTT and writes every
+//! `RobotState`, with its receive time, to an MCAP file for later analysis or
+//! playback (see `recording` in the library).
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, ConnectArgs, DecodeArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::recording::{Recorder, Session};
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::transport::ring_path;
+use project_robot_joint_pubsub::{
+    AsyncSubscriber, MqttTransport, ShmTransport, Subscriber, Transport, TransportKind,
+    ZmqTransport,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "recorder")]
+#[command(about = "Record robot joint angles to an MCAP file")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Output file (default: <topic>-<unix seconds>.mcap)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    connect: ConnectArgs,
+    /// Topic to record (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    decode: DecodeArgs,
+    /// MJCF model the states drive, stored in the file's metadata
+    /// (default: config `model`, else pro-models/example/scenes/left_hand_scene.xml)
+    #[arg(long)]
+    model: Option<PathBuf>,
+    /// Stop after this many states
+    #[arg(long)]
+    count: Option<u32>,
+    /// Stop after this many seconds
+    #[arg(long)]
+    duration: Option<u64>,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let source = match transport {
+        TransportKind::Zmq => cli.connect.connect(&config),
+        TransportKind::Mqtt => cli.transport.broker(&config),
+        TransportKind::Shm => ring_path(&topic).display().to_string(),
+        TransportKind::Zenoh if config.zenoh.is_empty() => "Zenoh scouting".to_string(),
+        TransportKind::Zenoh => config.zenoh.join(","),
+    };
+    let receiver: Box<dyn Transport> = match transport {
+        TransportKind::Zmq => {
+            let mut zmq = ZmqTransport::connect(&Context::new(), &source)?;
+            if cli.decode.legacy_frames {
+                zmq = zmq.accept_legacy_frames();
+            }
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+        TransportKind::Shm => Box::new(ShmTransport::open(&topic)?),
+        TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+    };
+    let mut subscriber = Subscriber::new(receiver, &topic)?;
+    if let Some(format) = cli.decode.format {
+        subscriber = subscriber.with_format(format);
+    }
+
+    let output = cli.output.unwrap_or_else(|| default_output(&topic));
+    let session = Session {
+        topic: topic.clone(),
+        source: source.clone(),
+        model: Some(cli.model.unwrap_or(config.model)),
+    };
+    let mut recorder = Recorder::create(&output, &session)?;
+    tracing::info!(?transport, %source, %topic, output = %output.display(), "recording; Ctrl-C to stop");
+
+    let mut states = AsyncSubscriber::spawn(subscriber);
+    let deadline = sleep(cli.duration.map_or(Duration::MAX, Duration::from_secs));
+    tokio::pin!(deadline);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    while cli.count.is_none_or(|count| recorder.len() < count) {
+        tokio::select! {
+            received = states.recv() => match received.context("receiving thread died")? {
+                Ok(state) => {
+                    recorder.record(&state, SystemTime::now())?;
+                    tracing::debug!(timestamp = state.timestamp, "recorded");
+                }
+                Err(e) if e.exit() == Exit::Network => {
+                    tracing::error!(error = %errors::report(&e), "receive error");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %errors::report(&e), "skipping message");
+                }
+            },
+            () = &mut deadline => break,
+            signal = &mut ctrl_c => {
+                signal.context("waiting for Ctrl-C")?;
+                break;
+            }
+        }
+    }
+    let recorded = recorder.len();
+    // Readers reject a file without the index and footer `finish` writes.
+    recorder.finish()?;
+    tracing::info!(states = recorded, output = %output.display(), "recording written");
+    Ok(())
+}
+
+fn default_output(topic: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    PathBuf::from(format!("{topic}-{stamp}.mcap"))
+}
@@ -0,0 +1,147 @@
+//! Serial bridge binary: republishes joint states a microcontroller writes to a
+//! serial port (UART, USB CDC) via ZMQ or an MQTT broker, so encoders on real
+//! hardware can drive the MuJoCo viewer.
+//!
+//! Behind the scenes (high level):
+//! - Opens `--port` at `--baud` (8N1, no flow control) through the `serialport` crate.
+//! - Splits what it reads into frames, newline-delimited JSON or COBS
+//!   (`--framing`), and decodes each as a `RobotState` (see `serial` in the library).
+//! - Publishes every state as soon as its frame ends, so the firmware sets the rate;
+//!   frames that do not decode are dropped, with a warning at most once a second.
+//! - Heartbeats go out as the headless publisher sends them, frames or not.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::serial::{Deframer, Framing};
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::{
+    MqttTransport, Publisher, ShmTransport, Transport, TransportKind, ZmqTransport,
+};
+use std::io::{self, Read as _};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "serial_bridge")]
+#[command(about = "Robot joint angles publisher fed by a microcontroller over a serial port")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Serial port to read, e.g. /dev/ttyACM0 or COM3
+    #[arg(long, value_name = "PORT")]
+    port: String,
+    /// Baud rate
+    #[arg(long, default_value_t = 115_200)]
+    baud: u32,
+    /// How frames are delimited: newline-delimited JSON, or COBS with any payload format
+    #[arg(long, value_enum, default_value_t = Framing::Json)]
+    framing: Framing,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Publish topic (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let format = cli.publish.format(&config);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    // Reads wake up at least this often, for heartbeats while the port is quiet.
+    let mut port = serialport::new(&cli.port, cli.baud)
+        .timeout(Duration::from_millis(100))
+        .open()
+        .map_err(io::Error::from)
+        .with_context(|| format!("opening {}", cli.port))?;
+
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, %topic, ?format, "starting serial bridge");
+            Box::new(ZmqTransport::bind(&Context::new(), &bind)?)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, ?format, "starting serial bridge over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, ?format, "starting serial bridge over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, ?format, "starting serial bridge over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_split_by_finger(cli.publish.split_by_finger);
+    tracing::info!(
+        port = %cli.port,
+        baud = cli.baud,
+        framing = ?cli.framing,
+        "waiting for frames"
+    );
+
+    let mut deframer = Deframer::new(cli.framing);
+    let mut buf = [0; 4096];
+    let mut published = 0u64;
+    let mut dropped = 0u64;
+    let mut last_warning: Option<Instant> = None;
+    let mut last_heartbeat: Option<Instant> = None;
+    loop {
+        let read = match port.read(&mut buf) {
+            Ok(0) => anyhow::bail!("{} closed", cli.port),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => 0,
+            Err(e) => return Err(e).with_context(|| format!("reading {}", cli.port)),
+        };
+        for frame in deframer.push(&buf[..read]) {
+            match frame {
+                Ok(robot_state) => {
+                    publisher.publish(&robot_state)?;
+                    published += 1;
+                    tracing::debug!(timestamp = robot_state.timestamp, published, "published");
+                }
+                Err(e) => {
+                    dropped += 1;
+                    if last_warning.is_none_or(|last| last.elapsed() >= Duration::from_secs(1)) {
+                        tracing::warn!(error = %errors::report(&e), dropped, "dropping frame");
+                        last_warning = Some(Instant::now());
+                    }
+                }
+            }
+        }
+
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+    }
+}
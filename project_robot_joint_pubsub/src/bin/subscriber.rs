@@ -1,57 +1,417 @@
-//! Subscriber binary: receives robot joint angles via ZMQ and visualizes them in MuJoCo.
+//! Subscriber binary: receives robot joint angles via ZMQ or MQTT and visualizes them in MuJoCo.
 //!
 //! Behind the scenes (high level):
-//! - Loads an MJCF model from disk (supports `<include .../>`).
-//! - Starts MuJoCo's C++ viewer (via `mujoco-rs` `cpp-viewer` feature).
-//! - Receives `RobotState` messages and applies joint positions/velocities into `MjData`.
-//! - Each loop: sync viewer state → render UI → run `mj_forward` (no time integration).
+//! - Loads an MJCF model from disk (supports `<include .../>`), or with several
+//!   `--model ROBOT_ID=PATH`, one scene holding them side by side with each robot's
+//!   states driving its own model, or with `ROBOT_ID@TOPIC=PATH`, each topic's
+//!   (see `scene` in the library).
+//! - With `--mirror L=R`, also drives robot `R` with the mirror image of robot `L`'s
+//!   states (see `mirror` in the library).
+//! - With `--joints` or `--joint-prefix`, applies and logs only those joints of each
+//!   state, leaving the rest of the model to other publishers (see `joint_filter`).
+//! - Starts MuJoCo's C++ viewer (via `mujoco-rs` `cpp-viewer` feature), with contact
+//!   points, contact forces, joint axes, or transparent geoms drawn from the start given
+//!   `--show` (the viewer's own C, F, J, and T keys toggle them while it runs), and the
+//!   camera at a `--camera` preset or saved viewpoint; `--save-camera` writes where the
+//!   camera was when the viewer closed (see `camera` in the library).
+//! - With `--render-to`, renders offscreen instead (MuJoCo's offscreen buffer, no window)
+//!   at `--render-fps` from the first state until the stream falls silent, writing a
+//!   video through `ffmpeg` or a directory of PNG frames (see `video` in the library).
+//! - Receives `RobotState` messages and applies joint positions/velocities into `MjData`,
+//!   optionally interpolating positions between the last two states every frame
+//!   (`--interpolation`). Batches from `publisher --batch` are applied state by state,
+//!   or only their newest state with `--batch-latest`. With `--keep-latest`, only the
+//!   newest of the states queued during a frame is applied, so a stalled viewer
+//!   catches up rather than replaying them (`--receive-hwm` also bounds the ZMQ queue).
+//! - Warns when neither states nor heartbeats have arrived for `--liveness-timeout`
+//!   milliseconds, and optionally freezes or zeroes the pose until they do (`--on-silence`).
+//!   Over ZMQ it also logs publishers connecting and disconnecting (ZMQ's socket
+//!   monitor), and says how many are connected in the warning and `--stats`.
+//! - Honors control commands on `<topic>/control` (see `control` in the library): an
+//!   emergency stop freezes or zeroes the pose (`--on-estop`) and a hold keeps it, both
+//!   ignoring states until a resume.
+//! - With `--query`, answers `get_state`, `get_joint_names`, and `get_model_info`
+//!   on a ZMQ REP socket (see `query` in the library).
+//! - With `--publish-poses`, republishes the world poses of sites or bodies (the
+//!   fingertips) after each new state, computed by `mj_forward` (see `poses` in the library).
+//! - With `--publish-feedback` (`--publish-actual`), republishes where the latest state's
+//!   joints actually are in the simulation on `robot_joints_actual`, for the GUI publisher
+//!   to compare and other tools to plot or log (see `feedback` in the library).
+//! - With `--stats`, logs (and optionally writes to CSV) receive rate, jitter, and latency.
+//!   Latency is corrected by the publisher's clock offset once heartbeat round trips over
+//!   `--publish-feedback` give one (see `clock` in the library).
+//! - With `--metrics`, serves Prometheus counters and gauges on `/metrics` (see
+//!   `metrics` in the library).
+//! - Each loop: sync viewer state → render UI → run `mj_forward` (no time integration),
+//!   or with `--mode actuate`, write the angles to the joints' actuators' `ctrl` and
+//!   run `mj_step`, so the dynamics (coupling, limits, gains) move the joints; with
+//!   `--mode pd`, apply PD forces towards the angles, run `mj_step`, and log how
+//!   far the joints lag them (see `pd` in the library). Stepping modes take as many
+//!   `mj_step`s a frame as keep simulated time with wall time, then sleep what is left.
+//! - With `--safety`, ramps each commanded position towards the received one within
+//!   per-joint velocity and acceleration limits, in every mode (see `safety` in the library).
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use clap::Parser;
-use project_robot_joint_pubsub::RobotState;
-use std::collections::HashMap;
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, DecodeArgs, TransportArgs};
+use project_robot_joint_pubsub::camera::{CameraArg, Viewpoint};
+use project_robot_joint_pubsub::clock::ClockSync;
+use project_robot_joint_pubsub::control::Status;
+use project_robot_joint_pubsub::feedback;
+use project_robot_joint_pubsub::interpolation::Interpolation;
+use project_robot_joint_pubsub::joint_filter::JointFilter;
+use project_robot_joint_pubsub::joint_log::JointLog;
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::metrics::Metrics;
+use project_robot_joint_pubsub::mirror::Mirror;
+use project_robot_joint_pubsub::pd::{Gains, JointGains, Tracking};
+use project_robot_joint_pubsub::poses;
+use project_robot_joint_pubsub::query::{ModelInfo, QueryServer, Snapshot};
+use project_robot_joint_pubsub::safety::{SafetyFilter, SafetyLimits};
+use project_robot_joint_pubsub::scene::{self, ModelArg, Route, Router};
+use project_robot_joint_pubsub::stats::Stats;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::video::{Size, VideoOut};
+use project_robot_joint_pubsub::{
+    ConnectionEvent, MqttTransport, Publisher, ShmTransport, Subscriber, Transport, TransportKind,
+    ZmqMonitor, ZmqTransport,
+};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use zmq::Context;
 
 use mujoco_rs::cpp_viewer::MjViewerCpp;
 use mujoco_rs::prelude::*;
+use mujoco_rs::renderer::MjRenderer;
+
+/// The most timesteps one frame integrates to catch simulated time up with wall
+/// time; a frame further behind than that lets the rest go rather than owe it.
+const MAX_SUBSTEPS: usize = 20;
 
 #[derive(Parser)]
 #[command(name = "subscriber")]
 #[command(about = "Robot joint angles subscriber with MuJoCo visualization")]
 struct Cli {
-    /// ZMQ connect address (default: tcp://localhost:5555)
-    #[arg(short, long, default_value = "tcp://localhost:5555")]
-    connect: String,
-    /// Filter topic (default: robot_joints)
-    #[arg(short, long, default_value = "robot_joints")]
-    topic: String,
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    transport: TransportArgs,
+    /// ZMQ connect address, repeatable or comma-separated to hear several publishers
+    /// (default: config `connect`, else tcp://localhost:5555)
+    #[arg(short, long, value_delimiter = ',')]
+    connect: Vec<String>,
+    /// Topic to take states from, repeatable; end it in `*` to take every topic
+    /// with that prefix, e.g. `hands/*` (default: config `topic`, else robot_joints)
+    #[arg(short, long = "topic", value_name = "TOPIC")]
+    topics: Vec<String>,
+    #[command(flatten)]
+    decode: DecodeArgs,
+    /// Apply only the newest state of each batch from `publisher --batch`,
+    /// instead of every one in order
+    #[arg(long)]
+    batch_latest: bool,
+    /// Apply only the newest of the states queued while a frame rendered (per robot
+    /// and topic), so a stalled viewer catches up instead of replaying them
+    #[arg(long)]
+    keep_latest: bool,
+    /// Queue at most N messages from each ZMQ publisher, dropping newer ones past
+    /// that (default: ZMQ's 1000)
+    #[arg(long, value_name = "N")]
+    receive_hwm: Option<i32>,
     /// MJCF model path (supports `<include/>`), relative to `project_robot_joint_pubsub/`
-    #[arg(long, default_value = "pro-models/example/scenes/left_hand_scene.xml")]
-    model: PathBuf,
+    /// (default: config `model`, else pro-models/example/scenes/left_hand_scene.xml).
+    /// Repeat as ROBOT_ID=PATH to show several robots, each driven by its own states,
+    /// or as ROBOT_ID@TOPIC=PATH to drive one by the states on TOPIC
+    #[arg(long = "model", value_name = "[ROBOT_ID[@TOPIC]=]PATH")]
+    models: Vec<ModelArg>,
+    /// Also drive robot TO with the mirror image of robot FROM's states (or of
+    /// its FROM/... joints), e.g. L=R
+    #[arg(long, value_name = "FROM=TO")]
+    mirror: Option<Mirror>,
+    /// Joints whose sign flips under --mirror, comma-separated
+    /// (default: those with `abd` in their name)
+    #[arg(long, value_name = "JOINT", value_delimiter = ',', requires = "mirror")]
+    mirror_flip: Vec<String>,
+    /// Apply and log only these joints, comma-separated, leaving the others where
+    /// they are; e.g. i1_MCP,t2_CMC when another publisher owns the rest of the hand
+    #[arg(long, value_name = "JOINT", value_delimiter = ',')]
+    joints: Vec<String>,
+    /// Apply and log only joints whose names start with PREFIX, repeatable or
+    /// comma-separated; with --joints, those too
+    #[arg(long, value_name = "PREFIX", value_delimiter = ',')]
+    joint_prefix: Vec<String>,
+    /// Smooth motion between states, one publish interval behind them
+    /// (default: config `interpolation`, else none)
+    #[arg(long, value_enum)]
+    interpolation: Option<Interpolation>,
+    /// Log receive rate, jitter, and end-to-end latency every SECS seconds
+    #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "1")]
+    stats: Option<u64>,
+    /// Also append each --stats report to this CSV file
+    #[arg(long, value_name = "FILE", requires = "stats")]
+    stats_csv: Option<PathBuf>,
+    /// Append every received joint to this CSV file, one row per joint per state
+    /// with its wall-clock receive time
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<PathBuf>,
+    /// Warn after this many milliseconds without a state or heartbeat, 0 never
+    /// (default: config `liveness_timeout_ms`, else 3000)
+    #[arg(long, value_name = "MS")]
+    liveness_timeout: Option<u64>,
+    /// What the pose does while the publisher is silent
+    #[arg(long, value_enum, default_value_t = OnSilence::Hold)]
+    on_silence: OnSilence,
+    /// What the pose does on an emergency stop (an `estop` on `<topic>/control`)
+    #[arg(long, value_enum, default_value_t = OnEStop::Freeze)]
+    on_estop: OnEStop,
+    /// Start the camera at a preset (front, palm, side) or a viewpoint file from --save-camera
+    #[arg(long, value_name = "PRESET|FILE")]
+    camera: Option<CameraArg>,
+    /// Write the camera's viewpoint to FILE when the viewer closes, for a later --camera
+    #[arg(long, value_name = "FILE", conflicts_with = "render_to")]
+    save_camera: Option<PathBuf>,
+    /// Render offscreen instead of opening the viewer: a video through ffmpeg for
+    /// PATH.mp4 (.mkv, .mov, .webm, .gif), else PNG frames in directory PATH. Frames start
+    /// with the first state and end when states stop for --liveness-timeout
+    #[arg(long, value_name = "PATH")]
+    render_to: Option<PathBuf>,
+    /// --render-to frame size, at most the model's offscreen buffer (default: that
+    /// buffer, `<visual><global offwidth offheight>`, 640x480 unless set)
+    #[arg(long, value_name = "WxH", requires = "render_to")]
+    render_size: Option<Size>,
+    /// --render-to frames per second
+    #[arg(long, value_name = "FPS", default_value_t = 30, requires = "render_to")]
+    render_fps: u32,
+    /// Draw these from the start, comma-separated; C, F, J, and T toggle them in the viewer
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "WHAT")]
+    show: Vec<Show>,
+    /// How received angles move the model
+    #[arg(long, value_enum, default_value_t = Mode::Pose)]
+    mode: Mode,
+    /// --mode pd stiffness, in N·m/rad, for joints the gains file does not set
+    #[arg(long, default_value_t = 10.0)]
+    kp: f64,
+    /// --mode pd damping, in N·m·s/rad, for joints the gains file does not set
+    #[arg(long, default_value_t = 0.5)]
+    kd: f64,
+    /// Per-joint --mode pd gains (TOML `[joints.<name>]` tables with kp and/or kd)
+    #[arg(long, value_name = "FILE")]
+    gains: Option<PathBuf>,
+    /// Rate-limit commanded positions with per-joint max velocity and acceleration
+    /// (TOML defaults and `[joints.<name>]` tables with max_velocity and/or max_acceleration)
+    #[arg(long, value_name = "FILE")]
+    safety: Option<PathBuf>,
+    /// Answer state queries on a ZMQ REP socket bound here, e.g. tcp://*:5556
+    #[arg(long, value_name = "ENDPOINT")]
+    query: Option<String>,
+    /// Serve Prometheus metrics on http://ADDR/metrics, e.g. 0.0.0.0:9465
+    #[arg(long, value_name = "ADDR")]
+    metrics: Option<String>,
+    /// Publish site or body poses after each new state, on TOPIC (default robot_poses)
+    #[arg(long, value_name = "TOPIC", num_args = 0..=1, default_missing_value = poses::TOPIC)]
+    publish_poses: Option<String>,
+    /// ZMQ bind address for --publish-poses (over MQTT, poses go to the same broker)
+    #[arg(long, value_name = "ENDPOINT", default_value = "tcp://*:5557")]
+    poses_bind: String,
+    /// Site or body whose pose to publish, repeatable (default: every named site,
+    /// else every named body)
+    #[arg(long = "pose-of", value_name = "NAME", requires = "publish_poses")]
+    pose_of: Vec<String>,
+    /// Publish the simulated joint angles of the latest state's robot every 20 ms, on
+    /// TOPIC (default robot_joints_actual)
+    #[arg(
+        long,
+        visible_alias = "publish-actual",
+        value_name = "TOPIC",
+        num_args = 0..=1,
+        default_missing_value = feedback::TOPIC
+    )]
+    publish_feedback: Option<String>,
+    /// ZMQ bind address for --publish-feedback (over MQTT, feedback goes to the same broker)
+    #[arg(long, value_name = "ENDPOINT", default_value = "tcp://*:5561")]
+    feedback_bind: String,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+/// A part of the model whose pose `--publish-poses` publishes.
+#[derive(Debug, Clone, Copy)]
+enum Tracked {
+    Site(usize),
+    Body(usize),
+}
+
+/// How received angles move the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Mode {
+    /// Set joint positions and velocities directly; no dynamics.
+    Pose,
+    /// Set each joint's actuator `ctrl` to the angle (for position actuators)
+    /// and step the simulation; joints without one stay where physics puts them.
+    Actuate,
+    /// Push each joint towards the angle with a PD controller's force and step
+    /// the simulation, reporting the tracking error.
+    Pd,
+}
+
+/// Something the viewer can draw on top of the model, to see e.g. a commanded pose
+/// pushing the fingers into each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Show {
+    /// Contact points (C)
+    Contacts,
+    /// Contact force arrows (F)
+    Forces,
+    /// Joint axes (J)
+    Joints,
+    /// Transparent geoms, to see contacts inside the hand (T)
+    Transparent,
+}
+
+impl Show {
+    fn flag(self) -> MjtVisFlag {
+        match self {
+            Show::Contacts => MjtVisFlag::mjVIS_CONTACTPOINT,
+            Show::Forces => MjtVisFlag::mjVIS_CONTACTFORCE,
+            Show::Joints => MjtVisFlag::mjVIS_JOINT,
+            Show::Transparent => MjtVisFlag::mjVIS_TRANSPARENT,
+        }
+    }
+}
+
+/// `--render-to`: the offscreen renderer and where its frames go.
+struct Offscreen<'m> {
+    renderer: MjRenderer<&'m MjModel>,
+    video: VideoOut,
+    every: Duration,
+    /// When the next frame is due; frames start with the first state.
+    next_frame: Option<Instant>,
+}
+
+/// The pose while no publisher is heard from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OnSilence {
+    /// Carry on as if nothing happened (the pose settles on the last state).
+    Hold,
+    /// Stop where it is, with every joint velocity zeroed.
+    Freeze,
+    /// Move every (hinge) joint to 0 rad at rest.
+    Zero,
+}
+
+/// The pose after an emergency stop, until a resume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OnEStop {
+    /// Stop where it is, with every joint velocity zeroed.
+    Freeze,
+    /// Move every (hinge) joint to 0 rad at rest.
+    Zero,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let source = match transport {
+        TransportKind::Zmq if cli.connect.is_empty() => config.connect,
+        TransportKind::Zmq => cli.connect.join(","),
+        TransportKind::Mqtt => cli.transport.broker(&config),
+        // A ring per topic, each named after it.
+        TransportKind::Shm => "shared memory".to_string(),
+        TransportKind::Zenoh if config.zenoh.is_empty() => "Zenoh scouting".to_string(),
+        TransportKind::Zenoh => config.zenoh.join(","),
+    };
+    let models = if cli.models.is_empty() {
+        vec![ModelArg {
+            robot_id: None,
+            topic: None,
+            path: config.model,
+        }]
+    } else {
+        cli.models
+    };
+    scene::validate(&models)?;
+    // Without --topic, hear every model's topic, and the configured one for
+    // models that follow a robot id.
+    let topics = if cli.topics.is_empty() {
+        let mut topics: Vec<String> = models.iter().filter_map(|m| m.topic.clone()).collect();
+        if models.iter().any(|model| model.topic.is_none()) {
+            topics.push(config.topic);
+        }
+        topics
+    } else {
+        cli.topics
+    };
+    let model_files = models
+        .iter()
+        .map(|model| model.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let interpolation = cli.interpolation.unwrap_or(config.interpolation);
+    let liveness_timeout = Some(cli.liveness_timeout.unwrap_or(config.liveness_timeout_ms))
+        .filter(|&ms| ms > 0)
+        .map(Duration::from_millis);
 
-    println!("👂 Starting Robot Joint Angles Subscriber with MuJoCo Visualization");
-    println!("🔌 Connecting to: {}", cli.connect);
-    println!("🎯 Topic filter: {}", cli.topic);
-    println!("📄 Model: {}", cli.model.display());
+    tracing::info!(
+        ?transport,
+        %source,
+        topics = %topics.join(", "),
+        models = %model_files,
+        ?interpolation,
+        "starting robot joint angles subscriber with MuJoCo visualization"
+    );
 
     // Load MuJoCo model and create data
-    println!("📦 Loading MuJoCo model...");
-    // Use `from_xml(path)` (file-based) so MuJoCo can resolve `<include file="..."/>`
-    // relative to the MJCF file location.
-    let model_path = std::fs::canonicalize(&cli.model).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to resolve model path '{}': {e}",
-            cli.model.display()
-        )
-    })?;
-    let model = MjModel::from_xml(&model_path)
-        .map_err(|e| anyhow::anyhow!("Failed to load MJCF '{}': {:?}", model_path.display(), e))?;
+    tracing::info!("loading MuJoCo model");
+    let model_paths = models
+        .iter()
+        .map(|model| {
+            std::fs::canonicalize(&model.path)
+                .with_context(|| format!("resolving model path '{}'", model.path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let model = match &models[..] {
+        // Use `from_xml(path)` (file-based) so MuJoCo can resolve `<include file="..."/>`
+        // relative to the MJCF file location.
+        [_] => MjModel::from_xml(&model_paths[0]).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load MJCF '{}': {:?}",
+                model_paths[0].display(),
+                e
+            )
+        })?,
+        // Each model is attached from its absolute path, so its includes still
+        // resolve next to it.
+        _ => {
+            let parts: Vec<_> = models
+                .iter()
+                .zip(&model_paths)
+                .map(|(model, path)| {
+                    (
+                        model.robot_id.as_deref().unwrap_or_default(),
+                        path.as_path(),
+                    )
+                })
+                .collect();
+            MjModel::from_xml_string(&scene::compose(&parts))
+                .map_err(|e| anyhow::anyhow!("Failed to compose MJCF of {model_files}: {:?}", e))?
+        }
+    };
     let mut data = MjData::new(&model);
 
     // Cache joint handles once (avoids repeated `mj_name2id` calls on every message).
@@ -70,100 +430,837 @@ fn main() -> Result<()> {
         }
     }
 
-    // Launch MuJoCo C++ viewer
-    println!("🎬 Launching MuJoCo C++ viewer...");
-    let mut viewer = MjViewerCpp::launch_passive(&model, &data, 100);
+    // The actuator driving each joint directly (joint transmission), by joint name.
+    let mut actuators: HashMap<String, usize> = HashMap::new();
+    for (id, (trn, target)) in model
+        .actuator_trntype()
+        .iter()
+        .zip(model.actuator_trnid())
+        .enumerate()
+    {
+        if *trn != MjtTrn::mjTRN_JOINT {
+            continue;
+        }
+        if let Some(name) = model.id_to_name(MjtObj::mjOBJ_JOINT, target[0]) {
+            actuators.insert(name.to_string(), id);
+        }
+    }
+    if cli.mode == Mode::Actuate {
+        if actuators.is_empty() {
+            anyhow::bail!("--mode actuate needs joint actuators, and {model_files} has none");
+        }
+        tracing::info!(
+            actuated = actuators.len(),
+            joints = joint_cache.len(),
+            "driving actuators; joints without one are left to the dynamics"
+        );
+    }
+
+    let default_gains = Gains {
+        kp: cli.kp,
+        kd: cli.kd,
+    };
+    let gains = match &cli.gains {
+        Some(path) => JointGains::load(path, default_gains)?,
+        None => JointGains::new(default_gains),
+    };
+    let mut safety = cli
+        .safety
+        .as_deref()
+        .map(SafetyLimits::load)
+        .transpose()?
+        .map(SafetyFilter::new);
+
+    let named = |kind: MjtObj, id: i32| {
+        model
+            .id_to_name(kind, id)
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+    };
+    let sites: Vec<_> = (0..model.ffi().nsite)
+        .filter_map(|id| {
+            named(MjtObj::mjOBJ_SITE, id).map(|name| (name, Tracked::Site(id as usize)))
+        })
+        .collect();
+    let bodies: Vec<_> = (1..model.ffi().nbody)
+        .filter_map(|id| {
+            named(MjtObj::mjOBJ_BODY, id).map(|name| (name, Tracked::Body(id as usize)))
+        })
+        .collect();
+    let tracked: Vec<(String, Tracked)> = if cli.pose_of.is_empty() {
+        if sites.is_empty() {
+            bodies
+        } else {
+            sites
+        }
+    } else {
+        cli.pose_of
+            .iter()
+            .map(|wanted| {
+                sites
+                    .iter()
+                    .chain(&bodies)
+                    .find(|(name, _)| name == wanted)
+                    .cloned()
+                    .with_context(|| format!("no site or body named '{wanted}' in {model_files}"))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut joint_names: Vec<String> = joint_cache.keys().cloned().collect();
+    joint_names.sort();
+    let model_info = ModelInfo {
+        path: model_files.clone(),
+        joints: njnt,
+        nq: model.ffi().nq.max(0) as usize,
+        nv: model.ffi().nv.max(0) as usize,
+        timestep: model.opt().timestep,
+    };
+
+    let viewpoint = match &cli.camera {
+        Some(camera) => {
+            let stat = model.stat();
+            let viewpoint = camera.viewpoint(stat.center, stat.extent)?;
+            tracing::info!(?viewpoint, "camera");
+            Some(viewpoint)
+        }
+        None => None,
+    };
+    if !cli.show.is_empty() {
+        tracing::info!(show = ?cli.show, "drawing");
+    }
+
+    // Launch MuJoCo C++ viewer, or with --render-to an offscreen renderer in its place
+    let mut viewer = None;
+    let mut offscreen = None;
+    match &cli.render_to {
+        None => {
+            tracing::info!("launching MuJoCo C++ viewer");
+            let mut launched = MjViewerCpp::launch_passive(&model, &data, 100);
+            let (opt, cam) = viewer_view(&mut launched);
+            set_view(opt, cam, &cli.show, viewpoint.as_ref());
+            viewer = Some(launched);
+        }
+        Some(path) => {
+            if liveness_timeout.is_none() {
+                anyhow::bail!("--render-to ends with the stream, so it needs a --liveness-timeout");
+            }
+            let global = &model.vis().global;
+            let buffer = (
+                global.offwidth.max(0) as u32,
+                global.offheight.max(0) as u32,
+            );
+            let size = cli.render_size.unwrap_or(Size {
+                width: buffer.0,
+                height: buffer.1,
+            });
+            if size.width > buffer.0 || size.height > buffer.1 {
+                anyhow::bail!(
+                    "--render-size {}x{} is larger than the offscreen buffer of {model_files} \
+                     ({}x{}); raise it with <visual><global offwidth=\"...\" offheight=\"...\"/>",
+                    size.width,
+                    size.height,
+                    buffer.0,
+                    buffer.1
+                );
+            }
+            let mut renderer = MjRenderer::builder()
+                .width(size.width)
+                .height(size.height)
+                .build(&model)
+                .map_err(|e| anyhow::anyhow!("starting the offscreen renderer: {e}"))?;
+            let mut opt = renderer.opts().clone();
+            // The viewer's starting camera, framing the whole model.
+            let mut cam = MjvCamera::new_free(&model);
+            set_view(&mut opt, &mut cam, &cli.show, viewpoint.as_ref());
+            renderer.set_opts(opt);
+            renderer.set_camera(cam);
+            let fps = cli.render_fps.max(1);
+            tracing::info!(path = %path.display(), ?size, fps, "rendering offscreen");
+            offscreen = Some(Offscreen {
+                renderer,
+                video: VideoOut::create(path, size, fps)?,
+                every: Duration::from_secs(1).div_f64(fps as f64),
+                next_frame: None,
+            });
+        }
+    }
 
     // Get timestep from model
     let timestep = model.opt().timestep;
 
-    // Connect to ZMQ publisher
-    println!("📡 Connecting to ZMQ publisher...");
-    let ctx = Context::new();
-    let socket = ctx.socket(zmq::SUB)?;
-    socket.connect(&cli.connect)?;
-    socket.set_subscribe(cli.topic.as_bytes())?;
-
-    // Set socket to non-blocking so we can check for messages without blocking the viewer
-    socket.set_rcvtimeo(10)?; // 10ms timeout
-
-    println!("✅ Ready! Waiting for joint data and visualizing...\n");
-
-    let mut last_received_timestamp = 0u64;
-
-    // Main loop: check for ZMQ messages and update simulation
-    while viewer.running() {
-        // Try to receive a message (non-blocking)
-        match socket.recv_string(zmq::DONTWAIT) {
-            Ok(Ok(msg)) => {
-                // Split topic and JSON data
-                if let Some((topic, json_data)) = msg.split_once(' ') {
-                    if topic == cli.topic {
-                        match serde_json::from_str::<RobotState>(json_data) {
-                            Ok(robot_state) => {
-                                // Update only if we have new data
-                                if robot_state.timestamp > last_received_timestamp {
-                                    last_received_timestamp = robot_state.timestamp;
-
-                                    // Apply joint updates by name.
-                                    //
-                                    // Notes:
-                                    // - Many hand joints are hinge joints (1 DoF): `qpos[0]` is the angle, `qvel[0]` is angular velocity.
-                                    // - For more complex joints (e.g., `free` or `ball`), this simplistic mapping won't be sufficient.
-                                    //   We intentionally "best-effort" update only the first DoF if present.
-                                    let mut _updated = 0usize;
-                                    for joint in &robot_state.joints {
-                                        let Some(joint_info) = joint_cache.get(&joint.joint_name)
-                                        else {
-                                            continue;
-                                        };
-
-                                        let mut view = joint_info.view_mut(&mut data);
-                                        if let Some(qpos0) = view.qpos.get_mut(0) {
-                                            *qpos0 = joint.angle_rad;
-                                        }
-                                        if let Some(qvel0) = view.qvel.get_mut(0) {
-                                            *qvel0 = joint.velocity;
-                                        }
-                                        _updated += 1;
-                                    }
-
-                                    // Intentionally no per-message logging here:
-                                    // printing at high frequency significantly slows down the render loop,
-                                    // and this subscriber is intended for real-time visualization.
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("❌ Failed to parse JSON: {}", e);
-                            }
+    let poses_publisher = match cli.publish_poses.as_deref() {
+        Some(poses_topic) => {
+            tracing::info!(topic = %poses_topic, tracked = tracked.len(), "publishing poses");
+            // Over ZMQ a socket of its own; over MQTT or Zenoh a second client of the same network.
+            let poses_transport: Box<dyn Transport> = match transport {
+                TransportKind::Zmq => {
+                    Box::new(ZmqTransport::bind(&Context::new(), &cli.poses_bind)?)
+                }
+                TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+                TransportKind::Shm => Box::new(ShmTransport::create(poses_topic)?),
+                TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+            };
+            let format = cli.decode.format.unwrap_or(config.format);
+            Some(Publisher::new(poses_transport, poses_topic).with_format(format))
+        }
+        None => None,
+    };
+
+    // Heard from the publisher's heartbeats, echoed in the feedback publisher's.
+    let clock = Arc::new(ClockSync::default());
+    let feedback_publisher = match cli.publish_feedback.as_deref() {
+        Some(feedback_topic) => {
+            tracing::info!(topic = %feedback_topic, "publishing feedback");
+            let feedback_transport: Box<dyn Transport> = match transport {
+                TransportKind::Zmq => {
+                    Box::new(ZmqTransport::bind(&Context::new(), &cli.feedback_bind)?)
+                }
+                TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+                TransportKind::Shm => Box::new(ShmTransport::create(feedback_topic)?),
+                TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+            };
+            let format = cli.decode.format.unwrap_or(config.format);
+            Some(
+                Publisher::new(feedback_transport, feedback_topic)
+                    .with_format(format)
+                    .with_clock_sync(Arc::clone(&clock)),
+            )
+        }
+        None => None,
+    };
+    let mut next_feedback = Instant::now();
+    let mut next_feedback_heartbeat = Instant::now();
+
+    // Connect to the ZMQ publisher, the MQTT broker, or the Zenoh network
+    tracing::info!(?transport, %source, "connecting");
+    // Polled with `try_recv` so checking for messages never blocks the viewer.
+    // Over ZMQ, publishers connecting and going are logged, so a dead endpoint
+    // does not just look like silence.
+    let mut connections = None;
+    let transport: Box<dyn Transport> = match transport {
+        TransportKind::Zmq => {
+            let mut endpoints = source.split(',');
+            let first = endpoints.next().unwrap_or_default();
+            let mut zmq = match cli.receive_hwm {
+                Some(hwm) => ZmqTransport::connect_with_hwm(&Context::new(), first, hwm)?,
+                None => ZmqTransport::connect(&Context::new(), first)?,
+            };
+            for endpoint in endpoints {
+                zmq.connect_also(endpoint)?;
+            }
+            if cli.decode.legacy_frames {
+                zmq = zmq.accept_legacy_frames();
+            }
+            connections = Some(zmq.monitor()?);
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+        TransportKind::Shm => {
+            let mut rings = topics.iter();
+            let mut shm = ShmTransport::open(rings.next().map_or("", String::as_str))?;
+            for topic in rings {
+                shm.open_also(topic)?;
+            }
+            Box::new(shm)
+        }
+        TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+    };
+    let mut subscriber = Subscriber::for_topics(transport, topics.iter().map(String::as_str))?
+        .with_batch_latest(cli.batch_latest)
+        .with_keep_latest(cli.keep_latest)
+        .with_clock_sync(Arc::clone(&clock));
+    if let Some(format) = cli.decode.format {
+        subscriber = subscriber.with_format(format);
+    }
+    if let Some(mut mirror) = cli.mirror {
+        if !cli.mirror_flip.is_empty() {
+            mirror = mirror.with_flip(cli.mirror_flip);
+        }
+        subscriber = subscriber.with_mirror(mirror);
+    }
+    let joint_filter = JointFilter::new(cli.joints, cli.joint_prefix);
+    if !joint_filter.is_all() {
+        tracing::info!(?joint_filter, "applying only the selected joints");
+    }
+
+    let query = cli
+        .query
+        .as_deref()
+        .map(|endpoint| {
+            tracing::info!(%endpoint, "answering state queries");
+            QueryServer::bind(&Context::new(), endpoint)
+        })
+        .transpose()?;
+    let metrics = cli.metrics.as_deref().map(start_metrics).transpose()?;
+
+    tracing::info!("ready; waiting for joint data");
+
+    let mut router = Router::new(&models, interpolation);
+    let mut unrouted = HashSet::new();
+    // Reused for `<prefix><joint name>` lookups, to keep the loop allocation-free.
+    let mut scoped_name = String::new();
+    // The prefix of the robot the latest state is for; its poses are published once.
+    let mut latest_prefix = String::new();
+    let mut poses_due = false;
+    let mut latest_state = None;
+    let started = Instant::now();
+    let mut silent = false;
+    let mut status = Status::default();
+    let stats_every = cli.stats.map(|secs| Duration::from_secs(secs.max(1)));
+    let mut stats = Stats::new(Instant::now());
+    let mut next_report = stats_every.map(|every| Instant::now() + every);
+    // --mode pd: each joint's target and gains, by scoped name, and how well they are met.
+    let mut pd_targets: HashMap<String, (f64, Gains)> = HashMap::new();
+    let mut tracking = Tracking::new();
+    let tracking_every = stats_every.unwrap_or(Duration::from_secs(1));
+    let mut next_tracking_report = Instant::now() + tracking_every;
+    let mut last_frame = Instant::now();
+    // Wall time and simulated time when the loop started; `--mode actuate` and `pd`
+    // step until the simulation has run as long as the wall clock.
+    let sim_started = Instant::now();
+    let mut sim_start_time = data.time();
+    let mut stats_csv = cli
+        .stats_csv
+        .as_ref()
+        .map(|path| {
+            csv::Writer::from_path(path).with_context(|| format!("creating {}", path.display()))
+        })
+        .transpose()?;
+    let mut joint_log = cli
+        .log_file
+        .as_deref()
+        .map(|path| {
+            tracing::info!(log_file = %path.display(), "logging received joints");
+            JointLog::append(path)
+        })
+        .transpose()?;
+
+    // Main loop: check for messages and update simulation
+    while viewer.as_ref().is_none_or(MjViewerCpp::running) {
+        let frame_start = Instant::now();
+        let received = subscriber.try_recv();
+        let topic = subscriber.last_topic().unwrap_or_default();
+        if let (Some(_), Ok(Some(robot_state))) = (stats_every, &received) {
+            // Latency by the publisher's clock, as `sent_at_us` is.
+            let wall_clock = clock.to_peer(SystemTime::now());
+            stats.record(&topic, robot_state, Instant::now(), wall_clock);
+        }
+        if let Some(metrics) = &metrics {
+            match &received {
+                Ok(Some(robot_state)) => metrics.received(robot_state, Instant::now()),
+                Err(e) if e.exit() == Exit::Data => metrics.parse_error(),
+                _ => {}
+            }
+        }
+        // The stream is measured whole; only the selected joints go further, and a
+        // state with none of them is as good as no state.
+        let received = received.map(|state| state.and_then(|state| joint_filter.apply(state)));
+        if let (Some(log), Ok(Some(robot_state))) = (&mut joint_log, &received) {
+            log.record(robot_state, SystemTime::now())?;
+        }
+        match received {
+            // Update only if we have new data for a robot in the scene
+            Ok(Some(robot_state)) => match router.accept(&robot_state, &topic, Instant::now()) {
+                Route::Fresh { prefix } => {
+                    // Apply joint velocities by name; one-coordinate positions are set below,
+                    // every frame. When actuating, the dynamics own both and nothing is set.
+                    //
+                    // Notes:
+                    // - Many hand joints are hinge joints (1 DoF): `qpos[0]` is the angle, `qvel[0]` is angular velocity.
+                    // - `ball` and `free` joints get every `qpos` coordinate the state carries
+                    //   (from older publishers only the angle, into `qpos[0]`), here and not
+                    //   interpolated; their velocity still only goes into `qvel[0]`.
+                    let posing = cli.mode == Mode::Pose && status == Status::Running;
+                    for joint in robot_state.joints.iter().filter(|_| posing) {
+                        scoped_name.clear();
+                        scoped_name.push_str(prefix);
+                        scoped_name.push_str(&joint.joint_name);
+                        let Some(joint_info) = joint_cache.get(&scoped_name) else {
+                            continue;
+                        };
+
+                        let mut view = joint_info.view_mut(&mut data);
+                        if let Some(qvel0) = view.qvel.get_mut(0) {
+                            *qvel0 = joint.velocity;
+                        }
+                        if view.qpos.len() > 1 {
+                            view.qpos
+                                .iter_mut()
+                                .zip(joint.positions())
+                                .for_each(|(qpos, &position)| *qpos = position);
                         }
                     }
+
+                    latest_prefix.clear();
+                    latest_prefix.push_str(prefix);
+                    poses_due = poses_publisher.is_some();
+                    latest_state = Some(robot_state);
+
+                    // Intentionally no per-message logging here:
+                    // printing at high frequency significantly slows down the render loop,
+                    // and this subscriber is intended for real-time visualization.
                 }
+                Route::Stale => {}
+                Route::Unrouted => {
+                    if unrouted.insert((robot_state.robot_id.clone(), topic.clone())) {
+                        tracing::warn!(
+                            robot_id = %robot_state.robot_id,
+                            %topic,
+                            "no --model for this robot or topic; ignoring its states"
+                        );
+                    }
+                }
+            },
+            // No message available: continue with simulation
+            Ok(None) => {}
+            Err(e) if e.exit() == Exit::Network => {
+                tracing::error!(error = %errors::report(&e), "receive error");
             }
-            Ok(Err(_)) => {
-                // No message available, continue with simulation
+            Err(e) => {
+                tracing::warn!(error = %errors::report(&e), "skipping message");
             }
-            Err(zmq::Error::EAGAIN) => {
-                // Timeout - no message available, continue
+        }
+
+        if let Some(monitor) = &mut connections {
+            match monitor.poll() {
+                Ok(events) => log_connections(&events, monitor.connections()),
+                Err(e) => tracing::warn!(error = %errors::report(&e), "ZMQ monitor failed"),
             }
-            Err(e) => {
-                eprintln!("❌ ZMQ receive error: {}", e);
+        }
+
+        for command in subscriber.take_controls() {
+            let next = status.after(command);
+            if next == status {
+                continue;
+            }
+            tracing::warn!(?command, ?status, ?next, "control command");
+            if next == Status::Stopped {
+                stop(
+                    cli.mode,
+                    cli.on_estop == OnEStop::Zero,
+                    &model,
+                    &mut data,
+                    &joint_cache,
+                    &actuators,
+                    &mut pd_targets,
+                );
+            }
+            // Limiting resumes from wherever the pose ended up meanwhile.
+            if let (Some(filter), Status::Running) = (&mut safety, next) {
+                filter.reset();
+            }
+            status = next;
+        }
+
+        if let Some(due) = next_report.filter(|due| Instant::now() >= *due) {
+            let offset_ms = clock.offset().map(|offset| offset.offset_ns as f64 / 1e6);
+            let publishers = connections.as_ref().map(ZmqMonitor::connections);
+            for report in stats.report(Instant::now(), SystemTime::now()) {
+                tracing::info!(
+                    publishers_connected = publishers,
+                    clock_offset_ms = offset_ms,
+                    topic = %report.topic,
+                    messages = report.messages,
+                    rate_hz = report.rate_hz,
+                    interval_ms = report.interval_ms,
+                    jitter_ms = report.jitter_ms,
+                    latency_ms = report.latency_ms,
+                    latency_max_ms = report.latency_max_ms,
+                    "stats"
+                );
+                if let Some(writer) = &mut stats_csv {
+                    writer.serialize(&report).context("writing stats CSV")?;
+                }
+            }
+            if let Some(writer) = &mut stats_csv {
+                writer.flush().context("writing stats CSV")?;
             }
+            next_report = stats_every.map(|every| due + every);
+        }
+
+        if let Some(query) = &query {
+            let snapshot = Snapshot {
+                state: latest_state.as_ref(),
+                joint_names: &joint_names,
+                model_info: &model_info,
+            };
+            if let Err(e) = query.serve_pending(&snapshot) {
+                tracing::warn!(error = %errors::report(&e), "answering a query failed");
+            }
+        }
+
+        let last_heard = subscriber.last_heard();
+        let silent_for = last_heard.unwrap_or(started).elapsed();
+        if liveness_timeout.is_some_and(|timeout| silent_for > timeout) != silent {
+            silent = !silent;
+            if silent {
+                // Whatever happens to the pose now, limiting resumes from where it ends up.
+                if let (Some(filter), OnSilence::Freeze | OnSilence::Zero) =
+                    (&mut safety, cli.on_silence)
+                {
+                    filter.reset();
+                }
+                tracing::warn!(
+                    silent_for_s = silent_for.as_secs_f64(),
+                    heard_before = last_heard.is_some(),
+                    publishers_connected = connections.as_ref().map(ZmqMonitor::connections),
+                    on_silence = ?cli.on_silence,
+                    "publisher went silent"
+                );
+                if cli.on_silence != OnSilence::Hold {
+                    stop(
+                        cli.mode,
+                        cli.on_silence == OnSilence::Zero,
+                        &model,
+                        &mut data,
+                        &joint_cache,
+                        &actuators,
+                        &mut pd_targets,
+                    );
+                }
+            } else {
+                tracing::info!("publisher is back");
+            }
+        }
+        if offscreen.is_some() && silent && last_heard.is_some() {
+            tracing::info!("stream ended; finishing the video");
+            break;
+        }
+
+        // One-coordinate joint positions for this frame: each robot's latest state's, or
+        // between its last two (left alone while a silent publisher's pose is frozen or
+        // zeroed, and while a control command holds or stops it).
+        let now = Instant::now();
+        let frame_s = (now - last_frame).as_secs_f64();
+        last_frame = now;
+        let poses = match (silent, cli.on_silence, status) {
+            (true, OnSilence::Freeze | OnSilence::Zero, _) => None,
+            (_, _, Status::Held | Status::Stopped) => None,
+            _ => Some(router.angles_at(now)),
+        };
+        for (prefix, name, angle_rad) in poses.into_iter().flatten() {
+            scoped_name.clear();
+            scoped_name.push_str(prefix);
+            scoped_name.push_str(name);
+            // With --safety, the command only ramps towards the angle, starting from
+            // the joint's position; limits are looked up by its name in its own model.
+            let angle_rad = match &mut safety {
+                Some(filter) => {
+                    let current = joint_cache
+                        .get(&scoped_name)
+                        .and_then(|joint_info| joint_info.view(&data).qpos.first().copied())
+                        .unwrap_or(angle_rad);
+                    filter.apply(&scoped_name, name, angle_rad, current, frame_s)
+                }
+                None => angle_rad,
+            };
+            if cli.mode == Mode::Actuate {
+                if let Some(&actuator) = actuators.get(&scoped_name) {
+                    data.ctrl_mut()[actuator] = angle_rad;
+                }
+                continue;
+            }
+            if cli.mode == Mode::Pd {
+                if !joint_cache.contains_key(&scoped_name) {
+                    continue;
+                }
+                // Gains are looked up by the joint's name in its own model.
+                match pd_targets.get_mut(&scoped_name) {
+                    Some((target, _)) => *target = angle_rad,
+                    None => {
+                        pd_targets.insert(scoped_name.clone(), (angle_rad, gains.get(name)));
+                    }
+                }
+                continue;
+            }
+            let Some(joint_info) = joint_cache.get(&scoped_name) else {
+                continue;
+            };
+            let mut view = joint_info.view_mut(&mut data);
+            // Ball and free joints got all their coordinates when the state arrived.
+            if let [qpos0] = &mut view.qpos[..] {
+                *qpos0 = angle_rad;
+            }
+        }
+
+        // PD forces for the first step of this frame.
+        apply_pd(&pd_targets, &joint_cache, &mut data, |name, error| {
+            tracking.record(name, error)
+        });
+        if Instant::now() >= next_tracking_report {
+            if let Some(report) = tracking.report() {
+                tracing::info!(
+                    rms_rad = report.rms_rad,
+                    max_rad = report.max_rad,
+                    worst_joint = %report.worst_joint,
+                    samples = report.samples,
+                    "tracking error"
+                );
+            }
+            next_tracking_report += tracking_every;
         }
 
         // Sync and render C++ viewer (sync doesn't take parameters, render needs explicit call)
-        // Order: sync -> render -> forward -> sleep
-        viewer.sync();
-        viewer.render(true); // render on screen and update the fps timer
+        // Order: sync -> render -> forward (or step) -> sleep
+        if let Some(viewer) = &mut viewer {
+            viewer.sync();
+            viewer.render(true); // render on screen and update the fps timer
+        }
+        // Offscreen, one frame per --render-fps tick of wall time from the first state,
+        // repeated when rendering falls behind so the video keeps the stream's timing.
+        if let (Some(offscreen), Some(_)) = (&mut offscreen, &latest_state) {
+            let now = Instant::now();
+            let due = *offscreen.next_frame.get_or_insert(now);
+            if now >= due {
+                offscreen.renderer.sync(&mut data);
+                let rgb = offscreen.renderer.rgb_flat().unwrap_or_default();
+                let mut next_frame = due;
+                while next_frame <= now {
+                    offscreen.video.write_bottom_up(rgb)?;
+                    next_frame += offscreen.every;
+                }
+                offscreen.next_frame = Some(next_frame);
+            }
+        }
 
         // For pose visualization driven by external joint angles, we do *not* integrate time.
         // `forward()` updates all derived quantities (kinematics/dynamics) from the current state.
-        data.forward();
+        // Actuating, `step()` integrates with the controls just written, as many
+        // timesteps as simulated time is behind wall time (PD forces renewed for each).
+        match cli.mode {
+            Mode::Pose => data.forward(),
+            Mode::Actuate | Mode::Pd => {
+                let wall_s = sim_started.elapsed().as_secs_f64();
+                for substep in 0..MAX_SUBSTEPS {
+                    if data.time() - sim_start_time >= wall_s {
+                        break;
+                    }
+                    if substep > 0 {
+                        apply_pd(&pd_targets, &joint_cache, &mut data, |_, _| {});
+                    }
+                    data.step();
+                }
+                // Still behind after MAX_SUBSTEPS: drop the debt, so a stall is not
+                // followed by the simulation racing to make it up.
+                if data.time() - sim_start_time < wall_s {
+                    sim_start_time = data.time() - wall_s;
+                }
+            }
+        }
+
+        // Poses of the frame just computed: the new state's, or with --interpolation
+        // the in-between pose drawn when it arrived.
+        if let (Some(publisher), Some(state), true) = (&poses_publisher, &latest_state, poses_due) {
+            poses_due = false;
+            // Only the parts of the robot the state was for, named as in its own model.
+            let bodies = tracked.iter().filter_map(|(name, part)| {
+                let name = name.strip_prefix(latest_prefix.as_str())?;
+                Some(match *part {
+                    Tracked::Site(id) => (
+                        name,
+                        data.site_xpos()[id],
+                        poses::quat_from_mat(&data.site_xmat()[id]),
+                    ),
+                    Tracked::Body(id) => (name, data.xpos()[id], data.xquat()[id]),
+                })
+            });
+            if let Err(e) = publisher.publish_poses(&poses::from_state(state, bodies)) {
+                tracing::warn!(error = %errors::report(&e), "publishing poses failed");
+            }
+        }
+
+        if let Some(publisher) = &feedback_publisher {
+            if Instant::now() >= next_feedback_heartbeat {
+                next_feedback_heartbeat = Instant::now() + feedback::HEARTBEAT_INTERVAL;
+                if let Err(e) = publisher.heartbeat() {
+                    tracing::warn!(error = %errors::report(&e), "feedback heartbeat failed");
+                }
+            }
+        }
+
+        // Where the latest state's robot actually got to, named as the state names its joints.
+        if let (Some(publisher), Some(state)) = (&feedback_publisher, &latest_state) {
+            if Instant::now() >= next_feedback {
+                next_feedback = Instant::now() + feedback::INTERVAL;
+                let views: Vec<_> = state
+                    .joints
+                    .iter()
+                    .filter_map(|joint| {
+                        let joint_info =
+                            joint_cache.get(&format!("{latest_prefix}{}", joint.joint_name))?;
+                        Some((joint.joint_name.as_str(), joint_info.view(&data)))
+                    })
+                    .collect();
+                let measured = views
+                    .iter()
+                    .map(|(name, view)| (*name, &view.qpos[..], &view.qvel[..]));
+                if let Err(e) = publisher.publish(&feedback::from_state(state, measured)) {
+                    tracing::warn!(error = %errors::report(&e), "publishing feedback failed");
+                }
+            }
+        }
 
-        // Sleep to match simulation timestep
-        std::thread::sleep(Duration::from_secs_f64(timestep));
+        // One frame per timestep: sleep for what the frame's work left of it.
+        let frame_end = frame_start + Duration::from_secs_f64(timestep);
+        std::thread::sleep(frame_end.saturating_duration_since(Instant::now()));
     }
 
-    println!("👋 Viewer closed. Exiting...");
+    if let Some(mut log) = joint_log {
+        log.flush()?;
+    }
+    if let Some(offscreen) = offscreen {
+        let frames = offscreen.video.finish()?;
+        let path = cli.render_to.unwrap_or_default();
+        tracing::info!(path = %path.display(), frames, "video written");
+        return Ok(());
+    }
+    if let (Some(path), Some(viewer)) = (&cli.save_camera, viewer.as_mut()) {
+        let (_, cam) = viewer_view(viewer);
+        let viewpoint = Viewpoint {
+            lookat: cam.lookat,
+            distance: cam.distance,
+            azimuth: cam.azimuth,
+            elevation: cam.elevation,
+        };
+        viewpoint.save(path)?;
+        tracing::info!(path = %path.display(), "camera viewpoint saved");
+    }
+    tracing::info!("viewer closed; exiting");
     Ok(())
 }
+
+/// The visualization options and free camera `viewer` draws with; mujoco-rs
+/// offers no accessors for them, only the `Simulate` pointer.
+fn viewer_view<'v>(
+    viewer: &'v mut MjViewerCpp<&MjModel>,
+) -> (&'v mut MjvOption, &'v mut MjvCamera) {
+    // SAFETY: `opt` and `cam` point at boxes `viewer` owns and frees only when it
+    // drops, and the returned borrows hold `viewer` mutably until they end; the
+    // viewer reads them only in `sync` and `render`, on this thread, which need
+    // `viewer` too.
+    unsafe {
+        let raw = viewer.__raw();
+        (&mut *(*raw).opt, &mut *(*raw).cam)
+    }
+}
+
+/// Draw `show` from the start, and put the free camera at `viewpoint` if given.
+fn set_view(
+    opt: &mut MjvOption,
+    cam: &mut MjvCamera,
+    show: &[Show],
+    viewpoint: Option<&Viewpoint>,
+) {
+    for show in show {
+        opt.flags[show.flag() as usize] = 1;
+    }
+    if let Some(viewpoint) = viewpoint {
+        cam.type_ = MjtCamera::mjCAMERA_FREE as i32;
+        cam.lookat = viewpoint.lookat;
+        cam.distance = viewpoint.distance;
+        cam.azimuth = viewpoint.azimuth;
+        cam.elevation = viewpoint.elevation;
+    }
+}
+
+/// Log publishers connecting, going, and not (yet) answering; `publishers` is
+/// how many are connected after them.
+fn log_connections(events: &[ConnectionEvent], publishers: usize) {
+    for event in events {
+        match event {
+            ConnectionEvent::Up(endpoint) => {
+                tracing::info!(%endpoint, publishers, "connected to publisher")
+            }
+            ConnectionEvent::Down(endpoint) => {
+                tracing::warn!(%endpoint, publishers, "publisher disconnected; reconnecting")
+            }
+            ConnectionEvent::Retrying(endpoint) => {
+                tracing::debug!(%endpoint, "publisher unreachable; retrying")
+            }
+        }
+    }
+}
+
+fn start_metrics(addr: &str) -> Result<Metrics> {
+    let (metrics, local) = Metrics::spawn(addr).with_context(|| format!("listening on {addr}"))?;
+    tracing::info!(metrics = %format!("http://{local}/metrics"), "serving Prometheus metrics");
+    Ok(metrics)
+}
+
+/// Write each PD target's force for the next step into `qfrc_applied`, on the
+/// joint's first DoF as everywhere else, passing `error` each joint's tracking error.
+fn apply_pd(
+    pd_targets: &HashMap<String, (f64, Gains)>,
+    joint_cache: &HashMap<String, MjJointDataInfo>,
+    data: &mut MjData<&MjModel>,
+    mut error: impl FnMut(&str, f64),
+) {
+    for (name, (target, joint_gains)) in pd_targets {
+        let Some(joint_info) = joint_cache.get(name) else {
+            continue;
+        };
+        let mut view = joint_info.view_mut(data);
+        let (Some(&position), Some(&velocity)) = (view.qpos.first(), view.qvel.first()) else {
+            continue;
+        };
+        if let Some(force) = view.qfrc_applied.get_mut(0) {
+            *force = joint_gains.force(*target, position, velocity);
+        }
+        error(name, target - position);
+    }
+}
+
+/// Stop every joint where it is now, or with `zero` send it to 0 rad at rest, the
+/// way `mode` moves joints: PD targets, actuator controls, or the positions themselves.
+/// Posed ball and free joints have no single angle, so `zero` puts them back at the
+/// model's default pose (`qpos0`) instead.
+fn stop(
+    mode: Mode,
+    zero: bool,
+    model: &MjModel,
+    data: &mut MjData<&MjModel>,
+    joint_cache: &HashMap<String, MjJointDataInfo>,
+    actuators: &HashMap<String, usize>,
+    pd_targets: &mut HashMap<String, (f64, Gains)>,
+) {
+    let angle_now = |data: &MjData<&MjModel>, name: &str| match (zero, joint_cache.get(name)) {
+        (false, Some(joint_info)) => joint_info.view(data).qpos.first().copied().unwrap_or(0.0),
+        _ => 0.0,
+    };
+    match mode {
+        // Hold the joints where they are now, or pull them to 0.
+        Mode::Pd => {
+            for (name, (target, _)) in pd_targets.iter_mut() {
+                *target = angle_now(data, name);
+            }
+        }
+        // Stop the actuators where their joints are now, or send them to 0.
+        Mode::Actuate => {
+            for (name, &actuator) in actuators {
+                let angle = angle_now(data, name);
+                data.ctrl_mut()[actuator] = angle;
+            }
+        }
+        Mode::Pose => {
+            let (jnt_type, qposadr) = (model.jnt_type(), model.jnt_qposadr());
+            for joint_info in joint_cache.values() {
+                let mut view = joint_info.view_mut(data);
+                view.qvel.fill(0.0);
+                if !zero {
+                    continue;
+                }
+                match jnt_type[joint_info.id] {
+                    MjtJoint::mjJNT_HINGE | MjtJoint::mjJNT_SLIDE => view.qpos[0] = 0.0,
+                    // A zeroed quaternion is no rotation at all; the default pose is.
+                    MjtJoint::mjJNT_BALL | MjtJoint::mjJNT_FREE => {
+                        let start = qposadr[joint_info.id] as usize;
+                        let len = view.qpos.len();
+                        view.qpos
+                            .copy_from_slice(&model.qpos0()[start..start + len]);
+                    }
+                }
+            }
+        }
+    }
+}
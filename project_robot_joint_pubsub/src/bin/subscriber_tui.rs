@@ -0,0 +1,303 @@
+//! Terminal dashboard subscriber: receives robot joint angles via ZMQ or MQTT and
+//! shows them live in the terminal (ratatui), for machines reached over SSH where
+//! the MuJoCo viewer can't run.
+//!
+//! ## What this shows
+//! - One row per joint of the latest state: angle and velocity, each with a bar
+//!   centered on zero that fills to `--angle-range` / `--velocity-range`.
+//! - Receive rate, jitter, and latency over the last second (see `stats` in the
+//!   library), the messages received, and how long ago the last one arrived,
+//!   in red once that passes `--stale-ms`.
+//! - The last receive or decode error, if any.
+//!
+//! `q`, Esc, or Ctrl-C quits. No MuJoCo needed: build with `--features tui`.
+
+use anyhow::Result;
+use clap::Parser;
+use project_robot_joint_pubsub::args::{ConfigArgs, ConnectArgs, DecodeArgs, TransportArgs};
+use project_robot_joint_pubsub::stats::{Report, Stats};
+use project_robot_joint_pubsub::transport::{open_zenoh, ring_path};
+use project_robot_joint_pubsub::{
+    MqttTransport, RobotState, ShmTransport, Subscriber, Transport, TransportKind, ZmqTransport,
+};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime};
+use zmq::Context;
+
+/// How often the receive rate and friends are recomputed.
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Width of the angle and velocity bars, in characters.
+const BAR_WIDTH: usize = 21;
+
+#[derive(Parser)]
+#[command(name = "subscriber_tui")]
+#[command(about = "Show received robot joint angles live in the terminal")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    connect: ConnectArgs,
+    /// Topic to show (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    decode: DecodeArgs,
+    /// Redraw interval in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    refresh: u64,
+    /// Angle in radians, either way, that fills an angle bar
+    #[arg(long, value_name = "RAD", default_value_t = std::f64::consts::PI)]
+    angle_range: f64,
+    /// Velocity in rad/s, either way, that fills a velocity bar
+    #[arg(long, value_name = "RAD_PER_S", default_value_t = 5.0)]
+    velocity_range: f64,
+    /// Milliseconds without a state before the last-received age turns red
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    stale_ms: u64,
+}
+
+/// What the dashboard shows, updated as states arrive.
+struct Dashboard {
+    topic: String,
+    source: String,
+    latest: Option<RobotState>,
+    last_heard: Option<Instant>,
+    received: u64,
+    stats: Stats,
+    report: Option<Report>,
+    next_report: Instant,
+    last_error: Option<String>,
+}
+
+impl Dashboard {
+    fn new(topic: String, source: String) -> Self {
+        let now = Instant::now();
+        Dashboard {
+            topic,
+            source,
+            latest: None,
+            last_heard: None,
+            received: 0,
+            stats: Stats::new(now),
+            report: None,
+            next_report: now + STATS_WINDOW,
+            last_error: None,
+        }
+    }
+
+    /// Take every state that has arrived since the last call.
+    fn receive(&mut self, subscriber: &Subscriber) {
+        loop {
+            match subscriber.try_recv() {
+                Ok(Some(state)) => {
+                    let now = Instant::now();
+                    self.stats
+                        .record(&self.topic, &state, now, SystemTime::now());
+                    self.latest = Some(state);
+                    self.last_heard = Some(now);
+                    self.received += 1;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    self.last_error = Some(errors::report(&e));
+                    break;
+                }
+            }
+        }
+        let now = Instant::now();
+        if now >= self.next_report {
+            self.report = self.stats.report(now, SystemTime::now()).pop();
+            self.next_report = now + STATS_WINDOW;
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    // No logging: the dashboard owns the terminal, and errors show in it.
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.clone().unwrap_or_else(|| config.topic.clone());
+    let source = match transport {
+        TransportKind::Zmq => cli.connect.connect(&config),
+        TransportKind::Mqtt => cli.transport.broker(&config),
+        TransportKind::Shm => ring_path(&topic).display().to_string(),
+        TransportKind::Zenoh if config.zenoh.is_empty() => "Zenoh scouting".to_string(),
+        TransportKind::Zenoh => config.zenoh.join(","),
+    };
+    let receiver: Box<dyn Transport> = match transport {
+        TransportKind::Zmq => {
+            let mut zmq = ZmqTransport::connect(&Context::new(), &source)?;
+            if cli.decode.legacy_frames {
+                zmq = zmq.accept_legacy_frames();
+            }
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => Box::new(MqttTransport::connect(&source)?),
+        TransportKind::Shm => Box::new(ShmTransport::open(&topic)?),
+        TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+    };
+    let mut subscriber = Subscriber::new(receiver, &topic)?;
+    if let Some(format) = cli.decode.format {
+        subscriber = subscriber.with_format(format);
+    }
+
+    let mut dashboard = Dashboard::new(topic, source);
+    let mut terminal = ratatui::init();
+    // Put the terminal back even when drawing fails.
+    let result = show(&mut terminal, &cli, &subscriber, &mut dashboard);
+    ratatui::restore();
+    result
+}
+
+fn show(
+    terminal: &mut DefaultTerminal,
+    cli: &Cli,
+    subscriber: &Subscriber,
+    dashboard: &mut Dashboard,
+) -> Result<()> {
+    let refresh = Duration::from_millis(cli.refresh.max(1));
+    loop {
+        dashboard.receive(subscriber);
+        terminal.draw(|frame| draw(frame, cli, dashboard))?;
+        if event::poll(refresh)? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press
+                    && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c)
+                {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, cli: &Cli, dashboard: &Dashboard) {
+    let [header, table, footer] = Layout::vertical([
+        Constraint::Length(5),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let robot = dashboard.latest.as_ref().map_or("-".to_string(), |state| {
+        format!("{} (sequence {})", state.robot_id, state.order())
+    });
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |ms| format!("{ms:.1} ms"));
+    let rate = dashboard.report.as_ref().map_or_else(
+        || "-".to_string(),
+        |report| {
+            format!(
+                "{:.1} Hz   jitter {}   latency {}",
+                report.rate_hz,
+                ms(report.jitter_ms),
+                ms(report.latency_ms)
+            )
+        },
+    );
+    let age = match dashboard.last_heard {
+        Some(at) => {
+            let age = at.elapsed();
+            let style = if age > Duration::from_millis(cli.stale_ms) {
+                Style::new().fg(Color::Red).bold()
+            } else {
+                Style::new().fg(Color::Green)
+            };
+            Span::styled(format!("{:.1} s ago", age.as_secs_f64()), style)
+        }
+        None => Span::styled("never", Style::new().fg(Color::Red).bold()),
+    };
+    let summary = vec![
+        Line::from(format!("robot     {robot}")),
+        Line::from(format!("rate      {rate}")),
+        Line::from(vec![
+            format!("received  {}   last ", dashboard.received).into(),
+            age,
+        ]),
+    ];
+    let title = format!(" {} @ {} ", dashboard.topic, dashboard.source);
+    frame.render_widget(
+        Paragraph::new(summary).block(Block::bordered().title(title)),
+        header,
+    );
+
+    let rows = dashboard
+        .latest
+        .iter()
+        .flat_map(|state| &state.joints)
+        .map(|joint| {
+            Row::new(vec![
+                joint.joint_name.clone(),
+                format!("{:+.3}", joint.angle_rad),
+                bar(joint.angle_rad, cli.angle_range, BAR_WIDTH),
+                format!("{:+.3}", joint.velocity),
+                bar(joint.velocity, cli.velocity_range, BAR_WIDTH),
+            ])
+        });
+    let widths = [
+        Constraint::Min(12),
+        Constraint::Length(8),
+        Constraint::Length(BAR_WIDTH as u16),
+        Constraint::Length(8),
+        Constraint::Length(BAR_WIDTH as u16),
+    ];
+    let table_widget = Table::new(rows, widths)
+        .header(Row::new(["joint", "rad", "angle", "rad/s", "velocity"]).style(Style::new().bold()))
+        .column_spacing(2)
+        .block(Block::bordered().title(" joints "));
+    frame.render_widget(table_widget, table);
+
+    let footer_line = match &dashboard.last_error {
+        Some(error) => Line::from(format!("last error: {error}")).fg(Color::Yellow),
+        None => Line::from("q to quit").fg(Color::DarkGray),
+    };
+    frame.render_widget(Paragraph::new(footer_line), footer);
+}
+
+/// `value` as a bar `width` characters wide, centered on zero and full at
+/// `full_scale` either way; a value beyond that fills its half and ends in `>`
+/// or `<`.
+fn bar(value: f64, full_scale: f64, width: usize) -> String {
+    let half = width / 2;
+    let fraction = if full_scale > 0.0 {
+        value / full_scale
+    } else {
+        0.0
+    };
+    let filled = ((fraction.abs().min(1.0) * half as f64).round() as usize).min(half);
+    let clipped = fraction.abs() > 1.0;
+    let mut cells = vec![' '; width];
+    cells[half] = '|';
+    for i in 1..=filled {
+        let cell = if value < 0.0 { half - i } else { half + i };
+        cells[cell] = '█';
+    }
+    if clipped {
+        if value < 0.0 {
+            cells[0] = '<';
+        } else {
+            cells[width - 1] = '>';
+        }
+    }
+    cells.into_iter().collect()
+}
@@ -0,0 +1,146 @@
+//! UDP bridge binary: republishes raw UDP sensor packets (a data glove, a mocap
+//! rig) as robot joint angles via ZMQ or an MQTT broker.
+//!
+//! Behind the scenes (high level):
+//! - Reads a packet layout file (see `udp_bridge` in the library) naming the joint
+//!   each float in a packet drives.
+//! - Listens on `--listen`; every packet that fits the layout is published as one
+//!   state, as soon as it arrives, so the sensor sets the rate.
+//! - Packets too short for the layout are dropped, with a warning at most once a
+//!   second; heartbeats go out as the headless publisher sends them, packets or not.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use project_robot_joint_pubsub::args::{ConfigArgs, HeartbeatArgs, PublishArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::udp_bridge::PacketLayout;
+use project_robot_joint_pubsub::{
+    MqttTransport, Publisher, ShmTransport, Transport, TransportKind, ZmqTransport,
+};
+use std::io;
+use std::net::UdpSocket;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "udp_bridge")]
+#[command(about = "Robot joint angles publisher fed by raw UDP sensor packets")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Which joint each value in a packet drives, and how values are encoded (TOML)
+    #[arg(long, value_name = "FILE", default_value = "udp_layout.toml")]
+    layout: PathBuf,
+    /// UDP address to receive sensor packets on
+    #[arg(long, value_name = "ADDR", default_value = "0.0.0.0:9870")]
+    listen: String,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    publish: PublishArgs,
+    /// Publish topic (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    heartbeat: HeartbeatArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let layout = PacketLayout::load(&cli.layout)?;
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let format = cli.publish.format(&config);
+    let heartbeat = cli.heartbeat.interval(&config);
+
+    let socket =
+        UdpSocket::bind(&cli.listen).with_context(|| format!("listening on {}", cli.listen))?;
+    // Wake up for heartbeats while no packets come.
+    socket.set_read_timeout(Some(heartbeat.unwrap_or(Duration::from_secs(1))))?;
+
+    let transport: Box<dyn Transport> = match cli.transport.kind(&config) {
+        TransportKind::Zmq => {
+            let bind = cli.publish.bind(&config);
+            tracing::info!(%bind, %topic, ?format, "starting UDP bridge");
+            Box::new(ZmqTransport::bind(&Context::new(), &bind)?)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            tracing::info!(%broker, %topic, ?format, "starting UDP bridge over MQTT");
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => {
+            tracing::info!(%topic, ?format, "starting UDP bridge over shared memory");
+            Box::new(ShmTransport::create(&topic)?)
+        }
+        TransportKind::Zenoh => {
+            tracing::info!(zenoh = ?config.zenoh, %topic, ?format, "starting UDP bridge over Zenoh");
+            open_zenoh(&config.zenoh)?
+        }
+    };
+    let publisher = Publisher::new(transport, &topic)
+        .with_format(format)
+        .with_split_by_finger(cli.publish.split_by_finger);
+    tracing::info!(
+        listen = %socket.local_addr()?,
+        layout = %cli.layout.display(),
+        robot_id = %layout.robot_id,
+        values = layout.joints.len(),
+        packet_bytes = layout.packet_len(),
+        "waiting for sensor packets"
+    );
+
+    // Room for a full-size datagram, so long packets are read whole and not truncated.
+    let mut buf = vec![0; 65536];
+    let mut sequence = 0;
+    let mut dropped = 0u64;
+    let mut last_warning: Option<Instant> = None;
+    let mut last_heartbeat: Option<Instant> = None;
+    loop {
+        match socket.recv_from(&mut buf) {
+            Ok((len, from)) => match layout.decode(&buf[..len], sequence + 1) {
+                Ok(robot_state) => {
+                    sequence += 1;
+                    publisher.publish(&robot_state)?;
+                    tracing::debug!(sequence, %from, "published");
+                }
+                Err(e) => {
+                    dropped += 1;
+                    if last_warning.is_none_or(|last| last.elapsed() >= Duration::from_secs(1)) {
+                        tracing::warn!(error = %e, %from, dropped, "dropping packet");
+                        last_warning = Some(Instant::now());
+                    }
+                }
+            },
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(e) => return Err(e).context("receiving sensor packets"),
+        }
+
+        if let Some(every) = heartbeat {
+            if last_heartbeat.is_none_or(|last| last.elapsed() >= every) {
+                publisher.heartbeat()?;
+                last_heartbeat = Some(Instant::now());
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! WebSocket relay: subscribes to robot joint angles via ZMQ or MQTT and re-serves
+//! every `RobotState` as JSON over WebSocket for browser dashboards, whichever
+//! payload format the publisher sends.
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use common::LogOutput;
+use errors::{Exit, ExitStatus};
+use project_robot_joint_pubsub::args::{ConfigArgs, ConnectArgs, DecodeArgs, TransportArgs};
+use project_robot_joint_pubsub::logging::LogArgs;
+use project_robot_joint_pubsub::transport::open_zenoh;
+use project_robot_joint_pubsub::ws::{Feed, DEFAULT_BACKLOG};
+use project_robot_joint_pubsub::{
+    AsyncSubscriber, MqttTransport, ShmTransport, Subscriber, Transport, TransportKind,
+    ZmqTransport,
+};
+use std::process::ExitCode;
+use tokio::net::TcpListener;
+use zmq::Context;
+
+#[derive(Parser)]
+#[command(name = "ws_relay")]
+#[command(about = "Re-serve robot joint angles as JSON over WebSocket")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Address the WebSocket server listens on
+    #[arg(long, default_value = "127.0.0.1:9001")]
+    ws_listen: String,
+    /// States a browser may fall behind by before it is disconnected
+    #[arg(long, default_value_t = DEFAULT_BACKLOG)]
+    backlog: usize,
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(flatten)]
+    connect: ConnectArgs,
+    /// Filter topic (default: config `topic`, else robot_joints)
+    #[arg(short, long)]
+    topic: Option<String>,
+    #[command(flatten)]
+    decode: DecodeArgs,
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            project_robot_joint_pubsub::exit_for(e.as_ref()).into()
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    cli.log.init(env!("CARGO_CRATE_NAME"), LogOutput::Stderr)?;
+    let config = cli.config.load()?;
+    let transport = cli.transport.kind(&config);
+    let topic = cli.topic.unwrap_or_else(|| config.topic.clone());
+    let source: Box<dyn Transport> = match transport {
+        TransportKind::Zmq => {
+            let connect = cli.connect.connect(&config);
+            let mut zmq = ZmqTransport::connect(&Context::new(), &connect)?;
+            if cli.decode.legacy_frames {
+                zmq = zmq.accept_legacy_frames();
+            }
+            Box::new(zmq)
+        }
+        TransportKind::Mqtt => {
+            let broker = cli.transport.broker(&config);
+            Box::new(MqttTransport::connect(&broker)?)
+        }
+        TransportKind::Shm => Box::new(ShmTransport::open(&topic)?),
+        TransportKind::Zenoh => open_zenoh(&config.zenoh)?,
+    };
+    let mut subscriber = Subscriber::new(source, &topic)?;
+    if let Some(format) = cli.decode.format {
+        subscriber = subscriber.with_format(format);
+    }
+
+    let listener = TcpListener::bind(&cli.ws_listen)
+        .await
+        .with_context(|| format!("listening on {}", cli.ws_listen))?;
+    tracing::info!(
+        ws_listen = %listener.local_addr()?,
+        ?transport,
+        %topic,
+        "relaying joint states to WebSocket clients"
+    );
+    let feed = Feed::new(cli.backlog);
+    let server = feed.clone().serve(listener);
+    tokio::pin!(server);
+
+    // `Feed::send` never waits, so relaying keeps up with receiving.
+    let mut states = AsyncSubscriber::spawn(subscriber);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            received = states.recv() => match received.context("receiving thread died")? {
+                Ok(state) => {
+                    let clients = feed.send(&state);
+                    tracing::trace!(timestamp = state.timestamp, clients, "relayed");
+                }
+                Err(e) if e.exit() == Exit::Network => {
+                    tracing::error!(error = %errors::report(&e), "receive error");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %errors::report(&e), "skipping message");
+                }
+            },
+            served = &mut server => return served.context("accepting WebSocket clients"),
+            signal = &mut ctrl_c => {
+                signal.context("waiting for Ctrl-C")?;
+                tracing::info!("relay stopped");
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Viewer camera viewpoints for the subscriber's `--camera`, so demo videos
+//! start from the same place every time.
+//!
+//! A [`Viewpoint`] is MuJoCo's free camera: the point it looks at, how far
+//! away it is, and from which direction. `--camera` takes a [`Preset`] (placed
+//! around the model from its center and size) or a TOML file that
+//! `--save-camera` wrote when a previous viewer closed:
+//!
+//! ```toml
+//! lookat = [0.0, 0.0, 0.1]   # m, world coordinates
+//! distance = 0.45            # m
+//! azimuth = 135.0            # degrees about the vertical axis
+//! elevation = -20.0          # degrees, negative looks down
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use errors::{Exit, ExitStatus};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A viewpoint file that could not be read or written.
+#[derive(Debug, Error)]
+pub enum CameraError {
+    #[error("reading {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("writing {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad viewpoint file")]
+    Toml(#[from] toml::de::Error),
+    #[error("viewpoint does not serialize to TOML")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+impl ExitStatus for CameraError {
+    fn exit(&self) -> Exit {
+        match self {
+            CameraError::Read { .. } | CameraError::Write { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// Where MuJoCo's free camera is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Viewpoint {
+    /// The point looked at, in world coordinates (m).
+    pub lookat: [f64; 3],
+    /// From `lookat` to the camera (m).
+    pub distance: f64,
+    /// Degrees about the vertical axis.
+    pub azimuth: f64,
+    /// Degrees above (positive) or below the horizontal; negative looks down.
+    pub elevation: f64,
+}
+
+impl FromStr for Viewpoint {
+    type Err = CameraError;
+
+    fn from_str(raw: &str) -> Result<Self, CameraError> {
+        Ok(toml::from_str(raw)?)
+    }
+}
+
+impl Viewpoint {
+    /// Read a viewpoint file.
+    pub fn load(path: &Path) -> Result<Self, CameraError> {
+        let raw = fs::read_to_string(path).map_err(|source| CameraError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// Write the viewpoint to `path`, replacing what it held.
+    pub fn save(&self, path: &Path) -> Result<(), CameraError> {
+        let raw = toml::to_string(self)?;
+        fs::write(path, raw).map_err(|source| CameraError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Built-in viewpoints, placed around a model by its center and extent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Preset {
+    /// Level, from the front (azimuth 180°).
+    Front,
+    /// Looking straight down (elevation -90°), onto the palm of a hand lying palm up.
+    Palm,
+    /// Level, from the side (azimuth 90°).
+    Side,
+}
+
+impl Preset {
+    /// This preset for a model whose bounding sphere is centered on `center`
+    /// with radius `extent`, at the distance MuJoCo's default free camera uses.
+    pub fn viewpoint(self, center: [f64; 3], extent: f64) -> Viewpoint {
+        let (azimuth, elevation) = match self {
+            Preset::Front => (180.0, 0.0),
+            // Exactly -90° leaves the camera's up direction undefined.
+            Preset::Palm => (90.0, -89.9),
+            Preset::Side => (90.0, 0.0),
+        };
+        Viewpoint {
+            lookat: center,
+            distance: 1.5 * extent,
+            azimuth,
+            elevation,
+        }
+    }
+}
+
+/// `--camera`: a preset, or a viewpoint file for anything else.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraArg {
+    Preset(Preset),
+    File(PathBuf),
+}
+
+impl FromStr for CameraArg {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        match <Preset as clap::ValueEnum>::from_str(raw, true) {
+            Ok(preset) => Ok(CameraArg::Preset(preset)),
+            Err(_) if raw.is_empty() => Err("expected a preset or a viewpoint file".into()),
+            Err(_) => Ok(CameraArg::File(raw.into())),
+        }
+    }
+}
+
+impl CameraArg {
+    /// The viewpoint for a model centered on `center` with radius `extent`.
+    pub fn viewpoint(&self, center: [f64; 3], extent: f64) -> Result<Viewpoint, CameraError> {
+        match self {
+            CameraArg::Preset(preset) => Ok(preset.viewpoint(center, extent)),
+            CameraArg::File(path) => Viewpoint::load(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_and_files_give_viewpoints() {
+        assert_eq!(
+            "front".parse::<CameraArg>().unwrap(),
+            CameraArg::Preset(Preset::Front)
+        );
+        let front = Preset::Front.viewpoint([0.0, 0.0, 0.1], 0.2);
+        assert_eq!(front.lookat, [0.0, 0.0, 0.1]);
+        assert!((front.distance - 0.3).abs() < 1e-12);
+
+        let path = std::env::temp_dir().join(format!("viewpoint-{}.toml", std::process::id()));
+        front.save(&path).unwrap();
+        let camera: CameraArg = path.to_str().unwrap().parse().unwrap();
+        assert_eq!(camera.viewpoint([9.0; 3], 9.0).unwrap(), front);
+        fs::remove_file(&path).unwrap();
+
+        let err = camera.viewpoint([0.0; 3], 1.0).unwrap_err();
+        assert_eq!(err.exit(), Exit::Io);
+        assert!("distance = 1.0".parse::<Viewpoint>().is_err());
+        assert!("".parse::<CameraArg>().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Wall-clock time for `timestamp`s, and how far a peer's clock is from ours.
+//!
+//! Latency across machines compares the publisher's clock with the
+//! subscriber's, so it is only meaningful once their offset is known. Every
+//! heartbeat carries a JSON [`Heartbeat`]: its send time and, if this side has
+//! heard one from the other, an [`Echo`] of the last of those. A side that
+//! both publishes and subscribes to its peer (`subscriber --publish-feedback`
+//! and the GUI publisher's `--feedback`, the headless publisher's
+//! `--clock-sync`, or `grpc_bridge`) then completes NTP-style round trips, and
+//! [`ClockSync`] estimates the offset from them, e.g.
+//! `{"sent_at_ns":1718000000000000000,"echo":{"sent_at_ns":...,"received_at_ns":...}}`.
+//!
+//! Heartbeats from older publishers are empty and only count as heartbeats.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Round trips [`ClockSync`] keeps to pick the best estimate from.
+pub const SAMPLES: usize = 8;
+
+/// Wall-clock nanoseconds since the Unix epoch.
+pub fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64)
+}
+
+/// One heartbeat's payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// Send time by the sender's clock, in nanoseconds since the Unix epoch.
+    pub sent_at_ns: u64,
+    /// The last heartbeat the sender heard from the receiver, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<Echo>,
+}
+
+/// A heartbeat heard, sent back to where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Echo {
+    /// Its `sent_at_ns`, by the clock of whoever sent it.
+    pub sent_at_ns: u64,
+    /// When it arrived, by the clock of whoever echoes it.
+    pub received_at_ns: u64,
+}
+
+/// A [`Heartbeat`] as a JSON payload.
+pub fn to_payload(heartbeat: &Heartbeat) -> Vec<u8> {
+    serde_json::to_vec(heartbeat).expect("Heartbeat always serializes")
+}
+
+/// A heartbeat payload as a [`Heartbeat`], or `None` for an empty one from an
+/// older publisher (or anything else that is not one).
+pub fn from_payload(payload: &[u8]) -> Option<Heartbeat> {
+    serde_json::from_slice(payload).ok()
+}
+
+/// The peer's clock relative to ours, from one round trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Offset {
+    /// The peer's clock minus ours, in nanoseconds.
+    pub offset_ns: i64,
+    /// The round trip's time on the wire, without the time the peer held
+    /// the echo; the offset is off by at most half of it.
+    pub round_trip_ns: u64,
+}
+
+/// Estimates a peer's clock offset from heartbeat round trips.
+///
+/// Shared between a [`Subscriber`](crate::Subscriber) hearing the peer's
+/// heartbeats and a [`Publisher`](crate::Publisher) sending ours to it (see
+/// their `with_clock_sync`), which may run on different threads.
+#[derive(Debug, Default)]
+pub struct ClockSync {
+    state: Mutex<SyncState>,
+}
+
+#[derive(Debug, Default)]
+struct SyncState {
+    /// The peer's last heartbeat, to echo in our next one.
+    heard: Option<Echo>,
+    /// The latest round trips, oldest first.
+    samples: VecDeque<Offset>,
+}
+
+impl ClockSync {
+    /// Take `heartbeat` from the peer, which arrived at `received_at_ns` by our
+    /// clock; if it echoes one of ours, that completes a round trip.
+    pub fn heard(&self, heartbeat: &Heartbeat, received_at_ns: u64) {
+        let mut state = self.state.lock().expect("clock sync lock");
+        state.heard = Some(Echo {
+            sent_at_ns: heartbeat.sent_at_ns,
+            received_at_ns,
+        });
+        let Some(echo) = heartbeat.echo else {
+            return;
+        };
+        // NTP's four times: ours out, theirs in, theirs out, ours in.
+        let [t1, t2, t3, t4] = [
+            echo.sent_at_ns,
+            echo.received_at_ns,
+            heartbeat.sent_at_ns,
+            received_at_ns,
+        ]
+        .map(i128::from);
+        let round_trip = (t4 - t1) - (t3 - t2);
+        if round_trip < 0 {
+            // A clock stepped mid-trip; nothing to learn from it.
+            return;
+        }
+        if state.samples.len() == SAMPLES {
+            state.samples.pop_front();
+        }
+        state.samples.push_back(Offset {
+            offset_ns: (((t2 - t1) + (t3 - t4)) / 2) as i64,
+            round_trip_ns: round_trip as u64,
+        });
+    }
+
+    /// The heartbeat to send at `now_ns`, echoing the peer's last one once.
+    pub fn heartbeat(&self, now_ns: u64) -> Heartbeat {
+        Heartbeat {
+            sent_at_ns: now_ns,
+            echo: self.state.lock().expect("clock sync lock").heard.take(),
+        }
+    }
+
+    /// The estimate from the quickest of the last [`SAMPLES`] round trips,
+    /// whose offset is the least blurred by queuing; `None` before the first.
+    pub fn offset(&self) -> Option<Offset> {
+        let state = self.state.lock().expect("clock sync lock");
+        state
+            .samples
+            .iter()
+            .min_by_key(|sample| sample.round_trip_ns)
+            .copied()
+    }
+
+    /// `local`, one of our clock's times, by the peer's clock; as it is
+    /// before the first round trip.
+    pub fn to_peer(&self, local: SystemTime) -> SystemTime {
+        match self.offset() {
+            Some(Offset { offset_ns, .. }) if offset_ns >= 0 => {
+                local + Duration::from_nanos(offset_ns as u64)
+            }
+            Some(Offset { offset_ns, .. }) => {
+                local - Duration::from_nanos(offset_ns.unsigned_abs())
+            }
+            None => local,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One heartbeat from `from` to `to`, sent at `sent` by our clock and
+    /// delivered `delay` later; `skew` is the sender's and the receiver's
+    /// clock minus ours.
+    fn deliver(from: &ClockSync, to: &ClockSync, sent: u64, delay: u64, skew: (u64, u64)) {
+        let heartbeat = from.heartbeat(sent + skew.0);
+        to.heard(&heartbeat, sent + delay + skew.1);
+    }
+
+    #[test]
+    fn round_trips_find_the_offset() {
+        let (ours, theirs) = (ClockSync::default(), ClockSync::default());
+        // Their clock runs 500 ns ahead of ours.
+        deliver(&ours, &theirs, 1_000, 100, (0, 500));
+        assert_eq!(ours.offset(), None);
+        deliver(&theirs, &ours, 1_200, 100, (500, 0));
+        assert_eq!(
+            ours.offset(),
+            Some(Offset {
+                offset_ns: 500,
+                round_trip_ns: 200
+            })
+        );
+        // Each heartbeat is echoed once: a second one completes no trip.
+        deliver(&theirs, &ours, 1_400, 100, (500, 0));
+        assert_eq!(ours.offset().unwrap().round_trip_ns, 200);
+
+        let at = UNIX_EPOCH + Duration::from_secs(10);
+        assert_eq!(ours.to_peer(at), at + Duration::from_nanos(500));
+    }
+
+    #[test]
+    fn the_quickest_round_trip_wins() {
+        let (ours, theirs) = (ClockSync::default(), ClockSync::default());
+        // A queued reply makes the trip slow and the offset off by half the extra delay.
+        for (sent, back_delay) in [(0, 900), (10_000, 100), (20_000, 2_000)] {
+            deliver(&ours, &theirs, sent, 100, (0, 500));
+            deliver(&theirs, &ours, sent + 200, back_delay, (500, 0));
+        }
+        assert_eq!(
+            ours.offset(),
+            Some(Offset {
+                offset_ns: 500,
+                round_trip_ns: 200
+            })
+        );
+    }
+
+    #[test]
+    fn older_heartbeats_are_empty() {
+        assert_eq!(from_payload(b""), None);
+        let heartbeat = Heartbeat {
+            sent_at_ns: 7,
+            echo: None,
+        };
+        assert_eq!(to_payload(&heartbeat), br#"{"sent_at_ns":7}"#);
+        assert_eq!(from_payload(&to_payload(&heartbeat)), Some(heartbeat));
+    }
+}
@@ -0,0 +1,250 @@
+//! Endpoint and timing settings shared by every binary.
+//!
+//! Layered, each over the last: built-in defaults, a TOML file, then
+//! `ROBOT_PUBSUB_*` environment variables; command-line flags override all of
+//! them. The file is `--config <file>`, else `$ROBOT_PUBSUB_CONFIG`, else
+//! `~/.config/robot_joint_pubsub/config.toml` (or
+//! `$XDG_CONFIG_HOME/robot_joint_pubsub/`, found through the shared `common`
+//! crate). Every key is optional.
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use common::AppDirs;
+use errors::{DataContext, IoContext};
+use serde::Deserialize;
+
+use crate::interpolation::Interpolation;
+use crate::wire::Format;
+
+/// Name used for the XDG config directory.
+pub const APP_NAME: &str = "robot_joint_pubsub";
+
+/// Prefix of the environment variables that override keys, e.g.
+/// `ROBOT_PUBSUB_INTERVAL_MS=20` for `interval_ms`.
+pub const ENV_PREFIX: &str = "ROBOT_PUBSUB_";
+
+/// Variable naming the config file when there is no `--config`.
+pub const CONFIG_VAR: &str = "ROBOT_PUBSUB_CONFIG";
+
+/// `config.toml` for every binary.
+///
+/// ```toml
+/// bind = "tcp://*:6000"
+/// connect = "tcp://robot-pc:6000"
+/// topic = "robot_joints"
+/// interval_ms = 20
+/// transport = "mqtt"           # or "zenoh", "shm", or "zmq" (the default)
+/// broker = "mqtt://broker.factory.lan:1883"
+/// zenoh = ["tcp/router.factory.lan:7447"]
+/// format = "protobuf"          # or "msgpack", or "json" (the default)
+/// interpolation = "linear"     # or "cubic", or "none" (the default)
+/// heartbeat_ms = 500
+/// liveness_timeout_ms = 2000
+/// joints = ["thumb_cmc", "index_mcp"]
+/// filter_prefix = ["f1_", "t"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// ZMQ address the publisher binds.
+    pub bind: String,
+    /// ZMQ address the subscriber connects to.
+    pub connect: String,
+    /// Topic prefix on every message.
+    pub topic: String,
+    /// Publisher interval in milliseconds.
+    pub interval_ms: u64,
+    /// MJCF model for the subscriber and GUI publisher, relative to
+    /// `project_robot_joint_pubsub/`.
+    pub model: PathBuf,
+    /// Which transport the binaries use.
+    pub transport: TransportKind,
+    /// MQTT broker (`host[:port]`) for [`TransportKind::Mqtt`]; `bind` and `connect` are then unused.
+    pub broker: String,
+    /// Zenoh endpoints (`tcp/host:7447`) for [`TransportKind::Zenoh`]; empty
+    /// finds peers by multicast scouting.
+    pub zenoh: Vec<String>,
+    /// Payload encoding the publishers send; subscribers detect it.
+    pub format: Format,
+    /// How the subscriber fills in poses between states.
+    pub interpolation: Interpolation,
+    /// Publisher heartbeat interval in milliseconds; 0 sends none.
+    pub heartbeat_ms: u64,
+    /// Subscriber warns after this many milliseconds without a state or
+    /// heartbeat; 0 never does.
+    pub liveness_timeout_ms: u64,
+    /// Joints the monitor plots; empty plots every joint.
+    pub joints: Vec<String>,
+    /// Joint name prefixes the GUI publisher shows sliders for; empty shows
+    /// every joint.
+    pub filter_prefix: Vec<String>,
+}
+
+/// How states travel from publisher to subscriber.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// ZMQ PUB/SUB: the publisher binds, subscribers connect to it.
+    #[default]
+    Zmq,
+    /// MQTT: both sides connect to a broker.
+    Mqtt,
+    /// A shared-memory ring per topic, for publisher and subscribers on one
+    /// host; `bind`, `connect`, and `broker` are then unused.
+    Shm,
+    /// Zenoh: peers find each other, or meet at the `zenoh` endpoints; `bind`,
+    /// `connect`, and `broker` are then unused.
+    Zenoh,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bind: "tcp://*:5555".to_string(),
+            connect: "tcp://localhost:5555".to_string(),
+            topic: "robot_joints".to_string(),
+            interval_ms: 100,
+            model: PathBuf::from("pro-models/example/scenes/left_hand_scene.xml"),
+            transport: TransportKind::Zmq,
+            broker: "localhost:1883".to_string(),
+            zenoh: Vec::new(),
+            format: Format::Json,
+            interpolation: Interpolation::None,
+            heartbeat_ms: 1000,
+            liveness_timeout_ms: 3000,
+            joints: Vec::new(),
+            filter_prefix: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// The file at `path` (the `--config` flag), else at `$ROBOT_PUBSUB_CONFIG`,
+    /// else the user's config file, with the process's `ROBOT_PUBSUB_*`
+    /// variables over it.
+    ///
+    /// A file named by the flag or variable must exist; the user's config file
+    /// may be missing, leaving the defaults.
+    pub fn load(path: Option<&Path>) -> errors::Result<Config> {
+        let named = path
+            .map(Path::to_path_buf)
+            .or_else(|| env::var_os(CONFIG_VAR).map(PathBuf::from));
+        let (path, required) = match named {
+            Some(path) => (path, true),
+            None => (AppDirs::new(APP_NAME)?.config_file(), false),
+        };
+        let file = match fs::read_to_string(&path) {
+            Ok(raw) => raw.parse().data_context("parsing", &path)?,
+            Err(e) if e.kind() == ErrorKind::NotFound && !required => toml::Table::new(),
+            Err(e) => return Err(e).io_context("reading", &path),
+        };
+        // Errors name the key, whether the file or a variable set it.
+        Config::layered(file, env::vars()).data_context("loading settings from", &path)
+    }
+
+    /// The keys in `file` with every `ROBOT_PUBSUB_<KEY>` in `vars` set over
+    /// them, over the defaults.
+    ///
+    /// A variable's value is read as TOML when it is a number, boolean, or
+    /// array (`ROBOT_PUBSUB_JOINTS='["wrist"]'`), and as a string otherwise,
+    /// so addresses and names need no quotes. Variables naming no key are
+    /// ignored.
+    pub fn layered(
+        mut file: toml::Table,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Config, toml::de::Error> {
+        for (name, value) in vars {
+            if name == CONFIG_VAR {
+                continue;
+            }
+            if let Some(key) = name.strip_prefix(ENV_PREFIX) {
+                file.insert(key.to_lowercase(), env_value(&value));
+            }
+        }
+        file.try_into()
+    }
+}
+
+/// `raw` as the TOML value it spells, or as a string if it spells none.
+fn env_value(raw: &str) -> toml::Value {
+    format!("value = {raw}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_file_keeps_defaults() {
+        let config: Config = toml::from_str("topic = \"arm\"\ninterval_ms = 20\n").unwrap();
+        assert_eq!(config.topic, "arm");
+        assert_eq!(config.interval_ms, 20);
+        assert_eq!(config.bind, Config::default().bind);
+        assert_eq!(config.transport, TransportKind::Zmq);
+    }
+
+    #[test]
+    fn transport_is_lowercase() {
+        let config: Config = toml::from_str("transport = \"mqtt\"\n").unwrap();
+        assert_eq!(config.transport, TransportKind::Mqtt);
+        assert!(toml::from_str::<Config>("transport = \"Mqtt\"\n").is_err());
+        let config: Config = toml::from_str("transport = \"zenoh\"\n").unwrap();
+        assert_eq!(config.transport, TransportKind::Zenoh);
+        let config: Config = toml::from_str("format = \"protobuf\"\n").unwrap();
+        assert_eq!(config.format, Format::Protobuf);
+        let config: Config = toml::from_str("format = \"msgpack\"\n").unwrap();
+        assert_eq!(config.format, Format::MessagePack);
+        let config: Config = toml::from_str("interpolation = \"cubic\"\n").unwrap();
+        assert_eq!(config.interpolation, Interpolation::Cubic);
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn variables_override_the_file() {
+        let file = "topic = \"arm\"\ninterval_ms = 20\nbind = \"tcp://*:6000\"\n"
+            .parse()
+            .unwrap();
+        let config = Config::layered(
+            file,
+            vars(&[
+                ("ROBOT_PUBSUB_INTERVAL_MS", "5"),
+                ("ROBOT_PUBSUB_TRANSPORT", "mqtt"),
+                ("ROBOT_PUBSUB_CONNECT", "tcp://robot-pc:6000"),
+                ("ROBOT_PUBSUB_JOINTS", "[\"wrist\", \"thumb\"]"),
+                ("ROBOT_PUBSUB_CONFIG", "elsewhere.toml"),
+                ("HOME", "/root"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.topic, "arm");
+        assert_eq!(config.bind, "tcp://*:6000");
+        assert_eq!(config.interval_ms, 5);
+        assert_eq!(config.transport, TransportKind::Mqtt);
+        assert_eq!(config.connect, "tcp://robot-pc:6000");
+        assert_eq!(config.joints, ["wrist", "thumb"]);
+        assert_eq!(config.model, Config::default().model);
+    }
+
+    #[test]
+    fn mistyped_variable_names_its_key() {
+        let error = Config::layered(
+            toml::Table::new(),
+            vars(&[("ROBOT_PUBSUB_INTERVAL_MS", "fast")]),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("interval_ms"), "{error}");
+    }
+}
@@ -0,0 +1,118 @@
+//! Commands that stop and restart whatever is following a publisher's states:
+//! an emergency stop, a hold, and a resume.
+//!
+//! They travel next to the states, on [`topic`], like heartbeats, so every
+//! subscriber to a topic hears them without another socket. The payload is
+//! always a JSON [`Control`], whatever `--format` the states use, e.g.
+//! `{"command":"estop","sent_at_us":1718000000000000}`.
+//!
+//! [`Publisher::control`](crate::Publisher::control) sends one (the GUI
+//! publisher's E-STOP, Hold, and Resume buttons);
+//! [`Subscriber::take_controls`](crate::Subscriber::take_controls) yields them,
+//! and [`Status::after`] says what a subscriber should be doing once it has.
+
+use serde::{Deserialize, Serialize};
+
+use crate::wire::WireError;
+
+/// The topic control commands for `topic` travel on.
+///
+/// ZMQ subscriptions match by prefix, so a ZMQ subscriber to `topic` receives
+/// them without subscribing again; subscribers that predate them skip them like
+/// any other topic that is not exactly theirs.
+pub fn topic(topic: &str) -> String {
+    format!("{topic}/control")
+}
+
+/// What a control message asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Stop now and ignore states until [`ControlCommand::Resume`].
+    #[serde(rename = "estop")]
+    EStop,
+    /// Keep the last commanded pose and ignore states until
+    /// [`ControlCommand::Resume`].
+    Hold,
+    /// Follow states again.
+    Resume,
+}
+
+/// One control message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Control {
+    pub command: ControlCommand,
+    /// Wall-clock send time in microseconds since the Unix epoch, stamped by
+    /// [`Publisher::control`](crate::Publisher::control).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at_us: Option<u64>,
+}
+
+/// `control` as a JSON payload.
+pub fn to_payload(control: &Control) -> Vec<u8> {
+    serde_json::to_vec(control).expect("Control always serializes")
+}
+
+/// A JSON payload as a [`Control`].
+pub fn from_payload(payload: &[u8]) -> Result<Control, WireError> {
+    serde_json::from_slice(payload).map_err(WireError::Json)
+}
+
+/// What a subscriber does with states, given the control commands so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Status {
+    /// Following states.
+    #[default]
+    Running,
+    /// Holding the last commanded pose.
+    Held,
+    /// Emergency-stopped.
+    Stopped,
+}
+
+impl Status {
+    /// The status once `command` has arrived. Only a resume ends an emergency
+    /// stop; a hold while stopped stays stopped.
+    pub fn after(self, command: ControlCommand) -> Status {
+        match (self, command) {
+            (_, ControlCommand::EStop) => Status::Stopped,
+            (Status::Stopped, ControlCommand::Hold) => Status::Stopped,
+            (_, ControlCommand::Hold) => Status::Held,
+            (_, ControlCommand::Resume) => Status::Running,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_small_json_objects() {
+        let control = Control {
+            command: ControlCommand::EStop,
+            sent_at_us: None,
+        };
+        assert_eq!(to_payload(&control), br#"{"command":"estop"}"#);
+        assert_eq!(
+            from_payload(br#"{"command":"resume"}"#).unwrap().command,
+            ControlCommand::Resume
+        );
+        assert!(matches!(
+            from_payload(br#"{"command":"explode"}"#),
+            Err(WireError::Json(_))
+        ));
+        assert_eq!(topic("robot_joints"), "robot_joints/control");
+    }
+
+    #[test]
+    fn only_resume_ends_an_emergency_stop() {
+        use ControlCommand::*;
+        let status = Status::default().after(Hold);
+        assert_eq!(status, Status::Held);
+        let status = status.after(EStop);
+        assert_eq!(status, Status::Stopped);
+        assert_eq!(status.after(Hold), Status::Stopped);
+        assert_eq!(status.after(Resume), Status::Running);
+    }
+}
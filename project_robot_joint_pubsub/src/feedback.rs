@@ -0,0 +1,91 @@
+//! Measured joint states, for closing the loop on a commanded pose.
+//!
+//! With `--publish-feedback` (or `--publish-actual`) the subscriber
+//! republishes, every [`INTERVAL`], where its simulated joints actually are:
+//! after PD forces or actuators (`--mode pd`, `--mode actuate`), limits, and
+//! contacts have had their say. These are ordinary [`RobotState`]s on
+//! [`TOPIC`], so the GUI publisher's `--feedback` can set them against what it
+//! commanded, and any subscriber (`monitor_gui`, `recorder`) can plot or log
+//! them as the ground truth. Heartbeats go out with them every
+//! [`HEARTBEAT_INTERVAL`], so a publisher listening for them can echo them
+//! back for [`clock`](crate::clock) sync.
+
+use std::time::Duration;
+
+use crate::{JointAngles, RobotState};
+
+/// The default topic feedback is published on.
+pub const TOPIC: &str = "robot_joints_actual";
+
+/// How often the subscriber publishes feedback.
+pub const INTERVAL: Duration = Duration::from_millis(20);
+
+/// How often the subscriber sends a heartbeat on the feedback topic.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The measured counterpart of the commanded `state`: its `timestamp`,
+/// `sequence`, and `robot_id`, with one joint per `(name, qpos, qvel)`. The angle and velocity
+/// are the first coordinates; joints with several (ball and free joints) keep
+/// them all in `qpos`. Torque is not measured and is 0.
+pub fn from_state<'a>(
+    state: &RobotState,
+    joints: impl IntoIterator<Item = (&'a str, &'a [f64], &'a [f64])>,
+) -> RobotState {
+    RobotState {
+        timestamp: state.timestamp,
+        robot_id: state.robot_id.clone(),
+        joints: joints
+            .into_iter()
+            .map(|(name, qpos, qvel)| JointAngles {
+                timestamp: state.timestamp,
+                joint_name: name.to_string(),
+                angle_rad: qpos.first().copied().unwrap_or_default(),
+                velocity: qvel.first().copied().unwrap_or_default(),
+                torque: 0.0,
+                qpos: if qpos.len() > 1 {
+                    qpos.to_vec()
+                } else {
+                    Vec::new()
+                },
+            })
+            .collect(),
+        sent_at_us: None,
+        sequence: state.sequence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measured_joints_keep_the_commanded_timestamp_and_robot() {
+        let commanded = RobotState {
+            timestamp: 9,
+            robot_id: "hand".into(),
+            joints: Vec::new(),
+            sent_at_us: Some(1),
+            sequence: Some(3),
+        };
+        let ball = [1.0, 0.0, 0.0, 0.0];
+        let state = from_state(
+            &commanded,
+            [
+                ("i1_MCP", &[0.4][..], &[0.1][..]),
+                ("wrist", &ball[..], &[0.0, 0.0, 0.0][..]),
+            ],
+        );
+        assert_eq!(
+            (state.timestamp, state.sequence, state.robot_id.as_str()),
+            (9, Some(3), "hand")
+        );
+        assert_eq!(state.sent_at_us, None);
+        assert_eq!(state.joints[0].joint_name, "i1_MCP");
+        assert_eq!(
+            (state.joints[0].angle_rad, state.joints[0].velocity),
+            (0.4, 0.1)
+        );
+        assert!(state.joints[0].qpos.is_empty());
+        assert_eq!(state.joints[1].qpos, ball);
+    }
+}
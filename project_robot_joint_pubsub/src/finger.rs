@@ -0,0 +1,131 @@
+//! Per-finger sub-topics: a publisher with
+//! [`Publisher::with_split_by_finger`](crate::Publisher::with_split_by_finger) sends
+//! each state as one part per finger on [`topic`], e.g. `robot_joints/index`
+//! and `robot_joints/thumb`, so a consumer that only cares about one finger
+//! subscribes to just that part.
+//!
+//! A [`Subscriber`](crate::Subscriber) to the whole topic hears every part
+//! and merges the parts of one state back into a single state.
+//!
+//! Joints belong to a finger by the ProHand naming scheme: a letter for the
+//! finger, the joint's number along it, then `_` (`t1_TM` thumb, `i2_PIP`
+//! index, `m`, `r`, `p`). Anything else goes on [`OTHER`].
+
+use crate::RobotState;
+
+/// Every finger, in the order a split state's parts are sent.
+pub const FINGERS: [&str; 5] = ["thumb", "index", "middle", "ring", "pinky"];
+
+/// The part for joints of no finger (a wrist, or a robot with other names).
+pub const OTHER: &str = "other";
+
+/// The topic part `finger` of states on `topic` travels on.
+pub fn topic(topic: &str, finger: &str) -> String {
+    format!("{topic}/{finger}")
+}
+
+/// Every part topic of `topic`, [`OTHER`]'s last.
+pub fn topics(topic: &str) -> impl Iterator<Item = String> + '_ {
+    FINGERS
+        .into_iter()
+        .chain([OTHER])
+        .map(move |finger| self::topic(topic, finger))
+}
+
+/// Whether `name` is a part's name: a finger or [`OTHER`].
+pub fn is_part(name: &str) -> bool {
+    name == OTHER || FINGERS.contains(&name)
+}
+
+/// The finger `joint_name` belongs to, or [`OTHER`].
+pub fn of(joint_name: &str) -> &'static str {
+    let mut chars = joint_name.chars();
+    let (Some(letter), Some(digit)) = (chars.next(), chars.next()) else {
+        return OTHER;
+    };
+    if !digit.is_ascii_digit() {
+        return OTHER;
+    }
+    match letter {
+        't' => "thumb",
+        'i' => "index",
+        'm' => "middle",
+        'r' => "ring",
+        'p' => "pinky",
+        _ => OTHER,
+    }
+}
+
+/// `state`'s joints grouped by finger, each group a state with `state`'s
+/// timestamp, sequence number, and `robot_id`; fingers without joints are
+/// left out.
+pub fn split(state: &RobotState) -> Vec<(&'static str, RobotState)> {
+    FINGERS
+        .into_iter()
+        .chain([OTHER])
+        .filter_map(|finger| {
+            let joints: Vec<_> = state
+                .joints
+                .iter()
+                .filter(|joint| of(&joint.joint_name) == finger)
+                .cloned()
+                .collect();
+            (!joints.is_empty()).then(|| {
+                let part = RobotState {
+                    joints,
+                    ..state.clone()
+                };
+                (finger, part)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JointAngles;
+
+    #[test]
+    fn joints_go_to_their_fingers() {
+        let joint = |name: &str| JointAngles {
+            timestamp: 7,
+            joint_name: name.into(),
+            angle_rad: 0.5,
+            velocity: 0.0,
+            torque: 0.0,
+            qpos: Vec::new(),
+        };
+        let state = RobotState {
+            timestamp: 7,
+            robot_id: "hand".into(),
+            joints: ["i1_MCP", "wrist", "t1_TM", "i2_PIP", "index_joint"]
+                .map(joint)
+                .into(),
+            sent_at_us: None,
+            sequence: Some(7),
+        };
+
+        let parts: Vec<_> = split(&state)
+            .into_iter()
+            .map(|(finger, part)| {
+                assert_eq!(
+                    (part.timestamp, part.sequence, part.robot_id.as_str()),
+                    (7, Some(7), "hand")
+                );
+                let names: Vec<_> = part.joints.into_iter().map(|j| j.joint_name).collect();
+                (finger, names)
+            })
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("thumb", vec!["t1_TM".to_string()]),
+                ("index", vec!["i1_MCP".into(), "i2_PIP".into()]),
+                ("other", vec!["wrist".into(), "index_joint".into()]),
+            ]
+        );
+        assert_eq!(topic("robot_joints", "thumb"), "robot_joints/thumb");
+        assert_eq!(topics("arm").last().unwrap(), "arm/other");
+    }
+}
@@ -0,0 +1,391 @@
+//! Gamepad teleop for `publisher_gamepad`: sticks, triggers, and buttons mapped
+//! to groups of joints by a TOML mapping file.
+//!
+//! ```toml
+//! robot_id = "left_hand"
+//! deadzone = 0.1               # stick values nearer the center count as 0
+//!
+//! # Full deflection moves the index finger at 2 rad/s; it stays put when released.
+//! [[axis]]
+//! axis = "left_stick_y"
+//! joints = ["i1_MCP", "i2_PIP", "i3_DIP"]
+//! mode = "rate"                # or "position": the angle is `scale` × the value
+//! scale = 2.0
+//! min = 0.0
+//! max = 1.6
+//!
+//! # Holding A (south) opens the index finger.
+//! [[button]]
+//! button = "south"
+//! joints = ["i1_MCP", "i2_PIP", "i3_DIP"]
+//! angle = 0.0
+//! ```
+//!
+//! Bindings apply in file order, so a later one wins over an earlier one on a
+//! shared joint. This module only does the mapping; reading the device is up to
+//! the binary (gilrs, behind the `gamepad` feature).
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{JointAngles, RobotState};
+
+/// A mapping file that could not be used.
+#[derive(Debug, Error)]
+pub enum GamepadMapError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad mapping file")]
+    Toml(#[from] toml::de::Error),
+    #[error("the mapping binds no joints")]
+    Empty,
+}
+
+impl ExitStatus for GamepadMapError {
+    fn exit(&self) -> Exit {
+        match self {
+            GamepadMapError::Io { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// An analog input: sticks run -1..=1 (up and right positive), triggers 0..=1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// A digital input, named by position (`south` is A on Xbox, ✕ on PlayStation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Button {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftThumb,
+    RightThumb,
+    Select,
+    Start,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+}
+
+/// How an axis moves its joints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AxisMode {
+    /// The angle changes at `scale` rad/s × the value, and holds at rest.
+    #[default]
+    Rate,
+    /// The angle is `scale` rad × the value, and returns when released.
+    Position,
+}
+
+/// One `[[axis]]` entry.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AxisBinding {
+    pub axis: Axis,
+    pub joints: Vec<String>,
+    #[serde(default)]
+    pub mode: AxisMode,
+    /// Negative to invert the axis.
+    #[serde(default = "one")]
+    pub scale: f64,
+    #[serde(default = "min_rad")]
+    pub min: f64,
+    #[serde(default = "max_rad")]
+    pub max: f64,
+}
+
+/// One `[[button]]` entry: its joints go to `angle` while it is held.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ButtonBinding {
+    pub button: Button,
+    pub joints: Vec<String>,
+    pub angle: f64,
+}
+
+/// A whole mapping file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GamepadMap {
+    #[serde(default = "robot_id")]
+    pub robot_id: String,
+    #[serde(default = "deadzone")]
+    pub deadzone: f64,
+    #[serde(default, rename = "axis")]
+    pub axes: Vec<AxisBinding>,
+    #[serde(default, rename = "button")]
+    pub buttons: Vec<ButtonBinding>,
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+fn min_rad() -> f64 {
+    -std::f64::consts::PI
+}
+
+fn max_rad() -> f64 {
+    std::f64::consts::PI
+}
+
+fn robot_id() -> String {
+    "gamepad".to_string()
+}
+
+fn deadzone() -> f64 {
+    0.1
+}
+
+impl GamepadMap {
+    /// Read a mapping file.
+    pub fn load(path: &Path) -> Result<Self, GamepadMapError> {
+        let raw = fs::read_to_string(path).map_err(|source| GamepadMapError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// Every bound joint, in the order it first appears.
+    pub fn joint_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        let bound = self.axes.iter().flat_map(|binding| &binding.joints);
+        for name in bound.chain(self.buttons.iter().flat_map(|binding| &binding.joints)) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+}
+
+impl std::str::FromStr for GamepadMap {
+    type Err = GamepadMapError;
+
+    fn from_str(raw: &str) -> Result<Self, GamepadMapError> {
+        let map: GamepadMap = toml::from_str(raw)?;
+        if map.joint_names().is_empty() {
+            return Err(GamepadMapError::Empty);
+        }
+        Ok(map)
+    }
+}
+
+/// The joint angles a gamepad has steered to so far.
+#[derive(Debug)]
+pub struct Teleop {
+    map: GamepadMap,
+    names: Vec<String>,
+    angles: Vec<f64>,
+    velocities: Vec<f64>,
+}
+
+impl Teleop {
+    /// Every bound joint at 0 rad.
+    pub fn new(map: GamepadMap) -> Self {
+        let names = map.joint_names();
+        Teleop {
+            angles: vec![0.0; names.len()],
+            velocities: vec![0.0; names.len()],
+            names,
+            map,
+        }
+    }
+
+    /// Advance by `dt` with the inputs as they are now: `axis` gives each
+    /// axis's value, `pressed` whether a button is held.
+    pub fn update(
+        &mut self,
+        dt: Duration,
+        axis: impl Fn(Axis) -> f64,
+        pressed: impl Fn(Button) -> bool,
+    ) {
+        let before = self.angles.clone();
+        let seconds = dt.as_secs_f64();
+        for binding in &self.map.axes {
+            let value = axis(binding.axis);
+            let value = if value.abs() < self.map.deadzone {
+                0.0
+            } else {
+                value
+            };
+            for joint in &binding.joints {
+                let i = index(&self.names, joint);
+                let angle = match binding.mode {
+                    AxisMode::Rate => self.angles[i] + binding.scale * value * seconds,
+                    AxisMode::Position => binding.scale * value,
+                };
+                self.angles[i] = angle.clamp(binding.min, binding.max);
+            }
+        }
+        for binding in self.map.buttons.iter().filter(|b| pressed(b.button)) {
+            for joint in &binding.joints {
+                self.angles[index(&self.names, joint)] = binding.angle;
+            }
+        }
+        for ((velocity, angle), before) in self.velocities.iter_mut().zip(&self.angles).zip(before)
+        {
+            *velocity = if seconds > 0.0 {
+                (angle - before) / seconds
+            } else {
+                0.0
+            };
+        }
+    }
+
+    /// The current pose as state number `sequence`.
+    pub fn state(&self, sequence: u64) -> RobotState {
+        RobotState {
+            timestamp: 0,
+            robot_id: self.map.robot_id.clone(),
+            joints: self
+                .names
+                .iter()
+                .zip(&self.angles)
+                .zip(&self.velocities)
+                .map(|((name, &angle_rad), &velocity)| JointAngles {
+                    timestamp: 0,
+                    joint_name: name.clone(),
+                    angle_rad,
+                    velocity,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: Some(sequence),
+        }
+    }
+}
+
+/// `joint`'s slot; every bound joint has one.
+fn index(names: &[String], joint: &str) -> usize {
+    names
+        .iter()
+        .position(|name| name == joint)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = r#"
+        robot_id = "left_hand"
+
+        [[axis]]
+        axis = "left_stick_y"
+        joints = ["i1_MCP", "i2_PIP"]
+        scale = 2.0
+        min = 0.0
+        max = 1.0
+
+        [[axis]]
+        axis = "right_trigger"
+        joints = ["t1_TM"]
+        mode = "position"
+        scale = 0.5
+
+        [[button]]
+        button = "south"
+        joints = ["i1_MCP"]
+        angle = 0.25
+    "#;
+
+    fn angle(teleop: &Teleop, joint: &str) -> f64 {
+        let state = teleop.state(1);
+        state
+            .joints
+            .iter()
+            .find(|j| j.joint_name == joint)
+            .unwrap()
+            .angle_rad
+    }
+
+    #[test]
+    fn reads_a_mapping_file() {
+        let map: GamepadMap = MAP.parse().unwrap();
+        assert_eq!(map.robot_id, "left_hand");
+        assert_eq!(map.deadzone, 0.1);
+        assert_eq!(map.joint_names(), ["i1_MCP", "i2_PIP", "t1_TM"]);
+        assert_eq!(map.axes[0].mode, AxisMode::Rate);
+        assert_eq!(map.axes[1].min, -std::f64::consts::PI);
+
+        assert!(matches!(
+            "robot_id = \"x\"".parse::<GamepadMap>(),
+            Err(GamepadMapError::Empty)
+        ));
+        let typo = "[[axis]]\naxis = \"left_stik_y\"\njoints = [\"a\"]\n";
+        assert!(matches!(
+            typo.parse::<GamepadMap>(),
+            Err(GamepadMapError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn the_shipped_mapping_parses() {
+        let map: GamepadMap = include_str!("../gamepad_map.toml").parse().unwrap();
+        assert_eq!(map.joint_names().len(), 16);
+    }
+
+    #[test]
+    fn sticks_steer_triggers_set_and_buttons_override() {
+        let mut teleop = Teleop::new(MAP.parse().unwrap());
+        let step = Duration::from_millis(100);
+        let stick = |value: f64| {
+            move |axis| match axis {
+                Axis::LeftStickY => value,
+                Axis::RightTrigger => 0.8,
+                _ => 0.0,
+            }
+        };
+
+        teleop.update(step, stick(1.0), |_| false);
+        assert!((angle(&teleop, "i1_MCP") - 0.2).abs() < 1e-9);
+        assert!((teleop.state(1).joints[0].velocity - 2.0).abs() < 1e-9);
+        assert!((angle(&teleop, "t1_TM") - 0.4).abs() < 1e-9);
+
+        // Inside the deadzone: the rate joints hold where they are.
+        teleop.update(step, stick(0.05), |_| false);
+        assert!((angle(&teleop, "i2_PIP") - 0.2).abs() < 1e-9);
+        assert_eq!(teleop.state(1).joints[1].velocity, 0.0);
+
+        // Clamped at `max`.
+        for _ in 0..20 {
+            teleop.update(step, stick(1.0), |_| false);
+        }
+        assert_eq!(angle(&teleop, "i2_PIP"), 1.0);
+
+        teleop.update(step, stick(1.0), |button| button == Button::South);
+        assert_eq!(angle(&teleop, "i1_MCP"), 0.25);
+        assert_eq!(angle(&teleop, "i2_PIP"), 1.0);
+        assert_eq!(teleop.state(7).robot_id, "left_hand");
+    }
+}
@@ -0,0 +1,46 @@
+//! Gravity compensation for the `gravity_comp` binary: it hears each state,
+//! runs MuJoCo's inverse dynamics on the pose with every joint at rest, and
+//! republishes the state with those torques in [`JointAngles::torque`] — what
+//! the joints must exert to hold the pose against gravity.
+//!
+//! Only the filling in lives here; the MuJoCo side needs the `viewer` feature.
+//!
+//! [`JointAngles::torque`]: crate::JointAngles::torque
+
+use crate::RobotState;
+
+/// The topic compensated states go out on, next to the states on `topic`.
+pub fn topic(topic: &str) -> String {
+    format!("{topic}_gravity")
+}
+
+/// `state` with each joint's torque from `torque`; joints it has none for (not
+/// in the model) keep the torque they arrived with.
+pub fn compensated(state: &RobotState, torque: impl Fn(&str) -> Option<f64>) -> RobotState {
+    let mut compensated = state.clone();
+    for joint in &mut compensated.joints {
+        if let Some(torque) = torque(&joint.joint_name) {
+            joint.torque = torque;
+        }
+    }
+    compensated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{simulated_state, JOINT_NAME};
+
+    #[test]
+    fn torques_replace_those_of_known_joints() {
+        let state = simulated_state(30);
+        let held = compensated(&state, |name| (name == JOINT_NAME).then_some(0.75));
+        assert_eq!(held.joints[0].torque, 0.75);
+        assert_eq!(held.joints[0].angle_rad, state.joints[0].angle_rad);
+        assert_eq!(held.sequence, Some(30));
+
+        let unknown = compensated(&state, |_| None);
+        assert_eq!(unknown.joints[0].torque, state.joints[0].torque);
+        assert_eq!(topic("robot_joints"), "robot_joints_gravity");
+    }
+}
@@ -0,0 +1,307 @@
+//! A gRPC service for teams whose tooling is gRPC-first:
+//! `robot_joint_pubsub.RobotJoints` in `proto/robot_joint_pubsub.proto`,
+//! served by the `grpc_bridge` binary between gRPC clients and the pub/sub
+//! transports.
+//!
+//! - `StreamStates` streams every [`RobotState`] passed to [`GrpcService::send`]
+//!   to each caller, optionally only one robot's. Like the WebSocket feed, a
+//!   caller that falls more than the backlog behind gets a `RESOURCE_EXHAUSTED`
+//!   status and the stream ends, so one slow client cannot hold up the rest.
+//! - `SendCommands` takes a stream of `JointCommand`s and hands each over as a
+//!   `RobotState` (see [`command_state`]) on the channel from
+//!   [`GrpcService::new`], for the binary to publish.
+//!
+//! The routing below is what `tonic-build` would generate for the service, written
+//! out by hand on top of the [`proto`](crate::proto) types, so building needs no
+//! `protoc` here either.
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::body::Body;
+use tonic::codegen::{http, BoxFuture, Service};
+use tonic::server::{ClientStreamingService, Grpc, NamedService, ServerStreamingService};
+use tonic::{Request, Response, Status, Streaming};
+use tonic_prost::ProstCodec;
+
+use crate::proto::{
+    ProtoCommandSummary, ProtoJointCommand, ProtoRobotState, ProtoStreamStatesRequest,
+};
+use crate::{JointAngles, RobotState};
+
+/// The fully qualified service name, as in the `.proto` file.
+pub const SERVICE: &str = "robot_joint_pubsub.RobotJoints";
+
+/// States a `StreamStates` caller may fall behind by before its stream ends.
+pub const DEFAULT_BACKLOG: usize = 64;
+
+/// Commands queued for the binary before `SendCommands` waits for it.
+const COMMAND_QUEUE: usize = 64;
+
+/// The service; clone it freely.
+#[derive(Clone)]
+pub struct GrpcService {
+    states: broadcast::Sender<Arc<ProtoRobotState>>,
+    commands: mpsc::Sender<RobotState>,
+    /// `robot_id` for commands that name none.
+    robot_id: Arc<str>,
+    /// Sequence number of the last command handed over, across every caller.
+    sequence: Arc<AtomicU64>,
+}
+
+impl GrpcService {
+    /// A service that ends streams lagging more than `backlog` states behind,
+    /// and the receiving end of the commands clients send, with `robot_id`
+    /// filled in where a command names none.
+    pub fn new(backlog: usize, robot_id: &str) -> (Self, mpsc::Receiver<RobotState>) {
+        let (states, _) = broadcast::channel(backlog);
+        let (commands, received) = mpsc::channel(COMMAND_QUEUE);
+        let service = GrpcService {
+            states,
+            commands,
+            robot_id: robot_id.into(),
+            sequence: Arc::default(),
+        };
+        (service, received)
+    }
+
+    /// Queue `state` for every `StreamStates` caller; returns how many there are.
+    pub fn send(&self, state: &RobotState) -> usize {
+        // An error only means nobody is streaming.
+        self.states
+            .send(Arc::new(ProtoRobotState::from(state)))
+            .unwrap_or(0)
+    }
+
+    /// Number of open `StreamStates` calls.
+    pub fn clients(&self) -> usize {
+        self.states.receiver_count()
+    }
+
+    /// Answer gRPC calls on `listener` until the task is cancelled.
+    pub async fn serve(self, listener: TcpListener) -> Result<(), tonic::transport::Error> {
+        tonic::transport::Server::builder()
+            .add_service(self)
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+    }
+
+    fn stream_states(
+        &self,
+        request: ProtoStreamStatesRequest,
+    ) -> impl Stream<Item = Result<ProtoRobotState, Status>> + Send + 'static {
+        let robot_id = request.robot_id;
+        let mut ended = false;
+        tracing::info!(%robot_id, clients = self.clients() + 1, "state stream opened");
+        BroadcastStream::new(self.states.subscribe())
+            .filter(move |state| {
+                // Lag errors always pass, so the caller learns why its stream ends.
+                robot_id.is_empty() || !state.as_ref().is_ok_and(|s| s.robot_id != robot_id)
+            })
+            .map(|state| match state {
+                Ok(state) => Ok(ProtoRobotState::clone(&state)),
+                Err(lagged) => {
+                    tracing::warn!(error = %lagged, "ending slow state stream");
+                    Err(Status::resource_exhausted(format!(
+                        "fell behind the state stream ({lagged})"
+                    )))
+                }
+            })
+            // Status errors end a gRPC stream; stop here so the client sees this one.
+            .map_while(move |state| {
+                (!std::mem::replace(&mut ended, state.is_err())).then_some(state)
+            })
+    }
+
+    async fn send_commands(
+        &self,
+        mut commands: Streaming<ProtoJointCommand>,
+    ) -> Result<ProtoCommandSummary, Status> {
+        let mut accepted = 0;
+        while let Some(command) = commands.message().await? {
+            let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+            let state = command_state(command, &self.robot_id, sequence);
+            self.commands
+                .send(state)
+                .await
+                .map_err(|_| Status::unavailable("the bridge is shutting down"))?;
+            accepted += 1;
+        }
+        tracing::debug!(accepted, "command stream closed");
+        Ok(ProtoCommandSummary { accepted })
+    }
+}
+
+/// The state a command stands for: one joint per target, in name order,
+/// numbered `sequence`, and from `robot_id` if the command names no robot.
+pub fn command_state(command: ProtoJointCommand, robot_id: &str, sequence: u64) -> RobotState {
+    let joints = command
+        .angles_rad
+        .into_iter()
+        .map(|(joint_name, angle_rad)| JointAngles {
+            timestamp: 0,
+            joint_name,
+            angle_rad,
+            velocity: 0.0,
+            torque: 0.0,
+            qpos: Vec::new(),
+        })
+        .collect();
+    RobotState {
+        timestamp: 0,
+        robot_id: if command.robot_id.is_empty() {
+            robot_id.to_string()
+        } else {
+            command.robot_id
+        },
+        joints,
+        sent_at_us: None,
+        sequence: Some(sequence),
+    }
+}
+
+struct StreamStates(GrpcService);
+
+impl ServerStreamingService<ProtoStreamStatesRequest> for StreamStates {
+    type Response = ProtoRobotState;
+    type ResponseStream =
+        std::pin::Pin<Box<dyn Stream<Item = Result<ProtoRobotState, Status>> + Send + 'static>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<ProtoStreamStatesRequest>) -> Self::Future {
+        let stream = self.0.stream_states(request.into_inner());
+        Box::pin(async move { Ok(Response::new(Box::pin(stream) as Self::ResponseStream)) })
+    }
+}
+
+struct SendCommands(GrpcService);
+
+impl ClientStreamingService<ProtoJointCommand> for SendCommands {
+    type Response = ProtoCommandSummary;
+    type Future = BoxFuture<Response<ProtoCommandSummary>, Status>;
+
+    fn call(&mut self, request: Request<Streaming<ProtoJointCommand>>) -> Self::Future {
+        let service = self.0.clone();
+        Box::pin(async move {
+            let summary = service.send_commands(request.into_inner()).await?;
+            Ok(Response::new(summary))
+        })
+    }
+}
+
+impl Service<http::Request<Body>> for GrpcService {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let service = self.clone();
+        match request.uri().path() {
+            "/robot_joint_pubsub.RobotJoints/StreamStates" => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::default());
+                Ok(grpc.server_streaming(StreamStates(service), request).await)
+            }),
+            "/robot_joint_pubsub.RobotJoints/SendCommands" => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::default());
+                Ok(grpc.client_streaming(SendCommands(service), request).await)
+            }),
+            _ => Box::pin(async move { Ok(Status::unimplemented("no such method").into_http()) }),
+        }
+    }
+}
+
+impl NamedService for GrpcService {
+    const NAME: &'static str = SERVICE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+    use tonic::client::Grpc as GrpcClient;
+    use tonic::codegen::http::uri::PathAndQuery;
+    use tonic::transport::Channel;
+
+    #[tokio::test]
+    async fn streams_states_and_hands_over_commands() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (service, mut commands) = GrpcService::new(DEFAULT_BACKLOG, "bridge");
+        tokio::spawn(service.clone().serve(listener));
+        let channel = Channel::from_shared(endpoint)
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = GrpcClient::new(channel);
+
+        client.ready().await.unwrap();
+        let mut states = client
+            .server_streaming(
+                Request::new(ProtoStreamStatesRequest {
+                    robot_id: "left_hand".to_string(),
+                }),
+                PathAndQuery::from_static("/robot_joint_pubsub.RobotJoints/StreamStates"),
+                ProstCodec::<ProtoStreamStatesRequest, ProtoRobotState>::default(),
+            )
+            .await
+            .unwrap()
+            .into_inner();
+        while service.clients() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let mut other = simulated_state(1);
+        other.robot_id = "right_hand".to_string();
+        service.send(&other);
+        let mut wanted = simulated_state(2);
+        wanted.robot_id = "left_hand".to_string();
+        service.send(&wanted);
+        let received = states.message().await.unwrap().unwrap();
+        assert_eq!(RobotState::from(received), wanted);
+
+        client.ready().await.unwrap();
+        let sent = tokio_stream::iter([
+            ProtoJointCommand {
+                robot_id: String::new(),
+                angles_rad: [("i2_PIP".to_string(), 0.5), ("i1_MCP".to_string(), 0.25)].into(),
+            },
+            ProtoJointCommand {
+                robot_id: "left_hand".to_string(),
+                angles_rad: [("t1_TM".to_string(), -0.1)].into(),
+            },
+        ]);
+        let summary = client
+            .client_streaming(
+                Request::new(sent),
+                PathAndQuery::from_static("/robot_joint_pubsub.RobotJoints/SendCommands"),
+                ProstCodec::<ProtoJointCommand, ProtoCommandSummary>::default(),
+            )
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(summary.accepted, 2);
+        let first = commands.recv().await.unwrap();
+        assert_eq!(
+            (first.sequence, first.robot_id.as_str()),
+            (Some(1), "bridge")
+        );
+        let names: Vec<_> = first.joints.iter().map(|j| j.joint_name.as_str()).collect();
+        assert_eq!(names, ["i1_MCP", "i2_PIP"]);
+        let second = commands.recv().await.unwrap();
+        assert_eq!(
+            (second.sequence, second.robot_id.as_str()),
+            (Some(2), "left_hand")
+        );
+        assert_eq!(second.joints[0].angle_rad, -0.1);
+    }
+}
@@ -0,0 +1,251 @@
+//! A rolling window of received states, for the `monitor_gui` binary's plots.
+//!
+//! [`History`] keeps every state received in the last `window`, stamped with
+//! its receive time, and hands out one joint's angle or velocity over that
+//! window as plot points. [`History::write_csv`] exports the window, one row
+//! per state:
+//!
+//! ```text
+//! time,i1_MCP,i1_MCP_velocity,m1_MCP,m1_MCP_velocity
+//! 0.000,0.1,0,0.2,0
+//! 0.010,0.11,1,0.2,0
+//! ```
+//!
+//! `time` is seconds since the first state the history saw; a joint a state
+//! does not carry leaves its columns empty.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::RobotState;
+
+/// An export that could not be written.
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("writing {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("writing CSV")]
+    Csv(#[from] csv::Error),
+}
+
+impl ExitStatus for HistoryError {
+    fn exit(&self) -> Exit {
+        match self {
+            HistoryError::Write { .. } => Exit::Io,
+            HistoryError::Csv(e) if e.is_io_error() => Exit::Io,
+            HistoryError::Csv(_) => Exit::Data,
+        }
+    }
+}
+
+/// Which of a joint's numbers to plot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quantity {
+    /// `angle_rad`, in radians.
+    #[default]
+    Angle,
+    /// `velocity`, in rad/s.
+    Velocity,
+}
+
+/// One received state, `time` seconds after the history's first.
+#[derive(Debug, Clone)]
+struct Row {
+    time: f64,
+    state: RobotState,
+}
+
+/// The states received in the last `window`.
+#[derive(Debug)]
+pub struct History {
+    window: Duration,
+    origin: Option<Instant>,
+    rows: VecDeque<Row>,
+    /// Every joint seen so far, kept when its rows leave the window.
+    joints: BTreeSet<String>,
+}
+
+impl History {
+    pub fn new(window: Duration) -> Self {
+        History {
+            window,
+            origin: None,
+            rows: VecDeque::new(),
+            joints: BTreeSet::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Keep `window` from now on, dropping older rows at the next [`History::push`].
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Add `state`, received at `now`, and drop what has left the window.
+    pub fn push(&mut self, state: RobotState, now: Instant) {
+        let origin = *self.origin.get_or_insert(now);
+        let time = now.saturating_duration_since(origin).as_secs_f64();
+        for joint in &state.joints {
+            if !self.joints.contains(&joint.joint_name) {
+                self.joints.insert(joint.joint_name.clone());
+            }
+        }
+        self.rows.push_back(Row { time, state });
+        let oldest = time - self.window.as_secs_f64();
+        while self.rows.front().is_some_and(|row| row.time < oldest) {
+            self.rows.pop_front();
+        }
+    }
+
+    /// Forget every row and joint; the next state starts the clock again.
+    pub fn clear(&mut self) {
+        self.origin = None;
+        self.rows.clear();
+        self.joints.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Every joint seen since the last [`History::clear`], alphabetically.
+    pub fn joint_names(&self) -> impl Iterator<Item = &str> {
+        self.joints.iter().map(String::as_str)
+    }
+
+    /// `[time, value]` points of `joint`'s `quantity`, oldest first, for the
+    /// states in the window that carry it.
+    pub fn points<'a>(
+        &'a self,
+        joint: &'a str,
+        quantity: Quantity,
+    ) -> impl Iterator<Item = [f64; 2]> + 'a {
+        self.rows.iter().filter_map(move |row| {
+            let angles = row.state.joints.iter().find(|j| j.joint_name == joint)?;
+            let value = match quantity {
+                Quantity::Angle => angles.angle_rad,
+                Quantity::Velocity => angles.velocity,
+            };
+            Some([row.time, value])
+        })
+    }
+
+    /// Write `joints`' angles and velocities over the window as CSV (see the
+    /// module docs).
+    pub fn write_csv<W: io::Write>(&self, writer: W, joints: &[&str]) -> Result<(), HistoryError> {
+        let mut csv = csv::Writer::from_writer(writer);
+        let mut header = vec!["time".to_string()];
+        for joint in joints {
+            header.push(joint.to_string());
+            header.push(format!("{joint}_velocity"));
+        }
+        csv.write_record(&header)?;
+        for row in &self.rows {
+            let mut record = vec![format!("{:.3}", row.time)];
+            for joint in joints {
+                match row.state.joints.iter().find(|j| j.joint_name == *joint) {
+                    Some(angles) => {
+                        record.push(angles.angle_rad.to_string());
+                        record.push(angles.velocity.to_string());
+                    }
+                    None => record.extend([String::new(), String::new()]),
+                }
+            }
+            csv.write_record(&record)?;
+        }
+        csv.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// [`History::write_csv`] to a new file at `path`.
+    pub fn export(&self, path: &Path, joints: &[&str]) -> Result<(), HistoryError> {
+        let file = File::create(path).map_err(|source| HistoryError::Write {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.write_csv(file, joints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JointAngles;
+
+    fn state(timestamp: u64, joints: &[(&str, f64, f64)]) -> RobotState {
+        RobotState {
+            timestamp,
+            robot_id: "hand".into(),
+            joints: joints
+                .iter()
+                .map(|&(name, angle_rad, velocity)| JointAngles {
+                    timestamp,
+                    joint_name: name.into(),
+                    angle_rad,
+                    velocity,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn keeps_only_the_window() {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_millis(950));
+        for i in 0..30u64 {
+            let at = start + Duration::from_millis(100 * i);
+            history.push(state(i, &[("a", i as f64, 1.0)]), at);
+        }
+        // 2.0 s through 2.9 s.
+        assert_eq!(history.len(), 10);
+        let angles: Vec<_> = history.points("a", Quantity::Angle).collect();
+        assert_eq!(angles.first(), Some(&[2.0, 20.0]));
+        assert_eq!(angles.last().map(|p| p[1]), Some(29.0));
+        assert!(history.points("a", Quantity::Velocity).all(|p| p[1] == 1.0));
+        assert_eq!(history.points("b", Quantity::Angle).count(), 0);
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.joint_names().count(), 0);
+    }
+
+    #[test]
+    fn exports_one_row_per_state() {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_secs(10));
+        history.push(state(1, &[("a", 0.5, 0.0), ("b", 1.0, 2.0)]), start);
+        history.push(
+            state(2, &[("a", 0.75, 1.5)]),
+            start + Duration::from_millis(250),
+        );
+        assert_eq!(history.joint_names().collect::<Vec<_>>(), ["a", "b"]);
+
+        let mut out = Vec::new();
+        history.write_csv(&mut out, &["a", "b"]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "time,a,a_velocity,b,b_velocity\n0.000,0.5,0,1,2\n0.250,0.75,1.5,,\n"
+        );
+    }
+}
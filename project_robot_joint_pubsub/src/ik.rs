@@ -0,0 +1,193 @@
+//! Position inverse kinematics for the GUI publisher's IK tab: find joint angles
+//! that put a point on the robot (a fingertip or the palm) at a target.
+//!
+//! [`solve`] is damped least squares on the point's position Jacobian. It knows
+//! nothing of MuJoCo: the caller supplies the point's position and Jacobian for
+//! any joint angles, so the solver is the same for a test's two-link arm and a
+//! twenty-joint hand.
+
+/// How hard [`solve`] tries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IkOptions {
+    pub max_iterations: usize,
+    /// Close enough, in meters.
+    pub tolerance: f64,
+    /// Damping λ: larger is steadier near singular poses but converges slower.
+    pub damping: f64,
+    /// Largest change of any joint per iteration, in radians.
+    pub max_step: f64,
+}
+
+impl Default for IkOptions {
+    fn default() -> Self {
+        IkOptions {
+            max_iterations: 100,
+            tolerance: 1e-4,
+            damping: 0.01,
+            max_step: 0.2,
+        }
+    }
+}
+
+/// Where [`solve`] ended up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IkSolution {
+    pub angles: Vec<f64>,
+    /// Distance left to the target, in meters.
+    pub error: f64,
+    pub iterations: usize,
+    /// Whether `error` is within the tolerance; if not, `angles` are the
+    /// closest found (the target may be out of reach).
+    pub converged: bool,
+}
+
+/// Joint angles within `limits` (one `(min, max)` per joint) that bring a point
+/// to `target`, starting from `start`.
+///
+/// `kinematics` returns, for given angles, the point's position and its 3×n
+/// position Jacobian, row-major (`jacobian[row * n + joint]`).
+pub fn solve(
+    target: [f64; 3],
+    start: &[f64],
+    limits: &[(f64, f64)],
+    options: &IkOptions,
+    mut kinematics: impl FnMut(&[f64]) -> ([f64; 3], Vec<f64>),
+) -> IkSolution {
+    let mut angles = start.to_vec();
+    let mut iterations = 0;
+    loop {
+        let (position, jacobian) = kinematics(&angles);
+        let error = [
+            target[0] - position[0],
+            target[1] - position[1],
+            target[2] - position[2],
+        ];
+        let distance = norm(error);
+        if distance <= options.tolerance || iterations == options.max_iterations {
+            return IkSolution {
+                angles,
+                error: distance,
+                iterations,
+                converged: distance <= options.tolerance,
+            };
+        }
+        let mut step = dls_step(&jacobian, error, options.damping);
+        let largest = step.iter().fold(0.0_f64, |max, dq| max.max(dq.abs()));
+        if largest > options.max_step {
+            step.iter_mut()
+                .for_each(|dq| *dq *= options.max_step / largest);
+        }
+        for ((angle, dq), &(min, max)) in angles.iter_mut().zip(step).zip(limits) {
+            *angle = (*angle + dq).clamp(min, max);
+        }
+        iterations += 1;
+    }
+}
+
+/// One damped least squares step, `Jᵀ (J Jᵀ + λ² I)⁻¹ e`, for a 3×n `jacobian`.
+pub fn dls_step(jacobian: &[f64], error: [f64; 3], damping: f64) -> Vec<f64> {
+    let n = jacobian.len() / 3;
+    let row = |r: usize| &jacobian[r * n..(r + 1) * n];
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+    let mut a = [[0.0; 3]; 3];
+    for (r, a_row) in a.iter_mut().enumerate() {
+        for (c, entry) in a_row.iter_mut().enumerate() {
+            *entry = dot(row(r), row(c)) + if r == c { damping * damping } else { 0.0 };
+        }
+    }
+    let y = solve3(a, error);
+    (0..n)
+        .map(|j| (0..3).map(|r| jacobian[r * n + j] * y[r]).sum())
+        .collect()
+}
+
+/// `x` with `a x = b`, by Cramer's rule; 0 if `a` is singular (no damping and
+/// a point no joint moves).
+fn solve3(a: [[f64; 3]; 3], b: [f64; 3]) -> [f64; 3] {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(a);
+    if d.abs() < f64::EPSILON {
+        return [0.0; 3];
+    }
+    let mut x = [0.0; 3];
+    for (c, x) in x.iter_mut().enumerate() {
+        let mut m = a;
+        for r in 0..3 {
+            m[r][c] = b[r];
+        }
+        *x = det(m) / d;
+    }
+    x
+}
+
+fn norm(v: [f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A planar arm in the xy plane: two links of 0.5 m on z-axis hinges.
+    fn two_link(q: &[f64]) -> ([f64; 3], Vec<f64>) {
+        let (a, b) = (q[0], q[0] + q[1]);
+        let position = [
+            0.5 * a.cos() + 0.5 * b.cos(),
+            0.5 * a.sin() + 0.5 * b.sin(),
+            0.0,
+        ];
+        #[rustfmt::skip]
+        let jacobian = vec![
+            -0.5 * a.sin() - 0.5 * b.sin(), -0.5 * b.sin(),
+            0.5 * a.cos() + 0.5 * b.cos(), 0.5 * b.cos(),
+            0.0, 0.0,
+        ];
+        (position, jacobian)
+    }
+
+    const LIMITS: [(f64, f64); 2] = [(-3.0, 3.0), (-3.0, 3.0)];
+
+    #[test]
+    fn reaches_a_target_within_reach() {
+        let target = [0.3, 0.6, 0.0];
+        let solution = solve(
+            target,
+            &[0.1, 0.5],
+            &LIMITS,
+            &IkOptions::default(),
+            two_link,
+        );
+        assert!(solution.converged, "{solution:?}");
+        let (position, _) = two_link(&solution.angles);
+        assert!(norm([position[0] - 0.3, position[1] - 0.6, 0.0]) < 1e-4);
+    }
+
+    #[test]
+    fn stops_closest_to_a_target_out_of_reach_within_limits() {
+        let options = IkOptions {
+            max_iterations: 200,
+            ..IkOptions::default()
+        };
+        // Two meters away; the arm reaches one. Stretched out towards it is 1 m short
+        // (damping keeps it from straightening all the way at the singular pose).
+        let solution = solve([2.0, 0.0, 0.0], &[0.3, 0.3], &LIMITS, &options, two_link);
+        assert!(!solution.converged);
+        assert_eq!(solution.iterations, 200);
+        assert!((solution.error - 1.0).abs() < 1e-2, "{solution:?}");
+
+        // The elbow may not bend past 0.1 rad, so the arm cannot fold back to the base.
+        let limits = [(-3.0, 3.0), (0.1, 3.0)];
+        let solution = solve([0.0, 0.0, 0.0], &[0.0, 1.0], &limits, &options, two_link);
+        assert!(!solution.converged);
+        assert!(solution.angles[1] >= 0.1);
+    }
+
+    #[test]
+    fn step_is_zero_for_a_point_no_joint_moves() {
+        assert_eq!(dls_step(&[0.0; 6], [1.0, 0.0, 0.0], 0.0), [0.0, 0.0]);
+    }
+}
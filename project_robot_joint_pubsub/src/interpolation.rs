@@ -0,0 +1,222 @@
+//! Smooth joint motion between received states, for the MuJoCo subscriber.
+//!
+//! States arrive at the publish rate but the viewer renders far more often, so
+//! drawing only the latest state makes a slowly published hand jump from pose to
+//! pose. An [`Interpolator`] instead plays the motion between the last two states
+//! over the time it took the latest one to arrive, one publish interval behind
+//! the stream, so every frame gets an in-between pose.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+use serde::Deserialize;
+
+use crate::RobotState;
+
+/// How the subscriber fills in poses between received states.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Interpolation {
+    /// Show each state as it arrives.
+    #[default]
+    None,
+    /// Straight lines between states.
+    Linear,
+    /// Cubic Hermite curves whose slope at each state is the slope from the state
+    /// before it, so consecutive segments meet without a kink.
+    Cubic,
+}
+
+/// One received state's joint angles.
+#[derive(Debug)]
+struct Sample {
+    received: Instant,
+    angles: HashMap<String, f64>,
+}
+
+/// The last few states received, sampled at render time.
+#[derive(Debug)]
+pub struct Interpolator {
+    mode: Interpolation,
+    /// Oldest first; three for [`Interpolation::Cubic`], else two.
+    history: VecDeque<Sample>,
+}
+
+impl Interpolator {
+    pub fn new(mode: Interpolation) -> Self {
+        Interpolator {
+            mode,
+            history: VecDeque::with_capacity(3),
+        }
+    }
+
+    /// Add `state`, received at `received`.
+    pub fn push(&mut self, state: &RobotState, received: Instant) {
+        let keep = if self.mode == Interpolation::Cubic {
+            3
+        } else {
+            2
+        };
+        if self.history.len() == keep {
+            self.history.pop_front();
+        }
+        self.history.push_back(Sample {
+            received,
+            angles: state
+                .joints
+                .iter()
+                .map(|joint| (joint.joint_name.clone(), joint.angle_rad))
+                .collect(),
+        });
+    }
+
+    /// Each joint of the latest state with the angle to show at `now`.
+    ///
+    /// Joints missing from earlier states hold the latest angle.
+    pub fn angles_at(&self, now: Instant) -> impl Iterator<Item = (&str, f64)> + '_ {
+        let n = self.history.len();
+        let latest = self.history.back();
+        let previous = n.checked_sub(2).map(|i| &self.history[i]);
+        let before = n.checked_sub(3).map(|i| &self.history[i]);
+        let (span, alpha) = match (previous, latest) {
+            (Some(previous), Some(latest)) if self.mode != Interpolation::None => {
+                let span = (latest.received - previous.received).as_secs_f64();
+                let played = now.saturating_duration_since(latest.received).as_secs_f64();
+                // A state that arrived together with the previous one is shown as is.
+                let alpha = if span > 0.0 {
+                    (played / span).min(1.0)
+                } else {
+                    1.0
+                };
+                (span, alpha)
+            }
+            _ => (0.0, 1.0),
+        };
+
+        latest
+            .into_iter()
+            .flat_map(|sample| &sample.angles)
+            .map(move |(name, &p1)| {
+                let Some(previous) = previous.filter(|_| alpha < 1.0) else {
+                    return (name.as_str(), p1);
+                };
+                let p0 = previous.angles.get(name).copied().unwrap_or(p1);
+                let angle = match self.mode {
+                    Interpolation::Cubic => {
+                        // Slopes scaled to the segment: the one into `p0` (or the
+                        // segment's own without an earlier state) and the one into `p1`.
+                        let m0 = match before {
+                            Some(before) if previous.received > before.received => {
+                                let pm = before.angles.get(name).copied().unwrap_or(p0);
+                                let gap = (previous.received - before.received).as_secs_f64();
+                                (p0 - pm) * span / gap
+                            }
+                            _ => p1 - p0,
+                        };
+                        let m1 = p1 - p0;
+                        hermite(p0, m0, p1, m1, alpha)
+                    }
+                    _ => p0 + (p1 - p0) * alpha,
+                };
+                (name.as_str(), angle)
+            })
+    }
+}
+
+/// The cubic Hermite curve from `p0` to `p1` with end slopes `m0` and `m1`
+/// (per whole segment), at `t` in `0..=1`.
+fn hermite(p0: f64, m0: f64, p1: f64, m1: f64, t: f64) -> f64 {
+    let (t2, t3) = (t * t, t * t * t);
+    (2.0 * t3 - 3.0 * t2 + 1.0) * p0
+        + (t3 - 2.0 * t2 + t) * m0
+        + (-2.0 * t3 + 3.0 * t2) * p1
+        + (t3 - t2) * m1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::JointAngles;
+
+    fn state(timestamp: u64, angles: &[(&str, f64)]) -> RobotState {
+        RobotState {
+            timestamp,
+            robot_id: "hand".to_string(),
+            joints: angles
+                .iter()
+                .map(|&(joint_name, angle_rad)| JointAngles {
+                    timestamp,
+                    joint_name: joint_name.to_string(),
+                    angle_rad,
+                    velocity: 0.0,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: None,
+        }
+    }
+
+    fn angle(interpolator: &Interpolator, joint: &str, now: Instant) -> f64 {
+        let (_, angle) = interpolator
+            .angles_at(now)
+            .find(|(name, _)| *name == joint)
+            .unwrap();
+        angle
+    }
+
+    #[test]
+    fn linear_plays_the_last_segment_over_the_last_interval() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let mut interpolator = Interpolator::new(Interpolation::Linear);
+        assert_eq!(interpolator.angles_at(start).count(), 0);
+
+        interpolator.push(&state(1, &[("a", 0.0)]), ms(0));
+        assert_eq!(angle(&interpolator, "a", ms(50)), 0.0);
+
+        interpolator.push(&state(2, &[("a", 1.0), ("b", 2.0)]), ms(100));
+        assert_eq!(angle(&interpolator, "a", ms(100)), 0.0);
+        assert!((angle(&interpolator, "a", ms(125)) - 0.25).abs() < 1e-9);
+        assert_eq!(angle(&interpolator, "a", ms(500)), 1.0);
+        // New in the latest state: nothing to start from.
+        assert_eq!(angle(&interpolator, "b", ms(125)), 2.0);
+
+        let snap = {
+            let mut snap = Interpolator::new(Interpolation::None);
+            snap.push(&state(1, &[("a", 0.0)]), ms(0));
+            snap.push(&state(2, &[("a", 1.0)]), ms(100));
+            snap
+        };
+        assert_eq!(angle(&snap, "a", ms(125)), 1.0);
+    }
+
+    #[test]
+    fn cubic_segments_meet_with_the_same_slope() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let mut interpolator = Interpolator::new(Interpolation::Cubic);
+        interpolator.push(&state(1, &[("a", 0.0)]), ms(0));
+        interpolator.push(&state(2, &[("a", 1.0)]), ms(100));
+        // With one segment the curve runs at the segment's slope: a straight line.
+        assert!((angle(&interpolator, "a", ms(150)) - 0.5).abs() < 1e-9);
+        let step = Duration::from_micros(10);
+        let end_slope =
+            angle(&interpolator, "a", ms(200)) - angle(&interpolator, "a", ms(200) - step);
+
+        interpolator.push(&state(3, &[("a", 1.0)]), ms(200));
+        let start_slope =
+            angle(&interpolator, "a", ms(200) + step) - angle(&interpolator, "a", ms(200));
+        assert_eq!(angle(&interpolator, "a", ms(200)), 1.0);
+        assert!(
+            (start_slope - end_slope).abs() < 1e-6,
+            "{start_slope} {end_slope}"
+        );
+        // Eases into the hold instead of overshooting it much.
+        assert!(angle(&interpolator, "a", ms(250)) < 1.2);
+        assert_eq!(angle(&interpolator, "a", ms(300)), 1.0);
+    }
+}
@@ -0,0 +1,105 @@
+//! A selection of joints by name, so a subscriber applies and logs only its
+//! share of the hand when several publishers each own a part of it
+//! (`subscriber --joints i1_MCP,t2_CMC --joint-prefix m`).
+//!
+//! A joint is selected if it is named exactly, or its name starts with one of
+//! the prefixes; with neither, every joint is. Joints left out of a state are
+//! left where they are, as if the state had never named them.
+
+use crate::RobotState;
+
+/// The joints a subscriber takes from each state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JointFilter {
+    names: Vec<String>,
+    prefixes: Vec<String>,
+}
+
+impl JointFilter {
+    /// Select the joints named `names` and those starting with one of `prefixes`.
+    pub fn new(names: Vec<String>, prefixes: Vec<String>) -> Self {
+        JointFilter { names, prefixes }
+    }
+
+    /// Whether this selects every joint.
+    pub fn is_all(&self) -> bool {
+        self.names.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Whether `joint_name` is selected.
+    pub fn selects(&self, joint_name: &str) -> bool {
+        self.is_all()
+            || self.names.iter().any(|name| name == joint_name)
+            || self
+                .prefixes
+                .iter()
+                .any(|prefix| joint_name.starts_with(prefix.as_str()))
+    }
+
+    /// `state` with only the selected joints, or `None` if it has none of them.
+    pub fn apply(&self, mut state: RobotState) -> Option<RobotState> {
+        if self.is_all() {
+            return Some(state);
+        }
+        state.joints.retain(|joint| self.selects(&joint.joint_name));
+        (!state.joints.is_empty()).then_some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JointAngles;
+
+    fn state(joints: &[&str]) -> RobotState {
+        RobotState {
+            timestamp: 3,
+            robot_id: "left_hand".into(),
+            joints: joints
+                .iter()
+                .map(|&name| JointAngles {
+                    timestamp: 3,
+                    joint_name: name.into(),
+                    angle_rad: 0.5,
+                    velocity: 0.0,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: Some(1),
+        }
+    }
+
+    fn names(state: &RobotState) -> Vec<&str> {
+        state
+            .joints
+            .iter()
+            .map(|joint| joint.joint_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn names_and_prefixes_select_together() {
+        let filter = JointFilter::new(vec!["t2_CMC".into()], vec!["i".into()]);
+        let kept = filter
+            .apply(state(&["t1_CMC", "t2_CMC", "i1_MCP", "i2_PIP", "m1_MCP"]))
+            .unwrap();
+        assert_eq!(names(&kept), ["t2_CMC", "i1_MCP", "i2_PIP"]);
+        assert_eq!(kept.sequence, Some(1));
+    }
+
+    #[test]
+    fn a_state_without_selected_joints_is_dropped() {
+        let filter = JointFilter::new(vec!["i1_MCP".into()], Vec::new());
+        assert_eq!(filter.apply(state(&["t1_CMC", "m1_MCP"])), None);
+    }
+
+    #[test]
+    fn no_selection_keeps_everything() {
+        let filter = JointFilter::default();
+        assert!(filter.is_all());
+        let all = state(&["t1_CMC", "i1_MCP"]);
+        assert_eq!(filter.apply(all.clone()), Some(all));
+    }
+}
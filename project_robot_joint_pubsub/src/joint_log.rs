@@ -0,0 +1,218 @@
+//! A CSV log of every received joint, for offline analysis
+//! (`subscriber --log-file`), one row per joint per state:
+//!
+//! ```text
+//! received_at,robot_id,timestamp,joint,angle_rad,velocity,torque,sent_at_us,sequence
+//! 1760540439.655719,pro_hand,1760540439654981000,i1_MCP,0.1,0,0,1760540439655012,1
+//! 1760540439.655719,pro_hand,1760540439654981000,i2_PIP,0.2,0,0,1760540439655012,1
+//! ```
+//!
+//! `received_at` is the wall-clock receive time in seconds since the Unix
+//! epoch (`pd.to_datetime(df.received_at, unit="s")` in pandas), `timestamp`
+//! the sample time in nanoseconds since it (`unit="ns"`), and `sent_at_us` and
+//! `sequence` are empty when the publisher did not set them. Logging
+//! to an existing file appends to it under the header already there.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::RobotState;
+
+/// Column names, in order.
+pub const HEADER: [&str; 9] = [
+    "received_at",
+    "robot_id",
+    "timestamp",
+    "joint",
+    "angle_rad",
+    "velocity",
+    "torque",
+    "sent_at_us",
+    "sequence",
+];
+
+/// Longest rows wait in the buffer, by receive time, before being written out.
+const FLUSH_EVERY: Duration = Duration::from_secs(1);
+
+/// A log that could not be opened or written.
+#[derive(Debug, Error)]
+pub enum JointLogError {
+    #[error("opening {}", path.display())]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("writing the joint log")]
+    Csv(#[from] csv::Error),
+}
+
+impl ExitStatus for JointLogError {
+    fn exit(&self) -> Exit {
+        match self {
+            JointLogError::Open { .. } => Exit::Io,
+            JointLogError::Csv(e) if e.is_io_error() => Exit::Io,
+            JointLogError::Csv(_) => Exit::Data,
+        }
+    }
+}
+
+/// Writes received states as CSV rows; buffered, so [`JointLog::flush`] (or
+/// drop) it when done.
+pub struct JointLog<W: io::Write> {
+    csv: csv::Writer<W>,
+    /// Receive time of the oldest row not yet flushed.
+    unflushed_since: Option<SystemTime>,
+}
+
+impl JointLog<File> {
+    /// Append to the file at `path`, creating it (with a header) if it is
+    /// missing or empty.
+    pub fn append(path: &Path) -> Result<Self, JointLogError> {
+        let open = |path: &Path| -> io::Result<(File, bool)> {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            let empty = file.metadata()?.len() == 0;
+            Ok((file, empty))
+        };
+        let (file, empty) = open(path).map_err(|source| JointLogError::Open {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        JointLog::new(file, empty)
+    }
+}
+
+impl<W: io::Write> JointLog<W> {
+    /// Log to `writer`, starting with the header if `header`.
+    pub fn new(writer: W, header: bool) -> Result<Self, JointLogError> {
+        let mut csv = csv::Writer::from_writer(writer);
+        if header {
+            csv.write_record(HEADER)?;
+        }
+        Ok(JointLog {
+            csv,
+            unflushed_since: None,
+        })
+    }
+
+    /// One row for each of `state`'s joints, received at `received_at`.
+    ///
+    /// Flushes once the oldest buffered row is [`FLUSH_EVERY`] old, so a
+    /// killed subscriber loses at most about a second.
+    pub fn record(
+        &mut self,
+        state: &RobotState,
+        received_at: SystemTime,
+    ) -> Result<(), JointLogError> {
+        let seconds = received_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let received = format!("{seconds:.6}");
+        let timestamp = state.timestamp.to_string();
+        let sent_at = state.sent_at_us.map_or(String::new(), |us| us.to_string());
+        let sequence = state.sequence.map_or(String::new(), |n| n.to_string());
+        for joint in &state.joints {
+            self.csv.write_record([
+                &received,
+                &state.robot_id,
+                &timestamp,
+                &joint.joint_name,
+                &joint.angle_rad.to_string(),
+                &joint.velocity.to_string(),
+                &joint.torque.to_string(),
+                &sent_at,
+                &sequence,
+            ])?;
+        }
+        let since = *self.unflushed_since.get_or_insert(received_at);
+        if received_at
+            .duration_since(since)
+            .is_ok_and(|age| age >= FLUSH_EVERY)
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write out every buffered row.
+    pub fn flush(&mut self) -> Result<(), JointLogError> {
+        self.csv.flush().map_err(csv::Error::from)?;
+        self.unflushed_since = None;
+        Ok(())
+    }
+
+    /// Flush and hand back the writer.
+    pub fn into_inner(self) -> Result<W, JointLogError> {
+        self.csv
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JointAngles;
+
+    fn state(sequence: u64, sent_at_us: Option<u64>) -> RobotState {
+        let timestamp = 1_760_540_439_650_000_000 + sequence;
+        let joint = |name: &str, angle_rad| JointAngles {
+            timestamp,
+            joint_name: name.into(),
+            angle_rad,
+            velocity: 0.5,
+            torque: 0.0,
+            qpos: Vec::new(),
+        };
+        RobotState {
+            timestamp,
+            robot_id: "hand".into(),
+            joints: vec![joint("i1_MCP", 0.1), joint("i2_PIP", -0.25)],
+            sent_at_us,
+            sequence: Some(sequence),
+        }
+    }
+
+    #[test]
+    fn writes_a_row_per_joint() {
+        let at = UNIX_EPOCH + Duration::from_micros(1_760_540_439_655_719);
+        let mut log = JointLog::new(Vec::new(), true).unwrap();
+        log.record(&state(1, Some(1_760_540_439_655_012)), at)
+            .unwrap();
+        log.record(&state(2, None), at + Duration::from_millis(10))
+            .unwrap();
+        let text = String::from_utf8(log.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            text,
+            "received_at,robot_id,timestamp,joint,angle_rad,velocity,torque,sent_at_us,sequence\n\
+             1760540439.655719,hand,1760540439650000001,i1_MCP,0.1,0.5,0,1760540439655012,1\n\
+             1760540439.655719,hand,1760540439650000001,i2_PIP,-0.25,0.5,0,1760540439655012,1\n\
+             1760540439.665719,hand,1760540439650000002,i1_MCP,0.1,0.5,0,,2\n\
+             1760540439.665719,hand,1760540439650000002,i2_PIP,-0.25,0.5,0,,2\n"
+        );
+    }
+
+    #[test]
+    fn appends_under_one_header() {
+        let path = std::env::temp_dir().join(format!("joint_log_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        for sequence in [1, 2] {
+            let mut log = JointLog::append(&path).unwrap();
+            log.record(&state(sequence, None), SystemTime::now())
+                .unwrap();
+            log.flush().unwrap();
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 5, "{text}");
+        assert!(lines[0].starts_with("received_at,"));
+        assert!(lines[1..].iter().all(|line| line.contains(",hand,")));
+    }
+}
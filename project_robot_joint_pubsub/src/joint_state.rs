@@ -0,0 +1,186 @@
+//! Conversion between [`RobotState`] and ROS 2 `sensor_msgs/JointState`.
+//!
+//! [`JointState`] mirrors the ROS message field for field, so this crate needs
+//! no ROS installation; the `ros2_bridge` crate copies it into the generated
+//! `sensor_msgs::msg::JointState` and back.
+//!
+//! | `RobotState`                 | `sensor_msgs/JointState`           |
+//! |------------------------------|------------------------------------|
+//! | `robot_id`                   | `header.frame_id`                  |
+//! | `joints[i].joint_name`       | `name[i]`                          |
+//! | `joints[i].angle_rad`        | `position[i]`                      |
+//! | `joints[i].velocity`         | `velocity[i]`                      |
+//! | `joints[i].torque`           | `effort[i]`                        |
+//! | `timestamp` (epoch ns)       | `header.stamp`                     |
+//! | `sequence`                   | — (ROS 2 headers have no `seq`)    |
+//! | `joints[i].qpos`             | — (one position per joint)         |
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::{JointAngles, RobotState};
+
+/// The fields of `sensor_msgs/JointState`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JointState {
+    /// `header.stamp.sec`.
+    pub stamp_sec: i32,
+    /// `header.stamp.nanosec`.
+    pub stamp_nanosec: u32,
+    /// `header.frame_id`.
+    pub frame_id: String,
+    pub name: Vec<String>,
+    pub position: Vec<f64>,
+    pub velocity: Vec<f64>,
+    pub effort: Vec<f64>,
+}
+
+/// A `JointState` that cannot be turned into a [`RobotState`].
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum JointStateError {
+    /// `position` must have one entry per name; `velocity` and `effort` may
+    /// also be empty (ROS allows leaving them out).
+    #[error("{field} has {got} entries for {names} joint names")]
+    LengthMismatch {
+        field: &'static str,
+        names: usize,
+        got: usize,
+    },
+}
+
+impl ExitStatus for JointStateError {
+    fn exit(&self) -> Exit {
+        Exit::Data
+    }
+}
+
+/// `timestamp`, in nanoseconds since the Unix epoch, as a ROS stamp's
+/// `(sec, nanosec)`.
+pub fn stamp(timestamp: u64) -> (i32, u32) {
+    (
+        (timestamp / 1_000_000_000) as i32,
+        (timestamp % 1_000_000_000) as u32,
+    )
+}
+
+/// `state` as a `JointState` stamped `stamp_sec.stamp_nanosec` (usually
+/// [`stamp`] of its `timestamp`).
+pub fn to_joint_state(state: &RobotState, stamp_sec: i32, stamp_nanosec: u32) -> JointState {
+    JointState {
+        stamp_sec,
+        stamp_nanosec,
+        frame_id: state.robot_id.clone(),
+        name: state.joints.iter().map(|j| j.joint_name.clone()).collect(),
+        position: state.joints.iter().map(|j| j.angle_rad).collect(),
+        velocity: state.joints.iter().map(|j| j.velocity).collect(),
+        effort: state.joints.iter().map(|j| j.torque).collect(),
+    }
+}
+
+/// `msg` as the `sequence`-th [`RobotState`], timestamped by its stamp; an
+/// empty `frame_id` becomes `default_robot_id`. An unset (zero) stamp is left
+/// for [`Publisher::publish`](crate::Publisher::publish) to fill in.
+pub fn from_joint_state(
+    msg: &JointState,
+    default_robot_id: &str,
+    sequence: u64,
+) -> Result<RobotState, JointStateError> {
+    let names = msg.name.len();
+    let check = |field: &'static str, values: &[f64], optional: bool| {
+        if values.len() == names || (optional && values.is_empty()) {
+            Ok(())
+        } else {
+            Err(JointStateError::LengthMismatch {
+                field,
+                names,
+                got: values.len(),
+            })
+        }
+    };
+    check("position", &msg.position, false)?;
+    check("velocity", &msg.velocity, true)?;
+    check("effort", &msg.effort, true)?;
+
+    let timestamp = u64::try_from(msg.stamp_sec)
+        .map_or(0, |sec| sec * 1_000_000_000 + u64::from(msg.stamp_nanosec));
+    let robot_id = if msg.frame_id.is_empty() {
+        default_robot_id
+    } else {
+        &msg.frame_id
+    };
+    let joints = msg
+        .name
+        .iter()
+        .enumerate()
+        .map(|(i, name)| JointAngles {
+            timestamp,
+            joint_name: name.clone(),
+            angle_rad: msg.position[i],
+            velocity: msg.velocity.get(i).copied().unwrap_or(0.0),
+            torque: msg.effort.get(i).copied().unwrap_or(0.0),
+            qpos: Vec::new(),
+        })
+        .collect();
+    Ok(RobotState {
+        timestamp,
+        robot_id: robot_id.to_string(),
+        joints,
+        sent_at_us: None,
+        sequence: Some(sequence),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    #[test]
+    fn round_trips_everything_but_qpos() {
+        let mut state = simulated_state(3);
+        state.timestamp = 12_000_000_500;
+        state.joints[0].timestamp = state.timestamp;
+        let (sec, nanosec) = stamp(state.timestamp);
+        let msg = to_joint_state(&state, sec, nanosec);
+        assert_eq!(msg.frame_id, state.robot_id);
+        assert_eq!(msg.name, [state.joints[0].joint_name.clone()]);
+        assert_eq!((msg.stamp_sec, msg.stamp_nanosec), (12, 500));
+        // ROS carries no sequence number: the bridge counts its own.
+        let back = from_joint_state(&msg, "unused", 3).unwrap();
+        // `JointState` has one position per joint: the ball joint keeps its angle only.
+        state.joints[0].qpos.clear();
+        assert_eq!(back, state);
+    }
+
+    #[test]
+    fn missing_velocity_and_effort_default_to_zero() {
+        let msg = JointState {
+            name: vec!["a".into(), "b".into()],
+            position: vec![0.5, -0.5],
+            ..JointState::default()
+        };
+        let state = from_joint_state(&msg, "arm", 9).unwrap();
+        assert_eq!(state.robot_id, "arm");
+        assert_eq!(state.joints[1].angle_rad, -0.5);
+        assert_eq!(state.joints[1].velocity, 0.0);
+        assert_eq!((state.timestamp, state.sequence), (0, Some(9)));
+    }
+
+    #[test]
+    fn rejects_arrays_that_do_not_match_the_names() {
+        let msg = JointState {
+            name: vec!["a".into(), "b".into()],
+            position: vec![0.5, -0.5],
+            effort: vec![1.0],
+            ..JointState::default()
+        };
+        assert_eq!(
+            from_joint_state(&msg, "arm", 1),
+            Err(JointStateError::LengthMismatch {
+                field: "effort",
+                names: 2,
+                got: 1
+            })
+        );
+    }
+}
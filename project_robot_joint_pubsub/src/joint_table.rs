@@ -0,0 +1,151 @@
+//! A model's joints as a table, printed by `model_info list-joints`, so joint
+//! names for `--filter-prefix`, `--joints`, `--joint-map`, and preset files can
+//! be copied from it rather than dug out of the MJCF.
+//!
+//! The binary reads the rows out of MuJoCo; this module only knows what a row
+//! holds and how it prints, so it builds without MuJoCo.
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// MuJoCo's joint types (`mjtJoint`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JointKind {
+    Free,
+    Ball,
+    Slide,
+    Hinge,
+}
+
+impl JointKind {
+    /// Degrees of freedom: its share of `qvel`.
+    pub fn dof(self) -> usize {
+        match self {
+            JointKind::Free => 6,
+            JointKind::Ball => 3,
+            JointKind::Slide | JointKind::Hinge => 1,
+        }
+    }
+
+    /// Position coordinates: its share of `qpos` (quaternions take four).
+    pub fn qpos(self) -> usize {
+        match self {
+            JointKind::Free => 7,
+            JointKind::Ball => 4,
+            JointKind::Slide | JointKind::Hinge => 1,
+        }
+    }
+
+    /// What its range is measured in; a ball joint's is its largest rotation.
+    pub fn unit(self) -> &'static str {
+        match self {
+            JointKind::Slide => "m",
+            JointKind::Free | JointKind::Ball | JointKind::Hinge => "rad",
+        }
+    }
+}
+
+impl fmt::Display for JointKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JointKind::Free => "free",
+            JointKind::Ball => "ball",
+            JointKind::Slide => "slide",
+            JointKind::Hinge => "hinge",
+        })
+    }
+}
+
+/// One joint of a model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JointRow {
+    /// Its name in the MJCF; empty for an unnamed joint.
+    pub name: String,
+    pub kind: JointKind,
+    /// Its `range`, if the joint is limited.
+    pub range: Option<(f64, f64)>,
+}
+
+impl JointRow {
+    /// Its range as printed: `-0.349 .. 1.571 rad`, or `unlimited`.
+    pub fn range_text(&self) -> String {
+        match self.range {
+            Some((min, max)) => format!("{min:.3} .. {max:.3} {}", self.kind.unit()),
+            None => "unlimited".to_string(),
+        }
+    }
+}
+
+/// `rows` as a table with a header line, columns aligned, one joint per line.
+pub fn render(rows: &[JointRow]) -> String {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .chain(["name".len()])
+        .max()
+        .unwrap_or_default();
+    let mut table = format!("{:name_width$}  type   dof  qpos  range\n", "name");
+    for row in rows {
+        let name = if row.name.is_empty() {
+            "(unnamed)"
+        } else {
+            &row.name
+        };
+        table.push_str(&format!(
+            "{name:name_width$}  {:5}  {:>3}  {:>4}  {}\n",
+            row.kind.to_string(),
+            row.kind.dof(),
+            row.kind.qpos(),
+            row.range_text()
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_line_up_under_the_header() {
+        let rows = [
+            JointRow {
+                name: "i1_MCP".into(),
+                kind: JointKind::Hinge,
+                range: Some((-0.349, 1.4)),
+            },
+            JointRow {
+                name: "wrist_slide".into(),
+                kind: JointKind::Slide,
+                range: None,
+            },
+            JointRow {
+                name: "root".into(),
+                kind: JointKind::Free,
+                range: None,
+            },
+        ];
+        assert_eq!(
+            render(&rows),
+            "name         type   dof  qpos  range\n\
+             i1_MCP       hinge    1     1  -0.349 .. 1.400 rad\n\
+             wrist_slide  slide    1     1  unlimited\n\
+             root         free     6     7  unlimited\n"
+        );
+    }
+
+    #[test]
+    fn rows_serialize_for_scripts() {
+        let row = JointRow {
+            name: "t1_TM".into(),
+            kind: JointKind::Ball,
+            range: Some((0.0, 1.0)),
+        };
+        assert_eq!(
+            serde_json::to_string(&row).unwrap(),
+            r#"{"name":"t1_TM","kind":"ball","range":[0.0,1.0]}"#
+        );
+    }
+}
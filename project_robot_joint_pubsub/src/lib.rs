@@ -1,16 +1,193 @@
 //! Shared data model for the robot joint pub/sub binaries.
 //!
 //! [`JointAngles`] and [`RobotState`] are the wire types serialized as JSON
-//! over a ZMQ PUB/SUB socket by `bin/publisher.rs` (or the GUI publisher) and
-//! decoded by `bin/subscriber.rs`. Keeping them in this library crate ensures
-//! both ends share a single definition.
+//! (or MessagePack or protobuf, see [`wire::Format`]) over a [`Transport`] (ZMQ PUB/SUB, MQTT, or Zenoh) by `bin/publisher.rs` (or the GUI
+//! publisher) and decoded by `bin/subscriber.rs`. Keeping them in this library crate ensures
+//! both ends share a single definition. [`RobotStateRef`] and [`JointAnglesRef`]
+//! are the same messages borrowing their names from the payload, for receive loops
+//! that need not keep them.
+//!
+//! [`BodyPoses`] are the Cartesian counterpart: site and body poses the subscriber
+//! computes from each state and republishes (see [`poses`]).
+//!
+//! [`Config`] holds the transport, endpoints, topic, and timing every binary starts from;
+//! see [`config`] for where it is read.
+//!
+//! # How this crate is organized
+//!
+//! - [`wire`] — the JSON, MessagePack, or protobuf payload, and the legacy
+//!   single-frame `"<topic> <json>"` ZMQ message.
+//! - [`proto`] — the protobuf message types, matching `proto/robot_joint_pubsub.proto`.
+//! - [`transport`] — the [`Transport`] trait, [`Publisher`] / [`Subscriber`] on
+//!   top of it ([`AsyncSubscriber`] to await states in tokio), and the backends:
+//!   `ZmqTransport` (feature `zmq-transport`, with `ZmqBroker` to share one
+//!   endpoint pair and `ZmqMonitor` to watch connections) and `MqttTransport` (feature `mqtt-transport`), both on by
+//!   default, `ZenohTransport` (feature `zenoh-transport`, opt-in, keyed by
+//!   robot), [`ShmTransport`] (a shared-memory ring for same-host pipelines),
+//!   and [`InprocTransport`] for tests.
+//! - `ws` — a WebSocket feed of states as JSON for browser dashboards
+//!   (feature `ws-relay`, on by default; served by the `ws_relay` binary).
+//! - `grpc` — the `RobotJoints` gRPC service streaming states and taking joint
+//!   commands (feature `grpc`; served by the `grpc_bridge` binary).
+//! - [`finger`] — per-finger sub-topics, for `--split-by-finger` on the
+//!   publishers.
+//! - [`control`] — emergency stop, hold, and resume commands on
+//!   `<topic>/control`, sent by the GUI publisher and honored by the subscriber.
+//! - [`recording`] — MCAP session recordings, written by the `recorder` binary.
+//! - [`sim`] — the headless publisher's deterministic simulated robot.
+//! - [`trajectory`] — joint trajectories from CSV, for `publisher --from-csv`,
+//!   and the GUI publisher's recorded takes exported as CSV.
+//! - [`waveform`] — per-joint sine, square, and chirp stimuli from TOML, for
+//!   `publisher --waveforms` and the GUI publisher's Generators tab.
+//! - [`sequence`] — YAML keyframe gestures for `publisher_sequence` and the GUI
+//!   publisher's Sequence tab.
+//! - [`gamepad`] — stick, trigger, and button mappings for the
+//!   `publisher_gamepad` teleop binary (feature `gamepad`).
+//! - [`udp_bridge`] — raw UDP sensor packets (data gloves, mocap) decoded by a
+//!   TOML layout, for the `udp_bridge` binary.
+//! - [`serial`] — newline-delimited JSON or COBS frames from a microcontroller's
+//!   serial port, for the `serial_bridge` binary.
+//! - [`pd`] — per-joint PD gains and tracking error for `subscriber --mode pd`.
+//! - [`safety`] — per-joint velocity and acceleration limits on commanded
+//!   positions, for `subscriber --safety`.
+//! - [`poses`] — site and body poses from MuJoCo's kinematics, published by
+//!   `subscriber --poses-bind`.
+//! - [`feedback`] — the subscriber's simulated joint states, published by
+//!   `subscriber --publish-feedback` on `robot_joints_actual` for the GUI
+//!   publisher's `--feedback` and other tools.
+//! - [`gravity`] — gravity-compensation torques in republished states, for
+//!   the `gravity_comp` binary (MuJoCo inverse dynamics).
+//! - [`presets`] — named poses saved to a TOML file by the GUI publisher.
+//! - [`random_pose`] — seeded random test poses within joint limits, for the GUI
+//!   publisher's Random pose button.
+//! - [`transition`] — eased moves between poses for the GUI publisher's presets.
+//! - [`ik`] — position inverse kinematics for the GUI publisher's IK tab.
+//! - [`interpolation`] — in-between poses for the subscriber's render loop.
+//! - [`camera`] — viewer viewpoints, preset or saved, for `subscriber --camera`.
+//! - [`video`] — offscreen-rendered frames as PNGs or an `ffmpeg` video, for
+//!   `subscriber --render-to`.
+//! - [`scene`] — several robot models in one subscriber scene, routed by
+//!   `robot_id` or topic.
+//! - [`mirror`] — one hand's states mirrored onto the other, for
+//!   `subscriber --mirror`.
+//! - [`joint_filter`] — the joints a subscriber applies and logs, for
+//!   `subscriber --joints` and `--joint-prefix`.
+//! - [`stats`] — receive rate, jitter, and latency for `subscriber --stats` and
+//!   `subscriber_tui`.
+//! - [`metrics`] — Prometheus counters and gauges on `GET /metrics`, for
+//!   `--metrics` on the publisher and subscriber.
+//! - [`joint_table`] — a model's joints as a table, printed by
+//!   `model_info list-joints`.
+//! - [`model_check`] — unlimited joints, repeated names, actuators without a
+//!   joint, and joints other files name that a model lacks, for
+//!   `model_info validate`.
+//! - [`joint_log`] — every received joint as a CSV row with its receive time,
+//!   for `subscriber --log-file`.
+//! - [`history`] — a rolling window of received states, plotted and exported
+//!   to CSV by the `monitor_gui` binary.
+//! - `query` — a ZMQ REP socket answering `get_state` and friends with JSON
+//!   (feature `zmq-transport`; `--query` on the subscriber and GUI publisher).
+//! - [`joint_state`] — conversion to and from ROS 2 `sensor_msgs/JointState`
+//!   (used by the separate `ros2_bridge` crate).
+//! - [`clock`] — wall-clock `timestamp`s, and peer clock offsets estimated
+//!   from heartbeat round trips, for latency across machines.
+//! - [`config`] — endpoints, topic, and timing from a config file and
+//!   `ROBOT_PUBSUB_*` variables.
+//! - [`logging`] — the `-v`/`-q`/`--log-json` flags every logging binary takes.
+//! - [`args`] — the `--config`, transport, endpoint, and encoding flags the
+//!   binaries share, each falling back to [`Config`].
+//!
+//! # Features
+//!
+//! Everything above except the ZMQ, MQTT, and Zenoh transports, `ws`, and `grpc` builds without
+//! libzmq, network clients, or MuJoCo, so other crates can depend on the message types
+//! with `default-features = false`. The binaries declare what they need:
+//! `publisher` needs the ZMQ and MQTT transports, `broker` only `zmq-transport`, `ws_relay`
+//! also `ws-relay`, `subscriber` and `gravity_comp` also `viewer` (MuJoCo), `model_info` only `viewer`, the slider publisher
+//! `zmq-transport` and `gui`, `publisher_gamepad` the transports and
+//! `gamepad` (gilrs), `publisher_sequence` and `udp_bridge` the transports, `monitor_gui`
+//! the transports and `monitor` (egui, without MuJoCo), `subscriber_tui`
+//! the transports and `tui` (ratatui), `serial_bridge` the transports and
+//! `serial` (serialport), and `grpc_bridge` the transports and `grpc` (tonic).
+//! Any of them takes `--transport zenoh` once built with `zenoh-transport`;
+//! without it, [`transport::open_zenoh`] says so.
+//!
+//! # Errors
+//!
+//! [`wire::WireError`], [`TransportError`], and `MqttError` are `thiserror` enums;
+//! the binaries wrap them in `anyhow` and exit with [`exit_for`]'s code (4 socket
+//! or broker, 5 bad message).
+
+pub mod args;
+pub mod camera;
+pub mod clock;
+pub mod config;
+pub mod control;
+pub mod feedback;
+pub mod finger;
+pub mod gamepad;
+pub mod gravity;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod ik;
+pub mod interpolation;
+pub mod joint_filter;
+pub mod joint_log;
+pub mod joint_state;
+pub mod joint_table;
+pub mod logging;
+pub mod metrics;
+pub mod mirror;
+pub mod model_check;
+pub mod pd;
+pub mod poses;
+pub mod presets;
+pub mod proto;
+#[cfg(feature = "zmq-transport")]
+pub mod query;
+pub mod random_pose;
+pub mod recording;
+pub mod safety;
+pub mod scene;
+pub mod sequence;
+pub mod serial;
+pub mod sim;
+pub mod stats;
+pub mod trajectory;
+pub mod transition;
+pub mod transport;
+pub mod udp_bridge;
+pub mod video;
+pub mod waveform;
+pub mod wire;
+#[cfg(feature = "ws-relay")]
+pub mod ws;
 
+pub use config::{Config, TransportKind};
+pub use transport::{
+    AsyncSubscriber, InprocTransport, Publisher, ShmError, ShmTransport, Subscriber, Transport,
+    TransportError,
+};
+#[cfg(feature = "zmq-transport")]
+pub use transport::{ConnectionEvent, ZmqBroker, ZmqMonitor, ZmqTransport};
+#[cfg(feature = "mqtt-transport")]
+pub use transport::{MqttError, MqttTransport};
+#[cfg(feature = "zenoh-transport")]
+pub use transport::{ZenohError, ZenohTransport};
+pub use wire::{Compression, Format};
+
+use std::borrow::Cow;
+use std::error::Error;
+
+use errors::{Exit, ExitStatus};
 use serde::{Deserialize, Serialize};
 
 /// Joint angle data sampled from a single robot joint at a point in time.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JointAngles {
-    /// Monotonic publisher-side sequence number (not wall-clock).
+    /// When the joint was sampled, in nanoseconds since the Unix epoch; as
+    /// [`RobotState::timestamp`].
     pub timestamp: u64,
     /// Human-readable joint identifier (e.g. `"shoulder_pan"`).
     pub joint_name: String,
@@ -20,15 +197,289 @@ pub struct JointAngles {
     pub velocity: f64,
     /// Joint torque in N·m.
     pub torque: f64,
+    /// Every position coordinate of a joint with more than one, in MuJoCo `qpos`
+    /// order: a ball joint's quaternion `[w, x, y, z]`, or a free joint's position
+    /// `[x, y, z]` then quaternion. Empty for hinge and slide joints, whose
+    /// position is `angle_rad`, and from older publishers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qpos: Vec<f64>,
+}
+
+impl JointAngles {
+    /// The joint's position coordinates: `qpos`, or `angle_rad` alone for a
+    /// one-coordinate joint.
+    pub fn positions(&self) -> &[f64] {
+        if self.qpos.is_empty() {
+            std::slice::from_ref(&self.angle_rad)
+        } else {
+            &self.qpos
+        }
+    }
 }
 
 /// Snapshot of every joint on a single robot at a point in time.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RobotState {
-    /// Monotonic publisher-side sequence number (not wall-clock).
+    /// When the state was sampled, in nanoseconds since the Unix epoch. Zero
+    /// until [`Publisher::publish`] stamps it, unless the source knows better
+    /// (a ROS message's stamp). Older publishers sent their sequence number here.
     pub timestamp: u64,
     /// Stable identifier for the publishing robot.
     pub robot_id: String,
     /// One [`JointAngles`] entry per joint, in publisher-defined order.
     pub joints: Vec<JointAngles>,
+    /// Wall-clock send time in microseconds since the Unix epoch, stamped by
+    /// [`Publisher::publish`]; absent from older publishers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at_us: Option<u64>,
+    /// Publisher-side sequence number, counting up from 1; absent from older
+    /// publishers and sources that do not count (a ROS message).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+}
+
+impl RobotState {
+    /// What orders the states of one publisher: `sequence`, or `timestamp`
+    /// from publishers without one (older ones sent their sequence number there).
+    pub fn order(&self) -> u64 {
+        self.sequence.unwrap_or(self.timestamp)
+    }
+}
+
+/// Several [`RobotState`]s from one publisher sent as one payload, for rates
+/// above what is worth a message each (see [`Publisher::with_batch`]).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RobotStateBatch {
+    /// The batched states, oldest first.
+    pub states: Vec<RobotState>,
+}
+
+/// A [`JointAngles`] borrowing its name from the payload it was decoded from
+/// (see [`wire::Format::decode_ref`]).
+///
+/// The name is a `Cow` rather than a `&str` so that every payload decodes:
+/// a JSON string with escapes, or a protobuf payload, has no `&str` to lend and
+/// comes out owned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JointAnglesRef<'a> {
+    /// As [`JointAngles::timestamp`].
+    pub timestamp: u64,
+    /// As [`JointAngles::joint_name`], borrowed where the payload allows.
+    #[serde(borrow)]
+    pub joint_name: Cow<'a, str>,
+    /// As [`JointAngles::angle_rad`].
+    pub angle_rad: f64,
+    /// As [`JointAngles::velocity`].
+    pub velocity: f64,
+    /// As [`JointAngles::torque`].
+    pub torque: f64,
+    /// As [`JointAngles::qpos`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qpos: Vec<f64>,
+}
+
+impl JointAnglesRef<'_> {
+    /// As [`JointAngles::positions`].
+    pub fn positions(&self) -> &[f64] {
+        if self.qpos.is_empty() {
+            std::slice::from_ref(&self.angle_rad)
+        } else {
+            &self.qpos
+        }
+    }
+}
+
+/// A [`RobotState`] borrowing its strings from the payload it was decoded from,
+/// for receive loops that only read each state: decoding it allocates no
+/// `String` per joint. [`RobotState::from`] makes it owned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RobotStateRef<'a> {
+    /// As [`RobotState::timestamp`].
+    pub timestamp: u64,
+    /// As [`RobotState::robot_id`], borrowed where the payload allows.
+    #[serde(borrow)]
+    pub robot_id: Cow<'a, str>,
+    /// As [`RobotState::joints`].
+    #[serde(borrow)]
+    pub joints: Vec<JointAnglesRef<'a>>,
+    /// As [`RobotState::sent_at_us`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at_us: Option<u64>,
+    /// As [`RobotState::sequence`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+}
+
+impl From<JointAnglesRef<'_>> for JointAngles {
+    fn from(joint: JointAnglesRef<'_>) -> Self {
+        JointAngles {
+            timestamp: joint.timestamp,
+            joint_name: joint.joint_name.into_owned(),
+            angle_rad: joint.angle_rad,
+            velocity: joint.velocity,
+            torque: joint.torque,
+            qpos: joint.qpos,
+        }
+    }
+}
+
+impl From<RobotStateRef<'_>> for RobotState {
+    fn from(state: RobotStateRef<'_>) -> Self {
+        RobotState {
+            timestamp: state.timestamp,
+            robot_id: state.robot_id.into_owned(),
+            joints: state.joints.into_iter().map(JointAngles::from).collect(),
+            sent_at_us: state.sent_at_us,
+            sequence: state.sequence,
+        }
+    }
+}
+
+impl<'a> From<&'a JointAngles> for JointAnglesRef<'a> {
+    fn from(joint: &'a JointAngles) -> Self {
+        JointAnglesRef {
+            timestamp: joint.timestamp,
+            joint_name: Cow::Borrowed(&joint.joint_name),
+            angle_rad: joint.angle_rad,
+            velocity: joint.velocity,
+            torque: joint.torque,
+            qpos: joint.qpos.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a RobotState> for RobotStateRef<'a> {
+    fn from(state: &'a RobotState) -> Self {
+        RobotStateRef {
+            timestamp: state.timestamp,
+            robot_id: Cow::Borrowed(&state.robot_id),
+            joints: state.joints.iter().map(JointAnglesRef::from).collect(),
+            sent_at_us: state.sent_at_us,
+            sequence: state.sequence,
+        }
+    }
+}
+
+/// Takes the strings over as they are, without copying them.
+impl From<RobotState> for RobotStateRef<'_> {
+    fn from(state: RobotState) -> Self {
+        let joints = state
+            .joints
+            .into_iter()
+            .map(|joint| JointAnglesRef {
+                timestamp: joint.timestamp,
+                joint_name: Cow::Owned(joint.joint_name),
+                angle_rad: joint.angle_rad,
+                velocity: joint.velocity,
+                torque: joint.torque,
+                qpos: joint.qpos,
+            })
+            .collect();
+        RobotStateRef {
+            timestamp: state.timestamp,
+            robot_id: Cow::Owned(state.robot_id),
+            joints,
+            sent_at_us: state.sent_at_us,
+            sequence: state.sequence,
+        }
+    }
+}
+
+/// Where one site or body of a robot is, in world coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyPose {
+    /// MuJoCo site or body name (e.g. `"index_tip"`).
+    pub name: String,
+    /// Position `[x, y, z]` in meters.
+    pub position: [f64; 3],
+    /// Orientation as a unit quaternion `[w, x, y, z]`, MuJoCo's order.
+    pub orientation: [f64; 4],
+}
+
+/// Cartesian poses computed from one [`RobotState`] by forward kinematics.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyPoses {
+    /// The `timestamp` of the state they were computed from.
+    pub timestamp: u64,
+    /// The `robot_id` of the state they were computed from.
+    pub robot_id: String,
+    /// One [`BodyPose`] per tracked site or body.
+    pub bodies: Vec<BodyPose>,
+    /// Wall-clock send time in microseconds since the Unix epoch, stamped by
+    /// [`Publisher::publish_poses`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at_us: Option<u64>,
+}
+
+/// The repo-wide exit code for a failed binary: socket and broker errors are
+/// [`Exit::Network`], undecodable messages [`Exit::Data`], and config or file
+/// errors follow [`errors::exit_for`].
+pub fn exit_for(err: &(dyn Error + 'static)) -> Exit {
+    errors::exit_for_with(err, |e| {
+        if let Some(err) = e.downcast_ref::<TransportError>() {
+            return Some(err.exit());
+        }
+        #[cfg(feature = "mqtt-transport")]
+        if let Some(err) = e.downcast_ref::<MqttError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<ShmError>() {
+            return Some(err.exit());
+        }
+        #[cfg(feature = "zenoh-transport")]
+        if let Some(err) = e.downcast_ref::<ZenohError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<recording::RecordingError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<trajectory::TrajectoryError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<gamepad::GamepadMapError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<udp_bridge::PacketLayoutError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<scene::SceneError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<camera::CameraError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<video::VideoError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<pd::GainsError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<safety::SafetyLimitsError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<presets::PresetsError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<random_pose::ConstraintsError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<sequence::SequenceError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<history::HistoryError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<waveform::WaveformError>() {
+            return Some(err.exit());
+        }
+        if let Some(err) = e.downcast_ref::<joint_log::JointLogError>() {
+            return Some(err.exit());
+        }
+        e.downcast_ref::<wire::WireError>()
+            .map(ExitStatus::exit)
+            .or_else(|| {
+                e.downcast_ref::<joint_state::JointStateError>()
+                    .map(ExitStatus::exit)
+            })
+    })
 }
@@ -0,0 +1,87 @@
+//! Logging flags every binary shares, over the `common` crate's `tracing` setup:
+//! `-v`/`-q` move the level up or down from `info`, and `--log-json` writes one
+//! JSON object per line. `RUST_LOG` and `LOG_FORMAT` still work, and `RUST_LOG`
+//! wins over `-v`/`-q` when set.
+
+use clap::{ArgAction, Args};
+use common::{init_logging_as, LogFormat, LogOutput};
+
+/// The logging flags; `#[command(flatten)]` them into a binary's `Cli`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Args)]
+pub struct LogArgs {
+    /// Log more: -v for debug (every sample), -vv for trace
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+    /// Log less: -q for warnings and errors only, -qq for errors only
+    #[arg(short, long, action = ArgAction::Count)]
+    pub quiet: u8,
+    /// Log one JSON object per line, as `LOG_FORMAT=json` does
+    #[arg(long)]
+    pub log_json: bool,
+}
+
+impl LogArgs {
+    /// The level the flags ask for.
+    pub fn level(&self) -> &'static str {
+        match i16::from(self.verbose) - i16::from(self.quiet) {
+            ..=-2 => "error",
+            -1 => "warn",
+            0 => "info",
+            1 => "debug",
+            2.. => "trace",
+        }
+    }
+
+    /// The `tracing` filter for binary crate `bin`: more detail only from
+    /// `bin` and this library, so `-vv` does not also trace every socket and
+    /// window in the dependencies; less from everything.
+    pub fn filter(&self, bin: &str) -> String {
+        let level = self.level();
+        if self.verbose > 0 {
+            format!("info,{}={level},{bin}={level}", env!("CARGO_CRATE_NAME"))
+        } else {
+            level.to_string()
+        }
+    }
+
+    /// Install the global subscriber for binary crate `bin` (pass
+    /// `env!("CARGO_CRATE_NAME")`), writing to `output`.
+    pub fn init(&self, bin: &str, output: LogOutput) -> errors::Result<()> {
+        let format = if self.log_json {
+            LogFormat::Json
+        } else {
+            LogFormat::from_env()?
+        };
+        init_logging_as(&self.filter(bin), output, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        log: LogArgs,
+    }
+
+    fn parse(args: &[&str]) -> Result<LogArgs, clap::Error> {
+        Cli::try_parse_from([&["bin"], args].concat()).map(|cli| cli.log)
+    }
+
+    #[test]
+    fn flags_move_the_level_from_info() {
+        assert_eq!(parse(&[]).unwrap().filter("publisher"), "info");
+        assert_eq!(parse(&["-q"]).unwrap().filter("publisher"), "warn");
+        assert_eq!(parse(&["-qqq"]).unwrap().filter("publisher"), "error");
+        assert_eq!(
+            parse(&["-v"]).unwrap().filter("publisher"),
+            "info,project_robot_joint_pubsub=debug,publisher=debug"
+        );
+        assert_eq!(parse(&["-vvv"]).unwrap().level(), "trace");
+        assert!(parse(&["--log-json"]).unwrap().log_json);
+        assert!(parse(&["-v", "-q"]).is_err());
+    }
+}
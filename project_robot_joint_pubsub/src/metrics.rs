@@ -0,0 +1,293 @@
+//! Prometheus metrics for long-running publishers and subscribers, served as
+//! plain text on `GET /metrics` (`--metrics <addr>` on `publisher` and
+//! `subscriber`).
+//!
+//! | Metric | Type | Meaning |
+//! |---|---|---|
+//! | `robot_joint_messages_sent_total` | counter | States published |
+//! | `robot_joint_messages_received_total` | counter | States received |
+//! | `robot_joint_parse_errors_total` | counter | Messages that did not decode |
+//! | `robot_joint_publish_rate_hz` | gauge | States published in the last second |
+//! | `robot_joint_receive_rate_hz` | gauge | States received in the last second |
+//! | `robot_joint_angle_radians{robot_id,joint}` | gauge | Each joint's last angle |
+//!
+//! The server is a few lines of blocking `std::net` on a thread of its own, so
+//! it works the same next to a tokio publisher and a MuJoCo render loop.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::RobotState;
+
+/// Window the rate gauges count over.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Longest a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// States counted in one direction, and when the last second's arrived.
+#[derive(Debug, Default)]
+struct Flow {
+    total: u64,
+    recent: VecDeque<Instant>,
+}
+
+impl Flow {
+    fn count(&mut self, now: Instant) {
+        self.total += 1;
+        self.recent.push_back(now);
+        self.forget_before(now);
+    }
+
+    fn forget_before(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|&at| now.saturating_duration_since(at) >= RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    fn rate_hz(&mut self, now: Instant) -> f64 {
+        self.forget_before(now);
+        self.recent.len() as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: Flow,
+    received: Flow,
+    parse_errors: u64,
+    /// Last angle by robot id, then joint name.
+    angles: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl Counters {
+    fn remember(&mut self, state: &RobotState) {
+        // Look up before inserting, so steady states allocate no keys.
+        let robot = match self.angles.get_mut(&state.robot_id) {
+            Some(robot) => robot,
+            None => self.angles.entry(state.robot_id.clone()).or_default(),
+        };
+        for joint in &state.joints {
+            match robot.get_mut(&joint.joint_name) {
+                Some(angle) => *angle = joint.angle_rad,
+                None => {
+                    robot.insert(joint.joint_name.clone(), joint.angle_rad);
+                }
+            }
+        }
+    }
+}
+
+/// The numbers behind `/metrics`; clone it freely, every clone counts into the
+/// same metrics.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    counters: Arc<Mutex<Counters>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    fn counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        // Counters stay consistent even if a holder panicked mid-update.
+        self.counters.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Count `state` as published at `now`.
+    pub fn sent(&self, state: &RobotState, now: Instant) {
+        let mut counters = self.counters();
+        counters.sent.count(now);
+        counters.remember(state);
+    }
+
+    /// Count `state` as received at `now`.
+    pub fn received(&self, state: &RobotState, now: Instant) {
+        let mut counters = self.counters();
+        counters.received.count(now);
+        counters.remember(state);
+    }
+
+    /// Count a message that did not decode.
+    pub fn parse_error(&self) {
+        self.counters().parse_errors += 1;
+    }
+
+    /// Every metric in the Prometheus text format, rates as of `now`.
+    pub fn render(&self, now: Instant) -> String {
+        let mut counters = self.counters();
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+            let _ = writeln!(
+                out,
+                "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}"
+            );
+        };
+        metric(
+            "robot_joint_messages_sent_total",
+            "counter",
+            "States published.",
+            counters.sent.total as f64,
+        );
+        metric(
+            "robot_joint_messages_received_total",
+            "counter",
+            "States received.",
+            counters.received.total as f64,
+        );
+        metric(
+            "robot_joint_parse_errors_total",
+            "counter",
+            "Messages that did not decode.",
+            counters.parse_errors as f64,
+        );
+        metric(
+            "robot_joint_publish_rate_hz",
+            "gauge",
+            "States published in the last second.",
+            counters.sent.rate_hz(now),
+        );
+        metric(
+            "robot_joint_receive_rate_hz",
+            "gauge",
+            "States received in the last second.",
+            counters.received.rate_hz(now),
+        );
+        out.push_str("# HELP robot_joint_angle_radians Each joint's last angle.\n");
+        out.push_str("# TYPE robot_joint_angle_radians gauge\n");
+        for (robot_id, joints) in &counters.angles {
+            for (joint, angle) in joints {
+                let _ = writeln!(
+                    out,
+                    "robot_joint_angle_radians{{robot_id=\"{}\",joint=\"{}\"}} {angle}",
+                    escape(robot_id),
+                    escape(joint)
+                );
+            }
+        }
+        out
+    }
+
+    /// New metrics served on `addr` by a thread of their own, and the address
+    /// actually bound (`addr` may ask for port 0).
+    pub fn spawn(addr: &str) -> io::Result<(Metrics, SocketAddr)> {
+        let listener = TcpListener::bind(addr)?;
+        let local = listener.local_addr()?;
+        let metrics = Metrics::new();
+        let server = metrics.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = server.serve(listener) {
+                tracing::error!(error = %e, "metrics server stopped");
+            }
+        });
+        Ok((metrics, local))
+    }
+
+    /// Answer HTTP requests on `listener` until it fails: `GET /metrics` gets
+    /// [`Metrics::render`], anything else a 404. Blocks, so run it on a thread.
+    pub fn serve(self, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, peer) = listener.accept()?;
+            if let Err(e) = self.answer(stream) {
+                tracing::debug!(%peer, error = %e, "metrics request failed");
+            }
+        }
+    }
+
+    fn answer(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Skip the headers; nothing in them changes the answer.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+            header.clear();
+        }
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) => ("200 OK", self.render(Instant::now())),
+            _ => ("404 Not Found", "try GET /metrics\n".to_string()),
+        };
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+/// `value` as a Prometheus label value (inside the quotes).
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+    use std::io::Read;
+
+    #[test]
+    fn renders_counters_rates_and_angles() {
+        let metrics = Metrics::new();
+        let start = Instant::now();
+        let mut state = simulated_state(1);
+        state.robot_id = "left \"hand\"".into();
+        for i in 0..5 {
+            metrics.sent(&state, start + Duration::from_millis(300 * i));
+        }
+        metrics.received(&state, start);
+        metrics.parse_error();
+
+        let text = metrics.render(start + Duration::from_millis(1_300));
+        assert!(
+            text.contains("robot_joint_messages_sent_total 5\n"),
+            "{text}"
+        );
+        assert!(text.contains("robot_joint_messages_received_total 1\n"));
+        assert!(text.contains("robot_joint_parse_errors_total 1\n"));
+        // Sent at 0.6, 0.9, and 1.2 s are within a second of 1.3 s.
+        assert!(text.contains("robot_joint_publish_rate_hz 3\n"));
+        assert!(text.contains("robot_joint_receive_rate_hz 0\n"));
+        let joint = &state.joints[0];
+        assert!(text.contains(&format!(
+            "robot_joint_angle_radians{{robot_id=\"left \\\"hand\\\"\",joint=\"{}\"}} {}\n",
+            joint.joint_name, joint.angle_rad
+        )));
+    }
+
+    #[test]
+    fn serves_metrics_over_http() {
+        let (metrics, addr) = Metrics::spawn("127.0.0.1:0").unwrap();
+        metrics.parse_error();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: test\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("\r\n\r\n# HELP robot_joint_messages_sent_total"));
+        assert!(response.contains("robot_joint_parse_errors_total 1\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
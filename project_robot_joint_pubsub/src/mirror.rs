@@ -0,0 +1,164 @@
+//! Mirror images of one hand's states for the other hand, so one glove or GUI
+//! stream drives both hands of a symmetric demo (`subscriber --mirror L=R`).
+//!
+//! A [`Mirror`] from `L` to `R` takes states whose `robot_id` is `L`, or
+//! whose joints are named `L/...`, and renames them to `R` and `R/...`.
+//! Flexion is the same on both hands, but abduction (spreading the fingers)
+//! points the other way on a mirrored hand, so those joints' angles,
+//! velocities, and torques change sign: by default every joint with `abd` in
+//! its name, or exactly those given to [`Mirror::with_flip`].
+//!
+//! Ball and free joints keep their `qpos` as it is; only hinge and slide
+//! joints mirror correctly.
+
+use std::str::FromStr;
+
+use crate::{JointAngles, RobotState};
+
+/// Maps one hand's states onto the other's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mirror {
+    from: String,
+    to: String,
+    /// Joints (without the `L/` prefix) whose sign flips; `None` for every
+    /// joint named like an abduction joint.
+    flip: Option<Vec<String>>,
+}
+
+impl FromStr for Mirror {
+    type Err = String;
+
+    /// `FROM=TO`, e.g. `L=R` or `left=right`.
+    fn from_str(raw: &str) -> Result<Self, String> {
+        match raw.split_once('=') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() && from != to => {
+                Ok(Mirror::new(from, to))
+            }
+            _ => Err(format!(
+                "expected FROM=TO with two different robots, got {raw:?}"
+            )),
+        }
+    }
+}
+
+impl Mirror {
+    /// Mirror robot `from`'s states (or `from/` joints) onto `to`.
+    pub fn new(from: &str, to: &str) -> Self {
+        Mirror {
+            from: from.to_string(),
+            to: to.to_string(),
+            flip: None,
+        }
+    }
+
+    /// Flip exactly these joints instead of the ones named like abduction.
+    pub fn with_flip(mut self, joints: Vec<String>) -> Self {
+        self.flip = Some(joints);
+        self
+    }
+
+    /// Whether `joint_name` (without a `from/` prefix) changes sign.
+    pub fn flips(&self, joint_name: &str) -> bool {
+        match &self.flip {
+            Some(joints) => joints.iter().any(|joint| joint == joint_name),
+            None => joint_name.to_ascii_lowercase().contains("abd"),
+        }
+    }
+
+    /// `state`'s mirror image for the other hand, if it is from this
+    /// mirror's hand.
+    pub fn apply(&self, state: &RobotState) -> Option<RobotState> {
+        let from_prefix = format!("{}/", self.from);
+        let prefixed = state
+            .joints
+            .iter()
+            .any(|joint| joint.joint_name.starts_with(&from_prefix));
+        if state.robot_id != self.from && !prefixed {
+            return None;
+        }
+        let joints = state
+            .joints
+            .iter()
+            .map(|joint| {
+                let (name, joint_name) = match joint.joint_name.strip_prefix(&from_prefix) {
+                    Some(name) => (name, format!("{}/{name}", self.to)),
+                    None => (joint.joint_name.as_str(), joint.joint_name.clone()),
+                };
+                let sign = if self.flips(name) { -1.0 } else { 1.0 };
+                JointAngles {
+                    joint_name,
+                    angle_rad: sign * joint.angle_rad,
+                    velocity: sign * joint.velocity,
+                    torque: sign * joint.torque,
+                    ..joint.clone()
+                }
+            })
+            .collect();
+        let robot_id = if state.robot_id == self.from {
+            self.to.clone()
+        } else {
+            state.robot_id.clone()
+        };
+        Some(RobotState {
+            robot_id,
+            joints,
+            ..state.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(robot_id: &str, joints: &[(&str, f64)]) -> RobotState {
+        RobotState {
+            timestamp: 3,
+            robot_id: robot_id.into(),
+            joints: joints
+                .iter()
+                .map(|&(name, angle_rad)| JointAngles {
+                    timestamp: 3,
+                    joint_name: name.into(),
+                    angle_rad,
+                    velocity: angle_rad,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: None,
+        }
+    }
+
+    fn angles(state: &RobotState) -> Vec<(&str, f64)> {
+        state
+            .joints
+            .iter()
+            .map(|joint| (joint.joint_name.as_str(), joint.angle_rad))
+            .collect()
+    }
+
+    #[test]
+    fn renames_the_hand_and_flips_abduction() {
+        let mirror: Mirror = "L=R".parse().unwrap();
+        let mirrored = mirror
+            .apply(&state("glove", &[("L/i1_MCP", 0.5), ("L/i0_ABD", 0.2)]))
+            .unwrap();
+        assert_eq!(mirrored.robot_id, "glove");
+        assert_eq!(angles(&mirrored), [("R/i1_MCP", 0.5), ("R/i0_ABD", -0.2)]);
+        assert_eq!(mirrored.joints[1].velocity, -0.2);
+
+        let by_robot = mirror.with_flip(vec!["t1_TM".into()]);
+        let mirrored = by_robot
+            .apply(&state("L", &[("t1_TM", 0.7), ("i0_ABD", 0.2)]))
+            .unwrap();
+        assert_eq!(mirrored.robot_id, "R");
+        assert_eq!(angles(&mirrored), [("t1_TM", -0.7), ("i0_ABD", 0.2)]);
+
+        assert!(by_robot.apply(&state("R", &[("t1_TM", 0.7)])).is_none());
+        assert!("L=L".parse::<Mirror>().is_err());
+        assert!("=R".parse::<Mirror>().is_err());
+        assert!("L".parse::<Mirror>().is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Sanity checks on a model and on the files that name its joints, run by
+//! `model_info validate` so drift between model variants and their presets,
+//! gamepad maps, and UDP layouts shows up before a run rather than as joints
+//! that silently never move.
+//!
+//! The binary reads joints and actuators out of MuJoCo; the checks themselves
+//! only compare names, so they build without MuJoCo.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::joint_table::{JointKind, JointRow};
+
+/// One actuator of a model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActuatorRow {
+    /// Its name in the MJCF, or `#<id>` for an unnamed one.
+    pub name: String,
+    /// The named joint it drives through a joint transmission, if any.
+    pub joint: Option<String>,
+}
+
+/// Something `validate` found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// A hinge, slide, or ball joint without a `range`; the GUI publisher's
+    /// slider falls back to ±π for it, and nothing stops a subscriber's target.
+    Unlimited { joint: String },
+    /// A name given to more than one joint or actuator, or listed more than
+    /// once in a file where each entry maps to its own joint.
+    Duplicate {
+        within: String,
+        name: String,
+        count: usize,
+    },
+    /// An actuator that drives no named joint, so `subscriber --mode actuate`
+    /// never commands it.
+    ActuatorWithoutJoint { actuator: String },
+    /// A joint named in a file that the model does not have.
+    MissingJoint { file: String, joint: String },
+}
+
+impl Problem {
+    /// Whether this breaks a run; the rest are warnings.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Problem::Unlimited { .. })
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Unlimited { joint } => write!(f, "joint {joint} has no limits"),
+            Problem::Duplicate {
+                within,
+                name,
+                count,
+            } => write!(f, "{name} appears {count} times in {within}"),
+            Problem::ActuatorWithoutJoint { actuator } => {
+                write!(f, "actuator {actuator} drives no named joint")
+            }
+            Problem::MissingJoint { file, joint } => {
+                write!(
+                    f,
+                    "{file} names joint {joint}, which the model does not have"
+                )
+            }
+        }
+    }
+}
+
+/// Unlimited joints, repeated joint and actuator names, and actuators that
+/// drive no named joint.
+pub fn check_model(joints: &[JointRow], actuators: &[ActuatorRow]) -> Vec<Problem> {
+    let unlimited = joints
+        .iter()
+        .filter(|row| row.kind != JointKind::Free && row.range.is_none())
+        .map(|row| Problem::Unlimited {
+            joint: if row.name.is_empty() {
+                "(unnamed)".to_string()
+            } else {
+                row.name.clone()
+            },
+        });
+    let orphans = actuators
+        .iter()
+        .filter(|actuator| actuator.joint.is_none())
+        .map(|actuator| Problem::ActuatorWithoutJoint {
+            actuator: actuator.name.clone(),
+        });
+
+    let mut problems: Vec<Problem> = unlimited.collect();
+    problems.extend(duplicates(
+        "the model's joints",
+        joints.iter().map(|row| row.name.as_str()),
+    ));
+    problems.extend(duplicates(
+        "the model's actuators",
+        actuators.iter().map(|actuator| actuator.name.as_str()),
+    ));
+    problems.extend(orphans);
+    problems
+}
+
+/// The joints `file` lists (`names`, in file order) that `joints` lacks, and
+/// any it lists twice.
+pub fn check_references<'a>(
+    joints: &[JointRow],
+    file: &str,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<Problem> {
+    let known: HashSet<&str> = joints.iter().map(|row| row.name.as_str()).collect();
+    let names: Vec<&str> = names.into_iter().collect();
+    let mut missing: Vec<&str> = Vec::new();
+    for &name in &names {
+        if !known.contains(name) && !missing.contains(&name) {
+            missing.push(name);
+        }
+    }
+    let mut problems: Vec<Problem> = missing
+        .into_iter()
+        .map(|joint| Problem::MissingJoint {
+            file: file.to_string(),
+            joint: joint.to_string(),
+        })
+        .collect();
+    problems.extend(duplicates(file, names));
+    problems
+}
+
+/// Every non-empty name in `names` that appears more than once.
+fn duplicates<'a>(within: &str, names: impl IntoIterator<Item = &'a str>) -> Vec<Problem> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for name in names.into_iter().filter(|name| !name.is_empty()) {
+        *counts.entry(name).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count > 1)
+        .map(|(name, count)| Problem::Duplicate {
+            within: within.to_string(),
+            name: name.to_string(),
+            count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joint(name: &str, kind: JointKind, range: Option<(f64, f64)>) -> JointRow {
+        JointRow {
+            name: name.into(),
+            kind,
+            range,
+        }
+    }
+
+    fn hand() -> Vec<JointRow> {
+        vec![
+            joint("root", JointKind::Free, None),
+            joint("t1_TM", JointKind::Hinge, Some((-0.3, 1.2))),
+            joint("i1_MCP", JointKind::Hinge, None),
+            joint("", JointKind::Slide, None),
+        ]
+    }
+
+    #[test]
+    fn the_model_reports_unlimited_joints_and_orphan_actuators() {
+        let actuators = [
+            ActuatorRow {
+                name: "a_t1_TM".into(),
+                joint: Some("t1_TM".into()),
+            },
+            ActuatorRow {
+                name: "tendon_pull".into(),
+                joint: None,
+            },
+        ];
+        let problems = check_model(&hand(), &actuators);
+        assert_eq!(
+            problems,
+            [
+                Problem::Unlimited {
+                    joint: "i1_MCP".into()
+                },
+                Problem::Unlimited {
+                    joint: "(unnamed)".into()
+                },
+                Problem::ActuatorWithoutJoint {
+                    actuator: "tendon_pull".into()
+                },
+            ]
+        );
+        assert!(!problems[0].is_error());
+        assert!(problems[2].is_error());
+    }
+
+    #[test]
+    fn repeated_names_are_counted_but_unnamed_ones_are_not() {
+        let mut joints = hand();
+        joints.push(joint("t1_TM", JointKind::Hinge, Some((0.0, 1.0))));
+        joints.push(joint("", JointKind::Hinge, Some((0.0, 1.0))));
+        let problems = check_model(&joints, &[]);
+        assert_eq!(
+            problems.last(),
+            Some(&Problem::Duplicate {
+                within: "the model's joints".into(),
+                name: "t1_TM".into(),
+                count: 2,
+            })
+        );
+        assert_eq!(
+            problems.last().unwrap().to_string(),
+            "t1_TM appears 2 times in the model's joints"
+        );
+    }
+
+    #[test]
+    fn a_file_naming_other_joints_is_reported_once_per_joint() {
+        let listed = ["t1_TM", "i2_PIP", "t1_TM", "i2_PIP"];
+        assert_eq!(
+            check_references(&hand(), "udp_layout.toml", listed),
+            [
+                Problem::MissingJoint {
+                    file: "udp_layout.toml".into(),
+                    joint: "i2_PIP".into(),
+                },
+                Problem::Duplicate {
+                    within: "udp_layout.toml".into(),
+                    name: "i2_PIP".into(),
+                    count: 2,
+                },
+                Problem::Duplicate {
+                    within: "udp_layout.toml".into(),
+                    name: "t1_TM".into(),
+                    count: 2,
+                },
+            ]
+        );
+        assert!(check_references(&hand(), "presets.toml", ["t1_TM", "root"]).is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! PD position control for `subscriber --mode pd`: received angles are targets,
+//! and each joint gets the generalized force
+//! `kp · (target − position) − kd · velocity` while MuJoCo steps the physics.
+//!
+//! Gains default to `--kp` / `--kd`; a gains file overrides them per joint:
+//!
+//! ```toml
+//! [joints.i1_MCP]
+//! kp = 8.0
+//! kd = 0.2
+//!
+//! [joints.t1_TM]
+//! kp = 12.0              # kd stays the default
+//! ```
+//!
+//! [`Tracking`] sums up how far the joints lag their targets, for the
+//! subscriber's periodic report.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// A gains file that could not be used.
+#[derive(Debug, Error)]
+pub enum GainsError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad gains file")]
+    Toml(#[from] toml::de::Error),
+}
+
+impl ExitStatus for GainsError {
+    fn exit(&self) -> Exit {
+        match self {
+            GainsError::Io { .. } => Exit::Io,
+            GainsError::Toml(_) => Exit::Data,
+        }
+    }
+}
+
+/// One joint's gains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gains {
+    /// Stiffness, in N·m/rad (N/m for slide joints).
+    pub kp: f64,
+    /// Damping, in N·m·s/rad (N·s/m for slide joints).
+    pub kd: f64,
+}
+
+impl Gains {
+    /// The force towards `target` for a joint at `position` moving at `velocity`.
+    pub fn force(&self, target: f64, position: f64, velocity: f64) -> f64 {
+        self.kp * (target - position) - self.kd * velocity
+    }
+}
+
+/// One `[joints.<name>]` table: whichever gains it sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GainsOverride {
+    kp: Option<f64>,
+    kd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GainsFile {
+    #[serde(default)]
+    joints: HashMap<String, GainsOverride>,
+}
+
+/// Every joint's gains: the defaults, except where a gains file says otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointGains {
+    default: Gains,
+    joints: HashMap<String, GainsOverride>,
+}
+
+impl JointGains {
+    /// `default` for every joint.
+    pub fn new(default: Gains) -> Self {
+        JointGains {
+            default,
+            joints: HashMap::new(),
+        }
+    }
+
+    /// `default` for every joint the gains file at `path` does not mention.
+    pub fn load(path: &Path, default: Gains) -> Result<Self, GainsError> {
+        let raw = fs::read_to_string(path).map_err(|source| GainsError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&raw, default)
+    }
+
+    /// Like [`JointGains::load`], from the file's text.
+    pub fn parse(raw: &str, default: Gains) -> Result<Self, GainsError> {
+        let file: GainsFile = toml::from_str(raw)?;
+        Ok(JointGains {
+            default,
+            joints: file.joints,
+        })
+    }
+
+    /// `joint`'s gains.
+    pub fn get(&self, joint: &str) -> Gains {
+        let set = self.joints.get(joint).copied().unwrap_or_default();
+        Gains {
+            kp: set.kp.unwrap_or(self.default.kp),
+            kd: set.kd.unwrap_or(self.default.kd),
+        }
+    }
+}
+
+/// Tracking error since the last [`Tracking::report`].
+#[derive(Debug, Default)]
+pub struct Tracking {
+    samples: u64,
+    sum_squares: f64,
+    worst: Option<(String, f64)>,
+}
+
+/// How far the joints were from their targets over one report period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackingReport {
+    /// Joint samples the report covers (joints × steps).
+    pub samples: u64,
+    /// Root mean square error over every sample, in radians.
+    pub rms_rad: f64,
+    /// The largest error, in radians, and the joint it was on.
+    pub max_rad: f64,
+    pub worst_joint: String,
+}
+
+impl Tracking {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One joint at one step, `error` = target − position.
+    pub fn record(&mut self, joint: &str, error: f64) {
+        self.samples += 1;
+        self.sum_squares += error * error;
+        if self
+            .worst
+            .as_ref()
+            .is_none_or(|(_, worst)| error.abs() > *worst)
+        {
+            self.worst = Some((joint.to_string(), error.abs()));
+        }
+    }
+
+    /// The errors recorded since the last report, if any were; starts over.
+    pub fn report(&mut self) -> Option<TrackingReport> {
+        let Tracking {
+            samples,
+            sum_squares,
+            worst,
+        } = std::mem::take(self);
+        let (worst_joint, max_rad) = worst?;
+        Some(TrackingReport {
+            samples,
+            rms_rad: (sum_squares / samples as f64).sqrt(),
+            max_rad,
+            worst_joint,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: Gains = Gains { kp: 10.0, kd: 0.5 };
+
+    #[test]
+    fn force_pulls_towards_the_target_and_damps() {
+        assert_eq!(DEFAULT.force(1.0, 0.5, 0.0), 5.0);
+        assert_eq!(DEFAULT.force(0.5, 0.5, 2.0), -1.0);
+        assert_eq!(DEFAULT.force(0.0, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn a_gains_file_overrides_the_defaults_per_joint() {
+        let gains = JointGains::parse(
+            "[joints.i1_MCP]\nkp = 8.0\nkd = 0.2\n\n[joints.t1_TM]\nkp = 12.0\n",
+            DEFAULT,
+        )
+        .unwrap();
+        assert_eq!(gains.get("i1_MCP"), Gains { kp: 8.0, kd: 0.2 });
+        assert_eq!(gains.get("t1_TM"), Gains { kp: 12.0, kd: 0.5 });
+        assert_eq!(gains.get("p1_MCP"), DEFAULT);
+        assert_eq!(JointGains::new(DEFAULT).get("i1_MCP"), DEFAULT);
+
+        assert!(matches!(
+            JointGains::parse("[joints.i1_MCP]\nkq = 8.0\n", DEFAULT),
+            Err(GainsError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn tracking_reports_rms_and_worst_then_starts_over() {
+        let mut tracking = Tracking::new();
+        assert_eq!(tracking.report(), None);
+        tracking.record("a", 0.3);
+        tracking.record("b", -0.4);
+        let report = tracking.report().unwrap();
+        assert_eq!(report.samples, 2);
+        assert!((report.rms_rad - 0.125_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(report.max_rad, 0.4);
+        assert_eq!(report.worst_joint, "b");
+        assert_eq!(tracking.report(), None);
+    }
+}
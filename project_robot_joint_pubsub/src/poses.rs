@@ -0,0 +1,126 @@
+//! Cartesian poses for consumers that want where the fingertips are, not the
+//! joint angles that put them there.
+//!
+//! The subscriber already runs MuJoCo's forward kinematics for every frame it
+//! draws; with `--poses-bind` it reads the tracked sites' (or bodies') world
+//! positions and orientations after each new state and publishes them as
+//! [`BodyPoses`] on [`TOPIC`], in the same formats as states (see
+//! [`Format::encode_poses`](crate::Format::encode_poses)).
+
+use crate::{BodyPose, BodyPoses, RobotState};
+
+/// The default topic poses are published on.
+pub const TOPIC: &str = "robot_poses";
+
+/// [`BodyPoses`] computed from `state`, one per `(name, position, orientation)`.
+pub fn from_state<'a>(
+    state: &RobotState,
+    bodies: impl IntoIterator<Item = (&'a str, [f64; 3], [f64; 4])>,
+) -> BodyPoses {
+    BodyPoses {
+        timestamp: state.timestamp,
+        robot_id: state.robot_id.clone(),
+        bodies: bodies
+            .into_iter()
+            .map(|(name, position, orientation)| BodyPose {
+                name: name.to_string(),
+                position,
+                orientation,
+            })
+            .collect(),
+        sent_at_us: None,
+    }
+}
+
+/// The unit quaternion `[w, x, y, z]` of a row-major 3×3 rotation matrix, as
+/// MuJoCo stores site and body orientations (`site_xmat`, `xmat`); `w` is never
+/// negative.
+pub fn quat_from_mat(m: &[f64; 9]) -> [f64; 4] {
+    let (xx, yy, zz) = (m[0], m[4], m[8]);
+    let trace = xx + yy + zz;
+    // Divide by the largest of 4w², 4x², 4y², 4z², which keeps the result
+    // accurate for rotations near 180°, where w is close to 0.
+    let q = if trace > 0.0 {
+        let s = 2.0 * (1.0 + trace).sqrt();
+        [
+            0.25 * s,
+            (m[7] - m[5]) / s,
+            (m[2] - m[6]) / s,
+            (m[3] - m[1]) / s,
+        ]
+    } else if xx > yy && xx > zz {
+        let s = 2.0 * (1.0 + xx - yy - zz).sqrt();
+        [
+            (m[7] - m[5]) / s,
+            0.25 * s,
+            (m[1] + m[3]) / s,
+            (m[2] + m[6]) / s,
+        ]
+    } else if yy > zz {
+        let s = 2.0 * (1.0 + yy - xx - zz).sqrt();
+        [
+            (m[2] - m[6]) / s,
+            (m[1] + m[3]) / s,
+            0.25 * s,
+            (m[5] + m[7]) / s,
+        ]
+    } else {
+        let s = 2.0 * (1.0 + zz - xx - yy).sqrt();
+        [
+            (m[3] - m[1]) / s,
+            (m[2] + m[6]) / s,
+            (m[5] + m[7]) / s,
+            0.25 * s,
+        ]
+    };
+    let norm = q.iter().map(|c| c * c).sum::<f64>().sqrt();
+    let sign = if q[0] < 0.0 { -1.0 } else { 1.0 };
+    q.map(|c| sign * c / norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    fn assert_close(actual: [f64; 4], expected: [f64; 4]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn quaternions_of_rotation_matrices() {
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        #[rustfmt::skip]
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        assert_close(quat_from_mat(&identity), [1.0, 0.0, 0.0, 0.0]);
+        // 90° about z: x goes to y.
+        #[rustfmt::skip]
+        let about_z = [0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        assert_close(quat_from_mat(&about_z), [half, 0.0, 0.0, half]);
+        // 180° turns take the branches that avoid dividing by w ≈ 0.
+        #[rustfmt::skip]
+        let about_x = [1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, -1.0];
+        assert_close(quat_from_mat(&about_x), [0.0, 1.0, 0.0, 0.0]);
+        #[rustfmt::skip]
+        let about_y = [-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0];
+        assert_close(quat_from_mat(&about_y), [0.0, 0.0, 1.0, 0.0]);
+        #[rustfmt::skip]
+        let about_z = [-1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0];
+        assert_close(quat_from_mat(&about_z), [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn poses_carry_the_state_they_came_from() {
+        let state = RobotState {
+            timestamp: 1_760_000_000_000_000_012,
+            ..simulated_state(12)
+        };
+        let poses = from_state(&state, [("tip", [0.1, 0.2, 0.3], [1.0, 0.0, 0.0, 0.0])]);
+        assert_eq!(poses.timestamp, 1_760_000_000_000_000_012);
+        assert_eq!(poses.robot_id, state.robot_id);
+        assert_eq!(poses.bodies[0].name, "tip");
+        assert_eq!(poses.bodies[0].position, [0.1, 0.2, 0.3]);
+    }
+}
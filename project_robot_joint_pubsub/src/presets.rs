@@ -0,0 +1,149 @@
+//! Named poses saved to disk, for the GUI publisher's preset buttons next to
+//! its built-in ones.
+//!
+//! A presets file is TOML, one table per preset keyed by joint name, in radians:
+//!
+//! ```toml
+//! [presets.pinch]
+//! i1_MCP = 0.9
+//! t1_TM = 0.7
+//! ```
+//!
+//! Applying a preset sets only the joints it names; the rest stay where they are.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use errors::{Exit, ExitStatus};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A presets file that could not be read or written.
+#[derive(Debug, Error)]
+pub enum PresetsError {
+    #[error("reading {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("writing {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad presets file")]
+    Toml(#[from] toml::de::Error),
+    #[error("presets do not serialize to TOML")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+impl ExitStatus for PresetsError {
+    fn exit(&self) -> Exit {
+        match self {
+            PresetsError::Read { .. } | PresetsError::Write { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// Joint angles in radians, by joint name.
+pub type Pose = BTreeMap<String, f64>;
+
+/// Every saved pose, by preset name (listed alphabetically).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Presets {
+    #[serde(default)]
+    presets: BTreeMap<String, Pose>,
+}
+
+impl Presets {
+    /// Read a presets file; one that does not exist yet holds no presets.
+    pub fn load(path: &Path) -> Result<Self, PresetsError> {
+        match fs::read_to_string(path) {
+            Ok(raw) => raw.parse(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Presets::default()),
+            Err(source) => Err(PresetsError::Read {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Write every preset to `path`, replacing what it held.
+    pub fn save(&self, path: &Path) -> Result<(), PresetsError> {
+        let raw = toml::to_string_pretty(self)?;
+        fs::write(path, raw).map_err(|source| PresetsError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Add `pose` as `name`, replacing any preset of that name.
+    pub fn insert(&mut self, name: &str, pose: Pose) {
+        self.presets.insert(name.to_string(), pose);
+    }
+
+    /// Every `(name, pose)`, alphabetically.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Pose)> {
+        self.presets
+            .iter()
+            .map(|(name, pose)| (name.as_str(), pose))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+}
+
+impl std::str::FromStr for Presets {
+    type Err = PresetsError;
+
+    fn from_str(raw: &str) -> Result<Self, PresetsError> {
+        Ok(toml::from_str(raw)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_a_file() {
+        let path = std::env::temp_dir().join(format!("presets-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert!(Presets::load(&path).unwrap().is_empty());
+
+        let mut presets = Presets::default();
+        presets.insert(
+            "pinch",
+            Pose::from([("i1_MCP".into(), 0.9), ("t1_TM".into(), 0.7)]),
+        );
+        presets.insert("flat", Pose::from([("i1_MCP".into(), 0.0)]));
+        presets.save(&path).unwrap();
+        let loaded = Presets::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, presets);
+        let names: Vec<_> = loaded.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["flat", "pinch"]);
+    }
+
+    #[test]
+    fn reads_hand_written_files() {
+        let presets: Presets = "[presets.pinch]\ni1_MCP = 0.9\n\"hand/t1_TM\" = 0.7\n"
+            .parse()
+            .unwrap();
+        let (name, pose) = presets.iter().next().unwrap();
+        assert_eq!(name, "pinch");
+        assert_eq!(pose["hand/t1_TM"], 0.7);
+        assert!(matches!(
+            "[pinch]\ni1_MCP = 0.9\n".parse::<Presets>(),
+            Err(PresetsError::Toml(_))
+        ));
+    }
+}
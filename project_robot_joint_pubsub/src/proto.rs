@@ -0,0 +1,218 @@
+//! Protobuf versions of the wire types, matching `proto/robot_joint_pubsub.proto`.
+//!
+//! Written by hand with `prost`'s derive rather than generated by `prost-build`,
+//! so building needs no `protoc`. `cargo xtask gen-proto` owns the `.proto` file
+//! and its tests check these field tags against it.
+//!
+//! The last three types are only the gRPC service's requests and replies (see
+//! `grpc`, feature `grpc`); they have no JSON counterpart.
+
+use std::collections::BTreeMap;
+
+use crate::{BodyPose, BodyPoses, JointAngles, RobotState, RobotStateBatch};
+
+/// `robot_joint_pubsub.JointAngles`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoJointAngles {
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+    #[prost(string, tag = "2")]
+    pub joint_name: String,
+    #[prost(double, tag = "3")]
+    pub angle_rad: f64,
+    #[prost(double, tag = "4")]
+    pub velocity: f64,
+    #[prost(double, tag = "5")]
+    pub torque: f64,
+    #[prost(double, repeated, tag = "6")]
+    pub qpos: Vec<f64>,
+}
+
+/// `robot_joint_pubsub.RobotState`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoRobotState {
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+    #[prost(string, tag = "2")]
+    pub robot_id: String,
+    #[prost(message, repeated, tag = "3")]
+    pub joints: Vec<ProtoJointAngles>,
+    #[prost(uint64, optional, tag = "4")]
+    pub sent_at_us: Option<u64>,
+    #[prost(uint64, optional, tag = "6")]
+    pub sequence: Option<u64>,
+}
+
+/// `robot_joint_pubsub.RobotStateBatch`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoRobotStateBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub states: Vec<ProtoRobotState>,
+}
+
+/// `robot_joint_pubsub.BodyPose`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoBodyPose {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(double, repeated, tag = "2")]
+    pub position: Vec<f64>,
+    #[prost(double, repeated, tag = "3")]
+    pub orientation: Vec<f64>,
+}
+
+/// `robot_joint_pubsub.BodyPoses`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoBodyPoses {
+    #[prost(uint64, tag = "1")]
+    pub timestamp: u64,
+    #[prost(string, tag = "2")]
+    pub robot_id: String,
+    #[prost(message, repeated, tag = "3")]
+    pub bodies: Vec<ProtoBodyPose>,
+    #[prost(uint64, optional, tag = "4")]
+    pub sent_at_us: Option<u64>,
+}
+
+/// `robot_joint_pubsub.StreamStatesRequest`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoStreamStatesRequest {
+    #[prost(string, tag = "1")]
+    pub robot_id: String,
+}
+
+/// `robot_joint_pubsub.JointCommand`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoJointCommand {
+    #[prost(string, tag = "1")]
+    pub robot_id: String,
+    #[prost(btree_map = "string, double", tag = "2")]
+    pub angles_rad: BTreeMap<String, f64>,
+}
+
+/// `robot_joint_pubsub.CommandSummary`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoCommandSummary {
+    #[prost(uint64, tag = "1")]
+    pub accepted: u64,
+}
+
+impl From<&JointAngles> for ProtoJointAngles {
+    fn from(joint: &JointAngles) -> Self {
+        ProtoJointAngles {
+            timestamp: joint.timestamp,
+            joint_name: joint.joint_name.clone(),
+            angle_rad: joint.angle_rad,
+            velocity: joint.velocity,
+            torque: joint.torque,
+            qpos: joint.qpos.clone(),
+        }
+    }
+}
+
+impl From<ProtoJointAngles> for JointAngles {
+    fn from(joint: ProtoJointAngles) -> Self {
+        JointAngles {
+            timestamp: joint.timestamp,
+            joint_name: joint.joint_name,
+            angle_rad: joint.angle_rad,
+            velocity: joint.velocity,
+            torque: joint.torque,
+            qpos: joint.qpos,
+        }
+    }
+}
+
+impl From<&RobotState> for ProtoRobotState {
+    fn from(state: &RobotState) -> Self {
+        ProtoRobotState {
+            timestamp: state.timestamp,
+            robot_id: state.robot_id.clone(),
+            joints: state.joints.iter().map(ProtoJointAngles::from).collect(),
+            sent_at_us: state.sent_at_us,
+            sequence: state.sequence,
+        }
+    }
+}
+
+impl From<ProtoRobotState> for RobotState {
+    fn from(state: ProtoRobotState) -> Self {
+        RobotState {
+            timestamp: state.timestamp,
+            robot_id: state.robot_id,
+            joints: state.joints.into_iter().map(JointAngles::from).collect(),
+            sent_at_us: state.sent_at_us,
+            sequence: state.sequence,
+        }
+    }
+}
+
+impl From<&RobotStateBatch> for ProtoRobotStateBatch {
+    fn from(batch: &RobotStateBatch) -> Self {
+        ProtoRobotStateBatch {
+            states: batch.states.iter().map(ProtoRobotState::from).collect(),
+        }
+    }
+}
+
+impl From<ProtoRobotStateBatch> for RobotStateBatch {
+    fn from(batch: ProtoRobotStateBatch) -> Self {
+        RobotStateBatch {
+            states: batch.states.into_iter().map(RobotState::from).collect(),
+        }
+    }
+}
+
+impl From<&BodyPose> for ProtoBodyPose {
+    fn from(pose: &BodyPose) -> Self {
+        ProtoBodyPose {
+            name: pose.name.clone(),
+            position: pose.position.to_vec(),
+            orientation: pose.orientation.to_vec(),
+        }
+    }
+}
+
+impl From<ProtoBodyPose> for BodyPose {
+    /// Missing components are 0 and extra ones are dropped: protobuf cannot
+    /// say that a repeated field has exactly three or four values.
+    fn from(pose: ProtoBodyPose) -> Self {
+        let mut position = [0.0; 3];
+        let mut orientation = [0.0; 4];
+        position
+            .iter_mut()
+            .zip(pose.position)
+            .for_each(|(to, from)| *to = from);
+        orientation
+            .iter_mut()
+            .zip(pose.orientation)
+            .for_each(|(to, from)| *to = from);
+        BodyPose {
+            name: pose.name,
+            position,
+            orientation,
+        }
+    }
+}
+
+impl From<&BodyPoses> for ProtoBodyPoses {
+    fn from(poses: &BodyPoses) -> Self {
+        ProtoBodyPoses {
+            timestamp: poses.timestamp,
+            robot_id: poses.robot_id.clone(),
+            bodies: poses.bodies.iter().map(ProtoBodyPose::from).collect(),
+            sent_at_us: poses.sent_at_us,
+        }
+    }
+}
+
+impl From<ProtoBodyPoses> for BodyPoses {
+    fn from(poses: ProtoBodyPoses) -> Self {
+        BodyPoses {
+            timestamp: poses.timestamp,
+            robot_id: poses.robot_id,
+            bodies: poses.bodies.into_iter().map(BodyPose::from).collect(),
+            sent_at_us: poses.sent_at_us,
+        }
+    }
+}
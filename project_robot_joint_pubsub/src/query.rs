@@ -0,0 +1,187 @@
+//! A ZMQ REP socket answering questions about the current robot state, so
+//! tools can poll one value instead of subscribing to the whole stream.
+//!
+//! A request is one frame holding the request name as text; the reply is one
+//! JSON object:
+//!
+//! | Request | Reply |
+//! |---|---|
+//! | `get_state` | `{"state": <RobotState>}`, or `{"state": null}` before the first one |
+//! | `get_joint_names` | `{"joint_names": ["finger_1_joint", ...]}` |
+//! | `get_model_info` | `{"model_info": <ModelInfo>}` |
+//!
+//! Anything else gets `{"error": "...", "requests": [...]}`. The subscriber and
+//! the GUI publisher serve these with `--query <endpoint>`.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::transport::TransportError;
+use crate::RobotState;
+
+/// What a client can ask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    GetState,
+    GetJointNames,
+    GetModelInfo,
+}
+
+impl Request {
+    /// Every request name, for the reply to an unknown one.
+    pub const NAMES: [&str; 3] = ["get_state", "get_joint_names", "get_model_info"];
+}
+
+impl FromStr for Request {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        match raw.trim() {
+            "get_state" => Ok(Request::GetState),
+            "get_joint_names" => Ok(Request::GetJointNames),
+            "get_model_info" => Ok(Request::GetModelInfo),
+            other => Err(format!("unknown request {other:?}")),
+        }
+    }
+}
+
+/// The MuJoCo model a binary runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModelInfo {
+    /// MJCF file, as given on the command line or in the config.
+    pub path: String,
+    /// Joints in the model.
+    pub joints: usize,
+    /// Position coordinates (`nq`).
+    pub nq: usize,
+    /// Degrees of freedom (`nv`).
+    pub nv: usize,
+    /// Simulation timestep in seconds.
+    pub timestep: f64,
+}
+
+/// Everything a request can be answered from, borrowed for one
+/// [`QueryServer::serve_pending`] call.
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot<'a> {
+    /// The latest state received (subscriber) or sent (GUI publisher).
+    pub state: Option<&'a RobotState>,
+    pub joint_names: &'a [String],
+    pub model_info: &'a ModelInfo,
+}
+
+/// The JSON reply to the raw `request`.
+pub fn answer(request: &[u8], snapshot: &Snapshot) -> String {
+    let request = std::str::from_utf8(request)
+        .map_err(|_| "request is not UTF-8 text".to_string())
+        .and_then(str::parse);
+    let reply = match request {
+        Ok(Request::GetState) => json!({ "state": snapshot.state }),
+        Ok(Request::GetJointNames) => json!({ "joint_names": snapshot.joint_names }),
+        Ok(Request::GetModelInfo) => json!({ "model_info": snapshot.model_info }),
+        Err(error) => json!({ "error": error, "requests": Request::NAMES }),
+    };
+    reply.to_string()
+}
+
+/// A bound REP socket, polled from a binary's main loop.
+pub struct QueryServer {
+    socket: zmq::Socket,
+}
+
+impl QueryServer {
+    /// Bind a REP socket at `endpoint`.
+    pub fn bind(ctx: &zmq::Context, endpoint: &str) -> Result<Self, TransportError> {
+        let socket = ctx.socket(zmq::REP)?;
+        socket.set_linger(0)?;
+        socket.bind(endpoint)?;
+        Ok(QueryServer { socket })
+    }
+
+    /// Answer every request already waiting, without blocking; returns how many.
+    pub fn serve_pending(&self, snapshot: &Snapshot) -> Result<usize, TransportError> {
+        let mut served = 0;
+        loop {
+            let request = match self.socket.recv_bytes(zmq::DONTWAIT) {
+                Ok(request) => request,
+                Err(zmq::Error::EAGAIN) => return Ok(served),
+                Err(e) => return Err(e.into()),
+            };
+            self.socket.send(answer(&request, snapshot).as_bytes(), 0)?;
+            served += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sim::simulated_state;
+
+    fn reply(request: &str, snapshot: &Snapshot) -> serde_json::Value {
+        serde_json::from_str(&answer(request.as_bytes(), snapshot)).unwrap()
+    }
+
+    #[test]
+    fn answers_each_request_as_json() {
+        let state = simulated_state(4);
+        let joint_names = vec!["finger_1".to_string(), "finger_2".to_string()];
+        let model_info = ModelInfo {
+            path: "hand.xml".to_string(),
+            joints: 2,
+            nq: 2,
+            nv: 2,
+            timestep: 0.002,
+        };
+        let mut snapshot = Snapshot {
+            state: None,
+            joint_names: &joint_names,
+            model_info: &model_info,
+        };
+        assert_eq!(reply("get_state", &snapshot), json!({ "state": null }));
+        snapshot.state = Some(&state);
+        assert_eq!(reply("get_state\n", &snapshot)["state"]["sequence"], 4);
+        assert_eq!(
+            reply("get_joint_names", &snapshot),
+            json!({ "joint_names": ["finger_1", "finger_2"] })
+        );
+        assert_eq!(
+            reply("get_model_info", &snapshot)["model_info"]["timestep"],
+            0.002
+        );
+        let unknown = reply("get_everything", &snapshot);
+        assert_eq!(unknown["error"], "unknown request \"get_everything\"");
+        assert_eq!(unknown["requests"][0], "get_state");
+    }
+
+    #[test]
+    fn serves_requests_over_rep() {
+        let ctx = zmq::Context::new();
+        let server = QueryServer::bind(&ctx, "inproc://query-test").unwrap();
+        let client = ctx.socket(zmq::REQ).unwrap();
+        client.connect("inproc://query-test").unwrap();
+        let model_info = ModelInfo::default();
+        let snapshot = Snapshot {
+            state: None,
+            joint_names: &[],
+            model_info: &model_info,
+        };
+
+        assert_eq!(server.serve_pending(&snapshot).unwrap(), 0);
+        client.send("get_joint_names", 0).unwrap();
+        // Poll the way a render loop would until the request has arrived.
+        let served = (0..100)
+            .map(|_| {
+                std::thread::sleep(Duration::from_millis(1));
+                server.serve_pending(&snapshot).unwrap()
+            })
+            .find(|&served| served > 0);
+        assert_eq!(served, Some(1));
+        let reply = client.recv_string(0).unwrap().unwrap();
+        assert_eq!(reply, r#"{"joint_names":[]}"#);
+    }
+}
@@ -0,0 +1,190 @@
+//! Random test poses for the GUI publisher's "Random pose" button: every
+//! joint at a random angle within its range, the same again for the same seed,
+//! so a pose that tripped up a consumer can be sent once more.
+//!
+//! [`Constraints`] keep the poses physically sane. They can be set in the
+//! window or read from a TOML file (`--random-constraints`):
+//!
+//! ```toml
+//! exclude_abduction = true   # leave joints with `abd` in their name alone (default false)
+//! max_fraction = 0.8         # use only the middle 80% of each range (default 1)
+//! exclude = ["t1_TM"]        # more joints to leave alone (default none)
+//! ```
+//!
+//! Joints left alone are not in the pose, so they stay where they are.
+
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::presets::Pose;
+
+/// A constraints file that could not be used.
+#[derive(Debug, Error)]
+pub enum ConstraintsError {
+    #[error("reading {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad random pose constraints")]
+    Toml(#[from] toml::de::Error),
+    #[error("max_fraction must be more than 0 and at most 1, got {0}")]
+    MaxFraction(f64),
+}
+
+impl ExitStatus for ConstraintsError {
+    fn exit(&self) -> Exit {
+        match self {
+            ConstraintsError::Read { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// Which joints a random pose moves, and how far.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Constraints {
+    /// Leave every joint with `abd` in its name (in any case) alone.
+    pub exclude_abduction: bool,
+    /// Draw from the middle `max_fraction` of each joint's range, keeping
+    /// clear of both limits.
+    pub max_fraction: f64,
+    /// Joints to leave alone, by full name.
+    pub exclude: Vec<String>,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            exclude_abduction: false,
+            max_fraction: 1.0,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Constraints {
+    type Err = ConstraintsError;
+
+    fn from_str(raw: &str) -> Result<Self, ConstraintsError> {
+        let constraints: Constraints = toml::from_str(raw)?;
+        if !(constraints.max_fraction > 0.0 && constraints.max_fraction <= 1.0) {
+            return Err(ConstraintsError::MaxFraction(constraints.max_fraction));
+        }
+        Ok(constraints)
+    }
+}
+
+impl Constraints {
+    /// Read a constraints file.
+    pub fn load(path: &Path) -> Result<Self, ConstraintsError> {
+        let raw = fs::read_to_string(path).map_err(|source| ConstraintsError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// Whether a random pose moves `joint`.
+    pub fn moves(&self, joint: &str) -> bool {
+        !(self.exclude_abduction && joint.to_ascii_lowercase().contains("abd")
+            || self.exclude.iter().any(|excluded| excluded == joint))
+    }
+}
+
+/// A random pose for `joints`, each given with its range in radians.
+///
+/// One number is drawn per joint in order, moved or not, so leaving a joint
+/// alone does not change the angles the others get from the same seed.
+pub fn random_pose<'a>(
+    joints: impl IntoIterator<Item = (&'a str, RangeInclusive<f64>)>,
+    seed: u64,
+    constraints: &Constraints,
+) -> Pose {
+    let mut rng = SplitMix64(seed);
+    let fraction = constraints.max_fraction.clamp(0.0, 1.0);
+    joints
+        .into_iter()
+        .filter_map(|(joint, range)| {
+            let u = rng.next_unit();
+            if !constraints.moves(joint) {
+                return None;
+            }
+            let (min, max) = (*range.start(), *range.end());
+            let margin = (max - min) * (1.0 - fraction) / 2.0;
+            let angle = min + margin + u * (max - min - 2.0 * margin);
+            Some((joint.to_string(), angle))
+        })
+        .collect()
+}
+
+/// SplitMix64: small, fast, and the same numbers for a seed on every platform
+/// and release, which a general-purpose RNG does not promise.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOINTS: [(&str, RangeInclusive<f64>); 3] = [
+        ("i0_CMC_abd", -0.3..=0.3),
+        ("i1_MCP", 0.0..=1.6),
+        ("t1_TM", -1.0..=1.0),
+    ];
+
+    #[test]
+    fn poses_repeat_per_seed_and_keep_to_the_constraints() {
+        let free = random_pose(JOINTS, 7, &Constraints::default());
+        assert_eq!(free.len(), 3);
+        assert_eq!(random_pose(JOINTS, 7, &Constraints::default()), free);
+        assert_ne!(random_pose(JOINTS, 8, &Constraints::default()), free);
+
+        let constraints: Constraints = "exclude_abduction = true\nmax_fraction = 0.5\n"
+            .parse()
+            .unwrap();
+        for seed in 0..100 {
+            let pose = random_pose(JOINTS, seed, &constraints);
+            assert_eq!(pose.keys().collect::<Vec<_>>(), ["i1_MCP", "t1_TM"]);
+            assert!((0.4..=1.2).contains(&pose["i1_MCP"]));
+            assert!((-0.5..=0.5).contains(&pose["t1_TM"]));
+        }
+
+        let excluded = Constraints {
+            exclude: vec!["t1_TM".into()],
+            ..Constraints::default()
+        };
+        let pose = random_pose(JOINTS, 7, &excluded);
+        assert_eq!(pose.len(), 2);
+        assert_eq!(pose["i1_MCP"], free["i1_MCP"]);
+
+        assert!(matches!(
+            "max_fraction = 0.0".parse::<Constraints>(),
+            Err(ConstraintsError::MaxFraction(_))
+        ));
+        assert!("exclude_abd = true".parse::<Constraints>().is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Recorded sessions: [`RobotState`]s with their receive times in an MCAP file.
+//!
+//! [MCAP](https://mcap.dev) is a self-contained log format that Foxglove and the
+//! `mcap` CLI open directly. A recording has one channel, named after the
+//! pub/sub topic, whose messages are the states as JSON (described by a JSON
+//! Schema, whatever format they arrived in), logged at the wall-clock time they
+//! were received. A `robot_joint_pubsub` metadata record holds the
+//! [`Session`] and the robot ids seen.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use errors::{Exit, ExitStatus};
+use mcap::records::{MessageHeader, Metadata};
+use mcap::{McapError, MessageStream, Summary, Writer};
+use thiserror::Error;
+
+use crate::wire::{Format, WireError};
+use crate::RobotState;
+
+/// Name of the metadata record describing the session.
+pub const METADATA_NAME: &str = "robot_joint_pubsub";
+
+/// Schema name, as for the protobuf message of the same shape.
+const SCHEMA_NAME: &str = "robot_joint_pubsub.RobotState";
+
+/// JSON Schema for the recorded messages, so viewers can show the fields.
+const JSON_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "timestamp": { "type": "integer" },
+    "robot_id": { "type": "string" },
+    "joints": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "properties": {
+          "timestamp": { "type": "integer" },
+          "joint_name": { "type": "string" },
+          "angle_rad": { "type": "number" },
+          "velocity": { "type": "number" },
+          "torque": { "type": "number" }
+        }
+      }
+    },
+    "sent_at_us": { "type": "integer" },
+    "sequence": { "type": "integer" }
+  }
+}"#;
+
+/// A recording that could not be written or read.
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("{action} {}", path.display())]
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad MCAP file")]
+    Mcap(#[source] Box<McapError>),
+    /// A message in the file is not a `RobotState`.
+    #[error("message {sequence} is not a RobotState")]
+    Message {
+        sequence: u32,
+        #[source]
+        source: WireError,
+    },
+}
+
+impl From<McapError> for RecordingError {
+    fn from(err: McapError) -> Self {
+        RecordingError::Mcap(Box::new(err))
+    }
+}
+
+impl ExitStatus for RecordingError {
+    fn exit(&self) -> Exit {
+        match self {
+            RecordingError::Io { .. } => Exit::Io,
+            RecordingError::Mcap(err) if matches!(**err, McapError::Io(_)) => Exit::Io,
+            RecordingError::Mcap(_) | RecordingError::Message { .. } => Exit::Data,
+        }
+    }
+}
+
+/// Where a recording came from, stored in its metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    /// The pub/sub topic, also the MCAP channel.
+    pub topic: String,
+    /// Where the states were received from (a ZMQ endpoint or MQTT broker).
+    pub source: String,
+    /// The MJCF model the states drive, if known.
+    pub model: Option<PathBuf>,
+}
+
+/// Writes received states to an MCAP file; call [`Recorder::finish`] when done.
+pub struct Recorder<W: Write + Seek> {
+    writer: Writer<W>,
+    channel: u16,
+    metadata: BTreeMap<String, String>,
+    robot_ids: BTreeSet<String>,
+    written: u32,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Create (or truncate) `path`, and its directory if needed.
+    pub fn create(path: &Path, session: &Session) -> Result<Self, RecordingError> {
+        let io_error = |action| {
+            move |source| RecordingError::Io {
+                action,
+                path: path.to_path_buf(),
+                source,
+            }
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(io_error("creating the directory for"))?;
+        }
+        let file = File::create(path).map_err(io_error("creating"))?;
+        Recorder::new(BufWriter::new(file), session)
+    }
+}
+
+impl<W: Write + Seek> Recorder<W> {
+    pub fn new(writer: W, session: &Session) -> Result<Self, RecordingError> {
+        let mut writer = Writer::new(writer)?;
+        let schema = writer.add_schema(SCHEMA_NAME, "jsonschema", JSON_SCHEMA.as_bytes())?;
+        let channel = writer.add_channel(schema, &session.topic, "json", &BTreeMap::new())?;
+        let mut metadata = BTreeMap::from([
+            ("topic".to_string(), session.topic.clone()),
+            ("source".to_string(), session.source.clone()),
+        ]);
+        if let Some(model) = &session.model {
+            metadata.insert("model".to_string(), model.display().to_string());
+        }
+        Ok(Recorder {
+            writer,
+            channel,
+            metadata,
+            robot_ids: BTreeSet::new(),
+            written: 0,
+        })
+    }
+
+    /// Append `state`, received at `received`.
+    pub fn record(
+        &mut self,
+        state: &RobotState,
+        received: SystemTime,
+    ) -> Result<(), RecordingError> {
+        let log_time = nanos_since_epoch(received);
+        let header = MessageHeader {
+            channel_id: self.channel,
+            sequence: self.written,
+            log_time,
+            publish_time: log_time,
+        };
+        self.writer
+            .write_to_known_channel(&header, &Format::Json.encode(state))?;
+        if !self.robot_ids.contains(&state.robot_id) {
+            self.robot_ids.insert(state.robot_id.clone());
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// States recorded so far.
+    pub fn len(&self) -> u32 {
+        self.written
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.written == 0
+    }
+
+    /// Write the metadata and the file's index, and flush; returns the writer.
+    pub fn finish(mut self) -> Result<W, RecordingError> {
+        let mut metadata = std::mem::take(&mut self.metadata);
+        let robot_ids: Vec<_> = self.robot_ids.iter().map(String::as_str).collect();
+        metadata.insert("robot_id".to_string(), robot_ids.join(","));
+        self.writer.write_metadata(&Metadata {
+            name: METADATA_NAME.to_string(),
+            metadata,
+        })?;
+        self.writer.finish()?;
+        let mut inner = self.writer.into_inner();
+        inner.flush().map_err(McapError::Io)?;
+        Ok(inner)
+    }
+}
+
+/// One recorded state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    /// Receive time, in nanoseconds since the Unix epoch.
+    pub log_time: u64,
+    pub state: RobotState,
+}
+
+/// A whole recording, read back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// The session metadata (`topic`, `source`, `robot_id`, and `model` if known).
+    pub metadata: BTreeMap<String, String>,
+    /// The states in the order they were recorded.
+    pub states: Vec<Recorded>,
+}
+
+impl Recording {
+    /// Read a recording file.
+    pub fn open(path: &Path) -> Result<Self, RecordingError> {
+        let bytes = fs::read(path).map_err(|source| RecordingError::Io {
+            action: "reading",
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Recording::from_bytes(&bytes)
+    }
+
+    /// Parse the contents of a recording file.
+    pub fn from_bytes(mcap: &[u8]) -> Result<Self, RecordingError> {
+        let mut metadata = BTreeMap::new();
+        if let Some(summary) = Summary::read(mcap)? {
+            for index in summary
+                .metadata_indexes
+                .iter()
+                .filter(|index| index.name == METADATA_NAME)
+            {
+                metadata.extend(mcap::read::metadata(mcap, index)?.metadata);
+            }
+        }
+        let states = MessageStream::new(mcap)?
+            .map(|message| {
+                let message = message?;
+                let state = Format::Json.decode(&message.data).map_err(|source| {
+                    RecordingError::Message {
+                        sequence: message.sequence,
+                        source,
+                    }
+                })?;
+                Ok(Recorded {
+                    log_time: message.log_time,
+                    state,
+                })
+            })
+            .collect::<Result<_, RecordingError>>()?;
+        Ok(Recording { metadata, states })
+    }
+
+    /// Time from the first state to the last.
+    pub fn duration(&self) -> Duration {
+        match (self.states.first(), self.states.last()) {
+            (Some(first), Some(last)) => Duration::from_nanos(last.log_time - first.log_time),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// The states from `offset` into the recording on, each with how long after
+    /// playback starts it is due when played at `rate` times real time.
+    pub fn schedule(&self, offset: Duration, rate: f64) -> Vec<(Duration, &RobotState)> {
+        let Some(first) = self.states.first() else {
+            return Vec::new();
+        };
+        let start = first.log_time + offset.as_nanos() as u64;
+        self.states
+            .iter()
+            .filter(|recorded| recorded.log_time >= start)
+            .map(|recorded| {
+                let due = Duration::from_nanos(recorded.log_time - start).div_f64(rate);
+                (due, &recorded.state)
+            })
+            .collect()
+    }
+}
+
+fn nanos_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sim::simulated_state;
+
+    #[test]
+    fn round_trips_states_and_metadata() {
+        let session = Session {
+            topic: "robot_joints".to_string(),
+            source: "tcp://localhost:5555".to_string(),
+            model: Some(PathBuf::from(
+                "pro-models/example/scenes/left_hand_scene.xml",
+            )),
+        };
+        let mut recorder = Recorder::new(Cursor::new(Vec::new()), &session).unwrap();
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for timestamp in 1..=3 {
+            let received = start + Duration::from_millis(20 * timestamp);
+            recorder
+                .record(&simulated_state(timestamp), received)
+                .unwrap();
+        }
+        assert_eq!(recorder.len(), 3);
+        let mcap = recorder.finish().unwrap().into_inner();
+
+        let recording = Recording::from_bytes(&mcap).unwrap();
+        assert_eq!(recording.metadata["topic"], "robot_joints");
+        assert_eq!(recording.metadata["source"], "tcp://localhost:5555");
+        assert_eq!(
+            recording.metadata["model"],
+            "pro-models/example/scenes/left_hand_scene.xml"
+        );
+        assert_eq!(recording.metadata["robot_id"], simulated_state(1).robot_id);
+        let sequences: Vec<_> = recording.states.iter().map(|r| r.state.sequence).collect();
+        assert_eq!(sequences, [Some(1), Some(2), Some(3)]);
+        assert_eq!(
+            recording.states[1].log_time - recording.states[0].log_time,
+            20_000_000
+        );
+    }
+
+    #[test]
+    fn schedules_from_an_offset_at_a_rate() {
+        let states = (0..5)
+            .map(|i| Recorded {
+                log_time: 1_000_000_000 + i * 100_000_000,
+                state: simulated_state(i + 1),
+            })
+            .collect();
+        let recording = Recording {
+            metadata: BTreeMap::new(),
+            states,
+        };
+        assert_eq!(recording.duration(), Duration::from_millis(400));
+
+        let schedule = recording.schedule(Duration::from_millis(150), 0.5);
+        let due: Vec<_> = schedule.iter().map(|(due, _)| due.as_millis()).collect();
+        // 200, 300, 400 ms in, started 150 ms in and played at half speed.
+        assert_eq!(due, [100, 300, 500]);
+        assert_eq!(schedule[0].1.sequence, Some(3));
+        assert_eq!(
+            recording.schedule(Duration::ZERO, 2.0)[4].0.as_millis(),
+            200
+        );
+        assert!(recording.schedule(Duration::from_secs(1), 1.0).is_empty());
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_mcap() {
+        let err = Recording::from_bytes(b"{\"timestamp\":1}").unwrap_err();
+        assert!(matches!(err, RecordingError::Mcap(_)), "{err:?}");
+        assert_eq!(err.exit(), Exit::Data);
+    }
+}
@@ -0,0 +1,278 @@
+//! A safety filter between received angles and the model: commanded positions
+//! may only change so fast, and speed up or slow down so quickly, so a jump
+//! (a GUI preset, a glitch in a trajectory) becomes a ramp instead of a
+//! teleport. Used by `subscriber --safety`.
+//!
+//! Limits come from a TOML file: defaults for every joint, overridden per joint.
+//!
+//! ```toml
+//! max_velocity = 3.0          # rad/s
+//! max_acceleration = 30.0     # rad/s²
+//!
+//! [joints.t1_TM]
+//! max_velocity = 1.5          # max_acceleration stays the default
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// A limits file that could not be used.
+#[derive(Debug, Error)]
+pub enum SafetyLimitsError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad safety limits file")]
+    Toml(#[from] toml::de::Error),
+    /// A limit of zero or less would hold the joint still forever.
+    #[error("{joint}: {limit} must be positive, got {value}")]
+    NotPositive {
+        joint: String,
+        limit: &'static str,
+        value: f64,
+    },
+}
+
+impl ExitStatus for SafetyLimitsError {
+    fn exit(&self) -> Exit {
+        match self {
+            SafetyLimitsError::Io { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// One joint's limits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// In rad/s (m/s for slide joints).
+    pub max_velocity: f64,
+    /// In rad/s² (m/s² for slide joints).
+    pub max_acceleration: f64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_velocity: 3.0,
+            max_acceleration: 30.0,
+        }
+    }
+}
+
+/// Whichever limits one part of the file sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LimitsOverride {
+    max_velocity: Option<f64>,
+    max_acceleration: Option<f64>,
+}
+
+impl LimitsOverride {
+    fn over(self, limits: Limits) -> Limits {
+        Limits {
+            max_velocity: self.max_velocity.unwrap_or(limits.max_velocity),
+            max_acceleration: self.max_acceleration.unwrap_or(limits.max_acceleration),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LimitsFile {
+    #[serde(flatten)]
+    default: LimitsOverride,
+    #[serde(default)]
+    joints: HashMap<String, LimitsOverride>,
+}
+
+/// Every joint's limits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SafetyLimits {
+    default: Limits,
+    joints: HashMap<String, Limits>,
+}
+
+impl SafetyLimits {
+    /// Read a limits file.
+    pub fn load(path: &Path) -> Result<Self, SafetyLimitsError> {
+        let raw = fs::read_to_string(path).map_err(|source| SafetyLimitsError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// `joint`'s limits.
+    pub fn get(&self, joint: &str) -> Limits {
+        self.joints.get(joint).copied().unwrap_or(self.default)
+    }
+}
+
+impl std::str::FromStr for SafetyLimits {
+    type Err = SafetyLimitsError;
+
+    fn from_str(raw: &str) -> Result<Self, SafetyLimitsError> {
+        let file: LimitsFile = toml::from_str(raw)?;
+        let default = file.default.over(Limits::default());
+        let joints: HashMap<_, _> = file
+            .joints
+            .into_iter()
+            .map(|(joint, set)| (joint, set.over(default)))
+            .collect();
+        let every = std::iter::once(("(default)", &default)).chain(
+            joints
+                .iter()
+                .map(|(joint, limits)| (joint.as_str(), limits)),
+        );
+        for (joint, limits) in every {
+            for (limit, value) in [
+                ("max_velocity", limits.max_velocity),
+                ("max_acceleration", limits.max_acceleration),
+            ] {
+                if value.is_nan() || value <= 0.0 {
+                    return Err(SafetyLimitsError::NotPositive {
+                        joint: joint.to_string(),
+                        limit,
+                        value,
+                    });
+                }
+            }
+        }
+        Ok(SafetyLimits { default, joints })
+    }
+}
+
+/// Where one joint's filtered command is and how fast it is moving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Motion {
+    position: f64,
+    velocity: f64,
+}
+
+/// Rate-limits commanded positions joint by joint.
+#[derive(Debug, Clone)]
+pub struct SafetyFilter {
+    limits: SafetyLimits,
+    joints: HashMap<String, Motion>,
+}
+
+impl SafetyFilter {
+    pub fn new(limits: SafetyLimits) -> Self {
+        SafetyFilter {
+            limits,
+            joints: HashMap::new(),
+        }
+    }
+
+    /// The position to command `dt` seconds after the last one, heading for
+    /// `target` within `joint`'s limits. `key` tells joints apart (two robots
+    /// may share joint names); a key seen for the first time, or since
+    /// [`SafetyFilter::reset`], starts from `current` at rest.
+    pub fn apply(&mut self, key: &str, joint: &str, target: f64, current: f64, dt: f64) -> f64 {
+        let limits = self.limits.get(joint);
+        let motion = match self.joints.get_mut(key) {
+            Some(motion) => motion,
+            None => self.joints.entry(key.to_string()).or_insert(Motion {
+                position: current,
+                velocity: 0.0,
+            }),
+        };
+        if dt <= 0.0 {
+            return motion.position;
+        }
+        let error = target - motion.position;
+        // As fast as allowed, but slow enough to stop at the target: braking from
+        // k steps' worth of speed change covers k(k+1)/2 of them times a·dt².
+        let step = limits.max_acceleration * dt;
+        let steps = ((1.0 + 8.0 * error.abs() / (step * dt)).sqrt() - 1.0) / 2.0;
+        let stopping = step * steps;
+        let speed = limits.max_velocity.min(stopping).min(error.abs() / dt);
+        let wanted = speed.copysign(error);
+        motion.velocity = wanted.clamp(motion.velocity - step, motion.velocity + step);
+        motion.position += motion.velocity * dt;
+        motion.position
+    }
+
+    /// Forget every joint, so each starts again from its `current` position.
+    pub fn reset(&mut self) {
+        self.joints.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.01;
+
+    #[test]
+    fn reads_defaults_and_per_joint_overrides() {
+        let limits: SafetyLimits = "max_velocity = 2.0\n\n[joints.t1_TM]\nmax_acceleration = 5.0\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            limits.get("t1_TM"),
+            Limits {
+                max_velocity: 2.0,
+                max_acceleration: 5.0
+            }
+        );
+        assert_eq!(limits.get("i1_MCP").max_acceleration, 30.0);
+        assert_eq!("".parse::<SafetyLimits>().unwrap(), SafetyLimits::default());
+
+        assert!(matches!(
+            "[joints.a]\nmax_velocity = 0.0\n".parse::<SafetyLimits>(),
+            Err(SafetyLimitsError::NotPositive { joint, .. }) if joint == "a"
+        ));
+        assert!(matches!(
+            "max_speed = 1.0\n".parse::<SafetyLimits>(),
+            Err(SafetyLimitsError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn a_jump_becomes_a_ramp_within_the_limits() {
+        let limits: SafetyLimits = "max_velocity = 2.0\nmax_acceleration = 20.0\n"
+            .parse()
+            .unwrap();
+        let mut filter = SafetyFilter::new(limits);
+        let mut previous = (0.0, 0.0);
+        let mut steps = 0;
+        loop {
+            let position = filter.apply("hand/i1", "i1", 1.0, 0.0, DT);
+            let velocity = (position - previous.0) / DT;
+            assert!(velocity.abs() <= 2.0 + 1e-9, "{velocity}");
+            assert!((velocity - previous.1).abs() <= 20.0 * DT + 1e-9);
+            assert!(position <= 1.0 + 1e-9, "overshot: {position}");
+            previous = (position, velocity);
+            steps += 1;
+            if (position - 1.0).abs() < 1e-6 || steps == 200 {
+                break;
+            }
+        }
+        // Half a second at 2 rad/s, plus a tenth of one each way to speed up and slow down.
+        assert!((55..=70).contains(&steps), "{steps}");
+    }
+
+    #[test]
+    fn small_moves_pass_and_new_joints_start_where_they_are() {
+        let mut filter = SafetyFilter::new(SafetyLimits::default());
+        assert_eq!(filter.apply("a", "a", 0.5, 0.5, DT), 0.5);
+        // A tiny step is within every limit, so it arrives in one go.
+        let moved = filter.apply("a", "a", 0.5001, 0.0, DT);
+        assert!((moved - 0.5001).abs() < 1e-12);
+
+        filter.reset();
+        assert!(filter.apply("a", "a", 3.0, -1.0, DT) < -0.9);
+    }
+}
@@ -0,0 +1,363 @@
+//! Several robots in one MuJoCo scene, for `subscriber --model ROBOT_ID=PATH ...`.
+//!
+//! With one `--model` the subscriber works as it always has: every state drives
+//! that model, whatever its `robot_id`. With several, each names the robot whose
+//! states drive it. [`compose`] attaches the models side by side in one scene,
+//! each under a `<robot_id>/` name prefix so their joints and bodies cannot
+//! clash, and a [`Router`] sends each state to its robot's joints.
+//!
+//! A model can also follow a topic instead of a `robot_id`
+//! (`--model left@hands/left=PATH`), for publishers that all send the same
+//! `robot_id`, such as two copies of one glove or GUI publishing a bimanual
+//! pair on `hands/left` and `hands/right`.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Instant;
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::interpolation::{Interpolation, Interpolator};
+use crate::RobotState;
+
+/// Distance between neighbouring robots in a composed scene, in meters.
+pub const SPACING_M: f64 = 0.3;
+
+/// `--model` arguments that do not make a scene.
+#[derive(Debug, Error)]
+pub enum SceneError {
+    #[error("with several models, each needs a robot id: --model ROBOT_ID={}", .0.display())]
+    MissingRobotId(PathBuf),
+    #[error("robot id {0:?} is given to more than one model")]
+    DuplicateRobotId(String),
+    #[error("topic {0:?} is given to more than one model")]
+    DuplicateTopic(String),
+}
+
+impl ExitStatus for SceneError {
+    fn exit(&self) -> Exit {
+        Exit::Usage
+    }
+}
+
+/// One `--model` argument: `PATH`, `ROBOT_ID=PATH`, or `ROBOT_ID@TOPIC=PATH`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelArg {
+    /// The robot whose states drive this model; any robot when absent. With
+    /// a `topic`, only the model's name in the scene.
+    pub robot_id: Option<String>,
+    /// The topic whose states drive this model, whatever their `robot_id`.
+    pub topic: Option<String>,
+    pub path: PathBuf,
+}
+
+impl FromStr for ModelArg {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        let invalid = || format!("expected [ROBOT_ID[@TOPIC]=]PATH, got {raw:?}");
+        let (key, path) = match raw.split_once('=') {
+            Some((key, path)) if !key.is_empty() => (Some(key), path),
+            _ => (None, raw),
+        };
+        let (robot_id, topic) = match key.map(|key| key.split_once('@')) {
+            Some(Some((robot_id, topic))) if robot_id.is_empty() || topic.is_empty() => {
+                return Err(invalid())
+            }
+            Some(Some((robot_id, topic))) => (Some(robot_id), Some(topic)),
+            Some(None) => (key, None),
+            None => (None, None),
+        };
+        if path.is_empty() {
+            return Err(invalid());
+        }
+        Ok(ModelArg {
+            robot_id: robot_id.map(str::to_string),
+            topic: topic.map(str::to_string),
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// Check that `models` make a scene: one model, or robot ids for all, no two
+/// alike, and no topic followed by two models.
+pub fn validate(models: &[ModelArg]) -> Result<(), SceneError> {
+    for (i, model) in models.iter().enumerate() {
+        if let Some(topic) = &model.topic {
+            if models[..i]
+                .iter()
+                .any(|other| other.topic.as_ref() == Some(topic))
+            {
+                return Err(SceneError::DuplicateTopic(topic.clone()));
+            }
+        }
+    }
+    if models.len() < 2 {
+        return Ok(());
+    }
+    for (i, model) in models.iter().enumerate() {
+        let Some(robot_id) = &model.robot_id else {
+            return Err(SceneError::MissingRobotId(model.path.clone()));
+        };
+        if models[..i]
+            .iter()
+            .any(|other| other.robot_id.as_ref() == Some(robot_id))
+        {
+            return Err(SceneError::DuplicateRobotId(robot_id.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// MJCF for one scene holding every `(robot_id, model file)`, spaced [`SPACING_M`]
+/// apart along y. Give absolute paths: they are resolved from wherever the
+/// returned text is loaded.
+pub fn compose(models: &[(&str, &Path)]) -> String {
+    let mut xml = String::from("<mujoco model=\"robots\">\n  <asset>\n");
+    for (robot_id, path) in models {
+        xml.push_str(&format!(
+            "    <model name=\"{}\" file=\"{}\"/>\n",
+            escape(robot_id),
+            escape(&path.display().to_string())
+        ));
+    }
+    xml.push_str("  </asset>\n  <worldbody>\n");
+    let middle = (models.len() as f64 - 1.0) / 2.0;
+    for (i, (robot_id, _)) in models.iter().enumerate() {
+        let y = (i as f64 - middle) * SPACING_M;
+        let robot_id = escape(robot_id);
+        xml.push_str(&format!(
+            "    <frame pos=\"0 {y} 0\">\n      <attach model=\"{robot_id}\" prefix=\"{robot_id}/\"/>\n    </frame>\n"
+        ));
+    }
+    xml.push_str("  </worldbody>\n</mujoco>\n");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// How a state was routed by [`Router::accept`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route<'a> {
+    /// Newer than the last from its robot; its joints are `prefix` + joint name.
+    Fresh { prefix: &'a str },
+    /// Not newer than the last state from its robot.
+    Stale,
+    /// From a robot (or on a topic) no model is for.
+    Unrouted,
+}
+
+/// One robot's model in the scene.
+#[derive(Debug)]
+struct Track {
+    /// `None` takes every robot's states (a scene of one model).
+    robot_id: Option<String>,
+    /// Takes this topic's states instead, whatever their robot.
+    topic: Option<String>,
+    prefix: String,
+    last_order: u64,
+    interpolator: Interpolator,
+}
+
+/// Sends each state to its robot's model, and tracks each robot's stream on its
+/// own: sequence numbers and interpolation are per publisher.
+#[derive(Debug)]
+pub struct Router {
+    tracks: Vec<Track>,
+}
+
+impl Router {
+    /// A router for `models`, which [`validate`] has accepted.
+    pub fn new(models: &[ModelArg], interpolation: Interpolation) -> Self {
+        let composed = models.len() > 1;
+        let tracks = models
+            .iter()
+            .map(|model| Track {
+                robot_id: model.robot_id.clone().filter(|_| composed),
+                topic: model.topic.clone(),
+                prefix: match (&model.robot_id, composed) {
+                    (Some(robot_id), true) => format!("{robot_id}/"),
+                    _ => String::new(),
+                },
+                last_order: 0,
+                interpolator: Interpolator::new(interpolation),
+            })
+            .collect();
+        Router { tracks }
+    }
+
+    /// Take `state`, received on `topic` at `now`, if it is the newest from
+    /// its robot; the model for `topic`, if any, takes it before its robot's.
+    pub fn accept(&mut self, state: &RobotState, topic: &str, now: Instant) -> Route<'_> {
+        let for_topic = self
+            .tracks
+            .iter()
+            .position(|track| track.topic.as_deref() == Some(topic));
+        let for_robot = || {
+            self.tracks.iter().position(|track| {
+                track.topic.is_none()
+                    && track
+                        .robot_id
+                        .as_ref()
+                        .is_none_or(|robot_id| *robot_id == state.robot_id)
+            })
+        };
+        let Some(track) = for_topic.or_else(for_robot).map(|i| &mut self.tracks[i]) else {
+            return Route::Unrouted;
+        };
+        if state.order() <= track.last_order {
+            return Route::Stale;
+        }
+        track.last_order = state.order();
+        track.interpolator.push(state, now);
+        Route::Fresh {
+            prefix: &track.prefix,
+        }
+    }
+
+    /// Every robot's joints as `(prefix, joint name, angle)` to show at `now`.
+    pub fn angles_at(&self, now: Instant) -> impl Iterator<Item = (&str, &str, f64)> + '_ {
+        self.tracks.iter().flat_map(move |track| {
+            track
+                .interpolator
+                .angles_at(now)
+                .map(|(joint, angle)| (track.prefix.as_str(), joint, angle))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    fn model(raw: &str) -> ModelArg {
+        raw.parse().unwrap()
+    }
+
+    fn state(robot_id: &str, sequence: u64) -> RobotState {
+        RobotState {
+            robot_id: robot_id.to_string(),
+            ..simulated_state(sequence)
+        }
+    }
+
+    #[test]
+    fn parses_and_validates_model_arguments() {
+        assert_eq!(
+            model("left=models/left.xml"),
+            ModelArg {
+                robot_id: Some("left".to_string()),
+                topic: None,
+                path: PathBuf::from("models/left.xml"),
+            }
+        );
+        assert_eq!(model("scene.xml").robot_id, None);
+        assert!("left=".parse::<ModelArg>().is_err());
+        let left = model("left@hands/left=a.xml");
+        assert_eq!(
+            (left.robot_id.as_deref(), left.topic.as_deref()),
+            (Some("left"), Some("hands/left"))
+        );
+        assert!("left@=a.xml".parse::<ModelArg>().is_err());
+        assert!("@hands/left=a.xml".parse::<ModelArg>().is_err());
+
+        assert!(validate(&[model("scene.xml")]).is_ok());
+        assert!(validate(&[model("l=a.xml"), model("r=b.xml")]).is_ok());
+        let err = validate(&[model("l=a.xml"), model("b.xml")]).unwrap_err();
+        assert!(matches!(err, SceneError::MissingRobotId(_)));
+        assert_eq!(err.exit(), Exit::Usage);
+        assert!(matches!(
+            validate(&[model("l=a.xml"), model("l=b.xml")]),
+            Err(SceneError::DuplicateRobotId(_))
+        ));
+        assert!(matches!(
+            validate(&[model("l@hands=a.xml"), model("r@hands=b.xml")]),
+            Err(SceneError::DuplicateTopic(_))
+        ));
+    }
+
+    #[test]
+    fn composes_models_side_by_side_under_prefixes() {
+        let xml = compose(&[
+            ("left", Path::new("/m/left.xml")),
+            ("right", Path::new("/m/right & co.xml")),
+        ]);
+        assert!(xml.contains(r#"<model name="left" file="/m/left.xml"/>"#));
+        assert!(xml.contains(r#"file="/m/right &amp; co.xml""#));
+        assert!(xml.contains(
+            "<frame pos=\"0 -0.15 0\">\n      <attach model=\"left\" prefix=\"left/\"/>"
+        ));
+        assert!(xml.contains(r#"<frame pos="0 0.15 0">"#));
+    }
+
+    #[test]
+    fn routes_states_by_robot_id() {
+        let now = Instant::now();
+        let mut router = Router::new(
+            &[model("left=l.xml"), model("right=r.xml")],
+            Interpolation::None,
+        );
+        assert_eq!(
+            router.accept(&state("left", 5), "robot_joints", now),
+            Route::Fresh { prefix: "left/" }
+        );
+        // Sequence numbers are per publisher.
+        assert_eq!(
+            router.accept(&state("right", 1), "robot_joints", now),
+            Route::Fresh { prefix: "right/" }
+        );
+        assert_eq!(
+            router.accept(&state("left", 5), "robot_joints", now),
+            Route::Stale
+        );
+        assert_eq!(
+            router.accept(&state("middle", 9), "robot_joints", now),
+            Route::Unrouted
+        );
+        let prefixes: Vec<_> = router.angles_at(now).map(|(prefix, _, _)| prefix).collect();
+        assert_eq!(prefixes, ["left/", "right/"]);
+
+        // One model takes everyone's states, under the bare joint names.
+        let mut single = Router::new(&[model("left=l.xml")], Interpolation::None);
+        assert_eq!(
+            single.accept(&state("anyone", 1), "robot_joints", now),
+            Route::Fresh { prefix: "" }
+        );
+    }
+
+    #[test]
+    fn routes_states_by_topic_whatever_their_robot() {
+        let now = Instant::now();
+        let mut router = Router::new(
+            &[
+                model("left@hands/left=l.xml"),
+                model("right@hands/right=r.xml"),
+                model("arm=a.xml"),
+            ],
+            Interpolation::None,
+        );
+        assert_eq!(
+            router.accept(&state("glove", 1), "hands/right", now),
+            Route::Fresh { prefix: "right/" }
+        );
+        assert_eq!(
+            router.accept(&state("glove", 1), "hands/left", now),
+            Route::Fresh { prefix: "left/" }
+        );
+        assert_eq!(
+            router.accept(&state("arm", 1), "robot_joints", now),
+            Route::Fresh { prefix: "arm/" }
+        );
+        assert_eq!(
+            router.accept(&state("glove", 2), "robot_joints", now),
+            Route::Unrouted
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! Keyframe gesture sequences from YAML, sampled into poses and [`RobotState`]s
+//! at any rate: scripted demos like a wave or counting to five, played by the
+//! GUI publisher's Sequence tab and the headless `publisher_sequence` binary.
+//!
+//! A sequence lists keyframes at increasing times in seconds. Each is a `pose`
+//! (joint angles in radians, by joint name) or a named `preset`, and may say how
+//! the motion *into* it eases (see [`Easing`]; default `ease-in-out`):
+//!
+//! ```yaml
+//! loop: true            # start over after the last keyframe (default false)
+//! keyframes:
+//!   - time: 0.0
+//!     preset: open_hand
+//!   - time: 0.5
+//!     pose: { i1_MCP: 1.4, i2_PIP: 1.4 }
+//!   - time: 1.0
+//!     preset: open_hand
+//!     easing: linear
+//! ```
+//!
+//! A keyframe only sets the joints it names; the others keep their angle from
+//! the keyframe before (and before their first mention, from their first).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+use yaml_rust::{ScanError, Yaml, YamlLoader};
+
+use crate::presets::Pose;
+use crate::transition::Easing;
+use crate::{JointAngles, RobotState};
+
+/// A sequence file that could not be used.
+#[derive(Debug, Error)]
+pub enum SequenceError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad YAML")]
+    Yaml(#[from] ScanError),
+    #[error("a sequence is a mapping with a `keyframes` list of at least one keyframe")]
+    Shape,
+    #[error("loop must be true or false")]
+    BadLoop,
+    #[error("keyframe {keyframe}: {field} must be {expected}")]
+    BadField {
+        /// Counted from 1.
+        keyframe: usize,
+        field: String,
+        expected: &'static str,
+    },
+    #[error("keyframe {keyframe}: time {time} does not come after the previous keyframe")]
+    TimeNotIncreasing { keyframe: usize, time: f64 },
+    #[error("keyframe {keyframe}: no preset named {name:?}")]
+    UnknownPreset { keyframe: usize, name: String },
+}
+
+impl ExitStatus for SequenceError {
+    fn exit(&self) -> Exit {
+        match self {
+            SequenceError::Io { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// Joint angles over time, eased from keyframe to keyframe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+    joint_names: Vec<String>,
+    /// Seconds, strictly increasing.
+    times: Vec<f64>,
+    /// Every joint's angle at each keyframe, in `joint_names` order.
+    angles: Vec<Vec<f64>>,
+    /// How the motion into each keyframe eases (the first one's is unused).
+    easings: Vec<Easing>,
+    looping: bool,
+}
+
+impl Sequence {
+    /// Read a YAML file, looking up `preset:` keyframes with `preset`.
+    pub fn load(path: &Path, preset: impl Fn(&str) -> Option<Pose>) -> Result<Self, SequenceError> {
+        let raw = fs::read_to_string(path).map_err(|source| SequenceError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Sequence::parse(&raw, preset)
+    }
+
+    /// Parse YAML in the format described in the [module docs](self).
+    pub fn parse(raw: &str, preset: impl Fn(&str) -> Option<Pose>) -> Result<Self, SequenceError> {
+        let docs = YamlLoader::load_from_str(raw)?;
+        let doc = docs.first().ok_or(SequenceError::Shape)?;
+        let looping = match &doc["loop"] {
+            Yaml::BadValue => false,
+            Yaml::Boolean(looping) => *looping,
+            _ => return Err(SequenceError::BadLoop),
+        };
+        let keyframes = match &doc["keyframes"] {
+            Yaml::Array(keyframes) if !keyframes.is_empty() => keyframes,
+            _ => return Err(SequenceError::Shape),
+        };
+
+        let mut times: Vec<f64> = Vec::new();
+        let mut poses = Vec::new();
+        let mut easings = Vec::new();
+        for (i, keyframe) in keyframes.iter().enumerate() {
+            let keyframe_no = i + 1;
+            let bad = |field: &str, expected| SequenceError::BadField {
+                keyframe: keyframe_no,
+                field: field.to_string(),
+                expected,
+            };
+            let Yaml::Hash(fields) = keyframe else {
+                return Err(bad("a keyframe", "a mapping"));
+            };
+            let (mut time, mut pose, mut easing) = (None, None, Easing::default());
+            for (key, value) in fields {
+                match key.as_str() {
+                    Some("time") => {
+                        time = Some(number(value).ok_or_else(|| bad("time", "a number"))?);
+                    }
+                    Some("pose") if pose.is_none() => {
+                        pose =
+                            Some(parse_pose(value).ok_or_else(|| {
+                                bad("pose", "a mapping of joint names to angles")
+                            })?);
+                    }
+                    Some("preset") if pose.is_none() => {
+                        let name = value.as_str().ok_or_else(|| bad("preset", "a name"))?;
+                        pose = Some(preset(name).ok_or_else(|| SequenceError::UnknownPreset {
+                            keyframe: keyframe_no,
+                            name: name.to_string(),
+                        })?);
+                    }
+                    Some("easing") => {
+                        easing = value
+                            .as_str()
+                            .and_then(|name| Easing::from_str(name, true).ok())
+                            .ok_or_else(|| {
+                                bad("easing", "linear, ease-in, ease-out, or ease-in-out")
+                            })?;
+                    }
+                    Some(field @ ("pose" | "preset")) => {
+                        return Err(bad(field, "the only pose or preset of its keyframe"))
+                    }
+                    Some(field) => return Err(bad(field, "one of time, pose, preset, easing")),
+                    None => return Err(bad("a key", "text")),
+                }
+            }
+            let time = time.ok_or_else(|| bad("time", "given"))?;
+            if times.last().is_some_and(|&previous| time <= previous) {
+                return Err(SequenceError::TimeNotIncreasing {
+                    keyframe: keyframe_no,
+                    time,
+                });
+            }
+            times.push(time);
+            poses.push(pose.ok_or_else(|| bad("pose or preset", "given"))?);
+            easings.push(easing);
+        }
+
+        // Fill each keyframe's gaps from the one before, and the first's from each
+        // joint's first mention.
+        let mut joint_names: Vec<String> = Vec::new();
+        let mut first: BTreeMap<&str, f64> = BTreeMap::new();
+        for pose in &poses {
+            for (joint, &angle) in pose {
+                if !first.contains_key(joint.as_str()) {
+                    first.insert(joint, angle);
+                    joint_names.push(joint.clone());
+                }
+            }
+        }
+        let mut current: Vec<f64> = joint_names
+            .iter()
+            .map(|name| first[name.as_str()])
+            .collect();
+        let angles = poses
+            .iter()
+            .map(|pose| {
+                for (name, angle) in joint_names.iter().zip(&mut current) {
+                    if let Some(&set) = pose.get(name) {
+                        *angle = set;
+                    }
+                }
+                current.clone()
+            })
+            .collect();
+        Ok(Sequence {
+            joint_names,
+            times,
+            angles,
+            easings,
+            looping,
+        })
+    }
+
+    /// Every joint any keyframe names, in order of first mention.
+    pub fn joint_names(&self) -> &[String] {
+        &self.joint_names
+    }
+
+    /// Time from the first keyframe to the last.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.times[self.times.len() - 1] - self.times[0])
+    }
+
+    /// Whether the file asked to start over after the last keyframe.
+    pub fn looping(&self) -> bool {
+        self.looping
+    }
+
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Whether a sequence played for `elapsed` has come to its end (never, looping).
+    pub fn finished(&self, elapsed: Duration) -> bool {
+        !self.looping && elapsed >= self.duration()
+    }
+
+    /// Each joint's `(angle, velocity)` `elapsed` after the first keyframe, in
+    /// [`Sequence::joint_names`] order. Past the end it holds the last keyframe,
+    /// or when looping starts over.
+    pub fn sample(&self, elapsed: Duration) -> Vec<(f64, f64)> {
+        let duration = self.duration().as_secs_f64();
+        let mut offset = elapsed.as_secs_f64();
+        if self.looping && duration > 0.0 {
+            offset %= duration;
+        }
+        let t = self.times[0] + offset;
+        // The segment [times[i], times[i + 1]] containing `t`, if any.
+        let segment = self.times.partition_point(|&time| time <= t).checked_sub(1);
+        match segment {
+            Some(i) if i + 1 < self.times.len() => {
+                let (t0, t1) = (self.times[i], self.times[i + 1]);
+                let easing = self.easings[i + 1];
+                let u = (t - t0) / (t1 - t0);
+                let (s, rate) = (easing.apply(u), easing.rate(u) / (t1 - t0));
+                self.angles[i]
+                    .iter()
+                    .zip(&self.angles[i + 1])
+                    .map(|(&a0, &a1)| (a0 + (a1 - a0) * s, (a1 - a0) * rate))
+                    .collect()
+            }
+            // Past the end: hold the last keyframe.
+            Some(i) => self.angles[i].iter().map(|&angle| (angle, 0.0)).collect(),
+            None => self.angles[0].iter().map(|&angle| (angle, 0.0)).collect(),
+        }
+    }
+
+    /// The state `elapsed` after the first keyframe (see [`Sequence::sample`]).
+    pub fn state_at(&self, elapsed: Duration, sequence: u64, robot_id: &str) -> RobotState {
+        let joints = self
+            .joint_names
+            .iter()
+            .zip(self.sample(elapsed))
+            .map(|(name, (angle_rad, velocity))| JointAngles {
+                timestamp: 0,
+                joint_name: name.clone(),
+                angle_rad,
+                velocity,
+                torque: 0.0,
+                qpos: Vec::new(),
+            })
+            .collect();
+        RobotState {
+            timestamp: 0,
+            robot_id: robot_id.to_string(),
+            joints,
+            sent_at_us: None,
+            sequence: Some(sequence),
+        }
+    }
+}
+
+/// A YAML integer or real as `f64`.
+fn number(value: &Yaml) -> Option<f64> {
+    match value {
+        Yaml::Integer(i) => Some(*i as f64),
+        _ => value.as_f64(),
+    }
+}
+
+fn parse_pose(value: &Yaml) -> Option<Pose> {
+    let Yaml::Hash(angles) = value else {
+        return None;
+    };
+    angles
+        .iter()
+        .map(|(joint, angle)| Some((joint.as_str()?.to_string(), number(angle)?)))
+        .collect()
+}
+
+/// Play, pause, and rewind for a [`Sequence`]: how much of it has played by any
+/// moment, not counting paused time.
+#[derive(Debug, Clone, Default)]
+pub struct Player {
+    /// Played before the last pause.
+    played: Duration,
+    /// When playing resumed, while it is playing.
+    resumed: Option<Instant>,
+}
+
+impl Player {
+    /// Play from where it was paused (the start, for a new player).
+    pub fn play(&mut self, now: Instant) {
+        self.resumed.get_or_insert(now);
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        self.played = self.elapsed(now);
+        self.resumed = None;
+    }
+
+    /// Back to the start, paused.
+    pub fn stop(&mut self) {
+        *self = Player::default();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.resumed.is_some()
+    }
+
+    /// How much has played by `now`.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        self.played
+            + self.resumed.map_or(Duration::ZERO, |resumed| {
+                now.saturating_duration_since(resumed)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAVE: &str = "
+loop: true
+keyframes:
+  - time: 0
+    preset: open
+  - time: 0.5
+    pose: { i1_MCP: 1.0 }
+    easing: linear
+  - time: 1.5
+    pose: { i1_MCP: 0.0, m1_MCP: 1.0 }
+";
+
+    fn presets(name: &str) -> Option<Pose> {
+        (name == "open").then(|| Pose::from([("t1_TM".into(), 0.25), ("i1_MCP".into(), 0.0)]))
+    }
+
+    fn angle(sequence: &Sequence, joint: &str, at_ms: u64) -> f64 {
+        let i = sequence
+            .joint_names()
+            .iter()
+            .position(|name| name == joint)
+            .unwrap();
+        sequence.sample(Duration::from_millis(at_ms))[i].0
+    }
+
+    #[test]
+    fn eases_between_keyframes_and_carries_joints_forward() {
+        let sequence = Sequence::parse(WAVE, presets).unwrap();
+        // BTreeMap order within the preset, then first mention.
+        assert_eq!(sequence.joint_names(), ["i1_MCP", "t1_TM", "m1_MCP"]);
+        assert_eq!(sequence.duration(), Duration::from_millis(1500));
+        assert!(sequence.looping());
+
+        // Linear into the second keyframe, with its slope as the velocity.
+        assert!((angle(&sequence, "i1_MCP", 250) - 0.5).abs() < 1e-12);
+        let i1 = sequence.sample(Duration::from_millis(250))[0];
+        assert!((i1.1 - 2.0).abs() < 1e-12);
+        // The preset's thumb angle holds through keyframes that do not name it,
+        // and m1_MCP starts at its first mention.
+        assert_eq!(angle(&sequence, "t1_TM", 1000), 0.25);
+        assert_eq!(angle(&sequence, "m1_MCP", 0), 1.0);
+        // Ease-in-out into the third keyframe passes halfway at half time.
+        assert!((angle(&sequence, "i1_MCP", 1000) - 0.5).abs() < 1e-12);
+        // Looping: 1.75 s is 0.25 s into the next pass.
+        assert!((angle(&sequence, "i1_MCP", 1750) - 0.5).abs() < 1e-12);
+        assert!(!sequence.finished(Duration::from_secs(10)));
+
+        let state = sequence.state_at(Duration::ZERO, 3, "hand");
+        assert_eq!((state.sequence, state.joints.len()), (Some(3), 3));
+    }
+
+    #[test]
+    fn a_finished_sequence_holds_its_last_keyframe() {
+        let mut sequence = Sequence::parse(WAVE, presets).unwrap();
+        sequence.set_looping(false);
+        assert!(sequence.finished(Duration::from_millis(1500)));
+        assert_eq!(angle(&sequence, "m1_MCP", 5000), 1.0);
+        assert_eq!(sequence.sample(Duration::from_secs(5))[2].1, 0.0);
+    }
+
+    #[test]
+    fn rejects_malformed_keyframes() {
+        let parse = |raw| Sequence::parse(raw, presets);
+        assert!(matches!(parse("keyframes: []"), Err(SequenceError::Shape)));
+        assert!(matches!(
+            parse("keyframes:\n  - time: 0\n    preset: fist\n"),
+            Err(SequenceError::UnknownPreset { keyframe: 1, name }) if name == "fist"
+        ));
+        assert!(matches!(
+            parse("keyframes:\n  - time: 1\n    preset: open\n  - time: 1\n    preset: open\n"),
+            Err(SequenceError::TimeNotIncreasing { keyframe: 2, .. })
+        ));
+        assert!(matches!(
+            parse("keyframes:\n  - time: 0\n    pose: { a: 1 }\n    easing: bouncy\n"),
+            Err(SequenceError::BadField { field, .. }) if field == "easing"
+        ));
+        assert!(matches!(
+            parse("keyframes:\n  - time: 0\n    pose: { a: 1 }\n    speed: 2\n"),
+            Err(SequenceError::BadField { field, .. }) if field == "speed"
+        ));
+        assert!(matches!(
+            parse("keyframes: [\n"),
+            Err(SequenceError::Yaml(_))
+        ));
+    }
+
+    #[test]
+    fn the_example_gestures_parse() {
+        for raw in [
+            include_str!("../sequences/wave.yaml"),
+            include_str!("../sequences/count_to_five.yaml"),
+        ] {
+            let sequence = Sequence::parse(raw, |_| None).unwrap();
+            assert!(sequence.duration() > Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn a_player_does_not_count_paused_time() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut player = Player::default();
+        assert_eq!(player.elapsed(start + second), Duration::ZERO);
+
+        player.play(start);
+        player.pause(start + second);
+        assert!(!player.is_playing());
+        assert_eq!(player.elapsed(start + 5 * second), second);
+        player.play(start + 5 * second);
+        assert_eq!(player.elapsed(start + 6 * second), 2 * second);
+
+        player.stop();
+        assert_eq!(player.elapsed(start + 7 * second), Duration::ZERO);
+    }
+}
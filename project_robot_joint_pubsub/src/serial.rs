@@ -0,0 +1,198 @@
+//! Framed joint data from a serial port, for the `serial_bridge` binary: a
+//! microcontroller reading joint encoders writes [`RobotState`]s over UART, and
+//! the bridge republishes them.
+//!
+//! Two framings, chosen with `--framing`:
+//!
+//! - [`Framing::Json`]: one JSON `RobotState` per line, easy to print from
+//!   any firmware and to read in a serial monitor.
+//! - [`Framing::Cobs`]: each payload [COBS]-encoded and ended by a `0x00`
+//!   byte, so binary payloads (protobuf from nanopb, MessagePack) can be sent.
+//!   The payload format is detected as for any received message
+//!   ([`Format::detect`]); [`cobs_encode`] is what the firmware has to do.
+//!
+//! Bytes before the first delimiter are dropped, since opening the port
+//! mid-stream usually starts in the middle of a frame.
+//!
+//! [COBS]: https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing
+
+use thiserror::Error;
+
+use crate::wire::{Format, WireError};
+use crate::RobotState;
+
+/// The longest frame kept; a stream with no delimiters is dropped in pieces this long.
+pub const MAX_FRAME: usize = 64 * 1024;
+
+/// How frames are delimited on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Framing {
+    /// Newline-delimited JSON.
+    #[default]
+    Json,
+    /// COBS-encoded payloads, each ended by a zero byte.
+    Cobs,
+}
+
+impl Framing {
+    fn delimiter(self) -> u8 {
+        match self {
+            Framing::Json => b'\n',
+            Framing::Cobs => 0,
+        }
+    }
+}
+
+/// A frame that does not hold a state.
+#[derive(Debug, Error)]
+pub enum FrameError {
+    #[error("frame is not valid COBS")]
+    Cobs,
+    #[error("frame longer than {MAX_FRAME} bytes without a delimiter")]
+    TooLong,
+    #[error(transparent)]
+    Wire(#[from] WireError),
+}
+
+/// Splits a byte stream into frames and decodes them.
+#[derive(Debug)]
+pub struct Deframer {
+    framing: Framing,
+    buf: Vec<u8>,
+    synced: bool,
+}
+
+impl Deframer {
+    pub fn new(framing: Framing) -> Self {
+        Deframer {
+            framing,
+            buf: Vec::new(),
+            synced: false,
+        }
+    }
+
+    /// Take in `bytes` as read from the port, and return every frame they
+    /// complete, decoded.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Result<RobotState, FrameError>> {
+        let delimiter = self.framing.delimiter();
+        let mut frames = Vec::new();
+        for &byte in bytes {
+            if byte != delimiter {
+                if self.buf.len() == MAX_FRAME {
+                    self.buf.clear();
+                    if self.synced {
+                        frames.push(Err(FrameError::TooLong));
+                    }
+                }
+                self.buf.push(byte);
+                continue;
+            }
+            if !std::mem::replace(&mut self.synced, true) {
+                self.buf.clear();
+                continue;
+            }
+            if let Some(frame) = self.decode() {
+                frames.push(frame);
+            }
+            self.buf.clear();
+        }
+        frames
+    }
+
+    /// The frame in `buf`, or `None` for an empty one (a blank line, or
+    /// back-to-back delimiters).
+    fn decode(&self) -> Option<Result<RobotState, FrameError>> {
+        match self.framing {
+            Framing::Json => {
+                let line = self.buf.trim_ascii();
+                (!line.is_empty()).then(|| Ok(Format::Json.decode(line)?))
+            }
+            Framing::Cobs => (!self.buf.is_empty()).then(|| {
+                let payload = cobs_decode(&self.buf).ok_or(FrameError::Cobs)?;
+                Ok(Format::detect(&payload).decode(&payload)?)
+            }),
+        }
+    }
+}
+
+/// `payload` COBS-encoded, without the trailing zero byte.
+pub fn cobs_encode(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + payload.len() / 254 + 2);
+    let mut code_at = 0;
+    out.push(0);
+    for &byte in payload {
+        if byte != 0 {
+            out.push(byte);
+        }
+        let run = out.len() - code_at;
+        if byte == 0 || run == 0xff {
+            out[code_at] = run as u8;
+            code_at = out.len();
+            out.push(0);
+        }
+    }
+    out[code_at] = (out.len() - code_at) as u8;
+    out
+}
+
+/// A COBS-encoded frame (without its zero byte) decoded, or `None` if it
+/// is not valid COBS.
+fn cobs_decode(frame: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut rest = frame;
+    while let Some((&code, tail)) = rest.split_first() {
+        let run = usize::from(code).checked_sub(1)?;
+        if run > tail.len() {
+            return None;
+        }
+        out.extend_from_slice(&tail[..run]);
+        rest = &tail[run..];
+        // A full 254-byte run carries no zero; the last group carries none either.
+        if code != 0xff && !rest.is_empty() {
+            out.push(0);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    #[test]
+    fn frames_decode_in_either_framing_after_the_first_delimiter() {
+        let mut state = simulated_state(3);
+        // Zero bytes in the payload, for COBS to stuff.
+        state.joints[0].angle_rad = 0.5;
+
+        let mut json = Deframer::new(Framing::Json);
+        let mut stream = b"t\": 1}\n\n".to_vec();
+        stream.extend(Format::Json.encode(&state));
+        stream.extend(b"\r\n{oops}\n");
+        let frames = json.push(&stream[..10]);
+        assert!(frames.is_empty());
+        let frames = json.push(&stream[10..]);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].as_ref().unwrap(), &state);
+        assert!(matches!(frames[1], Err(FrameError::Wire(_))));
+
+        let mut cobs = Deframer::new(Framing::Cobs);
+        let payload = Format::Protobuf.encode(&state);
+        assert!(payload.contains(&0));
+        let mut stream = vec![0x42, 0];
+        stream.extend(cobs_encode(&payload));
+        stream.push(0);
+        stream.extend([0x05, 0x01, 0]);
+        let frames = cobs.push(&stream);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].as_ref().unwrap(), &state);
+        assert!(matches!(frames[1], Err(FrameError::Cobs)));
+
+        for payload in [&[][..], &[0], &[0, 0], &[1; 300], &[0x11, 0, 0x22]] {
+            let encoded = cobs_encode(payload);
+            assert!(!encoded.contains(&0));
+            assert_eq!(cobs_decode(&encoded).unwrap(), payload);
+        }
+    }
+}
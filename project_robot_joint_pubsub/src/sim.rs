@@ -0,0 +1,41 @@
+//! The headless publisher's simulated robot: one ball joint moving on sine waves.
+
+use crate::{JointAngles, RobotState};
+
+/// Joint the simulated robot reports.
+pub const JOINT_NAME: &str = "ball_joint";
+/// `robot_id` on every simulated state.
+pub const ROBOT_ID: &str = "ball_robot";
+
+/// The simulated state for sequence number `sequence`, with a zero
+/// `timestamp` for [`Publisher::publish`](crate::Publisher::publish) to stamp.
+///
+/// Deterministic, so a receiver can recompute what it should have seen.
+pub fn simulated_state(sequence: u64) -> RobotState {
+    // Simulate ball position using sinusoidal motion
+    let t = sequence as f64 * 0.01;
+    let angle_rad = t.sin() * 2.0;
+    // A ball joint turns by `angle_rad` about an axis that circles the z axis,
+    // 0.3 rad off it, so all three rotational DoF move.
+    let axis = [
+        0.3_f64.sin() * t.cos(),
+        0.3_f64.sin() * t.sin(),
+        0.3_f64.cos(),
+    ];
+    let (sin, cos) = (angle_rad / 2.0).sin_cos();
+    let joint = JointAngles {
+        timestamp: 0,
+        joint_name: JOINT_NAME.to_string(),
+        angle_rad,                     // Simulated angle/position component
+        velocity: t.cos() * 0.1,       // Velocity component
+        torque: (t * 2.0).sin() * 0.5, // Torque component
+        qpos: vec![cos, sin * axis[0], sin * axis[1], sin * axis[2]],
+    };
+    RobotState {
+        timestamp: 0,
+        robot_id: ROBOT_ID.to_string(),
+        joints: vec![joint],
+        sent_at_us: None,
+        sequence: Some(sequence),
+    }
+}
@@ -0,0 +1,224 @@
+//! Receive statistics for `subscriber --stats`: rate, jitter, and latency.
+//!
+//! [`Stats`] collects every state a subscriber receives and, each time it is
+//! asked for a [`Report`], summarizes the window since the last one per topic:
+//! how many states arrived and how fast, how evenly spaced they were, and how
+//! long they took from [`Publisher::publish`](crate::Publisher::publish) to here.
+//!
+//! Latency compares the publisher's wall clock (`sent_at_us`) with the
+//! `wall_clock` passed to [`Stats::record`], so across machines it is only as
+//! good as the two clocks agree. Passing our clock through
+//! [`ClockSync::to_peer`](crate::clock::ClockSync::to_peer) takes out their
+//! offset as heartbeat round trips measure it; otherwise it takes NTP, or PTP
+//! for sub-millisecond numbers. States from publishers that do not stamp
+//! `sent_at_us` still count toward rate and jitter.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::RobotState;
+
+/// One topic's numbers for one window; also a row of `--stats-csv`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    /// End of the window, in seconds since the Unix epoch.
+    pub unix_time: f64,
+    pub topic: String,
+    /// States received in the window.
+    pub messages: u64,
+    /// States per second over the window.
+    pub rate_hz: f64,
+    /// Mean time between consecutive states.
+    pub interval_ms: Option<f64>,
+    /// Standard deviation of the time between consecutive states.
+    pub jitter_ms: Option<f64>,
+    /// Mean publish-to-receive time of the states that carry `sent_at_us`.
+    pub latency_ms: Option<f64>,
+    pub latency_max_ms: Option<f64>,
+}
+
+/// Running sums for one topic.
+#[derive(Debug, Default)]
+struct Topic {
+    messages: u64,
+    /// Receive time of the latest state, kept across windows.
+    last_arrival: Option<Instant>,
+    intervals: u64,
+    interval_sum: f64,
+    interval_sum_sq: f64,
+    latencies: u64,
+    latency_sum: f64,
+    latency_max: f64,
+}
+
+/// Statistics over the states received since the last [`Stats::report`].
+#[derive(Debug)]
+pub struct Stats {
+    window_start: Instant,
+    topics: BTreeMap<String, Topic>,
+}
+
+impl Stats {
+    /// Start the first window at `now`.
+    pub fn new(now: Instant) -> Self {
+        Stats {
+            window_start: now,
+            topics: BTreeMap::new(),
+        }
+    }
+
+    /// Count `state`, received on `topic` at `now` (`wall_clock` by our clock).
+    pub fn record(
+        &mut self,
+        topic: &str,
+        state: &RobotState,
+        now: Instant,
+        wall_clock: SystemTime,
+    ) {
+        let entry = match self.topics.get_mut(topic) {
+            Some(entry) => entry,
+            None => self.topics.entry(topic.to_string()).or_default(),
+        };
+        entry.messages += 1;
+        if let Some(last) = entry.last_arrival.replace(now) {
+            let interval = now.saturating_duration_since(last).as_secs_f64() * 1e3;
+            entry.intervals += 1;
+            entry.interval_sum += interval;
+            entry.interval_sum_sq += interval * interval;
+        }
+        if let Some(sent_at_us) = state.sent_at_us {
+            let received_us = wall_clock
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_micros() as u64);
+            // A publisher clock ahead of ours shows as negative latency; keep it
+            // visible rather than clamping it to zero.
+            let latency = (received_us as f64 - sent_at_us as f64) / 1e3;
+            if entry.latencies == 0 || latency > entry.latency_max {
+                entry.latency_max = latency;
+            }
+            entry.latencies += 1;
+            entry.latency_sum += latency;
+        }
+    }
+
+    /// Summarize the window ending at `now`, one [`Report`] per topic seen so
+    /// far, and start the next window.
+    pub fn report(&mut self, now: Instant, wall_clock: SystemTime) -> Vec<Report> {
+        let window = now.saturating_duration_since(self.window_start);
+        self.window_start = now;
+        let unix_time = wall_clock
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |since| since.as_secs_f64());
+        self.topics
+            .iter_mut()
+            .map(|(topic, entry)| {
+                let report = summarize(topic, entry, window, unix_time);
+                *entry = Topic {
+                    last_arrival: entry.last_arrival,
+                    ..Topic::default()
+                };
+                report
+            })
+            .collect()
+    }
+}
+
+fn summarize(topic: &str, entry: &Topic, window: Duration, unix_time: f64) -> Report {
+    let mean = |sum: f64, n: u64| (n > 0).then(|| sum / n as f64);
+    let interval_ms = mean(entry.interval_sum, entry.intervals);
+    let jitter_ms = interval_ms.map(|mean| {
+        let variance = entry.interval_sum_sq / entry.intervals as f64 - mean * mean;
+        variance.max(0.0).sqrt()
+    });
+    let seconds = window.as_secs_f64();
+    Report {
+        unix_time,
+        topic: topic.to_string(),
+        messages: entry.messages,
+        rate_hz: if seconds > 0.0 {
+            entry.messages as f64 / seconds
+        } else {
+            0.0
+        },
+        interval_ms,
+        jitter_ms,
+        latency_ms: mean(entry.latency_sum, entry.latencies),
+        latency_max_ms: (entry.latencies > 0).then_some(entry.latency_max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    #[test]
+    fn reports_rate_jitter_and_latency_per_window() {
+        let start = Instant::now();
+        let epoch = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+        let mut stats = Stats::new(start);
+        // Arrivals 0, 100, 300 ms in; the second was sent 5 ms before it arrived,
+        // the third 15 ms, the first is unstamped.
+        for (i, (arrival_ms, latency_ms)) in [(0, None), (100, Some(5)), (300, Some(15))]
+            .into_iter()
+            .enumerate()
+        {
+            let received = epoch + Duration::from_millis(arrival_ms);
+            let mut state = simulated_state(i as u64 + 1);
+            state.sent_at_us = latency_ms.map(|latency_ms| {
+                let sent = received - Duration::from_millis(latency_ms);
+                sent.duration_since(UNIX_EPOCH).unwrap().as_micros() as u64
+            });
+            let now = start + Duration::from_millis(arrival_ms);
+            stats.record("robot_joints", &state, now, received);
+        }
+
+        let reports = stats.report(start + Duration::from_millis(500), epoch);
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(
+            (report.topic.as_str(), report.messages),
+            ("robot_joints", 3)
+        );
+        assert!((report.rate_hz - 6.0).abs() < 1e-9);
+        assert!((report.interval_ms.unwrap() - 150.0).abs() < 1e-6);
+        assert!((report.jitter_ms.unwrap() - 50.0).abs() < 1e-6);
+        assert!((report.latency_ms.unwrap() - 10.0).abs() < 1e-6);
+        assert!((report.latency_max_ms.unwrap() - 15.0).abs() < 1e-6);
+
+        // The next window starts empty but still measures from the last arrival.
+        let report = &stats.report(start + Duration::from_secs(1), epoch)[0];
+        assert_eq!((report.messages, report.interval_ms), (0, None));
+        let state = simulated_state(4);
+        stats.record(
+            "robot_joints",
+            &state,
+            start + Duration::from_millis(1_300),
+            epoch,
+        );
+        let report = &stats.report(start + Duration::from_secs(2), epoch)[0];
+        assert!((report.interval_ms.unwrap() - 1_000.0).abs() < 1e-6);
+        assert_eq!(report.latency_ms, None);
+    }
+
+    #[test]
+    fn counts_each_topic_separately() {
+        let start = Instant::now();
+        let mut stats = Stats::new(start);
+        let now = SystemTime::now();
+        for topic in ["left_hand", "right_hand", "right_hand"] {
+            stats.record(topic, &simulated_state(1), start, now);
+        }
+        let counts: Vec<_> = stats
+            .report(start + Duration::from_secs(1), now)
+            .into_iter()
+            .map(|report| (report.topic, report.messages))
+            .collect();
+        assert_eq!(
+            counts,
+            [("left_hand".to_string(), 1), ("right_hand".to_string(), 2)]
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Joint trajectories from CSV, sampled into [`RobotState`]s at any rate.
+//!
+//! The first column is time in seconds, strictly increasing; every other
+//! column is one joint's angle in radians, named by its header:
+//!
+//! ```text
+//! time,finger_1_joint,finger_2_joint
+//! 0.0,0.00,0.10
+//! 0.5,0.25,0.10
+//! 1.0,0.50,0.40
+//! ```
+//!
+//! Between rows the angles are interpolated linearly, and each joint's velocity
+//! is the slope of the segment it is on. Torque is not in the file and is 0.
+//!
+//! [`Trajectory::from_states`] builds one from states captured over time (the
+//! GUI publisher's recordings), and [`Trajectory::save`] writes it back out in
+//! the same format.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::{JointAngles, RobotState};
+
+/// A CSV trajectory that could not be read.
+#[derive(Debug, Error)]
+pub enum TrajectoryError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("writing {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad CSV")]
+    Csv(#[from] csv::Error),
+    /// The header has no joint columns, or there are no rows.
+    #[error("trajectory needs a time column, at least one joint column, and one row")]
+    Empty,
+    #[error("line {line}: {column} is not a number: {value:?}")]
+    NotANumber {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    #[error("line {line}: time {time} does not come after the previous row")]
+    TimeNotIncreasing { line: u64, time: f64 },
+    /// A `--joint-map` entry names a column the file does not have.
+    #[error("no column named {0:?} to rename")]
+    UnknownColumn(String),
+}
+
+impl ExitStatus for TrajectoryError {
+    fn exit(&self) -> Exit {
+        match self {
+            TrajectoryError::Io { .. } | TrajectoryError::Write { .. } => Exit::Io,
+            TrajectoryError::Csv(e) if e.is_io_error() => Exit::Io,
+            TrajectoryError::UnknownColumn(_) => Exit::Usage,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// Joint angles over time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    joint_names: Vec<String>,
+    /// Seconds, strictly increasing.
+    times: Vec<f64>,
+    /// One row of angles per time, in `joint_names` order.
+    angles: Vec<Vec<f64>>,
+}
+
+impl Trajectory {
+    /// Read a CSV file.
+    pub fn load(path: &Path) -> Result<Self, TrajectoryError> {
+        let file = File::open(path).map_err(|source| TrajectoryError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Trajectory::from_csv(file)
+    }
+
+    /// Parse CSV text in the format described in the [module docs](self).
+    pub fn from_csv(reader: impl Read) -> Result<Self, TrajectoryError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let header = reader.headers()?.clone();
+        let joint_names: Vec<String> = header.iter().skip(1).map(str::to_string).collect();
+        if joint_names.is_empty() {
+            return Err(TrajectoryError::Empty);
+        }
+        let mut times: Vec<f64> = Vec::new();
+        let mut angles = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, |position| position.line());
+            let mut values = record.iter().zip(header.iter()).map(|(value, column)| {
+                value
+                    .parse::<f64>()
+                    .map_err(|_| TrajectoryError::NotANumber {
+                        line,
+                        column: column.to_string(),
+                        value: value.to_string(),
+                    })
+            });
+            let time = values.next().ok_or(TrajectoryError::Empty)??;
+            if times.last().is_some_and(|&previous| time <= previous) {
+                return Err(TrajectoryError::TimeNotIncreasing { line, time });
+            }
+            times.push(time);
+            angles.push(values.collect::<Result<Vec<_>, _>>()?);
+        }
+        if times.is_empty() {
+            return Err(TrajectoryError::Empty);
+        }
+        Ok(Trajectory {
+            joint_names,
+            times,
+            angles,
+        })
+    }
+
+    /// The trajectory through `samples`, each a state and its time since some
+    /// start. The first state's joints are the columns; a later state without
+    /// one of them holds its previous angle, and a sample no later than the
+    /// one before it is dropped.
+    pub fn from_states<'a>(
+        samples: impl IntoIterator<Item = (Duration, &'a RobotState)>,
+    ) -> Result<Self, TrajectoryError> {
+        let mut samples = samples.into_iter();
+        let (start, first) = samples.next().ok_or(TrajectoryError::Empty)?;
+        let joint_names: Vec<String> = first
+            .joints
+            .iter()
+            .map(|joint| joint.joint_name.clone())
+            .collect();
+        if joint_names.is_empty() {
+            return Err(TrajectoryError::Empty);
+        }
+        let mut times = vec![start.as_secs_f64()];
+        let mut angles = vec![first.joints.iter().map(|joint| joint.angle_rad).collect()];
+        for (time, state) in samples {
+            let time = time.as_secs_f64();
+            if time <= times[times.len() - 1] {
+                continue;
+            }
+            let previous: &Vec<f64> = &angles[angles.len() - 1];
+            let row = joint_names
+                .iter()
+                .zip(previous)
+                .map(|(name, &held)| {
+                    state
+                        .joints
+                        .iter()
+                        .find(|joint| joint.joint_name == *name)
+                        .map_or(held, |joint| joint.angle_rad)
+                })
+                .collect();
+            times.push(time);
+            angles.push(row);
+        }
+        Ok(Trajectory {
+            joint_names,
+            times,
+            angles,
+        })
+    }
+
+    /// Write the trajectory as CSV in the format [`Trajectory::from_csv`] reads.
+    pub fn write_csv(&self, writer: impl Write) -> Result<(), TrajectoryError> {
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record(
+            std::iter::once("time").chain(self.joint_names.iter().map(String::as_str)),
+        )?;
+        for (time, row) in self.times.iter().zip(&self.angles) {
+            csv.write_record(std::iter::once(time).chain(row).map(f64::to_string))?;
+        }
+        csv.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// [`Trajectory::write_csv`] to a new file at `path`.
+    pub fn save(&self, path: &Path) -> Result<(), TrajectoryError> {
+        let file = File::create(path).map_err(|source| TrajectoryError::Write {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.write_csv(io::BufWriter::new(file))
+    }
+
+    /// Rename the column `from` to the joint `to`.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), TrajectoryError> {
+        let name = self
+            .joint_names
+            .iter_mut()
+            .find(|name| *name == from)
+            .ok_or_else(|| TrajectoryError::UnknownColumn(from.to_string()))?;
+        *name = to.to_string();
+        Ok(())
+    }
+
+    pub fn joint_names(&self) -> &[String] {
+        &self.joint_names
+    }
+
+    /// Time from the first row to the last.
+    pub fn duration(&self) -> Duration {
+        let span = self.times[self.times.len() - 1] - self.times[0];
+        Duration::from_secs_f64(span)
+    }
+
+    /// The state `elapsed` after the first row, held at the last row once past it.
+    pub fn state_at(&self, elapsed: Duration, sequence: u64, robot_id: &str) -> RobotState {
+        let t = self.times[0] + elapsed.as_secs_f64();
+        // The segment [times[i], times[i + 1]] containing `t`, if any.
+        let segment = self.times.partition_point(|&time| time <= t).checked_sub(1);
+        let joints = self
+            .joint_names
+            .iter()
+            .enumerate()
+            .map(|(j, name)| {
+                let (angle_rad, velocity) = match segment {
+                    Some(i) if i + 1 < self.times.len() => {
+                        let (t0, t1) = (self.times[i], self.times[i + 1]);
+                        let (a0, a1) = (self.angles[i][j], self.angles[i + 1][j]);
+                        let slope = (a1 - a0) / (t1 - t0);
+                        (a0 + slope * (t - t0), slope)
+                    }
+                    // Past the end: hold the last angle.
+                    Some(i) => (self.angles[i][j], 0.0),
+                    None => (self.angles[0][j], 0.0),
+                };
+                JointAngles {
+                    timestamp: 0,
+                    joint_name: name.clone(),
+                    angle_rad,
+                    velocity,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                }
+            })
+            .collect();
+        RobotState {
+            timestamp: 0,
+            robot_id: robot_id.to_string(),
+            joints,
+            sent_at_us: None,
+            sequence: Some(sequence),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "time, finger_1, finger_2\n0.0, 0.0, 1.0\n0.5, 0.5, 1.0\n1.5, 0.0, 2.0\n";
+
+    #[test]
+    fn interpolates_between_rows() {
+        let trajectory = Trajectory::from_csv(CSV.as_bytes()).unwrap();
+        assert_eq!(trajectory.joint_names(), ["finger_1", "finger_2"]);
+        assert_eq!(trajectory.duration(), Duration::from_millis(1500));
+
+        let state = trajectory.state_at(Duration::from_millis(250), 7, "hand");
+        assert_eq!((state.sequence, state.robot_id.as_str()), (Some(7), "hand"));
+        assert!((state.joints[0].angle_rad - 0.25).abs() < 1e-12);
+        assert!((state.joints[0].velocity - 1.0).abs() < 1e-12);
+        assert_eq!(state.joints[1].angle_rad, 1.0);
+
+        let state = trajectory.state_at(Duration::from_secs(1), 8, "hand");
+        assert!((state.joints[0].angle_rad - 0.25).abs() < 1e-12);
+        assert!((state.joints[0].velocity + 0.5).abs() < 1e-12);
+        assert!((state.joints[1].angle_rad - 1.5).abs() < 1e-12);
+
+        let end = trajectory.state_at(Duration::from_secs(10), 9, "hand");
+        assert_eq!(end.joints[1].angle_rad, 2.0);
+        assert_eq!(end.joints[1].velocity, 0.0);
+    }
+
+    #[test]
+    fn renames_columns_to_joint_names() {
+        let mut trajectory = Trajectory::from_csv(CSV.as_bytes()).unwrap();
+        trajectory.rename("finger_2", "thumb_joint").unwrap();
+        assert_eq!(trajectory.joint_names(), ["finger_1", "thumb_joint"]);
+        let err = trajectory.rename("finger_9", "x").unwrap_err();
+        assert_eq!(err.exit(), Exit::Usage);
+    }
+
+    #[test]
+    fn recorded_states_round_trip_through_csv() {
+        let state = |angles: &[(&str, f64)]| RobotState {
+            timestamp: 1,
+            robot_id: "hand".into(),
+            joints: angles
+                .iter()
+                .map(|&(name, angle_rad)| JointAngles {
+                    timestamp: 1,
+                    joint_name: name.into(),
+                    angle_rad,
+                    velocity: 0.0,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                })
+                .collect(),
+            sent_at_us: None,
+            sequence: None,
+        };
+        let states = [
+            state(&[("a", 0.0), ("b", 1.0)]),
+            state(&[("a", 0.5)]),
+            state(&[("a", 9.0), ("b", 9.0)]),
+            state(&[("b", 2.0), ("a", 0.25)]),
+        ];
+        let times = [0.0, 0.5, 0.5, 1.5].map(Duration::from_secs_f64);
+        let trajectory = Trajectory::from_states(times.into_iter().zip(&states)).unwrap();
+
+        let mut csv = Vec::new();
+        trajectory.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv.clone()).unwrap(),
+            "time,a,b\n0,0,1\n0.5,0.5,1\n1.5,0.25,2\n"
+        );
+        assert_eq!(Trajectory::from_csv(csv.as_slice()).unwrap(), trajectory);
+        assert!(matches!(
+            Trajectory::from_states([]),
+            Err(TrajectoryError::Empty)
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let parse = |csv: &str| Trajectory::from_csv(csv.as_bytes()).unwrap_err();
+        assert!(matches!(parse("time\n0.0\n"), TrajectoryError::Empty));
+        assert!(matches!(parse("time,a\n"), TrajectoryError::Empty));
+        assert!(matches!(
+            parse("time,a\n0.0,1.0\n0.5,oops\n"),
+            TrajectoryError::NotANumber { line: 3, ref column, .. } if column == "a"
+        ));
+        assert!(matches!(
+            parse("time,a\n0.5,1.0\n0.5,2.0\n"),
+            TrajectoryError::TimeNotIncreasing { line: 3, .. }
+        ));
+        // A short row is a CSV error, not a silently missing joint.
+        assert!(matches!(
+            parse("time,a,b\n0.0,1.0\n"),
+            TrajectoryError::Csv(_)
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+//! Animated moves from one pose to another, for the GUI publisher's preset
+//! buttons: rather than snapping, the sliders travel to the preset over a set
+//! time, and every state published on the way carries an in-between pose.
+
+use std::time::{Duration, Instant};
+
+/// How progress through a [`Transition`] speeds up and slows down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Start slowly.
+    EaseIn,
+    /// End slowly.
+    EaseOut,
+    /// Start and end slowly, at rest at both ends.
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseIn => "Ease in",
+            Easing::EaseOut => "Ease out",
+            Easing::EaseInOut => "Ease in-out",
+        }
+    }
+
+    /// The fraction of the way covered when `t` of the time has passed; both
+    /// run from 0 to 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+
+    /// How fast [`Easing::apply`] changes at `t`, relative to linear (its derivative).
+    pub fn rate(self, t: f64) -> f64 {
+        if !(0.0..=1.0).contains(&t) {
+            return 0.0;
+        }
+        match self {
+            Easing::Linear => 1.0,
+            Easing::EaseIn => 3.0 * t * t,
+            Easing::EaseOut => 3.0 * (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => 6.0 * t * (1.0 - t),
+        }
+    }
+}
+
+/// A move of several joints from `from` to `to`, position by position.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    from: Vec<f64>,
+    to: Vec<f64>,
+    started: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Transition {
+    /// Start moving from `from` to `to` (in the same joint order) at `started`.
+    pub fn new(
+        from: Vec<f64>,
+        to: Vec<f64>,
+        started: Instant,
+        duration: Duration,
+        easing: Easing,
+    ) -> Self {
+        debug_assert_eq!(from.len(), to.len());
+        Transition {
+            from,
+            to,
+            started,
+            duration,
+            easing,
+        }
+    }
+
+    /// Each joint's position at `now`; `to` itself once the time is up.
+    pub fn at(&self, now: Instant) -> impl Iterator<Item = f64> + '_ {
+        let elapsed = now.saturating_duration_since(self.started);
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            elapsed.as_secs_f64() / self.duration.as_secs_f64()
+        };
+        let s = self.easing.apply(t);
+        self.from
+            .iter()
+            .zip(&self.to)
+            .map(move |(&from, &to)| if s >= 1.0 { to } else { from + (to - from) * s })
+    }
+
+    /// Whether the move has arrived by `now`.
+    pub fn done(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_easing_runs_from_start_to_end() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.apply(2.0), 1.0, "{easing:?}");
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseInOut.rate(0.5), 1.5);
+        assert_eq!(Easing::EaseInOut.rate(1.0), 0.0);
+        // At rest at both ends: barely moved after the first percent.
+        assert!(Easing::EaseInOut.apply(0.01) < 0.001);
+    }
+
+    #[test]
+    fn a_transition_passes_through_in_between_poses() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let transition = Transition::new(
+            vec![0.0, 1.0],
+            vec![1.0, -1.0],
+            start,
+            second,
+            Easing::Linear,
+        );
+
+        let halfway: Vec<_> = transition.at(start + second / 2).collect();
+        assert_eq!(halfway, [0.5, 0.0]);
+        assert!(!transition.done(start + second / 2));
+        let end: Vec<_> = transition.at(start + 2 * second).collect();
+        assert_eq!(end, [1.0, -1.0]);
+        assert!(transition.done(start + second));
+
+        let snap = Transition::new(vec![0.0], vec![1.0], start, Duration::ZERO, Easing::EaseIn);
+        assert_eq!(snap.at(start).collect::<Vec<_>>(), [1.0]);
+    }
+}
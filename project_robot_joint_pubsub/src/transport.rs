@@ -0,0 +1,1248 @@
+//! How `RobotState` messages travel, independent of the backend.
+//!
+//! A [`Transport`] moves opaque `(topic, payload)` messages. [`Publisher`] and
+//! [`Subscriber`] put [`wire`](crate::wire) payloads on top of any of them, so a
+//! binary picks a backend once and the rest of its code is the same:
+//!
+//! - [`ZmqTransport`] — ZMQ PUB/SUB over any endpoint (feature `zmq-transport`),
+//!   directly or through a [`ZmqBroker`].
+//! - [`MqttTransport`] — an MQTT broker (feature `mqtt-transport`).
+//! - [`ShmTransport`] — a shared-memory ring between processes on one host,
+//!   for high-rate streams; always built.
+//! - [`ZenohTransport`] — Zenoh peers that discover each other, keyed by robot
+//!   (feature `zenoh-transport`).
+//! - [`InprocTransport`] — channels within one process, for tests; always built.
+//!
+//! A new backend implements the three [`Transport`] methods and needs no
+//! changes to the binaries beyond offering it on the command line.
+//!
+//! Sending never blocks, so a [`Publisher`] is fine inside async code as it
+//! is. Receiving waits; async code wraps its [`Subscriber`] in an
+//! [`AsyncSubscriber`] to await states in `tokio::select!`.
+//!
+//! Publishers also send heartbeat messages on [`heartbeat_topic`], so a
+//! subscriber can tell an idle publisher from a dead one ([`Subscriber::last_heard`])
+//! and, given a [`ClockSync`] at both ends, how far apart their clocks are
+//! (see [`clock`](crate::clock)); and control commands on [`control::topic`] ([`Publisher::control`],
+//! [`Subscriber::take_controls`]).
+//!
+//! A publisher can also split each state by finger onto [`finger::topic`]s
+//! ([`Publisher::with_split_by_finger`]); subscribers to the whole topic merge
+//! the parts back together. Or, publishing faster than is worth a message per
+//! state, it can batch them onto [`batch_topic`] ([`Publisher::with_batch`]);
+//! subscribers unpack them in order. And with a deadband
+//! ([`Publisher::with_deadband`]) it skips states no different from the last
+//! one sent, but for a keepalive now and then.
+
+mod async_subscriber;
+mod inproc;
+#[cfg(feature = "mqtt-transport")]
+mod mqtt;
+mod shm;
+#[cfg(feature = "zenoh-transport")]
+mod zenoh;
+#[cfg(feature = "zmq-transport")]
+mod zmq;
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+use crate::clock::{self, ClockSync, Heartbeat};
+use crate::control::{self, Control, ControlCommand};
+use crate::finger;
+use crate::mirror::Mirror;
+use crate::wire::{self, Compression, Format, WireError};
+use crate::{BodyPoses, RobotState, RobotStateBatch, RobotStateRef};
+
+#[cfg(feature = "zenoh-transport")]
+pub use self::zenoh::{ZenohError, ZenohTransport, ALL_ROBOTS};
+#[cfg(feature = "zmq-transport")]
+pub use self::zmq::{ConnectionEvent, ZmqBroker, ZmqMonitor, ZmqTransport};
+pub use async_subscriber::AsyncSubscriber;
+pub use inproc::InprocTransport;
+#[cfg(feature = "mqtt-transport")]
+pub use mqtt::{parse_broker, MqttError, MqttTransport, DEFAULT_PORT};
+pub use shm::{ring_path, ShmError, ShmTransport};
+
+/// A backend failure or a message that could not be decoded.
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[cfg(feature = "zmq-transport")]
+    #[error("ZMQ socket error")]
+    Zmq(#[from] ::zmq::Error),
+    #[cfg(feature = "mqtt-transport")]
+    #[error(transparent)]
+    Mqtt(#[from] MqttError),
+    #[error(transparent)]
+    Shm(#[from] ShmError),
+    #[cfg(feature = "zenoh-transport")]
+    #[error(transparent)]
+    Zenoh(#[from] ZenohError),
+    /// A backend this build leaves out; it needs the named cargo feature.
+    #[error("built without the `{0}` feature")]
+    NotBuilt(&'static str),
+    /// The other side of the transport is gone for good.
+    #[error("transport closed")]
+    Closed,
+    #[error("bad message")]
+    Wire(#[from] WireError),
+    /// The topic was not UTF-8 text.
+    #[error("bad message: not UTF-8 text")]
+    NotText,
+}
+
+impl ExitStatus for TransportError {
+    fn exit(&self) -> Exit {
+        match self {
+            #[cfg(feature = "zmq-transport")]
+            TransportError::Zmq(_) => Exit::Network,
+            #[cfg(feature = "mqtt-transport")]
+            TransportError::Mqtt(err) => err.exit(),
+            TransportError::Shm(err) => err.exit(),
+            #[cfg(feature = "zenoh-transport")]
+            TransportError::Zenoh(err) => err.exit(),
+            TransportError::NotBuilt(_) => Exit::Usage,
+            TransportError::Closed => Exit::Network,
+            TransportError::Wire(_) | TransportError::NotText => Exit::Data,
+        }
+    }
+}
+
+/// A pub/sub backend that moves `(topic, payload)` messages.
+///
+/// Like ZMQ PUB/SUB, `send` never waits for receivers, and a receiver only gets
+/// messages on topics it subscribed to (possibly more, see [`Subscriber`]).
+pub trait Transport: Send {
+    /// Start receiving messages on `topic`.
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError>;
+
+    /// Start receiving messages on every topic that starts with `prefix`.
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError>;
+
+    /// Send one message to every current subscriber of `topic`.
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError>;
+
+    /// [`Transport::send`] a message about robot `robot_id`; backends that key
+    /// messages by robot ([`ZenohTransport`]) override it.
+    fn send_from(&self, robot_id: &str, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        let _ = robot_id;
+        self.send(topic, payload)
+    }
+
+    /// Wait up to `timeout` for the next message on a subscribed topic.
+    fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError>;
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        (**self).subscribe(topic)
+    }
+
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        (**self).subscribe_prefix(prefix)
+    }
+
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        (**self).send(topic, payload)
+    }
+
+    fn send_from(&self, robot_id: &str, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        (**self).send_from(robot_id, topic, payload)
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError> {
+        (**self).recv(timeout)
+    }
+}
+
+/// The topic heartbeats for `topic` travel on.
+///
+/// ZMQ subscriptions match by prefix, so a ZMQ subscriber to `topic` receives
+/// them without subscribing again; subscribers that predate heartbeats skip them
+/// like any other topic that is not exactly theirs.
+pub fn heartbeat_topic(topic: &str) -> String {
+    format!("{topic}/heartbeat")
+}
+
+/// The topic batches of states for `topic` travel on (see [`Publisher::with_batch`]).
+pub fn batch_topic(topic: &str) -> String {
+    format!("{topic}/batch")
+}
+
+/// A Zenoh peer through `endpoints`, as `ZenohTransport::open` joins, or
+/// [`TransportError::NotBuilt`] without the `zenoh-transport` feature, so the
+/// binaries take `--transport zenoh` either way.
+pub fn open_zenoh(endpoints: &[String]) -> Result<Box<dyn Transport>, TransportError> {
+    #[cfg(feature = "zenoh-transport")]
+    return Ok(Box::new(ZenohTransport::open(endpoints)?));
+    #[cfg(not(feature = "zenoh-transport"))]
+    {
+        let _ = endpoints;
+        Err(TransportError::NotBuilt("zenoh-transport"))
+    }
+}
+
+/// Publishes states on one topic, as JSON unless [`Publisher::with_format`] says otherwise.
+pub struct Publisher<T = Box<dyn Transport>> {
+    transport: T,
+    topic: String,
+    heartbeat_topic: String,
+    format: Format,
+    compression: Compression,
+    split_by_finger: bool,
+    batch: Option<Batch>,
+    deadband: Option<Deadband>,
+    clock: Option<Arc<ClockSync>>,
+}
+
+/// How far a [`Publisher`]'s joints must move before it sends again, and the
+/// last state it sent.
+struct Deadband {
+    epsilon: f64,
+    keepalive: Duration,
+    last: RefCell<Option<(RobotState, Instant)>>,
+}
+
+impl Deadband {
+    /// Whether `state` is worth sending: a joint moved more than `epsilon`
+    /// since the last state sent, its joints or robot differ, or that was
+    /// `keepalive` ago.
+    fn passes(&self, state: &RobotState) -> bool {
+        let last = self.last.borrow();
+        let Some((sent, at)) = last.as_ref() else {
+            return true;
+        };
+        if at.elapsed() >= self.keepalive
+            || sent.robot_id != state.robot_id
+            || sent.joints.len() != state.joints.len()
+        {
+            return true;
+        }
+        sent.joints.iter().zip(&state.joints).any(|(was, now)| {
+            was.joint_name != now.joint_name
+                || was.qpos.len() != now.qpos.len()
+                || (was.angle_rad - now.angle_rad).abs() > self.epsilon
+                || was
+                    .qpos
+                    .iter()
+                    .zip(&now.qpos)
+                    .any(|(was, now)| (was - now).abs() > self.epsilon)
+        })
+    }
+}
+
+/// The states a batching [`Publisher`] holds back, and since when.
+struct Batch {
+    topic: String,
+    window: Duration,
+    states: RefCell<Vec<RobotState>>,
+    since: Cell<Instant>,
+}
+
+impl<T: Transport> Publisher<T> {
+    pub fn new(transport: T, topic: &str) -> Self {
+        Publisher {
+            transport,
+            topic: topic.to_string(),
+            heartbeat_topic: heartbeat_topic(topic),
+            format: Format::default(),
+            compression: Compression::default(),
+            split_by_finger: false,
+            batch: None,
+            deadband: None,
+            clock: None,
+        }
+    }
+
+    /// Encode payloads as `format`.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Compress state and poses payloads with `compression`; subscribers
+    /// decompress them whatever they were told.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Send each state as one part per finger, on `<topic>/thumb`,
+    /// `<topic>/index`, and so on (see [`finger`]), if `split`.
+    pub fn with_split_by_finger(mut self, split: bool) -> Self {
+        self.split_by_finger = split;
+        self
+    }
+
+    /// Hold states back and send them together as one [`RobotStateBatch`] on
+    /// [`batch_topic`] once `window` has passed since the first, for rates
+    /// (1 kHz logging) where a message per state costs more than the states.
+    /// A zero `window` sends each state on its own. Batches are not split by
+    /// finger. Call [`Publisher::flush`] before dropping the publisher, or the
+    /// last batch is lost.
+    pub fn with_batch(mut self, window: Duration) -> Self {
+        self.batch = (!window.is_zero()).then(|| Batch {
+            topic: batch_topic(&self.topic),
+            window,
+            states: RefCell::default(),
+            since: Cell::new(Instant::now()),
+        });
+        self
+    }
+
+    /// Skip states in which no joint moved more than `epsilon` (radians, or
+    /// metres for a slide joint) since the last state sent, unless that was
+    /// `keepalive` ago, to save bandwidth while the pose holds still (idle GUI
+    /// sliders). Skipped states count as published. A zero `epsilon` skips only
+    /// exact repeats.
+    pub fn with_deadband(mut self, epsilon: f64, keepalive: Duration) -> Self {
+        self.deadband = Some(Deadband {
+            epsilon,
+            keepalive,
+            last: RefCell::default(),
+        });
+        self
+    }
+
+    /// Echo the heartbeats `sync` hears from this topic's subscribers in this
+    /// publisher's own, so they can estimate the clock offset; `sync` is the
+    /// one given to the [`Subscriber`] hearing them.
+    pub fn with_clock_sync(mut self, sync: Arc<ClockSync>) -> Self {
+        self.clock = Some(sync);
+        self
+    }
+
+    /// Send one state, with `sent_at_us` set to now and a zero `timestamp`
+    /// (the state's and its joints') set to now as well. Never blocks: with
+    /// no subscribers it is dropped. When batching, the state may only be held
+    /// back for the next batch; with a deadband, it may be skipped.
+    pub fn publish(&self, state: &RobotState) -> Result<(), TransportError> {
+        if let Some(deadband) = &self.deadband {
+            if !deadband.passes(state) {
+                return Ok(());
+            }
+            deadband.last.replace(Some((state.clone(), Instant::now())));
+        }
+        let now_ns = clock::now_ns();
+        let mut stamped = RobotState {
+            sent_at_us: Some(now_us()),
+            ..state.clone()
+        };
+        if stamped.timestamp == 0 {
+            stamped.timestamp = now_ns;
+        }
+        for joint in stamped
+            .joints
+            .iter_mut()
+            .filter(|joint| joint.timestamp == 0)
+        {
+            joint.timestamp = now_ns;
+        }
+        if let Some(batch) = &self.batch {
+            let mut states = batch.states.borrow_mut();
+            if states.is_empty() {
+                batch.since.set(Instant::now());
+            }
+            states.push(stamped);
+            drop(states);
+            if batch.since.get().elapsed() < batch.window {
+                return Ok(());
+            }
+            return self.flush();
+        }
+        if !self.split_by_finger {
+            return self.send_state(&self.topic, &stamped);
+        }
+        for (finger, part) in finger::split(&stamped) {
+            self.send_state(&finger::topic(&self.topic, finger), &part)?;
+        }
+        Ok(())
+    }
+
+    /// Send the states [`Publisher::with_batch`] is holding back as one batch,
+    /// if there are any.
+    pub fn flush(&self) -> Result<(), TransportError> {
+        let Some(batch) = &self.batch else {
+            return Ok(());
+        };
+        let held = RobotStateBatch {
+            states: batch.states.take(),
+        };
+        let Some(first) = held.states.first() else {
+            return Ok(());
+        };
+        let payload = self.compression.compress(self.format.encode_batch(&held));
+        self.transport
+            .send_from(&first.robot_id, &batch.topic, &payload)
+    }
+
+    fn send_state(&self, topic: &str, state: &RobotState) -> Result<(), TransportError> {
+        let payload = self.compression.compress(self.format.encode(state));
+        self.transport.send_from(&state.robot_id, topic, &payload)
+    }
+
+    /// Send poses instead of a state, stamped like [`Publisher::publish`]'s.
+    pub fn publish_poses(&self, poses: &BodyPoses) -> Result<(), TransportError> {
+        let stamped = BodyPoses {
+            sent_at_us: Some(now_us()),
+            ..poses.clone()
+        };
+        let payload = self
+            .compression
+            .compress(self.format.encode_poses(&stamped));
+        self.transport
+            .send_from(&stamped.robot_id, &self.topic, &payload)
+    }
+
+    /// Tell subscribers this publisher is alive, e.g. while it has nothing to
+    /// publish, and what its clock says.
+    pub fn heartbeat(&self) -> Result<(), TransportError> {
+        let now_ns = clock::now_ns();
+        let heartbeat = match &self.clock {
+            Some(sync) => sync.heartbeat(now_ns),
+            None => Heartbeat {
+                sent_at_ns: now_ns,
+                echo: None,
+            },
+        };
+        self.transport
+            .send(&self.heartbeat_topic, &clock::to_payload(&heartbeat))
+    }
+
+    /// Send a control command to this topic's subscribers, as JSON whatever the format.
+    pub fn control(&self, command: ControlCommand) -> Result<(), TransportError> {
+        let control = Control {
+            command,
+            sent_at_us: Some(now_us()),
+        };
+        self.transport
+            .send(&control::topic(&self.topic), &control::to_payload(&control))
+    }
+}
+
+/// Wall-clock microseconds since the Unix epoch, for `sent_at_us`.
+fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_micros() as u64)
+}
+
+/// Yields states published on one or more topics, each named exactly or as
+/// a prefix ending in `*`: `hands/*` takes states from `hands/left`,
+/// `hands/right`, and any other topic starting with `hands/`.
+///
+/// ZMQ filters subscriptions by prefix, so subscribing to `robot_joints` also
+/// receives `robot_joints_debug`; those messages are skipped here.
+///
+/// States split by finger arrive as parts on `<topic>/<finger>`; the parts of
+/// one state (same `robot_id` and timestamp) that arrive within
+/// [`MERGE_WINDOW`] of each other come out as one state. Batches on
+/// [`batch_topic`] come out one state at a time, oldest first, unless
+/// [`Subscriber::with_batch_latest`] asks for only the newest of each.
+///
+/// Each payload's [`Format`] is detected unless [`Subscriber::with_format`]
+/// pins one, in which case anything else is a [`WireError`].
+///
+/// Heartbeats are consumed here and only move [`Subscriber::last_heard`] (and
+/// feed [`Subscriber::with_clock_sync`]); control commands also wait for
+/// [`Subscriber::take_controls`].
+///
+/// With [`Subscriber::with_mirror`], each state from the mirrored hand is
+/// followed by its mirror image for the other hand.
+///
+/// With [`Subscriber::with_keep_latest`], states queued behind the next one
+/// are skipped but for the newest from each robot on each topic.
+pub struct Subscriber<T = Box<dyn Transport>> {
+    transport: T,
+    filters: Vec<TopicFilter>,
+    format: Option<Format>,
+    last_heard: Cell<Option<Instant>>,
+    last_topic: RefCell<Option<String>>,
+    controls: RefCell<Vec<ControlCommand>>,
+    /// A payload received while merging parts that belongs to the next state.
+    held: RefCell<Option<Received>>,
+    mirror: Option<Mirror>,
+    /// The mirror image of the last state, yielded next.
+    mirrored: RefCell<Option<RobotState>>,
+    /// The rest of the last batch, yielded next.
+    batched: RefCell<VecDeque<RobotState>>,
+    batch_latest: bool,
+    keep_latest: bool,
+    /// The newest queued states, each with its topic, yielded next.
+    latest: RefCell<VecDeque<(String, RobotState)>>,
+    /// What went wrong while skipping queued states, reported next.
+    deferred: RefCell<Option<TransportError>>,
+    clock: Option<Arc<ClockSync>>,
+}
+
+/// How long a [`Subscriber`] waits after one part of a split state for the
+/// next; a publisher sends them back to back.
+pub const MERGE_WINDOW: Duration = Duration::from_millis(2);
+
+/// A state payload, the topic of the state it belongs to, and whether it is
+/// one finger's part of that state or a batch of states.
+struct Received {
+    topic: String,
+    payload: Vec<u8>,
+    part: bool,
+    batch: bool,
+}
+
+/// One topic a [`Subscriber`] takes, exactly or by prefix.
+enum TopicFilter {
+    Exact(String),
+    Prefix(String),
+}
+
+impl TopicFilter {
+    fn parse(topic: &str) -> Self {
+        match topic.strip_suffix('*') {
+            Some(prefix) => TopicFilter::Prefix(prefix.to_string()),
+            None => TopicFilter::Exact(topic.to_string()),
+        }
+    }
+
+    fn matches(&self, topic: &str) -> bool {
+        match self {
+            TopicFilter::Exact(exact) => topic == exact,
+            TopicFilter::Prefix(prefix) => topic.starts_with(prefix.as_str()),
+        }
+    }
+
+    /// Subscribe `transport` to the topic, its per-finger parts, its batches,
+    /// its heartbeats, and its control commands; a prefix covers them all.
+    fn subscribe(&self, transport: &impl Transport) -> Result<(), TransportError> {
+        match self {
+            TopicFilter::Exact(topic) => {
+                transport.subscribe(topic)?;
+                for part_topic in finger::topics(topic) {
+                    transport.subscribe(&part_topic)?;
+                }
+                transport.subscribe(&batch_topic(topic))?;
+                transport.subscribe(&heartbeat_topic(topic))?;
+                transport.subscribe(&control::topic(topic))
+            }
+            TopicFilter::Prefix(prefix) => transport.subscribe_prefix(prefix),
+        }
+    }
+}
+
+/// What a message on a subscribed topic carries.
+enum Message {
+    State,
+    Part,
+    Batch,
+    Heartbeat,
+    Control,
+}
+
+impl<T: Transport> Subscriber<T> {
+    /// Subscribe `transport` to `topic` (`hands/*` for a prefix), its
+    /// per-finger parts, its batches, its heartbeats, and its control commands.
+    pub fn new(transport: T, topic: &str) -> Result<Self, TransportError> {
+        Subscriber::for_topics(transport, [topic])
+    }
+
+    /// Subscribe `transport` to each of `topics`, as [`Subscriber::new`] does
+    /// to one.
+    pub fn for_topics<'a>(
+        transport: T,
+        topics: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, TransportError> {
+        let filters: Vec<TopicFilter> = topics.into_iter().map(TopicFilter::parse).collect();
+        for filter in &filters {
+            filter.subscribe(&transport)?;
+        }
+        Ok(Subscriber {
+            transport,
+            filters,
+            format: None,
+            last_heard: Cell::new(None),
+            last_topic: RefCell::default(),
+            controls: RefCell::default(),
+            held: RefCell::default(),
+            mirror: None,
+            mirrored: RefCell::default(),
+            batched: RefCell::default(),
+            batch_latest: false,
+            keep_latest: false,
+            latest: RefCell::default(),
+            deferred: RefCell::default(),
+            clock: None,
+        })
+    }
+
+    /// Decode every payload as `format` instead of detecting it.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Also yield `mirror`'s image of every state it applies to, right after
+    /// the state itself.
+    pub fn with_mirror(mut self, mirror: Mirror) -> Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// Yield only the newest state of each batch, if `latest`: for a consumer
+    /// that follows the current pose rather than logging every sample.
+    pub fn with_batch_latest(mut self, latest: bool) -> Self {
+        self.batch_latest = latest;
+        self
+    }
+
+    /// Yield only the newest of the states already queued, if `latest`: one
+    /// per robot and topic, oldest first. For a consumer slower than the
+    /// stream (a stalled render loop) that should catch up to the current
+    /// pose rather than replay every state it missed.
+    pub fn with_keep_latest(mut self, latest: bool) -> Self {
+        self.keep_latest = latest;
+        self
+    }
+
+    /// Feed the publisher's heartbeats to `sync`, to estimate how far its clock
+    /// is from ours; share `sync` with a [`Publisher`] sending heartbeats back
+    /// ([`Publisher::with_clock_sync`]) to complete the round trips.
+    pub fn with_clock_sync(mut self, sync: Arc<ClockSync>) -> Self {
+        self.clock = Some(sync);
+        self
+    }
+
+    /// When the last message on this topic or a heartbeat arrived, if any has.
+    pub fn last_heard(&self) -> Option<Instant> {
+        self.last_heard.get()
+    }
+
+    /// The topic the last state came on, if any has; for one merged from
+    /// finger parts, the topic that was split.
+    pub fn last_topic(&self) -> Option<String> {
+        self.last_topic.borrow().clone()
+    }
+
+    /// The control commands received since the last call, oldest first. They
+    /// arrive while receiving states, so call this after [`Subscriber::recv`].
+    pub fn take_controls(&self) -> Vec<ControlCommand> {
+        self.controls.take()
+    }
+
+    /// The next state if one is already queued; never waits.
+    pub fn try_recv(&self) -> Result<Option<RobotState>, TransportError> {
+        self.recv(Duration::ZERO)
+    }
+
+    /// Wait up to `timeout` for the next state on this topic, or for the
+    /// first part of one split by finger (then up to [`MERGE_WINDOW`] more).
+    pub fn recv(&self, timeout: Duration) -> Result<Option<RobotState>, TransportError> {
+        if let Some(mirrored) = self.mirrored.take() {
+            return Ok(Some(mirrored));
+        }
+        let state = if self.keep_latest {
+            self.recv_latest(timeout)?
+        } else {
+            self.recv_state(timeout)?
+        };
+        if let (Some(mirror), Some(state)) = (&self.mirror, &state) {
+            self.mirrored.replace(mirror.apply(state));
+        }
+        Ok(state)
+    }
+
+    /// [`Subscriber::recv`], but handing the state to `f` with its names
+    /// borrowed from the payload instead of returning it, so a loop that only
+    /// reads each state allocates no `String` per joint. States merged from
+    /// finger parts, unpacked from batches, and mirror images are lent from an
+    /// owned state instead.
+    pub fn recv_with<R>(
+        &self,
+        timeout: Duration,
+        f: impl FnOnce(RobotStateRef<'_>) -> R,
+    ) -> Result<Option<R>, TransportError> {
+        if self.mirror.is_some()
+            || self.keep_latest
+            || self.mirrored.borrow().is_some()
+            || !self.batched.borrow().is_empty()
+        {
+            return Ok(self.recv(timeout)?.as_ref().map(|state| f(state.into())));
+        }
+        let Some(received) = self.recv_payload(timeout)? else {
+            return Ok(None);
+        };
+        if received.part || received.batch {
+            // `recv_payload` hands it back first, to merge or unpack.
+            self.held.replace(Some(received));
+            return Ok(self
+                .recv_state(Duration::ZERO)?
+                .as_ref()
+                .map(|state| f(state.into())));
+        }
+        self.last_topic.replace(Some(received.topic));
+        let format = self
+            .format
+            .unwrap_or_else(|| Format::detect(&received.payload));
+        Ok(Some(f(format.decode_ref(&received.payload)?)))
+    }
+
+    /// The next of the newest queued states, draining the queue first if
+    /// none are left from the last time.
+    fn recv_latest(&self, timeout: Duration) -> Result<Option<RobotState>, TransportError> {
+        if self.latest.borrow().is_empty() {
+            if let Some(err) = self.deferred.take() {
+                return Err(err);
+            }
+            let Some(first) = self.recv_state(timeout)? else {
+                return Ok(None);
+            };
+            let mut latest = vec![(self.last_topic().unwrap_or_default(), first)];
+            loop {
+                match self.recv_state(Duration::ZERO) {
+                    Ok(Some(state)) => {
+                        let topic = self.last_topic().unwrap_or_default();
+                        latest.retain(|(t, s)| (t, &s.robot_id) != (&topic, &state.robot_id));
+                        latest.push((topic, state));
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        self.deferred.replace(Some(err));
+                        break;
+                    }
+                }
+            }
+            self.latest.replace(latest.into());
+        }
+        let Some((topic, state)) = self.latest.borrow_mut().pop_front() else {
+            return Ok(None);
+        };
+        self.last_topic.replace(Some(topic));
+        Ok(Some(state))
+    }
+
+    fn recv_state(&self, timeout: Duration) -> Result<Option<RobotState>, TransportError> {
+        if let Some(state) = self.batched.borrow_mut().pop_front() {
+            return Ok(Some(state));
+        }
+        let Some(received) = self.recv_payload(timeout)? else {
+            return Ok(None);
+        };
+        self.last_topic.replace(Some(received.topic));
+        if received.batch {
+            let format = self
+                .format
+                .unwrap_or_else(|| Format::detect(&received.payload));
+            let mut states = format.decode_batch(&received.payload)?.states;
+            if self.batch_latest {
+                return Ok(states.pop());
+            }
+            let mut states = VecDeque::from(states);
+            let first = states.pop_front();
+            self.batched.replace(states);
+            return Ok(first);
+        }
+        let mut state = self.decode(&received.payload)?;
+        if !received.part {
+            return Ok(Some(state));
+        }
+        while let Some(next) = self.recv_payload(MERGE_WINDOW)? {
+            let same_state = next.part
+                && self.last_topic.borrow().as_ref() == Some(&next.topic)
+                && self.decode(&next.payload).is_ok_and(|part| {
+                    if part.robot_id == state.robot_id && part.timestamp == state.timestamp {
+                        state.joints.extend(part.joints);
+                        true
+                    } else {
+                        false
+                    }
+                });
+            if !same_state {
+                // Bad payloads, too, are reported by the next call.
+                self.held.replace(Some(next));
+                break;
+            }
+        }
+        Ok(Some(state))
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<RobotState, WireError> {
+        match self.format {
+            Some(format) => format.decode(payload),
+            None => wire::from_payload(payload),
+        }
+    }
+
+    /// Wait up to `timeout` for the next poses on this topic, for a subscriber
+    /// to a poses topic rather than a states one.
+    pub fn recv_poses(&self, timeout: Duration) -> Result<Option<BodyPoses>, TransportError> {
+        let Some(Received { payload, .. }) = self.recv_payload(timeout)? else {
+            return Ok(None);
+        };
+        let format = self.format.unwrap_or_else(|| Format::detect(&payload));
+        Ok(Some(format.decode_poses(&payload)?))
+    }
+
+    fn recv_payload(&self, timeout: Duration) -> Result<Option<Received>, TransportError> {
+        if let Some(held) = self.held.take() {
+            return Ok(Some(held));
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let Some((topic, payload)) = self.transport.recv(left)? else {
+                return Ok(None);
+            };
+            let Some((message, topic)) = self.classify(topic) else {
+                continue;
+            };
+            // Even an undecodable message means the publisher is there.
+            self.last_heard.set(Some(Instant::now()));
+            match message {
+                Message::Heartbeat => {
+                    // Older publishers' heartbeats are empty: nothing to sync.
+                    if let (Some(sync), Some(heartbeat)) =
+                        (&self.clock, clock::from_payload(&payload))
+                    {
+                        sync.heard(&heartbeat, clock::now_ns());
+                    }
+                }
+                Message::Control => {
+                    let control = control::from_payload(&payload)?;
+                    self.controls.borrow_mut().push(control.command);
+                }
+                Message::State | Message::Part | Message::Batch => {
+                    return Ok(Some(Received {
+                        topic,
+                        payload: wire::decompress(payload)?,
+                        part: matches!(message, Message::Part),
+                        batch: matches!(message, Message::Batch),
+                    }));
+                }
+            }
+        }
+    }
+
+    /// What a message on `topic` carries and the topic of the state it
+    /// belongs to, or `None` if no filter takes it.
+    fn classify(&self, topic: String) -> Option<(Message, String)> {
+        let matches = |topic: &str| self.filters.iter().any(|filter| filter.matches(topic));
+        if let Some((base, last)) = topic.rsplit_once('/') {
+            if matches(base) {
+                let message = if topic == heartbeat_topic(base) {
+                    Some(Message::Heartbeat)
+                } else if topic == control::topic(base) {
+                    Some(Message::Control)
+                } else if topic == batch_topic(base) {
+                    Some(Message::Batch)
+                } else if finger::is_part(last) {
+                    Some(Message::Part)
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    return Some((message, base.to_string()));
+                }
+            }
+        }
+        matches(&topic).then_some((Message::State, topic))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+
+    #[test]
+    fn publisher_and_subscriber_work_over_any_transport() {
+        let bus = InprocTransport::new();
+        let boxed: Box<dyn Transport> = Box::new(bus.connect());
+        let subscriber: Subscriber = Subscriber::new(boxed, "arm").unwrap();
+        let publisher = Publisher::new(bus, "arm");
+
+        publisher.publish(&simulated_state(3)).unwrap();
+        let state = subscriber.recv(Duration::from_secs(1)).unwrap().unwrap();
+        assert_eq!(state.sequence, Some(3));
+        // Stamped on publishing, the state and its joints alike.
+        assert!(state.timestamp > 1_700_000_000_000_000_000);
+        assert_eq!(state.joints[0].timestamp, state.timestamp);
+        assert!(state
+            .sent_at_us
+            .is_some_and(|us| us > 1_700_000_000_000_000));
+        assert!(subscriber.try_recv().unwrap().is_none());
+    }
+
+    #[test]
+    fn poses_travel_on_their_own_topic() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), crate::poses::TOPIC).unwrap();
+        let publisher =
+            Publisher::new(bus.connect(), crate::poses::TOPIC).with_format(Format::MessagePack);
+        let poses = crate::poses::from_state(
+            &simulated_state(4),
+            [("tip", [0.1, 0.0, 0.2], [1.0, 0.0, 0.0, 0.0])],
+        );
+
+        publisher.publish_poses(&poses).unwrap();
+        let received = subscriber
+            .recv_poses(Duration::from_secs(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.bodies, poses.bodies);
+        assert!(received.sent_at_us.is_some());
+    }
+
+    #[test]
+    fn other_topics_and_bad_payloads_are_told_apart() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "arm").unwrap();
+        // Subscribed to both, but `Subscriber` only wants exactly "arm".
+        subscriber.transport.subscribe("arm_debug").unwrap();
+
+        bus.send("arm_debug", b"{}").unwrap();
+        bus.send("arm", b"not json").unwrap();
+        let err = subscriber.try_recv().unwrap_err();
+        assert!(matches!(err, TransportError::Wire(_)), "{err:?}");
+        assert_eq!(err.exit(), Exit::Data);
+    }
+
+    #[test]
+    fn heartbeats_keep_a_subscriber_hearing_without_states() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "arm").unwrap();
+        let publisher = Publisher::new(bus.connect(), "arm");
+        assert_eq!(subscriber.last_heard(), None);
+
+        let before = Instant::now();
+        publisher.heartbeat().unwrap();
+        assert!(subscriber.try_recv().unwrap().is_none());
+        let heard = subscriber.last_heard().unwrap();
+        assert!(heard >= before);
+
+        publisher.publish(&simulated_state(1)).unwrap();
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().sequence, Some(1));
+        assert!(subscriber.last_heard().unwrap() >= heard);
+    }
+
+    #[test]
+    fn heartbeat_round_trips_estimate_the_clock_offset() {
+        let bus = InprocTransport::new();
+        let (ours, theirs) = (
+            Arc::new(ClockSync::default()),
+            Arc::new(ClockSync::default()),
+        );
+        // Each side publishes on its own topic and hears the other's.
+        let our_publisher = Publisher::new(bus.connect(), "arm").with_clock_sync(Arc::clone(&ours));
+        let their_subscriber = Subscriber::new(bus.connect(), "arm")
+            .unwrap()
+            .with_clock_sync(Arc::clone(&theirs));
+        let their_publisher = Publisher::new(bus.connect(), "arm_actual").with_clock_sync(theirs);
+        let our_subscriber = Subscriber::new(bus.connect(), "arm_actual")
+            .unwrap()
+            .with_clock_sync(Arc::clone(&ours));
+
+        our_publisher.heartbeat().unwrap();
+        assert!(their_subscriber.try_recv().unwrap().is_none());
+        their_publisher.heartbeat().unwrap();
+        assert!(our_subscriber.try_recv().unwrap().is_none());
+        // One clock at both ends: the offset is within the trip's uncertainty.
+        let offset = ours.offset().unwrap();
+        assert!(
+            offset.offset_ns.unsigned_abs() <= offset.round_trip_ns,
+            "{offset:?}"
+        );
+
+        // Heartbeats without a sync, or from older publishers, are only heartbeats.
+        Publisher::new(bus.connect(), "arm_actual")
+            .heartbeat()
+            .unwrap();
+        bus.send(&heartbeat_topic("arm_actual"), &[]).unwrap();
+        assert!(our_subscriber.try_recv().unwrap().is_none());
+        assert_eq!(ours.offset(), Some(offset));
+    }
+
+    #[test]
+    fn control_commands_wait_next_to_the_states() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "arm").unwrap();
+        let publisher = Publisher::new(bus.connect(), "arm").with_format(Format::Protobuf);
+
+        publisher.control(ControlCommand::Hold).unwrap();
+        publisher.publish(&simulated_state(1)).unwrap();
+        publisher.control(ControlCommand::EStop).unwrap();
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().sequence, Some(1));
+        assert!(subscriber.try_recv().unwrap().is_none());
+        assert_eq!(
+            subscriber.take_controls(),
+            [ControlCommand::Hold, ControlCommand::EStop]
+        );
+        assert!(subscriber.take_controls().is_empty());
+    }
+
+    #[test]
+    fn subscribers_detect_the_format_unless_told() {
+        let bus = InprocTransport::new();
+        let detecting = Subscriber::new(bus.connect(), "arm").unwrap();
+        let pinned = Subscriber::new(bus.connect(), "arm")
+            .unwrap()
+            .with_format(Format::Json);
+        let json = Publisher::new(bus.connect(), "arm");
+        let protobuf = Publisher::new(bus.connect(), "arm").with_format(Format::Protobuf);
+
+        json.publish(&simulated_state(1)).unwrap();
+        protobuf.publish(&simulated_state(2)).unwrap();
+        assert_eq!(detecting.try_recv().unwrap().unwrap().sequence, Some(1));
+        assert_eq!(detecting.try_recv().unwrap().unwrap().sequence, Some(2));
+        assert_eq!(pinned.try_recv().unwrap().unwrap().sequence, Some(1));
+        assert!(matches!(
+            pinned.try_recv(),
+            Err(TransportError::Wire(WireError::Json(_)))
+        ));
+    }
+    #[test]
+    fn compressed_payloads_decompress_whatever_the_format() {
+        let bus = InprocTransport::new();
+        let detecting = Subscriber::new(bus.connect(), "arm").unwrap();
+        let pinned = Subscriber::new(bus.connect(), "arm")
+            .unwrap()
+            .with_format(Format::Protobuf);
+        let publisher = Publisher::new(bus.connect(), "arm")
+            .with_format(Format::Protobuf)
+            .with_compression(Compression::Zstd);
+
+        publisher.publish(&simulated_state(5)).unwrap();
+        assert_eq!(detecting.try_recv().unwrap().unwrap().sequence, Some(5));
+        assert_eq!(pinned.try_recv().unwrap().unwrap().sequence, Some(5));
+    }
+
+    #[test]
+    fn finger_parts_merge_back_into_one_state() {
+        let bus = InprocTransport::new();
+        let whole = Subscriber::new(bus.connect(), "hand").unwrap();
+        let thumb = Subscriber::new(bus.connect(), &finger::topic("hand", "thumb")).unwrap();
+        let publisher = Publisher::new(bus.connect(), "hand").with_split_by_finger(true);
+        let state = |sequence| {
+            let mut state = simulated_state(sequence);
+            let joint = state.joints[0].clone();
+            state.joints = ["i1_MCP", "t1_TM", "t2_CMC", "wrist"]
+                .map(|name| crate::JointAngles {
+                    joint_name: name.into(),
+                    ..joint.clone()
+                })
+                .into();
+            state
+        };
+
+        publisher.publish(&state(1)).unwrap();
+        publisher.publish(&state(2)).unwrap();
+        for sequence in [1, 2] {
+            let merged = whole.try_recv().unwrap().unwrap();
+            assert_eq!(merged.sequence, Some(sequence));
+            let names: Vec<_> = merged
+                .joints
+                .iter()
+                .map(|j| j.joint_name.as_str())
+                .collect();
+            assert_eq!(names, ["t1_TM", "t2_CMC", "i1_MCP", "wrist"]);
+        }
+        assert!(whole.try_recv().unwrap().is_none());
+
+        let part = thumb.try_recv().unwrap().unwrap();
+        assert_eq!((part.sequence, part.joints.len()), (Some(1), 2));
+    }
+
+    #[test]
+    fn states_can_be_received_borrowed() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "hand").unwrap();
+        let whole = Publisher::new(bus.connect(), "hand").with_format(Format::MessagePack);
+        let split = Publisher::new(bus.connect(), "hand").with_split_by_finger(true);
+        let names = |state: RobotStateRef<'_>| {
+            let names: Vec<String> = state
+                .joints
+                .iter()
+                .map(|j| j.joint_name.to_string())
+                .collect();
+            (state.sequence, names)
+        };
+        let expected = simulated_state(1).joints[0].joint_name.clone();
+
+        whole.publish(&simulated_state(1)).unwrap();
+        split.publish(&simulated_state(2)).unwrap();
+        bus.send("hand", b"not json").unwrap();
+        for sequence in [1, 2] {
+            let (got, joints) = subscriber
+                .recv_with(Duration::ZERO, names)
+                .unwrap()
+                .unwrap();
+            assert_eq!((got, joints), (Some(sequence), vec![expected.clone()]));
+        }
+        assert!(subscriber.recv_with(Duration::ZERO, names).is_err());
+        assert!(subscriber
+            .recv_with(Duration::ZERO, names)
+            .unwrap()
+            .is_none());
+        assert_eq!(subscriber.last_topic().as_deref(), Some("hand"));
+    }
+
+    #[test]
+    fn batches_unpack_in_order_or_to_the_latest() {
+        let bus = InprocTransport::new();
+        let every = Subscriber::new(bus.connect(), "arm").unwrap();
+        let latest = Subscriber::new(bus.connect(), "arm")
+            .unwrap()
+            .with_batch_latest(true);
+        let publisher = Publisher::new(bus.connect(), "arm")
+            .with_format(Format::Protobuf)
+            .with_batch(Duration::from_secs(60));
+
+        for sequence in 1..=3 {
+            publisher.publish(&simulated_state(sequence)).unwrap();
+        }
+        // Held back until the window ends or the publisher flushes.
+        assert!(every.try_recv().unwrap().is_none());
+        publisher.flush().unwrap();
+        publisher.flush().unwrap();
+        bus.send("arm", &Format::Json.encode(&simulated_state(4)))
+            .unwrap();
+
+        let sequences: Vec<_> = std::iter::from_fn(|| every.try_recv().unwrap())
+            .map(|state| state.order())
+            .collect();
+        assert_eq!(sequences, [1, 2, 3, 4]);
+        assert_eq!(every.last_topic().as_deref(), Some("arm"));
+        assert_eq!(latest.try_recv().unwrap().unwrap().sequence, Some(3));
+        assert_eq!(latest.try_recv().unwrap().unwrap().sequence, Some(4));
+    }
+
+    #[test]
+    fn a_deadband_skips_states_that_barely_moved() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "arm").unwrap();
+        let publisher =
+            Publisher::new(bus.connect(), "arm").with_deadband(0.01, Duration::from_millis(50));
+        let moved = |sequence, by: f64| {
+            let mut state = simulated_state(1);
+            state.sequence = Some(sequence);
+            state.joints[0].angle_rad += by;
+            state
+        };
+
+        // The first state, a move within the deadband, one past it measured
+        // from the last sent (not the last offered), and a repeat.
+        for (sequence, by) in [(1, 0.0), (2, 0.008), (3, 0.016), (4, 0.016)] {
+            publisher.publish(&moved(sequence, by)).unwrap();
+        }
+        let sequences: Vec<_> = std::iter::from_fn(|| subscriber.try_recv().unwrap())
+            .map(|state| state.order())
+            .collect();
+        assert_eq!(sequences, [1, 3]);
+
+        // Holding still, a state goes out once the keepalive is up.
+        std::thread::sleep(Duration::from_millis(60));
+        publisher.publish(&moved(5, 0.016)).unwrap();
+        publisher.publish(&moved(6, 0.016)).unwrap();
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().order(), 5);
+        assert!(subscriber.try_recv().unwrap().is_none());
+    }
+
+    #[test]
+    fn keeping_the_latest_skips_the_backlog() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::for_topics(bus.connect(), ["arm", "hand"])
+            .unwrap()
+            .with_keep_latest(true);
+        let arm = Publisher::new(bus.connect(), "arm");
+        let hand = Publisher::new(bus.connect(), "hand");
+        let twin = |sequence| RobotState {
+            robot_id: "twin".into(),
+            ..simulated_state(sequence)
+        };
+
+        for sequence in 1..=5 {
+            arm.publish(&simulated_state(sequence)).unwrap();
+            arm.publish(&twin(sequence)).unwrap();
+            hand.publish(&simulated_state(sequence * 10)).unwrap();
+        }
+        arm.control(ControlCommand::Hold).unwrap();
+        bus.send("arm", b"not json").unwrap();
+        let latest: Vec<_> = (0..3)
+            .map(|_| {
+                let state = subscriber.try_recv().unwrap().unwrap();
+                let topic = subscriber.last_topic().unwrap();
+                (topic, state.order(), state.robot_id)
+            })
+            .collect();
+        let sim = crate::sim::ROBOT_ID.to_string();
+        assert_eq!(
+            latest,
+            [
+                ("arm".into(), 5, sim.clone()),
+                ("arm".into(), 5, "twin".into()),
+                ("hand".into(), 50, sim),
+            ]
+        );
+        // Messages on the way are still handled, and errors still reported.
+        assert_eq!(subscriber.take_controls(), [ControlCommand::Hold]);
+        assert!(subscriber.try_recv().is_err());
+
+        arm.publish(&simulated_state(6)).unwrap();
+        assert_eq!(subscriber.try_recv().unwrap().unwrap().order(), 6);
+    }
+
+    #[test]
+    fn subscribers_take_several_topics_and_prefixes() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::for_topics(bus.connect(), ["arm", "hands/*"]).unwrap();
+        let publish = |topic: &str, sequence| {
+            Publisher::new(bus.connect(), topic)
+                .publish(&simulated_state(sequence))
+                .unwrap();
+        };
+
+        publish("arm", 1);
+        publish("hands/left", 2);
+        publish("arm_debug", 3);
+        publish("hands/right", 4);
+        Publisher::new(bus.connect(), "hands/right")
+            .heartbeat()
+            .unwrap();
+        Publisher::new(bus.connect(), "hands/left")
+            .control(ControlCommand::Hold)
+            .unwrap();
+        for (sequence, topic) in [(1, "arm"), (2, "hands/left"), (4, "hands/right")] {
+            assert_eq!(subscriber.try_recv().unwrap().unwrap().order(), sequence);
+            assert_eq!(subscriber.last_topic().as_deref(), Some(topic));
+        }
+        assert!(subscriber.try_recv().unwrap().is_none());
+        assert_eq!(subscriber.take_controls(), [ControlCommand::Hold]);
+    }
+
+    #[test]
+    fn mirrored_states_follow_their_originals() {
+        let bus = InprocTransport::new();
+        let subscriber = Subscriber::new(bus.connect(), "arm")
+            .unwrap()
+            .with_mirror(Mirror::new(crate::sim::ROBOT_ID, "twin"));
+        let publisher = Publisher::new(bus.connect(), "arm");
+
+        publisher.publish(&simulated_state(1)).unwrap();
+        let robot_ids: Vec<_> = std::iter::from_fn(|| subscriber.try_recv().unwrap())
+            .map(|state| state.robot_id)
+            .collect();
+        assert_eq!(robot_ids, [crate::sim::ROBOT_ID, "twin"]);
+    }
+
+    #[cfg(not(feature = "zenoh-transport"))]
+    #[test]
+    fn zenoh_without_its_feature_is_a_usage_error() {
+        let err = open_zenoh(&[]).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "built without the `zenoh-transport` feature"
+        );
+        assert_eq!(err.exit(), Exit::Usage);
+    }
+}
@@ -0,0 +1,138 @@
+//! A [`Subscriber`] for async code: the blocking receive runs on a thread of
+//! its own and hands states over a channel, so a tokio task can wait for the
+//! next one alongside timers, signals, and sockets in `tokio::select!`.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::{Subscriber, Transport, TransportError};
+use crate::RobotState;
+
+/// How long one blocking receive waits, i.e. how soon the thread notices its
+/// [`AsyncSubscriber`] is gone.
+const POLL: Duration = Duration::from_millis(100);
+
+/// States the thread may get ahead of the task by; past that it waits, and
+/// the transport queues or drops as it does for a slow synchronous subscriber.
+const BACKLOG: usize = 256;
+
+/// Receives states without blocking the runtime. Dropping it stops the thread.
+///
+/// Only states (and the errors receiving them) come through; heartbeats are
+/// consumed on the thread and control commands dropped, so code that needs
+/// [`Subscriber::last_heard`] or [`Subscriber::take_controls`] should keep a
+/// [`Subscriber`] on a thread of its own.
+pub struct AsyncSubscriber {
+    states: mpsc::Receiver<Result<RobotState, TransportError>>,
+}
+
+impl AsyncSubscriber {
+    /// Start receiving with `subscriber` on a new thread.
+    pub fn spawn<T: Transport + 'static>(subscriber: Subscriber<T>) -> Self {
+        let (sender, states) = mpsc::channel(BACKLOG);
+        std::thread::spawn(move || {
+            while !sender.is_closed() {
+                let received = match subscriber.recv(POLL) {
+                    Ok(Some(state)) => Ok(state),
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                };
+                // A closed transport stays closed: report it once, then end the stream.
+                let closed = matches!(received, Err(TransportError::Closed));
+                if sender.blocking_send(received).is_err() || closed {
+                    break;
+                }
+            }
+        });
+        AsyncSubscriber { states }
+    }
+
+    /// The next state, or why one could not be received. `None` once the
+    /// transport has closed (after one [`TransportError::Closed`]), or if the
+    /// receiving thread has died.
+    ///
+    /// Cancel safe: in `tokio::select!`, a state is never lost to another
+    /// branch finishing first.
+    pub async fn recv(&mut self) -> Option<Result<RobotState, TransportError>> {
+        self.states.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::simulated_state;
+    use crate::{InprocTransport, Publisher};
+
+    #[tokio::test]
+    async fn states_arrive_alongside_timers() {
+        let bus = InprocTransport::new();
+        let mut states = AsyncSubscriber::spawn(Subscriber::new(bus.connect(), "arm").unwrap());
+        let publisher = Publisher::new(bus.connect(), "arm");
+        publisher.publish(&simulated_state(1)).unwrap();
+        publisher.publish(&simulated_state(2)).unwrap();
+
+        for expected in [1, 2] {
+            let state = tokio::select! {
+                state = states.recv() => state.unwrap().unwrap(),
+                _ = tokio::time::sleep(Duration::from_secs(5)) => panic!("no state"),
+            };
+            assert_eq!(state.sequence, Some(expected));
+        }
+        let idle = tokio::time::timeout(Duration::from_millis(50), states.recv()).await;
+        assert!(idle.is_err(), "nothing more was published");
+    }
+
+    #[tokio::test]
+    async fn bad_messages_come_through_as_errors() {
+        let bus = InprocTransport::new();
+        let mut states = AsyncSubscriber::spawn(Subscriber::new(bus.connect(), "arm").unwrap());
+        bus.connect().send("arm", b"{not json").unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), states.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(received, Err(TransportError::Wire(_))));
+    }
+    /// An endpoint whose bus can go away, as an MQTT or Zenoh connection does;
+    /// an `InprocTransport` endpoint keeps its bus alive.
+    struct Endpoint(std::sync::mpsc::Receiver<(String, Vec<u8>)>);
+
+    impl Transport for Endpoint {
+        fn subscribe(&self, _topic: &str) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        fn subscribe_prefix(&self, _prefix: &str) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        fn send(&self, _topic: &str, _payload: &[u8]) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError> {
+            match self.0.recv_timeout(timeout) {
+                Ok(message) => Ok(Some(message)),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Ok(None),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(TransportError::Closed),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn a_closed_transport_ends_the_stream() {
+        let (bus, inbox) = std::sync::mpsc::channel();
+        let mut states = AsyncSubscriber::spawn(Subscriber::new(Endpoint(inbox), "arm").unwrap());
+        drop(bus);
+        let patience = Duration::from_secs(5);
+        let received = tokio::time::timeout(patience, states.recv()).await.unwrap();
+        assert!(
+            matches!(received, Some(Err(TransportError::Closed))),
+            "{received:?}"
+        );
+        let after = tokio::time::timeout(patience, states.recv()).await.unwrap();
+        assert!(after.is_none(), "{after:?}");
+    }
+}
@@ -0,0 +1,181 @@
+//! An in-process bus of channels, so tests can run publisher and subscriber
+//! code without sockets, ports, or timing assumptions.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Transport, TransportError};
+
+type Message = (String, Vec<u8>);
+
+/// The topics an endpoint receives: these exactly, and any starting with one
+/// of the prefixes.
+#[derive(Default)]
+pub(super) struct Subscriptions {
+    pub(super) topics: HashSet<String>,
+    pub(super) prefixes: Vec<String>,
+}
+
+impl Subscriptions {
+    pub(super) fn contains(&self, topic: &str) -> bool {
+        self.topics.contains(topic)
+            || self
+                .prefixes
+                .iter()
+                .any(|prefix| topic.starts_with(prefix.as_str()))
+    }
+}
+
+/// One endpoint's subscriptions and inbox.
+struct Endpoint {
+    id: usize,
+    topics: Arc<Mutex<Subscriptions>>,
+    inbox: Sender<Message>,
+}
+
+#[derive(Default)]
+struct Bus {
+    next_id: usize,
+    endpoints: Vec<Endpoint>,
+}
+
+/// One endpoint on an in-process bus; [`InprocTransport::connect`] adds more.
+///
+/// A message reaches every *other* endpoint subscribed to exactly its topic
+/// or to a prefix of it, immediately and in order. Unlike ZMQ there is no slow-joiner window: an
+/// endpoint receives everything sent after it subscribed.
+pub struct InprocTransport {
+    id: usize,
+    bus: Arc<Mutex<Bus>>,
+    topics: Arc<Mutex<Subscriptions>>,
+    inbox: Receiver<Message>,
+}
+
+impl InprocTransport {
+    /// The first endpoint on a new bus.
+    pub fn new() -> Self {
+        InprocTransport::join(Arc::default())
+    }
+
+    /// Another endpoint on the same bus as `self`.
+    pub fn connect(&self) -> Self {
+        InprocTransport::join(Arc::clone(&self.bus))
+    }
+
+    fn join(bus: Arc<Mutex<Bus>>) -> Self {
+        let (tx, inbox) = mpsc::channel();
+        let topics = Arc::<Mutex<Subscriptions>>::default();
+        let id = {
+            let mut bus = bus.lock().expect("inproc bus lock");
+            let id = bus.next_id;
+            bus.next_id += 1;
+            bus.endpoints.push(Endpoint {
+                id,
+                topics: Arc::clone(&topics),
+                inbox: tx,
+            });
+            id
+        };
+        InprocTransport {
+            id,
+            bus,
+            topics,
+            inbox,
+        }
+    }
+}
+
+impl Default for InprocTransport {
+    fn default() -> Self {
+        InprocTransport::new()
+    }
+}
+
+impl Drop for InprocTransport {
+    fn drop(&mut self) {
+        if let Ok(mut bus) = self.bus.lock() {
+            bus.endpoints.retain(|endpoint| endpoint.id != self.id);
+        }
+    }
+}
+
+impl Transport for InprocTransport {
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        self.topics
+            .lock()
+            .expect("inproc topics lock")
+            .topics
+            .insert(topic.to_string());
+        Ok(())
+    }
+
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        self.topics
+            .lock()
+            .expect("inproc topics lock")
+            .prefixes
+            .push(prefix.to_string());
+        Ok(())
+    }
+
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        let bus = self.bus.lock().expect("inproc bus lock");
+        for endpoint in bus.endpoints.iter().filter(|e| e.id != self.id) {
+            if endpoint
+                .topics
+                .lock()
+                .expect("inproc topics lock")
+                .contains(topic)
+            {
+                // Endpoints leave the bus when dropped, so every inbox is live.
+                let _ = endpoint.inbox.send((topic.to_string(), payload.to_vec()));
+            }
+        }
+        Ok(())
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<Message>, TransportError> {
+        match self.inbox.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            // The bus holds our sender for as long as we exist.
+            Err(RecvTimeoutError::Disconnected) => Err(TransportError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivers_to_other_subscribed_endpoints_only() {
+        let a = InprocTransport::new();
+        let b = a.connect();
+        let c = a.connect();
+        a.subscribe("t").unwrap();
+        b.subscribe("t").unwrap();
+
+        a.send("t", b"hello").unwrap();
+        a.send("other", b"ignored").unwrap();
+        c.subscribe_prefix("t/").unwrap();
+        a.send("t/1", b"by prefix").unwrap();
+        assert_eq!(
+            b.recv(Duration::ZERO).unwrap(),
+            Some(("t".to_string(), b"hello".to_vec()))
+        );
+        assert_eq!(b.recv(Duration::ZERO).unwrap(), None);
+        assert_eq!(
+            c.recv(Duration::ZERO).unwrap(),
+            Some(("t/1".to_string(), b"by prefix".to_vec()))
+        );
+        // The sender does not hear itself, and `c` never subscribed to `t`.
+        assert_eq!(a.recv(Duration::ZERO).unwrap(), None);
+        assert_eq!(c.recv(Duration::ZERO).unwrap(), None);
+
+        drop(b);
+        a.send("t", b"nobody left").unwrap();
+    }
+}
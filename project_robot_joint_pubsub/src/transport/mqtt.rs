@@ -0,0 +1,206 @@
+//! MQTT publish/subscribe through a broker, for networks where the publisher
+//! cannot bind its own port.
+//!
+//! The pub/sub topic is the MQTT topic and each payload is the [`RobotState`](crate::RobotState) as
+//! JSON, so any MQTT client (`mosquitto_sub -t robot_joints`) can read it.
+//! Messages use QoS 0, like ZMQ PUB/SUB: a state that cannot be delivered is
+//! dropped, and the next one replaces it.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use errors::{Exit, ExitStatus};
+use rumqttc::{Client, ClientError, ConnectionError, Event, MqttOptions, Packet, QoS};
+use thiserror::Error;
+
+use super::{Transport, TransportError};
+
+/// Port used when `--broker` does not name one.
+pub const DEFAULT_PORT: u16 = 1883;
+
+/// Requests queued for the broker before [`MqttTransport`] starts dropping messages.
+const QUEUE: usize = 64;
+/// Pause before reconnecting after the broker connection fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// A broker address that does not parse, or a broker failure.
+#[derive(Debug, Error)]
+pub enum MqttError {
+    #[error("invalid broker address {0:?} (expected host[:port] or mqtt://host[:port])")]
+    Broker(String),
+    // Boxed: both rumqttc errors are large enough to bloat every `Result`.
+    /// MQTT wildcards only match whole levels, so a prefix must end at one.
+    #[error("cannot subscribe to topic prefix {0:?} over MQTT (it must be empty or end in '/')")]
+    Prefix(String),
+    #[error("MQTT client error")]
+    Client(#[source] Box<ClientError>),
+    #[error("MQTT connection error")]
+    Connection(#[source] Box<ConnectionError>),
+}
+
+impl From<ClientError> for MqttError {
+    fn from(err: ClientError) -> Self {
+        MqttError::Client(Box::new(err))
+    }
+}
+
+impl From<ConnectionError> for MqttError {
+    fn from(err: ConnectionError) -> Self {
+        MqttError::Connection(Box::new(err))
+    }
+}
+
+impl ExitStatus for MqttError {
+    fn exit(&self) -> Exit {
+        match self {
+            MqttError::Broker(_) | MqttError::Prefix(_) => Exit::Usage,
+            MqttError::Client(_) | MqttError::Connection(_) => Exit::Network,
+        }
+    }
+}
+
+/// Split `broker` (`host`, `host:port`, or `mqtt://host:port`) into host and port.
+pub fn parse_broker(broker: &str) -> Result<(String, u16), MqttError> {
+    let invalid = || MqttError::Broker(broker.to_string());
+    let address = broker.strip_prefix("mqtt://").unwrap_or(broker);
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+        None => (address, DEFAULT_PORT),
+    };
+    if host.is_empty() || host.contains('/') {
+        return Err(invalid());
+    }
+    Ok((host.to_string(), port))
+}
+
+fn options(broker: &str) -> Result<MqttOptions, MqttError> {
+    let (host, port) = parse_broker(broker)?;
+    // Client ids must be unique per broker: processes differ by pid, and
+    // transports within one process by a counter.
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let id = format!("{}-{}-{n}", crate::config::APP_NAME, std::process::id());
+    let mut options = MqttOptions::new(id, host, port);
+    options.set_keep_alive(Duration::from_secs(5));
+    Ok(options)
+}
+
+/// A broker connection that both publishes and receives.
+pub struct MqttTransport {
+    // Shared with the connection thread only weakly, so dropping the transport
+    // drops the last client and ends the thread.
+    client: Arc<Client>,
+    topics: Arc<Mutex<Vec<String>>>,
+    incoming: Receiver<(String, Vec<u8>)>,
+}
+
+impl MqttTransport {
+    /// Connect to `broker` in the background; like ZMQ, the broker does not need
+    /// to be up yet and the connection is retried until it is.
+    pub fn connect(broker: &str) -> Result<Self, MqttError> {
+        let (client, mut connection) = Client::new(options(broker)?, QUEUE);
+        let client = Arc::new(client);
+        let topics = Arc::<Mutex<Vec<String>>>::default();
+        let (tx, incoming) = mpsc::channel();
+        let weak_client = Arc::downgrade(&client);
+        let subscribed = Arc::clone(&topics);
+        thread::spawn(move || {
+            for event in connection.iter() {
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        let Some(client) = weak_client.upgrade() else {
+                            break;
+                        };
+                        // A clean session forgets subscriptions, so renew them on every connect.
+                        for topic in subscribed.lock().expect("MQTT topics lock").iter() {
+                            if let Err(e) = client.try_subscribe(topic, QoS::AtMostOnce) {
+                                tracing::warn!(error = %e, %topic, "MQTT subscribe failed");
+                            }
+                        }
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        if tx.send((publish.topic, publish.payload.to_vec())).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!(error = %errors::report(&e), "MQTT broker unreachable; retrying");
+                        thread::sleep(RECONNECT_DELAY);
+                    }
+                }
+            }
+        });
+        Ok(MqttTransport {
+            client,
+            topics,
+            incoming,
+        })
+    }
+}
+
+impl Transport for MqttTransport {
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        self.topics
+            .lock()
+            .expect("MQTT topics lock")
+            .push(topic.to_string());
+        // Queued until connected; renewed after every reconnect.
+        self.client
+            .try_subscribe(topic, QoS::AtMostOnce)
+            .map_err(MqttError::from)?;
+        Ok(())
+    }
+
+    /// Subscribes to `<prefix>#`.
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        if !(prefix.is_empty() || prefix.ends_with('/')) {
+            return Err(MqttError::Prefix(prefix.to_string()).into());
+        }
+        self.subscribe(&format!("{prefix}#"))
+    }
+
+    /// Never blocks: while the broker is unreachable and the queue is full,
+    /// messages are dropped.
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        match self
+            .client
+            .try_publish(topic, QoS::AtMostOnce, false, payload.to_vec())
+        {
+            Ok(()) | Err(ClientError::TryRequest(_)) => Ok(()),
+            Err(e) => Err(MqttError::from(e).into()),
+        }
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError> {
+        match self.incoming.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(TransportError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_broker_addresses() {
+        assert_eq!(
+            parse_broker("broker.local").unwrap(),
+            ("broker.local".to_string(), DEFAULT_PORT)
+        );
+        assert_eq!(
+            parse_broker("mqtt://10.0.0.5:1884").unwrap(),
+            ("10.0.0.5".to_string(), 1884)
+        );
+        for bad in ["", ":1883", "broker:port", "tcp://broker:1883"] {
+            let err = parse_broker(bad).unwrap_err();
+            assert_eq!(err.exit(), Exit::Usage, "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! A shared-memory ring buffer, for a publisher and subscribers on one host
+//! that want to skip the socket copies of ZMQ `ipc://`.
+//!
+//! Each ring is a file in `/dev/shm` (or the temp directory where there is
+//! none) named after the publisher's topic, mapped by every process using it:
+//! [`SLOTS`] fixed slots of [`SLOT_SIZE`] bytes, behind a small header holding
+//! the number of the last message written. One publisher owns a ring (it holds
+//! a lock on the file); any number of subscribers read it without telling it.
+//!
+//! Like ZMQ PUB/SUB, the publisher never waits: a subscriber that falls more
+//! than [`SLOTS`] messages behind skips ahead to the oldest message still in
+//! the ring, and a subscriber only sees messages written after it opened the
+//! ring (or all of them, if it opened before the publisher created it). Each slot is a seqlock, so a reader never returns a message the
+//! publisher was overwriting while it was copied out.
+//!
+//! The file is left behind when the publisher exits, so a restarted publisher
+//! continues the same ring and its subscribers keep reading.
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use errors::{Exit, ExitStatus};
+use memmap2::{MmapOptions, MmapRaw};
+use thiserror::Error;
+
+use super::inproc::Subscriptions;
+use super::{Transport, TransportError};
+
+/// Bytes per slot, header included; the most one message can take.
+pub const SLOT_SIZE: usize = 16 * 1024;
+/// Messages a ring holds before the oldest is overwritten.
+pub const SLOTS: usize = 256;
+
+/// Marks an initialized ring, and its layout version.
+const MAGIC: u64 = u64::from_le_bytes(*b"RJPSHM01");
+/// The magic number and the last message's number, padded to a cache line.
+const HEADER: usize = 64;
+/// A slot's sequence number, topic length, and payload length.
+const SLOT_HEADER: usize = 16;
+const RING_LEN: usize = HEADER + SLOTS * SLOT_SIZE;
+/// How often [`ShmTransport::recv`] looks at the rings while it waits.
+const POLL: Duration = Duration::from_micros(200);
+
+type Message = (String, Vec<u8>);
+/// A message's topic and payload as stored, before the topic is checked as text.
+type RawMessage = (Vec<u8>, Vec<u8>);
+
+/// A ring that could not be opened or written.
+#[derive(Debug, Error)]
+pub enum ShmError {
+    #[error("opening shared-memory ring {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("another publisher is writing shared-memory ring {}", .0.display())]
+    Busy(PathBuf),
+    #[error("message of {len} bytes does not fit a shared-memory slot (at most {max})")]
+    TooLarge { len: usize, max: usize },
+    #[error("cannot send: shared-memory ring opened for reading")]
+    ReadOnly,
+    #[error("cannot receive: shared-memory ring opened for writing")]
+    WriteOnly,
+}
+
+impl ExitStatus for ShmError {
+    fn exit(&self) -> Exit {
+        match self {
+            ShmError::Io { .. } => Exit::Io,
+            ShmError::Busy(_) => Exit::Network,
+            ShmError::TooLarge { .. } => Exit::Data,
+            ShmError::ReadOnly | ShmError::WriteOnly => Exit::Usage,
+        }
+    }
+}
+
+/// The file behind the ring called `name`: any character other than ASCII
+/// letters, digits, `-`, `_`, and `.` becomes `_`.
+pub fn ring_path(name: &str) -> PathBuf {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    let dir = Path::new("/dev/shm");
+    let dir = if dir.is_dir() {
+        dir.to_path_buf()
+    } else {
+        env::temp_dir()
+    };
+    dir.join(format!("robot_pubsub.{name}"))
+}
+
+/// One mapped ring file.
+struct Ring {
+    map: MmapRaw,
+}
+
+impl Ring {
+    fn atomic(&self, offset: usize) -> &AtomicU64 {
+        debug_assert!(offset.is_multiple_of(8) && offset + 8 <= RING_LEN);
+        // SAFETY: the map is RING_LEN bytes, page-aligned, and lives as long
+        // as `self`; every process only touches these words atomically.
+        unsafe { &*self.map.as_ptr().add(offset).cast::<AtomicU64>() }
+    }
+
+    fn magic(&self) -> &AtomicU64 {
+        self.atomic(0)
+    }
+
+    /// The number of the last message written; messages count from 1.
+    fn write_seq(&self) -> &AtomicU64 {
+        self.atomic(16)
+    }
+
+    fn slot(&self, seq: u64) -> usize {
+        HEADER + (seq - 1) as usize % SLOTS * SLOT_SIZE
+    }
+
+    /// The slot's sequence number: the message it holds, or 0 while it is written.
+    fn slot_seq(&self, slot: usize) -> &AtomicU64 {
+        self.atomic(slot)
+    }
+
+    fn lengths(&self, slot: usize) -> &AtomicU64 {
+        self.atomic(slot + 8)
+    }
+
+    /// Message `seq`, or `None` if the publisher overwrote it meanwhile.
+    fn read(&self, seq: u64) -> Option<RawMessage> {
+        let slot = self.slot(seq);
+        if self.slot_seq(slot).load(Ordering::Acquire) != seq {
+            return None;
+        }
+        let lengths = self.lengths(slot).load(Ordering::Relaxed);
+        let (topic_len, payload_len) = ((lengths >> 32) as usize, lengths as u32 as usize);
+        let topic_len = topic_len.min(SLOT_SIZE - SLOT_HEADER);
+        let payload_len = payload_len.min(SLOT_SIZE - SLOT_HEADER - topic_len);
+        let mut data = vec![0; topic_len + payload_len];
+        // SAFETY: the range lies in the slot. The publisher may be writing it
+        // concurrently; the sequence check below throws such a copy away.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.map.as_ptr().add(slot + SLOT_HEADER),
+                data.as_mut_ptr(),
+                data.len(),
+            );
+        }
+        fence(Ordering::Acquire);
+        if self.slot_seq(slot).load(Ordering::Relaxed) != seq {
+            return None;
+        }
+        let payload = data.split_off(topic_len);
+        Some((data, payload))
+    }
+
+    fn write(&self, topic: &[u8], payload: &[u8]) {
+        let seq = self.write_seq().load(Ordering::Relaxed) + 1;
+        let slot = self.slot(seq);
+        self.slot_seq(slot).store(0, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.lengths(slot).store(
+            (topic.len() as u64) << 32 | payload.len() as u64,
+            Ordering::Relaxed,
+        );
+        // SAFETY: `send` checked that both fit the slot, and only the lock
+        // holder writes.
+        unsafe {
+            let data = self.map.as_mut_ptr().add(slot + SLOT_HEADER);
+            ptr::copy_nonoverlapping(topic.as_ptr(), data, topic.len());
+            ptr::copy_nonoverlapping(payload.as_ptr(), data.add(topic.len()), payload.len());
+        }
+        self.slot_seq(slot).store(seq, Ordering::Release);
+        self.write_seq().store(seq, Ordering::Release);
+    }
+}
+
+/// A ring a subscriber reads, once its publisher has created it.
+struct Reader {
+    path: PathBuf,
+    ring: Option<Ring>,
+    /// The number of the next message to return.
+    next: u64,
+}
+
+impl Reader {
+    fn new(name: &str) -> Self {
+        Reader {
+            path: ring_path(name),
+            ring: None,
+            next: 0,
+        }
+    }
+
+    /// Map the ring if its publisher has set it up, starting after its last
+    /// message, or at its first for a ring that appeared after `open`.
+    fn attach(&mut self, from_start: bool) -> Result<(), ShmError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(source) => {
+                return Err(ShmError::Io {
+                    path: self.path.clone(),
+                    source,
+                })
+            }
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len != RING_LEN as u64 {
+            return Ok(());
+        }
+        let map = MmapOptions::new()
+            .len(RING_LEN)
+            .map_raw_read_only(&file)
+            .map_err(|source| ShmError::Io {
+                path: self.path.clone(),
+                source,
+            })?;
+        let ring = Ring { map };
+        if ring.magic().load(Ordering::Acquire) == MAGIC {
+            self.next = if from_start {
+                1
+            } else {
+                ring.write_seq().load(Ordering::Acquire) + 1
+            };
+            self.ring = Some(ring);
+        }
+        Ok(())
+    }
+
+    fn try_read(&mut self) -> Result<Option<RawMessage>, ShmError> {
+        if self.ring.is_none() {
+            self.attach(true)?;
+        }
+        let Some(ring) = &self.ring else {
+            return Ok(None);
+        };
+        loop {
+            let last = ring.write_seq().load(Ordering::Acquire);
+            if self.next > last + 1 {
+                // The publisher started the ring over.
+                self.next = last + 1;
+            }
+            if self.next > last {
+                return Ok(None);
+            }
+            if last - self.next >= SLOTS as u64 {
+                tracing::debug!(
+                    skipped = last + 1 - SLOTS as u64 - self.next,
+                    "fell behind a shared-memory ring"
+                );
+                self.next = last + 1 - SLOTS as u64;
+            }
+            if let Some(message) = ring.read(self.next) {
+                self.next += 1;
+                return Ok(Some(message));
+            }
+            // Overwritten while we read it: look again where the publisher is now.
+        }
+    }
+}
+
+enum Side {
+    Writer { ring: Ring, _lock: File },
+    Readers(RefCell<Vec<Reader>>),
+}
+
+/// The publisher or a subscriber on a shared-memory ring.
+///
+/// [`ShmTransport::create`] owns a ring and only sends;
+/// [`ShmTransport::open`] only receives, from one ring or several.
+pub struct ShmTransport {
+    side: Side,
+    topics: RefCell<Subscriptions>,
+    /// Which reader `recv` looks at first, so one busy ring cannot starve the rest.
+    turn: Cell<usize>,
+}
+
+impl ShmTransport {
+    /// Create the ring called `name`, or take over the one a previous
+    /// publisher left, continuing its message numbers.
+    pub fn create(name: &str) -> Result<Self, ShmError> {
+        let path = ring_path(name);
+        let io = |source| ShmError::Io {
+            path: path.clone(),
+            source,
+        };
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(io)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Err(ShmError::Busy(path)),
+            Err(TryLockError::Error(source)) => return Err(io(source)),
+        }
+        let reuse = file.metadata().map_err(io)?.len() == RING_LEN as u64;
+        if !reuse {
+            file.set_len(RING_LEN as u64).map_err(io)?;
+        }
+        let map = MmapOptions::new()
+            .len(RING_LEN)
+            .map_raw(&file)
+            .map_err(io)?;
+        let ring = Ring { map };
+        if !reuse || ring.magic().load(Ordering::Acquire) != MAGIC {
+            ring.write_seq().store(0, Ordering::Relaxed);
+            for seq in 1..=SLOTS as u64 {
+                ring.slot_seq(ring.slot(seq)).store(0, Ordering::Relaxed);
+            }
+            ring.magic().store(MAGIC, Ordering::Release);
+        }
+        Ok(ShmTransport {
+            side: Side::Writer { ring, _lock: file },
+            topics: RefCell::default(),
+            turn: Cell::new(0),
+        })
+    }
+
+    /// Read the ring called `name`. Its publisher may start later: until the
+    /// ring exists, `recv` finds nothing, and then reads from its first message.
+    pub fn open(name: &str) -> Result<Self, ShmError> {
+        let mut reader = Reader::new(name);
+        reader.attach(false)?;
+        Ok(ShmTransport {
+            side: Side::Readers(RefCell::new(vec![reader])),
+            topics: RefCell::default(),
+            turn: Cell::new(0),
+        })
+    }
+
+    /// Also read the ring called `name`, for a subscriber to several publishers.
+    pub fn open_also(&mut self, name: &str) -> Result<(), ShmError> {
+        let Side::Readers(readers) = &mut self.side else {
+            return Err(ShmError::WriteOnly);
+        };
+        let mut reader = Reader::new(name);
+        reader.attach(false)?;
+        readers.get_mut().push(reader);
+        Ok(())
+    }
+
+    /// The next subscribed message in any ring, without waiting.
+    fn try_recv(&self, readers: &RefCell<Vec<Reader>>) -> Result<Option<Message>, ShmError> {
+        let mut readers = readers.borrow_mut();
+        let count = readers.len();
+        for i in 0..count {
+            let reader = &mut readers[(self.turn.get() + i) % count];
+            while let Some((topic, payload)) = reader.try_read()? {
+                let Ok(topic) = String::from_utf8(topic) else {
+                    continue;
+                };
+                if self.topics.borrow().contains(&topic) {
+                    self.turn.set((self.turn.get() + i + 1) % count);
+                    return Ok(Some((topic, payload)));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Transport for ShmTransport {
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        self.topics.borrow_mut().topics.insert(topic.to_string());
+        Ok(())
+    }
+
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        self.topics.borrow_mut().prefixes.push(prefix.to_string());
+        Ok(())
+    }
+
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        let Side::Writer { ring, .. } = &self.side else {
+            return Err(ShmError::ReadOnly.into());
+        };
+        let len = topic.len() + payload.len();
+        let max = SLOT_SIZE - SLOT_HEADER;
+        if len > max {
+            return Err(ShmError::TooLarge { len, max }.into());
+        }
+        ring.write(topic.as_bytes(), payload);
+        Ok(())
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<Message>, TransportError> {
+        let Side::Readers(readers) = &self.side else {
+            return Err(ShmError::WriteOnly.into());
+        };
+        let start = Instant::now();
+        loop {
+            if let Some(message) = self.try_recv(readers)? {
+                return Ok(Some(message));
+            }
+            let waited = start.elapsed();
+            if waited >= timeout {
+                return Ok(None);
+            }
+            thread::sleep(POLL.min(timeout - waited));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribers_read_new_messages_and_skip_ahead_when_behind() {
+        let name = format!("test.{}", std::process::id());
+        let early = ShmTransport::open(&name).unwrap();
+        early.subscribe("t").unwrap();
+        let publisher = ShmTransport::create(&name).unwrap();
+        assert!(matches!(
+            ShmTransport::create(&name),
+            Err(ShmError::Busy(_))
+        ));
+        publisher.send("t", b"first").unwrap();
+        let late = ShmTransport::open(&name).unwrap();
+        late.subscribe_prefix("t").unwrap();
+
+        publisher.send("other", b"ignored").unwrap();
+        publisher.send("t/1", b"second").unwrap();
+        assert_eq!(
+            early.recv(Duration::ZERO).unwrap(),
+            Some(("t".to_string(), b"first".to_vec()))
+        );
+        assert_eq!(early.recv(Duration::ZERO).unwrap(), None);
+        assert_eq!(
+            late.recv(Duration::ZERO).unwrap(),
+            Some(("t/1".to_string(), b"second".to_vec()))
+        );
+
+        for i in 0..SLOTS + 10 {
+            publisher.send("t", i.to_string().as_bytes()).unwrap();
+        }
+        let (_, oldest) = early.recv(Duration::ZERO).unwrap().unwrap();
+        assert_eq!(oldest, b"10");
+        assert!(matches!(
+            publisher.send("t", &[0; SLOT_SIZE]),
+            Err(TransportError::Shm(ShmError::TooLarge { .. }))
+        ));
+        assert!(early.send("t", b"x").is_err());
+
+        // A new publisher continues where the last one stopped.
+        drop(publisher);
+        let publisher = ShmTransport::create(&name).unwrap();
+        publisher.send("t", b"again").unwrap();
+        let mut last = None;
+        while let Some((_, payload)) = late.recv(Duration::ZERO).unwrap() {
+            last = Some(payload);
+        }
+        assert_eq!(last.as_deref(), Some(&b"again"[..]));
+        std::fs::remove_file(ring_path(&name)).unwrap();
+    }
+}
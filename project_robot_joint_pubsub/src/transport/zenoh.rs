@@ -0,0 +1,214 @@
+//! Zenoh publish/subscribe, for networks where peers should find each other
+//! and route across subnets without fixed ZMQ endpoints or an MQTT broker.
+//!
+//! Messages are keyed by robot: a topic starting with `robot_` travels under
+//! `robot/<robot_id>/<rest>`, so `robot_joints` from `left_hand` is
+//! `robot/left_hand/joints` and its heartbeats `robot/all/joints/heartbeat`
+//! ([`ALL_ROBOTS`] stands in for messages that belong to no one robot). Any
+//! Zenoh tool can pick out one robot (`z_sub -k 'robot/left_hand/**'`).
+//! Other topics travel under their own name. Subscriptions take every robot.
+//!
+//! With no endpoints, peers find each other by multicast scouting; with
+//! `connect` endpoints (`tcp/router.lan:7447`), through those peers or a
+//! `zenohd` router. Like ZMQ PUB/SUB, a message nobody can take is dropped.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+use zenoh::key_expr::KeyExpr;
+use zenoh::{Session, Wait};
+
+use super::{Transport, TransportError};
+
+/// The `<robot_id>` chunk of messages for no one robot: heartbeats, control
+/// commands, and states without a `robot_id`.
+pub const ALL_ROBOTS: &str = "all";
+
+/// The first chunk of every robot-keyed key expression.
+const ROOT: &str = "robot";
+/// The topic prefix that robot-keyed key expressions stand for.
+const TOPIC_PREFIX: &str = "robot_";
+
+type Message = (String, Vec<u8>);
+
+/// A session that could not be opened, or a topic that makes no key expression.
+#[derive(Debug, Error)]
+pub enum ZenohError {
+    #[error("invalid Zenoh endpoint {0:?} (expected e.g. tcp/host:7447)")]
+    Endpoint(String),
+    /// Zenoh wildcards only match whole chunks, so a prefix must end at one.
+    #[error("cannot subscribe to topic prefix {0:?} over Zenoh (it must be empty or end in '/')")]
+    Prefix(String),
+    #[error("topic or robot id {0:?} is not a valid Zenoh key expression")]
+    Key(String),
+    #[error("Zenoh session error")]
+    Session(#[source] zenoh::Error),
+}
+
+impl ExitStatus for ZenohError {
+    fn exit(&self) -> Exit {
+        match self {
+            ZenohError::Endpoint(_) | ZenohError::Prefix(_) | ZenohError::Key(_) => Exit::Usage,
+            ZenohError::Session(_) => Exit::Network,
+        }
+    }
+}
+
+/// The key expression `topic` travels under for `robot_id`.
+pub fn key_expr(topic: &str, robot_id: &str) -> String {
+    match topic.strip_prefix(TOPIC_PREFIX) {
+        Some(rest) => format!("{ROOT}/{robot_id}/{rest}"),
+        None => topic.to_string(),
+    }
+}
+
+/// The topic a message under `key` was sent on: the inverse of [`key_expr`].
+pub fn topic_of(key: &str) -> String {
+    let robot_keyed = key
+        .strip_prefix(ROOT)
+        .and_then(|key| key.strip_prefix('/'))
+        .and_then(|key| key.split_once('/'));
+    match robot_keyed {
+        Some((_robot_id, rest)) => format!("{TOPIC_PREFIX}{rest}"),
+        None => key.to_string(),
+    }
+}
+
+/// The key expression matching every topic starting with `prefix`, from any robot.
+fn prefix_expr(prefix: &str) -> Result<String, ZenohError> {
+    if prefix.is_empty() {
+        return Ok("**".to_string());
+    }
+    // `robot_` is a whole chunk once keyed, so it ends at one like `hands/` does.
+    let rest = match prefix.strip_prefix(TOPIC_PREFIX) {
+        Some(rest) => rest,
+        None => prefix,
+    };
+    if !(rest.is_empty() || rest.ends_with('/')) {
+        return Err(ZenohError::Prefix(prefix.to_string()));
+    }
+    Ok(format!("{}**", key_expr(prefix, "*")))
+}
+
+/// A Zenoh session that both publishes and receives.
+pub struct ZenohTransport {
+    session: Session,
+    tx: Sender<Message>,
+    incoming: Receiver<Message>,
+}
+
+impl ZenohTransport {
+    /// Join the Zenoh network as a peer, through `endpoints` if there are any
+    /// and by multicast scouting otherwise.
+    pub fn open(endpoints: &[String]) -> Result<Self, ZenohError> {
+        let mut config = zenoh::Config::default();
+        if !endpoints.is_empty() {
+            let json = serde_json::to_string(endpoints).expect("endpoints serialize");
+            config
+                .insert_json5("connect/endpoints", &json)
+                .map_err(|_| ZenohError::Endpoint(endpoints.join(",")))?;
+        }
+        let session = zenoh::open(config).wait().map_err(ZenohError::Session)?;
+        let (tx, incoming) = mpsc::channel();
+        Ok(ZenohTransport {
+            session,
+            tx,
+            incoming,
+        })
+    }
+
+    fn declare(&self, key: String) -> Result<(), TransportError> {
+        let key = KeyExpr::try_from(key).map_err(|e| ZenohError::Key(e.to_string()))?;
+        let tx = self.tx.clone();
+        // Lives as long as the session; a closed inbox just drops what arrives.
+        self.session
+            .declare_subscriber(key)
+            .callback(move |sample| {
+                let topic = topic_of(sample.key_expr().as_str());
+                let _ = tx.send((topic, sample.payload().to_bytes().into_owned()));
+            })
+            .background()
+            .wait()
+            .map_err(ZenohError::Session)?;
+        Ok(())
+    }
+}
+
+impl Transport for ZenohTransport {
+    /// Subscribes to `topic` from every robot.
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        self.declare(key_expr(topic, "*"))
+    }
+
+    /// Subscribes to `<prefix>**` from every robot.
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        self.declare(prefix_expr(prefix)?)
+    }
+
+    /// Sends under [`ALL_ROBOTS`].
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        self.send_from(ALL_ROBOTS, topic, payload)
+    }
+
+    /// Never blocks: a message the network cannot take right away is dropped.
+    fn send_from(&self, robot_id: &str, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        let robot_id = if robot_id.is_empty() {
+            ALL_ROBOTS
+        } else {
+            robot_id
+        };
+        let key = key_expr(topic, robot_id);
+        let key = KeyExpr::try_from(key.as_str())
+            .ok()
+            .filter(|key| !key.is_wild() && !robot_id.contains('/'))
+            .ok_or_else(|| ZenohError::Key(key.clone()))?;
+        self.session
+            .put(key, payload.to_vec())
+            .wait()
+            .map_err(ZenohError::Session)?;
+        Ok(())
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError> {
+        match self.incoming.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(TransportError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robot_topics_are_keyed_by_robot() {
+        assert_eq!(
+            key_expr("robot_joints", "left_hand"),
+            "robot/left_hand/joints"
+        );
+        assert_eq!(
+            key_expr("robot_joints/heartbeat", ALL_ROBOTS),
+            "robot/all/joints/heartbeat"
+        );
+        assert_eq!(key_expr("hands/left", "left_hand"), "hands/left");
+        for topic in ["robot_joints", "robot_joints_actual/thumb", "hands/left"] {
+            assert_eq!(topic_of(&key_expr(topic, "pro_hand")), topic);
+        }
+    }
+
+    #[test]
+    fn prefixes_end_at_a_chunk() {
+        assert_eq!(prefix_expr("").unwrap(), "**");
+        assert_eq!(prefix_expr("robot_").unwrap(), "robot/*/**");
+        assert_eq!(prefix_expr("robot_joints/").unwrap(), "robot/*/joints/**");
+        assert_eq!(prefix_expr("hands/").unwrap(), "hands/**");
+        for bad in ["robot_joints", "hands"] {
+            let err = prefix_expr(bad).unwrap_err();
+            assert_eq!(err.exit(), Exit::Usage, "{bad:?}");
+        }
+    }
+}
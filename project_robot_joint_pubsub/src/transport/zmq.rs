@@ -0,0 +1,279 @@
+//! ZMQ PUB/SUB sockets that send each message as two frames: the topic, then
+//! the payload.
+//!
+//! Any ZMQ endpoint works: `tcp://` between machines, `ipc://` between
+//! processes on one host, `inproc://` between threads sharing a [`zmq::Context`].
+//!
+//! Publishers from before multipart frames sent one `"<topic> <payload>"`
+//! frame instead (see [`wire::encode`](crate::wire::encode));
+//! [`ZmqTransport::accept_legacy_frames`] lets a subscriber read those too.
+//!
+//! Publishers normally bind and subscribers connect to each of them. With a
+//! [`ZmqBroker`] in between, everyone connects to one well-known pair of
+//! endpoints instead: publishers to its XSUB side (a bind endpoint starting
+//! with `>`, see [`ZmqTransport::bind`]), subscribers to its XPUB side.
+//!
+//! A SUB socket queues up to 1000 messages per publisher (ZMQ's receive high
+//! water mark) and drops new ones past that; a small one
+//! ([`ZmqTransport::connect_with_hwm`]) bounds how far behind a slow reader
+//! can fall. ZMQ's `CONFLATE` option, which keeps only the newest message,
+//! cannot hold two-frame messages, so keeping only the newest state is up to
+//! the [`Subscriber`](super::Subscriber) ([`with_keep_latest`](super::Subscriber::with_keep_latest)).
+//!
+//! A peer that went away looks just like one with nothing to say, so a
+//! [`ZmqMonitor`] ([`ZmqTransport::monitor`]) watches the socket's connections
+//! come and go: publishers a subscriber reached, subscribers that reached a
+//! bound publisher.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use super::{Transport, TransportError};
+use crate::wire::WireError;
+
+/// A PUB socket (sends only) or a connected SUB socket (receives only).
+pub struct ZmqTransport {
+    ctx: zmq::Context,
+    socket: zmq::Socket,
+    /// The endpoints connected to (not bound), for [`ZmqTransport::monitor`].
+    connected: RefCell<Vec<String>>,
+    legacy_frames: bool,
+}
+
+impl ZmqTransport {
+    /// Bind a PUB socket at `endpoint`. An endpoint starting with `>`
+    /// (`>tcp://broker-host:5559`) is connected to instead, for publishing
+    /// through a [`ZmqBroker`].
+    pub fn bind(ctx: &zmq::Context, endpoint: &str) -> Result<Self, TransportError> {
+        let socket = ctx.socket(zmq::PUB)?;
+        // Do not hang on drop with undelivered messages.
+        socket.set_linger(0)?;
+        let connected = match endpoint.strip_prefix('>') {
+            Some(broker) => {
+                socket.connect(broker)?;
+                vec![broker.to_string()]
+            }
+            None => {
+                socket.bind(endpoint)?;
+                Vec::new()
+            }
+        };
+        Ok(ZmqTransport::new(ctx, socket, connected))
+    }
+
+    /// Connect a SUB socket to `endpoint`. The publisher does not need to be up
+    /// yet; ZMQ connects (and reconnects) in the background.
+    pub fn connect(ctx: &zmq::Context, endpoint: &str) -> Result<Self, TransportError> {
+        let socket = ctx.socket(zmq::SUB)?;
+        socket.set_linger(0)?;
+        socket.connect(endpoint)?;
+        Ok(ZmqTransport::new(ctx, socket, vec![endpoint.to_string()]))
+    }
+
+    /// [`ZmqTransport::connect`], queueing at most `hwm` messages from each
+    /// publisher (this one and any from [`ZmqTransport::connect_also`]) and
+    /// dropping new ones past that.
+    pub fn connect_with_hwm(
+        ctx: &zmq::Context,
+        endpoint: &str,
+        hwm: i32,
+    ) -> Result<Self, TransportError> {
+        let socket = ctx.socket(zmq::SUB)?;
+        socket.set_linger(0)?;
+        // Only connections made after setting it take it.
+        socket.set_rcvhwm(hwm)?;
+        socket.connect(endpoint)?;
+        Ok(ZmqTransport::new(ctx, socket, vec![endpoint.to_string()]))
+    }
+
+    fn new(ctx: &zmq::Context, socket: zmq::Socket, connected: Vec<String>) -> Self {
+        ZmqTransport {
+            ctx: ctx.clone(),
+            socket,
+            connected: RefCell::new(connected),
+            legacy_frames: false,
+        }
+    }
+
+    /// Also connect to `endpoint`, to hear several publishers on one socket.
+    pub fn connect_also(&self, endpoint: &str) -> Result<(), TransportError> {
+        self.socket.connect(endpoint)?;
+        self.connected.borrow_mut().push(endpoint.to_string());
+        Ok(())
+    }
+
+    /// Start watching this socket's connections. A connection that came up
+    /// before would never be reported, so the endpoints already connected to
+    /// are connected again under the monitor (messages in between are lost,
+    /// as on any reconnect).
+    pub fn monitor(&self) -> Result<ZmqMonitor, TransportError> {
+        static MONITORS: AtomicUsize = AtomicUsize::new(0);
+        let endpoint = format!(
+            "inproc://zmq-monitor-{}",
+            MONITORS.fetch_add(1, Ordering::Relaxed)
+        );
+        self.socket
+            .monitor(&endpoint, zmq::SocketEvent::ALL as i32)?;
+        let socket = self.ctx.socket(zmq::PAIR)?;
+        socket.set_linger(0)?;
+        socket.connect(&endpoint)?;
+        for connected in self.connected.borrow().iter() {
+            self.socket.disconnect(connected)?;
+            self.socket.connect(connected)?;
+        }
+        Ok(ZmqMonitor {
+            socket,
+            up: HashMap::new(),
+        })
+    }
+
+    /// Also accept single `"<topic> <payload>"` frames, split at the first space.
+    pub fn accept_legacy_frames(mut self) -> Self {
+        self.legacy_frames = true;
+        self
+    }
+}
+
+/// A change in one of a monitored socket's connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A connection at `endpoint` came up: to a publisher there, or from a
+    /// subscriber to the endpoint this socket is bound at.
+    Up(String),
+    /// A connection at `endpoint` went down; a connecting socket retries.
+    Down(String),
+    /// Connecting to `endpoint` failed (nobody listening yet, or unreachable);
+    /// ZMQ tries again shortly.
+    Retrying(String),
+}
+
+/// Reports a [`ZmqTransport`]'s connections as they come and go (see
+/// [`ZmqTransport::monitor`]).
+pub struct ZmqMonitor {
+    socket: zmq::Socket,
+    /// The connections up at each endpoint, by file descriptor: many accepted
+    /// at a bound endpoint, at most one to a connected one.
+    up: HashMap<String, HashSet<u32>>,
+}
+
+impl ZmqMonitor {
+    /// The changes since the last call, oldest first; never waits.
+    pub fn poll(&mut self) -> Result<Vec<ConnectionEvent>, TransportError> {
+        let mut events = Vec::new();
+        while self.socket.poll(zmq::POLLIN, 0)? > 0 {
+            let frames = self.socket.recv_multipart(0)?;
+            let [head, endpoint] = &frames[..] else {
+                continue;
+            };
+            // The event, then its value: the connection's descriptor for those used here.
+            let Some(&[e0, e1, v0, v1, v2, v3]) = head.get(..6) else {
+                continue;
+            };
+            let event = u16::from_ne_bytes([e0, e1]);
+            let fd = u32::from_ne_bytes([v0, v1, v2, v3]);
+            let endpoint = String::from_utf8_lossy(endpoint).into_owned();
+            let up = self.up.entry(endpoint.clone()).or_default();
+            if event == zmq::SocketEvent::ACCEPTED as u16 {
+                up.insert(fd);
+                events.push(ConnectionEvent::Up(endpoint));
+            } else if event == zmq::SocketEvent::CONNECTED as u16 {
+                // A new connection to an endpoint replaces the one before it, which
+                // `monitor` may have dropped and whose own disconnection comes later.
+                let replaced = !up.is_empty();
+                up.clear();
+                up.insert(fd);
+                if !replaced {
+                    events.push(ConnectionEvent::Up(endpoint));
+                }
+            } else if event == zmq::SocketEvent::DISCONNECTED as u16 {
+                if up.remove(&fd) {
+                    events.push(ConnectionEvent::Down(endpoint));
+                }
+            } else if event == zmq::SocketEvent::CONNECT_RETRIED as u16 {
+                events.push(ConnectionEvent::Retrying(endpoint));
+            }
+        }
+        Ok(events)
+    }
+
+    /// How many connections are up, as of the last [`ZmqMonitor::poll`].
+    pub fn connections(&self) -> usize {
+        self.up.values().map(HashSet::len).sum()
+    }
+}
+
+/// A ZMQ proxy that many publishers and subscribers share: publishers connect
+/// to its XSUB `frontend`, subscribers to its XPUB `backend`, and every message
+/// goes to every subscriber whose subscription matches, as with one publisher.
+pub struct ZmqBroker {
+    frontend: zmq::Socket,
+    backend: zmq::Socket,
+}
+
+impl ZmqBroker {
+    /// Bind the publisher side at `frontend` and the subscriber side at `backend`.
+    pub fn bind(ctx: &zmq::Context, frontend: &str, backend: &str) -> Result<Self, TransportError> {
+        let xsub = ctx.socket(zmq::XSUB)?;
+        xsub.set_linger(0)?;
+        xsub.bind(frontend)?;
+        let xpub = ctx.socket(zmq::XPUB)?;
+        xpub.set_linger(0)?;
+        xpub.bind(backend)?;
+        Ok(ZmqBroker {
+            frontend: xsub,
+            backend: xpub,
+        })
+    }
+
+    /// Forward messages one way and subscriptions the other until the context
+    /// is terminated; blocks, so give it a thread (or the process) of its own.
+    pub fn run(self) -> Result<(), TransportError> {
+        zmq::proxy(&self.frontend, &self.backend)?;
+        Ok(())
+    }
+}
+
+impl Transport for ZmqTransport {
+    /// Subscribes by prefix, as ZMQ does: `robot_joints` also matches `robot_joints_debug`.
+    fn subscribe(&self, topic: &str) -> Result<(), TransportError> {
+        self.socket.set_subscribe(topic.as_bytes())?;
+        Ok(())
+    }
+
+    fn subscribe_prefix(&self, prefix: &str) -> Result<(), TransportError> {
+        self.subscribe(prefix)
+    }
+
+    fn send(&self, topic: &str, payload: &[u8]) -> Result<(), TransportError> {
+        self.socket.send_multipart([topic.as_bytes(), payload], 0)?;
+        Ok(())
+    }
+
+    fn recv(&self, timeout: Duration) -> Result<Option<(String, Vec<u8>)>, TransportError> {
+        if self.socket.poll(zmq::POLLIN, timeout.as_millis() as i64)? == 0 {
+            return Ok(None);
+        }
+        let mut frames = self.socket.recv_multipart(0)?;
+        let (topic, payload) = match frames.len() {
+            2 => {
+                let payload = frames.pop().expect("two frames");
+                (frames.pop().expect("two frames"), payload)
+            }
+            1 if self.legacy_frames => {
+                let mut frame = frames.pop().expect("one frame");
+                let space = frame
+                    .iter()
+                    .position(|&b| b == b' ')
+                    .ok_or(WireError::MissingTopic)?;
+                let payload = frame.split_off(space + 1);
+                frame.truncate(space);
+                (frame, payload)
+            }
+            frames => return Err(WireError::Frames(frames).into()),
+        };
+        let topic = String::from_utf8(topic).map_err(|_| TransportError::NotText)?;
+        Ok(Some((topic, payload)))
+    }
+}
@@ -0,0 +1,239 @@
+//! Raw UDP sensor packets for the `udp_bridge` binary: data gloves and mocap
+//! rigs whose SDK can only send a block of floats, turned into
+//! [`RobotState`]s by a TOML layout file.
+//!
+//! ```toml
+//! robot_id = "left_hand"
+//! value = "f32le"          # f32le (default), f32be, f64le, or f64be
+//! header = 0               # bytes to skip before the first value (default 0)
+//! units = "deg"            # rad (default) or deg, converted to radians
+//! # One name per value, in packet order; "" skips a value the hand has no joint for.
+//! joints = ["t0_TM_abd", "t1_TM", "", "i1_MCP", "i2_PIP"]
+//! ```
+//!
+//! A packet holds at least `header` bytes and one value per joint; bytes past
+//! the last value (a checksum, a timestamp) are ignored. Velocities are left
+//! at 0, since a glove reports positions only.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{JointAngles, RobotState};
+
+/// A layout file that could not be used.
+#[derive(Debug, Error)]
+pub enum PacketLayoutError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad packet layout")]
+    Toml(#[from] toml::de::Error),
+    #[error("the layout maps no values to joints")]
+    Empty,
+}
+
+impl ExitStatus for PacketLayoutError {
+    fn exit(&self) -> Exit {
+        match self {
+            PacketLayoutError::Io { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// A packet that does not fit its layout.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("packet of {got} bytes, the layout needs at least {expected}")]
+pub struct PacketError {
+    pub expected: usize,
+    pub got: usize,
+}
+
+/// How each value in a packet is encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Value {
+    /// 4-byte little-endian float.
+    #[default]
+    F32le,
+    /// 4-byte big-endian (network order) float.
+    F32be,
+    /// 8-byte little-endian float.
+    F64le,
+    /// 8-byte big-endian float.
+    F64be,
+}
+
+impl Value {
+    /// Bytes per value.
+    pub fn size(self) -> usize {
+        match self {
+            Value::F32le | Value::F32be => 4,
+            Value::F64le | Value::F64be => 8,
+        }
+    }
+
+    fn read(self, bytes: &[u8]) -> f64 {
+        match self {
+            Value::F32le => f32::from_le_bytes(bytes.try_into().unwrap()).into(),
+            Value::F32be => f32::from_be_bytes(bytes.try_into().unwrap()).into(),
+            Value::F64le => f64::from_le_bytes(bytes.try_into().unwrap()),
+            Value::F64be => f64::from_be_bytes(bytes.try_into().unwrap()),
+        }
+    }
+}
+
+/// The angle unit a packet's values are in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Rad,
+    Deg,
+}
+
+/// A whole layout file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacketLayout {
+    #[serde(default = "robot_id")]
+    pub robot_id: String,
+    #[serde(default)]
+    pub value: Value,
+    #[serde(default)]
+    pub header: usize,
+    #[serde(default)]
+    pub units: Units,
+    /// One per value, in packet order; empty names are skipped.
+    pub joints: Vec<String>,
+}
+
+fn robot_id() -> String {
+    "udp".to_string()
+}
+
+impl FromStr for PacketLayout {
+    type Err = PacketLayoutError;
+
+    fn from_str(raw: &str) -> Result<Self, PacketLayoutError> {
+        let layout: PacketLayout = toml::from_str(raw)?;
+        if layout.joints.iter().all(String::is_empty) {
+            return Err(PacketLayoutError::Empty);
+        }
+        Ok(layout)
+    }
+}
+
+impl PacketLayout {
+    /// Read a layout file.
+    pub fn load(path: &Path) -> Result<Self, PacketLayoutError> {
+        let raw = fs::read_to_string(path).map_err(|source| PacketLayoutError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// The fewest bytes a packet can hold.
+    pub fn packet_len(&self) -> usize {
+        self.header + self.joints.len() * self.value.size()
+    }
+
+    /// The state `packet` holds, numbered `sequence`.
+    pub fn decode(&self, packet: &[u8], sequence: u64) -> Result<RobotState, PacketError> {
+        let expected = self.packet_len();
+        if packet.len() < expected {
+            return Err(PacketError {
+                expected,
+                got: packet.len(),
+            });
+        }
+        let values = packet[self.header..expected].chunks_exact(self.value.size());
+        let joints = self
+            .joints
+            .iter()
+            .zip(values)
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, bytes)| {
+                let value = self.value.read(bytes);
+                JointAngles {
+                    timestamp: 0,
+                    joint_name: name.clone(),
+                    angle_rad: match self.units {
+                        Units::Rad => value,
+                        Units::Deg => value.to_radians(),
+                    },
+                    velocity: 0.0,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                }
+            })
+            .collect();
+        Ok(RobotState {
+            timestamp: 0,
+            robot_id: self.robot_id.clone(),
+            joints,
+            sent_at_us: None,
+            sequence: Some(sequence),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets_decode_by_the_layout() {
+        let layout: PacketLayout =
+            "value = \"f32be\"\nheader = 2\nunits = \"deg\"\njoints = [\"i1_MCP\", \"\", \"t1_TM\"]\n"
+                .parse()
+                .unwrap();
+        assert_eq!(layout.robot_id, "udp");
+        assert_eq!(layout.packet_len(), 14);
+
+        let mut packet = vec![0xAB, 0xCD];
+        for value in [90.0f32, 1234.0, -45.0] {
+            packet.extend(value.to_be_bytes());
+        }
+        packet.push(0xFF); // trailing checksum
+        let state = layout.decode(&packet, 7).unwrap();
+        assert_eq!(state.sequence, Some(7));
+        assert_eq!(state.joints.len(), 2);
+        assert_eq!(state.joints[0].joint_name, "i1_MCP");
+        assert!((state.joints[0].angle_rad - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert_eq!(state.joints[1].joint_name, "t1_TM");
+        assert!((state.joints[1].angle_rad + std::f64::consts::FRAC_PI_4).abs() < 1e-9);
+
+        assert_eq!(
+            layout.decode(&packet[..13], 8),
+            Err(PacketError {
+                expected: 14,
+                got: 13
+            })
+        );
+        assert!(matches!(
+            "joints = [\"\"]".parse::<PacketLayout>(),
+            Err(PacketLayoutError::Empty)
+        ));
+        assert!("joints = []\nvalue = \"f16\""
+            .parse::<PacketLayout>()
+            .is_err());
+    }
+
+    #[test]
+    fn the_shipped_layout_parses() {
+        let layout: PacketLayout = include_str!("../udp_layout.toml").parse().unwrap();
+        assert_eq!(layout.value, Value::F32le);
+        assert_eq!(layout.joints.len(), 16);
+    }
+}
@@ -0,0 +1,279 @@
+//! Frames rendered offscreen, for `subscriber --render-to`: demo videos from a
+//! replayed recording on machines with no display to open a viewer on (CI,
+//! over SSH).
+//!
+//! A [`VideoOut`] takes each frame as the RGB bytes MuJoCo reads back, rows
+//! bottom first, and writes it right side up. Where it goes depends on the path:
+//!
+//! - `demo.mp4` (or `.mkv`, `.mov`, `.webm`, `.gif`): a video, encoded by an
+//!   `ffmpeg` process the frames are piped to, which must be on `PATH`.
+//! - anything else: a directory of `frame_000000.png`, `frame_000001.png`, …,
+//!   created if need be, for any encoder later.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::str::FromStr;
+
+use errors::{Exit, ExitStatus};
+use thiserror::Error;
+
+/// Extensions written as a video by `ffmpeg` rather than as PNG frames.
+pub const VIDEO_EXTENSIONS: [&str; 5] = ["mp4", "mkv", "mov", "webm", "gif"];
+
+/// Frames that could not be written.
+#[derive(Debug, Error)]
+pub enum VideoError {
+    #[error("writing {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("encoding {}", path.display())]
+    Png {
+        path: PathBuf,
+        #[source]
+        source: png::EncodingError,
+    },
+    #[error("starting ffmpeg (is it installed and on PATH?)")]
+    Spawn(#[source] io::Error),
+    #[error("ffmpeg failed to encode {} ({status})", path.display())]
+    Ffmpeg {
+        path: PathBuf,
+        status: std::process::ExitStatus,
+    },
+    #[error("expected a frame of {expected} bytes, got {got}")]
+    FrameSize { expected: usize, got: usize },
+}
+
+impl ExitStatus for VideoError {
+    fn exit(&self) -> Exit {
+        match self {
+            VideoError::FrameSize { .. } => Exit::Data,
+            _ => Exit::Io,
+        }
+    }
+}
+
+/// A frame size in pixels, `WIDTHxHEIGHT` on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        let parsed = raw.split_once(['x', 'X']).and_then(|(width, height)| {
+            Some(Size {
+                width: width.trim().parse().ok()?,
+                height: height.trim().parse().ok()?,
+            })
+        });
+        match parsed {
+            Some(size) if size.width > 0 && size.height > 0 => Ok(size),
+            _ => Err(format!("expected WIDTHxHEIGHT, e.g. 1280x720, got {raw:?}")),
+        }
+    }
+}
+
+impl Size {
+    /// Bytes in one RGB frame of this size.
+    pub fn rgb_len(self) -> usize {
+        self.width as usize * self.height as usize * 3
+    }
+}
+
+/// Where rendered frames go.
+pub struct VideoOut {
+    path: PathBuf,
+    size: Size,
+    sink: Sink,
+    frames: u64,
+    // One frame, flipped right side up.
+    flipped: Vec<u8>,
+}
+
+enum Sink {
+    Frames,
+    Ffmpeg {
+        child: Child,
+        stdin: BufWriter<ChildStdin>,
+    },
+}
+
+impl VideoOut {
+    /// Start writing `size` frames, shown `fps` times a second, to `path`: a
+    /// video if its extension is one of [`VIDEO_EXTENSIONS`], otherwise a
+    /// directory of PNG frames.
+    pub fn create(path: &Path, size: Size, fps: u32) -> Result<Self, VideoError> {
+        let is_video = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        let sink = if is_video {
+            let mut child = Command::new("ffmpeg")
+                .args(["-y", "-loglevel", "error"])
+                .args(["-f", "rawvideo", "-pix_fmt", "rgb24"])
+                .args(["-s", &format!("{}x{}", size.width, size.height)])
+                .args(["-r", &fps.to_string(), "-i", "-"])
+                // Most players (and H.264 itself) want yuv420p, which needs even sides.
+                .args([
+                    "-vf",
+                    "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+                    "-pix_fmt",
+                    "yuv420p",
+                ])
+                .arg(path)
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(VideoError::Spawn)?;
+            let stdin = child.stdin.take().expect("ffmpeg stdin is piped");
+            Sink::Ffmpeg {
+                child,
+                stdin: BufWriter::new(stdin),
+            }
+        } else {
+            fs::create_dir_all(path).map_err(|source| VideoError::Write {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            Sink::Frames
+        };
+        Ok(VideoOut {
+            path: path.to_path_buf(),
+            size,
+            sink,
+            frames: 0,
+            flipped: Vec::with_capacity(size.rgb_len()),
+        })
+    }
+
+    /// Write one frame, given as MuJoCo (OpenGL) reads it back: RGB, rows
+    /// bottom first.
+    pub fn write_bottom_up(&mut self, rgb: &[u8]) -> Result<(), VideoError> {
+        let expected = self.size.rgb_len();
+        if rgb.len() != expected {
+            return Err(VideoError::FrameSize {
+                expected,
+                got: rgb.len(),
+            });
+        }
+        self.flipped.clear();
+        for row in rgb.chunks_exact(self.size.width as usize * 3).rev() {
+            self.flipped.extend_from_slice(row);
+        }
+        match &mut self.sink {
+            Sink::Frames => {
+                let path = self.path.join(format!("frame_{:06}.png", self.frames));
+                write_png(&path, self.size, &self.flipped)?;
+            }
+            Sink::Ffmpeg { stdin, .. } => {
+                stdin
+                    .write_all(&self.flipped)
+                    .map_err(|source| VideoError::Write {
+                        path: self.path.clone(),
+                        source,
+                    })?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Finish the video (waiting for `ffmpeg` to encode the last frames) and
+    /// return how many frames were written.
+    pub fn finish(self) -> Result<u64, VideoError> {
+        if let Sink::Ffmpeg {
+            child: mut ffmpeg,
+            stdin,
+        } = self.sink
+        {
+            let write_error = |source| VideoError::Write {
+                path: self.path.clone(),
+                source,
+            };
+            // Closing stdin tells ffmpeg the stream has ended.
+            drop(
+                stdin
+                    .into_inner()
+                    .map_err(|e| write_error(e.into_error()))?,
+            );
+            let status = ffmpeg.wait().map_err(write_error)?;
+            if !status.success() {
+                return Err(VideoError::Ffmpeg {
+                    path: self.path,
+                    status,
+                });
+            }
+        }
+        Ok(self.frames)
+    }
+}
+
+fn write_png(path: &Path, size: Size, rgb: &[u8]) -> Result<(), VideoError> {
+    let png_error = |source| VideoError::Png {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = File::create(path).map_err(|source| VideoError::Write {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size.width, size.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    writer.write_image_data(rgb).map_err(png_error)?;
+    writer.finish().map_err(png_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_written_right_side_up_and_numbered() {
+        assert_eq!(
+            "1280x720".parse::<Size>(),
+            Ok(Size {
+                width: 1280,
+                height: 720
+            })
+        );
+        assert!("1280".parse::<Size>().is_err());
+        assert!("0x720".parse::<Size>().is_err());
+
+        let dir = std::env::temp_dir().join(format!("video-{}", std::process::id()));
+        let size = Size {
+            width: 1,
+            height: 2,
+        };
+        let mut video = VideoOut::create(&dir, size, 30).unwrap();
+        // Bottom row red, top row blue.
+        let frame = [255, 0, 0, 0, 0, 255];
+        video.write_bottom_up(&frame).unwrap();
+        video.write_bottom_up(&frame).unwrap();
+        assert!(matches!(
+            video.write_bottom_up(&frame[..3]),
+            Err(VideoError::FrameSize {
+                expected: 6,
+                got: 3
+            })
+        ));
+        assert_eq!(video.finish().unwrap(), 2);
+
+        let decoder = png::Decoder::new(io::BufReader::new(
+            File::open(dir.join("frame_000001.png")).unwrap(),
+        ));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(pixels, [0, 0, 255, 255, 0, 0]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
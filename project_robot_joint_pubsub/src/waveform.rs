@@ -0,0 +1,422 @@
+//! Per-joint test stimuli for the headless publisher (`publisher --waveforms`):
+//! each named joint follows a sine, square, or chirp, so a whole hand can be
+//! driven the same way run after run without the GUI. The GUI publisher's
+//! Generators tab builds the same [`Waveform`]s by hand, one joint at a time.
+//!
+//! Waveforms come from a TOML file, one table per joint:
+//!
+//! ```toml
+//! [joints.f1_MCP]
+//! wave = "sine"           # or "square", or "chirp" (default "sine")
+//! amplitude = 0.6         # rad, either side of the offset
+//! frequency = 0.5         # Hz; where a chirp starts
+//! phase = 1.57            # rad (default 0)
+//! offset = 0.7            # rad, the center (default 0)
+//!
+//! [joints.t1_TM]
+//! wave = "chirp"
+//! amplitude = 0.3
+//! frequency = 0.2
+//! end_frequency = 2.0     # Hz; chirps only
+//! sweep = 10.0            # s from frequency to end_frequency, then again (default 10)
+//! ```
+//!
+//! Velocity is the waveform's derivative (0 on a square's flat tops), and
+//! torque is 0.
+
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use errors::{Exit, ExitStatus};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{JointAngles, RobotState};
+
+/// Sweep time of a chirp that does not give one, in seconds.
+pub const DEFAULT_SWEEP: f64 = 10.0;
+
+/// A waveform file that could not be used.
+#[derive(Debug, Error)]
+pub enum WaveformError {
+    #[error("reading {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("bad waveform file")]
+    Toml(#[from] toml::de::Error),
+    #[error("a waveform file needs at least one [joints.<name>] table")]
+    Empty,
+    #[error("{joint}: {field} must be {expected}")]
+    BadField {
+        joint: String,
+        field: &'static str,
+        expected: &'static str,
+    },
+}
+
+impl ExitStatus for WaveformError {
+    fn exit(&self) -> Exit {
+        match self {
+            WaveformError::Io { .. } => Exit::Io,
+            _ => Exit::Data,
+        }
+    }
+}
+
+/// How a joint moves about its offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Sine,
+    /// `+amplitude` for the first half of each cycle, `-amplitude` for the second.
+    Square,
+    /// A sine whose frequency rises (or falls) linearly from the waveform's
+    /// `frequency` to `end_frequency` over `sweep` seconds, then starts over.
+    Chirp {
+        end_frequency: f64,
+        sweep: f64,
+    },
+}
+
+/// One joint's waveform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Waveform {
+    pub shape: Shape,
+    /// In radians, either side of `offset`.
+    pub amplitude: f64,
+    /// In Hz; a chirp's starting frequency.
+    pub frequency: f64,
+    /// In radians, added to the waveform's phase.
+    pub phase: f64,
+    /// In radians.
+    pub offset: f64,
+}
+
+impl Waveform {
+    /// Angle and velocity `t` seconds in.
+    pub fn sample(&self, t: f64) -> (f64, f64) {
+        let Waveform {
+            amplitude: a,
+            frequency: f,
+            phase,
+            offset,
+            ..
+        } = *self;
+        match self.shape {
+            Shape::Sine => {
+                let theta = TAU * f * t + phase;
+                (offset + a * theta.sin(), a * TAU * f * theta.cos())
+            }
+            Shape::Square => {
+                // Fraction of the cycle, so the edges fall exactly on the half periods.
+                let cycle = (f * t + phase / TAU).rem_euclid(1.0);
+                let level = if cycle < 0.5 { a } else { -a };
+                (offset + level, 0.0)
+            }
+            Shape::Chirp {
+                end_frequency,
+                sweep,
+            } => {
+                let tau = t.rem_euclid(sweep);
+                let rate = (end_frequency - f) / sweep;
+                let theta = TAU * (f * tau + rate * tau * tau / 2.0) + phase;
+                let frequency_now = f + rate * tau;
+                (
+                    offset + a * theta.sin(),
+                    a * TAU * frequency_now * theta.cos(),
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Wave {
+    #[default]
+    Sine,
+    Square,
+    Chirp,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveformEntry {
+    #[serde(default)]
+    wave: Wave,
+    amplitude: f64,
+    frequency: f64,
+    #[serde(default)]
+    phase: f64,
+    #[serde(default)]
+    offset: f64,
+    end_frequency: Option<f64>,
+    sweep: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveformFile {
+    #[serde(default)]
+    joints: BTreeMap<String, WaveformEntry>,
+}
+
+impl WaveformEntry {
+    fn check(self, joint: &str) -> Result<Waveform, WaveformError> {
+        let bad = |field, expected| WaveformError::BadField {
+            joint: joint.to_string(),
+            field,
+            expected,
+        };
+        for (field, value) in [
+            ("amplitude", self.amplitude),
+            ("phase", self.phase),
+            ("offset", self.offset),
+        ] {
+            if !value.is_finite() {
+                return Err(bad(field, "a finite number"));
+            }
+        }
+        if !(self.frequency.is_finite() && self.frequency >= 0.0) {
+            return Err(bad("frequency", "zero or more"));
+        }
+        let shape = match self.wave {
+            Wave::Sine | Wave::Square => {
+                if self.end_frequency.is_some() {
+                    return Err(bad("end_frequency", "left out except on chirps"));
+                }
+                if self.sweep.is_some() {
+                    return Err(bad("sweep", "left out except on chirps"));
+                }
+                if matches!(self.wave, Wave::Sine) {
+                    Shape::Sine
+                } else {
+                    Shape::Square
+                }
+            }
+            Wave::Chirp => {
+                let end_frequency = self
+                    .end_frequency
+                    .ok_or_else(|| bad("end_frequency", "given for a chirp"))?;
+                if !(end_frequency.is_finite() && end_frequency >= 0.0) {
+                    return Err(bad("end_frequency", "zero or more"));
+                }
+                let sweep = self.sweep.unwrap_or(DEFAULT_SWEEP);
+                if !(sweep.is_finite() && sweep > 0.0) {
+                    return Err(bad("sweep", "positive"));
+                }
+                Shape::Chirp {
+                    end_frequency,
+                    sweep,
+                }
+            }
+        };
+        Ok(Waveform {
+            shape,
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+            phase: self.phase,
+            offset: self.offset,
+        })
+    }
+}
+
+/// Every joint's waveform, by joint name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveforms {
+    joints: BTreeMap<String, Waveform>,
+}
+
+impl Waveforms {
+    /// Read a waveform file.
+    pub fn load(path: &Path) -> Result<Self, WaveformError> {
+        let raw = fs::read_to_string(path).map_err(|source| WaveformError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        raw.parse()
+    }
+
+    /// The joints driven, in name order.
+    pub fn joint_names(&self) -> impl Iterator<Item = &str> {
+        self.joints.keys().map(String::as_str)
+    }
+
+    /// `joint`'s waveform, if the file gives it one.
+    pub fn get(&self, joint: &str) -> Option<&Waveform> {
+        self.joints.get(joint)
+    }
+
+    /// Every joint's state `elapsed` after the start, in name order.
+    pub fn state_at(&self, elapsed: Duration, sequence: u64, robot_id: &str) -> RobotState {
+        let t = elapsed.as_secs_f64();
+        let joints = self
+            .joints
+            .iter()
+            .map(|(name, waveform)| {
+                let (angle_rad, velocity) = waveform.sample(t);
+                JointAngles {
+                    timestamp: 0,
+                    joint_name: name.clone(),
+                    angle_rad,
+                    velocity,
+                    torque: 0.0,
+                    qpos: Vec::new(),
+                }
+            })
+            .collect();
+        RobotState {
+            timestamp: 0,
+            robot_id: robot_id.to_string(),
+            joints,
+            sent_at_us: None,
+            sequence: Some(sequence),
+        }
+    }
+}
+
+impl FromStr for Waveforms {
+    type Err = WaveformError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let file: WaveformFile = toml::from_str(raw)?;
+        if file.joints.is_empty() {
+            return Err(WaveformError::Empty);
+        }
+        let joints = file
+            .joints
+            .into_iter()
+            .map(|(joint, entry)| {
+                let waveform = entry.check(&joint)?;
+                Ok((joint, waveform))
+            })
+            .collect::<Result<_, WaveformError>>()?;
+        Ok(Waveforms { joints })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn samples_each_shape() {
+        let sine = Waveform {
+            shape: Shape::Sine,
+            amplitude: 0.5,
+            frequency: 2.0,
+            phase: 0.0,
+            offset: 1.0,
+        };
+        // A quarter period in: at the peak, standing still.
+        let (angle, velocity) = sine.sample(0.125);
+        assert!(
+            close(angle, 1.5) && close(velocity, 0.0),
+            "{angle} {velocity}"
+        );
+        assert!(close(sine.sample(0.0).1, 0.5 * TAU * 2.0));
+
+        let square = Waveform {
+            shape: Shape::Square,
+            ..sine
+        };
+        assert_eq!(square.sample(0.0), (1.5, 0.0));
+        assert_eq!(square.sample(0.24), (1.5, 0.0));
+        assert_eq!(square.sample(0.25), (0.5, 0.0));
+        assert_eq!(square.sample(0.5), (1.5, 0.0));
+
+        let chirp = Waveform {
+            shape: Shape::Chirp {
+                end_frequency: 4.0,
+                sweep: 2.0,
+            },
+            frequency: 1.0,
+            ..sine
+        };
+        // Velocity at a zero crossing is amplitude * 2π * the frequency then.
+        assert!(close(chirp.sample(0.0).1, 0.5 * TAU * 1.0));
+        let (_, end) = chirp.sample(2.0 - 1e-12);
+        assert!((end.abs() - 0.5 * TAU * 4.0).abs() < 1e-6, "{end}");
+        // Then the sweep starts over.
+        assert_eq!(chirp.sample(2.0), chirp.sample(0.0));
+    }
+
+    #[test]
+    fn parses_joints_with_defaults() {
+        let waveforms: Waveforms = "
+            [joints.f1_MCP]
+            amplitude = 0.6
+            frequency = 0.5
+            offset = 0.7
+
+            [joints.t1_TM]
+            wave = \"chirp\"
+            amplitude = 0.3
+            frequency = 0.2
+            end_frequency = 2.0
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            waveforms.joint_names().collect::<Vec<_>>(),
+            ["f1_MCP", "t1_TM"]
+        );
+        let mcp = waveforms.get("f1_MCP").unwrap();
+        assert_eq!((mcp.shape, mcp.phase, mcp.offset), (Shape::Sine, 0.0, 0.7));
+        assert_eq!(
+            waveforms.get("t1_TM").unwrap().shape,
+            Shape::Chirp {
+                end_frequency: 2.0,
+                sweep: DEFAULT_SWEEP
+            }
+        );
+
+        let state = waveforms.state_at(Duration::ZERO, 7, "hand");
+        assert_eq!((state.sequence, state.robot_id.as_str()), (Some(7), "hand"));
+        assert_eq!(state.joints[0].joint_name, "f1_MCP");
+        assert!(close(state.joints[0].angle_rad, 0.7));
+    }
+
+    #[test]
+    fn rejects_incomplete_or_mismatched_joints() {
+        let bad = |raw: &str| raw.parse::<Waveforms>().unwrap_err();
+        assert!(matches!(bad(""), WaveformError::Empty));
+        assert!(matches!(
+            bad("[joints.a]\namplitude = 1.0\n"),
+            WaveformError::Toml(_)
+        ));
+        assert!(matches!(
+            bad("[joints.a]\nwave = \"triangle\"\namplitude = 1.0\nfrequency = 1.0\n"),
+            WaveformError::Toml(_)
+        ));
+        assert!(matches!(
+            bad("[joints.a]\nwave = \"chirp\"\namplitude = 1.0\nfrequency = 1.0\n"),
+            WaveformError::BadField {
+                field: "end_frequency",
+                ..
+            }
+        ));
+        assert!(matches!(
+            bad("[joints.a]\namplitude = 1.0\nfrequency = 1.0\nsweep = 2.0\n"),
+            WaveformError::BadField { field: "sweep", .. }
+        ));
+        assert!(matches!(
+            bad("[joints.a]\namplitude = 1.0\nfrequency = -1.0\n"),
+            WaveformError::BadField {
+                field: "frequency",
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,527 @@
+//! The message format: every payload is one [`RobotState`] (or, on the poses
+//! topic, one [`BodyPoses`], and on a batch topic one [`RobotStateBatch`]), as JSON, MessagePack, or protobuf (see [`Format`]). Over ZMQ the topic and the
+//! payload are two frames of one message; transports with their own topics
+//! (MQTT, in-process) carry the bare payload.
+//!
+//! A payload may also be zstd-compressed (see [`Compression`]); subscribers
+//! recognize the zstd frame and [`decompress`] it before decoding.
+//!
+//! [`encode`] and [`decode`] are the older single-frame `"<topic> <json>"`
+//! format, which subscribers can still accept (see `ZmqTransport`).
+
+use std::io::{self, Read};
+
+use errors::{Exit, ExitStatus};
+use prost::Message as _;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::proto::{ProtoBodyPoses, ProtoRobotState, ProtoRobotStateBatch};
+use crate::{BodyPoses, RobotState, RobotStateBatch, RobotStateRef};
+
+/// How a [`RobotState`] payload is encoded.
+///
+/// JSON is readable with any tool and is what browsers get from `ws_relay`;
+/// protobuf is several times smaller, which adds up at 50–200 Hz with 20+
+/// joints. MessagePack sits between them: binary, but self-describing like
+/// JSON, so it needs no schema. Receivers need not be told: [`Format::detect`]
+/// tells them apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// `RobotState` as a JSON object.
+    #[default]
+    Json,
+    /// `RobotState` as a MessagePack map with the same field names as the JSON.
+    #[serde(rename = "msgpack")]
+    #[value(name = "msgpack")]
+    MessagePack,
+    /// `robot_joint_pubsub.RobotState` from `proto/robot_joint_pubsub.proto`.
+    Protobuf,
+}
+
+impl Format {
+    /// The format of a received payload.
+    ///
+    /// A JSON payload is an object (or an array), so it starts with `{` (or
+    /// `[`) once a UTF-8 byte order mark, as text tools add, is skipped. A
+    /// MessagePack one is a map, so it starts with a map marker (`0x80`–`0x8f`,
+    /// `0xde`, `0xdf`). A protobuf one starts with neither: `{` would be field
+    /// 15 opening a group, `[` field 11 ending one, and a byte from `0x80` a
+    /// field numbered 16 or more; the schema has none of those.
+    ///
+    /// Leading whitespace, as hand-written files add, is skipped too, but only
+    /// when the rest is text: a protobuf batch starts with `\n` (field 1,
+    /// length-delimited) and then the first state's length, `[` for 91 bytes
+    /// and `{` for 123, yet the state's own tags are control characters, which
+    /// JSON never holds raw.
+    pub fn detect(payload: &[u8]) -> Format {
+        let text = json_text(payload);
+        let body = text.trim_ascii_start();
+        if matches!(body.first(), Some(b'{' | b'[')) && (body.len() == text.len() || is_text(body))
+        {
+            return Format::Json;
+        }
+        match payload.first() {
+            Some(0x80..=0x8f | 0xde | 0xdf) => Format::MessagePack,
+            _ => Format::Protobuf,
+        }
+    }
+
+    /// `state` as a payload in this format.
+    pub fn encode(self, state: &RobotState) -> Vec<u8> {
+        match self {
+            Format::Json => to_payload(state).into_bytes(),
+            // Named fields keep the payload self-describing; only plain data, so it cannot fail.
+            Format::MessagePack => {
+                rmp_serde::to_vec_named(state).expect("RobotState serializes to MessagePack")
+            }
+            Format::Protobuf => ProtoRobotState::from(state).encode_to_vec(),
+        }
+    }
+
+    /// Decode a payload that is known to be in this format.
+    pub fn decode(self, payload: &[u8]) -> Result<RobotState, WireError> {
+        match self {
+            Format::Json => serde_json::from_slice(json_text(payload)).map_err(WireError::Json),
+            Format::MessagePack => rmp_serde::from_slice(payload).map_err(WireError::MessagePack),
+            Format::Protobuf => ProtoRobotState::decode(payload)
+                .map(RobotState::from)
+                .map_err(WireError::Protobuf),
+        }
+    }
+
+    /// [`Format::decode`], but with the names borrowed from `payload` where
+    /// the format allows: JSON strings without escapes, and every MessagePack
+    /// string. Protobuf decodes owned.
+    pub fn decode_ref(self, payload: &[u8]) -> Result<RobotStateRef<'_>, WireError> {
+        match self {
+            Format::Json => serde_json::from_slice(json_text(payload)).map_err(WireError::Json),
+            Format::MessagePack => rmp_serde::from_slice(payload).map_err(WireError::MessagePack),
+            Format::Protobuf => ProtoRobotState::decode(payload)
+                .map(|proto| RobotState::from(proto).into())
+                .map_err(WireError::Protobuf),
+        }
+    }
+
+    /// `batch` as a payload in this format.
+    pub fn encode_batch(self, batch: &RobotStateBatch) -> Vec<u8> {
+        match self {
+            Format::Json => serde_json::to_vec(batch).expect("RobotStateBatch serializes to JSON"),
+            Format::MessagePack => {
+                rmp_serde::to_vec_named(batch).expect("RobotStateBatch serializes to MessagePack")
+            }
+            Format::Protobuf => ProtoRobotStateBatch::from(batch).encode_to_vec(),
+        }
+    }
+
+    /// Decode a batch payload that is known to be in this format.
+    pub fn decode_batch(self, payload: &[u8]) -> Result<RobotStateBatch, WireError> {
+        match self {
+            Format::Json => serde_json::from_slice(json_text(payload)).map_err(WireError::Json),
+            Format::MessagePack => rmp_serde::from_slice(payload).map_err(WireError::MessagePack),
+            Format::Protobuf => ProtoRobotStateBatch::decode(payload)
+                .map(RobotStateBatch::from)
+                .map_err(WireError::Protobuf),
+        }
+    }
+
+    /// `poses` as a payload in this format.
+    pub fn encode_poses(self, poses: &BodyPoses) -> Vec<u8> {
+        match self {
+            Format::Json => serde_json::to_vec(poses).expect("BodyPoses serializes to JSON"),
+            Format::MessagePack => {
+                rmp_serde::to_vec_named(poses).expect("BodyPoses serializes to MessagePack")
+            }
+            Format::Protobuf => ProtoBodyPoses::from(poses).encode_to_vec(),
+        }
+    }
+
+    /// Decode a poses payload that is known to be in this format.
+    pub fn decode_poses(self, payload: &[u8]) -> Result<BodyPoses, WireError> {
+        match self {
+            Format::Json => serde_json::from_slice(json_text(payload)).map_err(WireError::Json),
+            Format::MessagePack => rmp_serde::from_slice(payload).map_err(WireError::MessagePack),
+            Format::Protobuf => ProtoBodyPoses::decode(payload)
+                .map(BodyPoses::from)
+                .map_err(WireError::Protobuf),
+        }
+    }
+}
+
+/// `payload` without a leading UTF-8 byte order mark, which `serde_json` refuses.
+fn json_text(payload: &[u8]) -> &[u8] {
+    payload.strip_prefix(b"\xef\xbb\xbf").unwrap_or(payload)
+}
+
+/// Whether `bytes` has no control characters but whitespace, as JSON text.
+fn is_text(bytes: &[u8]) -> bool {
+    !bytes
+        .iter()
+        .any(|&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r'))
+}
+
+/// Whether payloads are compressed on top of their [`Format`].
+///
+/// Full-hand JSON at 100+ Hz shrinks several times under zstd, at the cost of
+/// some CPU on both ends; protobuf gains less. Receivers need not be told:
+/// a zstd frame starts with [`ZSTD_MAGIC`], which no uncompressed payload
+/// does (as protobuf it would be a varint field 5, which `RobotState` reserves
+/// and no other message has).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Payloads as encoded.
+    #[default]
+    None,
+    /// Each payload as one zstd frame (roughly zstd level 1).
+    Zstd,
+}
+
+/// The first bytes of every zstd frame.
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Largest payload [`decompress`] inflates to, so a malicious frame cannot
+/// exhaust memory; far above any real state.
+pub const MAX_DECOMPRESSED: u64 = 16 << 20;
+
+impl Compression {
+    /// `payload` compressed this way.
+    pub fn compress(self, payload: Vec<u8>) -> Vec<u8> {
+        match self {
+            Compression::None => payload,
+            Compression::Zstd => ruzstd::encoding::compress_to_vec(
+                payload.as_slice(),
+                ruzstd::encoding::CompressionLevel::Fastest,
+            ),
+        }
+    }
+}
+
+/// `payload` as its [`Format`] encoded it: inflated if it is a zstd frame,
+/// else as it is.
+pub fn decompress(payload: Vec<u8>) -> Result<Vec<u8>, WireError> {
+    if !payload.starts_with(&ZSTD_MAGIC) {
+        return Ok(payload);
+    }
+    let decoder = ruzstd::decoding::StreamingDecoder::new(payload.as_slice())
+        .map_err(|e| WireError::Zstd(io::Error::other(e)))?;
+    let mut inflated = Vec::new();
+    decoder
+        .take(MAX_DECOMPRESSED + 1)
+        .read_to_end(&mut inflated)
+        .map_err(WireError::Zstd)?;
+    if inflated.len() as u64 > MAX_DECOMPRESSED {
+        return Err(WireError::Zstd(io::Error::other(format!(
+            "inflates past {MAX_DECOMPRESSED} bytes"
+        ))));
+    }
+    Ok(inflated)
+}
+
+/// A message that is not a topic and a valid payload.
+#[derive(Debug, Error)]
+pub enum WireError {
+    /// A ZMQ message that is not a topic frame and a payload frame.
+    #[error("expected a topic frame and a payload frame, got {0} frame(s)")]
+    Frames(usize),
+    /// No space separating the topic from the payload in a single-frame message.
+    #[error("message has no topic prefix")]
+    MissingTopic,
+    /// The payload is not a JSON message of the expected type.
+    #[error("payload does not decode from JSON")]
+    Json(#[source] serde_json::Error),
+    /// The payload is not a MessagePack message of the expected type.
+    #[error("payload does not decode from MessagePack")]
+    MessagePack(#[source] rmp_serde::decode::Error),
+    /// The payload is not a protobuf message of the expected type.
+    #[error("payload does not decode from protobuf")]
+    Protobuf(#[source] prost::DecodeError),
+    /// The payload starts like a zstd frame but does not decompress.
+    #[error("payload does not decompress from zstd")]
+    Zstd(#[source] io::Error),
+}
+
+impl ExitStatus for WireError {
+    fn exit(&self) -> Exit {
+        Exit::Data
+    }
+}
+
+/// `state` as a JSON payload.
+pub fn to_payload(state: &RobotState) -> String {
+    // `RobotState` is plain strings and numbers, so serializing cannot fail.
+    serde_json::to_string(state).expect("RobotState serializes to JSON")
+}
+
+/// Decode a payload in either format, see [`Format::detect`].
+pub fn from_payload(payload: &[u8]) -> Result<RobotState, WireError> {
+    Format::detect(payload).decode(payload)
+}
+
+/// [`from_payload`], borrowing the names from `payload` (see [`Format::decode_ref`]).
+pub fn from_payload_ref(payload: &[u8]) -> Result<RobotStateRef<'_>, WireError> {
+    Format::detect(payload).decode_ref(payload)
+}
+
+/// Render `state` as a legacy single-frame JSON ZMQ message on `topic`.
+pub fn encode(topic: &str, state: &RobotState) -> String {
+    format!("{topic} {}", to_payload(state))
+}
+
+/// Split a legacy single-frame ZMQ message into its topic and decoded state.
+pub fn decode(message: &str) -> Result<(&str, RobotState), WireError> {
+    let (topic, json) = message.split_once(' ').ok_or(WireError::MissingTopic)?;
+    Ok((topic, from_payload(json.as_bytes())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::sim::simulated_state;
+    use crate::JointAngles;
+
+    #[test]
+    fn round_trips_through_the_text_format() {
+        let state = simulated_state(7);
+        let message = encode("robot_joints", &state);
+        assert!(message.starts_with("robot_joints {\"timestamp\":0,"));
+        assert!(message.ends_with(",\"sequence\":7}"));
+        let (topic, decoded) = decode(&message).unwrap();
+        assert_eq!(topic, "robot_joints");
+        assert_eq!(decoded.sequence, Some(7));
+        assert_eq!(decoded.joints[0].joint_name, state.joints[0].joint_name);
+        // JSON float parsing may land one ULP away from the printed value.
+        assert!((decoded.joints[0].angle_rad - state.joints[0].angle_rad).abs() < 1e-12);
+    }
+
+    #[test]
+    fn payloads_are_bare_json() {
+        let payload = to_payload(&simulated_state(4));
+        assert!(payload.starts_with("{\"timestamp\":0,"));
+        assert_eq!(from_payload(payload.as_bytes()).unwrap().sequence, Some(4));
+        // Older publishers send no `sequence`; their `timestamp` orders them.
+        let legacy = from_payload(br#"{"timestamp":4,"robot_id":"arm","joints":[]}"#).unwrap();
+        assert_eq!((legacy.sequence, legacy.order()), (None, 4));
+        assert!(matches!(
+            Format::Json.decode(b"robot_joints {}"),
+            Err(WireError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn protobuf_payloads_round_trip_and_are_detected() {
+        let state = simulated_state(9);
+        let payload = Format::Protobuf.encode(&state);
+        assert_eq!(Format::detect(&payload), Format::Protobuf);
+        assert!(payload.len() < Format::Json.encode(&state).len());
+        // Doubles travel as their exact bits, unlike through JSON text.
+        assert_eq!(from_payload(&payload).unwrap(), state);
+        assert!(matches!(
+            Format::Protobuf.decode(b"\x1a\xff"),
+            Err(WireError::Protobuf(_))
+        ));
+        // Told the wrong format, a payload fails instead of being guessed at.
+        assert!(matches!(
+            Format::Json.decode(&payload),
+            Err(WireError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn json_after_whitespace_or_a_byte_order_mark_is_detected() {
+        let json = to_payload(&simulated_state(3));
+        let state = from_payload(json.as_bytes()).unwrap();
+        for payload in [
+            format!("\n  {json}"),
+            format!("\u{feff}{json}"),
+            format!("\u{feff}\r\n\t{json}"),
+        ] {
+            assert_eq!(Format::detect(payload.as_bytes()), Format::Json);
+            assert_eq!(from_payload(payload.as_bytes()).unwrap(), state);
+        }
+        assert_eq!(Format::detect(b" [1, 2]"), Format::Json);
+        assert_eq!(Format::detect(b"\xef\xbb\xbf"), Format::Protobuf);
+    }
+
+    #[test]
+    fn message_pack_payloads_round_trip_and_are_detected() {
+        let state = simulated_state(11);
+        let payload = Format::MessagePack.encode(&state);
+        assert_eq!(Format::detect(&payload), Format::MessagePack);
+        assert!(payload.len() < Format::Json.encode(&state).len());
+        assert_eq!(from_payload(&payload).unwrap(), state);
+        // Field names travel with the data, so it reads back as JSON's shape too.
+        let value: serde_json::Value = rmp_serde::from_slice(&payload).unwrap();
+        assert_eq!(value["robot_id"], state.robot_id.as_str());
+        assert!(matches!(
+            Format::MessagePack.decode(&[0x83, 0xa1]),
+            Err(WireError::MessagePack(_))
+        ));
+    }
+
+    #[test]
+    fn every_format_round_trips_a_many_joint_state() {
+        let mut state = simulated_state(5);
+        state.joints = (0..24)
+            .map(|i| JointAngles {
+                joint_name: format!("finger_{i}"),
+                angle_rad: -1.5 + 0.125 * i as f64,
+                ..state.joints[0].clone()
+            })
+            .collect();
+        state.sent_at_us = Some(1_760_000_000_123_456);
+        for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+            let payload = format.encode(&state);
+            assert_eq!(Format::detect(&payload), format);
+            let decoded = format.decode(&payload).unwrap();
+            assert_eq!(decoded.joints.len(), 24, "{format:?}");
+            assert_eq!(decoded.joints[23].joint_name, "finger_23", "{format:?}");
+            assert_eq!(decoded.sent_at_us, state.sent_at_us, "{format:?}");
+            assert_eq!(decoded.sequence, state.sequence, "{format:?}");
+            assert_eq!(decoded.joints[23].qpos, state.joints[23].qpos, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn borrowed_decoding_lends_names_where_it_can() {
+        let state = simulated_state(12);
+        for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+            let payload = format.encode(&state);
+            let borrowed = from_payload_ref(&payload).unwrap();
+            let lent = matches!(borrowed.joints[0].joint_name, Cow::Borrowed(_));
+            assert_eq!(lent, format != Format::Protobuf, "{format:?}");
+            assert_eq!(RobotState::from(borrowed), format.decode(&payload).unwrap());
+        }
+
+        // An escaped name has nothing to lend, but still decodes.
+        let escaped = r#"{"timestamp":1,"robot_id":"arm","joints":[{"timestamp":1,"joint_name":"el\u0062ow","angle_rad":0.5,"velocity":0.0,"torque":0.0}]}"#;
+        let borrowed = Format::Json.decode_ref(escaped.as_bytes()).unwrap();
+        assert!(matches!(borrowed.robot_id, Cow::Borrowed("arm")));
+        assert!(matches!(&borrowed.joints[0].joint_name, Cow::Owned(name) if name == "elbow"));
+        assert_eq!(borrowed.joints[0].positions(), [0.5]);
+        assert!(matches!(
+            Format::MessagePack.decode_ref(&[0x83, 0xa1]),
+            Err(WireError::MessagePack(_))
+        ));
+    }
+
+    #[test]
+    fn ball_joints_carry_every_coordinate_and_hinges_none() {
+        let state = simulated_state(40);
+        assert_eq!(state.joints[0].positions().len(), 4);
+        let json = to_payload(&state);
+        assert!(json.contains("\"qpos\":["), "{json}");
+
+        // Older publishers send no `qpos`: one coordinate, the angle.
+        let hinge = r#"{"timestamp":1,"robot_id":"arm","joints":[{"timestamp":1,"joint_name":"elbow","angle_rad":0.5,"velocity":0.0,"torque":0.0}]}"#;
+        for payload in [
+            hinge.as_bytes().to_vec(),
+            Format::Protobuf.encode(&from_payload(hinge.as_bytes()).unwrap()),
+        ] {
+            let decoded = from_payload(&payload).unwrap();
+            assert!(decoded.joints[0].qpos.is_empty());
+            assert_eq!(decoded.joints[0].positions(), [0.5]);
+        }
+        assert!(!to_payload(&from_payload(hinge.as_bytes()).unwrap()).contains("qpos"));
+    }
+
+    #[test]
+    fn every_format_round_trips_a_batch() {
+        let batch = RobotStateBatch {
+            states: (1..=5).map(simulated_state).collect(),
+        };
+        for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+            let payload = format.encode_batch(&batch);
+            assert_eq!(Format::detect(&payload), format);
+            let decoded = format.decode_batch(&payload).unwrap();
+            let sequences: Vec<_> = decoded.states.iter().map(|s| s.order()).collect();
+            assert_eq!(sequences, [1, 2, 3, 4, 5], "{format:?}");
+        }
+        let empty = Format::Protobuf.encode_batch(&RobotStateBatch::default());
+        assert!(Format::Protobuf
+            .decode_batch(&empty)
+            .unwrap()
+            .states
+            .is_empty());
+    }
+
+    #[test]
+    fn protobuf_batches_opening_like_json_after_a_newline_are_protobuf() {
+        // `\n` then the first state's length: `[` for 91 bytes, `{` for 123.
+        for len in [91, 123] {
+            let mut first = simulated_state(1);
+            first.joints.truncate(1);
+            first.robot_id.clear();
+            while Format::Protobuf.encode(&first).len() < len {
+                first.robot_id.push('r');
+            }
+            assert_eq!(Format::Protobuf.encode(&first).len(), len);
+            let batch = RobotStateBatch {
+                states: vec![first, simulated_state(2)],
+            };
+            let payload = Format::Protobuf.encode_batch(&batch);
+            assert_eq!(payload[..2], [b'\n', len as u8]);
+            assert_eq!(Format::detect(&payload), Format::Protobuf);
+            assert_eq!(Format::Protobuf.decode_batch(&payload).unwrap(), batch);
+        }
+    }
+
+    #[test]
+    fn every_format_round_trips_body_poses() {
+        let poses = crate::poses::from_state(
+            &simulated_state(6),
+            [
+                ("index_tip", [0.01, -0.02, 0.3], [1.0, 0.0, 0.0, 0.0]),
+                ("thumb_tip", [0.04, 0.05, 0.25], [0.5, 0.5, -0.5, 0.5]),
+            ],
+        );
+        for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+            let payload = format.encode_poses(&poses);
+            assert_eq!(Format::detect(&payload), format);
+            assert_eq!(format.decode_poses(&payload).unwrap(), poses, "{format:?}");
+        }
+        // A state is not poses, and poses are not a state.
+        assert!(Format::Json
+            .decode_poses(&Format::Json.encode(&simulated_state(6)))
+            .is_err());
+        assert!(Format::Json
+            .decode(&Format::Json.encode_poses(&poses))
+            .is_err());
+    }
+
+    #[test]
+    fn zstd_payloads_shrink_and_are_recognized() {
+        let mut state = simulated_state(12);
+        state.joints = (0..24)
+            .map(|i| JointAngles {
+                joint_name: format!("finger_{i}"),
+                ..state.joints[0].clone()
+            })
+            .collect();
+        for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+            let payload = format.encode(&state);
+            let compressed = Compression::Zstd.compress(payload.clone());
+            assert!(compressed.starts_with(&ZSTD_MAGIC));
+            assert!(compressed.len() < payload.len(), "{format:?}");
+            assert_eq!(decompress(compressed).unwrap(), payload);
+            // Uncompressed payloads pass through untouched.
+            assert_eq!(decompress(payload.clone()).unwrap(), payload);
+        }
+        assert_eq!(Compression::None.compress(b"{}".to_vec()), b"{}");
+        let mut truncated = Compression::Zstd.compress(to_payload(&state).into_bytes());
+        truncated.truncate(12);
+        assert!(matches!(decompress(truncated), Err(WireError::Zstd(_))));
+    }
+
+    #[test]
+    fn rejects_malformed_frames() {
+        assert!(matches!(
+            decode("robot_joints"),
+            Err(WireError::MissingTopic)
+        ));
+        assert!(matches!(decode("robot_joints {}"), Err(WireError::Json(_))));
+    }
+}
@@ -0,0 +1,91 @@
+//! A WebSocket feed that re-serves [`RobotState`]s as JSON text frames, one per
+//! state, for browser dashboards (see `ws_dashboard.html`).
+//!
+//! Every connected client gets every state through a bounded broadcast queue.
+//! [`Feed::send`] never waits for clients: one that falls more than the queue
+//! behind is disconnected, so a stalled browser tab cannot hold up the others.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::RobotState;
+
+/// States a client may fall behind by before it is dropped.
+pub const DEFAULT_BACKLOG: usize = 64;
+
+/// The sending side of the feed; clone it freely.
+#[derive(Clone)]
+pub struct Feed {
+    tx: broadcast::Sender<Arc<str>>,
+}
+
+impl Feed {
+    /// A feed that drops clients lagging more than `backlog` states behind.
+    pub fn new(backlog: usize) -> Self {
+        let (tx, _) = broadcast::channel(backlog);
+        Feed { tx }
+    }
+
+    /// Queue `state` for every connected client; returns how many there are.
+    pub fn send(&self, state: &RobotState) -> usize {
+        // `RobotState` is plain strings and numbers, so serializing cannot fail.
+        let json = serde_json::to_string(state).expect("RobotState serializes to JSON");
+        // An error only means nobody is connected.
+        self.tx.send(json.into()).unwrap_or(0)
+    }
+
+    /// Number of connected clients.
+    pub fn clients(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Accept WebSocket clients on `listener` until the task is cancelled.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            tokio::spawn(self.clone().client(stream, peer));
+        }
+    }
+
+    async fn client(self, stream: TcpStream, peer: SocketAddr) {
+        let mut socket = match tokio_tungstenite::accept_async(stream).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                tracing::debug!(%peer, error = %e, "WebSocket handshake failed");
+                return;
+            }
+        };
+        let mut states = self.tx.subscribe();
+        tracing::info!(%peer, clients = self.clients(), "client connected");
+        loop {
+            tokio::select! {
+                state = states.recv() => match state {
+                    Ok(json) => {
+                        if socket.send(Message::text(&*json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!(%peer, missed, "dropping slow client");
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                // Clients only listen; anything but a close (or an error) is ignored.
+                incoming = socket.next() => match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+        // Unsubscribe before saying goodbye, so `clients()` is current once the peer sees the close.
+        drop(states);
+        let _ = socket.close(None).await;
+        tracing::info!(%peer, "client disconnected");
+    }
+}
@@ -0,0 +1,167 @@
+//! A publisher task and a subscriber task on one `InprocTransport` bus, the way
+//! the tokio binaries (`ws_relay`, `recorder`) run them: round trips in every
+//! format, topic filtering, and what a malformed payload does to the stream.
+
+use std::time::Duration;
+
+use project_robot_joint_pubsub::sim::simulated_state;
+use project_robot_joint_pubsub::wire::{Format, WireError};
+use project_robot_joint_pubsub::{
+    AsyncSubscriber, InprocTransport, Publisher, RobotState, Subscriber, Transport, TransportError,
+};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+const TOPIC: &str = "robot_joints";
+
+/// How long a test waits for something that should happen promptly.
+const PATIENCE: Duration = Duration::from_secs(5);
+
+/// Publish `simulated_state(1..=count)` on `topic` from a task of its own,
+/// yielding between states so the subscriber task runs alongside.
+fn publisher_task(publisher: Publisher<InprocTransport>, count: u64) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        for sequence in 1..=count {
+            publisher.publish(&simulated_state(sequence)).unwrap();
+            tokio::task::yield_now().await;
+        }
+    })
+}
+
+/// The next result from `states`, waiting up to [`PATIENCE`].
+async fn next(states: &mut AsyncSubscriber) -> Result<RobotState, TransportError> {
+    timeout(PATIENCE, states.recv())
+        .await
+        .expect("a message within the patience window")
+        .expect("receiving thread alive")
+}
+
+/// The format a payload failed to decode from, if that is what `err` is.
+fn failed_format(err: &TransportError) -> Option<Format> {
+    match err {
+        TransportError::Wire(WireError::Json(_)) => Some(Format::Json),
+        TransportError::Wire(WireError::MessagePack(_)) => Some(Format::MessagePack),
+        TransportError::Wire(WireError::Protobuf(_)) => Some(Format::Protobuf),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn every_format_round_trips_between_tasks() {
+    for format in [Format::Json, Format::MessagePack, Format::Protobuf] {
+        let bus = InprocTransport::new();
+        // Subscribed before the publisher task starts, so nothing may be missing.
+        let mut states = AsyncSubscriber::spawn(Subscriber::new(bus.connect(), TOPIC).unwrap());
+        let sender = publisher_task(Publisher::new(bus, TOPIC).with_format(format), 100);
+
+        for sequence in 1..=100 {
+            let state = next(&mut states).await.unwrap();
+            assert_same_state(&state, &simulated_state(sequence));
+            assert!(state.sent_at_us.is_some(), "{format:?} lost sent_at_us");
+        }
+        sender.await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn only_the_subscribed_topic_comes_through() {
+    let bus = InprocTransport::new();
+    // Take every topic at the transport, so the `Subscriber` has to do the filtering.
+    let endpoint = bus.connect();
+    endpoint.subscribe_prefix("").unwrap();
+    let subscriber = Subscriber::new(endpoint, TOPIC).unwrap();
+
+    let tagged = |robot_id: &str, sequence| {
+        let mut state = simulated_state(sequence);
+        state.robot_id = robot_id.to_string();
+        state
+    };
+    let sender = tokio::spawn(async move {
+        let noise = [
+            Publisher::new(bus.connect(), "robot_joints_debug"),
+            Publisher::new(bus.connect(), "other"),
+        ];
+        let main = Publisher::new(bus, TOPIC);
+        for sequence in 1..=20 {
+            for (publisher, robot_id) in noise.iter().zip(["debug", "other"]) {
+                publisher.publish(&tagged(robot_id, sequence)).unwrap();
+            }
+            main.heartbeat().unwrap();
+            main.publish(&tagged("main", sequence)).unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+
+    let mut states = AsyncSubscriber::spawn(subscriber);
+    for sequence in 1..=20 {
+        let state = next(&mut states).await.unwrap();
+        assert_eq!((state.robot_id.as_str(), state.order()), ("main", sequence));
+    }
+    sender.await.unwrap();
+    // Nothing else was queued behind the last state.
+    assert!(timeout(Duration::from_millis(50), states.recv())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn malformed_payloads_are_reported_and_skipped() {
+    let bus = InprocTransport::new();
+    let mut states = AsyncSubscriber::spawn(Subscriber::new(bus.connect(), TOPIC).unwrap());
+    let malformed: [&[u8]; 3] = [
+        // Truncated JSON, MessagePack, and protobuf.
+        br#"{"timestamp":1,"robot_id":"#,
+        &[0x83, 0xa9, b't'],
+        &[0xff, 0xff, 0xff],
+    ];
+    for payload in malformed {
+        bus.send(TOPIC, payload).unwrap();
+    }
+    let publisher = Publisher::new(bus, TOPIC);
+    publisher.publish(&simulated_state(1)).unwrap();
+
+    let formats = [Format::Json, Format::MessagePack, Format::Protobuf];
+    for (payload, format) in malformed.iter().zip(formats) {
+        let err = next(&mut states).await.unwrap_err();
+        assert_eq!(failed_format(&err), Some(format), "{payload:?}: {err:?}");
+    }
+    // The stream goes on after each bad payload.
+    assert_same_state(&next(&mut states).await.unwrap(), &simulated_state(1));
+}
+
+#[tokio::test]
+async fn a_pinned_format_rejects_the_others() {
+    let bus = InprocTransport::new();
+    let subscriber = Subscriber::new(bus.connect(), TOPIC)
+        .unwrap()
+        .with_format(Format::Json);
+    let mut states = AsyncSubscriber::spawn(subscriber);
+    let protobuf = Publisher::new(bus.connect(), TOPIC).with_format(Format::Protobuf);
+    let json = Publisher::new(bus, TOPIC);
+
+    protobuf.publish(&simulated_state(1)).unwrap();
+    json.publish(&simulated_state(2)).unwrap();
+
+    let err = next(&mut states).await.unwrap_err();
+    assert_eq!(failed_format(&err), Some(Format::Json), "{err:?}");
+    assert_same_state(&next(&mut states).await.unwrap(), &simulated_state(2));
+}
+
+/// Assert `actual` is `expected`, but for the timestamps the publisher stamped
+/// on the way; floats within a tolerance, as a JSON round trip may move one by
+/// an ULP.
+fn assert_same_state(actual: &RobotState, expected: &RobotState) {
+    assert_eq!(actual.order(), expected.order());
+    assert_eq!(actual.robot_id, expected.robot_id);
+    assert_eq!(actual.joints.len(), expected.joints.len());
+    for (got, want) in actual.joints.iter().zip(&expected.joints) {
+        assert_eq!(got.joint_name, want.joint_name);
+        for (name, got, want) in [
+            ("angle_rad", got.angle_rad, want.angle_rad),
+            ("velocity", got.velocity, want.velocity),
+            ("torque", got.torque, want.torque),
+        ] {
+            assert!((got - want).abs() < 1e-12, "{name}: {got} != {want}");
+        }
+    }
+}
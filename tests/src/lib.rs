@@ -0,0 +1,124 @@
+//! Helpers shared by the integration tests in `tests/`.
+//!
+//! [`HeadlessPublisher`] runs the same loop as `bin/publisher.rs` (simulated
+//! states at a fixed interval) on a background thread, so a test can start,
+//! stop, and restart it without spawning processes or linking MuJoCo.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use project_robot_joint_pubsub::sim::simulated_state;
+use project_robot_joint_pubsub::{Publisher, RobotState, Subscriber, Transport, ZmqTransport};
+
+/// Publish interval used by the tests: fast, but far below the socket's buffer limit.
+pub const INTERVAL: Duration = Duration::from_millis(2);
+
+/// How long a test waits for something that should happen promptly.
+pub const PATIENCE: Duration = Duration::from_secs(5);
+
+/// The headless publisher loop on a background thread; stops when dropped.
+pub struct HeadlessPublisher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HeadlessPublisher {
+    /// Bind `endpoint` (or connect, for a `>`-prefixed one) and publish
+    /// `simulated_state(first), (first + 1), …` on `topic`.
+    pub fn start(ctx: &zmq::Context, endpoint: &str, topic: &str, first: u64) -> HeadlessPublisher {
+        let transport = ZmqTransport::bind(ctx, endpoint).expect("bind publisher");
+        let publisher = Publisher::new(transport, topic);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || {
+                for sequence in first.. {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    publisher
+                        .publish(&simulated_state(sequence))
+                        .expect("publish");
+                    thread::sleep(INTERVAL);
+                }
+            }
+        });
+        HeadlessPublisher {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for HeadlessPublisher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            // Surface a publisher panic instead of hiding it.
+            if thread.join().is_err() && !thread::panicking() {
+                panic!("publisher thread panicked");
+            }
+        }
+    }
+}
+
+/// A SUB socket connected to `endpoint`, subscribed to exactly `topic`.
+pub fn zmq_subscriber(ctx: &zmq::Context, endpoint: &str, topic: &str) -> Subscriber<ZmqTransport> {
+    let transport = ZmqTransport::connect(ctx, endpoint).expect("connect subscriber");
+    Subscriber::new(transport, topic).expect("subscribe")
+}
+
+/// A fresh `ipc://` endpoint in the temp dir, unique to this process and `name`.
+pub fn ipc_endpoint(name: &str) -> String {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("til-pubsub-{}-{name}.sock", std::process::id()));
+    format!("ipc://{}", path.display())
+}
+
+/// Wait up to [`PATIENCE`] for the next state; panics if none arrives.
+pub fn next_state(subscriber: &Subscriber<impl Transport>) -> RobotState {
+    subscriber
+        .recv(PATIENCE)
+        .expect("receive")
+        .expect("a state within the patience window")
+}
+
+/// Receive until `done` holds for a state, returning that state.
+pub fn wait_for(
+    subscriber: &Subscriber<impl Transport>,
+    done: impl Fn(&RobotState) -> bool,
+) -> RobotState {
+    let deadline = Instant::now() + PATIENCE;
+    while Instant::now() < deadline {
+        if let Some(state) = subscriber.recv(Duration::from_millis(50)).expect("receive")
+            && done(&state)
+        {
+            return state;
+        }
+    }
+    panic!("no matching state within {PATIENCE:?}");
+}
+
+/// Assert `actual` is `expected` after a JSON round trip.
+///
+/// `serde_json` may parse a float one ULP away from what it printed, so the
+/// numeric fields are compared with a tolerance and everything else exactly,
+/// but for the timestamps the publisher stamped on the way.
+pub fn assert_same_state(actual: &RobotState, expected: &RobotState) {
+    assert_eq!(actual.order(), expected.order());
+    assert_eq!(actual.robot_id, expected.robot_id);
+    assert_eq!(actual.joints.len(), expected.joints.len());
+    for (got, want) in actual.joints.iter().zip(&expected.joints) {
+        assert_eq!(got.joint_name, want.joint_name);
+        for (name, got, want) in [
+            ("angle_rad", got.angle_rad, want.angle_rad),
+            ("velocity", got.velocity, want.velocity),
+            ("torque", got.torque, want.torque),
+        ] {
+            assert!((got - want).abs() < 1e-12, "{name}: {got} != {want}");
+        }
+    }
+}
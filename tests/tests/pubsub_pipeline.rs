@@ -0,0 +1,247 @@
+//! The publisher → subscriber pipeline over real ZMQ sockets, in one process.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use integration_tests::{
+    HeadlessPublisher, PATIENCE, assert_same_state, ipc_endpoint, next_state, wait_for,
+    zmq_subscriber,
+};
+use project_robot_joint_pubsub::sim::simulated_state;
+use project_robot_joint_pubsub::wire::{self, Format, WireError};
+use project_robot_joint_pubsub::{
+    ConnectionEvent, Subscriber, TransportError, ZmqBroker, ZmqMonitor, ZmqTransport,
+};
+
+const TOPIC: &str = "robot_joints";
+
+#[test]
+fn delivers_simulated_states_intact_over_inproc() {
+    let ctx = zmq::Context::new();
+    let _publisher = HeadlessPublisher::start(&ctx, "inproc://delivery", TOPIC, 1);
+    let subscriber = zmq_subscriber(&ctx, "inproc://delivery", TOPIC);
+
+    for _ in 0..20 {
+        let state = next_state(&subscriber);
+        assert_same_state(&state, &simulated_state(state.order()));
+    }
+}
+
+#[test]
+fn preserves_publish_order_over_ipc() {
+    let ctx = zmq::Context::new();
+    let endpoint = ipc_endpoint("order");
+    let _publisher = HeadlessPublisher::start(&ctx, &endpoint, TOPIC, 1);
+    let subscriber = zmq_subscriber(&ctx, &endpoint, TOPIC);
+
+    // Whatever arrives first (a late joiner misses the start), the rest follow in sequence.
+    let mut previous = next_state(&subscriber).order();
+    for _ in 0..100 {
+        let sequence = next_state(&subscriber).order();
+        assert_eq!(sequence, previous + 1, "gap or reordering after {previous}");
+        previous = sequence;
+    }
+}
+
+#[test]
+fn only_the_exact_topic_reaches_the_subscriber() {
+    let ctx = zmq::Context::new();
+    let publisher = ctx.socket(zmq::PUB).unwrap();
+    publisher.bind("inproc://topics").unwrap();
+    let subscriber = zmq_subscriber(&ctx, "inproc://topics", TOPIC);
+
+    let tagged = |robot_id: &str, sequence| {
+        let mut state = simulated_state(sequence);
+        state.robot_id = robot_id.to_string();
+        state
+    };
+    // Keep sending rounds until the subscription is live, then check what came through.
+    let mut received = Vec::new();
+    for round in 0..500 {
+        // Same prefix as TOPIC, so ZMQ itself lets it through.
+        for (topic, robot_id) in [
+            ("robot_joints_debug", "debug"),
+            ("other", "other"),
+            (TOPIC, "main"),
+        ] {
+            let payload = Format::Json.encode(&tagged(robot_id, round));
+            publisher
+                .send_multipart([topic.as_bytes(), &payload], 0)
+                .unwrap();
+        }
+        if let Some(state) = subscriber.recv(Duration::from_millis(10)).unwrap() {
+            received.push(state);
+        }
+        if received.len() >= 10 {
+            break;
+        }
+    }
+    assert!(received.len() >= 10, "subscription never became live");
+    assert!(received.iter().all(|state| state.robot_id == "main"));
+}
+
+#[test]
+fn a_prefix_takes_every_topic_under_it() {
+    let ctx = zmq::Context::new();
+    let _left = HeadlessPublisher::start(&ctx, "inproc://left", "hands/left", 1);
+    let _right = HeadlessPublisher::start(&ctx, "inproc://right", "hands/right", 1);
+    let transport = ZmqTransport::connect(&ctx, "inproc://left").unwrap();
+    transport.connect_also("inproc://right").unwrap();
+    let subscriber = Subscriber::new(transport, "hands/*").unwrap();
+
+    let mut topics = BTreeSet::new();
+    for _ in 0..1000 {
+        next_state(&subscriber);
+        topics.extend(subscriber.last_topic());
+        if topics.len() == 2 {
+            break;
+        }
+    }
+    assert_eq!(
+        topics,
+        BTreeSet::from(["hands/left".into(), "hands/right".into()])
+    );
+}
+
+#[test]
+fn subscriber_reconnects_to_a_restarted_publisher() {
+    let ctx = zmq::Context::new();
+    let endpoint = ipc_endpoint("reconnect");
+    // Connecting first is fine: ZMQ keeps retrying until someone binds.
+    let subscriber = zmq_subscriber(&ctx, &endpoint, TOPIC);
+
+    let first = HeadlessPublisher::start(&ctx, &endpoint, TOPIC, 1);
+    assert!(next_state(&subscriber).order() < 1_000);
+    drop(first);
+
+    // A new publisher run, numbered from 1000 so its states are recognisable.
+    let _second = HeadlessPublisher::start(&ctx, &endpoint, TOPIC, 1_000);
+    let state = wait_for(&subscriber, |state| state.order() >= 1_000);
+    assert_same_state(&state, &simulated_state(state.order()));
+}
+
+/// Poll `monitor` until it has reported `wanted`, or panic after [`PATIENCE`].
+fn wait_for_event(monitor: &mut ZmqMonitor, wanted: &ConnectionEvent) {
+    let deadline = std::time::Instant::now() + PATIENCE;
+    while std::time::Instant::now() < deadline {
+        if monitor.poll().unwrap().contains(wanted) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("no {wanted:?} within {PATIENCE:?}");
+}
+
+#[test]
+fn monitors_see_connections_come_and_go() {
+    let ctx = zmq::Context::new();
+    let endpoint = ipc_endpoint("monitor");
+    let publisher = ZmqTransport::bind(&ctx, &endpoint).unwrap();
+    let mut accepted = publisher.monitor().unwrap();
+    // Connected before it was monitored, and reported all the same.
+    let subscriber = ZmqTransport::connect(&ctx, &endpoint).unwrap();
+    let mut connected = subscriber.monitor().unwrap();
+
+    wait_for_event(&mut connected, &ConnectionEvent::Up(endpoint.clone()));
+    assert_eq!(connected.connections(), 1);
+    wait_for_event(&mut accepted, &ConnectionEvent::Up(endpoint.clone()));
+
+    // A publisher that went away is a disconnection, not just silence.
+    drop(publisher);
+    wait_for_event(&mut connected, &ConnectionEvent::Down(endpoint.clone()));
+    assert_eq!(connected.connections(), 0);
+}
+
+#[test]
+fn one_subscriber_hears_two_publishers() {
+    let ctx = zmq::Context::new();
+    let _left = HeadlessPublisher::start(&ctx, "inproc://left", TOPIC, 1);
+    let _right = HeadlessPublisher::start(&ctx, "inproc://right", TOPIC, 1_000_000);
+    let transport = ZmqTransport::connect(&ctx, "inproc://left").unwrap();
+    transport.connect_also("inproc://right").unwrap();
+    let subscriber = Subscriber::new(transport, TOPIC).unwrap();
+
+    wait_for(&subscriber, |state| state.order() < 1_000_000);
+    wait_for(&subscriber, |state| state.order() >= 1_000_000);
+}
+
+#[test]
+fn publishers_and_subscribers_meet_at_a_broker() {
+    let ctx = zmq::Context::new();
+    let broker = ZmqBroker::bind(&ctx, "inproc://broker_front", "inproc://broker_back").unwrap();
+    // Proxies until the process exits; its sockets keep the context alive.
+    std::thread::spawn(move || broker.run());
+    let _left = HeadlessPublisher::start(&ctx, ">inproc://broker_front", TOPIC, 1);
+    let _right = HeadlessPublisher::start(&ctx, ">inproc://broker_front", TOPIC, 1_000_000);
+    let subscriber = zmq_subscriber(&ctx, "inproc://broker_back", TOPIC);
+
+    let state = wait_for(&subscriber, |state| state.order() < 1_000_000);
+    assert_same_state(&state, &simulated_state(state.order()));
+    wait_for(&subscriber, |state| state.order() >= 1_000_000);
+}
+
+#[test]
+fn json_and_protobuf_frames_share_a_topic() {
+    let ctx = zmq::Context::new();
+    let publisher = ctx.socket(zmq::PUB).unwrap();
+    publisher.bind("inproc://formats").unwrap();
+    let subscriber = zmq_subscriber(&ctx, "inproc://formats", TOPIC);
+
+    // Odd sequence numbers as JSON, even ones as protobuf.
+    let mut received = Vec::new();
+    for sequence in (1..1_000).step_by(2) {
+        let json = Format::Json.encode(&simulated_state(sequence));
+        publisher
+            .send_multipart([TOPIC.as_bytes(), &json], 0)
+            .unwrap();
+        let protobuf = Format::Protobuf.encode(&simulated_state(sequence + 1));
+        publisher
+            .send_multipart([TOPIC.as_bytes(), &protobuf], 0)
+            .unwrap();
+        while let Some(state) = subscriber.recv(Duration::from_millis(5)).unwrap() {
+            received.push(state);
+        }
+        if received.len() >= 10 {
+            break;
+        }
+    }
+    assert!(received.len() >= 10, "subscription never became live");
+    for state in &received {
+        assert_same_state(state, &simulated_state(state.order()));
+    }
+    assert!(received.iter().any(|state| state.order() % 2 == 0));
+    assert!(received.iter().any(|state| state.order() % 2 == 1));
+}
+
+#[test]
+fn legacy_single_frames_are_read_only_when_accepted() {
+    let ctx = zmq::Context::new();
+    let publisher = ctx.socket(zmq::PUB).unwrap();
+    publisher.bind("inproc://legacy").unwrap();
+    let strict = zmq_subscriber(&ctx, "inproc://legacy", TOPIC);
+    let transport = ZmqTransport::connect(&ctx, "inproc://legacy")
+        .unwrap()
+        .accept_legacy_frames();
+    let lenient = Subscriber::new(transport, TOPIC).unwrap();
+
+    // What a publisher from before multipart frames sends.
+    let mut received = None;
+    for sequence in 1..500 {
+        publisher
+            .send(wire::encode(TOPIC, &simulated_state(sequence)).as_str(), 0)
+            .unwrap();
+        if let Some(state) = lenient.recv(Duration::from_millis(10)).unwrap() {
+            received = Some(state);
+            break;
+        }
+    }
+    let state = received.expect("subscription never became live");
+    assert_same_state(&state, &simulated_state(state.order()));
+
+    // Subscribed before `lenient`, so the same frames reached it: all rejected.
+    let err = strict.recv(PATIENCE).unwrap_err();
+    assert!(
+        matches!(err, TransportError::Wire(WireError::Frames(1))),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,85 @@
+//! Code written against the `Transport` trait, driven by `InprocTransport`
+//! and `ShmTransport`: no sockets, and no slow-joiner delays to wait out.
+
+use std::thread;
+use std::time::Duration;
+
+use integration_tests::{PATIENCE, assert_same_state, next_state};
+use project_robot_joint_pubsub::sim::simulated_state;
+use project_robot_joint_pubsub::transport::ring_path;
+use project_robot_joint_pubsub::{InprocTransport, Publisher, ShmTransport, Subscriber, Transport};
+
+const TOPIC: &str = "robot_joints";
+
+#[test]
+fn every_state_arrives_in_order_across_threads() {
+    let bus = InprocTransport::new();
+    // Subscribed before anything is sent, so nothing may be missing.
+    let subscriber = Subscriber::new(bus.connect(), TOPIC).unwrap();
+    let publisher = Publisher::new(bus, TOPIC);
+    let sender = thread::spawn(move || {
+        for sequence in 1..=200 {
+            publisher.publish(&simulated_state(sequence)).unwrap();
+        }
+    });
+
+    for sequence in 1..=200 {
+        assert_same_state(&next_state(&subscriber), &simulated_state(sequence));
+    }
+    sender.join().unwrap();
+}
+
+#[test]
+fn boxed_backends_are_interchangeable() {
+    let bus = InprocTransport::new();
+    // What the binaries hold after choosing a backend from the command line.
+    let boxed: Box<dyn Transport> = Box::new(bus.connect());
+    let subscriber: Subscriber = Subscriber::new(boxed, TOPIC).unwrap();
+    let boxed: Box<dyn Transport> = Box::new(bus);
+    let publisher: Publisher = Publisher::new(boxed, TOPIC);
+
+    publisher.publish(&simulated_state(7)).unwrap();
+    assert_eq!(next_state(&subscriber).order(), 7);
+    assert!(
+        subscriber
+            .recv(Duration::from_millis(10))
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn fans_out_to_every_subscriber() {
+    let bus = InprocTransport::new();
+    let subscribers: Vec<_> = (0..3)
+        .map(|_| Subscriber::new(bus.connect(), TOPIC).unwrap())
+        .collect();
+    let publisher = Publisher::new(bus, TOPIC);
+
+    publisher.publish(&simulated_state(1)).unwrap();
+    for subscriber in &subscribers {
+        let state = subscriber.recv(PATIENCE).unwrap().expect("a state");
+        assert_eq!(state.order(), 1);
+    }
+}
+
+#[test]
+fn shared_memory_carries_split_states_in_order() {
+    let ring = format!("{TOPIC}.{}", std::process::id());
+    // Opened before the ring exists, so it reads from the first message; 30
+    // states in parts fit the ring, so none can be skipped however late it reads.
+    let subscriber = Subscriber::new(ShmTransport::open(&ring).unwrap(), TOPIC).unwrap();
+    let publisher =
+        Publisher::new(ShmTransport::create(&ring).unwrap(), TOPIC).with_split_by_finger(true);
+    let sender = thread::spawn(move || {
+        for sequence in 1..=30 {
+            publisher.publish(&simulated_state(sequence)).unwrap();
+        }
+    });
+
+    for sequence in 1..=30 {
+        assert_same_state(&next_state(&subscriber), &simulated_state(sequence));
+    }
+    sender.join().unwrap();
+    std::fs::remove_file(ring_path(&ring)).unwrap();
+}
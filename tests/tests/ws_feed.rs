@@ -0,0 +1,81 @@
+//! The WebSocket feed served by `ws_relay`, with real clients on a loopback port.
+
+use futures::StreamExt;
+use integration_tests::{PATIENCE, assert_same_state};
+use project_robot_joint_pubsub::RobotState;
+use project_robot_joint_pubsub::sim::simulated_state;
+use project_robot_joint_pubsub::ws::Feed;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+type Client = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Serve `feed` on a free loopback port and connect `clients` to it.
+async fn serve(feed: &Feed, clients: usize) -> Vec<Client> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(feed.clone().serve(listener));
+    let mut connected = Vec::new();
+    for _ in 0..clients {
+        connected.push(connect_async(&url).await.unwrap().0);
+    }
+    // The server subscribes each client right after its handshake.
+    timeout(PATIENCE, async {
+        while feed.clients() < clients {
+            sleep(std::time::Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("clients registered");
+    connected
+}
+
+async fn next_state(client: &mut Client) -> RobotState {
+    let message = timeout(PATIENCE, client.next())
+        .await
+        .expect("a frame within the patience window")
+        .expect("stream open")
+        .expect("valid frame");
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn every_client_receives_every_state_as_json() {
+    let feed = Feed::new(16);
+    let mut clients = serve(&feed, 2).await;
+
+    for sequence in 1..=5 {
+        assert_eq!(feed.send(&simulated_state(sequence)), 2);
+    }
+    for client in &mut clients {
+        for sequence in 1..=5 {
+            assert_same_state(&next_state(client).await, &simulated_state(sequence));
+        }
+    }
+}
+
+#[tokio::test]
+async fn a_client_that_falls_behind_is_disconnected() {
+    let feed = Feed::new(2);
+    let mut clients = serve(&feed, 1).await;
+
+    // On this single-threaded runtime the client task cannot run until we
+    // yield, so these overflow its queue; sending must not wait for it.
+    for sequence in 1..=10 {
+        feed.send(&simulated_state(sequence));
+    }
+    let client = &mut clients[0];
+    let closed = timeout(PATIENCE, async {
+        loop {
+            match client.next().await {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    })
+    .await;
+    assert!(closed.is_ok(), "slow client was not dropped");
+    assert_eq!(feed.clients(), 0);
+}
@@ -0,0 +1,116 @@
+//! A single launcher for the repo's projects.
+//!
+//! Each subcommand reuses the `clap::Args` the project itself exposes
+//! ([`project_notes::NotesArgs`], [`project_manufacturers::ManufacturersArgs`],
+//! [`xtask::Command`]), so `til notes --help` and `project_notes --help` can
+//! never disagree, and then calls the same library entry point the project's
+//! own binary does. A failure exits with the same code ([`exit_for`]) the
+//! project's binary would use.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use common::{LogOutput, init_logging};
+use errors::{Exit, ExitStatus};
+use project_manufacturers::{ManufacturersArgs, ManufacturersError};
+use project_notes::NotesArgs;
+
+/// `til` command line.
+#[derive(Debug, Parser)]
+#[command(name = "til", version, about = "Launcher for the rust-til projects")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// One project to run.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Inventory TUI (project_inventory_system).
+    Inventory,
+    /// Append a timestamp marker to a notes file (project_notes).
+    Notes(NotesArgs),
+    /// Look up NHTSA manufacturers by keyword (project_manufacturers).
+    Manufacturers(ManufacturersArgs),
+    /// Robot joint pub/sub tools: demos, bag recording, schema generation (xtask).
+    Pubsub {
+        #[command(subcommand)]
+        command: xtask::Command,
+    },
+}
+
+/// Run the chosen project.
+pub async fn run(cli: Cli) -> Result<()> {
+    // The inventory TUI owns the terminal and sends its logs to a file itself;
+    // everything else logs to stderr.
+    if !matches!(cli.command, Command::Inventory) {
+        init_logging("info", LogOutput::Stderr)?;
+    }
+    match cli.command {
+        Command::Inventory => project_inventory_system::run()?,
+        Command::Notes(args) => project_notes::run(&args)?,
+        Command::Manufacturers(args) => project_manufacturers::run(&args.keyword).await?,
+        Command::Pubsub { command } => xtask::run(xtask::Cli { command }).await?,
+    }
+    Ok(())
+}
+
+/// The repo-wide exit code for a failure from any subcommand.
+pub fn exit_for(err: &anyhow::Error) -> Exit {
+    errors::exit_for_with(err.as_ref(), |e| {
+        e.downcast_ref::<ManufacturersError>()
+            .map(ExitStatus::exit)
+            .or_else(|| xtask::classify(e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(std::iter::once("til").chain(args.iter().copied()))
+            .unwrap()
+            .command
+    }
+
+    #[test]
+    fn definitions_are_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn subcommands_reuse_project_arguments() {
+        match parse(&["notes", "today.txt"]) {
+            Command::Notes(args) => assert_eq!(args.filename.unwrap().to_str(), Some("today.txt")),
+            other => panic!("parsed {other:?}"),
+        }
+        assert!(matches!(
+            parse(&["notes"]),
+            Command::Notes(NotesArgs { filename: None })
+        ));
+        match parse(&["manufacturers", "BMW"]) {
+            Command::Manufacturers(args) => assert_eq!(args.keyword, "BMW"),
+            other => panic!("parsed {other:?}"),
+        }
+        assert!(matches!(
+            parse(&["pubsub", "gen-proto", "--check"]),
+            Command::Pubsub {
+                command: xtask::Command::GenProto(_)
+            }
+        ));
+        assert!(Cli::try_parse_from(["til", "manufacturers"]).is_err());
+        assert!(Cli::try_parse_from(["til", "guess"]).is_err());
+    }
+
+    #[test]
+    fn failures_keep_the_projects_exit_codes() {
+        let shape = anyhow::Error::from(ManufacturersError::Shape("missing `Results` array"));
+        assert_eq!(exit_for(&shape), Exit::Data);
+        let notes = anyhow::Error::from(errors::AppError::from(errors::ValidationError::Missing {
+            field: "filename",
+        }));
+        assert_eq!(exit_for(&notes), Exit::Usage);
+        assert_eq!(exit_for(&anyhow::anyhow!("child failed")), Exit::Failure);
+    }
+}
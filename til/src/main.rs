@@ -0,0 +1,19 @@
+//! `til` entry point: parse the command line and hand off to [`til::run`].
+//!
+//! Failures print the error with its causes and exit with [`til::exit_for`]'s code.
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use til::Cli;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match til::run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            til::exit_for(&e).into()
+        }
+    }
+}
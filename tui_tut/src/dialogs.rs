@@ -0,0 +1,147 @@
+//! Config-driven dialogs: a flow of Cursive dialogs described in a TOML file.
+//!
+//! Each `[[dialog]]` table has an `id`, a `title`, body `text`, and a list of
+//! `[[dialog.button]]` entries. A button's `action` decides what happens when
+//! it is pressed:
+//!
+//! - `goto` — open the dialog named by `target` on top of the current one
+//! - `back` — close the current dialog and return to the previous one
+//! - `message` — pop up an info box with `text` on top of the current dialog
+//! - `quit` — exit the app
+//!
+//! ```toml
+//! start = "welcome"
+//!
+//! [[dialog]]
+//! id = "welcome"
+//! title = "Welcome"
+//! text = "Ready to focus?"
+//!
+//! [[dialog.button]]
+//! label = "Yes"
+//! action = "goto"
+//! target = "done"
+//! ```
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use cursive::Cursive;
+use cursive::views::Dialog;
+use serde::Deserialize;
+
+/// The whole dialog flow: where to start and every dialog that can be shown.
+#[derive(Debug, Deserialize)]
+pub struct DialogFlow {
+    pub start: String,
+    #[serde(rename = "dialog")]
+    pub dialogs: Vec<DialogDef>,
+}
+
+/// One dialog box: a title, body text, and its buttons.
+#[derive(Debug, Deserialize)]
+pub struct DialogDef {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(rename = "button", default)]
+    pub buttons: Vec<ButtonDef>,
+}
+
+/// A button label plus the follow-up action it triggers.
+#[derive(Debug, Deserialize)]
+pub struct ButtonDef {
+    pub label: String,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// What a button does when pressed.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    Goto { target: String },
+    Back,
+    Message { text: String },
+    Quit,
+}
+
+impl DialogFlow {
+    /// Read and validate a dialog flow from a TOML file.
+    pub fn load(path: &Path) -> Result<DialogFlow> {
+        let raw =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let flow: DialogFlow =
+            toml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?;
+        flow.validate()?;
+        Ok(flow)
+    }
+
+    /// Check that ids are unique and that `start` and every `goto` target exist.
+    fn validate(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        for dialog in &self.dialogs {
+            if !ids.insert(dialog.id.as_str()) {
+                bail!("duplicate dialog id `{}`", dialog.id);
+            }
+        }
+        if !ids.contains(self.start.as_str()) {
+            bail!("start dialog `{}` is not defined", self.start);
+        }
+        for dialog in &self.dialogs {
+            for button in &dialog.buttons {
+                if let Action::Goto { target } = &button.action
+                    && !ids.contains(target.as_str())
+                {
+                    bail!(
+                        "button `{}` in dialog `{}` points to unknown dialog `{}`",
+                        button.label,
+                        dialog.id,
+                        target
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn find(&self, id: &str) -> Option<&DialogDef> {
+        self.dialogs.iter().find(|dialog| dialog.id == id)
+    }
+}
+
+/// Push the flow's start dialog onto `siv`.
+pub fn show_start(siv: &mut Cursive, flow: Arc<DialogFlow>) {
+    let start = flow.start.clone();
+    show(siv, flow, &start);
+}
+
+// Build the dialog named `id` and push it as a new layer.
+fn show(siv: &mut Cursive, flow: Arc<DialogFlow>, id: &str) {
+    let Some(def) = flow.find(id) else {
+        siv.add_layer(Dialog::info(format!("Unknown dialog: {id}")));
+        return;
+    };
+
+    let mut dialog = Dialog::text(def.text.clone()).title(def.title.clone());
+    for button in &def.buttons {
+        let flow = Arc::clone(&flow);
+        let action = button.action.clone();
+        dialog.add_button(button.label.clone(), move |s| match &action {
+            Action::Goto { target } => show(s, Arc::clone(&flow), target),
+            Action::Back => {
+                // Keep the last dialog on screen; popping it would leave an empty TUI.
+                if s.screen().len() > 1 {
+                    s.pop_layer();
+                }
+            }
+            Action::Message { text } => s.add_layer(Dialog::info(text.clone())),
+            Action::Quit => s.quit(),
+        });
+    }
+    siv.add_layer(dialog);
+}
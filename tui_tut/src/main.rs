@@ -3,13 +3,44 @@ use cursive::view::Nameable;
 use cursive::views::{Dialog, TextView};
 use cursive::{Cursive, CursiveExt};
 
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-fn main() {
+mod dialogs;
+
+use dialogs::DialogFlow;
+use errors::Exit;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            // An unreadable dialogs file is an I/O error, a malformed one a parse error.
+            errors::exit_for_with(e.as_ref(), |e| {
+                e.is::<toml::de::Error>().then_some(Exit::Data)
+            })
+            .into()
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
     // Initialize cursive with crossterm backend for better terminal compatibility
     let mut siv = Cursive::default();
+
+    // `cargo run -- dialogs.toml` renders the dialog flow from that file instead of the timer.
+    if let Some(path) = env::args().nth(1) {
+        let flow = DialogFlow::load(&PathBuf::from(path))?;
+        dialogs::show_start(&mut siv, Arc::new(flow));
+        siv.run();
+        return Ok(());
+    }
+
     // Variable declarations
     // 25 min pomodoro duration
     let pomodoro_duration = Arc::new(Mutex::new(25 * 60));
@@ -125,18 +156,21 @@ fn main() {
             thread::sleep(Duration::from_secs(1));
 
             let timer_counter_refresh = Arc::clone(&timer_counter_for_refresh);
-            cb_sink
-                .send(Box::new(move |s| {
-                    let time_left = *timer_counter_refresh.lock().unwrap();
-                    s.call_on_name("timer", |view: &mut TextView| {
-                        view.set_content(format!("Time: {}", format_time(time_left)));
-                    });
-                }))
-                .unwrap();
+            let sent = cb_sink.send(Box::new(move |s| {
+                let time_left = *timer_counter_refresh.lock().unwrap();
+                s.call_on_name("timer", |view: &mut TextView| {
+                    view.set_content(format!("Time: {}", format_time(time_left)));
+                });
+            }));
+            // The UI has quit and dropped its end of the channel: stop refreshing.
+            if sent.is_err() {
+                break;
+            }
         }
     });
 
     siv.run();
+    Ok(())
 }
 
 // Helper function to format time in MM:SS format for easier display.
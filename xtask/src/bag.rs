@@ -0,0 +1,221 @@
+//! `record-bag`: capture pub/sub traffic to a JSON-lines file.
+//!
+//! Each line is one message: `{"t_ms": 12, "topic": "robot_joints", "msg": {...}}`,
+//! where `t_ms` counts from the start of the recording and `msg` is the decoded
+//! JSON payload (or the raw text if it is not JSON). Messages are a topic frame
+//! and a payload frame; single `"<topic> <payload>"` frames from older
+//! publishers are recorded too. Binary payloads (`--format msgpack` or
+//! `protobuf`) are not text and are skipped.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde_json::{Value, json};
+use tokio::process::Command;
+
+use crate::demo::{PUBSUB_PACKAGE, Supervisor, bin_path, cargo_build};
+use crate::{parse_seconds, target_dir, workspace_root};
+
+// Receive timeout, i.e. how quickly the recorder notices it should stop.
+const RECV_TIMEOUT_MS: i32 = 100;
+
+/// Arguments for `cargo xtask record-bag`.
+#[derive(Debug, Args)]
+pub struct RecordBagArgs {
+    /// ZMQ address to subscribe to.
+    #[arg(long, default_value = "tcp://localhost:5555")]
+    pub connect: String,
+    /// Topic to record.
+    #[arg(long, default_value = "robot_joints")]
+    pub topic: String,
+    /// Output file (default: `target/bags/<topic>-<unix seconds>.jsonl`).
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Stop after this many messages.
+    #[arg(long)]
+    pub count: Option<u64>,
+    /// Stop after this many seconds.
+    #[arg(long, value_parser = parse_seconds)]
+    pub duration: Option<Duration>,
+    /// Also build and run the headless publisher for the length of the recording.
+    #[arg(long)]
+    pub spawn_publisher: bool,
+}
+
+/// Record until `--count`, `--duration`, Ctrl-C, or the spawned publisher exits.
+pub async fn record_bag(args: RecordBagArgs) -> Result<()> {
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output(&args.topic));
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    let file = File::create(&output).with_context(|| format!("creating {}", output.display()))?;
+
+    let mut supervisor = Supervisor::new();
+    if args.spawn_publisher {
+        // The headless publisher only needs the default features (no MuJoCo).
+        cargo_build(PUBSUB_PACKAGE, &["publisher"], &[], false).await?;
+        let mut command = Command::new(bin_path("debug", "publisher"));
+        command.current_dir(workspace_root().join(PUBSUB_PACKAGE));
+        supervisor.spawn("publisher", command)?;
+    }
+
+    let ctx = zmq::Context::new();
+    let socket = ctx.socket(zmq::SUB)?;
+    socket.connect(&args.connect)?;
+    socket.set_subscribe(args.topic.as_bytes())?;
+    socket.set_rcvtimeo(RECV_TIMEOUT_MS)?;
+    tracing::info!(connect = %args.connect, topic = %args.topic, output = %output.display(), "recording");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let recorder = tokio::task::spawn_blocking({
+        let stop = Arc::clone(&stop);
+        let topic = args.topic.clone();
+        let count = args.count;
+        move || record(&socket, &topic, BufWriter::new(file), &stop, count)
+    });
+    tokio::pin!(recorder);
+
+    let recorded = tokio::select! {
+        result = &mut recorder => result,
+        stopped = supervisor.wait(args.duration) => {
+            stopped?;
+            stop.store(true, Ordering::Relaxed);
+            (&mut recorder).await
+        }
+    };
+    supervisor.shutdown().await?;
+    let recorded = recorded.context("recorder thread panicked")??;
+    tracing::info!(messages = recorded, output = %output.display(), "bag written");
+    Ok(())
+}
+
+// Blocking receive loop; returns the number of messages written.
+fn record(
+    socket: &zmq::Socket,
+    topic: &str,
+    mut out: impl Write,
+    stop: &AtomicBool,
+    count: Option<u64>,
+) -> Result<u64> {
+    let started = Instant::now();
+    let mut written = 0;
+    while !stop.load(Ordering::Relaxed) && count.is_none_or(|count| written < count) {
+        let frames = match socket.recv_multipart(0) {
+            Ok(frames) => frames,
+            Err(zmq::Error::EAGAIN) => continue,
+            Err(err) => return Err(err).context("receiving"),
+        };
+        let Ok(frames) = frames
+            .into_iter()
+            .map(String::from_utf8)
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            tracing::warn!("skipping non-UTF-8 message");
+            continue;
+        };
+        let (msg_topic, payload) = match frames.as_slice() {
+            [topic, payload] => (topic.as_str(), payload.as_str()),
+            [message] => match message.split_once(' ') {
+                Some(split) => split,
+                None => continue,
+            },
+            _ => continue,
+        };
+        // Subscriptions match by prefix, so `robot` would also see `robot_joints`.
+        if msg_topic != topic {
+            continue;
+        }
+        writeln!(out, "{}", bag_line(started.elapsed(), msg_topic, payload))?;
+        written += 1;
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+/// One bag record for a message received `elapsed` after recording started.
+pub fn bag_line(elapsed: Duration, topic: &str, payload: &str) -> String {
+    let msg = serde_json::from_str(payload).unwrap_or_else(|_| Value::String(payload.to_string()));
+    json!({ "t_ms": elapsed.as_millis() as u64, "topic": topic, "msg": msg }).to_string()
+}
+
+fn default_output(topic: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    bags_dir().join(format!("{topic}-{stamp}.jsonl"))
+}
+
+fn bags_dir() -> PathBuf {
+    target_dir().join("bags")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bag_lines_embed_json_payloads() {
+        let line = bag_line(
+            Duration::from_millis(42),
+            "robot_joints",
+            r#"{"timestamp":7}"#,
+        );
+        assert_eq!(
+            line,
+            r#"{"msg":{"timestamp":7},"t_ms":42,"topic":"robot_joints"}"#
+        );
+        let raw = bag_line(Duration::ZERO, "t", "not json");
+        assert!(raw.contains(r#""msg":"not json""#), "{raw}");
+    }
+
+    #[test]
+    fn records_only_the_exact_topic_over_inproc() {
+        let ctx = zmq::Context::new();
+        let publisher = ctx.socket(zmq::PUB).unwrap();
+        publisher.bind("inproc://bag-test").unwrap();
+        let subscriber = ctx.socket(zmq::SUB).unwrap();
+        subscriber.connect("inproc://bag-test").unwrap();
+        subscriber.set_subscribe(b"robot").unwrap();
+        subscriber.set_rcvtimeo(RECV_TIMEOUT_MS).unwrap();
+        // Let the subscription reach the publisher before sending.
+        std::thread::sleep(Duration::from_millis(50));
+        publisher
+            .send_multipart(["robot_arm", "{\"timestamp\":1}"], 0)
+            .unwrap();
+        publisher
+            .send_multipart(["robot", "{\"timestamp\":2}"], 0)
+            .unwrap();
+        // A single-frame message from an older publisher.
+        publisher.send("robot {\"timestamp\":3}", 0).unwrap();
+
+        let mut out = Vec::new();
+        let stop = AtomicBool::new(false);
+        let written = record(&subscriber, "robot", &mut out, &stop, Some(2)).unwrap();
+        assert_eq!(written, 2);
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["msg"]["timestamp"], 2);
+        assert_eq!(lines[1]["msg"]["timestamp"], 3);
+    }
+
+    #[test]
+    fn default_output_lands_in_the_bags_dir() {
+        let path = default_output("robot_joints");
+        assert!(path.starts_with(bags_dir()));
+        assert_eq!(path.extension().and_then(|ext| ext.to_str()), Some("jsonl"));
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("robot_joints-"), "{name}");
+    }
+}
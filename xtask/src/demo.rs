@@ -0,0 +1,248 @@
+//! `run-demo`: build a demo's binaries once, then run them side by side.
+//!
+//! The binaries are started directly from the target dir (not through
+//! `cargo run`), so the [`Supervisor`] holds the real processes and teardown
+//! cannot leave an orphaned viewer behind.
+
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use clap::{Args, ValueEnum};
+use tokio::process::{Child, Command};
+use tokio::time::{Instant, sleep};
+
+use crate::{parse_seconds, target_dir, workspace_root};
+
+/// Package holding the robot joint pub/sub binaries.
+pub const PUBSUB_PACKAGE: &str = "project_robot_joint_pubsub";
+
+// How often the supervisor checks whether a child has exited.
+const POLL: Duration = Duration::from_millis(100);
+
+/// Demos `run-demo` knows how to launch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Demo {
+    /// Joint-angle publisher plus the MuJoCo subscriber/viewer.
+    Pubsub,
+}
+
+/// Arguments for `cargo xtask run-demo`.
+#[derive(Debug, Args)]
+pub struct RunDemoArgs {
+    /// Which demo to launch.
+    pub demo: Demo,
+    /// Drive the demo from the egui slider publisher instead of the headless one.
+    #[arg(long)]
+    pub gui: bool,
+    /// Build and run the release profile.
+    #[arg(long)]
+    pub release: bool,
+    /// Stop everything after this many seconds (handy for smoke runs).
+    #[arg(long, value_parser = parse_seconds)]
+    pub duration: Option<Duration>,
+}
+
+/// Build the demo, start its processes, and stop them all together.
+pub async fn run_demo(args: RunDemoArgs) -> Result<()> {
+    let Demo::Pubsub = args.demo;
+    require_mujoco()?;
+    let publisher = if args.gui {
+        "publisher_joint_slider_gui_eframe"
+    } else {
+        "publisher"
+    };
+    let bins = [publisher, "subscriber"];
+    let features: &[&str] = if args.gui { &["gui"] } else { &["viewer"] };
+    cargo_build(PUBSUB_PACKAGE, &bins, features, args.release).await?;
+
+    let profile = if args.release { "release" } else { "debug" };
+    let project_dir = workspace_root().join(PUBSUB_PACKAGE);
+    let mut supervisor = Supervisor::new();
+    for bin in bins {
+        // Model paths in the pub/sub config are relative to the project folder.
+        let mut command = Command::new(bin_path(profile, bin));
+        command.current_dir(&project_dir);
+        supervisor.spawn(bin, command)?;
+    }
+
+    let stop = supervisor.wait(args.duration).await?;
+    supervisor.shutdown().await?;
+    match stop {
+        Stop::Exited { name, status } if !status.success() => {
+            bail!("{name} exited with {status}")
+        }
+        Stop::Exited { name, .. } => tracing::info!(%name, "demo finished"),
+        Stop::Interrupted => tracing::info!("interrupted; demo stopped"),
+        Stop::TimedOut => tracing::info!("duration elapsed; demo stopped"),
+    }
+    Ok(())
+}
+
+/// Fail early, with the fix, when the pub/sub viewer binaries cannot link MuJoCo.
+pub fn require_mujoco() -> Result<()> {
+    if std::env::var_os("MUJOCO_STATIC_LINK_DIR").is_none() {
+        bail!(
+            "MUJOCO_STATIC_LINK_DIR is not set; build MuJoCo with \
+             `just project_robot_joint_pubsub mujoco-build` and export the path it prints"
+        );
+    }
+    Ok(())
+}
+
+/// `cargo build -p <package> --bin <bin>... --features <f>,...`, streaming Cargo's own output.
+pub async fn cargo_build(
+    package: &str,
+    bins: &[&str],
+    features: &[&str],
+    release: bool,
+) -> Result<()> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command
+        .current_dir(workspace_root())
+        .args(["build", "--package", package]);
+    for bin in bins {
+        command.args(["--bin", bin]);
+    }
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+    if release {
+        command.arg("--release");
+    }
+    tracing::info!(package, ?bins, ?features, release, "building");
+    let status = command.status().await.context("running cargo build")?;
+    if !status.success() {
+        bail!("cargo build for {package} failed with {status}");
+    }
+    Ok(())
+}
+
+/// Path of a built binary for `profile` (`"debug"` or `"release"`).
+pub fn bin_path(profile: &str, bin: &str) -> PathBuf {
+    target_dir()
+        .join(profile)
+        .join(format!("{bin}{}", std::env::consts::EXE_SUFFIX))
+}
+
+/// Why [`Supervisor::wait`] returned.
+#[derive(Debug)]
+pub enum Stop {
+    /// A child exited on its own.
+    Exited { name: String, status: ExitStatus },
+    /// Ctrl-C was pressed.
+    Interrupted,
+    /// The time limit passed.
+    TimedOut,
+}
+
+/// A set of named child processes that live and die together.
+///
+/// Children are spawned with `kill_on_drop`, so even an early `?` return
+/// cannot leak them.
+#[derive(Debug, Default)]
+pub struct Supervisor {
+    children: Vec<(String, Child)>,
+}
+
+impl Supervisor {
+    pub fn new() -> Supervisor {
+        Supervisor::default()
+    }
+
+    /// Start `command` (stdin detached, output inherited) under `name`.
+    pub fn spawn(&mut self, name: &str, mut command: Command) -> Result<()> {
+        command.stdin(Stdio::null()).kill_on_drop(true);
+        let child = command
+            .spawn()
+            .with_context(|| format!("starting {name}"))?;
+        tracing::info!(name, pid = child.id(), "started");
+        self.children.push((name.to_string(), child));
+        Ok(())
+    }
+
+    /// Block until any child exits, Ctrl-C arrives, or `limit` passes.
+    ///
+    /// With no children this is simply "Ctrl-C or time limit".
+    pub async fn wait(&mut self, limit: Option<Duration>) -> Result<Stop> {
+        let deadline = limit.map(|limit| Instant::now() + limit);
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+        loop {
+            tokio::select! {
+                result = &mut ctrl_c => {
+                    result.context("listening for Ctrl-C")?;
+                    return Ok(Stop::Interrupted);
+                }
+                () = sleep(POLL) => {
+                    for (name, child) in &mut self.children {
+                        if let Some(status) = child.try_wait()? {
+                            tracing::info!(name = %name, %status, "exited");
+                            return Ok(Stop::Exited { name: name.clone(), status });
+                        }
+                    }
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        return Ok(Stop::TimedOut);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Kill every child that is still running and reap them all.
+    pub async fn shutdown(mut self) -> Result<()> {
+        for (name, child) in &mut self.children {
+            if child.try_wait()?.is_none() {
+                child
+                    .start_kill()
+                    .with_context(|| format!("stopping {name}"))?;
+                child.wait().await?;
+                tracing::info!(name = %name, "stopped");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    #[tokio::test]
+    async fn first_exit_stops_the_rest() {
+        let mut supervisor = Supervisor::new();
+        supervisor.spawn("long", sh("sleep 30")).unwrap();
+        supervisor.spawn("short", sh("exit 3")).unwrap();
+
+        let started = std::time::Instant::now();
+        match supervisor.wait(None).await.unwrap() {
+            Stop::Exited { name, status } => {
+                assert_eq!(name, "short");
+                assert_eq!(status.code(), Some(3));
+            }
+            other => panic!("unexpected stop: {other:?}"),
+        }
+        supervisor.shutdown().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn time_limit_ends_the_wait() {
+        let mut supervisor = Supervisor::new();
+        supervisor.spawn("long", sh("sleep 30")).unwrap();
+        let stop = supervisor
+            .wait(Some(Duration::from_millis(200)))
+            .await
+            .unwrap();
+        assert!(matches!(stop, Stop::TimedOut));
+        supervisor.shutdown().await.unwrap();
+    }
+}
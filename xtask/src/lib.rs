@@ -0,0 +1,120 @@
+//! Repo automation, written in Rust instead of shell so it behaves the same
+//! everywhere and tears down what it starts.
+//!
+//! Invoked as `cargo xtask <command>` (the alias lives in `.cargo/config.toml`).
+//!
+//! # How this crate is organized
+//!
+//! - [`demo`] — `run-demo`: build and launch a multi-process demo under a
+//!   [`demo::Supervisor`], stopping every process when one exits, on Ctrl-C, or
+//!   after `--duration`.
+//! - [`bag`] — `record-bag`: subscribe to the pub/sub topic and write every
+//!   message to a JSON-lines "bag" file for later replay or inspection.
+//! - [`proto`] — `gen-proto`: render the `.proto` schema for the pub/sub wire
+//!   types, or check that the committed copy is current.
+//!
+//! Tasks fail with `anyhow` errors; [`exit_for`] turns one into the repo-wide
+//! exit code (`errors::exit`).
+
+pub mod bag;
+pub mod demo;
+pub mod proto;
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use errors::Exit;
+
+/// `cargo xtask` command line.
+#[derive(Debug, Parser)]
+#[command(name = "xtask", about = "Repo automation for rust-til")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// One automation task.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Build and launch a multi-process demo; Ctrl-C stops every process.
+    RunDemo(demo::RunDemoArgs),
+    /// Record pub/sub messages to a JSON-lines bag file.
+    RecordBag(bag::RecordBagArgs),
+    /// Regenerate the pub/sub `.proto` schema (or verify it with `--check`).
+    GenProto(proto::GenProtoArgs),
+}
+
+/// Run one parsed command to completion.
+pub async fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::RunDemo(args) => demo::run_demo(args).await,
+        Command::RecordBag(args) => bag::record_bag(args).await,
+        Command::GenProto(args) => proto::gen_proto(args),
+    }
+}
+
+/// The exit code for a failed task: socket errors are [`Exit::Network`], I/O
+/// errors [`Exit::Io`]; anything else (a failed build, a stale schema) is
+/// [`Exit::Failure`].
+pub fn exit_for(err: &anyhow::Error) -> Exit {
+    errors::exit_for_with(err.as_ref(), classify)
+}
+
+/// The [`Exit`] for error types that only xtask's tasks produce, for launchers
+/// that wrap them (`errors::exit_for_with(err, xtask::classify)`).
+pub fn classify(err: &(dyn Error + 'static)) -> Option<Exit> {
+    err.is::<zmq::Error>().then_some(Exit::Network)
+}
+
+/// The repository root (the directory holding the workspace `Cargo.toml`).
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives one level below the workspace root")
+}
+
+/// Where Cargo puts build output: `$CARGO_TARGET_DIR`, else `<root>/target`.
+pub fn target_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("target"))
+}
+
+/// Parse a `--duration` value given in whole or fractional seconds.
+pub fn parse_seconds(raw: &str) -> Result<Duration, String> {
+    let secs: f64 = raw
+        .parse()
+        .map_err(|_| format!("expected seconds, got {raw:?}"))?;
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| format!("expected non-negative seconds, got {raw:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_and_io_failures_get_their_own_exit_codes() {
+        let socket = anyhow::Error::from(zmq::Error::EADDRINUSE).context("binding publisher");
+        assert_eq!(exit_for(&socket), Exit::Network);
+        let io = anyhow::Error::from(std::io::Error::other("disk full")).context("writing bag");
+        assert_eq!(exit_for(&io), Exit::Io);
+        assert_eq!(exit_for(&anyhow::anyhow!("stale schema")), Exit::Failure);
+    }
+
+    #[test]
+    fn workspace_root_holds_the_workspace_manifest() {
+        let manifest = std::fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("[workspace]"));
+    }
+
+    #[test]
+    fn seconds_accept_fractions_and_reject_junk() {
+        assert_eq!(parse_seconds("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_seconds("-1").is_err());
+        assert!(parse_seconds("soon").is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! `cargo xtask` entry point: parse the command line and hand off to the library.
+//!
+//! Failures print the error with its causes and exit with [`xtask::exit_for`]'s code.
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use common::{LogOutput, init_logging};
+use xtask::Cli;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match init_logging("info", LogOutput::Stderr) {
+        Ok(()) => xtask::run(cli).await,
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            xtask::exit_for(&e).into()
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! `gen-proto`: a protobuf schema for the pub/sub wire types.
+//!
+//! The pub/sub binaries send JSON or protobuf payloads, and non-Rust consumers
+//! (Python tooling, loggers, bridges) want a schema for either. [`MESSAGES`]
+//! mirrors `JointAngles`, `RobotState`, `RobotStateBatch`, `BodyPose`, and `BodyPoses` from
+//! `project_robot_joint_pubsub/src/lib.rs` and the `prost` field tags in its
+//! `src/proto.rs`; unit tests fail when any of them drift apart, and when the
+//! committed `.proto` is stale. [`SERVICE`] and [`SERVICE_MESSAGES`] describe the
+//! gRPC service `grpc_bridge` serves, whose messages only exist as `prost` types.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+
+use crate::workspace_root;
+
+/// Committed schema, relative to the workspace root.
+pub const PROTO_PATH: &str = "project_robot_joint_pubsub/proto/robot_joint_pubsub.proto";
+
+/// `(proto type, field name, doc)` of one message field.
+pub type Field = (&'static str, &'static str, &'static str);
+
+/// One `message` block.
+#[derive(Debug)]
+pub struct Message {
+    pub name: &'static str,
+    pub doc: &'static str,
+    /// Numbered from 1 in order.
+    pub fields: &'static [Field],
+    /// `(field number, doc)`, ascending, never to be used; numbering skips them.
+    pub reserved: &'static [(usize, &'static str)],
+}
+
+impl Message {
+    /// Each field with its number.
+    pub fn numbered(&self) -> Vec<(usize, &Field)> {
+        let mut number = 0;
+        self.fields
+            .iter()
+            .map(|field| {
+                number += 1;
+                while self
+                    .reserved
+                    .iter()
+                    .any(|&(reserved, _)| reserved == number)
+                {
+                    number += 1;
+                }
+                (number, field)
+            })
+            .collect()
+    }
+}
+
+/// The wire types, in the order they appear in the schema.
+pub const MESSAGES: &[Message] = &[
+    Message {
+        name: "JointAngles",
+        doc: "Joint angle data sampled from a single robot joint at a point in time.",
+        fields: &[
+            (
+                "uint64",
+                "timestamp",
+                "When sampled, in nanoseconds since the Unix epoch; older publishers sent a sequence number here.",
+            ),
+            (
+                "string",
+                "joint_name",
+                "Human-readable joint identifier (e.g. \"shoulder_pan\").",
+            ),
+            ("double", "angle_rad", "Joint angle in radians."),
+            ("double", "velocity", "Joint angular velocity in rad/s."),
+            ("double", "torque", "Joint torque in N·m."),
+            (
+                "repeated double",
+                "qpos",
+                "Every position coordinate of a ball ([w, x, y, z]) or free ([x, y, z, w, x, y, z]) joint; empty for hinge and slide joints.",
+            ),
+        ],
+        reserved: &[],
+    },
+    Message {
+        name: "RobotState",
+        doc: "Snapshot of every joint on a single robot at a point in time.",
+        fields: &[
+            (
+                "uint64",
+                "timestamp",
+                "When sampled, in nanoseconds since the Unix epoch; older publishers sent a sequence number here.",
+            ),
+            (
+                "string",
+                "robot_id",
+                "Stable identifier for the publishing robot.",
+            ),
+            (
+                "repeated JointAngles",
+                "joints",
+                "One entry per joint, in publisher-defined order.",
+            ),
+            (
+                "optional uint64",
+                "sent_at_us",
+                "Wall-clock send time in microseconds since the Unix epoch; unset from older publishers.",
+            ),
+            (
+                "optional uint64",
+                "sequence",
+                "Publisher-side sequence number, counting up from 1; unset from older publishers.",
+            ),
+        ],
+        reserved: &[(
+            5,
+            "As a varint, field 5 is keyed 0x28, the first byte of a zstd frame; receivers tell compressed payloads apart by it.",
+        )],
+    },
+    Message {
+        name: "RobotStateBatch",
+        doc: "Several states from one publisher, sent as one payload on <topic>/batch.",
+        fields: &[(
+            "repeated RobotState",
+            "states",
+            "The batched states, oldest first.",
+        )],
+        reserved: &[],
+    },
+    Message {
+        name: "BodyPose",
+        doc: "Where one site or body of a robot is, in world coordinates.",
+        fields: &[
+            (
+                "string",
+                "name",
+                "MuJoCo site or body name (e.g. \"index_tip\").",
+            ),
+            (
+                "repeated double",
+                "position",
+                "Position [x, y, z] in meters.",
+            ),
+            (
+                "repeated double",
+                "orientation",
+                "Orientation as a unit quaternion [w, x, y, z], MuJoCo's order.",
+            ),
+        ],
+        reserved: &[],
+    },
+    Message {
+        name: "BodyPoses",
+        doc: "Cartesian poses computed from one RobotState by forward kinematics.",
+        fields: &[
+            (
+                "uint64",
+                "timestamp",
+                "The timestamp of the state they were computed from.",
+            ),
+            (
+                "string",
+                "robot_id",
+                "The robot_id of the state they were computed from.",
+            ),
+            (
+                "repeated BodyPose",
+                "bodies",
+                "One entry per tracked site or body.",
+            ),
+            (
+                "optional uint64",
+                "sent_at_us",
+                "Wall-clock send time in microseconds since the Unix epoch.",
+            ),
+        ],
+        reserved: &[],
+    },
+];
+
+/// The gRPC service's own requests and replies, after [`MESSAGES`].
+pub const SERVICE_MESSAGES: &[Message] = &[
+    Message {
+        name: "StreamStatesRequest",
+        doc: "Which states a StreamStates call receives.",
+        fields: &[(
+            "string",
+            "robot_id",
+            "Only states from this robot; empty for every robot.",
+        )],
+        reserved: &[],
+    },
+    Message {
+        name: "JointCommand",
+        doc: "Target angles for some joints of one robot, republished as a RobotState.",
+        fields: &[
+            (
+                "string",
+                "robot_id",
+                "The robot the targets are for; empty for the bridge's --robot-id.",
+            ),
+            (
+                "map<string, double>",
+                "angles_rad",
+                "Target angle in radians by joint name; joints left out keep their angle.",
+            ),
+        ],
+        reserved: &[],
+    },
+    Message {
+        name: "CommandSummary",
+        doc: "The reply once a client closes its SendCommands stream.",
+        fields: &[(
+            "uint64",
+            "accepted",
+            "Commands republished from the stream.",
+        )],
+        reserved: &[],
+    },
+];
+
+/// One `service` block.
+#[derive(Debug)]
+pub struct Service {
+    pub name: &'static str,
+    pub doc: &'static str,
+    /// `(rpc name, request, response, doc)`; `stream ` marks a streamed side.
+    pub rpcs: &'static [(&'static str, &'static str, &'static str, &'static str)],
+}
+
+/// The service `grpc_bridge` serves.
+pub const SERVICE: Service = Service {
+    name: "RobotJoints",
+    doc: "Joint states and commands over gRPC, bridged to the pub/sub transports by `grpc_bridge`.",
+    rpcs: &[
+        (
+            "StreamStates",
+            "StreamStatesRequest",
+            "stream RobotState",
+            "Every state the bridge receives from now on, as it arrives.",
+        ),
+        (
+            "SendCommands",
+            "stream JointCommand",
+            "CommandSummary",
+            "Publish each command as a RobotState, in order, until the client closes the stream.",
+        ),
+    ],
+};
+
+/// Arguments for `cargo xtask gen-proto`.
+#[derive(Debug, Args)]
+pub struct GenProtoArgs {
+    /// Exit with an error instead of writing when the committed schema is stale.
+    #[arg(long)]
+    pub check: bool,
+}
+
+/// Write [`PROTO_PATH`], or with `--check` verify it is current.
+pub fn gen_proto(args: GenProtoArgs) -> Result<()> {
+    let path = proto_path();
+    let rendered = render();
+    if args.check {
+        let committed = fs::read_to_string(&path).unwrap_or_default();
+        if committed != rendered {
+            bail!(
+                "{} is out of date; run `cargo xtask gen-proto`",
+                path.display()
+            );
+        }
+        tracing::info!(path = %path.display(), "schema is up to date");
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(&path, rendered).with_context(|| format!("writing {}", path.display()))?;
+    tracing::info!(path = %path.display(), "schema written");
+    Ok(())
+}
+
+/// The full `.proto` file text.
+pub fn render() -> String {
+    let mut out = String::from(
+        "// Generated by `cargo xtask gen-proto`; do not edit.\n\
+         // Mirrors the JSON wire types in project_robot_joint_pubsub/src/lib.rs.\n\
+         // Publishers send a RobotState (or, with `--batch`, a RobotStateBatch) as JSON or in this\n\
+         // encoding (`--format protobuf`), and `subscriber --poses-bind` sends BodyPoses the same way.\n\
+         // `grpc_bridge` serves the RobotJoints service at the end.\n\n\
+         syntax = \"proto3\";\n\n\
+         package robot_joint_pubsub;\n",
+    );
+    for message in MESSAGES.iter().chain(SERVICE_MESSAGES) {
+        out.push_str(&format!(
+            "\n// {}\nmessage {} {{\n",
+            message.doc, message.name
+        ));
+        let mut reserved = message.reserved.iter().peekable();
+        for (number, (ty, name, doc)) in message.numbered() {
+            while let Some((skipped, why)) = reserved.next_if(|&&(skipped, _)| skipped < number) {
+                out.push_str(&format!("  // {why}\n  reserved {skipped};\n"));
+            }
+            out.push_str(&format!("  // {doc}\n  {ty} {name} = {number};\n"));
+        }
+        out.push_str("}\n");
+    }
+    out.push_str(&format!(
+        "\n// {}\nservice {} {{\n",
+        SERVICE.doc, SERVICE.name
+    ));
+    for (name, request, response, doc) in SERVICE.rpcs {
+        out.push_str(&format!(
+            "  // {doc}\n  rpc {name}({request}) returns ({response});\n"
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn proto_path() -> PathBuf {
+    workspace_root().join(PROTO_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `pub` field names of `pub struct <name>` in the pub/sub library source.
+    fn rust_fields(source: &str, name: &str) -> Vec<String> {
+        let start = source
+            .find(&format!("pub struct {name} {{"))
+            .unwrap_or_else(|| panic!("struct {name} not found"));
+        let body = &source[start..];
+        let body = &body[..body.find("\n}").expect("struct body ends")];
+        body.lines()
+            .filter_map(|line| line.trim().strip_prefix("pub "))
+            .filter_map(|rest| rest.split_once(':'))
+            .map(|(field, _)| field.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn schema_matches_the_rust_wire_types() {
+        let source =
+            fs::read_to_string(workspace_root().join("project_robot_joint_pubsub/src/lib.rs"))
+                .unwrap();
+        for message in MESSAGES {
+            let proto: Vec<_> = message.fields.iter().map(|(_, name, _)| *name).collect();
+            assert_eq!(
+                rust_fields(&source, message.name),
+                proto,
+                "{}",
+                message.name
+            );
+        }
+    }
+
+    // `(field name, tag)` of the `prost` struct `Proto<name>` in the pub/sub library.
+    fn prost_tags(source: &str, name: &str) -> Vec<(String, usize)> {
+        let start = source
+            .find(&format!("pub struct Proto{name} {{"))
+            .unwrap_or_else(|| panic!("struct Proto{name} not found"));
+        let body = &source[start..];
+        let body = &body[..body.find("\n}").expect("struct body ends")];
+        let lines: Vec<_> = body.lines().map(str::trim).collect();
+        lines
+            .windows(2)
+            .filter_map(|pair| {
+                let tag = pair[0].split_once("tag = \"")?.1.split_once('"')?.0;
+                let field = pair[1].strip_prefix("pub ")?.split_once(':')?.0;
+                Some((field.to_string(), tag.parse().ok()?))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn schema_matches_the_prost_types() {
+        let source =
+            fs::read_to_string(workspace_root().join("project_robot_joint_pubsub/src/proto.rs"))
+                .unwrap();
+        for message in MESSAGES.iter().chain(SERVICE_MESSAGES) {
+            let proto: Vec<_> = message
+                .numbered()
+                .into_iter()
+                .map(|(number, (_, name, _))| (name.to_string(), number))
+                .collect();
+            assert_eq!(prost_tags(&source, message.name), proto, "{}", message.name);
+        }
+    }
+
+    #[test]
+    fn committed_schema_is_current() {
+        let committed = fs::read_to_string(proto_path()).unwrap();
+        assert_eq!(committed, render(), "run `cargo xtask gen-proto`");
+    }
+
+    #[test]
+    fn fields_are_numbered_from_one() {
+        let rendered = render();
+        assert!(rendered.contains("  uint64 timestamp = 1;\n"));
+        assert!(rendered.contains("  repeated JointAngles joints = 3;\n"));
+        assert!(rendered.contains("  reserved 5;\n  // Publisher-side"));
+        assert!(rendered.contains("  optional uint64 sequence = 6;\n"));
+        assert!(
+            rendered
+                .contains("  rpc StreamStates(StreamStatesRequest) returns (stream RobotState);\n")
+        );
+    }
+}